@@ -0,0 +1,44 @@
+//! Microbenchmarks for the same hot paths checked by `tests/codegen.rs`, run with `cargo bench -p grit-data-prison-codegen`
+//!
+//! Each benchmark reports the average time per call over a [Prison] of `LEN` values, so changes to inline
+//! attributes can be compared against a plain `Vec` doing the same work
+#![allow(clippy::needless_return)]
+
+use grit_data_prison::{single_threaded::Prison, CellKey};
+use grit_data_prison_codegen::{codegen_visit_mut_add, codegen_visit_ref_read};
+use std::{hint::black_box, time::Instant};
+
+const LEN: usize = 1024;
+const ROUNDS: usize = 10_000;
+
+//FN bench()
+fn bench<F: FnMut()>(name: &str, mut operation: F) {
+    operation();
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        operation();
+    }
+    let per_call = start.elapsed().as_secs_f64() * 1e9 / (ROUNDS * LEN) as f64;
+    println!("{:<24} {:>8.3} ns/call", name, per_call);
+}
+
+fn main() {
+    let prison: Prison<u64> = Prison::with_capacity(LEN);
+    let keys: Vec<CellKey> = (0..LEN as u64).map(|val| prison.insert(val).unwrap()).collect();
+    let mut plain: Vec<u64> = (0..LEN as u64).collect();
+    bench("Vec index add", || {
+        for idx in 0..LEN {
+            plain[black_box(idx)] += 1;
+        }
+    });
+    bench("Prison::visit_mut() add", || {
+        for key in keys.iter() {
+            black_box(codegen_visit_mut_add(&prison, black_box(*key)));
+        }
+    });
+    bench("Prison::visit_ref() read", || {
+        for key in keys.iter() {
+            black_box(codegen_visit_ref_read(&prison, black_box(*key)));
+        }
+    });
+}
@@ -0,0 +1,26 @@
+//! `#[no_mangle]` shims around the hot paths of [Prison], compiled in release mode by `tests/codegen.rs`
+//! so that the emitted assembly of each shim can be checked for leftover calls and instruction bloat
+//!
+//! Every shim should reduce to the bounds check, generation check, reference count bookkeeping and the
+//! closure body, with the only remaining calls being the cold paths that wake tasks waiting on the [Prison]
+//! and run removals deferred with [Prison::remove_when_free()]
+#![allow(clippy::needless_return)]
+
+use grit_data_prison::{single_threaded::Prison, CellKey};
+
+//FN codegen_visit_mut_add()
+#[no_mangle]
+pub fn codegen_visit_mut_add(prison: &Prison<u64>, key: CellKey) -> bool {
+    return prison
+        .visit_mut(key, |val| {
+            *val += 1;
+            Ok(())
+        })
+        .is_ok();
+}
+
+//FN codegen_visit_ref_read()
+#[no_mangle]
+pub fn codegen_visit_ref_read(prison: &Prison<u64>, key: CellKey) -> u64 {
+    return prison.visit_ref(key, |val| Ok(*val)).unwrap_or(0);
+}
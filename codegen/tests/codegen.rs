@@ -0,0 +1,78 @@
+#![allow(clippy::needless_return)]
+
+use std::{fs, path::PathBuf, process::Command};
+
+//STATIC MAX_INSTRUCTIONS
+/// Instruction budget for every shim, leaving some headroom over the ~82 instructions currently emitted (including the visit depth bookkeeping
+/// and the calls into the cold waker and pending removal paths) so that unrelated compiler upgrades do not fail the test,
+/// while an out-of-line call or inlined slow path will
+const MAX_INSTRUCTIONS: usize = 88;
+
+//STATIC ALLOWED_CALLS
+/// Symbols that shims may still call, matched by substring of the (mangled) call target
+const ALLOWED_CALLS: &[&str] = &["wake_all_cold", "_run_pending_removals"];
+
+//FN emit_release_asm()
+fn emit_release_asm() -> String {
+    let manifest = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+    let target_dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+    let status = Command::new(env!("CARGO"))
+        .args(["rustc", "--release", "--lib", "--manifest-path", manifest, "--target-dir"])
+        .arg(&target_dir)
+        .args(["--", "--emit", "asm", "-C", "codegen-units=1"])
+        .status()
+        .expect("failed to run cargo");
+    assert!(status.success(), "release build of the codegen shims failed");
+    let deps = target_dir.join("release").join("deps");
+    let asm_file = fs::read_dir(&deps)
+        .expect("missing deps directory")
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_string_lossy();
+            name.starts_with("grit_data_prison_codegen-") && name.ends_with(".s")
+        })
+        .max_by_key(|path| fs::metadata(path).and_then(|meta| meta.modified()).ok())
+        .expect("no assembly emitted for the codegen shims");
+    return fs::read_to_string(asm_file).expect("failed to read emitted assembly");
+}
+
+//FN instructions()
+/// Return the instructions (without labels or assembler directives) of the function `symbol`
+fn instructions<'a>(asm: &'a str, symbol: &str) -> Vec<&'a str> {
+    let label = format!("{}:", symbol);
+    let mut lines = asm.lines().skip_while(|line| line.trim() != label).skip(1);
+    let body = lines.by_ref().take_while(|line| !line.trim().starts_with(".cfi_endproc") && !line.trim().starts_with(".Lfunc_end"));
+    let body: Vec<&str> = body
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty() && !line.starts_with('.') && !line.starts_with('#') && !line.ends_with(':'))
+        .collect();
+    assert!(!body.is_empty(), "symbol `{}` not found in emitted assembly", symbol);
+    return body;
+}
+
+//FN assert_inlined()
+fn assert_inlined(asm: &str, symbol: &str) {
+    let body = instructions(asm, symbol);
+    for instruction in body.iter() {
+        if instruction.starts_with("call") && !ALLOWED_CALLS.iter().any(|allowed| instruction.contains(allowed)) {
+            panic!("`{}` did not fully inline, found `{}`:\n{}", symbol, instruction, body.join("\n"));
+        }
+    }
+    assert!(
+        body.len() <= MAX_INSTRUCTIONS,
+        "`{}` compiled to {} instructions (budget {}):\n{}",
+        symbol,
+        body.len(),
+        MAX_INSTRUCTIONS,
+        body.join("\n")
+    );
+}
+
+//TEST Prison::visit_mut() / Prison::visit_ref() inline
+#[cfg(target_arch = "x86_64")]
+#[test]
+fn visit_closures_fully_inline() {
+    let asm = emit_release_asm();
+    assert_inlined(&asm, "codegen_visit_mut_add");
+    assert_inlined(&asm, "codegen_visit_ref_read");
+}
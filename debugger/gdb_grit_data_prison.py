@@ -0,0 +1,119 @@
+# GDB pretty-printers for grit-data-prison
+#
+# These are embedded in every binary that links grit-data-prison (see the `debugger_visualizer`
+# attribute in src/lib.rs) and load automatically when gdb's auto-load safe-path allows it.
+# They can also be loaded by hand with:
+#
+#     (gdb) source /path/to/grit-data-prison/debugger/gdb_grit_data_prison.py
+#
+# A `Prison<T>` prints its length, usage, generation and active accesses, and expands into one entry
+# per slot: free slots show their free-list links, values show their generation, reference count and value.
+# Values are never read while mutably referenced, and show as `<redacted>` while
+# `Prison::set_redact_values(true)` is in effect.
+
+import gdb
+import gdb.printing
+
+
+def _usize_bits():
+    return gdb.lookup_type("usize").sizeof * 8
+
+
+class _Consts:
+    def __init__(self):
+        bits = _usize_bits()
+        self.disc = 1 << (bits - 1)
+        self.invalid = self.disc - 1
+        self.mut = (1 << bits) - 1
+        self.hidden = self.mut - 1
+
+
+def _find_ptr(val):
+    """Walk down the (version dependent) RawVec fields until reaching a raw pointer"""
+    while val.type.strip_typedefs().code != gdb.TYPE_CODE_PTR:
+        fields = val.type.strip_typedefs().fields()
+        if not fields:
+            return None
+        val = val[fields[0]]
+    return val
+
+
+def _vec_items(vec):
+    elem_type = vec.type.strip_typedefs().template_argument(0)
+    length = int(vec["len"])
+    ptr = _find_ptr(vec["buf"]).cast(elem_type.pointer())
+    for idx in range(length):
+        yield ptr[idx]
+
+
+def _link(val, consts):
+    return "None" if val == consts.invalid else str(val)
+
+
+def _refs(refs, consts):
+    if refs == consts.mut:
+        return "mut"
+    if refs == consts.hidden:
+        return "hidden"
+    return str(refs)
+
+
+def _cell_val(cell):
+    # MaybeUninit<T> { uninit: (), value: ManuallyDrop<T> { value: T } }
+    return cell["val"]["value"]["value"]
+
+
+class PrisonPrinter:
+    def __init__(self, val):
+        self.internal = val["internal"]["value"]
+        self.consts = _Consts()
+
+    def to_string(self):
+        internal = self.internal
+        length = int(internal["vec"]["len"])
+        used = length - int(internal["free_count"])
+        return "Prison(len: %d, used: %d, generation: %d, accesses: %d)" % (
+            length,
+            used,
+            int(internal["generation"]),
+            int(internal["access_count"]["count"]),
+        )
+
+    def children(self):
+        consts = self.consts
+        redact = bool(self.internal["redact_values"])
+        for idx, cell in enumerate(_vec_items(self.internal["vec"])):
+            refs_or_next = int(cell["refs_or_next"])
+            d_gen_or_prev = int(cell["d_gen_or_prev"])
+            gen_or_prev = d_gen_or_prev & consts.invalid
+            if d_gen_or_prev & consts.disc:
+                yield (
+                    "[%d]" % idx,
+                    "free (next: %s, prev: %s)" % (_link(refs_or_next, consts), _link(gen_or_prev, consts)),
+                )
+                continue
+            name = "[%d] gen: %d, refs: %s" % (idx, gen_or_prev, _refs(refs_or_next, consts))
+            if redact:
+                yield (name, "<redacted>")
+            elif refs_or_next == consts.mut:
+                yield (name, "<mutably referenced>")
+            else:
+                yield (name, _cell_val(cell))
+
+
+class CellKeyPrinter:
+    def __init__(self, val):
+        self.val = val
+
+    def to_string(self):
+        return "CellKey(idx: %d, gen: %d)" % (int(self.val["idx"]), int(self.val["gen"]))
+
+
+def build_pretty_printer():
+    printer = gdb.printing.RegexpCollectionPrettyPrinter("grit_data_prison")
+    printer.add_printer("Prison", "^grit_data_prison::single_threaded::Prison<.+>$", PrisonPrinter)
+    printer.add_printer("CellKey", "^grit_data_prison::CellKey$", CellKeyPrinter)
+    return printer
+
+
+gdb.printing.register_pretty_printer(gdb.current_objfile(), build_pretty_printer(), replace=True)
@@ -0,0 +1,151 @@
+# LLDB formatters for grit-data-prison
+#
+# Load them by hand (or from ~/.lldbinit) with:
+#
+#     (lldb) command script import /path/to/grit-data-prison/debugger/lldb_grit_data_prison.py
+#
+# A `Prison<T>` shows its length, usage, generation and active accesses as a summary, and expands into one
+# child per slot: free slots show their free-list links, values show their generation, reference count and value.
+# Values are never read while mutably referenced, and are not expanded while `Prison::set_redact_values(true)`
+# is in effect. The raw `PrisonCell` internals are summarized instead of expanded for the same reason.
+
+import lldb
+
+PRISON_REGEX = "^grit_data_prison::single_threaded::Prison<.+>$"
+CELL_REGEX = "^grit_data_prison::single_threaded::PrisonCell<.+>$"
+KEY_NAME = "grit_data_prison::CellKey"
+
+
+class _Consts:
+    def __init__(self, target):
+        bits = target.GetAddressByteSize() * 8
+        self.disc = 1 << (bits - 1)
+        self.invalid = self.disc - 1
+        self.mut = (1 << bits) - 1
+        self.hidden = self.mut - 1
+
+
+def _find_ptr(val):
+    """Walk down the (version dependent) RawVec fields until reaching a raw pointer"""
+    while not val.GetType().IsPointerType():
+        if val.GetNumChildren() == 0:
+            return None
+        val = val.GetChildAtIndex(0)
+    return val
+
+
+def _internal(prison):
+    return prison.GetNonSyntheticValue().GetChildMemberWithName("internal").GetChildMemberWithName("value")
+
+
+def _link(val, consts):
+    return "None" if val == consts.invalid else str(val)
+
+
+def _refs(refs, consts):
+    if refs == consts.mut:
+        return "mut"
+    if refs == consts.hidden:
+        return "hidden"
+    return str(refs)
+
+
+def _cell_state(cell, consts):
+    refs_or_next = cell.GetChildMemberWithName("refs_or_next").GetValueAsUnsigned()
+    d_gen_or_prev = cell.GetChildMemberWithName("d_gen_or_prev").GetValueAsUnsigned()
+    gen_or_prev = d_gen_or_prev & consts.invalid
+    if d_gen_or_prev & consts.disc:
+        return (True, "free (next: %s, prev: %s)" % (_link(refs_or_next, consts), _link(gen_or_prev, consts)))
+    return (False, "gen: %d, refs: %s" % (gen_or_prev, _refs(refs_or_next, consts)), refs_or_next == consts.mut)
+
+
+def prison_summary(valobj, _dict):
+    internal = _internal(valobj)
+    length = internal.GetChildMemberWithName("vec").GetChildMemberWithName("len").GetValueAsUnsigned()
+    free = internal.GetChildMemberWithName("free_count").GetValueAsUnsigned()
+    generation = internal.GetChildMemberWithName("generation").GetValueAsUnsigned()
+    accesses = internal.GetChildMemberWithName("access_count").GetChildMemberWithName("count").GetValueAsUnsigned()
+    return "len: %d, used: %d, generation: %d, accesses: %d" % (length, length - free, generation, accesses)
+
+
+def cell_summary(valobj, _dict):
+    return _cell_state(valobj.GetNonSyntheticValue(), _Consts(valobj.GetTarget()))[1]
+
+
+def cell_key_summary(valobj, _dict):
+    idx = valobj.GetChildMemberWithName("idx").GetValueAsUnsigned()
+    gen = valobj.GetChildMemberWithName("gen").GetValueAsUnsigned()
+    return "idx: %d, gen: %d" % (idx, gen)
+
+
+class PrisonSyntheticProvider:
+    def __init__(self, valobj, _dict):
+        self.valobj = valobj
+        self.update()
+
+    def update(self):
+        internal = _internal(self.valobj)
+        vec = internal.GetChildMemberWithName("vec")
+        self.consts = _Consts(self.valobj.GetTarget())
+        self.redact = internal.GetChildMemberWithName("redact_values").GetValueAsUnsigned() != 0
+        self.length = vec.GetChildMemberWithName("len").GetValueAsUnsigned()
+        self.cell_type = vec.GetType().GetTemplateArgumentType(0)
+        ptr = _find_ptr(vec.GetChildMemberWithName("buf"))
+        self.data = ptr.GetValueAsUnsigned() if ptr is not None else 0
+        return False
+
+    def has_children(self):
+        return True
+
+    def num_children(self):
+        return self.length
+
+    def get_child_index(self, name):
+        try:
+            return int(name.lstrip("[").split("]")[0])
+        except ValueError:
+            return -1
+
+    def get_child_at_index(self, idx):
+        if idx < 0 or idx >= self.length:
+            return None
+        addr = self.data + idx * self.cell_type.GetByteSize()
+        cell = self.valobj.CreateValueFromAddress("[%d]" % idx, addr, self.cell_type)
+        state = _cell_state(cell.GetNonSyntheticValue(), self.consts)
+        if state[0] or self.redact or state[2]:
+            # Free, redacted or mutably referenced slots show only the cell summary, never the value
+            return cell
+        # MaybeUninit<T> { uninit: (), value: ManuallyDrop<T> { value: T } }
+        val = cell.GetNonSyntheticValue().GetChildMemberWithName("val").GetChildMemberWithName("value").GetChildMemberWithName("value")
+        return self.valobj.CreateValueFromAddress("[%d] %s" % (idx, state[1]), val.GetLoadAddress(), val.GetType())
+
+
+class PrisonCellSyntheticProvider:
+    def __init__(self, valobj, _dict):
+        self.valobj = valobj
+
+    def update(self):
+        return False
+
+    def has_children(self):
+        return False
+
+    def num_children(self):
+        return 0
+
+    def get_child_index(self, _name):
+        return -1
+
+    def get_child_at_index(self, _idx):
+        return None
+
+
+def __lldb_init_module(debugger, _dict):
+    category = "grit_data_prison"
+    module = __name__
+    debugger.HandleCommand('type summary add -x "%s" -F %s.prison_summary -w %s' % (PRISON_REGEX, module, category))
+    debugger.HandleCommand('type synthetic add -x "%s" -l %s.PrisonSyntheticProvider -w %s' % (PRISON_REGEX, module, category))
+    debugger.HandleCommand('type summary add -x "%s" -F %s.cell_summary -w %s' % (CELL_REGEX, module, category))
+    debugger.HandleCommand('type synthetic add -x "%s" -l %s.PrisonCellSyntheticProvider -w %s' % (CELL_REGEX, module, category))
+    debugger.HandleCommand('type summary add "%s" -F %s.cell_key_summary -w %s' % (KEY_NAME, module, category))
+    debugger.HandleCommand("type category enable %s" % category)
@@ -0,0 +1,129 @@
+//! `#[derive(PrisonValue)]` for [grit-data-prison](https://docs.rs/grit-data-prison), re-exported by it
+//! with its `derive` feature
+//!
+//! The derive implements `PrisonValue` for a struct that stores its own `CellKey`, and declares a typed
+//! newtype key for it, exactly like the `prison_value!` macro does. The field holding the `CellKey` is the
+//! one marked `#[prison_key]`, or else the only field whose type is named `CellKey`. The newtype key has the
+//! visibility of the struct and is named after it with a `Key` suffix, unless it is named with
+//! `#[prison_value(key = Name)]`:
+//! ```rust,ignore
+//! #[derive(PrisonValue)]
+//! #[prison_value(key = EnemyId)]
+//! pub struct Enemy {
+//!     #[prison_key]
+//!     id: CellKey,
+//!     target: CellKey,
+//! }
+//! ```
+//! The generated code refers to the crate as `::grit_data_prison`, so it must not be renamed in `Cargo.toml`
+#![allow(clippy::needless_return)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, spanned::Spanned, Data, DeriveInput, Error, Fields, Ident, Member, Type};
+
+//FN derive_prison_value()
+/// Implement `PrisonValue` for a struct with a `CellKey` field, and declare a typed newtype key for it
+///
+/// See the [crate] documentation for the attributes
+#[proc_macro_derive(PrisonValue, attributes(prison_key, prison_value))]
+pub fn derive_prison_value(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    return match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    };
+}
+
+//FN expand()
+fn expand(input: &DeriveInput) -> Result<TokenStream2, Error> {
+    let name = &input.ident;
+    let vis = &input.vis;
+    let key_name = key_name(input)?;
+    let field = key_field(input)?;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let doc = format!("Typed key of a [`{}`] in a `Prison`, generated by `#[derive(PrisonValue)]`", name);
+    return Ok(quote! {
+        #[doc = #doc]
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        #vis struct #key_name(pub ::grit_data_prison::CellKey);
+
+        impl ::core::convert::From<::grit_data_prison::CellKey> for #key_name {
+            fn from(key: ::grit_data_prison::CellKey) -> Self {
+                return #key_name(key);
+            }
+        }
+
+        impl ::core::convert::From<#key_name> for ::grit_data_prison::CellKey {
+            fn from(key: #key_name) -> Self {
+                return key.0;
+            }
+        }
+
+        impl #impl_generics ::grit_data_prison::PrisonValue for #name #ty_generics #where_clause {
+            type Key = #key_name;
+
+            fn key(&self) -> ::grit_data_prison::CellKey {
+                return self.#field;
+            }
+
+            fn set_key(&mut self, key: ::grit_data_prison::CellKey) {
+                self.#field = key;
+            }
+        }
+    });
+}
+
+//FN key_name()
+/// Return the name given with `#[prison_value(key = Name)]`, or the struct name with a `Key` suffix
+fn key_name(input: &DeriveInput) -> Result<Ident, Error> {
+    let mut key_name = None;
+    for attr in input.attrs.iter().filter(|attr| attr.path().is_ident("prison_value")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("key") {
+                return Err(meta.error("expected `key = Name`"));
+            }
+            if key_name.is_some() {
+                return Err(meta.error("the key is already named"));
+            }
+            key_name = Some(meta.value()?.parse::<Ident>()?);
+            return Ok(());
+        })?;
+    }
+    return Ok(key_name.unwrap_or_else(|| format_ident!("{}Key", input.ident)));
+}
+
+//FN key_field()
+/// Return the field marked `#[prison_key]`, or else the only field whose type is named `CellKey`
+fn key_field(input: &DeriveInput) -> Result<Member, Error> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => return Err(Error::new(input.ident.span(), "`PrisonValue` can only be derived for structs")),
+    };
+    let members: Vec<(Member, &syn::Field)> = match fields {
+        Fields::Named(named) => named.named.iter().map(|field| (Member::Named(field.ident.clone().unwrap()), field)).collect(),
+        Fields::Unnamed(unnamed) => unnamed.unnamed.iter().enumerate().map(|(idx, field)| (Member::from(idx), field)).collect(),
+        Fields::Unit => Vec::new(),
+    };
+    let marked: Vec<&(Member, &syn::Field)> = members.iter().filter(|(_, field)| field.attrs.iter().any(|attr| attr.path().is_ident("prison_key"))).collect();
+    match marked.as_slice() {
+        [(member, _)] => return Ok(member.clone()),
+        [_, (_, second), ..] => return Err(Error::new(second.span(), "only one field can be marked `#[prison_key]`")),
+        [] => {}
+    }
+    let keys: Vec<&(Member, &syn::Field)> = members.iter().filter(|(_, field)| is_cell_key(&field.ty)).collect();
+    match keys.as_slice() {
+        [(member, _)] => return Ok(member.clone()),
+        [] => return Err(Error::new(input.ident.span(), "`PrisonValue` needs a `CellKey` field to store the key in")),
+        _ => return Err(Error::new(input.ident.span(), "more than one `CellKey` field, mark the one that stores the key with `#[prison_key]`")),
+    }
+}
+
+//FN is_cell_key()
+fn is_cell_key(ty: &Type) -> bool {
+    return match ty {
+        Type::Path(path) => path.qself.is_none() && path.path.segments.last().is_some_and(|segment| segment.ident == "CellKey"),
+        _ => false,
+    };
+}
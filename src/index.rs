@@ -0,0 +1,308 @@
+use crate::{single_threaded::Prison, AccessError, BTreeMap, CellKey, Vec};
+
+#[cfg(test)]
+mod tests;
+
+//====== Aabb ======
+//STRUCT Aabb
+/// An axis-aligned rectangular region used to query a [GridIndex], given by its lowest and highest corner
+///
+/// Both edges are inclusive, so a point lying exactly on the border of the region is inside it
+#[derive(Debug, Clone, Copy, PartialEq)] //COV_IGNORE
+pub struct Aabb {
+    /// The corner of the region with the lowest `x` and `y` coordinates
+    pub min: [f32; 2],
+    /// The corner of the region with the highest `x` and `y` coordinates
+    pub max: [f32; 2],
+}
+
+impl Aabb {
+    //FN Aabb::new()
+    /// Create a new [Aabb] spanning from `min` to `max`
+    pub fn new(min: [f32; 2], max: [f32; 2]) -> Self {
+        return Aabb { min, max };
+    }
+
+    //FN Aabb::around()
+    /// Create a new [Aabb] centered on `point` that extends `radius` in every direction
+    pub fn around(point: [f32; 2], radius: f32) -> Self {
+        return Aabb {
+            min: [point[0] - radius, point[1] - radius],
+            max: [point[0] + radius, point[1] + radius],
+        };
+    }
+
+    //FN Aabb::contains()
+    /// Return `true` if `point` lies inside the region or on its border
+    pub fn contains(&self, point: [f32; 2]) -> bool {
+        return point[0] >= self.min[0] && point[0] <= self.max[0] && point[1] >= self.min[1] && point[1] <= self.max[1];
+    }
+}
+
+//====== GridIndex ======
+//STRUCT GridIndex
+/// A uniform-grid spatial index over the values of a [Prison], answering "which values are inside
+/// this region" without visiting every value
+///
+/// Every tracked value is filed under the square grid cell its position falls into, as reported by the
+/// `position` function passed to [GridIndex::new()]. The index is kept up to date through the same
+/// change notifications the [Prison] already provides:
+/// - values inserted or removed through [GridIndex::insert()] and [GridIndex::remove()] are filed and unfiled immediately
+/// - values *mutated* anywhere (any `visit_*_mut()` or `guard_*_mut()`) are marked dirty by the [Prison], and [GridIndex::sync()] drains those marks and re-files only the values that changed
+/// - values removed directly from the [Prison] are dropped from the index by the next [GridIndex::sync()]
+///
+/// Operations that move values to new keys (such as `compact()`) invalidate the index, call
+/// [GridIndex::rebuild()] afterwards
+///
+/// Only available with the `index` feature
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison, index::{Aabb, GridIndex}};
+/// # fn main() -> Result<(), AccessError> {
+/// struct Unit {
+///     pos: [f32; 2],
+/// }
+/// let units: Prison<Unit> = Prison::new();
+/// let mut grid = GridIndex::new(10.0, |unit: &Unit| unit.pos);
+/// let near = grid.insert(&units, Unit { pos: [1.0, 1.0] })?;
+/// let far = grid.insert(&units, Unit { pos: [55.0, -30.0] })?;
+/// assert_eq!(grid.keys_in_aabb(Aabb::around([0.0, 0.0], 5.0)), vec![near]);
+/// units.visit_mut(far, |unit| {
+///     unit.pos = [2.0, -2.0];
+///     Ok(())
+/// })?;
+/// grid.sync(&units)?;
+/// assert_eq!(grid.keys_in_aabb(Aabb::around([0.0, 0.0], 5.0)).len(), 2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct GridIndex<T> {
+    cell_size: f32,
+    position: fn(&T) -> [f32; 2],
+    tracked: Vec<Option<Tracked>>,
+    buckets: BTreeMap<(i64, i64), Vec<CellKey>>,
+    len: usize,
+}
+
+//STRUCT Tracked
+#[doc(hidden)]
+#[derive(Clone, Copy)] //COV_IGNORE
+struct Tracked {
+    key: CellKey,
+    point: [f32; 2],
+    bucket: (i64, i64),
+}
+
+impl<T> GridIndex<T> {
+    //FN GridIndex::new()
+    /// Create a new, empty [GridIndex] that files values in square grid cells `cell_size` wide,
+    /// using `position` to read the position of each value
+    ///
+    /// `cell_size` should be positive and roughly match the size of the regions you usually query: much
+    /// smaller cells make each query inspect many cells, much larger ones make each cell hold many values
+    pub fn new(cell_size: f32, position: fn(&T) -> [f32; 2]) -> Self {
+        return GridIndex {
+            cell_size,
+            position,
+            tracked: Vec::new(),
+            buckets: BTreeMap::new(),
+            len: 0,
+        };
+    }
+
+    //FN GridIndex::len()
+    /// Return the number of values currently tracked by the [GridIndex]
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    //FN GridIndex::is_empty()
+    /// Return `true` if the [GridIndex] tracks no values
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
+
+    //FN GridIndex::cell_size()
+    /// Return the width of the grid cells used by the [GridIndex]
+    pub fn cell_size(&self) -> f32 {
+        return self.cell_size;
+    }
+
+    //FN GridIndex::position_of()
+    /// Return the position the [GridIndex] last recorded for `key`, or [None] if it is not tracked
+    pub fn position_of(&self, key: CellKey) -> Option<[f32; 2]> {
+        match self.tracked.get(key.idx) {
+            Some(Some(tracked)) if tracked.key == key => return Some(tracked.point),
+            _ => return None,
+        }
+    }
+
+    //FN GridIndex::insert()
+    /// Insert `value` into `prison` and immediately file it in the [GridIndex], returning its [CellKey]
+    /// ## Errors
+    /// Any error returned by [Prison::insert()], in which case the index is left unchanged
+    pub fn insert(&mut self, prison: &Prison<T>, value: T) -> Result<CellKey, AccessError> {
+        let point = (self.position)(&value);
+        let key = prison.insert(value)?;
+        self._place(key, point);
+        return Ok(key);
+    }
+
+    //FN GridIndex::track()
+    /// Start tracking a value that was inserted into `prison` without going through the [GridIndex],
+    /// or re-read the position of one that is already tracked
+    /// ## Errors
+    /// Any error returned by [Prison::visit_ref()], in which case the index is left unchanged
+    pub fn track(&mut self, prison: &Prison<T>, key: CellKey) -> Result<(), AccessError> {
+        let position = self.position;
+        let point = prison.visit_ref(key, |val| Ok(position(val)))?;
+        self._place(key, point);
+        return Ok(());
+    }
+
+    //FN GridIndex::remove()
+    /// Remove the value at `key` from `prison` and stop tracking it, returning the value
+    /// ## Errors
+    /// Any error returned by [Prison::remove()], in which case the index is left unchanged
+    pub fn remove(&mut self, prison: &Prison<T>, key: CellKey) -> Result<T, AccessError> {
+        let val = prison.remove(key)?;
+        self.untrack(key);
+        return Ok(val);
+    }
+
+    //FN GridIndex::untrack()
+    /// Stop tracking `key` without touching the [Prison], returning `true` if it was tracked
+    pub fn untrack(&mut self, key: CellKey) -> bool {
+        match self.tracked.get(key.idx) {
+            Some(Some(tracked)) if tracked.key == key => {
+                self._unplace(key.idx);
+                return true;
+            }
+            _ => return false,
+        }
+    }
+
+    //FN GridIndex::sync()
+    /// Bring the [GridIndex] up to date with every change made to `prison` since the last call
+    ///
+    /// This drains the dirty marks of `prison` with [Prison::drain_dirty()] and re-reads the position
+    /// of every tracked value among them, then stops tracking values that are no longer in `prison`.
+    /// Because the dirty marks are consumed, the drained keys are returned so they can be passed on to
+    /// anything else that needs them
+    /// ## Errors
+    /// Any error returned by [Prison::visit_ref()] while re-reading a position, the values already
+    /// re-filed keep their new position
+    pub fn sync(&mut self, prison: &Prison<T>) -> Result<Vec<CellKey>, AccessError> {
+        let dirty = prison.drain_dirty();
+        for key in dirty.iter() {
+            if self.position_of(*key).is_some() {
+                self.track(prison, *key)?;
+            }
+        }
+        for idx in 0..self.tracked.len() {
+            if let Some(tracked) = self.tracked[idx] {
+                if !prison.contains_key(tracked.key) {
+                    self._unplace(idx);
+                }
+            }
+        }
+        return Ok(dirty);
+    }
+
+    //FN GridIndex::rebuild()
+    /// Forget every tracked value and file every value currently in `prison` from scratch
+    ///
+    /// Use this to build an index for a [Prison] that already holds values, or after an operation
+    /// that moved values to new keys
+    /// ## Errors
+    /// Any error returned by [Prison::visit_ref()] while reading a position, in which case the index
+    /// only tracks the values filed before the error
+    pub fn rebuild(&mut self, prison: &Prison<T>) -> Result<(), AccessError> {
+        self.clear();
+        for key in prison.keys() {
+            self.track(prison, key)?;
+        }
+        return Ok(());
+    }
+
+    //FN GridIndex::clear()
+    /// Stop tracking every value, without touching the [Prison]
+    pub fn clear(&mut self) {
+        self.tracked.clear();
+        self.buckets.clear();
+        self.len = 0;
+    }
+
+    //FN GridIndex::keys_in_aabb()
+    /// Return the [CellKey] of every tracked value whose recorded position lies inside `region`
+    ///
+    /// Keys are grouped by grid cell, ordered by the cell's `x` then `y` coordinate
+    pub fn keys_in_aabb(&self, region: Aabb) -> Vec<CellKey> {
+        let (x_0, y_0) = self._bucket(region.min);
+        let (x_1, y_1) = self._bucket(region.max);
+        let mut keys = Vec::new();
+        if x_0 > x_1 || y_0 > y_1 {
+            return keys;
+        }
+        for (&(_, y), bucket) in self.buckets.range((x_0, y_0)..=(x_1, y_1)) {
+            if y < y_0 || y > y_1 {
+                continue;
+            }
+            for key in bucket.iter() {
+                if let Some(Some(tracked)) = self.tracked.get(key.idx) {
+                    if region.contains(tracked.point) {
+                        keys.push(*key);
+                    }
+                }
+            }
+        }
+        return keys;
+    }
+
+    //FN GridIndex::_bucket()
+    #[doc(hidden)]
+    fn _bucket(&self, point: [f32; 2]) -> (i64, i64) {
+        return (_floor_div(point[0], self.cell_size), _floor_div(point[1], self.cell_size));
+    }
+
+    //FN GridIndex::_place()
+    #[doc(hidden)]
+    fn _place(&mut self, key: CellKey, point: [f32; 2]) {
+        if key.idx >= self.tracked.len() {
+            self.tracked.resize(key.idx + 1, None);
+        }
+        if self.tracked[key.idx].is_some() {
+            self._unplace(key.idx);
+        }
+        let bucket = self._bucket(point);
+        self.buckets.entry(bucket).or_default().push(key);
+        self.tracked[key.idx] = Some(Tracked { key, point, bucket });
+        self.len += 1;
+    }
+
+    //FN GridIndex::_unplace()
+    #[doc(hidden)]
+    fn _unplace(&mut self, idx: usize) {
+        if let Some(tracked) = self.tracked[idx].take() {
+            if let Some(bucket) = self.buckets.get_mut(&tracked.bucket) {
+                bucket.retain(|key| key.idx != idx);
+                if bucket.is_empty() {
+                    self.buckets.remove(&tracked.bucket);
+                }
+            }
+            self.len -= 1;
+        }
+    }
+}
+
+//FN _floor_div()
+#[doc(hidden)]
+#[inline(always)]
+fn _floor_div(coord: f32, size: f32) -> i64 {
+    let quotient = coord / size;
+    let truncated = quotient as i64;
+    if (truncated as f32) > quotient {
+        return truncated - 1;
+    }
+    return truncated;
+}
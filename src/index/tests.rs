@@ -0,0 +1,45 @@
+#![allow(unused_variables)]
+//====== Testing ======
+use super::*;
+
+//TEST GridIndex::keys_in_aabb()
+#[test]
+fn grid_index_queries_and_sync() -> Result<(), AccessError> {
+    let prison: Prison<[f32; 2]> = Prison::new();
+    let mut grid = GridIndex::new(10.0, |pos: &[f32; 2]| *pos);
+    let key_0 = grid.insert(&prison, [1.0, 1.0])?;
+    let key_1 = grid.insert(&prison, [-1.0, -1.0])?;
+    let key_2 = grid.insert(&prison, [25.0, 5.0])?;
+    let key_3 = prison.insert([3.0, -3.0])?;
+    assert_eq!(grid.len(), 3);
+    assert_eq!(grid.keys_in_aabb(Aabb::around([0.0, 0.0], 2.0)), vec![key_1, key_0]);
+    assert_eq!(grid.keys_in_aabb(Aabb::new([0.0, 0.0], [30.0, 30.0])), vec![key_0, key_2]);
+    assert!(grid.keys_in_aabb(Aabb::new([5.0, 5.0], [-5.0, -5.0])).is_empty());
+    grid.track(&prison, key_3)?;
+    assert_eq!(grid.keys_in_aabb(Aabb::around([0.0, 0.0], 5.0)), vec![key_1, key_3, key_0]);
+    prison.visit_mut(key_2, |pos| {
+        *pos = [0.5, 0.5];
+        Ok(())
+    })?;
+    prison.visit_mut(key_0, |pos| {
+        *pos = [-40.0, 12.0];
+        Ok(())
+    })?;
+    prison.remove(key_1)?;
+    assert_eq!(grid.sync(&prison)?, vec![key_2, key_0]);
+    assert_eq!(grid.len(), 3);
+    assert_eq!(grid.position_of(key_0), Some([-40.0, 12.0]));
+    assert_eq!(grid.position_of(key_1), None);
+    assert_eq!(grid.keys_in_aabb(Aabb::around([0.0, 0.0], 5.0)), vec![key_3, key_2]);
+    assert_eq!(grid.keys_in_aabb(Aabb::around([-40.0, 12.0], 0.0)), vec![key_0]);
+    assert_eq!(grid.remove(&prison, key_2)?, [0.5, 0.5]);
+    assert!(!grid.untrack(key_2));
+    assert!(grid.untrack(key_3));
+    assert_eq!(grid.len(), 1);
+    grid.rebuild(&prison)?;
+    assert_eq!(grid.len(), 2);
+    assert_eq!(grid.keys_in_aabb(Aabb::new([-50.0, -50.0], [50.0, 50.0])), vec![key_0, key_3]);
+    grid.clear();
+    assert!(grid.is_empty());
+    Ok(())
+}
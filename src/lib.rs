@@ -284,13 +284,18 @@ fn main() -> Result<(), AccessError> {
 }
 ```
 See the documentation on [JailCell](crate::single_threaded::JailCell) for more info
+
+Both [Prison](crate::single_threaded::Prison) and [JailCell](crate::single_threaded::JailCell) share the same reference counting
+rules, and the core of [JailCell](crate::single_threaded::JailCell) is available on its own as [RefCountCell<T>](crate::single_threaded::RefCountCell),
+with a `try_borrow()`/`try_borrow_mut()` interface for building your own containers with the same fallible-borrow semantics
 # Why this strange syntax?
 
 For the `visit()` methodology, closures provide a safe sandbox to access mutable references, as they cant be moved out of the closure,
 and because the `visit()` functions that take the closures handle all of the
 safety and housekeeping needed before and after.
 
-Since closures use generics the rust compiler can inline them in many/most/all? cases.
+Since closures use generics the rust compiler can inline them, and for simple closures passed to `visit_mut()` and `visit_ref()`
+this is verified by codegen tests (see [performance](#performance))
 
 The `guard()` methodology requires the values not be able to leak, alias, or never reset their reference counts,
 so they are wrapped in structs that provide limited access to the references and know how to
@@ -379,20 +384,78 @@ fn main() -> Result<(), AccessError> {
 }
 ```
 # Crate Features
-`no_std`: This crate can be used with the `no_std` feature to use only imports from the `core` library instead of the `std` library
+`std` (default): Links the standard library, enabling the APIs that need an operating system (implies `alloc`)
+
+`alloc`: The only required feature, this crate can be used in `no_std` environments with a global allocator by disabling default features and enabling `alloc` alone, in which case every API that needs `std` (see `profile` and `offload`) is compiled out. The old `no_std` feature is deprecated and only kept so that existing `features = ["no_std"]` dependencies still build: it just enables `alloc`, so it changes nothing while `std` is enabled
+
+`unsafe_opt`: Opts into every optimization that trusts the internal invariants of the library instead of checking them, currently the same as `major_malf_is_undefined`
+
+`rand`: Enables random sampling helpers such as `sample_keys()` and `visit_random_mut()` on [Prison](crate::single_threaded::Prison), using any random number generator that implements `rand_core::RngCore`
+
+`profile`: Enables `profile_scope()` on [Prison](crate::single_threaded::Prison), which attributes reference acquisitions, errors, and time spent inside `visit()` closures to named scopes, and `visit_mut_timed()` and `visit_ref_timed()`, which record closure durations in per-key and per-scope histograms (requires `std`)
+
+`lru`: Stamps every cell of a [Prison](crate::single_threaded::Prison) with an access counter whenever it is used, enabling `least_recently_used()` and `most_recently_used()` queries (adds 8 bytes per element)
+
+`uuid`: Assigns every value inserted into a [Prison](crate::single_threaded::Prison) a unique 64-bit uuid that stays the same for as long as the value lives there, enabling `uuid_of()` and `key_by_uuid()` lookups that keep working when other operations move values to new indexes (adds 8 bytes per element and 16 bytes per valid value)
+
+`dirty`: Marks every value of a [Prison](crate::single_threaded::Prison) as dirty whenever it is mutably referenced, enabling `drain_dirty()` to report which values changed since the last call (adds up to 8 bytes per element)
+
+`transform`: Enables `with_transform()` on [Prison](crate::single_threaded::Prison), which stores every value in an encoded form produced by a user-supplied function and transparently decodes it into a scratch value when it is visited, cloned, or removed, allowing large seldom-read values to be kept compressed or obfuscated in memory
+
+`recycle_stats`: Counts how many times each cell of a [Prison](crate::single_threaded::Prison) has had its value retired by a removal, overwrite, or recycle, enabling `recycle_count()` and `most_recycled()` to find hot slots that drive generation growth (adds 8 bytes per element)
+
+`ttl`: Enables `insert_with_ttl()`, `extend_ttl()`, `ttl_remaining()` and `tick_expiry()` on [Prison](crate::single_threaded::Prison), letting values expire a number of caller-defined ticks after insertion so that short-lived entries like sessions or projectiles are removed without a separate timer structure (adds 16 bytes per element)
+
+`serde`: Implements `Serialize` and `Deserialize` for [Prison](crate::single_threaded::Prison) and [CellKey], preserving the index, generation, and free-list layout of every cell so that [CellKey]'s saved alongside a [Prison](crate::single_threaded::Prison) remain valid after it is reloaded
+
+`index`: Enables the `index` module with `GridIndex`, a uniform-grid spatial index over the values of a [Prison](crate::single_threaded::Prison) that is kept up to date with the marks of the `dirty` feature (implies `dirty`, requires `std`)
+
+`raw`: Enables `raw_cells()` on [SnapshotRef](crate::single_threaded::SnapshotRef) and `overwrite_raw()` on [Prison](crate::single_threaded::Prison) for value types implementing the `PlainData` marker trait, exposing every value as plain bytes so tooling such as asset hot-reloaders can inspect and patch live values without knowing their type
+
+`offload`: Enables `enable_offload()`, `offload()` and `offload_coldest()` on [Prison](crate::single_threaded::Prison), spilling unreferenced cold values to a backing file through user-supplied serialize and deserialize functions and transparently reloading them the next time they are accessed, so that tooling can hold far more values than fit comfortably in memory (implies `lru`, requires `std`)
+
+`derive`: Enables `#[derive(PrisonValue)]` from the companion `grit-data-prison-derive` crate, which implements [PrisonValue] for a struct that stores its own [CellKey] and declares a typed newtype key for it, exactly like the [prison_value!] macro does without the `syn` and `quote` dependencies
 
 Major Malfunctions:
-this crate can be passed one of three (optional) features that define how the library handles behavior that is DEFINITELY un-intended and should be considered a bug in the library itself. It defaults to `major_malf_is_err` if none are specified:
+this crate can be passed one of three (optional) features that define how the library handles behavior that is DEFINITELY un-intended and should be considered a bug in the library itself. It defaults to `major_malf_is_err` if none are specified, and enabling more than one is a compile error, so the crate cannot be built with `--all-features` (list the features explicitly instead):
 - `major_malf_is_err`: major malfunctions will be returned as an [AccessError::MAJOR_MALFUNCTION(msg)], this is the default even if not specified
 - `major_malf_is_panic`: major malfunctions will result in a call to `panic(msg)` describing the unexpected behavior
 - `major_malf_is_undefined`: branches where a major malfunction would nomally be are replaced with [unreachable_unchecked()], possibly allowing them to be removed from compilation entirely
+
+In debug builds, dropping a [Prison](crate::single_threaded::Prison) also verifies that no accesses are outstanding
+(which can only happen if a guard was leaked) and that its house-keeping values are consistent. A failed check panics
+with `major_malf_is_panic` and is otherwise printed to stderr (or ignored without `std`)
+### Minimum Supported Rust Version
+The MSRV is Rust 1.81, declared as `rust-version` in `Cargo.toml` so that older toolchains fail with a clear message
+instead of an obscure error. It is set by the newest language and library APIs the crate depends on:
+- [Error] for [AccessError] is implemented through `core::error::Error` in every build, including `no_std` (1.81)
+- `#![debugger_visualizer]` embeds the gdb pretty-printer script into the compiled crate (1.71)
 # Performance
 
 ### Speed
-(Benchmarks are Coming Soon™)
+`visit_mut()`, `visit_ref()` and the reference counting they perform are marked `#[inline(always)]`, so a simple closure
+compiles down to the bounds check, generation check and reference count bookkeeping around the closure body (about 30
+instructions on x86_64), with only the rare path that wakes waiting tasks kept out of line. This is enforced by the
+`codegen` crate in this repository, whose tests build `#[no_mangle]` shims in release mode and fail if the emitted
+assembly contains any other call or grows past a fixed instruction budget. Microbenchmarks comparing the same shims
+to plain [Vec] indexing can be run with `cargo bench -p grit-data-prison-codegen`
+
+Batch operations such as `visit_many_*()`, `guard_many_*()` and `visit_set_*()` keep the references they
+collect in inline buffers with room for 8 elements, so the common case of visiting a handful of values at once
+never touches the heap. Batches larger than that spill over into a normal [Vec]
+
+### Storage
+Every [Prison](crate::single_threaded::Prison) keeps its cells in a [PrisonStorage] backend chosen by its second type
+parameter, `Prison<T, S = VecStorage>`. The visit, guard, insert and removal logic is written once against the sealed
+[StorageContainer] trait, so additional storage modes can be added without duplicating it. [VecStorage] is the default
+and currently the only backend, which keeps `Prison<T>` exactly as it was
 
 ### Size
-[Prison<T>](crate::single_threaded::Prison) has 4 [usize] house-keeping values in addition to a [Vec<PrisonCell<T>>]
+[Prison<T>](crate::single_threaded::Prison) has 8 [usize] house-keeping values and a [Vec] of wakers for tasks waiting on a value
+in addition to a [Vec<PrisonCell<T>>], all of which are used by every access.
+The state of the less common features (move hooks, key maintenance, groups, reserved index ranges, pins, watched keys,
+deferred removals, spare buffers for large batch accesses, placeholders, index validation, interrupts and redaction) lives in a box that is only allocated the first time one of them is used, and every optional crate feature
+that needs house-keeping values (`profile`, `lru`, `ttl`, `uuid`, `dirty`, `transform` and `offload`) only adds them when it is enabled
 
 Although the abstract of each `PrisonCell<T>` is as described as found in [How is This Safe?!](#how-is-this-safe),
 the truth of the matter it that Rust was not optimising the memory footprint where it could have done so using Enums, so I had to roll my own
@@ -403,9 +466,9 @@ type of enum:
         - In addition, the most significant bit of `d_gen_or_prev` is reserved for marking the variant of the `PrisonCell` (the `d` is for `discriminant`). This means the *ACTUAL* maximum generation count is [isize::MAX](std::isize::MAX), but the prev index is unafected because a [Vec] cannot have more than [isize::MAX](std::isize::MAX) elements anyway...
     - field `val` is a [`MaybeUninit<T>`] that is always assumed uninitialized when the element is in `Free` state, and always assumed initialized when it is in `Cell` state.
 
-Therefore the total _additional_ size compared to a [Vec<T>] on a 64-bit system is 32 bytes flat + 16 bytes per element,
-and these values are validated in the test suite with an optional test that checks [mem::size_of](std::mem::size_of) for several
-types of `T`
+Therefore without optional features the total _additional_ size compared to a [Vec<T>] on a 64-bit system is 88 bytes flat plus
+16 bytes per element, plus a single 208 byte allocation once one of the less common features is used. These values are validated
+in the test suite with a test that checks [mem::size_of](std::mem::size_of) for several types of `T`
 
 # How this crate may change in the future
 
@@ -419,10 +482,10 @@ Possible future additions may include:
 - [x] Switch to reference counting with same memory footprint
 - [ ] Const Generic bounds to customize the size of internal utility values
 - [ ] More public methods (as long as they make sense and don't bloat the API)
-- [ ] Multi-thread safe `AtomicPrison<T>`
+- [x] Multi-thread safe [AtomicPrison<T>](crate::multi_threaded::AtomicPrison)
 - [x] ? Single standalone value version, [JailCell<T>](crate::single_threaded::JailCell)
-- [ ] ? Multi-thread safe standalone value version, `AtomicJailCell<T>`
-- [ ] ?? Completely unchecked and unsafe version `UnPrison<T>`
+- [x] ? Multi-thread safe standalone value version, [AtomicJailCell<T>](crate::multi_threaded::AtomicJailCell)
+- [x] ?? Completely unchecked and unsafe version [UnPrison<T>](crate::single_threaded::UnPrison)
 - [ ] ??? Multi-thread ~~safe~~ unsafe version `AtomicUnPrison<T>`
 
 # How to Help/Contribute
@@ -445,6 +508,7 @@ The best way to do this would be to follow these steps:
     - solve the problem in your branch and create a pull request into the `dev` branch with a message explaining everything
     - create a pull request with only the test proving the failure point with a message describing why it is a failure and that *this pull request does not solve the problem*
 # Changelog
+ - Unreleased: BREAKING change: [AccessError] is now `#[non_exhaustive]`, so a `match` on it outside this crate needs a wildcard arm. Every variant is covered by one of its classification methods (`is_contention()`, `is_stale_key()`, `is_capacity()`, `is_refused()`, `is_io()`, `is_bug()`), which lets future variants be added without another breaking change
  - Version 0.4.0: BREAKING change: change `peek_ref()` and `peek_ref_idx()` to return [Result<T, AccessError>] instead of [Option<T>], and add `peek_ref()` to [JailCell](crate::single_threaded::JailCell)
      - I know it's a very small difference, but breaking is breaking, sorry! It should have been a `Result` from the beginning to match the existing API and allow easy error propogation inside functions that expect `AccessError`s without a bunch of boilerplate testing for `Some`/`None` just to return a `AccessError::ValueDeleted` anyway
  - Version 0.3.1: Non-Breaking feature: `peek_ref()` and `peek_ref_idx()`, UNSAFE methods that allow the caller to get a reference to a value while bypassing reference counting and other safety checks
@@ -468,39 +532,73 @@ The best way to do this would be to follow these steps:
 #![deny(missing_docs)]
 #![allow(clippy::needless_return)]
 #![allow(clippy::needless_lifetimes)]
+#![debugger_visualizer(gdb_script_file = "../debugger/gdb_grit_data_prison.py")]
+
+//====== Crate Features ======
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+#[cfg(not(feature = "alloc"))]
+compile_error!("grit-data-prison stores its values in a `Vec` and requires the `alloc` feature (enabled by default through `std`)");
+
+#[cfg(any(
+    all(feature = "major_malf_is_err", feature = "major_malf_is_panic"),
+    all(feature = "major_malf_is_err", feature = "major_malf_is_undefined"),
+    all(feature = "major_malf_is_panic", feature = "major_malf_is_undefined"),
+))]
+compile_error!("only one of `major_malf_is_err`, `major_malf_is_panic` and `major_malf_is_undefined` (implied by `unsafe_opt`) may be enabled");
 
 //====== Crate Imports ======
-#[cfg(not(feature = "no_std"))]
-pub(crate) use std::{
-    borrow::{Borrow, BorrowMut},
-    cell::UnsafeCell,
-    error::Error,
-    fmt::{Debug, Display},
-    hint::unreachable_unchecked,
-    mem::{replace as mem_replace, MaybeUninit},
-    ops::{Deref, DerefMut, RangeBounds},
+extern crate alloc;
+
+// Lets the tests use `#[derive(PrisonValue)]`, whose output names this crate by its absolute path
+#[cfg(all(test, feature = "derive"))]
+extern crate self as grit_data_prison;
+
+pub(crate) use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
 };
 
-#[cfg(feature = "no_std")]
+#[cfg(feature = "index")]
+pub(crate) use alloc::collections::BTreeMap;
+
 pub(crate) use core::{
     borrow::{Borrow, BorrowMut},
-    cell::UnsafeCell,
+    cell::{Cell, UnsafeCell},
+    cmp::Ordering as CmpOrdering,
+    error::Error,
     fmt::{Debug, Display},
-    hint::unreachable_unchecked,
-    mem::{replace as mem_replace, MaybeUninit},
-    ops::{Deref, DerefMut, RangeBounds},
+    future::Future,
+    hash::{Hash, Hasher},
+    hint::{spin_loop, unreachable_unchecked},
+    marker::PhantomData,
+    mem::{align_of, replace as mem_replace, size_of, swap as mem_swap, take as mem_take, ManuallyDrop, MaybeUninit},
+    ops::{AddAssign, Deref, DerefMut, Index, RangeBounds, SubAssign},
+    panic::{RefUnwindSafe, UnwindSafe},
+    pin::Pin,
+    ptr::{drop_in_place, eq as ptr_eq, read as ptr_read, NonNull},
+    slice::{from_raw_parts as slice_from_raw_parts, from_raw_parts_mut as slice_from_raw_parts_mut, Iter as SliceIter},
+    sync::atomic::{AtomicBool, AtomicU32, AtomicUsize, Ordering as AtomicOrdering},
+    task::{Context, Poll, Waker},
 };
 
-#[cfg(feature = "no_std")]
-pub(crate) trait Error: Debug + Display {
-    fn source(&self) -> Option<&(dyn Error + 'static)> {
-        None
-    }
-}
-
 /// Module defining the version(s) of [Prison<T>](crate::single_threaded::Prison) and [JailCell<T>](crate::single_threaded::JailCell) suitable for use only from within a single-thread
 pub mod single_threaded;
 
+/// Module defining the version(s) of [Prison<T>](crate::single_threaded::Prison) and [JailCell<T>](crate::single_threaded::JailCell) that can be shared between threads, [AtomicPrison<T>](crate::multi_threaded::AtomicPrison) and [AtomicJailCell<T>](crate::multi_threaded::AtomicJailCell)
+pub mod multi_threaded;
+
+/// Module defining companion structures that index the values of a [Prison<T>](crate::single_threaded::Prison), such as the spatial [GridIndex](crate::index::GridIndex)
+#[cfg(feature = "index")]
+pub mod index;
+
+/// Derive macro generating the same items as [prison_value!], see [PrisonValue]
+#[cfg(feature = "derive")]
+pub use grit_data_prison_derive::PrisonValue;
+
 //ENUM AccessError
 /// Error type that provides helpful information about why an operation on any
 /// [Prison](crate::single_threaded::Prison) or [JailCell](crate::single_threaded::JailCell) failed
@@ -527,7 +625,12 @@ pub mod single_threaded;
 /// [std::fmt::Debug] traits, with the `Display` version giving a short description of the problem,
 /// and the `Debug` version giving a more in-depth explaination of exactly why an error had to be
 /// returned
+///
+/// [AccessError] is `#[non_exhaustive]`, so new variants can be added without a breaking change. Code outside this crate
+/// that needs to react to a family of errors rather than one variant should use the classification methods such as
+/// [AccessError::is_contention()] instead of a `match` with a wildcard arm
 #[derive(PartialEq, Eq)] //COV_IGNORE
+#[non_exhaustive]
 pub enum AccessError {
     /// Indicates that an operation attempted to access an index beyond the range of the [Prison<T>](crate::single_threaded::Prison),
     /// along with the offending index
@@ -558,6 +661,56 @@ pub enum AccessError {
     MaximumCapacityReached,
     /// Indicates that you (somehow) reached the limit for reference counting immutable references
     MaximumImmutableReferencesReached(usize),
+    /// Indicates that one or more members of a [CellKeySet] could not be referenced because they were
+    /// already referenced by another operation, along with the full set of blocked keys
+    SetMembersBlocked(CellKeySet),
+    /// Indicates that a [GroupKey] did not reference a group in the [Prison<T>](crate::single_threaded::Prison),
+    /// either because it was already removed or because it came from a different [Prison<T>](crate::single_threaded::Prison),
+    /// along with the group id in question
+    GroupNotFound(usize),
+    /// Indicates that a long-running operation reached a checkpoint after an interrupt was requested with
+    /// [Prison::request_interrupt()](crate::single_threaded::Prison::request_interrupt)
+    InterruptRequested,
+    /// Indicates that a [ScriptHandle] created by one [Prison<T>](crate::single_threaded::Prison) was used with a different one,
+    /// along with the id of the [Prison<T>](crate::single_threaded::Prison) that created the handle
+    ForeignHandle(usize),
+    /// Indicates that an operation attempted to remove or overwrite a value that was pinned with
+    /// [Prison::pin()](crate::single_threaded::Prison::pin), along with the index in question
+    ValuePinned(usize),
+    /// Indicates that an `_idx` method was passed an index salted for a different [Prison](crate::single_threaded::Prison)
+    /// (or not salted at all) while index validation was enabled, along with the index in question
+    ///
+    /// See [Prison::set_idx_validation()](crate::single_threaded::Prison::set_idx_validation)
+    ForeignIndex(usize),
+    /// Indicates that an operation requiring an idle [Prison](crate::single_threaded::Prison) found
+    /// references that were never released, along with the number of outstanding accesses
+    AccessesOutstanding(usize),
+    /// Indicates that an operation that could modify a [Prison](crate::single_threaded::Prison) (or mutably reference one of its values)
+    /// was attempted while it was sealed with [Prison::seal_read_only()](crate::single_threaded::Prison::seal_read_only)
+    PrisonSealed,
+    /// Indicates that a `visit()` closure was not run because the [Prison](crate::single_threaded::Prison) was already
+    /// running as many nested `visit()` closures as its depth limit allows, along with the limit in question
+    ///
+    /// See [Prison::set_depth_limit()](crate::single_threaded::Prison::set_depth_limit)
+    VisitDepthExceeded(usize),
+    /// Indicates that raw bytes written over a value did not have exactly the size of the value,
+    /// along with the size of the value and the number of bytes given, in that order
+    RawSizeMismatch(usize, usize),
+    /// Indicates that an operation attempted to access a value inserted with
+    /// [Prison::insert_hidden()](crate::single_threaded::Prison::insert_hidden) that was not published yet,
+    /// along with the index in question
+    ValueHidden(usize),
+    /// Indicates that a value could not be offloaded to or reloaded from the backing file of a
+    /// [Prison](crate::single_threaded::Prison), either because no file was set with
+    /// `Prison::enable_offload()` (with the `offload` feature) or because reading or writing the file failed,
+    /// along with the index in question
+    OffloadFailed(usize),
+    /// Indicates that a method visiting a pair of values, such as [Prison::visit_pair_mut()](crate::single_threaded::Prison::visit_pair_mut),
+    /// was given two keys with the same index, along with the index in question
+    PairSameIndex(usize),
+    /// Indicates that an operation choosing one of the values of a [Prison](crate::single_threaded::Prison), such as
+    /// `Prison::visit_random_mut()` (with the `rand` feature), found no visible value to choose from
+    NoValuesToChoose,
     /// Indicates that the operation created an invalid and unexpected state. This may have resulted in memory leaking, mutable aliasing, undefined behavior, etc.
     ///
     /// This error should be considered a BUG inside the library crate `grit-data-prison` and reported to the author of the crate
@@ -568,7 +721,7 @@ pub enum AccessError {
 impl AccessError {
     /// Returns a string that shows the [AccessError] variant and value, if any
     pub fn kind(&self) -> String {
-        match &*self {
+        match self {
             Self::IndexOutOfRange(idx) => format!("AccessError::IndexOutOfRange({})", idx),
             Self::ValueAlreadyMutablyReferenced(idx) => {
                 format!("AccessError::ValueAlreadyMutablyReferenced({})", idx)
@@ -577,31 +730,147 @@ impl AccessError {
                 format!("AccessError::ValueStillImmutablyReferenced({})", idx)
             }
             Self::InsertAtMaxCapacityWhileAValueIsReferenced => {
-                format!("AccessError::InsertAtMaxCapacityWhileAValueIsReferenced")
+                "AccessError::InsertAtMaxCapacityWhileAValueIsReferenced".to_string()
             }
             Self::ValueDeleted(idx, gen) => format!("AccessError::ValueDeleted({}, {})", idx, gen),
             Self::MaxValueForGenerationReached => {
-                format!("AccessError::MaxValueForGenerationReached")
+                "AccessError::MaxValueForGenerationReached".to_string()
             }
             Self::RemoveWhileValueReferenced(idx) => {
                 format!("AccessError::RemoveWhileValueReferenced({})", idx)
             }
             Self::IndexIsNotFree(idx) => format!("AccessError::IndexIsNotFree({})", idx),
-            Self::MaximumCapacityReached => format!("AccessError::MaximumCapacityReached"),
+            Self::MaximumCapacityReached => "AccessError::MaximumCapacityReached".to_string(),
             Self::MaximumImmutableReferencesReached(idx) => {
                 format!("AccessError::MaximumImmutableReferencesReached({})", idx)
             }
             Self::OverwriteWhileValueReferenced(idx) => {
                 format!("AccessError::OverwriteWhileValueReferenced({})", idx)
             }
+            Self::SetMembersBlocked(set) => format!("AccessError::SetMembersBlocked({:?})", set.as_slice()),
+            Self::GroupNotFound(id) => format!("AccessError::GroupNotFound({})", id),
+            Self::InterruptRequested => "AccessError::InterruptRequested".to_string(),
+            Self::ForeignHandle(id) => format!("AccessError::ForeignHandle({})", id),
+            Self::ValuePinned(idx) => format!("AccessError::ValuePinned({})", idx),
+            Self::ForeignIndex(idx) => format!("AccessError::ForeignIndex({:#x})", idx),
+            Self::AccessesOutstanding(count) => format!("AccessError::AccessesOutstanding({})", count),
+            Self::PrisonSealed => "AccessError::PrisonSealed".to_string(),
+            Self::VisitDepthExceeded(limit) => format!("AccessError::VisitDepthExceeded({})", limit),
+            Self::RawSizeMismatch(size, given) => format!("AccessError::RawSizeMismatch({}, {})", size, given),
+            Self::ValueHidden(idx) => format!("AccessError::ValueHidden({})", idx),
+            Self::OffloadFailed(idx) => format!("AccessError::OffloadFailed({})", idx),
+            Self::PairSameIndex(idx) => format!("AccessError::PairSameIndex({})", idx),
+            Self::NoValuesToChoose => "AccessError::NoValuesToChoose".to_string(),
             Self::MAJOR_MALFUNCTION(msg) => format!("AccessError::MAJOR_MALFUNCTION({})", msg),
         }
     }
+
+    /// Returns `true` if the error was caused by another active reference blocking the operation
+    ///
+    /// These errors are temporary: the same operation may succeed once the conflicting
+    /// `visit()` or `guard()` ends
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(42)?;
+    /// prison.visit_mut(key_0, |val_0| {
+    ///     let err = prison.visit_ref(key_0, |val_0_again| Ok(())).unwrap_err();
+    ///     assert!(err.is_contention());
+    ///     assert!(err.is_retryable());
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_contention(&self) -> bool {
+        matches!(
+            self,
+            Self::ValueAlreadyMutablyReferenced(_)
+                | Self::ValueStillImmutablyReferenced(_)
+                | Self::OverwriteWhileValueReferenced(_)
+                | Self::RemoveWhileValueReferenced(_)
+                | Self::InsertAtMaxCapacityWhileAValueIsReferenced
+                | Self::MaximumImmutableReferencesReached(_)
+                | Self::SetMembersBlocked(_)
+                | Self::AccessesOutstanding(_)
+        )
+    }
+
+    /// Returns `true` if the error was caused by a [CellKey], index, or handle that does not
+    /// reference a valid value, either because the value was deleted, because it never existed,
+    /// or because it belongs to a different [Prison](crate::single_threaded::Prison)
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(42)?;
+    /// prison.remove(key_0)?;
+    /// assert!(prison.visit_ref(key_0, |val_0| Ok(())).unwrap_err().is_stale_key());
+    /// assert!(prison.visit_ref_idx(10, |val_10| Ok(())).unwrap_err().is_stale_key());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_stale_key(&self) -> bool {
+        matches!(
+            self,
+            Self::ValueDeleted(_, _)
+                | Self::IndexOutOfRange(_)
+                | Self::GroupNotFound(_)
+                | Self::ForeignHandle(_)
+                | Self::ForeignIndex(_)
+        )
+    }
+
+    /// Returns `true` if the error was caused by reaching a hard limit of the [Prison](crate::single_threaded::Prison),
+    /// such as the maximum capacity, the maximum generation, or the visit depth limit
+    pub fn is_capacity(&self) -> bool {
+        matches!(
+            self,
+            Self::MaximumCapacityReached | Self::MaxValueForGenerationReached | Self::VisitDepthExceeded(_)
+        )
+    }
+
+    /// Returns `true` if the operation was refused because of how it was called or because of a state the caller
+    /// put the [Prison](crate::single_threaded::Prison) or value in, such as a sealed [Prison](crate::single_threaded::Prison),
+    /// a pinned or hidden value, an occupied index, or an empty [Prison](crate::single_threaded::Prison) to choose from,
+    /// so it will keep failing until the caller changes something
+    pub fn is_refused(&self) -> bool {
+        matches!(
+            self,
+            Self::PrisonSealed
+                | Self::ValuePinned(_)
+                | Self::ValueHidden(_)
+                | Self::IndexIsNotFree(_)
+                | Self::RawSizeMismatch(_, _)
+                | Self::PairSameIndex(_)
+                | Self::NoValuesToChoose
+        )
+    }
+
+    /// Returns `true` if the error came from reading or writing the backing file of the `offload` feature
+    pub fn is_io(&self) -> bool {
+        matches!(self, Self::OffloadFailed(_))
+    }
+
+    /// Returns `true` if the error is an [AccessError::MAJOR_MALFUNCTION(msg)], indicating a bug
+    /// inside this crate rather than a misuse of its API
+    pub fn is_bug(&self) -> bool {
+        matches!(self, Self::MAJOR_MALFUNCTION(_))
+    }
+
+    /// Returns `true` if the exact same operation may succeed if attempted again later without any
+    /// other changes to its inputs
+    ///
+    /// This includes every error where [AccessError::is_contention()] is true, as well as [AccessError::InterruptRequested]
+    pub fn is_retryable(&self) -> bool {
+        self.is_contention() || matches!(self, Self::InterruptRequested)
+    }
 }
 
 impl Display for AccessError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &*self {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
             Self::IndexOutOfRange(idx) => write!(f, "Index [{}] is out of range", idx),
             Self::ValueAlreadyMutablyReferenced(idx) => write!(f, "Value at index [{}] is already being mutably referenced by another operation", idx),
             Self::ValueStillImmutablyReferenced(idx) => write!(f, "Value at index [{}] is still being immutably referenced by another operation, cannot mutably reference", idx),
@@ -613,14 +882,28 @@ impl Display for AccessError {
             Self::MaximumCapacityReached => write!(f, "Prison has reached the maximum capacity allowed by Rust"),
             Self::MaximumImmutableReferencesReached(idx) => write!(f, "Value at index [{}] has reached the maximum number of immutable references: {}", idx, usize::MAX - 2),
             Self::OverwriteWhileValueReferenced(idx) => write!(f, "Value at index [{}] still has active references, cannot overwrite", idx),
+            Self::SetMembersBlocked(set) => write!(f, "{} value(s) in the requested set are already being referenced by other operations: {:?}", set.len(), set.as_slice()),
+            Self::GroupNotFound(id) => write!(f, "Group [{}] does not exist", id),
+            Self::InterruptRequested => write!(f, "An interrupt was requested, operation stopped at a checkpoint"),
+            Self::ForeignHandle(id) => write!(f, "Handle was created by a different Prison (id {})", id),
+            Self::ForeignIndex(idx) => write!(f, "Index {:#x} was not salted for this Prison", idx),
+            Self::AccessesOutstanding(count) => write!(f, "Prison still has {} outstanding accesses", count),
+            Self::PrisonSealed => write!(f, "Prison is sealed read-only, cannot modify or mutably reference its values"),
+            Self::ValuePinned(idx) => write!(f, "Value at index [{}] is pinned, cannot remove or overwrite", idx),
+            Self::VisitDepthExceeded(limit) => write!(f, "Visit closures are already nested {} deep, cannot visit deeper", limit),
+            Self::RawSizeMismatch(size, given) => write!(f, "Cannot write {} raw bytes over a value of {} bytes", given, size),
+            Self::ValueHidden(idx) => write!(f, "Value at index {} is hidden until it is published", idx),
+            Self::OffloadFailed(idx) => write!(f, "Value at index {} could not be offloaded to or reloaded from the offload file", idx),
+            Self::PairSameIndex(idx) => write!(f, "Both keys of a pair referenced index {}", idx),
+            Self::NoValuesToChoose => write!(f, "Prison has no visible values to choose from"),
             Self::MAJOR_MALFUNCTION(msg) => write!(f, "{}\n-------\nIndicates that the operation created an invalid and unexpected state. This may have resulted in memory leaking, mutable aliasing, undefined behavior, etc.", msg),
         }
     }
 }
 
 impl Debug for AccessError {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        match &*self {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
             Self::IndexOutOfRange(idx) => write!(f, "Index [{}] is out of range", idx),
             Self::ValueAlreadyMutablyReferenced(idx) => write!(f, "Value at index [{}] is already being mutably referenced by another operation\n---------\nMutably referencing the same cell twice or immutably referencing a value being mutably referenced violates Rust's memory saftey rules", idx),
             Self::ValueStillImmutablyReferenced(idx) => write!(f, "Value at index [{}] is still being immutably referenced by another operation, cannot mutably reference\n---------\nMutably referencing a cell while an immutable reference to it is still in scope violates Rust's memory saftey rules", idx),
@@ -632,6 +915,20 @@ impl Debug for AccessError {
             Self::MaximumCapacityReached => write!(f, "Prison has reached the maximum capacity allowed by Rust\n---------\nRust does not allow a [Vec] to have a capacity longer than [isize::MAX] becuase most operating systems only allow half of the total memory space to be addressed by programs"),
             Self::MaximumImmutableReferencesReached(idx) => write!(f, "Value at index [{}] has reached the maximum number of immutable references: {}\n---------\nThis highly unlikely scenario means you somehow created {} immutable references to the value already", idx, usize::MAX - 2, usize::MAX - 2),
             Self::OverwriteWhileValueReferenced(idx)=> write!(f, "Value at index [{}] still has active references, cannot overwrite\n---------\nOverwriting a value with active references is the same as mutating a variable being immutably referenced, violating Rust's memory safety rules", idx),
+            Self::SetMembersBlocked(set) => write!(f, "{} value(s) in the requested set are already being referenced by other operations: {:?}\n---------\nAll members of the set are checked before failing so the complete list of conflicting keys can be reported at once, and no references from the set are held after this error is returned", set.len(), set.as_slice()),
+            Self::GroupNotFound(id) => write!(f, "Group [{}] does not exist\n---------\nThe group was either already removed with Prison::remove_group(), or the GroupKey was created by a different Prison", id),
+            Self::InterruptRequested => write!(f, "An interrupt was requested, operation stopped at a checkpoint\n---------\nAnother part of the program called Prison::request_interrupt() because it needs the Prison to become idle (for example to perform maintenance), so the operation should release its references and be retried after Prison::clear_interrupt()"),
+            Self::ForeignHandle(id) => write!(f, "Handle was created by a different Prison (id {})\n---------\nA ScriptHandle can only be used with the Prison that created it, using it with another Prison would reference an unrelated value", id),
+            Self::ForeignIndex(idx) => write!(f, "Index {:#x} was not salted for this Prison\n---------\nWhile index validation is enabled, indexes passed to `_idx` methods must come from Prison::salt_idx() on the same Prison", idx),
+            Self::AccessesOutstanding(count) => write!(f, "Prison still has {} outstanding accesses\n---------\nThe Prison was consumed while references were still counted, which can only happen if a guard was leaked (for example with mem::forget())", count),
+            Self::PrisonSealed => write!(f, "Prison is sealed read-only, cannot modify or mutably reference its values\n---------\nReferences to a sealed Prison are not counted, so nothing may change until Prison::unseal() is called"),
+            Self::ValuePinned(idx) => write!(f, "Value at index [{}] is pinned, cannot remove or overwrite\n---------\nPinned values are protected from removal and overwriting until Prison::unpin() is called on them", idx),
+            Self::VisitDepthExceeded(limit) => write!(f, "Visit closures are already nested {} deep, cannot visit deeper\n---------\nThe Prison was given a depth limit with Prison::set_depth_limit(), usually to catch accidental recursion through visit closures. Prison::max_depth() reports the deepest nesting reached so far", limit),
+            Self::RawSizeMismatch(size, given) => write!(f, "Cannot write {} raw bytes over a value of {} bytes\n---------\nRaw byte views replace the whole value at once, so the bytes must come from a value of the same type (or at least the same size). Prison::raw_size() reports the expected size", given, size),
+            Self::ValueHidden(idx) => write!(f, "Value at index {} is hidden until it is published\n---------\nThe value was inserted with Prison::insert_hidden() so that it cannot be observed before it is fully built. Only Prison::visit_hidden_mut() can access it until Prison::publish() is called with its key", idx),
+            Self::OffloadFailed(idx) => write!(f, "Value at index {} could not be offloaded to or reloaded from the offload file\n---------\nEither no offload file was set with Prison::enable_offload(), or reading or writing the file failed. A value that failed to reload stays offloaded, so the access can be retried once the file is readable again", idx),
+            Self::PairSameIndex(idx) => write!(f, "Both keys of a pair referenced index {}\n---------\nA pair of mutable references must point to two distinct values. Check whether the two keys are the same before visiting them as a pair, and visit the value once instead", idx),
+            Self::NoValuesToChoose => write!(f, "Prison has no visible values to choose from\n---------\nThe Prison is empty, or every value in it is hidden with insert_hidden() and not published yet"),
             Self::MAJOR_MALFUNCTION(msg) => write!(f, "{}\n-------\nIndicates that the operation created an invalid and unexpected state. This may have resulted in memory leaking, mutable aliasing, undefined behavior, etc.\n---------\nThis error should be considered a BUG inside the library crate `grit-data-prison` and reported to the author of the crate", msg),
         }
     }
@@ -639,12 +936,99 @@ impl Debug for AccessError {
 
 impl Error for AccessError {}
 
+//STRUCT BatchError
+/// Error type returned by the `_batch` methods of [Prison](crate::single_threaded::Prison), such as
+/// [Prison::visit_batch_mut()](crate::single_threaded::Prison::visit_batch_mut), that keep going after a failure
+///
+/// Holds every [AccessError] encountered along with the position of the [CellKey] in the input slice that caused it,
+/// in ascending order of position, so a large imported dataset can be checked in a single pass instead of being retried
+/// every time the first problem is fixed. A [BatchError] always holds at least one error
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, BatchError, CellKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// prison.remove(key_0)?;
+/// let err = prison.clone_many_vals_batch(&[key_0, key_1, CellKey::from_raw_parts(5, 0)]).unwrap_err();
+/// assert_eq!(err.len(), 2);
+/// assert_eq!(err.positions().collect::<Vec<_>>(), vec![0, 2]);
+/// assert_eq!(err.first(), (0, &AccessError::ValueDeleted(0, 0)));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Eq, PartialEq)] //COV_IGNORE
+pub struct BatchError {
+    errors: Vec<(usize, AccessError)>,
+}
+
+impl BatchError {
+    //FN BatchError::from_errors()
+    #[doc(hidden)]
+    pub(crate) fn from_errors(errors: Vec<(usize, AccessError)>) -> Result<(), BatchError> {
+        if errors.is_empty() {
+            return Ok(());
+        }
+        return Err(BatchError { errors });
+    }
+
+    /// Return the number of errors
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        return self.errors.len();
+    }
+
+    /// Return the first error (the one with the lowest position) and its position
+    pub fn first(&self) -> (usize, &AccessError) {
+        let (pos, err) = &self.errors[0];
+        return (*pos, err);
+    }
+
+    /// Return an iterator over the positions that failed, in ascending order
+    pub fn positions(&self) -> impl Iterator<Item = usize> + '_ {
+        return self.errors.iter().map(|(pos, _)| *pos);
+    }
+
+    /// Return an iterator over every `(position, error)` pair in ascending order of position
+    pub fn iter(&self) -> SliceIter<'_, (usize, AccessError)> {
+        return self.errors.iter();
+    }
+
+    /// Consume the [BatchError] and return every `(position, error)` pair in ascending order of position
+    pub fn into_errors(self) -> Vec<(usize, AccessError)> {
+        return self.errors;
+    }
+}
+
+//IMPL Display for BatchError
+impl Display for BatchError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let (pos, err) = self.first();
+        return write!(f, "{} operation(s) in the batch failed, first at position {}: {}", self.errors.len(), pos, err);
+    }
+}
+
+impl Error for BatchError {}
+
+//IMPL From<BatchError> for AccessError
+/// Converts a [BatchError] into its first [AccessError], discarding the rest, so `_batch` methods can be used with `?`
+/// inside functions and closures that return [AccessError]
+impl From<BatchError> for AccessError {
+    fn from(batch: BatchError) -> Self {
+        return batch.errors.into_iter().next().map(|(_, err)| err).unwrap_or(AccessError::MAJOR_MALFUNCTION(String::from("empty BatchError")));
+    }
+}
+
 //STRUCT CellKey
 /// Struct that defines a packaged index into a [Prison](crate::single_threaded::Prison)
 ///
 /// This struct is designed to be passed to some other struct or function that needs to be able to
 /// reference the data stored at the cell number.
-#[derive(Debug, Copy, Clone, Eq, PartialEq)] //COV_IGNORE
+///
+/// Keys can be hashed and are ordered by index first and generation second, so they can be used as keys of a
+/// [HashMap](std::collections::HashMap) or [BTreeMap](std::collections::BTreeMap) and sorted into index order
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, PartialOrd, Ord)] //COV_IGNORE
 pub struct CellKey {
     idx: usize,
     gen: usize,
@@ -676,24 +1060,1501 @@ impl CellKey {
     }
 }
 
-//====== Crate Utilities ======
-//FN extract_true_start_end
-#[doc(hidden)]
-fn extract_true_start_end<B>(range: B, max_len: usize) -> (usize, usize)
-where
-    B: RangeBounds<usize>,
-{
-    let start = match range.start_bound() {
-        std::ops::Bound::Included(first) => *first,
-        std::ops::Bound::Excluded(one_before_first) => *one_before_first + 1,
-        std::ops::Bound::Unbounded => 0,
-    };
-    let end = match range.end_bound() {
-        std::ops::Bound::Included(last) => *last + 1,
-        std::ops::Bound::Excluded(one_after_last) => *one_after_last,
-        std::ops::Bound::Unbounded => max_len,
-    };
-    return (start, end);
+//IMPL Serialize for CellKey
+#[cfg(feature = "serde")]
+impl serde::Serialize for CellKey {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        return (self.idx, self.gen).serialize(serializer);
+    }
+}
+
+//IMPL Deserialize for CellKey
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for CellKey {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let (idx, gen) = <(usize, usize)>::deserialize(deserializer)?;
+        return Ok(CellKey { idx, gen });
+    }
+}
+
+//STRUCT TypedKey
+/// A [CellKey] that also records the type of value it was created for, so that it can only be used
+/// with a [Prison<T>](crate::single_threaded::Prison) holding that exact type
+///
+/// Returned by [Prison::insert_typed()](crate::single_threaded::Prison::insert_typed) and accepted by the
+/// `*_typed()` methods of [Prison](crate::single_threaded::Prison), turning a key from a `Prison<Enemy>` that is
+/// accidentally used on a `Prison<Bullet>` into a compile error. It has the same size as a [CellKey] and
+/// converts into one with [TypedKey::key()] for every other method
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, TypedKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// struct Enemy(u32);
+/// let enemies: Prison<Enemy> = Prison::new();
+/// let key: TypedKey<Enemy> = enemies.insert_typed(Enemy(100))?;
+/// enemies.visit_mut_typed(key, |enemy| {
+///     enemy.0 -= 10;
+///     Ok(())
+/// })?;
+/// assert_eq!(enemies.visit_ref(key.key(), |enemy| Ok(enemy.0))?, 90);
+/// # Ok(())
+/// # }
+/// ```
+/// ```compile_fail
+/// # use grit_data_prison::{AccessError, CellKey, TypedKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// struct Enemy(u32);
+/// struct Bullet(u32);
+/// let enemies: Prison<Enemy> = Prison::new();
+/// let bullets: Prison<Bullet> = Prison::new();
+/// let key = enemies.insert_typed(Enemy(100))?;
+/// // will not compile: (error[E0308]: mismatched types)
+/// bullets.visit_ref_typed(key, |bullet| Ok(()))?;
+/// # Ok(())
+/// # }
+/// ```
+pub struct TypedKey<T> {
+    key: CellKey,
+    val_type: PhantomData<fn() -> T>,
+}
+
+impl<T> TypedKey<T> {
+    /// Attach a value type to an untyped [CellKey]
+    ///
+    /// Nothing checks that the [CellKey] actually came from a [Prison](crate::single_threaded::Prison) holding `T`,
+    /// this is the explicit escape hatch for keys that were stored or transmitted without their type
+    pub fn from_key(key: CellKey) -> TypedKey<T> {
+        return TypedKey {
+            key,
+            val_type: PhantomData,
+        };
+    }
+
+    /// Return the untyped [CellKey]
+    pub fn key(&self) -> CellKey {
+        return self.key;
+    }
+
+    /// Return only the index of the key, see [CellKey::idx()]
+    pub fn idx(&self) -> usize {
+        return self.key.idx;
+    }
+}
+
+//IMPL Clone for TypedKey
+impl<T> Clone for TypedKey<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+//IMPL Copy for TypedKey
+impl<T> Copy for TypedKey<T> {}
+
+//IMPL PartialEq for TypedKey
+impl<T> PartialEq for TypedKey<T> {
+    fn eq(&self, other: &Self) -> bool {
+        return self.key == other.key;
+    }
+}
+
+//IMPL Eq for TypedKey
+impl<T> Eq for TypedKey<T> {}
+
+//IMPL Hash for TypedKey
+impl<T> Hash for TypedKey<T> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.key.hash(state);
+    }
+}
+
+//IMPL PartialOrd for TypedKey
+impl<T> PartialOrd for TypedKey<T> {
+    fn partial_cmp(&self, other: &Self) -> Option<CmpOrdering> {
+        return Some(self.cmp(other));
+    }
+}
+
+//IMPL Ord for TypedKey
+impl<T> Ord for TypedKey<T> {
+    fn cmp(&self, other: &Self) -> CmpOrdering {
+        return self.key.cmp(&other.key);
+    }
+}
+
+//IMPL Debug for TypedKey
+impl<T> Debug for TypedKey<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        return f.debug_struct("TypedKey").field("idx", &self.key.idx).field("gen", &self.key.gen).finish();
+    }
+}
+
+//IMPL From<TypedKey> for CellKey
+impl<T> From<TypedKey<T>> for CellKey {
+    fn from(typed_key: TypedKey<T>) -> Self {
+        return typed_key.key;
+    }
+}
+
+//IMPL From<CellKey> for TypedKey
+impl<T> From<CellKey> for TypedKey<T> {
+    fn from(key: CellKey) -> Self {
+        return TypedKey::from_key(key);
+    }
+}
+
+//STRUCT CellKeySet
+/// A set of [CellKey]'s kept sorted by index, with at most one [CellKey] per index
+///
+/// Because the keys are always sorted and de-duplicated, a [CellKeySet] can be passed to
+/// [Prison::visit_set_mut()](crate::single_threaded::Prison::visit_set_mut) and related methods
+/// to acquire references in index order (better memory locality) without building a new key slice
+/// for every call
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{CellKey, CellKeySet};
+/// # fn main() {
+/// let mut set = CellKeySet::new();
+/// assert!(set.insert(CellKey::from_raw_parts(4, 0)));
+/// assert!(set.insert(CellKey::from_raw_parts(1, 0)));
+/// assert!(!set.insert(CellKey::from_raw_parts(4, 0)));
+/// assert_eq!(set.len(), 2);
+/// assert_eq!(set.as_slice()[0].idx(), 1);
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)] //COV_IGNORE
+pub struct CellKeySet {
+    keys: Vec<CellKey>,
+}
+
+impl CellKeySet {
+    /// Create a new, empty [CellKeySet]
+    pub fn new() -> CellKeySet {
+        return CellKeySet { keys: Vec::new() };
+    }
+
+    /// Create a new, empty [CellKeySet] with room for `size` keys before re-allocating
+    pub fn with_capacity(size: usize) -> CellKeySet {
+        return CellKeySet {
+            keys: Vec::with_capacity(size),
+        };
+    }
+
+    /// Add a [CellKey] to the set, returning `true` if no key with the same index was already present
+    ///
+    /// If a key with the same index but a different generation was present, it is replaced
+    pub fn insert(&mut self, key: CellKey) -> bool {
+        match self.keys.binary_search_by_key(&key.idx, |k| k.idx) {
+            Ok(pos) => {
+                self.keys[pos] = key;
+                return false;
+            }
+            Err(pos) => {
+                self.keys.insert(pos, key);
+                return true;
+            }
+        }
+    }
+
+    /// Remove a [CellKey] from the set, returning `true` if it was present
+    pub fn remove(&mut self, key: CellKey) -> bool {
+        match self.keys.binary_search_by_key(&key.idx, |k| k.idx) {
+            Ok(pos) if self.keys[pos] == key => {
+                self.keys.remove(pos);
+                return true;
+            }
+            _ => return false,
+        }
+    }
+
+    /// Return `true` if the exact [CellKey] (index and generation) is in the set
+    pub fn contains(&self, key: CellKey) -> bool {
+        match self.keys.binary_search_by_key(&key.idx, |k| k.idx) {
+            Ok(pos) => self.keys[pos] == key,
+            Err(_) => false,
+        }
+    }
+
+    /// Return `true` if any [CellKey] with the given index is in the set
+    pub fn contains_idx(&self, idx: usize) -> bool {
+        return self.keys.binary_search_by_key(&idx, |k| k.idx).is_ok();
+    }
+
+    /// Return the number of keys in the set
+    pub fn len(&self) -> usize {
+        return self.keys.len();
+    }
+
+    /// Return `true` if the set has no keys
+    pub fn is_empty(&self) -> bool {
+        return self.keys.is_empty();
+    }
+
+    /// Remove all keys from the set
+    pub fn clear(&mut self) {
+        self.keys.clear();
+    }
+
+    /// Return an iterator over the keys in the set in ascending index order
+    pub fn iter(&self) -> SliceIter<'_, CellKey> {
+        return self.keys.iter();
+    }
+
+    /// Return the keys in the set as a slice sorted in ascending index order
+    pub fn as_slice(&self) -> &[CellKey] {
+        return self.keys.as_slice();
+    }
+}
+
+//IMPL FromIterator for CellKeySet
+impl FromIterator<CellKey> for CellKeySet {
+    fn from_iter<I: IntoIterator<Item = CellKey>>(iter: I) -> Self {
+        let mut keys: Vec<CellKey> = iter.into_iter().collect();
+        keys.reverse();
+        keys.sort_by_key(|k| k.idx);
+        keys.dedup_by_key(|k| k.idx);
+        return CellKeySet { keys };
+    }
+}
+
+//IMPL From<&[CellKey]> for CellKeySet
+impl From<&[CellKey]> for CellKeySet {
+    fn from(keys: &[CellKey]) -> Self {
+        return keys.iter().copied().collect();
+    }
+}
+
+//IMPL IntoIterator for &CellKeySet
+impl<'a> IntoIterator for &'a CellKeySet {
+    type Item = &'a CellKey;
+    type IntoIter = SliceIter<'a, CellKey>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.keys.iter();
+    }
+}
+
+//FN plan_disjoint_batches()
+/// Partition a list of [CellKey]'s into `n_batches` [CellKeySet]s that never share an index
+///
+/// Keys are sorted by index and de-duplicated exactly like collecting them into a [CellKeySet] (when several keys
+/// share an index, the last one wins), then split into contiguous index ranges whose sizes differ by at most one.
+/// Because no index appears in more than one batch, every batch can be visited with
+/// [Prison::visit_set_mut()](crate::single_threaded::Prison::visit_set_mut) independently of the others,
+/// for example by separate workers or in separate chunks of a frame, without any of them conflicting
+///
+/// Exactly `n_batches` batches are returned (trailing batches are empty if there are fewer unique indexes than batches),
+/// and an `n_batches` of 0 is treated as 1
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{plan_disjoint_batches, AccessError, CellKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let mut keys = Vec::new();
+/// for i in 0..7 {
+///     keys.push(prison.insert(i)?);
+/// }
+/// keys.push(keys[2]);
+/// let plan = plan_disjoint_batches(&keys, 3);
+/// assert_eq!(plan.len(), 3);
+/// assert_eq!(plan.num_keys(), 7);
+/// assert_eq!(plan.batch(0).len(), 3);
+/// assert_eq!(plan.batch(2).len(), 2);
+/// for batch in &plan {
+///     prison.visit_set_mut(batch, |vals| {
+///         for val in vals.iter_mut() {
+///             **val *= 10;
+///         }
+///         Ok(())
+///     })?;
+/// }
+/// assert_eq!(prison.clone_val(keys[6])?, 60);
+/// # Ok(())
+/// # }
+/// ```
+pub fn plan_disjoint_batches(keys: &[CellKey], n_batches: usize) -> BatchPlan {
+    let n_batches = n_batches.max(1);
+    let set = CellKeySet::from(keys);
+    let base = set.len() / n_batches;
+    let extra = set.len() % n_batches;
+    let mut batches = Vec::with_capacity(n_batches);
+    let mut start = 0;
+    for batch in 0..n_batches {
+        let end = start + base + if batch < extra { 1 } else { 0 };
+        batches.push(CellKeySet {
+            keys: set.keys[start..end].to_vec(),
+        });
+        start = end;
+    }
+    return BatchPlan { batches };
+}
+
+//STRUCT BatchPlan
+/// A list of [CellKeySet]s guaranteed not to share any index, created by [plan_disjoint_batches()]
+///
+/// Batches are ordered by index, so batch 0 holds the lowest indexes
+#[derive(Debug, Clone, Default, Eq, PartialEq)] //COV_IGNORE
+pub struct BatchPlan {
+    batches: Vec<CellKeySet>,
+}
+
+impl BatchPlan {
+    /// Return the number of batches
+    pub fn len(&self) -> usize {
+        return self.batches.len();
+    }
+
+    /// Return `true` if there are no batches
+    pub fn is_empty(&self) -> bool {
+        return self.batches.is_empty();
+    }
+
+    /// Return the total number of keys across all batches
+    pub fn num_keys(&self) -> usize {
+        return self.batches.iter().map(|batch| batch.len()).sum();
+    }
+
+    /// Return the batch at position `n`
+    ///
+    /// ## Panics
+    /// If `n` is not less than [BatchPlan::len()]
+    pub fn batch(&self, n: usize) -> &CellKeySet {
+        return &self.batches[n];
+    }
+
+    /// Return an iterator over the batches in order
+    pub fn iter(&self) -> SliceIter<'_, CellKeySet> {
+        return self.batches.iter();
+    }
+
+    /// Consume the plan and return the batches
+    pub fn into_batches(self) -> Vec<CellKeySet> {
+        return self.batches;
+    }
+}
+
+//IMPL IntoIterator for &BatchPlan
+impl<'a> IntoIterator for &'a BatchPlan {
+    type Item = &'a CellKeySet;
+    type IntoIter = SliceIter<'a, CellKeySet>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        return self.batches.iter();
+    }
+}
+
+//STRUCT GroupKey
+/// Struct that identifies a group of values inserted together with
+/// [Prison::insert_group()](crate::single_threaded::Prison::insert_group)
+///
+/// A [GroupKey] is only meaningful for the [Prison](crate::single_threaded::Prison) that created it,
+/// and becomes invalid once the group is removed with
+/// [Prison::remove_group()](crate::single_threaded::Prison::remove_group)
+#[derive(Debug, Copy, Clone, Eq, PartialEq)] //COV_IGNORE
+pub struct GroupKey {
+    id: usize,
+}
+
+impl GroupKey {
+    /// Return the unique id of the group within its [Prison](crate::single_threaded::Prison)
+    pub fn id(&self) -> usize {
+        return self.id;
+    }
+}
+
+//STRUCT KeyRemap
+/// A mapping from old [CellKey]'s to new [CellKey]'s, returned by operations that
+/// move values to different cells so that externally stored keys can be updated
+///
+/// Lookups are performed with a binary search over the old indexes
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// let mut out = Vec::new();
+/// let remap = prison.serialize_reachable(&[key_1], |_| [], &mut out, |out, val, _| {
+///     out.push(*val);
+///     Ok::<(), AccessError>(())
+/// })?;
+/// assert_eq!(remap.get(key_1), Some(CellKey::from_raw_parts(0, 0)));
+/// assert_eq!(remap.get(key_0), None);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)] //COV_IGNORE
+pub struct KeyRemap {
+    pairs: Vec<(CellKey, CellKey)>,
+}
+
+impl KeyRemap {
+    //FN KeyRemap::from_pairs()
+    #[doc(hidden)]
+    pub(crate) fn from_pairs(mut pairs: Vec<(CellKey, CellKey)>) -> KeyRemap {
+        pairs.sort_unstable_by_key(|(old_key, _)| old_key.idx);
+        return KeyRemap { pairs };
+    }
+
+    /// Return the new [CellKey] for an old [CellKey], or [None] if the old key was not remapped
+    /// (including when its generation does not match)
+    pub fn get(&self, old_key: CellKey) -> Option<CellKey> {
+        match self.pairs.binary_search_by_key(&old_key.idx, |(old, _)| old.idx) {
+            Ok(pos) if self.pairs[pos].0.gen == old_key.gen => Some(self.pairs[pos].1),
+            _ => None,
+        }
+    }
+
+    /// Return the number of remapped keys
+    pub fn len(&self) -> usize {
+        return self.pairs.len();
+    }
+
+    /// Return `true` if no keys were remapped
+    pub fn is_empty(&self) -> bool {
+        return self.pairs.is_empty();
+    }
+
+    /// Return an iterator over every `(old_key, new_key)` pair in ascending order of the old index
+    pub fn iter(&self) -> SliceIter<'_, (CellKey, CellKey)> {
+        return self.pairs.iter();
+    }
+}
+
+//STRUCT KeyspaceTable
+/// A compact, read-only snapshot of which [CellKey]'s are valid in a [Prison](crate::single_threaded::Prison),
+/// returned by [Prison::export_keyspace()](crate::single_threaded::Prison::export_keyspace)
+///
+/// The generation of every index is bit-packed using only as many bits as the highest generation needs,
+/// so a table is usually far smaller than the [Prison](crate::single_threaded::Prison) it describes. It
+/// owns all of its data and is [Send] + [Sync], so it can be handed to another thread (or written out
+/// with [KeyspaceTable::to_words()] and read back by another process with
+/// [KeyspaceTable::import_validation_table()]) to validate keys without ever touching the
+/// [Prison](crate::single_threaded::Prison) itself. Two snapshots can be compared with [KeyspaceTable::diff()]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, KeyspaceTable, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// let table = prison.export_keyspace();
+/// let handle = std::thread::spawn(move || table.is_valid(key_0) && table.is_valid(key_1));
+/// assert!(handle.join().unwrap());
+/// prison.remove(key_1)?;
+/// let words = prison.export_keyspace().to_words();
+/// let table = KeyspaceTable::import_validation_table(&words).unwrap();
+/// assert!(table.is_valid(key_0));
+/// assert!(!table.is_valid(key_1));
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default, Eq, PartialEq)] //COV_IGNORE
+pub struct KeyspaceTable {
+    bits: u32,
+    len: usize,
+    words: Vec<u64>,
+}
+
+impl KeyspaceTable {
+    //FN KeyspaceTable::from_generations()
+    /// Every entry is `None` for a free index or `Some(gen)` for a valid one
+    #[doc(hidden)]
+    pub(crate) fn from_generations(entries: &[Option<usize>]) -> KeyspaceTable {
+        let highest = entries.iter().map(|entry| KeyspaceTable::_encode(*entry)).max().unwrap_or(0);
+        let bits = (u64::BITS - highest.leading_zeros()).max(1);
+        let mut table = KeyspaceTable {
+            bits,
+            len: entries.len(),
+            words: vec![0; KeyspaceTable::_word_count(entries.len(), bits)],
+        };
+        for (idx, entry) in entries.iter().enumerate() {
+            table._set(idx, KeyspaceTable::_encode(*entry));
+        }
+        return table;
+    }
+
+    /// Return the number of indexes described by the table, including free ones
+    pub fn len(&self) -> usize {
+        return self.len;
+    }
+
+    /// Return `true` if the table describes no indexes at all
+    pub fn is_empty(&self) -> bool {
+        return self.len == 0;
+    }
+
+    /// Return the number of bits used to store the generation of each index
+    pub fn bits_per_index(&self) -> u32 {
+        return self.bits;
+    }
+
+    /// Return `true` if the [CellKey] referenced a valid value when the table was exported
+    pub fn is_valid(&self, key: CellKey) -> bool {
+        return self.generation_at(key.idx) == Some(key.gen);
+    }
+
+    /// Return the generation of the value at `idx` when the table was exported, or [None] if the
+    /// index was free or out of range
+    pub fn generation_at(&self, idx: usize) -> Option<usize> {
+        if idx >= self.len {
+            return None;
+        }
+        return KeyspaceTable::_decode(self._get(idx));
+    }
+
+    /// Compare this table to a `newer` one, returning the keys that are only valid in this table
+    /// (removed since) and the keys that are only valid in `newer` (inserted since), in that order
+    ///
+    /// Both lists are in index order. An index whose value was replaced shows up in both lists
+    pub fn diff(&self, newer: &KeyspaceTable) -> (Vec<CellKey>, Vec<CellKey>) {
+        let mut removed = Vec::new();
+        let mut added = Vec::new();
+        for idx in 0..self.len.max(newer.len) {
+            let old_gen = self.generation_at(idx);
+            let new_gen = newer.generation_at(idx);
+            if old_gen == new_gen {
+                continue;
+            }
+            if let Some(gen) = old_gen {
+                removed.push(CellKey { idx, gen });
+            }
+            if let Some(gen) = new_gen {
+                added.push(CellKey { idx, gen });
+            }
+        }
+        return (removed, added);
+    }
+
+    /// Return the table as a flat list of words that can be stored or sent anywhere and turned back into
+    /// an identical table with [KeyspaceTable::import_validation_table()]
+    pub fn to_words(&self) -> Vec<u64> {
+        let mut words = Vec::with_capacity(self.words.len() + 2);
+        words.push(self.bits as u64);
+        words.push(self.len as u64);
+        words.extend_from_slice(&self.words);
+        return words;
+    }
+
+    /// Rebuild a table from the words produced by [KeyspaceTable::to_words()]
+    ///
+    /// Returns [None] if `words` is not a table produced by [KeyspaceTable::to_words()] (for example
+    /// because it was truncated)
+    pub fn import_validation_table(words: &[u64]) -> Option<KeyspaceTable> {
+        let (bits, len) = match words {
+            [bits @ 1..=64, len, ..] if *len <= usize::MAX as u64 => (*bits as u32, *len as usize),
+            _ => return None,
+        };
+        if words.len() - 2 != KeyspaceTable::_word_count(len, bits) {
+            return None;
+        }
+        return Some(KeyspaceTable {
+            bits,
+            len,
+            words: words[2..].to_vec(),
+        });
+    }
+
+    //FN KeyspaceTable::_encode()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _encode(entry: Option<usize>) -> u64 {
+        match entry {
+            Some(gen) => return gen as u64 + 1,
+            None => return 0,
+        }
+    }
+
+    //FN KeyspaceTable::_decode()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _decode(raw: u64) -> Option<usize> {
+        match raw {
+            0 => return None,
+            raw => return Some((raw - 1) as usize),
+        }
+    }
+
+    //FN KeyspaceTable::_word_count()
+    #[doc(hidden)]
+    fn _word_count(len: usize, bits: u32) -> usize {
+        return ((len as u128 * bits as u128).div_ceil(64)) as usize;
+    }
+
+    //FN KeyspaceTable::_get()
+    #[doc(hidden)]
+    fn _get(&self, idx: usize) -> u64 {
+        let bit = idx as u128 * self.bits as u128;
+        let (word, shift) = ((bit / 64) as usize, (bit % 64) as u32);
+        let mut raw = self.words[word] >> shift;
+        if shift + self.bits > 64 {
+            raw |= self.words[word + 1] << (64 - shift);
+        }
+        return raw & (u64::MAX >> (64 - self.bits));
+    }
+
+    //FN KeyspaceTable::_set()
+    #[doc(hidden)]
+    fn _set(&mut self, idx: usize, raw: u64) {
+        let bit = idx as u128 * self.bits as u128;
+        let (word, shift) = ((bit / 64) as usize, (bit % 64) as u32);
+        self.words[word] |= raw << shift;
+        if shift + self.bits > 64 {
+            self.words[word + 1] |= raw >> (64 - shift);
+        }
+    }
+}
+
+//STRUCT ScriptHandle
+/// A 16-byte plain-old-data handle to a value in a [Prison](crate::single_threaded::Prison), designed to be stored
+/// inside scripting VMs or other foreign memory
+///
+/// Unlike a [CellKey], a [ScriptHandle] also records which [Prison](crate::single_threaded::Prison) created it, so
+/// using it with a different [Prison](crate::single_threaded::Prison) is detected and returns
+/// [AccessError::ForeignHandle(prison_id)] instead of silently referencing an unrelated value.
+/// The index and generation are validated on every use exactly like a [CellKey]
+///
+/// A [ScriptHandle] can be converted to and from a single [u128] for storage
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, ScriptHandle, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let stored_in_vm: u128 = prison.handle(key_0)?.to_bits();
+/// let handle = ScriptHandle::from_bits(stored_in_vm);
+/// prison.visit_handle_mut(handle, |val_0| {
+///     *val_0 += 1;
+///     Ok(())
+/// })?;
+/// let other_prison: Prison<u32> = Prison::new();
+/// other_prison.insert(99)?;
+/// assert!(other_prison.visit_handle_ref(handle, |val| Ok(())).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[repr(C)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq)] //COV_IGNORE
+pub struct ScriptHandle {
+    idx: u32,
+    prison_id: u32,
+    gen: u64,
+}
+
+impl ScriptHandle {
+    /// Create a handle from an index, the id of the [Prison](crate::single_threaded::Prison) it belongs to, and a generation
+    ///
+    /// Not recomended in most cases, use [Prison::handle()](crate::single_threaded::Prison::handle) instead
+    pub fn from_raw_parts(idx: u32, prison_id: u32, gen: u64) -> ScriptHandle {
+        return ScriptHandle {
+            idx,
+            prison_id,
+            gen,
+        };
+    }
+
+    /// Return the index, prison id, and generation of the handle, in that order
+    pub fn into_raw_parts(&self) -> (u32, u32, u64) {
+        return (self.idx, self.prison_id, self.gen);
+    }
+
+    /// Pack the handle into a single [u128]
+    pub fn to_bits(&self) -> u128 {
+        return (self.idx as u128) | ((self.prison_id as u128) << 32) | ((self.gen as u128) << 64);
+    }
+
+    /// Unpack a handle previously packed with [ScriptHandle::to_bits()]
+    pub fn from_bits(bits: u128) -> ScriptHandle {
+        return ScriptHandle {
+            idx: bits as u32,
+            prison_id: (bits >> 32) as u32,
+            gen: (bits >> 64) as u64,
+        };
+    }
+}
+
+//STRUCT Scratch
+/// A reusable bump allocator for temporary buffers needed inside `visit()` closures
+///
+/// Pass a [Scratch] to [Prison::visit_many_mut_scratch()](crate::single_threaded::Prison::visit_many_mut_scratch)
+/// or [Prison::visit_many_ref_scratch()](crate::single_threaded::Prison::visit_many_ref_scratch) and the closure
+/// receives a shared reference to it that can hand out any number of temporary slices. Every slice borrows the
+/// [Scratch] for the duration of the closure only, and the [Scratch] is reset as soon as the closure returns, so
+/// the same memory is reused by the next visit instead of going back to the global allocator
+///
+/// Only [Copy] types can be allocated, since values in a [Scratch] are never dropped. When a request does not
+/// fit in the current memory a new chunk is allocated, and the next reset merges all chunks into one that is large
+/// enough for the whole visit, so a [Scratch] reused for the same workload stops allocating after the first visit
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, Scratch, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(3)?;
+/// let key_1 = prison.insert(5)?;
+/// let mut scratch = Scratch::with_capacity(64);
+/// prison.visit_many_mut_scratch(&[key_0, key_1], &mut scratch, |vals, scratch| {
+///     let doubled = scratch.alloc_slice_fill_with(vals.len(), |i| *vals[i] * 2);
+///     assert_eq!(doubled, &[6, 10]);
+///     Ok(())
+/// })?;
+/// assert_eq!(scratch.used(), 0);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug)] //COV_IGNORE
+pub struct Scratch {
+    chunks: UnsafeCell<Vec<Vec<MaybeUninit<u8>>>>,
+    used: Cell<usize>,
+    total_used: Cell<usize>,
+}
+
+impl Scratch {
+    //FN Scratch::new()
+    /// Create a new [Scratch] with no memory allocated until it is first used
+    pub fn new() -> Scratch {
+        return Scratch::with_capacity(0);
+    }
+
+    //FN Scratch::with_capacity()
+    /// Create a new [Scratch] with room for `bytes` bytes of temporary allocations
+    pub fn with_capacity(bytes: usize) -> Scratch {
+        let mut chunks = Vec::new();
+        if bytes > 0 {
+            chunks.push(Vec::with_capacity(bytes));
+        }
+        return Scratch {
+            chunks: UnsafeCell::new(chunks),
+            used: Cell::new(0),
+            total_used: Cell::new(0),
+        };
+    }
+
+    //FN Scratch::capacity()
+    /// Return the total number of bytes the [Scratch] currently holds across all of its chunks
+    pub fn capacity(&self) -> usize {
+        let chunks = unsafe { &*self.chunks.get() };
+        return chunks.iter().map(|chunk| chunk.capacity()).sum();
+    }
+
+    //FN Scratch::used()
+    /// Return the number of bytes handed out since the last reset, including alignment padding
+    pub fn used(&self) -> usize {
+        return self.total_used.get();
+    }
+
+    //FN Scratch::alloc_slice_fill()
+    /// Allocate a temporary slice of `len` copies of `val`
+    pub fn alloc_slice_fill<E: Copy>(&self, len: usize, val: E) -> &mut [E] {
+        return self.alloc_slice_fill_with(len, |_| val);
+    }
+
+    //FN Scratch::alloc_slice_copy()
+    /// Allocate a temporary copy of `src`
+    pub fn alloc_slice_copy<E: Copy>(&self, src: &[E]) -> &mut [E] {
+        return self.alloc_slice_fill_with(src.len(), |i| src[i]);
+    }
+
+    //FN Scratch::alloc_slice_fill_with()
+    /// Allocate a temporary slice of `len` elements, where the element at each index is the result of `fill(index)`
+    #[allow(clippy::mut_from_ref)]
+    pub fn alloc_slice_fill_with<E: Copy, F: FnMut(usize) -> E>(&self, len: usize, mut fill: F) -> &mut [E] {
+        let ptr = self._alloc_raw(size_of::<E>() * len, align_of::<E>()) as *mut E;
+        for i in 0..len {
+            unsafe { ptr.add(i).write(fill(i)) };
+        }
+        return unsafe { slice_from_raw_parts_mut(ptr, len) };
+    }
+
+    //FN Scratch::reset()
+    /// Forget every allocation made so far so the memory can be reused
+    ///
+    /// If more than one chunk was needed since the last reset, they are replaced with a single chunk large
+    /// enough to hold all of them. This is called automatically after every `*_scratch()` visit
+    pub fn reset(&mut self) {
+        let chunks = self.chunks.get_mut();
+        if chunks.len() > 1 {
+            let total = chunks.iter().map(|chunk| chunk.capacity()).sum();
+            chunks.clear();
+            chunks.push(Vec::with_capacity(total));
+        }
+        self.used.set(0);
+        self.total_used.set(0);
+    }
+
+    //FN Scratch::_alloc_raw()
+    #[doc(hidden)]
+    fn _alloc_raw(&self, bytes: usize, align: usize) -> *mut u8 {
+        if bytes == 0 {
+            return NonNull::<u8>::dangling().as_ptr().wrapping_add(align - 1);
+        }
+        let chunks = unsafe { &mut *self.chunks.get() };
+        if let Some(chunk) = chunks.last_mut() {
+            let base = chunk.as_mut_ptr() as usize;
+            let start = ((base + self.used.get() + align - 1) & !(align - 1)) - base;
+            if start + bytes <= chunk.capacity() {
+                self.total_used.set(self.total_used.get() + (start + bytes - self.used.get()));
+                self.used.set(start + bytes);
+                return unsafe { chunk.as_mut_ptr().add(start) as *mut u8 };
+            }
+        }
+        let last_cap = chunks.last().map_or(0, |chunk| chunk.capacity());
+        let new_cap = (bytes + align - 1).max(last_cap * 2).max(64);
+        let mut chunk: Vec<MaybeUninit<u8>> = Vec::with_capacity(new_cap);
+        let base = chunk.as_mut_ptr() as usize;
+        let start = ((base + align - 1) & !(align - 1)) - base;
+        let ptr = unsafe { chunk.as_mut_ptr().add(start) as *mut u8 };
+        chunks.push(chunk);
+        self.total_used.set(self.total_used.get() + start + bytes);
+        self.used.set(start + bytes);
+        return ptr;
+    }
+}
+
+//IMPL Default for Scratch
+impl Default for Scratch {
+    fn default() -> Self {
+        return Scratch::new();
+    }
+}
+
+//TRAIT PlainData
+/// Marker trait for types whose values are nothing but initialized bytes, enabling the raw byte views
+/// [SnapshotRef::raw_cells()](crate::single_threaded::SnapshotRef::raw_cells) and
+/// [Prison::overwrite_raw()](crate::single_threaded::Prison::overwrite_raw)
+///
+/// Implemented for every integer and floating point primitive, and for arrays of [PlainData] types
+///
+/// Only available with the `raw` feature
+/// ## Safety
+/// Implementors must contain no padding, pointers, or references, and every possible bit pattern of
+/// `size_of::<Self>()` bytes must be a valid value of the type
+#[cfg(feature = "raw")]
+pub unsafe trait PlainData: Copy + 'static {}
+
+#[cfg(feature = "raw")]
+macro_rules! impl_plain_data {
+    ($($T:ty),*) => {
+        $(unsafe impl PlainData for $T {})*
+    };
+}
+
+#[cfg(feature = "raw")]
+impl_plain_data!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+#[cfg(feature = "raw")]
+unsafe impl<T: PlainData, const N: usize> PlainData for [T; N] {}
+
+//TRAIT PrisonValue
+/// Trait for value types that store their own [CellKey], for the common pattern where a value
+/// needs to know where it lives in a [Prison](crate::single_threaded::Prison)
+///
+/// Values implementing this trait can be inserted with [Prison::insert_keyed()](crate::single_threaded::Prison::insert_keyed),
+/// which writes the new [CellKey] into the value and returns it as the typed `Key`, and looked up or inserted in one
+/// step with [Prison::get_or_insert_with_key()](crate::single_threaded::Prison::get_or_insert_with_key). You rarely
+/// need to implement this by hand, the [prison_value!] macro generates it along with a newtype key for any struct with
+/// a [CellKey] field
+///
+/// With the `derive` feature, `#[derive(PrisonValue)]` generates the same items. The field holding the [CellKey]
+/// is the one marked `#[prison_key]`, or else the only field of type [CellKey], and the newtype key is named after
+/// the struct with a `Key` suffix unless named with `#[prison_value(key = Name)]`:
+/// ```rust
+/// # #[cfg(feature = "derive")]
+/// # fn main() -> Result<(), grit_data_prison::AccessError> {
+/// # use grit_data_prison::{AccessError, CellKey, PrisonValue, single_threaded::Prison};
+/// #[derive(PrisonValue)]
+/// #[prison_value(key = EnemyId)]
+/// struct Enemy {
+///     #[prison_key]
+///     id: CellKey,
+///     target: CellKey,
+/// }
+/// let enemies: Prison<Enemy> = Prison::new();
+/// let unset = CellKey::from_raw_parts(0, 0);
+/// let id: EnemyId = enemies.insert_keyed(Enemy { id: unset, target: unset })?;
+/// assert_eq!(enemies.visit_ref(id.into(), |enemy| Ok(enemy.typed_key()))?, id);
+/// # Ok(())
+/// # }
+/// # #[cfg(not(feature = "derive"))]
+/// # fn main() {}
+/// ```
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{prison_value, AccessError, CellKey, PrisonValue, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// struct Player {
+///     key: CellKey,
+///     name: &'static str,
+/// }
+/// prison_value!(Player { key } => pub PlayerKey);
+/// let players: Prison<Player> = Prison::new();
+/// let key_0: PlayerKey = players.insert_keyed(Player { key: CellKey::from_raw_parts(0, 0), name: "Alice" })?;
+/// players.visit_ref(key_0.into(), |player| {
+///     assert_eq!(player.typed_key(), key_0);
+///     assert_eq!(player.key(), key_0.0);
+///     Ok(())
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+pub trait PrisonValue {
+    /// The typed key for this value type
+    type Key: Copy + From<CellKey> + Into<CellKey>;
+
+    /// Return the [CellKey] stored in the value
+    fn key(&self) -> CellKey;
+
+    /// Store a new [CellKey] in the value
+    fn set_key(&mut self, key: CellKey);
+
+    /// Return the [CellKey] stored in the value as the typed `Key`
+    fn typed_key(&self) -> Self::Key {
+        return Self::Key::from(self.key());
+    }
+}
+
+//MACRO prison_value!
+/// Implement [PrisonValue] for a struct by naming the field that holds its [CellKey], and declare
+/// a newtype key (with the given visibility) that wraps a [CellKey] for that struct only
+///
+/// The newtype key is [Copy], [Eq] and [Debug], and converts to and from [CellKey] with [From].
+/// This is a declarative macro, so it works without the proc-macro companion crate and its `syn`/`quote`
+/// dependencies that `#[derive(PrisonValue)]` needs (see the `derive` feature). See [PrisonValue] for an example
+#[macro_export]
+macro_rules! prison_value {
+    ($STRUCT:ty { $FIELD:ident } => $VIS:vis $KEY:ident) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        $VIS struct $KEY(pub $crate::CellKey);
+
+        impl From<$crate::CellKey> for $KEY {
+            fn from(key: $crate::CellKey) -> Self {
+                return $KEY(key);
+            }
+        }
+
+        impl From<$KEY> for $crate::CellKey {
+            fn from(key: $KEY) -> Self {
+                return key.0;
+            }
+        }
+
+        impl $crate::PrisonValue for $STRUCT {
+            type Key = $KEY;
+
+            fn key(&self) -> $crate::CellKey {
+                return self.$FIELD;
+            }
+
+            fn set_key(&mut self, key: $crate::CellKey) {
+                self.$FIELD = key;
+            }
+        }
+    };
+}
+
+//STRUCT SelfKeyed
+/// Wrapper that stores the [CellKey] of a value alongside it, so any type can implement [PrisonValue]
+/// without a hand-written key field
+///
+/// Pair it with [Prison::maintain_keys()](crate::single_threaded::Prison::maintain_keys) and the stored key
+/// is written by the [Prison](crate::single_threaded::Prison) itself and is never stale. The wrapper
+/// dereferences to the inner value, and its typed key is a [TypedKey]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, SelfKeyed, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<SelfKeyed<Vec<u32>>> = Prison::new();
+/// prison.maintain_keys()?;
+/// let key_0 = prison.insert(SelfKeyed::new(vec![1, 2]))?;
+/// prison.visit_mut(key_0, |list| {
+///     list.push(3);
+///     assert_eq!(list.key(), key_0);
+///     Ok(())
+/// })?;
+/// assert_eq!(prison.remove(key_0)?.into_inner(), vec![1, 2, 3]);
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)] //COV_IGNORE
+pub struct SelfKeyed<T> {
+    key: CellKey,
+    value: T,
+}
+
+impl<T> SelfKeyed<T> {
+    /// Wrap a value with a placeholder [CellKey], which is replaced once the value is inserted
+    pub fn new(value: T) -> SelfKeyed<T> {
+        return SelfKeyed {
+            key: CellKey::from_raw_parts(usize::MAX, 0),
+            value,
+        };
+    }
+
+    /// Return the [CellKey] stored with the value
+    pub fn key(&self) -> CellKey {
+        return self.key;
+    }
+
+    /// Unwrap the inner value, discarding the stored [CellKey]
+    pub fn into_inner(self) -> T {
+        return self.value;
+    }
+}
+
+//IMPL Deref for SelfKeyed
+impl<T> Deref for SelfKeyed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &Self::Target {
+        return &self.value;
+    }
+}
+
+//IMPL DerefMut for SelfKeyed
+impl<T> DerefMut for SelfKeyed<T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        return &mut self.value;
+    }
+}
+
+//IMPL PrisonValue for SelfKeyed
+impl<T> PrisonValue for SelfKeyed<T> {
+    type Key = TypedKey<SelfKeyed<T>>;
+
+    fn key(&self) -> CellKey {
+        return self.key;
+    }
+
+    fn set_key(&mut self, key: CellKey) {
+        self.key = key;
+    }
+}
+
+//TRAIT ValueGuardRef
+/// Trait implemented by every guard that holds an immutable (or mutable) reference to a single value,
+/// allowing generic code to accept "any guard to a `T`" regardless of whether it came from a
+/// [Prison](crate::single_threaded::Prison) or a [JailCell](crate::single_threaded::JailCell)
+///
+/// Implemented by [PrisonValueRef](crate::single_threaded::PrisonValueRef), [PrisonValueMut](crate::single_threaded::PrisonValueMut),
+/// [JailValueRef](crate::single_threaded::JailValueRef), and [JailValueMut](crate::single_threaded::JailValueMut)
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, ValueGuardRef, single_threaded::{Prison, JailCell}};
+/// # fn main() -> Result<(), AccessError> {
+/// fn total<G: ValueGuardRef<u32>>(guards: &[G]) -> u32 {
+///     return guards.iter().map(|guard| *guard.value()).sum();
+/// }
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// let jail: JailCell<u32> = JailCell::new(5);
+/// assert_eq!(total(&[prison.guard_ref(key_0)?, prison.guard_ref(key_1)?]), 30);
+/// assert_eq!(total(&[jail.guard_ref()?]), 5);
+/// # Ok(())
+/// # }
+/// ```
+pub trait ValueGuardRef<T>: Deref<Target = T> {
+    /// Return an immutable reference to the guarded value
+    #[inline(always)]
+    fn value(&self) -> &T {
+        return self;
+    }
+
+    /// Release the guard, exactly like calling the `unguard()` function of the concrete guard type
+    #[inline(always)]
+    fn release(self)
+    where
+        Self: Sized,
+    {
+    }
+}
+
+//TRAIT ValueGuardMut
+/// Trait implemented by every guard that holds a mutable reference to a single value,
+/// allowing generic code to accept "any mutable guard to a `T`" regardless of whether it came from a
+/// [Prison](crate::single_threaded::Prison) or a [JailCell](crate::single_threaded::JailCell)
+///
+/// Implemented by [PrisonValueMut](crate::single_threaded::PrisonValueMut) and [JailValueMut](crate::single_threaded::JailValueMut)
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, ValueGuardMut, ValueGuardRef, single_threaded::{Prison, JailCell}};
+/// # fn main() -> Result<(), AccessError> {
+/// fn heal<G: ValueGuardMut<u32>>(mut guard: G) {
+///     *guard.value_mut() += 10;
+///     guard.release();
+/// }
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let jail: JailCell<u32> = JailCell::new(5);
+/// heal(prison.guard_mut(key_0)?);
+/// heal(jail.guard_mut()?);
+/// assert_eq!(prison.clone_val(key_0)?, 20);
+/// assert_eq!(jail.clone_val(), 15);
+/// # Ok(())
+/// # }
+/// ```
+pub trait ValueGuardMut<T>: ValueGuardRef<T> + DerefMut {
+    /// Return a mutable reference to the guarded value
+    #[inline(always)]
+    fn value_mut(&mut self) -> &mut T {
+        return self;
+    }
+}
+
+//TRAIT PrisonStorage
+/// Trait for the storage backends a [Prison](crate::single_threaded::Prison) can keep its cells in, chosen with the
+/// second type parameter of `Prison<T, S>`
+///
+/// All of the visit, guard, insert, and removal logic of a [Prison](crate::single_threaded::Prison) is written once
+/// against [StorageContainer], so a new storage mode (chunked, fixed-capacity, struct-of-arrays, ...) only needs a
+/// container type and never a copy of that logic. [VecStorage] is the default and the only backend for now, so
+/// `Prison<T>` is exactly the same type it always was. A non-default backend is selected either with a type
+/// annotation or by passing it to [Prison::with_storage()](crate::single_threaded::Prison::with_storage)
+///
+/// This trait is sealed: the safety of every reference handed out by a [Prison](crate::single_threaded::Prison) depends
+/// on the container upholding the guarantees documented on [StorageContainer], so backends can only be added by this crate
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, VecStorage, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32, VecStorage> = Prison::with_storage(VecStorage);
+/// let key_0 = prison.insert(10)?;
+/// prison.visit_mut(key_0, |val_0| {
+///     *val_0 += 5;
+///     Ok(())
+/// })?;
+/// assert_eq!(prison.clone_val(key_0)?, 15);
+/// # Ok(())
+/// # }
+/// ```
+pub trait PrisonStorage: sealed::Sealed {
+    /// The container this backend stores elements of type `E` in
+    type Container<E>: StorageContainer<E>;
+}
+
+//TRAIT StorageContainer
+/// Trait for the growable container of a [PrisonStorage] backend
+///
+/// Reads and in-place writes go through the slice it dereferences to, so a container only provides the operations
+/// that change its length or capacity. Every container must guarantee that:
+/// - elements never move in memory unless [StorageContainer::push()] is called while `len() == capacity()`, or one
+///   of [StorageContainer::reserve()], [StorageContainer::reserve_exact()], or [StorageContainer::shrink_to_fit()]
+///   is called
+/// - [StorageContainer::capacity()] never reports more elements than can be pushed without moving the existing ones
+///
+/// This trait is sealed, see [PrisonStorage]
+pub trait StorageContainer<E>: Deref<Target = [E]> + DerefMut + Default + sealed::Sealed {
+    /// Create an empty container with room for at least `capacity` elements
+    fn with_capacity(capacity: usize) -> Self;
+
+    /// Create a container holding the elements of `vec` in the same order
+    fn from_vec(vec: Vec<E>) -> Self;
+
+    /// Consume the container and return its elements in order
+    fn into_vec(self) -> Vec<E>;
+
+    /// Return the number of elements the container can hold without moving them
+    fn capacity(&self) -> usize;
+
+    /// Append an element to the end of the container
+    fn push(&mut self, element: E);
+
+    /// Remove and return the last element, or `None` if the container is empty
+    fn pop(&mut self) -> Option<E>;
+
+    /// Drop every element at or after `len`, doing nothing if the container is already shorter
+    fn truncate(&mut self, len: usize);
+
+    /// Make room for at least `additional` more elements, possibly more to avoid frequent reallocations
+    fn reserve(&mut self, additional: usize);
+
+    /// Make room for at least `additional` more elements, allocating as little extra space as possible
+    fn reserve_exact(&mut self, additional: usize);
+
+    /// Release as much unused capacity as possible
+    fn shrink_to_fit(&mut self);
+}
+
+//STRUCT VecStorage
+/// The default [PrisonStorage] backend, storing every cell contiguously in a single [Vec]
+#[derive(Debug, Copy, Clone, Default, Eq, PartialEq)] //COV_IGNORE
+pub struct VecStorage;
+
+//IMPL PrisonStorage for VecStorage
+impl PrisonStorage for VecStorage {
+    type Container<E> = Vec<E>;
+}
+
+//IMPL StorageContainer for Vec
+impl<E> StorageContainer<E> for Vec<E> {
+    #[inline(always)]
+    fn with_capacity(capacity: usize) -> Self {
+        return Vec::with_capacity(capacity);
+    }
+
+    #[inline(always)]
+    fn from_vec(vec: Vec<E>) -> Self {
+        return vec;
+    }
+
+    #[inline(always)]
+    fn into_vec(self) -> Vec<E> {
+        return self;
+    }
+
+    #[inline(always)]
+    fn capacity(&self) -> usize {
+        return Vec::capacity(self);
+    }
+
+    #[inline(always)]
+    fn push(&mut self, element: E) {
+        Vec::push(self, element);
+    }
+
+    #[inline(always)]
+    fn pop(&mut self) -> Option<E> {
+        return Vec::pop(self);
+    }
+
+    #[inline(always)]
+    fn truncate(&mut self, len: usize) {
+        Vec::truncate(self, len);
+    }
+
+    #[inline(always)]
+    fn reserve(&mut self, additional: usize) {
+        Vec::reserve(self, additional);
+    }
+
+    #[inline(always)]
+    fn reserve_exact(&mut self, additional: usize) {
+        Vec::reserve_exact(self, additional);
+    }
+
+    #[inline(always)]
+    fn shrink_to_fit(&mut self) {
+        Vec::shrink_to_fit(self);
+    }
+}
+
+//MOD sealed
+/// Keeps [PrisonStorage] and [StorageContainer] from being implemented outside this crate
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for super::VecStorage {}
+    impl<E> Sealed for super::Vec<E> {}
+}
+
+//MACRO visit_keys!
+/// Visit several values in a [Prison](crate::single_threaded::Prison) at once, binding each one to its own name
+/// instead of indexing into a slice
+///
+/// Each binding is written as `mut name = key` for a mutable reference or `ref name = key` for an immutable one,
+/// followed by `=>` and a block that must evaluate to a [Result<R, AccessError>]. The references are acquired
+/// in the order they are listed (with [Prison::guard_mut()](crate::single_threaded::Prison::guard_mut) and
+/// [Prison::guard_ref()](crate::single_threaded::Prison::guard_ref)) and all released when the block ends,
+/// so if any acquisition fails the ones before it are released and the block never runs. Just like the closures
+/// passed to `visit()` methods, `?` and `return` inside the block return from the block itself
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, visit_keys, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_gold = prison.insert(100)?;
+/// let key_price = prison.insert(30)?;
+/// let key_swords = prison.insert(0)?;
+/// visit_keys!(prison, mut gold = key_gold, ref price = key_price, mut swords = key_swords => {
+///     *gold -= *price;
+///     *swords += 1;
+///     Ok(())
+/// })?;
+/// assert_eq!(prison.clone_val(key_gold)?, 70);
+/// assert!(visit_keys!(prison, mut a = key_gold, ref b = key_gold => { Ok(()) }).is_err());
+/// # Ok(())
+/// # }
+/// ```
+#[macro_export]
+macro_rules! visit_keys {
+    ($PRISON:expr, $($KIND:tt $NAME:ident = $KEY:expr),+ $(,)? => $BODY:block) => {
+        (|| -> ::core::result::Result<_, $crate::AccessError> {
+            let prison = &$PRISON;
+            $($crate::visit_keys!(@bind prison, $KIND $NAME = $KEY);)+
+            $BODY
+        })()
+    };
+    (@bind $PRISON:ident, mut $NAME:ident = $KEY:expr) => {
+        let mut $NAME = $PRISON.guard_mut($KEY)?;
+        #[allow(unused_variables)]
+        let $NAME: &mut _ = &mut *$NAME;
+    };
+    (@bind $PRISON:ident, ref $NAME:ident = $KEY:expr) => {
+        let $NAME = $PRISON.guard_ref($KEY)?;
+        #[allow(unused_variables)]
+        let $NAME: &_ = &*$NAME;
+    };
+}
+
+//====== Crate Utilities ======
+//FN extract_true_start_end
+#[doc(hidden)]
+fn extract_true_start_end<B>(range: B, max_len: usize) -> (usize, usize)
+where
+    B: RangeBounds<usize>,
+{
+    let start = match range.start_bound() {
+        core::ops::Bound::Included(first) => *first,
+        core::ops::Bound::Excluded(one_before_first) => *one_before_first + 1,
+        core::ops::Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        core::ops::Bound::Included(last) => *last + 1,
+        core::ops::Bound::Excluded(one_after_last) => *one_after_last,
+        core::ops::Bound::Unbounded => max_len,
+    };
+    return (start, end);
+}
+
+//STRUCT InlineVec
+#[doc(hidden)]
+pub(crate) const INLINE_CAP: usize = 8;
+
+#[doc(hidden)]
+pub(crate) const MAX_SPARE_BUFS: usize = 4;
+
+/// A minimal `SmallVec`-style buffer that stores up to [INLINE_CAP] elements inline
+/// before spilling everything into a heap-allocated [Vec]
+#[doc(hidden)]
+pub(crate) struct InlineVec<E> {
+    len: usize,
+    inline: [MaybeUninit<E>; INLINE_CAP],
+    heap: Vec<E>,
+}
+
+impl<E> InlineVec<E> {
+    /// Whether a `Vec<E>` has the same allocation layout as a `Vec<usize>`
+    const POOLABLE: bool = size_of::<E>() == size_of::<usize>() && align_of::<E>() == align_of::<usize>();
+
+    //FN InlineVec::with_capacity()
+    #[inline(always)]
+    pub(crate) fn with_capacity(cap: usize) -> Self {
+        return Self {
+            len: 0,
+            inline: unsafe { MaybeUninit::uninit().assume_init() },
+            heap: if cap > INLINE_CAP { Vec::with_capacity(cap) } else { Vec::new() },
+        };
+    }
+
+    //FN InlineVec::with_capacity_from()
+    /// Like [InlineVec::with_capacity()], but takes the heap storage from a pool of spare buffers when
+    /// the elements are pointer-sized, so repeated batches larger than [INLINE_CAP] do not allocate.
+    /// There is no pool until the first spilled buffer is recycled
+    #[inline(always)]
+    pub(crate) fn with_capacity_from(cap: usize, pool: Option<&mut Vec<Vec<usize>>>) -> Self {
+        if cap <= INLINE_CAP || !Self::POOLABLE {
+            return Self::with_capacity(cap);
+        }
+        let mut buf = match pool.and_then(|pool| pool.pop()) {
+            Some(buf) => buf,
+            None => return Self::with_capacity(cap),
+        };
+        buf.reserve(cap);
+        let mut buf = ManuallyDrop::new(buf);
+        return Self {
+            len: 0,
+            inline: unsafe { MaybeUninit::uninit().assume_init() },
+            heap: unsafe { Vec::from_raw_parts(buf.as_mut_ptr() as *mut E, 0, buf.capacity()) },
+        };
+    }
+
+    //FN InlineVec::recycle_into()
+    /// Clear the [InlineVec] and hand its heap storage back to a pool of spare buffers,
+    /// unless the pool already holds [MAX_SPARE_BUFS] buffers
+    #[inline(always)]
+    pub(crate) fn recycle_into(&mut self, pool: &mut Vec<Vec<usize>>) {
+        if !self.is_spilled() || !Self::POOLABLE || pool.len() >= MAX_SPARE_BUFS {
+            return;
+        }
+        let mut heap = mem_take(&mut self.heap);
+        heap.clear();
+        let mut heap = ManuallyDrop::new(heap);
+        pool.push(unsafe { Vec::from_raw_parts(heap.as_mut_ptr() as *mut usize, 0, heap.capacity()) });
+    }
+
+    //FN InlineVec::is_spilled()
+    #[inline(always)]
+    pub(crate) fn is_spilled(&self) -> bool {
+        return self.heap.capacity() > 0;
+    }
+
+    //FN InlineVec::as_slice()
+    #[inline(always)]
+    pub(crate) fn as_slice(&self) -> &[E] {
+        return self;
+    }
+
+    //FN InlineVec::as_mut_slice()
+    #[inline(always)]
+    pub(crate) fn as_mut_slice(&mut self) -> &mut [E] {
+        return self;
+    }
+
+    //FN InlineVec::push()
+    #[inline(always)]
+    pub(crate) fn push(&mut self, elem: E) {
+        if self.is_spilled() {
+            self.heap.push(elem);
+            return;
+        }
+        if self.len == INLINE_CAP {
+            self.heap.reserve(INLINE_CAP * 2);
+            for slot in self.inline.iter_mut() {
+                self.heap.push(unsafe { slot.assume_init_read() });
+            }
+            self.len = 0;
+            self.heap.push(elem);
+            return;
+        }
+        self.inline[self.len].write(elem);
+        self.len += 1;
+    }
+}
+
+impl<E> Deref for InlineVec<E> {
+    type Target = [E];
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        if self.is_spilled() {
+            return &self.heap;
+        }
+        return unsafe { slice_from_raw_parts(self.inline.as_ptr() as *const E, self.len) };
+    }
+}
+
+impl<E> DerefMut for InlineVec<E> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        if self.is_spilled() {
+            return &mut self.heap;
+        }
+        return unsafe { slice_from_raw_parts_mut(self.inline.as_mut_ptr() as *mut E, self.len) };
+    }
+}
+
+impl<E> Drop for InlineVec<E> {
+    fn drop(&mut self) {
+        if !self.is_spilled() {
+            unsafe { drop_in_place(self.as_mut_slice()) };
+        }
+    }
 }
 
 //MACRO internal!
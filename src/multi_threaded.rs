@@ -0,0 +1,1014 @@
+use crate::{
+    internal, spin_loop, AccessError, AtomicBool, AtomicOrdering, AtomicUsize, Borrow, BorrowMut, CellKey, Deref,
+    DerefMut, MaybeUninit, RefUnwindSafe, UnsafeCell, UnwindSafe, ValueGuardMut, ValueGuardRef, Vec,
+};
+
+#[cfg(test)]
+mod tests;
+
+//====== Misc Types ======
+//STRUCT AtomicRefs
+#[doc(hidden)]
+struct AtomicRefs {}
+
+impl AtomicRefs {
+    const MUT: usize = usize::MAX;
+    const FREE: usize = usize::MAX - 1;
+    const MAX_IMMUT: usize = usize::MAX - 2;
+    const MAX_CAP: usize = isize::MAX as usize;
+}
+
+//====== AtomicPrison ======
+//STRUCT AtomicPrison
+/// The multi-threaded implementation of [Prison](crate::single_threaded::Prison)
+///
+/// [AtomicPrison<T>] works like a [Prison](crate::single_threaded::Prison), but is both [Send] and [Sync]
+/// (whenever `T` is [Send] + [Sync]), so it can be shared between threads that visit or guard *distinct* values
+/// at the same time. Each element has an atomic reference counter and the [AtomicPrison] has an atomic master
+/// access counter, so values are released without any locking. Obtaining a reference, inserting, and removing
+/// briefly take an internal spin lock that only protects the bookkeeping, never the values themselves,
+/// so no lock is held while a `visit()` closure runs or a guard is alive.
+///
+/// The same rules as [Prison](crate::single_threaded::Prison) apply across all threads: a value can have either
+/// one mutable reference or any number of immutable references, a value cannot be removed while it is
+/// referenced, and inserting can only grow the underlying [Vec] while no values are referenced. Attempting to
+/// break these rules returns an [AccessError] instead of blocking, so a thread that finds a value busy is free to retry later
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: AtomicPrison<u32> = AtomicPrison::with_capacity(10);
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// std::thread::scope(|scope| {
+///     scope.spawn(|| prison.visit_mut(key_0, |val_0| {
+///         *val_0 += 1;
+///         Ok(())
+///     }));
+///     scope.spawn(|| prison.visit_mut(key_1, |val_1| {
+///         *val_1 += 2;
+///         Ok(())
+///     }));
+/// });
+/// assert_eq!(prison.clone_val(key_0)?, 11);
+/// assert_eq!(prison.clone_val(key_1)?, 22);
+/// # Ok(())
+/// # }
+/// ```
+pub struct AtomicPrison<T> {
+    lock: AtomicBool,
+    access_count: AtomicUsize,
+    internal: UnsafeCell<AtomicPrisonInternal<T>>,
+}
+
+//IMPL Send for AtomicPrison
+unsafe impl<T: Send> Send for AtomicPrison<T> {}
+
+//IMPL Sync for AtomicPrison
+unsafe impl<T: Send + Sync> Sync for AtomicPrison<T> {}
+
+//IMPL RefUnwindSafe for AtomicPrison
+impl<T: RefUnwindSafe> RefUnwindSafe for AtomicPrison<T> {}
+
+impl<T> AtomicPrison<T> {
+    //FN AtomicPrison::new()
+    /// Create a new [AtomicPrison<T>] with the default allocation strategy ([Vec::new()])
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+    /// # fn main() {
+    /// let my_prison: AtomicPrison<u32> = AtomicPrison::new();
+    /// assert_eq!(my_prison.vec_cap(), 0);
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn new() -> Self {
+        return Self::with_capacity(0);
+    }
+
+    //FN AtomicPrison::with_capacity()
+    /// Create a new [AtomicPrison<T>] with a specific starting capacity ([Vec::with_capacity()])
+    ///
+    /// Because the underlying [Vec] can only grow while no values are referenced, choosing a capacity large
+    /// enough for every value that will be inserted while other threads are visiting values is strongly recommended
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+    /// # fn main() {
+    /// let my_prison: AtomicPrison<u32> = AtomicPrison::with_capacity(1000);
+    /// assert_eq!(my_prison.vec_cap(), 1000);
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn with_capacity(size: usize) -> Self {
+        return Self {
+            lock: AtomicBool::new(false),
+            access_count: AtomicUsize::new(0),
+            internal: UnsafeCell::new(AtomicPrisonInternal {
+                generation: 0,
+                free: Vec::new(),
+                vec: Vec::with_capacity(size),
+            }),
+        };
+    }
+
+    //FN AtomicPrison::vec_len()
+    /// Return the length of the underlying [Vec]
+    ///
+    /// Because an [AtomicPrison] may have values that are free/deleted that are still counted
+    /// within the length of the [Vec], this value should not be used to determine how many
+    /// *valid* elements exist in the [AtomicPrison]
+    pub fn vec_len(&self) -> usize {
+        let _lock = self._lock();
+        return internal!(self).vec.len();
+    }
+
+    //FN AtomicPrison::vec_cap()
+    /// Return the capacity of the underlying [Vec]
+    pub fn vec_cap(&self) -> usize {
+        let _lock = self._lock();
+        return internal!(self).vec.capacity();
+    }
+
+    //FN AtomicPrison::num_free()
+    /// Return the number of spaces available for elements to be added to the [AtomicPrison]
+    /// without reallocating more memory.
+    pub fn num_free(&self) -> usize {
+        let _lock = self._lock();
+        let internal = internal!(self);
+        return internal.free.len() + internal.vec.capacity() - internal.vec.len();
+    }
+
+    //FN AtomicPrison::num_used()
+    /// Return the number of spaces currently occupied by valid elements in the [AtomicPrison]
+    pub fn num_used(&self) -> usize {
+        let _lock = self._lock();
+        let internal = internal!(self);
+        return internal.vec.len() - internal.free.len();
+    }
+
+    //FN AtomicPrison::insert()
+    /// Insert a value into the [AtomicPrison] and recieve a [CellKey] that can be used to
+    /// reference it in the future
+    ///
+    /// As long as there are sufficient free cells or vector capacity to do so,
+    /// you may `insert()` to the [AtomicPrison] while any of its elements have active references,
+    /// including from other threads
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: AtomicPrison<u32> = AtomicPrison::with_capacity(1);
+    /// let key_0 = prison.insert(10)?;
+    /// prison.visit_ref(key_0, |val_0| {
+    ///     assert_eq!(prison.insert(20), Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced));
+    ///     Ok(())
+    /// })?;
+    /// assert!(prison.insert(20).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::InsertAtMaxCapacityWhileAValueIsReferenced] if the [AtomicPrison] is full and any value is referenced
+    /// - [AccessError::MaximumCapacityReached] if the [AtomicPrison] cannot grow any further
+    pub fn insert(&self, value: T) -> Result<CellKey, AccessError> {
+        let _lock = self._lock();
+        let internal = internal!(self);
+        if let Some(idx) = internal.free.pop() {
+            let cell = &internal.vec[idx];
+            unsafe { (*cell.val.get()).write(value) };
+            cell.gen.store(internal.generation, AtomicOrdering::Relaxed);
+            cell.refs.store(0, AtomicOrdering::Release);
+            return Ok(CellKey::from_raw_parts(idx, internal.generation));
+        }
+        if internal.vec.capacity() <= internal.vec.len() {
+            if self.access_count.load(AtomicOrdering::Acquire) > 0 {
+                return Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
+            }
+            if internal.vec.len() >= AtomicRefs::MAX_CAP {
+                return Err(AccessError::MaximumCapacityReached);
+            }
+        }
+        internal.vec.push(AtomicPrisonCell {
+            refs: AtomicUsize::new(0),
+            gen: AtomicUsize::new(internal.generation),
+            val: UnsafeCell::new(MaybeUninit::new(value)),
+        });
+        return Ok(CellKey::from_raw_parts(internal.vec.len() - 1, internal.generation));
+    }
+
+    //FN AtomicPrison::remove()
+    /// Remove and return the element indexed by the provided [CellKey]
+    ///
+    /// As long as the element doesnt have an active reference in any thread you can `remove()` it
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: AtomicPrison<String> = AtomicPrison::new();
+    /// let key_0 = prison.insert(String::from("Hello"))?;
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// assert_eq!(prison.remove(key_0), Err(AccessError::RemoveWhileValueReferenced(0)));
+    /// drop(grd_0);
+    /// assert_eq!(prison.remove(key_0)?, String::from("Hello"));
+    /// assert_eq!(prison.remove(key_0), Err(AccessError::ValueDeleted(0, 0)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::RemoveWhileValueReferenced(idx)] if the value is currently referenced
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was already removed *OR* the [CellKey] generation doesn't match
+    /// - [AccessError::MaxValueForGenerationReached] if the generation counter cannot be incremented any further
+    pub fn remove(&self, key: CellKey) -> Result<T, AccessError> {
+        let (idx, gen) = key.into_raw_parts();
+        let _lock = self._lock();
+        let internal = internal!(self);
+        let cell = match internal.vec.get(idx) {
+            Some(cell) => cell,
+            None => return Err(AccessError::IndexOutOfRange(idx)),
+        };
+        match cell.refs.load(AtomicOrdering::Acquire) {
+            AtomicRefs::FREE => return Err(AccessError::ValueDeleted(idx, gen)),
+            _ if cell.gen.load(AtomicOrdering::Relaxed) != gen => return Err(AccessError::ValueDeleted(idx, gen)),
+            0 => {}
+            _ => return Err(AccessError::RemoveWhileValueReferenced(idx)),
+        }
+        if gen >= internal.generation {
+            if gen == usize::MAX {
+                return Err(AccessError::MaxValueForGenerationReached);
+            }
+            internal.generation = gen + 1;
+        }
+        cell.refs.store(AtomicRefs::FREE, AtomicOrdering::Relaxed);
+        internal.free.push(idx);
+        return Ok(unsafe { (*cell.val.get()).assume_init_read() });
+    }
+
+    //FN AtomicPrison::visit_mut()
+    /// Visit a single value in the [AtomicPrison], obtaining a mutable reference to the
+    /// value that is passed into a closure you provide.
+    ///
+    /// While the closure runs, no other thread can reference the value, but every other value
+    /// remains available to every thread
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: AtomicPrison<u32> = AtomicPrison::new();
+    /// let key_0 = prison.insert(42)?;
+    /// prison.visit_mut(key_0, |val_0| {
+    ///     assert!(prison.visit_ref(key_0, |val_0_again| Ok(())).is_err());
+    ///     *val_0 += 1;
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(prison.clone_val(key_0)?, 43);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if the value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if the value has any immutable references
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation doesn't match
+    /// - Any error returned by the closure
+    pub fn visit_mut<R, F>(&self, key: CellKey, operation: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&mut T) -> Result<R, AccessError>,
+    {
+        return self.visit_mut_with(key, operation);
+    }
+
+    //FN AtomicPrison::visit_ref()
+    /// Visit a single value in the [AtomicPrison], obtaining an immutable reference to the
+    /// value that is passed into a closure you provide.
+    ///
+    /// Any number of threads may visit the same value immutably at the same time
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: AtomicPrison<u32> = AtomicPrison::new();
+    /// let key_0 = prison.insert(42)?;
+    /// prison.visit_ref(key_0, |ref_42_a| {
+    ///     prison.visit_ref(key_0, |ref_42_b| {
+    ///         assert_eq!(*ref_42_a, *ref_42_b);
+    ///         Ok(())
+    ///     })
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if the value is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if the value has the maximum number of immutable references
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation doesn't match
+    /// - Any error returned by the closure
+    pub fn visit_ref<R, F>(&self, key: CellKey, operation: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> Result<R, AccessError>,
+    {
+        return self.visit_ref_with(key, operation);
+    }
+
+    //FN AtomicPrison::visit_mut_with()
+    /// Identical to [AtomicPrison::visit_mut()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()](crate::single_threaded::Prison::visit_mut_with) for details
+    /// ## Errors
+    /// - Any error that [AtomicPrison::visit_mut()] could return, converted into `E`
+    /// - Any error returned by the closure
+    pub fn visit_mut_with<R, E, F>(&self, key: CellKey, operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnOnce(&mut T) -> Result<R, E>,
+    {
+        let mut grd = self.guard_mut(key)?;
+        return operation(&mut grd);
+    }
+
+    //FN AtomicPrison::visit_ref_with()
+    /// Identical to [AtomicPrison::visit_ref()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()](crate::single_threaded::Prison::visit_mut_with) for details
+    /// ## Errors
+    /// - Any error that [AtomicPrison::visit_ref()] could return, converted into `E`
+    /// - Any error returned by the closure
+    pub fn visit_ref_with<R, E, F>(&self, key: CellKey, operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnOnce(&T) -> Result<R, E>,
+    {
+        let grd = self.guard_ref(key)?;
+        return operation(&grd);
+    }
+
+    //FN AtomicPrison::guard_mut()
+    /// Return an [AtomicPrisonValueMut] that marks the value as mutably referenced for as long as it is in scope
+    ///
+    /// Guards cannot be sent to another thread, but any thread can guard its own values
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: AtomicPrison<u32> = AtomicPrison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let mut grd_0 = prison.guard_mut(key_0)?;
+    /// *grd_0 += 5;
+    /// std::thread::scope(|scope| {
+    ///     assert!(scope.spawn(|| prison.guard_ref(key_0).is_err()).join().unwrap());
+    /// });
+    /// drop(grd_0);
+    /// assert_eq!(*prison.guard_ref(key_0)?, 15);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// See [AtomicPrison::visit_mut()]
+    pub fn guard_mut<'a>(&'a self, key: CellKey) -> Result<AtomicPrisonValueMut<'a, T>, AccessError> {
+        let (idx, gen) = key.into_raw_parts();
+        let _lock = self._lock();
+        let cell = self._cell(idx, gen)?;
+        match cell.refs.load(AtomicOrdering::Acquire) {
+            0 => {}
+            AtomicRefs::MUT => return Err(AccessError::ValueAlreadyMutablyReferenced(idx)),
+            _ => return Err(AccessError::ValueStillImmutablyReferenced(idx)),
+        }
+        cell.refs.store(AtomicRefs::MUT, AtomicOrdering::Relaxed);
+        self.access_count.fetch_add(1, AtomicOrdering::Relaxed);
+        return Ok(AtomicPrisonValueMut {
+            cell,
+            prison_accesses: &self.access_count,
+        });
+    }
+
+    //FN AtomicPrison::guard_ref()
+    /// Return an [AtomicPrisonValueRef] that marks the value as immutably referenced for as long as it is in scope
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicPrison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: AtomicPrison<u32> = AtomicPrison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// std::thread::scope(|scope| {
+    ///     assert_eq!(scope.spawn(|| *prison.guard_ref(key_0).unwrap()).join().unwrap(), 10);
+    /// });
+    /// assert!(prison.guard_mut(key_0).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// See [AtomicPrison::visit_ref()]
+    pub fn guard_ref<'a>(&'a self, key: CellKey) -> Result<AtomicPrisonValueRef<'a, T>, AccessError> {
+        let (idx, gen) = key.into_raw_parts();
+        let _lock = self._lock();
+        let cell = self._cell(idx, gen)?;
+        match cell.refs.load(AtomicOrdering::Acquire) {
+            AtomicRefs::MUT => return Err(AccessError::ValueAlreadyMutablyReferenced(idx)),
+            AtomicRefs::MAX_IMMUT => return Err(AccessError::MaximumImmutableReferencesReached(idx)),
+            _ => {}
+        }
+        cell.refs.fetch_add(1, AtomicOrdering::Relaxed);
+        self.access_count.fetch_add(1, AtomicOrdering::Relaxed);
+        return Ok(AtomicPrisonValueRef {
+            cell,
+            prison_accesses: &self.access_count,
+        });
+    }
+
+    //FN AtomicPrison::clone_val()
+    /// Clones the requested value out of the [AtomicPrison] into a new variable
+    ///
+    /// The value is immutably referenced for the duration of the clone, so it cannot be
+    /// cloned while another thread holds a mutable reference to it
+    /// ## Errors
+    /// See [AtomicPrison::visit_ref()]
+    pub fn clone_val(&self, key: CellKey) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        return Ok(self.guard_ref(key)?.clone());
+    }
+
+    //FN AtomicPrison::_lock()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _lock(&self) -> AtomicPrisonLock<'_> {
+        while self
+            .lock
+            .compare_exchange_weak(false, true, AtomicOrdering::Acquire, AtomicOrdering::Relaxed)
+            .is_err()
+        {
+            spin_loop();
+        }
+        return AtomicPrisonLock { lock: &self.lock };
+    }
+
+    //FN AtomicPrison::_cell()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _cell(&self, idx: usize, gen: usize) -> Result<&AtomicPrisonCell<T>, AccessError> {
+        let cell = match internal!(self).vec.get(idx) {
+            Some(cell) => cell,
+            None => return Err(AccessError::IndexOutOfRange(idx)),
+        };
+        if cell.refs.load(AtomicOrdering::Relaxed) == AtomicRefs::FREE || cell.gen.load(AtomicOrdering::Relaxed) != gen {
+            return Err(AccessError::ValueDeleted(idx, gen));
+        }
+        return Ok(cell);
+    }
+}
+
+//IMPL Default for AtomicPrison
+impl<T> Default for AtomicPrison<T> {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+//STRUCT AtomicPrisonInternal
+#[doc(hidden)]
+struct AtomicPrisonInternal<T> {
+    generation: usize,
+    free: Vec<usize>,
+    vec: Vec<AtomicPrisonCell<T>>,
+}
+
+//STRUCT AtomicPrisonCell
+#[doc(hidden)]
+struct AtomicPrisonCell<T> {
+    refs: AtomicUsize,
+    gen: AtomicUsize,
+    val: UnsafeCell<MaybeUninit<T>>,
+}
+
+//IMPL Drop for AtomicPrisonCell
+impl<T> Drop for AtomicPrisonCell<T> {
+    fn drop(&mut self) {
+        if *self.refs.get_mut() != AtomicRefs::FREE {
+            unsafe { self.val.get_mut().assume_init_drop() };
+        }
+    }
+}
+
+//STRUCT AtomicPrisonLock
+#[doc(hidden)]
+struct AtomicPrisonLock<'a> {
+    lock: &'a AtomicBool,
+}
+
+//IMPL Drop for AtomicPrisonLock
+impl<'a> Drop for AtomicPrisonLock<'a> {
+    fn drop(&mut self) {
+        self.lock.store(false, AtomicOrdering::Release);
+    }
+}
+
+//------ Guarded AtomicPrison ------
+//STRUCT AtomicPrisonValueMut
+/// Struct representing a mutable reference to a value that has been allowed to leave the
+/// [AtomicPrison] temporarily, but remains guarded by a wrapper to prevent it from leaking or never unlocking
+///
+/// [AtomicPrisonValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
+/// [Borrow<T>], [BorrowMut<T>], [ValueGuardRef<T>] and [ValueGuardMut<T>], exactly like
+/// [PrisonValueMut](crate::single_threaded::PrisonValueMut)
+///
+/// You can obtain an [AtomicPrisonValueMut] by calling `guard_mut()` on an [AtomicPrison]
+pub struct AtomicPrisonValueMut<'a, T> {
+    cell: &'a AtomicPrisonCell<T>,
+    prison_accesses: &'a AtomicUsize,
+}
+
+impl<'a, T> AtomicPrisonValueMut<'a, T> {
+    //FN AtomicPrisonValueMut::unguard()
+    /// Manually end an [AtomicPrisonValueMut] value's temporary guarded absence from the [AtomicPrison]
+    pub fn unguard(_prison_val_mut: Self) {}
+}
+
+//IMPL Drop for AtomicPrisonValueMut
+impl<'a, T> Drop for AtomicPrisonValueMut<'a, T> {
+    fn drop(&mut self) {
+        self.cell.refs.store(0, AtomicOrdering::Release);
+        self.prison_accesses.fetch_sub(1, AtomicOrdering::Release);
+    }
+}
+
+//IMPL Deref for AtomicPrisonValueMut
+impl<'a, T> Deref for AtomicPrisonValueMut<'a, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { (*self.cell.val.get()).assume_init_ref() }
+    }
+}
+
+//IMPL DerefMut for AtomicPrisonValueMut
+impl<'a, T> DerefMut for AtomicPrisonValueMut<'a, T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { (*self.cell.val.get()).assume_init_mut() }
+    }
+}
+
+//IMPL AsRef for AtomicPrisonValueMut
+impl<'a, T> AsRef<T> for AtomicPrisonValueMut<'a, T> {
+    #[inline(always)]
+    fn as_ref(&self) -> &T {
+        return self;
+    }
+}
+
+//IMPL AsMut for AtomicPrisonValueMut
+impl<'a, T> AsMut<T> for AtomicPrisonValueMut<'a, T> {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut T {
+        return self;
+    }
+}
+
+//IMPL Borrow for AtomicPrisonValueMut
+impl<'a, T> Borrow<T> for AtomicPrisonValueMut<'a, T> {
+    #[inline(always)]
+    fn borrow(&self) -> &T {
+        return self;
+    }
+}
+
+//IMPL BorrowMut for AtomicPrisonValueMut
+impl<'a, T> BorrowMut<T> for AtomicPrisonValueMut<'a, T> {
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut T {
+        return self;
+    }
+}
+
+//IMPL ValueGuardRef for AtomicPrisonValueMut
+impl<'a, T> ValueGuardRef<T> for AtomicPrisonValueMut<'a, T> {}
+
+//IMPL ValueGuardMut for AtomicPrisonValueMut
+impl<'a, T> ValueGuardMut<T> for AtomicPrisonValueMut<'a, T> {}
+
+//STRUCT AtomicPrisonValueRef
+/// Struct representing an immutable reference to a value that has been allowed to leave the
+/// [AtomicPrison] temporarily, but remains guarded by a wrapper to prevent it from leaking or never unlocking
+///
+/// [AtomicPrisonValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], [Borrow<T>] and [ValueGuardRef<T>], exactly like
+/// [PrisonValueRef](crate::single_threaded::PrisonValueRef)
+///
+/// You can obtain an [AtomicPrisonValueRef] by calling `guard_ref()` on an [AtomicPrison]
+pub struct AtomicPrisonValueRef<'a, T> {
+    cell: &'a AtomicPrisonCell<T>,
+    prison_accesses: &'a AtomicUsize,
+}
+
+//IMPL UnwindSafe for AtomicPrisonValueRef
+impl<'a, T: RefUnwindSafe> UnwindSafe for AtomicPrisonValueRef<'a, T> {}
+
+impl<'a, T> AtomicPrisonValueRef<'a, T> {
+    //FN AtomicPrisonValueRef::unguard()
+    /// Manually end an [AtomicPrisonValueRef] value's temporary guarded absence from the [AtomicPrison]
+    pub fn unguard(_prison_val_ref: Self) {}
+}
+
+//IMPL Drop for AtomicPrisonValueRef
+impl<'a, T> Drop for AtomicPrisonValueRef<'a, T> {
+    fn drop(&mut self) {
+        self.cell.refs.fetch_sub(1, AtomicOrdering::Release);
+        self.prison_accesses.fetch_sub(1, AtomicOrdering::Release);
+    }
+}
+
+//IMPL Deref for AtomicPrisonValueRef
+impl<'a, T> Deref for AtomicPrisonValueRef<'a, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { (*self.cell.val.get()).assume_init_ref() }
+    }
+}
+
+//IMPL AsRef for AtomicPrisonValueRef
+impl<'a, T> AsRef<T> for AtomicPrisonValueRef<'a, T> {
+    #[inline(always)]
+    fn as_ref(&self) -> &T {
+        return self;
+    }
+}
+
+//IMPL Borrow for AtomicPrisonValueRef
+impl<'a, T> Borrow<T> for AtomicPrisonValueRef<'a, T> {
+    #[inline(always)]
+    fn borrow(&self) -> &T {
+        return self;
+    }
+}
+
+//IMPL ValueGuardRef for AtomicPrisonValueRef
+impl<'a, T> ValueGuardRef<T> for AtomicPrisonValueRef<'a, T> {}
+
+//====== AtomicJailCell ======
+//STRUCT AtomicJailCell
+/// Represents a single standalone value that can be shared between threads while upholding memory safety
+/// with an atomic reference counter
+///
+/// This is the thread-safe counterpart to [JailCell](crate::single_threaded::JailCell): it has the same
+/// `visit_ref()`, `visit_mut()` and `clone_val()` methods, and is both [Send] and [Sync] whenever `T` is [Send] + [Sync].
+/// Guards come in two flavors: `try_guard_ref()` and `try_guard_mut()` return an [AccessError] immediately if the value
+/// is busy, while `guard_ref()` and `guard_mut()` block the current thread until the value becomes available
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicJailCell};
+/// # fn main() -> Result<(), AccessError> {
+/// let counter: AtomicJailCell<u32> = AtomicJailCell::new(0);
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             for _ in 0..100 {
+///                 *counter.guard_mut() += 1;
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(counter.into_inner(), 400);
+/// # Ok(())
+/// # }
+/// ```
+pub struct AtomicJailCell<T> {
+    refs: AtomicUsize,
+    val: UnsafeCell<T>,
+}
+
+//IMPL Send for AtomicJailCell
+unsafe impl<T: Send> Send for AtomicJailCell<T> {}
+
+//IMPL Sync for AtomicJailCell
+unsafe impl<T: Send + Sync> Sync for AtomicJailCell<T> {}
+
+//IMPL RefUnwindSafe for AtomicJailCell
+impl<T: RefUnwindSafe> RefUnwindSafe for AtomicJailCell<T> {}
+
+impl<T> AtomicJailCell<T> {
+    //FN AtomicJailCell::new()
+    /// Creates a new [AtomicJailCell] with the supplied value of type `T`
+    pub fn new(value: T) -> AtomicJailCell<T> {
+        return AtomicJailCell {
+            refs: AtomicUsize::new(0),
+            val: UnsafeCell::new(value),
+        };
+    }
+
+    //FN AtomicJailCell::into_inner()
+    /// Consume the [AtomicJailCell] and return its value
+    pub fn into_inner(self) -> T {
+        return self.val.into_inner();
+    }
+
+    //FN AtomicJailCell::visit_mut()
+    /// Obtain a mutable reference to the [AtomicJailCell]'s internal value that gets passed to
+    /// a closure you provide, without blocking
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicJailCell};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let jail: AtomicJailCell<u32> = AtomicJailCell::new(42);
+    /// jail.visit_mut(|val| {
+    ///     assert_eq!(jail.visit_ref(|val| Ok(())), Err(AccessError::ValueAlreadyMutablyReferenced(0)));
+    ///     *val += 1;
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(jail.clone_val(), 43);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(0)] if value has any number of immutable references
+    /// - Any error returned by the closure
+    pub fn visit_mut<R, F>(&self, operation: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&mut T) -> Result<R, AccessError>,
+    {
+        return self.visit_mut_with(operation);
+    }
+
+    //FN AtomicJailCell::visit_ref()
+    /// Obtain an immutable reference to the [AtomicJailCell]'s internal value that gets passed to
+    /// a closure you provide, without blocking
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(0)] if value has the maximum number of immutable references
+    /// - Any error returned by the closure
+    pub fn visit_ref<R, F>(&self, operation: F) -> Result<R, AccessError>
+    where
+        F: FnOnce(&T) -> Result<R, AccessError>,
+    {
+        return self.visit_ref_with(operation);
+    }
+
+    //FN AtomicJailCell::visit_mut_with()
+    /// Identical to [AtomicJailCell::visit_mut()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()](crate::single_threaded::Prison::visit_mut_with) for details
+    /// ## Errors
+    /// - Any error that [AtomicJailCell::visit_mut()] could return, converted into `E`
+    /// - Any error returned by the closure
+    pub fn visit_mut_with<R, E, F>(&self, operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnOnce(&mut T) -> Result<R, E>,
+    {
+        let mut grd = self.try_guard_mut()?;
+        return operation(&mut grd);
+    }
+
+    //FN AtomicJailCell::visit_ref_with()
+    /// Identical to [AtomicJailCell::visit_ref()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()](crate::single_threaded::Prison::visit_mut_with) for details
+    /// ## Errors
+    /// - Any error that [AtomicJailCell::visit_ref()] could return, converted into `E`
+    /// - Any error returned by the closure
+    pub fn visit_ref_with<R, E, F>(&self, operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnOnce(&T) -> Result<R, E>,
+    {
+        let grd = self.try_guard_ref()?;
+        return operation(&grd);
+    }
+
+    //FN AtomicJailCell::try_guard_mut()
+    /// Return an [AtomicJailValueMut] that marks the value as mutably referenced for as long as it is in scope,
+    /// or an error immediately if the value is currently referenced
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, multi_threaded::AtomicJailCell};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let jail: AtomicJailCell<u32> = AtomicJailCell::new(42);
+    /// let grd_ref = jail.try_guard_ref()?;
+    /// assert_eq!(jail.try_guard_mut().err(), Some(AccessError::ValueStillImmutablyReferenced(0)));
+    /// drop(grd_ref);
+    /// *jail.try_guard_mut()? += 1;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(0)] if value has any number of immutable references
+    pub fn try_guard_mut<'a>(&'a self) -> Result<AtomicJailValueMut<'a, T>, AccessError> {
+        match self
+            .refs
+            .compare_exchange(0, AtomicRefs::MUT, AtomicOrdering::Acquire, AtomicOrdering::Relaxed)
+        {
+            Ok(_) => return Ok(AtomicJailValueMut { jail: self }),
+            Err(AtomicRefs::MUT) => return Err(AccessError::ValueAlreadyMutablyReferenced(0)),
+            Err(_) => return Err(AccessError::ValueStillImmutablyReferenced(0)),
+        }
+    }
+
+    //FN AtomicJailCell::try_guard_ref()
+    /// Return an [AtomicJailValueRef] that marks the value as immutably referenced for as long as it is in scope,
+    /// or an error immediately if the value is currently mutably referenced
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(0)] if value has the maximum number of immutable references
+    pub fn try_guard_ref<'a>(&'a self) -> Result<AtomicJailValueRef<'a, T>, AccessError> {
+        let mut refs = self.refs.load(AtomicOrdering::Relaxed);
+        loop {
+            match refs {
+                AtomicRefs::MUT => return Err(AccessError::ValueAlreadyMutablyReferenced(0)),
+                AtomicRefs::MAX_IMMUT => return Err(AccessError::MaximumImmutableReferencesReached(0)),
+                _ => {}
+            }
+            match self
+                .refs
+                .compare_exchange_weak(refs, refs + 1, AtomicOrdering::Acquire, AtomicOrdering::Relaxed)
+            {
+                Ok(_) => return Ok(AtomicJailValueRef { jail: self }),
+                Err(current) => refs = current,
+            }
+        }
+    }
+
+    //FN AtomicJailCell::guard_mut()
+    /// Return an [AtomicJailValueMut] that marks the value as mutably referenced for as long as it is in scope,
+    /// blocking the current thread until no other references remain
+    ///
+    /// This will never return if the current thread already holds a reference to the value
+    pub fn guard_mut<'a>(&'a self) -> AtomicJailValueMut<'a, T> {
+        loop {
+            if let Ok(grd) = self.try_guard_mut() {
+                return grd;
+            }
+            spin_loop();
+        }
+    }
+
+    //FN AtomicJailCell::guard_ref()
+    /// Return an [AtomicJailValueRef] that marks the value as immutably referenced for as long as it is in scope,
+    /// blocking the current thread until the value is not mutably referenced
+    ///
+    /// This will never return if the current thread already holds a mutable reference to the value
+    pub fn guard_ref<'a>(&'a self) -> AtomicJailValueRef<'a, T> {
+        loop {
+            if let Ok(grd) = self.try_guard_ref() {
+                return grd;
+            }
+            spin_loop();
+        }
+    }
+
+    //FN AtomicJailCell::clone_val()
+    /// Clones the value out of the [AtomicJailCell] into a new variable
+    ///
+    /// Unlike [JailCell::clone_val()](crate::single_threaded::JailCell::clone_val), the value is immutably referenced
+    /// for the duration of the clone, blocking until no other thread holds a mutable reference to it
+    pub fn clone_val(&self) -> T
+    where
+        T: Clone,
+    {
+        return self.guard_ref().clone();
+    }
+}
+
+//------ Guarded AtomicJailCell ------
+//STRUCT AtomicJailValueMut
+/// A guarded wrapper around a mutable reference to the value contained in an [AtomicJailCell]
+///
+/// [AtomicJailValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
+/// [Borrow<T>], [BorrowMut<T>], [ValueGuardRef<T>] and [ValueGuardMut<T>], exactly like
+/// [JailValueMut](crate::single_threaded::JailValueMut)
+pub struct AtomicJailValueMut<'a, T> {
+    jail: &'a AtomicJailCell<T>,
+}
+
+impl<'a, T> AtomicJailValueMut<'a, T> {
+    //FN AtomicJailValueMut::unguard()
+    /// Manually end an [AtomicJailValueMut] value's temporary guarded absence from the [AtomicJailCell]
+    pub fn unguard(_jail_val_mut: Self) {}
+}
+
+//IMPL Drop for AtomicJailValueMut
+impl<'a, T> Drop for AtomicJailValueMut<'a, T> {
+    fn drop(&mut self) {
+        self.jail.refs.store(0, AtomicOrdering::Release);
+    }
+}
+
+//IMPL Deref for AtomicJailValueMut
+impl<'a, T> Deref for AtomicJailValueMut<'a, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.jail.val.get() }
+    }
+}
+
+//IMPL DerefMut for AtomicJailValueMut
+impl<'a, T> DerefMut for AtomicJailValueMut<'a, T> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { &mut *self.jail.val.get() }
+    }
+}
+
+//IMPL AsRef for AtomicJailValueMut
+impl<'a, T> AsRef<T> for AtomicJailValueMut<'a, T> {
+    #[inline(always)]
+    fn as_ref(&self) -> &T {
+        return self;
+    }
+}
+
+//IMPL AsMut for AtomicJailValueMut
+impl<'a, T> AsMut<T> for AtomicJailValueMut<'a, T> {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut T {
+        return self;
+    }
+}
+
+//IMPL Borrow for AtomicJailValueMut
+impl<'a, T> Borrow<T> for AtomicJailValueMut<'a, T> {
+    #[inline(always)]
+    fn borrow(&self) -> &T {
+        return self;
+    }
+}
+
+//IMPL BorrowMut for AtomicJailValueMut
+impl<'a, T> BorrowMut<T> for AtomicJailValueMut<'a, T> {
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut T {
+        return self;
+    }
+}
+
+//IMPL ValueGuardRef for AtomicJailValueMut
+impl<'a, T> ValueGuardRef<T> for AtomicJailValueMut<'a, T> {}
+
+//IMPL ValueGuardMut for AtomicJailValueMut
+impl<'a, T> ValueGuardMut<T> for AtomicJailValueMut<'a, T> {}
+
+//STRUCT AtomicJailValueRef
+/// A guarded wrapper around an immutable reference to the value contained in an [AtomicJailCell]
+///
+/// [AtomicJailValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], [Borrow<T>] and [ValueGuardRef<T>], exactly like
+/// [JailValueRef](crate::single_threaded::JailValueRef)
+pub struct AtomicJailValueRef<'a, T> {
+    jail: &'a AtomicJailCell<T>,
+}
+
+//IMPL UnwindSafe for AtomicJailValueRef
+impl<'a, T: RefUnwindSafe> UnwindSafe for AtomicJailValueRef<'a, T> {}
+
+impl<'a, T> AtomicJailValueRef<'a, T> {
+    //FN AtomicJailValueRef::unguard()
+    /// Manually end an [AtomicJailValueRef] value's temporary guarded absence from the [AtomicJailCell]
+    pub fn unguard(_jail_val_ref: Self) {}
+}
+
+//IMPL Drop for AtomicJailValueRef
+impl<'a, T> Drop for AtomicJailValueRef<'a, T> {
+    fn drop(&mut self) {
+        self.jail.refs.fetch_sub(1, AtomicOrdering::Release);
+    }
+}
+
+//IMPL Deref for AtomicJailValueRef
+impl<'a, T> Deref for AtomicJailValueRef<'a, T> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*self.jail.val.get() }
+    }
+}
+
+//IMPL AsRef for AtomicJailValueRef
+impl<'a, T> AsRef<T> for AtomicJailValueRef<'a, T> {
+    #[inline(always)]
+    fn as_ref(&self) -> &T {
+        return self;
+    }
+}
+
+//IMPL Borrow for AtomicJailValueRef
+impl<'a, T> Borrow<T> for AtomicJailValueRef<'a, T> {
+    #[inline(always)]
+    fn borrow(&self) -> &T {
+        return self;
+    }
+}
+
+//IMPL ValueGuardRef for AtomicJailValueRef
+impl<'a, T> ValueGuardRef<T> for AtomicJailValueRef<'a, T> {}
@@ -0,0 +1,222 @@
+#![allow(dead_code)]
+#![allow(unused_variables)]
+//====== Testing ======
+use std::sync::Arc;
+
+use super::*;
+
+//MACRO assert_atomic_prison_state!
+/// prison, access_count, gen, free_count, vec_len
+macro_rules! assert_atomic_prison_state {
+    ($P:ident, $A_CNT:expr, $GEN:expr, $F_CNT:expr, $LEN:expr) => {
+        let p = &internal!($P);
+        let a_cnt = $P.access_count.load(AtomicOrdering::SeqCst);
+        if a_cnt != $A_CNT || p.generation != $GEN || p.free.len() != $F_CNT || p.vec.len() != $LEN {
+            panic!("\nIncorrect prison state:\n\tEXP:\taccess_count: {}, gen: {}, free_count: {}, vec_len: {}\n\tGOT:\taccess_count: {}, gen: {}, free_count: {}, vec_len: {}\n",
+            $A_CNT, $GEN, $F_CNT, $LEN,
+            a_cnt, p.generation, p.free.len(), p.vec.len());
+        }
+    };
+}
+
+//MACRO assert_atomic_cell_refs!
+/// prison, index, refs
+macro_rules! assert_atomic_cell_refs {
+    ($P:ident, $IDX:expr, $REFS:expr) => {
+        let refs = internal!($P).vec[$IDX].refs.load(AtomicOrdering::SeqCst);
+        if refs != $REFS {
+            panic!("\nIndex {} unexpected refs:\n\tEXP:\t{}\n\tGOT:\t{}\n", $IDX, $REFS, refs);
+        }
+    };
+}
+
+//TEST AtomicPrison::insert() and AtomicPrison::remove()
+#[test]
+fn atomic_prison_insert_remove() -> Result<(), AccessError> {
+    let prison: AtomicPrison<u32> = AtomicPrison::with_capacity(2);
+    let key_0 = prison.insert(0)?;
+    let key_1 = prison.insert(1)?;
+    assert_atomic_prison_state!(prison, 0, 0, 0, 2);
+    let grd_1 = prison.guard_ref(key_1)?;
+    assert_eq!(prison.insert(2), Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced));
+    assert_eq!(prison.remove(key_1), Err(AccessError::RemoveWhileValueReferenced(1)));
+    assert_eq!(prison.remove(key_0)?, 0);
+    assert_atomic_prison_state!(prison, 1, 1, 1, 2);
+    assert_atomic_cell_refs!(prison, 0, AtomicRefs::FREE);
+    assert_eq!(prison.remove(key_0), Err(AccessError::ValueDeleted(0, 0)));
+    assert_eq!(prison.guard_ref(key_0).err(), Some(AccessError::ValueDeleted(0, 0)));
+    let key_2 = prison.insert(2)?;
+    assert_eq!(key_2, CellKey::from_raw_parts(0, 1));
+    assert_eq!(prison.guard_mut(key_0).err(), Some(AccessError::ValueDeleted(0, 0)));
+    assert_eq!(prison.guard_mut(CellKey::from_raw_parts(5, 0)).err(), Some(AccessError::IndexOutOfRange(5)));
+    drop(grd_1);
+    assert_atomic_prison_state!(prison, 0, 1, 0, 2);
+    assert_eq!(prison.num_used(), 2);
+    prison.insert(3)?;
+    assert_eq!(prison.vec_len(), 3);
+    Ok(())
+}
+
+//TEST AtomicPrison::guard_mut() and AtomicPrison::guard_ref()
+#[test]
+fn atomic_prison_guards() -> Result<(), AccessError> {
+    let prison: AtomicPrison<u32> = AtomicPrison::new();
+    let key_0 = prison.insert(10)?;
+    let grd_a = prison.guard_ref(key_0)?;
+    let grd_b = prison.guard_ref(key_0)?;
+    assert_atomic_cell_refs!(prison, 0, 2);
+    assert_atomic_prison_state!(prison, 2, 0, 0, 1);
+    assert_eq!(prison.guard_mut(key_0).err(), Some(AccessError::ValueStillImmutablyReferenced(0)));
+    drop(grd_a);
+    drop(grd_b);
+    assert_atomic_cell_refs!(prison, 0, 0);
+    let mut grd_mut = prison.guard_mut(key_0)?;
+    *grd_mut += 1;
+    assert_atomic_cell_refs!(prison, 0, AtomicRefs::MUT);
+    assert_eq!(prison.guard_ref(key_0).err(), Some(AccessError::ValueAlreadyMutablyReferenced(0)));
+    assert_eq!(prison.guard_mut(key_0).err(), Some(AccessError::ValueAlreadyMutablyReferenced(0)));
+    AtomicPrisonValueMut::unguard(grd_mut);
+    internal!(prison).vec[0].refs.store(AtomicRefs::MAX_IMMUT, AtomicOrdering::SeqCst);
+    assert_eq!(prison.guard_ref(key_0).err(), Some(AccessError::MaximumImmutableReferencesReached(0)));
+    internal!(prison).vec[0].refs.store(0, AtomicOrdering::SeqCst);
+    assert_eq!(prison.clone_val(key_0)?, 11);
+    assert_eq!(prison.visit_mut(key_0, |val| Ok(*val + 1))?, 12);
+    let res: Result<(), Box<dyn std::error::Error>> = prison.visit_ref_with(CellKey::from_raw_parts(3, 0), |_| Ok(()));
+    assert_eq!(res.unwrap_err().to_string(), AccessError::IndexOutOfRange(3).to_string());
+    assert_atomic_prison_state!(prison, 0, 0, 0, 1);
+    Ok(())
+}
+
+//TEST AtomicPrison: concurrent visits
+#[test]
+fn atomic_prison_concurrent_visits() -> Result<(), AccessError> {
+    const THREADS: usize = 8;
+    const ROUNDS: usize = 1000;
+    let prison: AtomicPrison<usize> = AtomicPrison::with_capacity(THREADS * 2);
+    let keys: Vec<CellKey> = (0..THREADS).map(|_| prison.insert(0)).collect::<Result<_, _>>()?;
+    let shared = prison.insert(7)?;
+    std::thread::scope(|scope| {
+        for key in keys.iter().copied() {
+            let prison = &prison;
+            scope.spawn(move || {
+                for _ in 0..ROUNDS {
+                    prison
+                        .visit_mut(key, |val| {
+                            *val += 1;
+                            Ok(())
+                        })
+                        .unwrap();
+                    prison
+                        .visit_ref(shared, |val| {
+                            assert_eq!(*val, 7);
+                            Ok(())
+                        })
+                        .unwrap();
+                    let temp = prison.insert(usize::MAX).unwrap();
+                    assert_eq!(prison.remove(temp).unwrap(), usize::MAX);
+                }
+            });
+        }
+    });
+    for key in keys {
+        assert_eq!(prison.clone_val(key)?, ROUNDS);
+    }
+    assert_eq!(prison.num_used(), THREADS + 1);
+    assert_eq!(prison.access_count.load(AtomicOrdering::SeqCst), 0);
+    assert!(internal!(prison).generation <= THREADS * ROUNDS);
+    Ok(())
+}
+
+//TEST AtomicPrison: drop
+#[test]
+fn atomic_prison_drop() -> Result<(), AccessError> {
+    let counter = Arc::new(());
+    let prison: AtomicPrison<Arc<()>> = AtomicPrison::new();
+    let key_0 = prison.insert(counter.clone())?;
+    prison.insert(counter.clone())?;
+    prison.insert(counter.clone())?;
+    drop(prison.remove(key_0)?);
+    assert_eq!(Arc::strong_count(&counter), 3);
+    drop(prison);
+    assert_eq!(Arc::strong_count(&counter), 1);
+    Ok(())
+}
+
+//TEST AtomicPrison: Send + Sync
+#[test]
+fn atomic_prison_send_sync() {
+    fn assert_send_sync<S: Send + Sync>() {}
+    assert_send_sync::<AtomicPrison<u32>>();
+    assert_send_sync::<AtomicPrison<String>>();
+}
+
+//TEST AtomicPrison and AtomicJailCell: UnwindSafe + RefUnwindSafe
+#[test]
+fn atomic_unwind_safe() -> Result<(), AccessError> {
+    fn assert_ref_unwind_safe<S: RefUnwindSafe>() {}
+    fn assert_unwind_safe<S: UnwindSafe>() {}
+    assert_ref_unwind_safe::<AtomicPrison<String>>();
+    assert_ref_unwind_safe::<AtomicJailCell<String>>();
+    assert_unwind_safe::<AtomicPrisonValueRef<String>>();
+    assert_unwind_safe::<AtomicJailValueRef<String>>();
+    let prison: AtomicPrison<u32> = AtomicPrison::new();
+    let key_0 = prison.insert(0)?;
+    assert!(std::panic::catch_unwind(|| prison.visit_mut(key_0, |_| -> Result<(), AccessError> { panic!() })).is_err());
+    assert_atomic_cell_refs!(prison, 0, 0);
+    assert_atomic_prison_state!(prison, 0, 0, 0, 1);
+    let jail: AtomicJailCell<u32> = AtomicJailCell::new(0);
+    assert!(std::panic::catch_unwind(|| jail.visit_ref(|_| -> Result<(), AccessError> { panic!() })).is_err());
+    assert_eq!(jail.refs.load(AtomicOrdering::SeqCst), 0);
+    Ok(())
+}
+
+//TEST AtomicJailCell
+#[test]
+fn atomic_jail_guards() -> Result<(), AccessError> {
+    let jail: AtomicJailCell<u32> = AtomicJailCell::new(10);
+    let grd_a = jail.try_guard_ref()?;
+    let grd_b = jail.guard_ref();
+    assert_eq!(jail.refs.load(AtomicOrdering::SeqCst), 2);
+    assert_eq!(jail.try_guard_mut().err(), Some(AccessError::ValueStillImmutablyReferenced(0)));
+    assert_eq!(jail.visit_mut(|_| Ok(())), Err(AccessError::ValueStillImmutablyReferenced(0)));
+    drop(grd_a);
+    drop(grd_b);
+    let mut grd_mut = jail.try_guard_mut()?;
+    *grd_mut += 1;
+    assert_eq!(jail.refs.load(AtomicOrdering::SeqCst), AtomicRefs::MUT);
+    assert_eq!(jail.try_guard_ref().err(), Some(AccessError::ValueAlreadyMutablyReferenced(0)));
+    assert_eq!(jail.try_guard_mut().err(), Some(AccessError::ValueAlreadyMutablyReferenced(0)));
+    AtomicJailValueMut::unguard(grd_mut);
+    jail.refs.store(AtomicRefs::MAX_IMMUT, AtomicOrdering::SeqCst);
+    assert_eq!(jail.try_guard_ref().err(), Some(AccessError::MaximumImmutableReferencesReached(0)));
+    jail.refs.store(0, AtomicOrdering::SeqCst);
+    assert_eq!(jail.visit_ref(|val| Ok(*val))?, 11);
+    let res: Result<(), Box<dyn std::error::Error>> = jail.visit_mut_with(|val| Err(format!("bad value {}", val).into()));
+    assert_eq!(res.unwrap_err().to_string(), "bad value 11");
+    assert_eq!(jail.refs.load(AtomicOrdering::SeqCst), 0);
+    Ok(())
+}
+
+//TEST AtomicJailCell: blocking guards across threads
+#[test]
+fn atomic_jail_blocking() {
+    const THREADS: usize = 8;
+    const ROUNDS: usize = 1000;
+    let jail: AtomicJailCell<(usize, usize)> = AtomicJailCell::new((0, 0));
+    std::thread::scope(|scope| {
+        for _ in 0..THREADS {
+            scope.spawn(|| {
+                for _ in 0..ROUNDS {
+                    let mut grd = jail.guard_mut();
+                    grd.0 += 1;
+                    grd.1 += 1;
+                    drop(grd);
+                    let grd = jail.guard_ref();
+                    assert_eq!(grd.0, grd.1);
+                }
+            });
+        }
+    });
+    assert_eq!(jail.clone_val(), (THREADS * ROUNDS, THREADS * ROUNDS));
+    assert_eq!(jail.into_inner().0, THREADS * ROUNDS);
+}
@@ -1,7 +1,32 @@
 use crate::{
-    extract_true_start_end, internal, major_malfunction, mem_replace, unreachable_unchecked,
-    AccessError, Borrow, BorrowMut, CellKey, Debug, Deref, DerefMut, MaybeUninit, RangeBounds,
-    UnsafeCell,
+    extract_true_start_end, format, internal, major_malfunction, mem_replace, mem_swap, mem_take, size_of, unreachable_unchecked,
+    AccessError, AddAssign, AtomicOrdering, BatchError, AtomicU32, Borrow, BorrowMut, CellKey, CellKeySet, CmpOrdering, Context,
+    Debug, Deref, DerefMut, Future, GroupKey, Hash, Hasher, Index, InlineVec, KeyRemap, KeyspaceTable, ManuallyDrop, MaybeUninit, Pin, Poll,
+    PrisonStorage, PrisonValue, ptr_eq, ptr_read, RangeBounds, RefUnwindSafe, Scratch, ScriptHandle, StorageContainer, SubAssign, TypedKey, UnsafeCell, UnwindSafe, ValueGuardMut, ValueGuardRef, Vec, VecStorage, Waker, vec, Box,
+};
+
+#[cfg(feature = "rand")]
+use rand_core::RngCore;
+
+#[cfg(feature = "uuid")]
+use core::sync::atomic::AtomicU64;
+
+#[cfg(feature = "serde")]
+use core::marker::PhantomData;
+
+#[cfg(feature = "serde")]
+use crate::String;
+
+#[cfg(feature = "raw")]
+use crate::{slice_from_raw_parts, slice_from_raw_parts_mut, PlainData};
+
+#[cfg(feature = "profile")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "offload")]
+use std::{
+    fs::File,
+    io::{Read, Seek, SeekFrom, Write},
 };
 
 #[cfg(test)]
@@ -9,10 +34,113 @@ use crate::{
 
 //====== Misc Types ======
 //STRUCT Refs
+/// The reference counting rules shared by every cell in a [Prison], [RefCountCell], and [JailCell]
+///
+/// A reference count of `0` means unreferenced, [Refs::MUT] means mutably referenced, [Refs::HIDDEN]
+/// means the value is hidden from every access until published, and anything else is the number of
+/// active immutable references
+#[doc(hidden)]
 struct Refs {}
 impl Refs {
     const MUT: usize = usize::MAX;
-    const MAX_IMMUT: usize = Self::MUT - 1;
+    const HIDDEN: usize = Self::MUT - 1;
+    const MAX_IMMUT: usize = Self::MUT - 2;
+
+    //FN Refs::add_mut()
+    #[inline(always)]
+    fn add_mut(refs: &mut usize, idx: usize) -> Result<(), AccessError> {
+        match *refs {
+            0 => {
+                *refs = Refs::MUT;
+                return Ok(());
+            }
+            Refs::MUT => return Err(AccessError::ValueAlreadyMutablyReferenced(idx)),
+            Refs::HIDDEN => return Err(AccessError::ValueHidden(idx)),
+            _ => return Err(AccessError::ValueStillImmutablyReferenced(idx)),
+        }
+    }
+
+    //FN Refs::add_imm()
+    /// Returns `true` if this was the first reference to the value
+    #[inline(always)]
+    fn add_imm(refs: &mut usize, idx: usize) -> Result<bool, AccessError> {
+        match *refs {
+            Refs::MUT => return Err(AccessError::ValueAlreadyMutablyReferenced(idx)),
+            Refs::HIDDEN => return Err(AccessError::ValueHidden(idx)),
+            Refs::MAX_IMMUT => return Err(AccessError::MaximumImmutableReferencesReached(idx)),
+            _ => {
+                *refs += 1;
+                return Ok(*refs == 1);
+            }
+        }
+    }
+
+    //FN Refs::downgrade()
+    /// Turns the single mutable reference into a single immutable reference without releasing the value
+    #[inline(always)]
+    fn downgrade(refs: &mut usize) {
+        *refs = 1;
+    }
+
+    //FN Refs::upgrade()
+    /// Turns the single immutable reference into a single mutable reference without releasing the value
+    #[inline(always)]
+    fn upgrade(refs: &mut usize) {
+        *refs = Refs::MUT;
+    }
+
+    //FN Refs::remove_mut()
+    #[inline(always)]
+    fn remove_mut(refs: &mut usize) {
+        *refs = 0;
+    }
+
+    //FN Refs::remove_imm()
+    /// Returns `true` if this was the last reference to the value
+    #[inline(always)]
+    fn remove_imm(refs: &mut usize) -> bool {
+        *refs -= 1;
+        return *refs == 0;
+    }
+
+    //FN Refs::kind()
+    #[inline(always)]
+    fn kind(refs: usize) -> RefKind {
+        match refs {
+            0 => return RefKind::None,
+            Refs::MUT => return RefKind::Mutable,
+            Refs::HIDDEN => return RefKind::Hidden,
+            count => return RefKind::Immutable(count),
+        }
+    }
+}
+
+//STRUCT AccessCount
+/// The master access counter of a [Prison], together with the wakers of every future waiting for a value to be released
+#[doc(hidden)]
+#[derive(Debug)] //COV_IGNORE
+struct AccessCount {
+    count: usize,
+    wakers: Vec<Waker>,
+}
+impl AccessCount {
+    //FN AccessCount::wake_all()
+    #[inline(always)]
+    fn wake_all(&mut self) {
+        if !self.wakers.is_empty() {
+            self.wake_all_cold();
+        }
+    }
+
+    //FN AccessCount::wake_all_cold()
+    /// Kept out of line so that releasing a reference inlines to a single check of `wakers`
+    #[cold]
+    #[inline(never)]
+    fn wake_all_cold(&mut self) {
+        for waker in mem_take(&mut self.wakers) {
+            waker.wake();
+        }
+    }
 }
 
 //STRUCT IdxD
@@ -69,12 +197,250 @@ impl IdxD {
 /// the [Prison] will insert values into free spaces before they consider extending the [Vec],
 /// minimizing reallocations when possible.
 ///
+/// ### Thread Safety
+/// [Prison<T>] is [Send] whenever `T` is [Send], so a [Prison] can be moved into another thread
+/// (for example into a job that takes exclusive ownership of it). It is *not* [Sync]: the reference
+/// counting is not atomic, so only one thread at a time can ever access a [Prison], and it cannot be moved
+/// while any `visit()` or `guard()` borrows it
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<String> = Prison::new();
+/// let key_0 = prison.insert(String::from("Hello"))?;
+/// let prison = std::thread::spawn(move || {
+///     prison.visit_mut(key_0, |val_0| {
+///         val_0.push_str(", World!");
+///         Ok(())
+///     }).unwrap();
+///     prison
+/// }).join().unwrap();
+/// assert_eq!(prison.clone_val(key_0)?, String::from("Hello, World!"));
+/// # Ok(())
+/// # }
+/// ```
+/// ### Example
+/// ```compile_fail
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(42)?;
+/// std::thread::scope(|scope| {
+///     // will not compile: (error[E0277]: `UnsafeCell<PrisonInternal<u32>>` cannot be shared between threads safely)
+///     scope.spawn(|| prison.visit_mut(key_0, |val_0| Ok(())));
+///     prison.visit_mut(key_0, |val_0| Ok(()))
+/// })?;
+/// # Ok(())
+/// # }
+/// ```
+/// ### Panic Safety
+/// Every reference count acquired by a `visit()` method or guard is released while unwinding if the
+/// closure (or any other code) panics, so the [Prison] itself is never left in an inconsistent state and
+/// [Prison<T>] is [RefUnwindSafe] whenever `T` is. This lets you use a `&Prison` inside
+/// [std::panic::catch_unwind()] without [AssertUnwindSafe](std::panic::AssertUnwindSafe). A value that
+/// was being mutated when the panic happened may still be left partially modified, exactly like a value
+/// behind a `&mut` reference. For the same reason the mutable guards are not [UnwindSafe], while
+/// [PrisonValueRef] and [PrisonSliceRef] are whenever `T` is [RefUnwindSafe]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(42)?;
+/// let result = std::panic::catch_unwind(|| {
+///     prison.visit_ref(key_0, |val_0| -> Result<(), AccessError> {
+///         panic!("frame update failed");
+///     })
+/// });
+/// assert!(result.is_err());
+/// assert!(prison.visit_mut(key_0, |val_0| Ok(())).is_ok());
+/// # Ok(())
+/// # }
+/// ```
 /// See the crate-level documentation or individual methods for more info
-#[derive(Debug)] //COV_IGNORE
-pub struct Prison<T> {
-    internal: UnsafeCell<PrisonInternal<T>>,
+pub struct Prison<T, S: PrisonStorage = VecStorage> {
+    internal: UnsafeCell<PrisonInternal<T, S>>,
+}
+
+//IMPL Debug for Prison
+/// Shows the length, usage, generation and active accesses of the [Prison], followed by the state of every
+/// slot: free slots with their free-list links, and values with their generation, reference count and value
+///
+/// Values that are currently mutably referenced are never read and show as `<mutably referenced>`, and every
+/// value shows as `<redacted>` while [Prison::set_redact_values()] is enabled
+impl<T: Debug, S: PrisonStorage> Debug for Prison<T, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let internal = internal!(self);
+        let slots = DebugSlots { prison: self };
+        return f
+            .debug_struct("Prison")
+            .field("len", &internal.vec.len())
+            .field("used", &(internal.vec.len() - internal.free_count))
+            .field("generation", &internal.generation)
+            .field("accesses", &internal.access_count.count)
+            .field("slots", &slots)
+            .finish();
+    }
+}
+
+//STRUCT DebugSlots
+#[doc(hidden)]
+struct DebugSlots<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+}
+
+//IMPL Debug for DebugSlots
+impl<'a, T: Debug, S: PrisonStorage> Debug for DebugSlots<'a, T, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let prison = self.prison;
+        let internal = internal!(prison);
+        let mut list = f.debug_list();
+        for idx in 0..internal.vec.len() {
+            list.entry(&DebugSlot { prison, idx });
+        }
+        return list.finish();
+    }
+}
+
+//STRUCT DebugSlot
+#[doc(hidden)]
+struct DebugSlot<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    idx: usize,
+}
+
+//IMPL Debug for DebugSlot
+impl<'a, T: Debug, S: PrisonStorage> Debug for DebugSlot<'a, T, S> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let prison = self.prison;
+        let internal = internal!(prison);
+        let cell = &internal.vec[self.idx];
+        if cell.is_free() {
+            let mut free = f.debug_struct("Free");
+            free.field("idx", &self.idx);
+            if internal.is_reserved(self.idx) {
+                free.field("reserved", &true);
+            }
+            return free
+                .field("next", &DebugLink(cell.refs_or_next))
+                .field("prev", &DebugLink(IdxD::val(cell.d_gen_or_prev)))
+                .finish();
+        }
+        let mut val = f.debug_struct("Val");
+        val.field("idx", &self.idx).field("gen", &IdxD::val(cell.d_gen_or_prev));
+        match cell.refs_or_next {
+            Refs::MUT => val.field("refs", &format_args!("mut")),
+            Refs::HIDDEN => val.field("refs", &format_args!("hidden")),
+            refs => val.field("refs", &refs),
+        };
+        #[cfg(feature = "offload")]
+        if internal.offloaded_pos(self.idx).is_ok() {
+            return val.field("val", &format_args!("<offloaded>")).finish();
+        }
+        if internal.redact_values() {
+            return val.field("val", &format_args!("<redacted>")).finish();
+        }
+        if cell.refs_or_next == Refs::MUT {
+            return val.field("val", &format_args!("<mutably referenced>")).finish();
+        }
+        let cell_val = unsafe { cell.val.assume_init_ref() };
+        prison._decoded_ref(cell_val, |cell_val| {
+            val.field("val", cell_val);
+        });
+        return val.finish();
+    }
+}
+
+//STRUCT DebugLink
+#[doc(hidden)]
+struct DebugLink(usize);
+
+//IMPL Debug for DebugLink
+impl Debug for DebugLink {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        return match self.0 {
+            IdxD::INVALID => f.write_str("None"),
+            idx => write!(f, "{}", idx),
+        };
+    }
+}
+
+//IMPL Clone for Prison
+/// See [Prison::clone_all()]
+///
+/// # Panics
+/// If a value is currently mutably referenced, or (with the `offload` feature) an offloaded value could not be reloaded
+impl<T: Clone, S: PrisonStorage> Clone for Prison<T, S> {
+    fn clone(&self) -> Self {
+        match self.clone_all() {
+            Ok(prison) => return prison,
+            Err(acc_err) => panic!("Prison::clone() failed: {}", acc_err),
+        }
+    }
+}
+
+//IMPL PartialEq for Prison
+/// #### This operation has O(N) time complexity
+///
+/// Two prisons are equal when they hold values with the same [CellKey]'s and equal (decoded) values, regardless of their
+/// capacity, free-list order, reference counts, or any other bookkeeping. This is mostly useful in tests, for example to
+/// compare a [Prison] against one restored from a snapshot
+///
+/// A value that is currently mutably referenced cannot be read, so it never compares equal (and neither does an offloaded value that fails to reload)
+impl<T: PartialEq, S: PrisonStorage> PartialEq for Prison<T, S> {
+    fn eq(&self, other: &Self) -> bool {
+        let (internal_a, internal_b) = (internal!(self), internal!(other));
+        let len = internal_a.vec.len().max(internal_b.vec.len());
+        for idx in 0..len {
+            let (cell_a, cell_b) = match (internal_a.vec.get(idx), internal_b.vec.get(idx)) {
+                (Some(cell_a), Some(cell_b)) if cell_a.is_cell() && cell_b.is_cell() => (cell_a, cell_b),
+                (Some(cell), _) | (_, Some(cell)) if cell.is_cell() => return false,
+                _ => continue,
+            };
+            if cell_a.d_gen_or_prev != cell_b.d_gen_or_prev || cell_a.refs_or_next == Refs::MUT || cell_b.refs_or_next == Refs::MUT {
+                return false;
+            }
+            #[cfg(feature = "offload")]
+            if internal!(self).reload(idx).is_err() || internal!(other).reload(idx).is_err() {
+                return false;
+            }
+            let (val_a, val_b) = unsafe { (cell_a.val.assume_init_ref(), cell_b.val.assume_init_ref()) };
+            if !self._decoded_ref(val_a, |val_a| other._decoded_ref(val_b, |val_b| val_a == val_b)) {
+                return false;
+            }
+        }
+        return true;
+    }
+}
+
+//IMPL From<Vec> for Prison
+/// #### This operation has O(N) time complexity
+///
+/// Move every value of the [Vec] into a new [Prison] sized to fit them exactly, value `n` receiving
+/// the [CellKey] with index `n` and generation `0`
+impl<T> From<Vec<T>> for Prison<T> {
+    fn from(vals: Vec<T>) -> Self {
+        let prison = Prison::with_capacity(vals.len());
+        if let Err(acc_err) = prison.insert_many(vals) {
+            panic!("Prison::from() failed: {}", acc_err); //COV_IGNORE
+        }
+        return prison;
+    }
+}
+
+//IMPL FromIterator for Prison
+/// #### This operation has O(N) time complexity
+///
+/// Collect every value into a new [Prison], see [Prison::from()](From)
+impl<T> FromIterator<T> for Prison<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        return Prison::from(iter.into_iter().collect::<Vec<T>>());
+    }
 }
 
+//IMPL RefUnwindSafe for Prison
+impl<T: RefUnwindSafe, S: PrisonStorage> RefUnwindSafe for Prison<T, S> {}
+
 impl<T> Prison<T> {
     //FN Prison::new()
     /// Create a new [Prison] with the default allocation strategy ([Vec::new()])
@@ -100,13 +466,7 @@ impl<T> Prison<T> {
     #[inline(always)]
     pub fn new() -> Self {
         return Self {
-            internal: UnsafeCell::new(PrisonInternal {
-                access_count: 0,
-                free_count: 0,
-                generation: 0,
-                next_free: IdxD::INVALID,
-                vec: Vec::new(),
-            }),
+            internal: UnsafeCell::new(PrisonInternal::new(Vec::new(), None)),
         };
     }
 
@@ -134,13 +494,153 @@ impl<T> Prison<T> {
     #[inline(always)]
     pub fn with_capacity(size: usize) -> Self {
         return Self {
-            internal: UnsafeCell::new(PrisonInternal {
-                access_count: 0,
-                free_count: 0,
-                generation: 0,
-                next_free: IdxD::INVALID,
-                vec: Vec::with_capacity(size),
-            }),
+            internal: UnsafeCell::new(PrisonInternal::new(Vec::with_capacity(size), None)),
+        };
+    }
+
+    //FN Prison::with_placeholder()
+    /// Create a new [Prison<T>] that fills every removed space with a placeholder value produced by the
+    /// provided function, instead of leaving it uninitialized
+    ///
+    /// Free spaces still behave exactly as they do in any other [Prison]: they cannot be visited or guarded,
+    /// and any [CellKey] to a removed value is still invalid. However, because every space always holds a valid
+    /// value, reads that ignore reference counting and free/deleted status ([Prison::peek_ref_or_placeholder_idx()])
+    /// can never touch uninitialized memory. This is useful for soft-real-time systems that prefer reading
+    /// stale or placeholder data over handling errors.
+    ///
+    /// The placeholder is created once for every removal and dropped when the space is filled again
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<String> = Prison::with_placeholder(|| String::from("<removed>"));
+    /// let key_0 = prison.insert(String::from("Hello"))?;
+    /// prison.remove(key_0)?;
+    /// assert!(prison.visit_ref(key_0, |val_0| Ok(())).is_err());
+    /// let placeholder = unsafe { prison.peek_ref_or_placeholder_idx(0)? };
+    /// assert_eq!(*placeholder, String::from("<removed>"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn with_placeholder(placeholder: fn() -> T) -> Self {
+        return Self::with_capacity_and_placeholder(0, placeholder);
+    }
+
+    //FN Prison::with_capacity_and_placeholder()
+    /// Create a new [Prison<T>] with a specific starting capacity ([Vec::with_capacity()]) that fills every
+    /// removed space with a placeholder value produced by the provided function
+    ///
+    /// See [Prison::with_placeholder()] for more info
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() {
+    /// let my_prison: Prison<u32> = Prison::with_capacity_and_placeholder(1000, || 0);
+    /// assert!(my_prison.vec_cap() == 1000)
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn with_capacity_and_placeholder(size: usize, placeholder: fn() -> T) -> Self {
+        return Self {
+            internal: UnsafeCell::new(PrisonInternal::new(Vec::with_capacity(size), Some(placeholder))),
+        };
+    }
+
+    //FN Prison::with_transform()
+    /// Create a new [Prison<T>] that stores every value in a transformed form, passing values through
+    /// `encode` when they enter the [Prison] and through `decode` when they are accessed or leave it
+    ///
+    /// This allows large, seldom-read values to be kept compressed or obfuscated while in memory. The transform is
+    /// applied transparently by the following methods (and any method built on them, such as [Prison::insert_group()],
+    /// [Prison::remove_group()] or the `visit_handle_*()` methods):
+    /// - `insert()`, `insert_at()`, `insert_at_idx_or_append()` and `overwrite()` encode the new value
+    /// - `remove()` and `remove_idx()` return the decoded value
+    /// - `visit_mut()`, `visit_ref()`, `visit_mut_idx()` and `visit_ref_idx()` decode the value into a scratch slot that lives for the duration of the closure
+    /// - `visit_mut()` and `visit_mut_idx()` also encode the scratch slot back into the [Prison] afterwards
+    /// - `clone_val()`, `clone_val_idx()` and the `clone_many_vals*()` methods return decoded values
+    /// - `serialize_reachable()` passes decoded values to both of its closures
+    /// - `map_into()`, `into_inner_checked()` and `leak_all()` convert or return decoded values
+    ///
+    /// Every other method (guards, slices, multi-value visits, etc.) references the value in its stored, encoded form.
+    /// `decode` must not panic: if it does while a value is visited, the value remains referenced
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<Vec<u8>> = Prison::with_transform(
+    ///     |plain| plain.into_iter().map(|byte| byte ^ 0xAA).collect(),
+    ///     |hidden| hidden.iter().map(|byte| byte ^ 0xAA).collect(),
+    /// );
+    /// let key_0 = prison.insert(b"secret".to_vec())?;
+    /// prison.visit_mut(key_0, |val_0| {
+    ///     val_0.push(b'!');
+    ///     Ok(())
+    /// })?;
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// assert_ne!(grd_0.as_slice(), b"secret!");
+    /// drop(grd_0);
+    /// assert_eq!(prison.remove(key_0)?, b"secret!");
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "transform")]
+    #[inline(always)]
+    pub fn with_transform(encode: fn(T) -> T, decode: fn(&T) -> T) -> Self {
+        return Self::with_capacity_and_transform(0, encode, decode);
+    }
+
+    //FN Prison::with_capacity_and_transform()
+    /// Create a new [Prison<T>] with a specific starting capacity ([Vec::with_capacity()]) that stores every
+    /// value in a transformed form
+    ///
+    /// See [Prison::with_transform()] for more info
+    #[cfg(feature = "transform")]
+    #[inline(always)]
+    pub fn with_capacity_and_transform(size: usize, encode: fn(T) -> T, decode: fn(&T) -> T) -> Self {
+        let mut internal = PrisonInternal::new(Vec::with_capacity(size), None);
+        internal.transform = Some((encode, decode));
+        return Self {
+            internal: UnsafeCell::new(internal),
+        };
+    }
+}
+
+impl<T, S: PrisonStorage> Prison<T, S> {
+    //FN Prison::with_storage()
+    /// Create a new [Prison] that keeps its values in the given [PrisonStorage] backend
+    ///
+    /// [Prison::new()] always uses the default [VecStorage], this is the constructor to use when the backend
+    /// is chosen by a generic parameter
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, VecStorage, single_threaded::Prison};
+    /// # fn main() {
+    /// let my_prison: Prison<u32, VecStorage> = Prison::with_storage(VecStorage);
+    /// assert_eq!(my_prison.vec_cap(), 0)
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn with_storage(storage: S) -> Self {
+        return Self::with_capacity_and_storage(0, storage);
+    }
+
+    //FN Prison::with_capacity_and_storage()
+    /// Create a new [Prison] with a specific starting capacity that keeps its values in the given [PrisonStorage] backend
+    ///
+    /// See [Prison::with_capacity()] and [Prison::with_storage()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, VecStorage, single_threaded::Prison};
+    /// # fn main() {
+    /// let my_prison: Prison<u32, VecStorage> = Prison::with_capacity_and_storage(1000, VecStorage);
+    /// assert!(my_prison.vec_cap() == 1000)
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn with_capacity_and_storage(size: usize, _storage: S) -> Self {
+        return Self {
+            internal: UnsafeCell::new(PrisonInternal::new(S::Container::with_capacity(size), None)),
         };
     }
 
@@ -168,80 +668,341 @@ impl<T> Prison<T> {
         return internal!(self).vec.capacity();
     }
 
-    //FN Prison::num_free()
-    /// Return the number of spaces available for elements to be added to the [Prison]
-    /// without reallocating more memory.
-    #[inline(always)]
-    pub fn num_free(&self) -> usize {
-        let internal = internal!(self);
-        return internal.free_count + internal.vec.capacity() - internal.vec.len();
-    }
-
-    //FN Prison::num_used()
-    /// Return the number of spaces currently occupied by valid elements in the [Prison]
-    #[inline(always)]
-    pub fn num_used(&self) -> usize {
-        let internal = internal!(self);
-        return internal.vec.len() - internal.free_count;
-    }
-
-    //FN Prison::density()
-    /// Return the ratio of used space to total space in the [Prison]
-    ///
-    /// 0.0 = 0% used, 1.0 = 100% used
-    pub fn density(&self) -> f32 {
-        let internal = internal!(self);
-        let used = internal.vec.len() - internal.free_count;
-        let cap = internal.vec.capacity();
-        return (used as f32) / (cap as f32);
-    }
-
-    //FN Prison::insert()
-    /// Insert a value into the [Prison] and recieve a [CellKey] that can be used to
-    /// reference it in the future
+    //FN Prison::reserve()
+    /// Reserve capacity for at least `additional` more elements to be added to the underlying [Vec]
+    /// without reallocating, like [Vec::reserve()]
     ///
-    /// As long as there are sufficient free cells or vector capacity to do so,
-    /// you may `insert()` to the [Prison] while any of its elements have active references
+    /// Because reallocating would invalidate active references, this fails if the [Vec] must grow while
+    /// any value is referenced. If the current capacity is already sufficient this does nothing and succeeds
+    /// regardless of active references
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let string_prison: Prison<String> = Prison::with_capacity(10);
-    /// let key_0 = string_prison.insert(String::from("Hello, "))?;
-    /// string_prison.visit_ref(key_0, |first_string| {
-    ///     let key_1 = string_prison.insert(String::from("World!"))?;
-    ///     string_prison.visit_ref(key_1, |second_string| {
-    ///         let hello_world = format!("{}{}", first_string, second_string);
-    ///         assert_eq!(hello_world, "Hello, World!");
-    ///         Ok(())
-    ///     });
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.reserve(10)?;
+    /// assert!(prison.vec_cap() >= 10);
+    /// let key_0 = prison.insert(0)?;
+    /// prison.visit_ref(key_0, |_| {
+    ///     assert!(prison.reserve(5).is_ok());
+    ///     assert_eq!(prison.reserve(100), Err(AccessError::AccessesOutstanding(1)));
     ///     Ok(())
-    /// });
+    /// })?;
     /// # Ok(())
     /// # }
     /// ```
-    /// However, if the [Prison] is at maxumum capacity, attempting to `insert()`
-    /// during while there are active references to any element will cause the operation to fail and a
-    /// [AccessError::InsertAtMaxCapacityWhileAValueIsReferenced] to be returned
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::MaximumCapacityReached] if the new capacity would exceed the maximum capacity
+    /// - [AccessError::AccessesOutstanding(count)] if the [Vec] must grow while any value is referenced
+    pub fn reserve(&self, additional: usize) -> Result<(), AccessError> {
+        return self._reserve(additional, false);
+    }
+
+    //FN Prison::reserve_exact()
+    /// Reserve the minimum capacity for exactly `additional` more elements to be added to the underlying [Vec]
+    /// without reallocating, like [Vec::reserve_exact()]
+    ///
+    /// Follows the same rules as [Prison::reserve()] regarding active references
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let string_prison: Prison<String> = Prison::with_capacity(1);
-    /// let key_0 = string_prison.insert(String::from("Hello, "))?;
-    /// string_prison.visit_ref(key_0, |first_string| {
-    ///     assert!(string_prison.insert(String::from("World!")).is_err());
-    ///     Ok(())
-    /// })?;
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(0)?;
+    /// prison.reserve_exact(9)?;
+    /// assert!(prison.vec_cap() >= 10);
+    /// assert_eq!(prison.num_free(), prison.vec_cap() - 1);
     /// # Ok(())
     /// # }
     /// ```
-    #[inline(always)]
-    pub fn insert(&self, value: T) -> Result<CellKey, AccessError> {
-        let internal = internal!(self);
-        if internal.next_free == IdxD::INVALID {
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::MaximumCapacityReached] if the new capacity would exceed the maximum capacity
+    /// - [AccessError::AccessesOutstanding(count)] if the [Vec] must grow while any value is referenced
+    pub fn reserve_exact(&self, additional: usize) -> Result<(), AccessError> {
+        return self._reserve(additional, true);
+    }
+
+    //FN Prison::shrink_to_fit()
+    /// Drop every free cell at the end of the underlying [Vec] and shrink its capacity as much as possible,
+    /// like [Vec::shrink_to_fit()]
+    ///
+    /// Unlike [Prison::purge()], no values are moved and every [CellKey] stays valid, so only the free cells
+    /// after the last valid value (or reserved index) are given back. Because shrinking reallocates the [Vec],
+    /// this fails if any value is referenced. The spare buffers reported by [Prison::spare_buffer_bytes()] are freed as well
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::with_capacity(100);
+    /// let keys: Vec<CellKey> = (0..50).map(|val| prison.insert(val)).collect::<Result<_, _>>()?;
+    /// for key in &keys[10..] {
+    ///     prison.remove(*key)?;
+    /// }
+    /// prison.remove(keys[5])?;
+    /// prison.shrink_to_fit()?;
+    /// assert_eq!(prison.vec_len(), 10);
+    /// assert!(prison.vec_cap() < 100);
+    /// assert_eq!(prison.num_used(), 9);
+    /// assert_eq!(prison.insert(100)?.idx(), 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::AccessesOutstanding(count)] if any value is currently referenced
+    pub fn shrink_to_fit(&self) -> Result<(), AccessError> {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        if internal.access_count.count > 0 {
+            return Err(AccessError::AccessesOutstanding(internal.access_count.count));
+        }
+        let mut new_len = internal.vec.len();
+        while new_len > 0 && internal.vec[new_len - 1].is_free() && !internal.is_reserved(new_len - 1) {
+            new_len -= 1;
+        }
+        self.truncate(new_len)?;
+        internal.vec.shrink_to_fit();
+        if let Some(spare_bufs) = internal.spare_bufs() {
+            *spare_bufs = Vec::new();
+        }
+        return Ok(());
+    }
+
+    //FN Prison::spare_buffer_bytes()
+    /// Return the number of bytes held by the spare buffers this [Prison] keeps for batch accesses
+    ///
+    /// Batch methods such as [Prison::visit_many_mut()], [Prison::visit_set_ref()] and [Prison::guard_many_mut()]
+    /// store up to 8 references inline, and need heap buffers for larger batches. Instead of allocating them on
+    /// every call, the [Prison] keeps up to 4 spare buffers and reuses them, so repeating a batch access of the
+    /// same size (for example once per frame) does not allocate after the first time. Nested batch accesses that
+    /// find no spare buffer simply allocate their own. The buffers only grow to the largest batch seen, and can be
+    /// freed with [Prison::shrink_to_fit()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let keys = prison.insert_many(0..100)?;
+    /// prison.visit_many_ref(&keys[..4], |_| Ok(()))?;
+    /// assert_eq!(prison.spare_buffer_bytes(), 0);
+    /// prison.visit_many_ref(&keys, |_| Ok(()))?;
+    /// let bytes = prison.spare_buffer_bytes();
+    /// assert!(bytes >= 2 * 100 * std::mem::size_of::<usize>());
+    /// prison.visit_many_ref(&keys, |_| Ok(()))?;
+    /// assert_eq!(prison.spare_buffer_bytes(), bytes);
+    /// prison.shrink_to_fit()?;
+    /// assert_eq!(prison.spare_buffer_bytes(), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn spare_buffer_bytes(&self) -> usize {
+        let internal = internal!(self);
+        return internal.spare_bufs().map_or(0, |spare_bufs| spare_bufs.iter().map(|buf| buf.capacity() * size_of::<usize>()).sum());
+    }
+
+    //FN Prison::truncate()
+    /// Remove every cell at an index greater than or equal to `len`, dropping any values they hold,
+    /// like [Vec::truncate()]
+    ///
+    /// Free cells in the range are unlinked from the free list, and any reserved indexes in the range are released.
+    /// [CellKey]s to removed values return [AccessError::IndexOutOfRange(idx)] until the [Prison] grows again,
+    /// and [AccessError::ValueDeleted(idx, gen)] afterwards. Nothing is changed if `len` is greater than or
+    /// equal to [Prison::vec_len()], and the capacity of the [Vec] is left alone
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = Prison::new();
+    /// let key_0 = prison.insert("kept")?;
+    /// let key_1 = prison.insert("dropped")?;
+    /// prison.truncate(1)?;
+    /// assert_eq!(prison.vec_len(), 1);
+    /// assert_eq!(prison.clone_val(key_1), Err(AccessError::IndexOutOfRange(1)));
+    /// let key_2 = prison.insert("new")?;
+    /// assert_eq!(key_2.idx(), 1);
+    /// assert_eq!(prison.clone_val(key_1), Err(AccessError::ValueDeleted(1, 0)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::RemoveWhileValueReferenced(idx)] if a value in the range is currently referenced
+    /// - [AccessError::ValuePinned(idx)] if a value in the range is pinned with [Prison::pin()]
+    /// - [AccessError::MaxValueForGenerationReached] if a removed value would need a generation above the maximum
+    ///
+    /// The [Prison] is left unchanged if any error is returned
+    pub fn truncate(&self, len: usize) -> Result<(), AccessError> {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        let old_len = internal.vec.len();
+        if len >= old_len {
+            return Ok(());
+        }
+        let mut new_gen = internal.generation;
+        for (idx, cell) in internal.vec.iter().enumerate().skip(len) {
+            if !cell.is_cell() {
+                continue;
+            }
+            if cell.refs_or_next > 0 {
+                return Err(AccessError::RemoveWhileValueReferenced(idx));
+            }
+            if internal.is_pinned(idx) {
+                return Err(AccessError::ValuePinned(idx));
+            }
+            let cell_gen = IdxD::val(cell.d_gen_or_prev);
+            if cell_gen >= new_gen {
+                if cell_gen == IdxD::MAX_GEN {
+                    return Err(AccessError::MaxValueForGenerationReached);
+                }
+                new_gen = cell_gen + 1;
+            }
+        }
+        internal.generation = new_gen;
+        for idx in len..old_len {
+            if internal.vec[idx].is_free() {
+                self._unlink_free(idx)?;
+                if internal.placeholder().is_some() {
+                    unsafe { internal.vec[idx].val.assume_init_drop() };
+                }
+            } else {
+                #[cfg(feature = "uuid")]
+                internal.release_uuid(idx);
+            }
+        }
+        if let Some(extras) = internal.extras.as_mut() {
+            extras.reserved.retain(|(res_start, _)| *res_start < len);
+            if let Some((_, res_end)) = extras.reserved.last_mut() {
+                *res_end = (*res_end).min(len);
+            }
+        }
+        #[cfg(feature = "dirty")]
+        internal.dirty.retain(|dirty_idx| *dirty_idx < len);
+        internal.vec.truncate(len);
+        return Ok(());
+    }
+
+    //FN Prison::num_free()
+    /// Return the number of spaces available for elements to be added to the [Prison]
+    /// without reallocating more memory.
+    #[inline(always)]
+    pub fn num_free(&self) -> usize {
+        let internal = internal!(self);
+        return internal.free_count - internal.reserved_free() + internal.vec.capacity() - internal.vec.len();
+    }
+
+    //FN Prison::num_used()
+    /// Return the number of spaces currently occupied by valid elements in the [Prison]
+    #[inline(always)]
+    pub fn num_used(&self) -> usize {
+        let internal = internal!(self);
+        return internal.vec.len() - internal.free_count;
+    }
+
+    //FN Prison::density()
+    /// Return the ratio of used space to total space in the [Prison]
+    ///
+    /// 0.0 = 0% used, 1.0 = 100% used
+    pub fn density(&self) -> f32 {
+        let internal = internal!(self);
+        let used = internal.vec.len() - internal.free_count;
+        let cap = internal.vec.capacity();
+        return (used as f32) / (cap as f32);
+    }
+
+    //FN Prison::first_free_idx()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Return the lowest index holding a free cell that [Prison::insert_at()] could fill, or [None] if
+    /// every cell currently holds a value
+    ///
+    /// Free cells inside a reserved range (see [Prison::reserve_index_range()]) are included, since
+    /// `insert_at()` accepts them. Only cells within the current length are considered, capacity that
+    /// has not been pushed yet is not. The returned index is already salted (see [Prison::salt_idx()]), so
+    /// it can be passed straight to `insert_at()`. Together with [Prison::next_free_after()] this lets a
+    /// placement policy walk the free cells in ascending order without checking [Prison::state_idx()] for
+    /// every index. When the [Prison] has no free cells at all this returns immediately
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let keys = prison.insert_many([0, 1, 2, 3, 4])?;
+    /// assert_eq!(prison.first_free_idx(), None);
+    /// prison.remove(keys[3])?;
+    /// prison.remove(keys[1])?;
+    /// assert_eq!(prison.first_free_idx(), Some(1));
+    /// assert_eq!(prison.next_free_after(1), Some(3));
+    /// assert_eq!(prison.next_free_after(3), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn first_free_idx(&self) -> Option<usize> {
+        return self._free_idx_from(0);
+    }
+
+    //FN Prison::next_free_after()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Return the lowest index strictly greater than `idx` holding a free cell that [Prison::insert_at()]
+    /// could fill, or [None] if there is no such cell
+    ///
+    /// See [Prison::first_free_idx()]. `idx` itself does not need to be free or even in range, and
+    /// [None] is also returned if it belongs to another [Prison] while index validation is enabled
+    pub fn next_free_after(&self, idx: usize) -> Option<usize> {
+        let idx = self._idx(idx).ok()?;
+        return self._free_idx_from(idx.checked_add(1)?);
+    }
+
+    //FN Prison::insert()
+    /// Insert a value into the [Prison] and recieve a [CellKey] that can be used to
+    /// reference it in the future
+    ///
+    /// As long as there are sufficient free cells or vector capacity to do so,
+    /// you may `insert()` to the [Prison] while any of its elements have active references
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let string_prison: Prison<String> = Prison::with_capacity(10);
+    /// let key_0 = string_prison.insert(String::from("Hello, "))?;
+    /// string_prison.visit_ref(key_0, |first_string| {
+    ///     let key_1 = string_prison.insert(String::from("World!"))?;
+    ///     string_prison.visit_ref(key_1, |second_string| {
+    ///         let hello_world = format!("{}{}", first_string, second_string);
+    ///         assert_eq!(hello_world, "Hello, World!");
+    ///         Ok(())
+    ///     });
+    ///     Ok(())
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// However, if the [Prison] is at maxumum capacity, attempting to `insert()`
+    /// during while there are active references to any element will cause the operation to fail and a
+    /// [AccessError::InsertAtMaxCapacityWhileAValueIsReferenced] to be returned
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let string_prison: Prison<String> = Prison::with_capacity(1);
+    /// let key_0 = string_prison.insert(String::from("Hello, "))?;
+    /// string_prison.visit_ref(key_0, |first_string| {
+    ///     assert!(string_prison.insert(String::from("World!")).is_err());
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn insert(&self, value: T) -> Result<CellKey, AccessError> {
+        self._check_unsealed()?;
+        let value = self._encode(value);
+        let internal = internal!(self);
+        if internal.next_free == IdxD::INVALID {
             if internal.vec.capacity() <= internal.vec.len() {
-                if internal.access_count > 0 {
+                if internal.access_count.count > 0 {
                     return Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
                 }
                 if internal.vec.capacity() == IdxD::MAX_CAP {
@@ -251,21 +1012,29 @@ impl<T> Prison<T> {
             internal
                 .vec
                 .push(PrisonCell::new_cell(value, internal.generation));
-            return Ok(CellKey {
+            #[cfg(feature = "lru")]
+            internal.touch(internal.vec.len() - 1);
+            #[cfg(feature = "uuid")]
+            internal.assign_uuid(internal.vec.len() - 1);
+            return Ok(self._write_key(CellKey {
                 idx: internal.vec.len() - 1,
                 gen: internal.generation,
-            });
+            }));
         }
         let new_idx = internal.next_free;
+        let has_placeholder = internal.placeholder().is_some();
         match &mut internal.vec[new_idx] {
             free if free.is_free() => {
-                internal.free_count -= 1;
-                internal.next_free = free.refs_or_next;
-                free.make_cell_unchecked(value, internal.generation);
-                Ok(CellKey {
+                self._unlink_free(new_idx)?;
+                free.make_cell_unchecked(value, internal.generation, has_placeholder);
+                #[cfg(feature = "lru")]
+                internal.touch(new_idx);
+                #[cfg(feature = "uuid")]
+                internal.assign_uuid(new_idx);
+                Ok(self._write_key(CellKey {
                     idx: new_idx,
                     gen: internal.generation,
-                })
+                }))
             }
             _ => major_malfunction!( //COV_IGNORE
                 "`Prison` had a recorded `next_free` index ({}) that WAS NOT FREE", //COV_IGNORE
@@ -315,165 +1084,702 @@ impl<T> Prison<T> {
     /// ```
     #[inline(always)]
     pub fn insert_at(&self, idx: usize, value: T) -> Result<CellKey, AccessError> {
-        let internal: &mut PrisonInternal<T> = internal!(self);
+        self._check_unsealed()?;
+        let idx = self._idx(idx)?;
+        let value = self._encode(value);
+        let internal: &mut PrisonInternal<T, S> = internal!(self);
         if idx >= internal.vec.len() {
             return Err(AccessError::IndexOutOfRange(idx));
         }
+        let has_placeholder = internal.placeholder().is_some();
         match &mut internal.vec[idx] {
             free if free.is_free() => {
-                let prev = IdxD::val(free.d_gen_or_prev);
-                if prev != IdxD::INVALID {
-                    match &mut internal!(self).vec[prev] {
-                        prev_free if prev_free.is_free() => prev_free.refs_or_next = free.refs_or_next,
-                        _ => major_malfunction!("a `Free` index ({}) had a `prev_free` that pointed to an index ({}) that WAS NOT FREE", idx, prev) //COV_IGNORE
-                    }
-                } else if internal.next_free == idx {
-                    internal.next_free = free.refs_or_next;
-                } else {
-                    major_malfunction!("a `Free` index ({}) had a `prev_free` value that indicated `INVALID`, meaning it should have been the top of the `free` stack, but `Prison.next_free` ({}) did not match its index", prev, internal.next_free) //COV_IGNORE
-                }
-                if free.refs_or_next != IdxD::INVALID {
-                    match &mut internal!(self).vec[free.refs_or_next] {
-                        next_free if next_free.is_free() => next_free.d_gen_or_prev = IdxD::new_type_b(prev),
-                        _ => major_malfunction!("a `Free` index ({}) had a `next_free` that pointed to an index ({}) that WAS NOT FREE", idx, free.refs_or_next) //COV_IGNORE
-                    }
-                }
-                internal.free_count -= 1;
-                free.make_cell_unchecked(value, internal.generation);
-                return Ok(CellKey {
+                self._unlink_free(idx)?;
+                free.make_cell_unchecked(value, internal.generation, has_placeholder);
+                #[cfg(feature = "lru")]
+                internal.touch(idx);
+                #[cfg(feature = "uuid")]
+                internal.assign_uuid(idx);
+                return Ok(self._write_key(CellKey {
                     idx,
                     gen: internal.generation,
-                });
+                }));
             }
             _ => return Err(AccessError::IndexIsNotFree(idx)),
         }
     }
 
-    //FN Prison::overwrite()
-    /// Insert or overwrite a value in the [Prison] at the specified index and recieve a
-    /// [CellKey] that can be used to reference it in the future
-    ///
-    /// Similar to [Prison::insert_at()] but does not require the space be marked as free.
+    //FN Prison::insert_at_idx_or_append()
+    /// Insert a value at the specified index if it is free, or push it onto the end of the [Prison]
+    /// if the index is exactly equal to its current length
     ///
-    /// Note: Overwriting a value that isn't marked as free will invalidate any [CellKey]
-    /// that could have been used to reference it and cause a lookup using the old
-    /// key(s) to return an [AccessError::ValueDeleted(idx, gen)]
+    /// This is intended for loading ordered data where each value has a known index: unlike
+    /// [Prison::insert()], appending never fills a free cell somewhere else in the [Prison], and unlike
+    /// [Prison::insert_at()], the index one past the end is accepted and grows the underlying [Vec]
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let string_prison: Prison<String> = Prison::with_capacity(10);
-    /// let key_0 = string_prison.insert(String::from("Hello, "))?;
-    /// let key_1_a = string_prison.insert(String::from("World!"))?;
-    /// // string_prison.remove(key_1)?; // removal not needed
-    /// let key_1_b = string_prison.overwrite(1, String::from("Rust!!"))?;
-    /// string_prison.visit_many_ref(&[key_0, key_1_b], |vals| {
-    ///     let hello_world = format!("{}{}", vals[0], vals[1]);
-    ///     assert_eq!(hello_world, "Hello, Rust!!");
-    ///     Ok(())
-    /// });
-    /// assert!(string_prison.visit_ref(key_1_a, |deleted_val| Ok(())).is_err());
-    /// assert!(string_prison.overwrite(10, String::from("Oops...")).is_err());
+    /// let prison: Prison<&str> = Prison::with_capacity(10);
+    /// let key_0 = prison.insert_at_idx_or_append(0, "zero")?;
+    /// let key_1 = prison.insert_at_idx_or_append(1, "one")?;
+    /// prison.remove(key_0)?;
+    /// let key_2 = prison.insert_at_idx_or_append(2, "two")?;
+    /// assert_eq!(key_2.idx(), 2);
+    /// let key_0 = prison.insert_at_idx_or_append(0, "zero again")?;
+    /// assert_eq!(key_0.idx(), 0);
+    /// assert!(prison.insert_at_idx_or_append(1, "taken").is_err());
+    /// assert!(prison.insert_at_idx_or_append(5, "too far").is_err());
     /// # Ok(())
     /// # }
-    #[inline(always)]
-    pub fn overwrite(&self, idx: usize, value: T) -> Result<CellKey, AccessError> {
-        let internal: &mut PrisonInternal<T> = internal!(self);
-        if idx >= internal.vec.len() {
-            return Err(AccessError::IndexOutOfRange(idx));
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::IndexIsNotFree(idx)] if the index is within range but holds a value
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is greater than the current length
+    /// - [AccessError::InsertAtMaxCapacityWhileAValueIsReferenced] if appending must grow past capacity while any value is referenced
+    /// - [AccessError::MaximumCapacityReached] if appending would exceed the maximum capacity
+    pub fn insert_at_idx_or_append(&self, idx: usize, value: T) -> Result<CellKey, AccessError> {
+        self._check_unsealed()?;
+        let raw_idx = self._idx(idx)?;
+        let internal: &mut PrisonInternal<T, S> = internal!(self);
+        if raw_idx < internal.vec.len() {
+            return self.insert_at(idx, value);
         }
-        match &mut internal.vec[idx] {
-            cell if cell.is_cell() => {
-                if cell.refs_or_next > 0 {
-                    return Err(AccessError::OverwriteWhileValueReferenced(idx));
-                }
-                let cell_gen = IdxD::val(cell.d_gen_or_prev);
-                if cell_gen >= internal.generation {
-                    if cell_gen == IdxD::MAX_GEN {
-                        return Err(AccessError::MaxValueForGenerationReached);
-                    }
-                    internal.generation = cell_gen + 1;
-                }
-                cell.overwrite_cell_unchecked(value, internal.generation);
-                return Ok(CellKey {
-                    idx,
-                    gen: internal.generation,
-                });
+        if raw_idx > internal.vec.len() {
+            return Err(AccessError::IndexOutOfRange(raw_idx));
+        }
+        let value = self._encode(value);
+        if internal.vec.capacity() <= internal.vec.len() {
+            if internal.access_count.count > 0 {
+                return Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
             }
-            free => {
-                let prev = IdxD::val(free.d_gen_or_prev);
-                if prev != IdxD::INVALID {
-                    match &mut internal!(self).vec[prev] {
-                        prev_free if prev_free.is_free() => prev_free.refs_or_next = free.refs_or_next,
-                        _ => major_malfunction!("a `Free` index ({}) had a `prev_free` that pointed to an index ({}) that WAS NOT FREE", idx, prev) //COV_IGNORE
-                    }
-                } else if internal.next_free == idx {
-                    internal.next_free = free.refs_or_next;
-                } else {
-                    major_malfunction!("a `free` index ({}) had a `prev_free` value that indicated `INVALID`, meaning it should have been the top of the `free` stack, but `Prison.next_free` ({}) did not match its index", prev, internal.next_free) //COV_IGNORE
-                }
-                if free.refs_or_next != IdxD::INVALID {
-                    match &mut internal!(self).vec[free.refs_or_next] {
-                        next_free if next_free.is_free() => next_free.d_gen_or_prev = IdxD::new_type_b(prev),
-                        _ => major_malfunction!("a `Free` index ({}) had a `next_free` that pointed to an index ({}) that WAS NOT FREE", idx, free.refs_or_next) //COV_IGNORE
-                    }
-                }
-                internal.free_count -= 1;
-                free.make_cell_unchecked(value, internal.generation);
-                return Ok(CellKey {
-                    idx,
-                    gen: internal.generation,
-                });
+            if internal.vec.capacity() == IdxD::MAX_CAP {
+                return Err(AccessError::MaximumCapacityReached);
             }
         }
+        internal
+            .vec
+            .push(PrisonCell::new_cell(value, internal.generation));
+        #[cfg(feature = "lru")]
+        internal.touch(raw_idx);
+        #[cfg(feature = "uuid")]
+        internal.assign_uuid(raw_idx);
+        return Ok(self._write_key(CellKey {
+            idx: raw_idx,
+            gen: internal.generation,
+        }));
     }
 
-    //FN Prison::remove()
-    /// Remove and return the element indexed by the provided [CellKey]
+    //FN Prison::insert_many()
+    /// Insert every value from an iterator into the [Prison], returning their [CellKey]'s in the same order
     ///
-    /// As long as the element doesn't have an active reference you can `.remove()` it
+    /// Free cells are filled first, then the remaining values are pushed onto the end of the underlying [Vec]
+    /// after growing it at most once, so inserting thousands of values costs a single capacity check and
+    /// reallocation. Because all capacity is secured before the first value is inserted, either every value
+    /// is inserted or none are
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let string_prison: Prison<String> = Prison::with_capacity(15);
-    /// let key_0 = string_prison.insert(String::from("Hello, "))?;
-    /// let key_1 = string_prison.insert(String::from("World!"))?;
-    /// let mut take_world = String::new();
-    /// string_prison.visit_ref(key_0, |hello| {
-    ///     take_world = string_prison.remove(key_1)?;
+    /// let prison: Prison<u32> = Prison::with_capacity(3);
+    /// let key_0 = prison.insert(0)?;
+    /// let key_1 = prison.insert(1)?;
+    /// prison.remove(key_0)?;
+    /// let keys = prison.insert_many(10..15)?;
+    /// assert_eq!(keys.len(), 5);
+    /// assert_eq!(keys[0].idx(), 0);
+    /// assert_eq!(prison.clone_many_vals(&keys)?, vec![10, 11, 12, 13, 14]);
+    /// prison.visit_ref(key_1, |_| {
+    ///     assert_eq!(prison.insert_many(0..100), Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced));
     ///     Ok(())
     /// })?;
-    /// assert_eq!(take_world, "World!");
+    /// assert_eq!(prison.num_used(), 6);
     /// # Ok(())
     /// # }
     /// ```
-    /// However, if the element *does* have an active reference, either from `visit()` or `guard()`,
-    /// `remove()` will return an [AccessError::RemoveWhileValueReferenced(idx)] with the index in question
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::InsertAtMaxCapacityWhileAValueIsReferenced] if the [Vec] must grow while any value is referenced
+    /// - [AccessError::MaximumCapacityReached] if the new capacity would exceed the maximum capacity
+    pub fn insert_many<I>(&self, values: I) -> Result<Vec<CellKey>, AccessError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self._check_unsealed()?;
+        let values: Vec<T> = values.into_iter().collect();
+        let internal = internal!(self);
+        let appended = values.len().saturating_sub(internal.free_count - internal.reserved_free());
+        self._reserve_for_insert(appended)?;
+        let mut keys = Vec::with_capacity(values.len());
+        for value in values {
+            match self.insert(value) {
+                Ok(key) => keys.push(key),
+                Err(acc_err) => return self._undo_inserts(&keys, acc_err),
+            }
+        }
+        return Ok(keys);
+    }
+
+    //FN Prison::insert_many_at()
+    /// Insert every `(index, value)` pair from an iterator into the [Prison], returning their [CellKey]'s in the same order
+    ///
+    /// Each pair follows the rules of [Prison::insert_at_idx_or_append()], so this is suited to loading ordered data
+    /// where each value has a known index. The underlying [Vec] grows at most once, for all appended values together.
+    /// If any pair cannot be inserted, every value inserted by this call is removed again before the error is returned
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
-    /// # fn main() -> Result<(), AccessError>  {
-    /// let string_prison: Prison<String> = Prison::with_capacity(15);
-    /// let key_0 = string_prison.insert(String::from("Everything"))?;
-    /// string_prison.visit_ref(key_0, |everything| {
-    ///     assert!(string_prison.remove(key_0).is_err());
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = Prison::new();
+    /// let keys = prison.insert_many_at([(0, "zero"), (1, "one"), (2, "two")])?;
+    /// prison.remove(keys[1])?;
+    /// assert_eq!(prison.insert_many_at([(1, "one again"), (2, "taken")]), Err(AccessError::IndexIsNotFree(2)));
+    /// assert_eq!(prison.num_used(), 2);
+    /// let keys = prison.insert_many_at([(1, "one again"), (3, "three")])?;
+    /// assert_eq!(keys[1].idx(), 3);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - Any error returned by [Prison::insert_at_idx_or_append()]
+    pub fn insert_many_at<I>(&self, values: I) -> Result<Vec<CellKey>, AccessError>
+    where
+        I: IntoIterator<Item = (usize, T)>,
+    {
+        self._check_unsealed()?;
+        let values: Vec<(usize, T)> = values.into_iter().collect();
+        let len = internal!(self).vec.len();
+        let mut appended = 0;
+        for (idx, _) in values.iter() {
+            if self._idx(*idx)? >= len {
+                appended += 1;
+            }
+        }
+        self._reserve_for_insert(appended)?;
+        let mut keys = Vec::with_capacity(values.len());
+        for (idx, value) in values {
+            match self.insert_at_idx_or_append(idx, value) {
+                Ok(key) => keys.push(key),
+                Err(acc_err) => return self._undo_inserts(&keys, acc_err),
+            }
+        }
+        return Ok(keys);
+    }
+
+    //FN Prison::insert_hidden()
+    /// Insert a value into the [Prison] that stays hidden until [Prison::publish()] is called with its [CellKey]
+    ///
+    /// The value is live and its [CellKey] is stable, but every `visit()`, `guard()`, clone, or other access
+    /// to it fails with [AccessError::ValueHidden(idx)], and bulk operations skip it as if it were referenced.
+    /// This lets a value be built in several steps (for example load a mesh, then its textures) without other
+    /// systems ever observing it half-built: the owner of the key keeps working on it with
+    /// [Prison::visit_hidden_mut()] and publishes it once it is complete. A hidden value can still be
+    /// removed with [Prison::remove()] to abandon it
+    ///
+    /// Follows the same rules as [Prison::insert()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<Vec<&str>> = Prison::new();
+    /// let key_0 = prison.insert_hidden(vec!["mesh"])?;
+    /// assert_eq!(prison.clone_val(key_0), Err(AccessError::ValueHidden(0)));
+    /// prison.visit_hidden_mut(key_0, |model| {
+    ///     model.push("textures");
     ///     Ok(())
     /// })?;
+    /// assert!(prison.is_hidden(key_0));
+    /// prison.publish(key_0)?;
+    /// assert_eq!(prison.clone_val(key_0)?, vec!["mesh", "textures"]);
     /// # Ok(())
     /// # }
     /// ```
-    #[inline(always)]
-    pub fn remove(&self, key: CellKey) -> Result<T, AccessError> {
+    /// ## Errors
+    /// - Any error returned by [Prison::insert()]
+    pub fn insert_hidden(&self, value: T) -> Result<CellKey, AccessError> {
+        let key = self.insert(value)?;
+        internal!(self).vec[key.idx].refs_or_next = Refs::HIDDEN;
+        return Ok(key);
+    }
+
+    //FN Prison::publish()
+    /// Make a value inserted with [Prison::insert_hidden()] accessible like any other value
+    ///
+    /// Publishing a value that is not hidden does nothing
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation does not match
+    pub fn publish(&self, key: CellKey) -> Result<(), AccessError> {
+        self._check_unsealed()?;
+        self._check_key(key)?;
         let internal = internal!(self);
-        if key.idx >= internal.vec.len() {
-            return Err(AccessError::IndexOutOfRange(key.idx));
+        let cell = &mut internal.vec[key.idx];
+        if cell.refs_or_next == Refs::HIDDEN {
+            cell.refs_or_next = 0;
+            internal.access_count.wake_all();
         }
+        return Ok(());
+    }
+
+    //FN Prison::is_hidden()
+    /// Return `true` if the [CellKey] refers to a valid value inserted with [Prison::insert_hidden()]
+    /// that has not been published yet
+    pub fn is_hidden(&self, key: CellKey) -> bool {
+        return self._check_key(key).is_ok() && internal!(self).vec[key.idx].refs_or_next == Refs::HIDDEN;
+    }
+
+    //FN Prison::visit_hidden_mut()
+    /// Visit a value whether or not it is hidden, obtaining a mutable reference to it
+    ///
+    /// This is how the owner of a value inserted with [Prison::insert_hidden()] finishes building it: while
+    /// the closure runs the value is mutably referenced, and afterwards it is hidden again. For a value that
+    /// is not hidden this behaves exactly like [Prison::visit_mut()], so the same code keeps working after
+    /// the value is published. See [Prison::insert_hidden()] for an example
+    /// ## Errors
+    /// - Any error that [Prison::visit_mut()] could return
+    /// - Any error returned by the closure
+    pub fn visit_hidden_mut<R, F>(&self, key: CellKey, mut operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&mut T) -> Result<R, AccessError>,
+    {
+        if !self.is_hidden(key) {
+            return self.visit_mut(key, operation);
+        }
+        self._check_depth()?;
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        let cell = &mut internal.vec[key.idx];
+        let accesses = &mut internal.access_count;
+        cell.refs_or_next = Refs::MUT;
+        accesses.count += 1;
+        let _release = RefRelease::new(self, &mut cell.refs_or_next, |refs| {
+            **refs = Refs::HIDDEN;
+            accesses.count -= 1;
+            accesses.wake_all();
+        });
+        return self._visit(|| self._decoded_mut(unsafe { cell.val.assume_init_mut() }, |val| operation(val)));
+    }
+
+    //FN Prison::insert_typed()
+    /// Insert a value into the [Prison] exactly like [Prison::insert()], but receive a [TypedKey<T>] that can only
+    /// be used with a [Prison] holding the same type of value
+    ///
+    /// See [TypedKey] for an example
+    /// ## Errors
+    /// Same as [Prison::insert()]
+    pub fn insert_typed(&self, value: T) -> Result<TypedKey<T>, AccessError> {
+        return Ok(TypedKey::from_key(self.insert(value)?));
+    }
+
+    //FN Prison::visit_mut_typed()
+    /// Exactly like [Prison::visit_mut()], but accepting a [TypedKey<T>] instead of an untyped [CellKey]
+    /// ## Errors
+    /// Same as [Prison::visit_mut()]
+    pub fn visit_mut_typed<R, F>(&self, key: TypedKey<T>, operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&mut T) -> Result<R, AccessError>,
+    {
+        return self.visit_mut(key.key(), operation);
+    }
+
+    //FN Prison::visit_ref_typed()
+    /// Exactly like [Prison::visit_ref()], but accepting a [TypedKey<T>] instead of an untyped [CellKey]
+    /// ## Errors
+    /// Same as [Prison::visit_ref()]
+    pub fn visit_ref_typed<R, F>(&self, key: TypedKey<T>, operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&T) -> Result<R, AccessError>,
+    {
+        return self.visit_ref(key.key(), operation);
+    }
+
+    //FN Prison::remove_typed()
+    /// Exactly like [Prison::remove()], but accepting a [TypedKey<T>] instead of an untyped [CellKey]
+    /// ## Errors
+    /// Same as [Prison::remove()]
+    pub fn remove_typed(&self, key: TypedKey<T>) -> Result<T, AccessError> {
+        return self.remove(key.key());
+    }
+
+    //FN Prison::insert_keyed()
+    /// Insert a value that stores its own [CellKey] into the [Prison], writing the new [CellKey] into the
+    /// value with [PrisonValue::set_key()] and returning it as the typed [PrisonValue::Key]
+    ///
+    /// Follows the same rules as [Prison::insert()]. The key is written straight into the new cell before
+    /// the [CellKey] is returned, so once the value is inserted nothing can fail. See [PrisonValue] for an example
+    /// ## Errors
+    /// - Any error returned by [Prison::insert()]
+    pub fn insert_keyed(&self, value: T) -> Result<T::Key, AccessError>
+    where
+        T: PrisonValue,
+    {
+        let key = self.insert(value)?;
+        self._write_key_with(key, <T as PrisonValue>::set_key);
+        return Ok(T::Key::from(key));
+    }
+
+    //FN Prison::get_or_insert_with()
+    /// Return a [PrisonValueMut] guarding the value at the [CellKey] if it is still valid, otherwise insert
+    /// the value returned by `make_value` and return a [PrisonValueMut] guarding the new value instead,
+    /// along with the [CellKey] of whichever value was guarded
+    ///
+    /// If the key's index is free the new value is inserted at that same index (like [Prison::insert_at()]),
+    /// otherwise it goes wherever [Prison::insert()] would put it. Either way the new value gets the current
+    /// generation, so the returned [CellKey] only equals the one passed in if the old value was still valid.
+    /// `make_value` is only called when a value is inserted
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<String> = Prison::new();
+    /// let key_0 = prison.insert(String::from("cached"))?;
+    /// let (key, grd) = prison.get_or_insert_with(key_0, || String::from("fresh"))?;
+    /// assert_eq!((key, grd.as_str()), (key_0, "cached"));
+    /// drop(grd);
+    /// prison.remove(key_0)?;
+    /// let (key, mut grd) = prison.get_or_insert_with(key_0, || String::from("fresh"))?;
+    /// grd.push('!');
+    /// assert_eq!((key, grd.as_str()), (CellKey::from_raw_parts(0, 1), "fresh!"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - Any error returned by [Prison::guard_mut()] if the key is valid
+    /// - Any error returned by [Prison::insert()] or [Prison::insert_at()] if a new value is inserted
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn get_or_insert_with<'a, F>(
+        &'a self,
+        key: CellKey,
+        make_value: F,
+    ) -> Result<(CellKey, PrisonValueMut<'a, T, S>), AccessError>
+    where
+        F: FnOnce() -> T,
+    {
+        let key = self._get_or_insert_key(key, make_value)?;
+        let grd = self.guard_mut(key)?;
+        return Ok((key, grd));
+    }
+
+    //FN Prison::get_or_insert_with_key()
+    /// Exactly like [Prison::get_or_insert_with()], but for values that store their own [CellKey]: it takes and returns
+    /// the typed [PrisonValue::Key], and writes the [CellKey] of a newly inserted value into it with [PrisonValue::set_key()]
+    /// before guarding it, like [Prison::insert_keyed()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{prison_value, AccessError, CellKey, PrisonValue, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// struct Chunk {
+    ///     key: CellKey,
+    ///     loaded_from: &'static str,
+    /// }
+    /// prison_value!(Chunk { key } => ChunkKey);
+    /// let chunks: Prison<Chunk> = Prison::new();
+    /// let unset = CellKey::from_raw_parts(0, 0);
+    /// let key_0 = chunks.insert_keyed(Chunk { key: unset, loaded_from: "disk" })?;
+    /// chunks.remove(key_0.into())?;
+    /// let (key, grd) = chunks.get_or_insert_with_key(key_0, || Chunk { key: unset, loaded_from: "generator" })?;
+    /// assert_eq!(key, ChunkKey(CellKey::from_raw_parts(0, 1)));
+    /// assert_eq!((grd.typed_key(), grd.loaded_from), (key, "generator"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - Any error returned by [Prison::get_or_insert_with()]
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn get_or_insert_with_key<'a, F>(
+        &'a self,
+        key: T::Key,
+        make_value: F,
+    ) -> Result<(T::Key, PrisonValueMut<'a, T, S>), AccessError>
+    where
+        T: PrisonValue,
+        F: FnOnce() -> T,
+    {
+        let key: CellKey = key.into();
+        let new_key = self._get_or_insert_key(key, make_value)?;
+        if new_key != key {
+            self._write_key_with(new_key, <T as PrisonValue>::set_key);
+        }
+        let grd = self.guard_mut(new_key)?;
+        return Ok((T::Key::from(new_key), grd));
+    }
+
+    //FN Prison::reserve_index_range()
+    /// Reserve a range of indexes so that they are never filled by [Prison::insert()]
+    ///
+    /// Empty reserved indexes can only be filled with [Prison::insert_at()] or [Prison::overwrite()],
+    /// and when a value at a reserved index is removed the index stays reserved and empty.
+    /// This lets an authority (for example a game server) assign deterministic indexes to shared values
+    /// while local-only values use the rest of the [Prison]. Values already in the range are left alone,
+    /// and the [Prison] is extended with empty cells if the range reaches past its end
+    ///
+    /// Reserving an index that is already reserved does nothing. An unbounded range end reserves up to
+    /// the current length of the [Prison]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = Prison::new();
+    /// prison.reserve_index_range(0..100)?;
+    /// let key_local = prison.insert("local")?;
+    /// assert_eq!(key_local.idx(), 100);
+    /// let key_shared = prison.insert_at(42, "from server")?;
+    /// prison.remove(key_shared)?;
+    /// assert_eq!(prison.insert("local 2")?.idx(), 101);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::InsertAtMaxCapacityWhileAValueIsReferenced] if the [Prison] must grow past its capacity while any value is referenced
+    /// - [AccessError::MaximumCapacityReached] if the range reaches past the maximum capacity
+    pub fn reserve_index_range<R>(&self, range: R) -> Result<(), AccessError>
+    where
+        R: RangeBounds<usize>,
+    {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        let len = internal.vec.len();
+        let (start, end) = extract_true_start_end(range, len);
+        if start >= end {
+            return Ok(());
+        }
+        if end > len {
+            if end > IdxD::MAX_CAP {
+                return Err(AccessError::MaximumCapacityReached);
+            }
+            if end > internal.vec.capacity() && internal.access_count.count > 0 {
+                return Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
+            }
+        }
+        for idx in start..end.min(len) {
+            if internal.vec[idx].is_free() && !internal.is_reserved(idx) {
+                self._unlink_free(idx)?;
+                let cell = &mut internal.vec[idx];
+                cell.refs_or_next = IdxD::INVALID;
+                cell.d_gen_or_prev = IdxD::new_type_b(IdxD::INVALID);
+                internal.free_count += 1;
+                internal.extras().reserved_free += 1;
+            }
+        }
+        for idx in len.max(start)..end {
+            while internal.vec.len() < idx {
+                internal.vec.push(PrisonCell::new_free(IdxD::INVALID, IdxD::INVALID, internal.placeholder()));
+                let new_idx = internal.vec.len() - 1;
+                self._link_free(new_idx)?;
+            }
+            internal.vec.push(PrisonCell::new_free(IdxD::INVALID, IdxD::INVALID, internal.placeholder()));
+            internal.free_count += 1;
+            internal.extras().reserved_free += 1;
+        }
+        let (mut new_start, mut new_end) = (start, end);
+        let extras = internal.extras();
+        extras.reserved.retain(|(res_start, res_end)| {
+            if *res_end < new_start || *res_start > new_end {
+                return true;
+            }
+            new_start = new_start.min(*res_start);
+            new_end = new_end.max(*res_end);
+            false
+        });
+        let pos = extras.reserved.partition_point(|(res_start, _)| *res_start < new_start);
+        extras.reserved.insert(pos, (new_start, new_end));
+        return Ok(());
+    }
+
+    //FN Prison::release_index_range()
+    /// Release a range of reserved indexes, returning any that are empty to normal use by [Prison::insert()]
+    ///
+    /// See [Prison::reserve_index_range()]. Indexes in the range that were not reserved are ignored
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.reserve_index_range(0..2)?;
+    /// assert!(prison.is_index_reserved(1));
+    /// prison.release_index_range(1..)?;
+    /// assert!(!prison.is_index_reserved(1));
+    /// assert_eq!(prison.insert(10)?.idx(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::MAJOR_MALFUNCTION(msg)] if the free list is found to be corrupted
+    pub fn release_index_range<R>(&self, range: R) -> Result<(), AccessError>
+    where
+        R: RangeBounds<usize>,
+    {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        let (start, end) = extract_true_start_end(range, internal.vec.len());
+        if start >= end {
+            return Ok(());
+        }
+        let mut remaining = Vec::with_capacity(internal.reserved().len() + 1);
+        for (res_start, res_end) in mem_take(&mut internal.extras().reserved) {
+            if res_start < start {
+                remaining.push((res_start, res_end.min(start)));
+            }
+            if res_end > end {
+                remaining.push((res_start.max(end), res_end));
+            }
+            for idx in res_start.max(start)..res_end.min(end).min(internal.vec.len()) {
+                if internal.vec[idx].is_free() {
+                    internal.free_count -= 1;
+                    internal.extras().reserved_free -= 1;
+                    self._link_free(idx)?;
+                }
+            }
+        }
+        internal.extras().reserved = remaining;
+        return Ok(());
+    }
+
+    //FN Prison::is_index_reserved()
+    /// Return `true` if the index is reserved, see [Prison::reserve_index_range()]
+    pub fn is_index_reserved(&self, idx: usize) -> bool {
+        return internal!(self).is_reserved(idx);
+    }
+
+    //FN Prison::overwrite()
+    /// Insert or overwrite a value in the [Prison] at the specified index and recieve a
+    /// [CellKey] that can be used to reference it in the future
+    ///
+    /// Similar to [Prison::insert_at()] but does not require the space be marked as free.
+    ///
+    /// Note: Overwriting a value that isn't marked as free will invalidate any [CellKey]
+    /// that could have been used to reference it and cause a lookup using the old
+    /// key(s) to return an [AccessError::ValueDeleted(idx, gen)]. Values pinned with
+    /// [Prison::pin()] cannot be overwritten and return [AccessError::ValuePinned(idx)]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let string_prison: Prison<String> = Prison::with_capacity(10);
+    /// let key_0 = string_prison.insert(String::from("Hello, "))?;
+    /// let key_1_a = string_prison.insert(String::from("World!"))?;
+    /// // string_prison.remove(key_1)?; // removal not needed
+    /// let key_1_b = string_prison.overwrite(1, String::from("Rust!!"))?;
+    /// string_prison.visit_many_ref(&[key_0, key_1_b], |vals| {
+    ///     let hello_world = format!("{}{}", vals[0], vals[1]);
+    ///     assert_eq!(hello_world, "Hello, Rust!!");
+    ///     Ok(())
+    /// });
+    /// assert!(string_prison.visit_ref(key_1_a, |deleted_val| Ok(())).is_err());
+    /// assert!(string_prison.overwrite(10, String::from("Oops...")).is_err());
+    /// # Ok(())
+    /// # }
+    #[inline(always)]
+    pub fn overwrite(&self, idx: usize, value: T) -> Result<CellKey, AccessError> {
+        self._check_unsealed()?;
+        let idx = self._idx(idx)?;
+        let value = self._encode(value);
+        let internal: &mut PrisonInternal<T, S> = internal!(self);
+        if idx >= internal.vec.len() {
+            return Err(AccessError::IndexOutOfRange(idx));
+        }
+        let has_placeholder = internal.placeholder().is_some();
+        match &mut internal.vec[idx] {
+            cell if cell.is_cell() => {
+                if cell.refs_or_next > 0 {
+                    return Err(AccessError::OverwriteWhileValueReferenced(idx));
+                }
+                if internal!(self).is_pinned(idx) {
+                    return Err(AccessError::ValuePinned(idx));
+                }
+                let cell_gen = IdxD::val(cell.d_gen_or_prev);
+                if cell_gen >= internal.generation {
+                    if cell_gen == IdxD::MAX_GEN {
+                        return Err(AccessError::MaxValueForGenerationReached);
+                    }
+                    internal.generation = cell_gen + 1;
+                }
+                cell.overwrite_cell_unchecked(value, internal.generation);
+                #[cfg(feature = "lru")]
+                internal.touch(idx);
+                #[cfg(feature = "uuid")]
+                {
+                    internal.release_uuid(idx);
+                    internal.assign_uuid(idx);
+                }
+                return Ok(self._write_key(CellKey {
+                    idx,
+                    gen: internal.generation,
+                }));
+            }
+            free => {
+                self._unlink_free(idx)?;
+                free.make_cell_unchecked(value, internal.generation, has_placeholder);
+                #[cfg(feature = "lru")]
+                internal.touch(idx);
+                #[cfg(feature = "uuid")]
+                internal.assign_uuid(idx);
+                return Ok(self._write_key(CellKey {
+                    idx,
+                    gen: internal.generation,
+                }));
+            }
+        }
+    }
+
+    //FN Prison::remove()
+    /// Remove and return the element indexed by the provided [CellKey]
+    ///
+    /// As long as the element doesn't have an active reference you can `.remove()` it
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let string_prison: Prison<String> = Prison::with_capacity(15);
+    /// let key_0 = string_prison.insert(String::from("Hello, "))?;
+    /// let key_1 = string_prison.insert(String::from("World!"))?;
+    /// let mut take_world = String::new();
+    /// string_prison.visit_ref(key_0, |hello| {
+    ///     take_world = string_prison.remove(key_1)?;
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(take_world, "World!");
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// However, if the element *does* have an active reference, either from `visit()` or `guard()`,
+    /// `remove()` will return an [AccessError::RemoveWhileValueReferenced(idx)] with the index in question.
+    /// Likewise, values pinned with [Prison::pin()] return [AccessError::ValuePinned(idx)]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError>  {
+    /// let string_prison: Prison<String> = Prison::with_capacity(15);
+    /// let key_0 = string_prison.insert(String::from("Everything"))?;
+    /// string_prison.visit_ref(key_0, |everything| {
+    ///     assert!(string_prison.remove(key_0).is_err());
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[inline(always)]
+    pub fn remove(&self, key: CellKey) -> Result<T, AccessError> {
+        self._check_unsealed()?;
+        #[cfg(feature = "offload")]
+        internal!(self).reload(key.idx)?;
+        let internal = internal!(self);
+        if key.idx >= internal.vec.len() {
+            return Err(AccessError::IndexOutOfRange(key.idx));
+        }
+        let reserved = internal.is_reserved(key.idx);
+        let link_next = if reserved { IdxD::INVALID } else { internal.next_free };
+        let placeholder = internal.placeholder();
         let removed_val = match &mut internal.vec[key.idx] {
             cell if cell.is_cell_and_gen_match(key.gen) => {
-                if cell.refs_or_next > 0 {
+                if cell.refs_or_next > 0 && cell.refs_or_next != Refs::HIDDEN {
                     return Err(AccessError::RemoveWhileValueReferenced(key.idx));
                 }
+                if internal!(self).is_pinned(key.idx) {
+                    return Err(AccessError::ValuePinned(key.idx));
+                }
                 let cell_gen = IdxD::val(cell.d_gen_or_prev);
                 if cell_gen >= internal.generation {
                     if cell_gen == IdxD::MAX_GEN {
@@ -481,10 +1787,17 @@ impl<T> Prison<T> {
                     }
                     internal.generation = cell_gen + 1;
                 }
-                cell.make_free_unchecked(internal.next_free, IdxD::INVALID)
+                cell.make_free_unchecked(link_next, IdxD::INVALID, placeholder)
             }
             _ => return Err(AccessError::ValueDeleted(key.idx, key.gen)),
         };
+        #[cfg(feature = "uuid")]
+        internal.release_uuid(key.idx);
+        if reserved {
+            internal.free_count += 1;
+            internal.extras().reserved_free += 1;
+            return Ok(self._decode(removed_val));
+        }
         if internal.next_free != IdxD::INVALID {
             match &mut internal.vec[internal.next_free] {
                 free if free.is_free() => {
@@ -498,7 +1811,76 @@ impl<T> Prison<T> {
         }
         internal.next_free = key.idx;
         internal.free_count += 1;
-        return Ok(removed_val);
+        return Ok(self._decode(removed_val));
+    }
+
+    //FN Prison::remove_when_free()
+    /// Remove the value at the [CellKey] now if it is not referenced, or as soon as its last reference is released otherwise,
+    /// returning a [RemovalTicket] that can be used to check on or cancel a removal that is still pending
+    ///
+    /// A pending removal runs automatically when the guard or `visit()` closure holding the last reference to the value
+    /// releases it, exactly as if [Prison::remove()] was called at that moment, and the removed value is dropped.
+    /// Registering a value that already has a pending removal returns the same ticket. A pending removal is discarded
+    /// without removing anything if the value is removed, moved or invalidated by other means first. If the removal fails
+    /// when the last reference is released (for example because the value was pinned in the meantime), it stays pending
+    /// and is retried on every later release, and when the value is unpinned. Releasing a reference only checks whether
+    /// any removal is pending
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let grd_1 = prison.guard_ref(key_1)?;
+    /// let ticket_0 = prison.remove_when_free(key_0)?;
+    /// let ticket_1 = prison.remove_when_free(key_1)?;
+    /// assert!(!prison.removal_pending(ticket_0));
+    /// assert!(prison.removal_pending(ticket_1));
+    /// assert_eq!(*grd_1, 20);
+    /// drop(grd_1);
+    /// assert!(!prison.removal_pending(ticket_1));
+    /// assert_eq!(prison.num_used(), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or the generation does not match
+    /// - [AccessError::ValuePinned(idx)] if the value is pinned
+    /// - [AccessError::MaxValueForGenerationReached] if the value is not referenced and the generation counter is exhausted
+    pub fn remove_when_free(&self, key: CellKey) -> Result<RemovalTicket, AccessError> {
+        self._check_unsealed()?;
+        self._check_key(key)?;
+        let internal = internal!(self);
+        if internal.is_pinned(key.idx) {
+            return Err(AccessError::ValuePinned(key.idx));
+        }
+        let refs = internal.vec[key.idx].refs_or_next;
+        if refs == 0 || refs == Refs::HIDDEN {
+            self.remove(key)?;
+        } else if !internal.pending_removals().contains(&key) {
+            internal.extras().pending_removals.push(key);
+        }
+        return Ok(RemovalTicket { key });
+    }
+
+    //FN Prison::removal_pending()
+    /// Return `true` if the removal registered by [Prison::remove_when_free()] has not run (or been discarded) yet
+    pub fn removal_pending(&self, ticket: RemovalTicket) -> bool {
+        return internal!(self).pending_removals().contains(&ticket.key) && self._check_key(ticket.key).is_ok();
+    }
+
+    //FN Prison::cancel_removal()
+    /// Cancel a removal registered by [Prison::remove_when_free()] that is still pending, returning `true` if it was pending
+    pub fn cancel_removal(&self, ticket: RemovalTicket) -> bool {
+        let pending = self.removal_pending(ticket);
+        let internal = internal!(self);
+        if let Some(extras) = internal.extras.as_mut() {
+            extras.pending_removals.retain(|key| *key != ticket.key);
+        }
+        return pending;
     }
 
     //FN Prison::remove_idx()
@@ -524,7 +1906,8 @@ impl<T> Prison<T> {
     /// # }
     /// ```
     /// However, if the element *does* have an active reference, either from `visit()` or `guard()`,
-    /// `.remove_idx()` will return an [AccessError::RemoveWhileValueReferenced(idx)] with the index in question
+    /// `.remove_idx()` will return an [AccessError::RemoveWhileValueReferenced(idx)] with the index in question.
+    /// Likewise, values pinned with [Prison::pin()] return [AccessError::ValuePinned(idx)]
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
@@ -540,15 +1923,25 @@ impl<T> Prison<T> {
     /// ```
     #[inline(always)]
     pub fn remove_idx(&self, idx: usize) -> Result<T, AccessError> {
+        self._check_unsealed()?;
+        let idx = self._idx(idx)?;
+        #[cfg(feature = "offload")]
+        internal!(self).reload(idx)?;
         let internal = internal!(self);
         if idx >= internal.vec.len() {
             return Err(AccessError::IndexOutOfRange(idx));
         }
+        let reserved = internal.is_reserved(idx);
+        let link_next = if reserved { IdxD::INVALID } else { internal.next_free };
+        let placeholder = internal.placeholder();
         let removed_val = match &mut internal.vec[idx] {
             cell if cell.is_cell() => {
                 if cell.refs_or_next > 0 {
                     return Err(AccessError::RemoveWhileValueReferenced(idx));
                 }
+                if internal!(self).is_pinned(idx) {
+                    return Err(AccessError::ValuePinned(idx));
+                }
                 let cell_gen = IdxD::val(cell.d_gen_or_prev);
                 if cell_gen >= internal.generation {
                     if cell_gen == IdxD::MAX_GEN {
@@ -556,10 +1949,17 @@ impl<T> Prison<T> {
                     }
                     internal.generation = cell_gen + 1;
                 }
-                cell.make_free_unchecked(internal.next_free, IdxD::INVALID)
+                cell.make_free_unchecked(link_next, IdxD::INVALID, placeholder)
             }
             _ => return Err(AccessError::ValueDeleted(idx, 0)),
         };
+        #[cfg(feature = "uuid")]
+        internal.release_uuid(idx);
+        if reserved {
+            internal.free_count += 1;
+            internal.extras().reserved_free += 1;
+            return Ok(self._decode(removed_val));
+        }
         if internal.next_free != IdxD::INVALID {
             match &mut internal.vec[internal.next_free] {
                 free if free.is_free() => {
@@ -573,7 +1973,7 @@ impl<T> Prison<T> {
         }
         internal.next_free = idx;
         internal.free_count += 1;
-        return Ok(removed_val);
+        return Ok(self._decode(removed_val));
     }
 
     //FN Prison::visit_mut()
@@ -596,6 +1996,22 @@ impl<T> Prison<T> {
     /// # Ok(())
     /// # }
     /// ```
+    /// Whatever value the closure returns is passed back out of `visit_mut()`
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// let key_0 = u32_prison.insert(42)?;
+    /// let old_val = u32_prison.visit_mut(key_0, |mut_ref_42| {
+    ///     let old_val = *mut_ref_42;
+    ///     *mut_ref_42 = 69;
+    ///     Ok(old_val)
+    /// })?;
+    /// assert_eq!(old_val, 42);
+    /// # Ok(())
+    /// # }
+    /// ```
     /// ## Errors
     /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
     /// - [AccessError::ValueStillImmutablyReferenced(idx)] if element has any number of immutable references
@@ -635,14 +2051,11 @@ impl<T> Prison<T> {
     /// # }
     /// ```
     #[inline(always)]
-    pub fn visit_mut<F>(&self, key: CellKey, mut operation: F) -> Result<(), AccessError>
+    pub fn visit_mut<R, F>(&self, key: CellKey, operation: F) -> Result<R, AccessError>
     where
-        F: FnMut(&mut T) -> Result<(), AccessError>,
+        F: FnMut(&mut T) -> Result<R, AccessError>,
     {
-        let (cell, accesses) = self._add_mut_ref(key.idx, key.gen, true)?;
-        let res = operation(unsafe { cell.val.assume_init_mut() });
-        _remove_mut_ref(&mut cell.refs_or_next, accesses);
-        return res;
+        return self.visit_mut_with(key, operation);
     }
 
     //FN Prison::visit_ref()
@@ -668,7 +2081,19 @@ impl<T> Prison<T> {
     /// # Ok(())
     /// # }
     /// ```
-    /// ## Errors
+    /// Whatever value the closure returns is passed back out of `visit_ref()`
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let string_prison: Prison<String> = Prison::new();
+    /// let key_0 = string_prison.insert(String::from("Hello"))?;
+    /// let len = string_prison.visit_ref(key_0, |hello| Ok(hello.len()))?;
+    /// assert_eq!(len, 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
     /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
     /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references already
     /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
@@ -706,14 +2131,11 @@ impl<T> Prison<T> {
     /// # }
     /// ```
     #[inline(always)]
-    pub fn visit_ref<F>(&self, key: CellKey, mut operation: F) -> Result<(), AccessError>
+    pub fn visit_ref<R, F>(&self, key: CellKey, operation: F) -> Result<R, AccessError>
     where
-        F: FnMut(&T) -> Result<(), AccessError>,
+        F: FnMut(&T) -> Result<R, AccessError>,
     {
-        let (cell, accesses) = self._add_imm_ref(key.idx, key.gen, true)?;
-        let res = operation(unsafe { cell.val.assume_init_ref() });
-        _remove_imm_ref(&mut cell.refs_or_next, accesses);
-        return res;
+        return self.visit_ref_with(key, operation);
     }
 
     //FN Prison::visit_mut_idx()
@@ -777,14 +2199,11 @@ impl<T> Prison<T> {
     /// # }
     /// ```
     #[inline(always)]
-    pub fn visit_mut_idx<F>(&self, idx: usize, mut operation: F) -> Result<(), AccessError>
+    pub fn visit_mut_idx<R, F>(&self, idx: usize, operation: F) -> Result<R, AccessError>
     where
-        F: FnMut(&mut T) -> Result<(), AccessError>,
+        F: FnMut(&mut T) -> Result<R, AccessError>,
     {
-        let (cell, accesses) = self._add_mut_ref(idx, 0, false)?;
-        let res = operation(unsafe { cell.val.assume_init_mut() });
-        _remove_mut_ref(&mut cell.refs_or_next, accesses);
-        return res;
+        return self.visit_mut_idx_with(idx, operation);
     }
 
     //FN Prison::visit_ref_idx()
@@ -850,14 +2269,419 @@ impl<T> Prison<T> {
     /// # }
     /// ```
     #[inline(always)]
-    pub fn visit_ref_idx<F>(&self, idx: usize, mut operation: F) -> Result<(), AccessError>
+    pub fn visit_ref_idx<R, F>(&self, idx: usize, operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&T) -> Result<R, AccessError>,
+    {
+        return self.visit_ref_idx_with(idx, operation);
+    }
+
+    //FN Prison::visit_mut_with()
+    /// Identical to [Prison::visit_mut()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// This allows the [Prison] to be used directly with your own application-level error type:
+    /// any [AccessError] produced while obtaining the reference is converted with [From], and errors
+    /// returned by the closure are passed back unchanged
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// #[derive(Debug, PartialEq)]
+    /// enum BankError {
+    ///     Access(AccessError),
+    ///     InsufficientFunds,
+    /// }
+    /// impl From<AccessError> for BankError {
+    ///     fn from(err: AccessError) -> Self {
+    ///         BankError::Access(err)
+    ///     }
+    /// }
+    /// # fn main() -> Result<(), BankError> {
+    /// let accounts: Prison<u32> = Prison::new();
+    /// let key_0 = accounts.insert(100)?;
+    /// let withdraw = |amount: u32| accounts.visit_mut_with(key_0, |balance| {
+    ///     if *balance < amount {
+    ///         return Err(BankError::InsufficientFunds);
+    ///     }
+    ///     *balance -= amount;
+    ///     Ok(*balance)
+    /// });
+    /// assert_eq!(withdraw(60), Ok(40));
+    /// assert_eq!(withdraw(60), Err(BankError::InsufficientFunds));
+    /// accounts.remove(key_0)?;
+    /// assert_eq!(withdraw(10), Err(BankError::Access(AccessError::ValueDeleted(0, 0))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - Any error that [Prison::visit_mut()] could return, converted into `E`
+    /// - Any error returned by the closure
+    #[inline(always)]
+    pub fn visit_mut_with<R, E, F>(&self, key: CellKey, mut operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnMut(&mut T) -> Result<R, E>,
+    {
+        self._check_depth()?;
+        let (cell, accesses) = self._add_mut_ref(key.idx, key.gen, true)?;
+        let _release = RefRelease::new(self, &mut cell.refs_or_next, |refs| _remove_mut_ref(refs, accesses));
+        return self._visit(|| self._decoded_mut(unsafe { cell.val.assume_init_mut() }, |val| operation(val)));
+    }
+
+    //FN Prison::visit_ref_with()
+    /// Identical to [Prison::visit_ref()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()] for details
+    /// ## Errors
+    /// - Any error that [Prison::visit_ref()] could return, converted into `E`
+    /// - Any error returned by the closure
+    #[inline(always)]
+    pub fn visit_ref_with<R, E, F>(&self, key: CellKey, mut operation: F) -> Result<R, E>
     where
-        F: FnMut(&T) -> Result<(), AccessError>,
+        E: From<AccessError>,
+        F: FnMut(&T) -> Result<R, E>,
     {
+        self._check_depth()?;
+        let (cell, accesses) = self._add_imm_ref(key.idx, key.gen, true)?;
+        let _release = RefRelease::new(self, &mut cell.refs_or_next, |refs| _remove_imm_ref(refs, accesses));
+        return self._visit(|| self._decoded_ref(unsafe { cell.val.assume_init_ref() }, |val| operation(val)));
+    }
+
+    //FN Prison::visit_mut_idx_with()
+    /// Identical to [Prison::visit_mut_idx()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()] for details
+    /// ## Errors
+    /// - Any error that [Prison::visit_mut_idx()] could return, converted into `E`
+    /// - Any error returned by the closure
+    #[inline(always)]
+    pub fn visit_mut_idx_with<R, E, F>(&self, idx: usize, mut operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnMut(&mut T) -> Result<R, E>,
+    {
+        self._check_depth()?;
+        let idx = self._idx(idx)?;
+        let (cell, accesses) = self._add_mut_ref(idx, 0, false)?;
+        let _release = RefRelease::new(self, &mut cell.refs_or_next, |refs| _remove_mut_ref(refs, accesses));
+        return self._visit(|| self._decoded_mut(unsafe { cell.val.assume_init_mut() }, |val| operation(val)));
+    }
+
+    //FN Prison::visit_ref_idx_with()
+    /// Identical to [Prison::visit_ref_idx()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()] for details
+    /// ## Errors
+    /// - Any error that [Prison::visit_ref_idx()] could return, converted into `E`
+    /// - Any error returned by the closure
+    #[inline(always)]
+    pub fn visit_ref_idx_with<R, E, F>(&self, idx: usize, mut operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnMut(&T) -> Result<R, E>,
+    {
+        self._check_depth()?;
+        let idx = self._idx(idx)?;
         let (cell, accesses) = self._add_imm_ref(idx, 0, false)?;
-        let res = operation(unsafe { cell.val.assume_init_ref() });
-        _remove_imm_ref(&mut cell.refs_or_next, accesses);
-        return res;
+        let _release = RefRelease::new(self, &mut cell.refs_or_next, |refs| _remove_imm_ref(refs, accesses));
+        return self._visit(|| self._decoded_ref(unsafe { cell.val.assume_init_ref() }, |val| operation(val)));
+    }
+
+    //FN Prison::handle()
+    /// Return a [ScriptHandle] for the value indexed by the [CellKey], suitable for storing in scripting VMs
+    /// or other foreign memory
+    ///
+    /// See [ScriptHandle] for details
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range or does not fit in a [u32]
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation does not match
+    pub fn handle(&self, key: CellKey) -> Result<ScriptHandle, AccessError> {
+        let internal = internal!(self);
+        match internal.vec.get(key.idx) {
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) => {
+                let idx = u32::try_from(key.idx).map_err(|_| AccessError::IndexOutOfRange(key.idx))?;
+                return Ok(ScriptHandle::from_raw_parts(idx, internal.id(), key.gen as u64));
+            }
+            Some(_) => return Err(AccessError::ValueDeleted(key.idx, key.gen)),
+            None => return Err(AccessError::IndexOutOfRange(key.idx)),
+        }
+    }
+
+    //FN Prison::handle_to_key()
+    /// Convert a [ScriptHandle] created by this [Prison] back into a [CellKey]
+    ///
+    /// The returned key is not checked for validity, it is exactly as valid as the [CellKey] the handle was created from
+    /// ## Errors
+    /// - [AccessError::ForeignHandle(prison_id)] if the handle was created by a different [Prison]
+    pub fn handle_to_key(&self, handle: ScriptHandle) -> Result<CellKey, AccessError> {
+        let (idx, prison_id, gen) = handle.into_raw_parts();
+        if prison_id != internal!(self).id() {
+            return Err(AccessError::ForeignHandle(prison_id as usize));
+        }
+        return Ok(CellKey {
+            idx: idx as usize,
+            gen: gen as usize,
+        });
+    }
+
+    //FN Prison::visit_handle_mut()
+    /// Identical to [Prison::visit_mut()] but using a [ScriptHandle] instead of a [CellKey]
+    /// ## Errors
+    /// - [AccessError::ForeignHandle(prison_id)] if the handle was created by a different [Prison]
+    /// - Any error that [Prison::visit_mut()] could return
+    #[inline(always)]
+    pub fn visit_handle_mut<R, F>(&self, handle: ScriptHandle, operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&mut T) -> Result<R, AccessError>,
+    {
+        return self.visit_mut(self.handle_to_key(handle)?, operation);
+    }
+
+    //FN Prison::visit_handle_ref()
+    /// Identical to [Prison::visit_ref()] but using a [ScriptHandle] instead of a [CellKey]
+    /// ## Errors
+    /// - [AccessError::ForeignHandle(prison_id)] if the handle was created by a different [Prison]
+    /// - Any error that [Prison::visit_ref()] could return
+    #[inline(always)]
+    pub fn visit_handle_ref<R, F>(&self, handle: ScriptHandle, operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&T) -> Result<R, AccessError>,
+    {
+        return self.visit_ref(self.handle_to_key(handle)?, operation);
+    }
+
+    //FN Prison::set_redact_values()
+    /// Enable or disable hiding every value when this [Prison] is printed with [Debug]
+    ///
+    /// While enabled, each value shows as `<redacted>` but the slot states (generations, reference counts and
+    /// free-list links) are still shown, so prisons holding sensitive data can be logged or inspected in a debugger
+    /// without leaking their contents. Values are shown by default
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let passwords: Prison<String> = Prison::new();
+    /// passwords.insert(String::from("hunter2"))?;
+    /// assert!(format!("{:?}", passwords).contains("hunter2"));
+    /// passwords.set_redact_values(true);
+    /// assert!(passwords.values_redacted());
+    /// assert!(!format!("{:?}", passwords).contains("hunter2"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_redact_values(&self, redact: bool) {
+        internal!(self).extras().redact_values = redact;
+    }
+
+    //FN Prison::values_redacted()
+    /// Return `true` if values are hidden when this [Prison] is printed with [Debug], see [Prison::set_redact_values()]
+    pub fn values_redacted(&self) -> bool {
+        return internal!(self).redact_values();
+    }
+
+    //FN Prison::set_move_hook()
+    /// Set (or clear with [None]) a function that is called for every value whose [CellKey] is changed by a
+    /// compaction such as [Prison::purge()] or [Prison::compact()], with its old key, its new key, and a mutable reference to the value
+    ///
+    /// This lets values that embed their own [CellKey] (or that are tracked by external indexes) fix themselves
+    /// up as they move. Hooks run after the compaction has finished, in index order of the old keys, with the value
+    /// mutably referenced as in [Prison::visit_mut()]. Values whose key did not change are not passed to the hook
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// struct Entity {
+    ///     me: CellKey,
+    ///     hp: u32,
+    /// }
+    /// let entities: Prison<Entity> = Prison::new();
+    /// entities.set_move_hook(Some(|_old_key, new_key, entity: &mut Entity| entity.me = new_key));
+    /// let key_0 = entities.insert(Entity { me: CellKey::from_raw_parts(0, 0), hp: 10 })?;
+    /// let key_1 = entities.insert(Entity { me: CellKey::from_raw_parts(1, 0), hp: 20 })?;
+    /// entities.remove(key_0)?;
+    /// let remap = entities.purge()?;
+    /// let key_1 = remap.get(key_1).unwrap();
+    /// entities.visit_ref(key_1, |entity| {
+    ///     assert_eq!(entity.me, key_1);
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_move_hook(&self, hook: Option<fn(CellKey, CellKey, &mut T)>) {
+        internal!(self).extras().move_hook = hook;
+    }
+
+    //FN Prison::maintain_keys()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Have the [Prison] keep the [CellKey] stored inside every value up to date, using [PrisonValue::set_key()]
+    ///
+    /// The current [CellKey] is written into every existing value immediately, and from then on the new [CellKey]
+    /// is written into the value whenever it is given one: by [Prison::insert()], [Prison::insert_at()],
+    /// [Prison::overwrite()] and the methods built on them, by [Prison::recycle()], and for every value moved by
+    /// [Prison::compact()] or [Prison::purge()]. This is the guaranteed version of what [Prison::insert_keyed()]
+    /// and a hand-written [Prison::set_move_hook()] only do for the paths they cover. See [SelfKeyed](crate::SelfKeyed) for a wrapper
+    /// that adds the key to any value type
+    ///
+    /// The setting is copied by [Prison::filtered_clone()] but not by [Prison::map_into()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, SelfKeyed, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<SelfKeyed<&str>> = Prison::new();
+    /// prison.maintain_keys()?;
+    /// let key_0 = prison.insert(SelfKeyed::new("Alice"))?;
+    /// let key_1 = prison.overwrite(0, SelfKeyed::new("Bob"))?;
+    /// assert_ne!(key_0, key_1);
+    /// prison.visit_ref(key_1, |bob| {
+    ///     assert_eq!(bob.key(), key_1);
+    ///     assert_eq!(**bob, "Bob");
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::AccessesOutstanding(count)] if any value is currently referenced
+    pub fn maintain_keys(&self) -> Result<(), AccessError>
+    where
+        T: PrisonValue,
+    {
+        let internal = internal!(self);
+        if internal.access_count.count > 0 {
+            return Err(AccessError::AccessesOutstanding(internal.access_count.count));
+        }
+        #[cfg(feature = "offload")]
+        internal.reload_all()?;
+        internal.extras().key_setter = Some(<T as PrisonValue>::set_key);
+        for idx in 0..internal.vec.len() {
+            let cell = &internal.vec[idx];
+            if cell.is_cell() {
+                self._write_key(CellKey {
+                    idx,
+                    gen: IdxD::val(cell.d_gen_or_prev),
+                });
+            }
+        }
+        return Ok(());
+    }
+
+    //FN Prison::set_idx_validation()
+    /// Enable or disable validation of the indexes passed to `_idx` methods
+    ///
+    /// [CellKey]s carry a generation that catches stale keys, but a plain [usize] index carries nothing
+    /// that ties it to the [Prison] it came from. While validation is enabled, every `_idx` method
+    /// (including [Prison::insert_at()], [Prison::overwrite()] and the [GuardCtx] `_idx` methods) expects
+    /// an index produced by [Prison::salt_idx()], which folds a cheap hash of this [Prison]'s unique id
+    /// into the top 16 bits of the index. In debug builds an index with the wrong salt returns
+    /// [AccessError::ForeignIndex(idx)]; in release builds the salt bits are simply stripped
+    ///
+    /// Range-based methods such as [Prison::visit_slice_mut()] are not affected. While validation is enabled
+    /// only indexes below `2^(usize::BITS - 16)` can be salted
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let players: Prison<u32> = Prison::new();
+    /// let monsters: Prison<u32> = Prison::new();
+    /// players.set_idx_validation(true);
+    /// monsters.set_idx_validation(true);
+    /// let key_0 = players.insert(100)?;
+    /// monsters.insert(5)?;
+    /// let player_idx = players.salt_idx(key_0.idx());
+    /// players.visit_mut_idx(player_idx, |hp| {
+    ///     *hp -= 10;
+    ///     Ok(())
+    /// })?;
+    /// # #[cfg(debug_assertions)]
+    /// assert_eq!(monsters.clone_val_idx(player_idx), Err(AccessError::ForeignIndex(player_idx)));
+    /// players.set_idx_validation(false);
+    /// assert_eq!(players.clone_val_idx(0)?, 90);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_idx_validation(&self, enabled: bool) {
+        let internal = internal!(self);
+        let id = internal.id();
+        internal.extras().idx_salt = match enabled {
+            true => {
+                let hash = id.wrapping_mul(0x9E37_79B9) >> 16;
+                ((hash as usize) | 1) << IDX_SALT_SHIFT
+            }
+            false => 0,
+        };
+    }
+
+    //FN Prison::idx_validation()
+    /// Return `true` if index validation is enabled, see [Prison::set_idx_validation()]
+    pub fn idx_validation(&self) -> bool {
+        return internal!(self).idx_salt() != 0;
+    }
+
+    //FN Prison::salt_idx()
+    /// Fold this [Prison]'s salt into a raw index so it can be passed to `_idx` methods while
+    /// index validation is enabled, see [Prison::set_idx_validation()]
+    ///
+    /// Returns the index unchanged when validation is disabled. Salting an already salted index
+    /// does nothing
+    pub fn salt_idx(&self, idx: usize) -> usize {
+        let salt = internal!(self).idx_salt();
+        if salt == 0 {
+            return idx;
+        }
+        return (idx & !IDX_SALT_MASK) | salt;
+    }
+
+    //FN Prison::uuid_of()
+    /// Return the stable uuid assigned to the value at the [CellKey] when it was inserted
+    ///
+    /// Every value inserted into any [Prison] receives a unique 64-bit uuid (assigned sequentially
+    /// from a global counter, never 0). Unlike a [CellKey], the uuid of a value never changes
+    /// while the value lives in its [Prison], even when operations move it to a different index,
+    /// so [Prison::key_by_uuid()] can always recover its current [CellKey].
+    /// Overwriting or recycling a value gives it a new uuid
+    ///
+    /// Requires the `uuid` feature (adds 8 bytes per element, plus 16 bytes per valid value)
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = Prison::new();
+    /// let key_0 = prison.insert("hero")?;
+    /// let uuid = prison.uuid_of(key_0)?;
+    /// assert_eq!(prison.key_by_uuid(uuid), Some(key_0));
+    /// prison.remove(key_0)?;
+    /// assert_eq!(prison.key_by_uuid(uuid), None);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or the generation does not match
+    #[cfg(feature = "uuid")]
+    pub fn uuid_of(&self, key: CellKey) -> Result<u64, AccessError> {
+        self._check_key(key)?;
+        return Ok(internal!(self).vec[key.idx].uuid);
+    }
+
+    //FN Prison::key_by_uuid()
+    /// Return the current [CellKey] of the value with the given uuid, or [None] if no value
+    /// in this [Prison] has it
+    ///
+    /// See [Prison::uuid_of()]. Requires the `uuid` feature
+    #[cfg(feature = "uuid")]
+    pub fn key_by_uuid(&self, uuid: u64) -> Option<CellKey> {
+        let internal = internal!(self);
+        let pos = internal.uuids.binary_search_by_key(&uuid, |(uuid, _)| *uuid).ok()?;
+        let idx = internal.uuids[pos].1;
+        return Some(CellKey {
+            idx,
+            gen: IdxD::val(internal.vec[idx].d_gen_or_prev),
+        });
     }
 
     //FN Prison::visit_many_mut()
@@ -936,9 +2760,14 @@ impl<T> Prison<T> {
     where
         F: FnMut(&mut [&mut T]) -> Result<(), AccessError>,
     {
-        let (mut vals, mut refs, accesses) = self._add_many_mut_refs(keys)?;
-        let result = operation(&mut vals);
-        _remove_many_mut_refs(&mut refs, accesses);
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_many_mut_refs(keys)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_mut_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        let result = self._visit(|| operation(&mut vals));
+        self._recycle_buf(&mut vals);
         return result;
     }
 
@@ -1027,9 +2856,14 @@ impl<T> Prison<T> {
     where
         F: FnMut(&[&T]) -> Result<(), AccessError>,
     {
-        let (vals, mut refs, accesses) = self._add_many_imm_refs(keys)?;
-        let result = operation(&vals);
-        _remove_many_imm_refs(&mut refs, accesses);
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_many_imm_refs(keys)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_imm_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        let result = self._visit(|| operation(&vals));
+        self._recycle_buf(&mut vals);
         return result;
     }
 
@@ -1114,9 +2948,14 @@ impl<T> Prison<T> {
     where
         F: FnMut(&mut [&mut T]) -> Result<(), AccessError>,
     {
-        let (mut vals, mut refs, accesses) = self._add_many_mut_refs_idx(indexes)?;
-        let result = operation(&mut vals);
-        _remove_many_mut_refs(&mut refs, accesses);
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_many_mut_refs_idx(indexes)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_mut_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        let result = self._visit(|| operation(&mut vals));
+        self._recycle_buf(&mut vals);
         return result;
     }
 
@@ -1210,1728 +3049,9998 @@ impl<T> Prison<T> {
     where
         F: FnMut(&[&T]) -> Result<(), AccessError>,
     {
-        let (vals, mut refs, accesses) = self._add_many_imm_refs_idx(indexes)?;
-        let result = operation(&vals);
-        _remove_many_imm_refs(&mut refs, accesses);
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_many_imm_refs_idx(indexes)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_imm_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        let result = self._visit(|| operation(&vals));
+        self._recycle_buf(&mut vals);
         return result;
     }
 
-    //FN Prison::visit_slice_mut()
-    /// Visit a slice of values in the [Prison] at the same time, obtaining a mutable reference
-    /// to all of them in the same closure.
+    //FN Prison::visit_pair_mut()
+    /// Visit exactly two distinct values in the [Prison] at the same time, obtaining a mutable reference
+    /// to each of them in the same closure, in the order they were requested
     ///
-    /// Internally this is strictly identical to passing [Prison::visit_many_mut_idx()] a list of all
-    /// indexes in the slice range, and is subject to all the same restrictions and errors
+    /// This is the common case of [Prison::visit_many_mut()] with two keys (for example resolving a collision
+    /// between two entities), but it never allocates and the closure receives the two values directly.
+    /// Two keys with the same index are rejected up front with [AccessError::PairSameIndex(idx)], even if
+    /// their generations differ
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let u32_prison: Prison<u32> = Prison::new();
-    /// u32_prison.insert(42)?;
-    /// u32_prison.insert(43)?;
-    /// u32_prison.insert(44)?;
-    /// u32_prison.insert(45)?;
-    /// u32_prison.insert(46)?;
-    /// u32_prison.visit_slice_mut(2..5, |last_three| {
-    ///     assert_eq!(*last_three[0], 44);
-    ///     assert_eq!(*last_three[1], 45);
-    ///     assert_eq!(*last_three[2], 46);
+    /// let prison: Prison<(i32, i32)> = Prison::new();
+    /// let key_0 = prison.insert((10, 1))?;
+    /// let key_1 = prison.insert((-4, 2))?;
+    /// prison.visit_pair_mut(key_0, key_1, |body_a, body_b| {
+    ///     std::mem::swap(&mut body_a.0, &mut body_b.0);
     ///     Ok(())
-    /// });
+    /// })?;
+    /// assert_eq!(prison.clone_val(key_0)?, (-4, 1));
+    /// assert_eq!(prison.visit_pair_mut(key_1, key_1, |_, _| Ok(())), Err(AccessError::PairSameIndex(1)));
     /// # Ok(())
     /// # }
     /// ```
-    /// Any standard [Range<usize>](std::ops::Range) notation is allowed as the first paramater,
-    /// but care must be taken because it is not guaranteed every index within range is a valid
-    /// value or does not have any other references to it that would violate Rust's memory safety.
+    /// ## Errors
+    /// - [AccessError::PairSameIndex(idx)] if both keys have the same index
+    /// - Any error returned by [Prison::visit_mut()] for either key
+    pub fn visit_pair_mut<R, F>(&self, key_a: CellKey, key_b: CellKey, mut operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&mut T, &mut T) -> Result<R, AccessError>,
+    {
+        self._check_depth()?;
+        if key_a.idx == key_b.idx {
+            return Err(AccessError::PairSameIndex(key_a.idx));
+        }
+        let (cell_a, accesses) = self._add_mut_ref(key_a.idx, key_a.gen, true)?;
+        let _release_a = RefRelease::new(self, &mut cell_a.refs_or_next, |refs| _remove_mut_ref(refs, accesses));
+        let (cell_b, accesses) = self._add_mut_ref(key_b.idx, key_b.gen, true)?;
+        let _release_b = RefRelease::new(self, &mut cell_b.refs_or_next, |refs| _remove_mut_ref(refs, accesses));
+        return self._visit(|| {
+            self._decoded_mut(unsafe { cell_a.val.assume_init_mut() }, |val_a| {
+                self._decoded_mut(unsafe { cell_b.val.assume_init_mut() }, |val_b| operation(val_a, val_b))
+            })
+        });
+    }
+
+    //FN Prison::visit_array_mut()
+    /// Exactly like [Prison::visit_many_mut()], but taking a fixed-size array of keys and passing the closure a
+    /// fixed-size array of mutable references in the same order
+    ///
+    /// Because the number of values is known at compile time the array can be destructured directly in the
+    /// closure's parameter list, and indexing it needs no bounds checks
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let u32_prison: Prison<u32> = Prison::new();
-    /// u32_prison.insert(42)?;
-    /// u32_prison.insert(43)?;
-    /// u32_prison.insert(44)?;
-    /// u32_prison.insert(45)?;
-    /// u32_prison.insert(46)?;
-    /// assert!(u32_prison.visit_slice_mut(2..5,  |last_three| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_mut(2..=4, |also_last_three| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_mut(2..,   |again_last_three| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_mut(..3,   |first_three| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_mut(..=3,  |first_four| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_mut(..,    |all| Ok(())).is_ok());
-    /// u32_prison.remove_idx(2)?;
-    /// assert!(u32_prison.visit_slice_mut(..,    |all| Ok(())).is_err());
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(1)?;
+    /// let key_1 = prison.insert(2)?;
+    /// let key_2 = prison.insert(3)?;
+    /// prison.visit_array_mut([key_2, key_0, key_1], |[val_2, val_0, val_1]| {
+    ///     *val_2 += *val_0 + *val_1;
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(prison.clone_val(key_2)?, 6);
+    /// assert!(prison.visit_array_mut([key_0, key_0], |_| Ok(())).is_err());
     /// # Ok(())
     /// # }
     /// ```
-    /// See [Prison::visit_many_mut_idx()] for more info
-    pub fn visit_slice_mut<R, F>(&self, range: R, operation: F) -> Result<(), AccessError>
+    /// ## Errors
+    /// Same as [Prison::visit_many_mut()]
+    pub fn visit_array_mut<const N: usize, R, F>(&self, keys: [CellKey; N], mut operation: F) -> Result<R, AccessError>
     where
-        R: RangeBounds<usize>,
-        F: FnMut(&mut [&mut T]) -> Result<(), AccessError>,
+        F: FnMut([&mut T; N]) -> Result<R, AccessError>,
     {
-        let (start, end) = extract_true_start_end(range, self.vec_len());
-        let idxs: Vec<usize> = (start..end).collect();
-        self.visit_many_mut_idx(&idxs, operation)
+        self._check_depth()?;
+        let (vals, refs, accesses) = self._add_array_mut_refs(&keys)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_mut_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        return self._visit(|| operation(vals));
     }
 
-    //FN Prison::visit_slice_ref()
-    /// Visit a slice of values in the [Prison] at the same time, obtaining an immutable reference
-    /// to all of them in the same closure.
+    //FN Prison::visit_many_mut_scratch()
+    /// Exactly like [Prison::visit_many_mut()], but the closure also receives a [Scratch] it can use for temporary
+    /// buffers instead of allocating new [Vec]s on every visit
     ///
-    /// Internally this is strictly identical to passing [Prison::visit_many_ref_idx()] a list of all
-    /// indexes in the slice range, and is subject to all the same restrictions and errors
+    /// The [Scratch] is reset after the closure returns (whether or not it returned an error), so memory
+    /// allocated during one visit is reused by the next one
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # use grit_data_prison::{AccessError, CellKey, Scratch, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let u32_prison: Prison<u32> = Prison::new();
-    /// u32_prison.insert(42)?;
-    /// u32_prison.insert(43)?;
-    /// u32_prison.insert(44)?;
-    /// u32_prison.insert(45)?;
-    /// u32_prison.insert(46)?;
-    /// u32_prison.visit_slice_ref(2..5, |last_three| {
-    ///     assert_eq!(*last_three[0], 44);
-    ///     assert_eq!(*last_three[1], 45);
-    ///     assert_eq!(*last_three[2], 46);
-    ///     u32_prison.visit_slice_ref(0..3, |first_three| {
-    ///         assert_eq!(*first_three[0], 42);
-    ///         assert_eq!(*first_three[1], 43);
-    ///         assert_eq!(*first_three[2], 44);
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(3)?;
+    /// let key_1 = prison.insert(1)?;
+    /// let key_2 = prison.insert(2)?;
+    /// let mut scratch = Scratch::new();
+    /// for _ in 0..3 {
+    ///     prison.visit_many_mut_scratch(&[key_0, key_1, key_2], &mut scratch, |vals, scratch| {
+    ///         let sorted = scratch.alloc_slice_fill_with(vals.len(), |i| *vals[i]);
+    ///         sorted.sort_unstable();
+    ///         for (val, new_val) in vals.iter_mut().zip(sorted.iter()) {
+    ///             **val = *new_val;
+    ///         }
     ///         Ok(())
-    ///     });
-    ///     Ok(())
-    /// });
+    ///     })?;
+    /// }
+    /// assert_eq!(prison.clone_many_vals(&[key_0, key_1, key_2])?, vec![1, 2, 3]);
     /// # Ok(())
     /// # }
     /// ```
-    /// Any standard [Range<usize>](std::ops::Range) notation is allowed as the first paramater,
-    /// but care must be taken because it is not guaranteed every index within range is a valid
-    /// value or does not have any other references to it that would violate Rust's memory safety.
-    /// ### Example
-    /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
-    /// # fn main() -> Result<(), AccessError> {
-    /// let u32_prison: Prison<u32> = Prison::new();
-    /// u32_prison.insert(42)?;
-    /// u32_prison.insert(43)?;
-    /// u32_prison.insert(44)?;
-    /// u32_prison.insert(45)?;
-    /// u32_prison.insert(46)?;
-    /// assert!(u32_prison.visit_slice_ref(2..5,  |last_three| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_ref(2..=4, |also_last_three| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_ref(2..,   |again_last_three| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_ref(..3,   |first_three| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_ref(..=3,  |first_four| Ok(())).is_ok());
-    /// assert!(u32_prison.visit_slice_ref(..,    |all| Ok(())).is_ok());
-    /// u32_prison.remove_idx(2)?;
-    /// assert!(u32_prison.visit_slice_ref(..,    |all| Ok(())).is_err());
-    /// # Ok(())
-    /// # }
-    /// ```
-    /// See [Prison::visit_many_ref_idx()] for more info
-    pub fn visit_slice_ref<R, F>(&self, range: R, operation: F) -> Result<(), AccessError>
+    /// ## Errors
+    /// Returns the same errors as [Prison::visit_many_mut()]
+    pub fn visit_many_mut_scratch<F>(
+        &self,
+        keys: &[CellKey],
+        scratch: &mut Scratch,
+        mut operation: F,
+    ) -> Result<(), AccessError>
     where
-        R: RangeBounds<usize>,
-        F: FnMut(&[&T]) -> Result<(), AccessError>,
+        F: FnMut(&mut [&mut T], &Scratch) -> Result<(), AccessError>,
     {
-        let (start, end) = extract_true_start_end(range, self.vec_len());
-        let idxs: Vec<usize> = (start..end).collect();
-        self.visit_many_ref_idx(&idxs, operation)
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_many_mut_refs(keys)?;
+        let release = RefRelease::new(self, refs, |refs| {
+            _remove_many_mut_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        let result = self._visit(|| operation(&mut vals, scratch));
+        self._recycle_buf(&mut vals);
+        drop(release);
+        scratch.reset();
+        return result;
     }
 
-    //FN Prison::guard_mut()
-    /// Return a [PrisonValueMut] that contains a mutable reference to the element and wraps it in
-    /// guarding data that automatically frees its reference count it when it goes out of scope.
+    //FN Prison::visit_many_ref_scratch()
+    /// Exactly like [Prison::visit_many_ref()], but the closure also receives a [Scratch] it can use for temporary
+    /// buffers instead of allocating new [Vec]s on every visit
     ///
-    /// [PrisonValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
-    /// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value
-    ///
-    /// As long as the [PrisonValueMut] remains in scope, the element where it's value resides in the
-    /// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
-    /// You can manually drop the [PrisonValueMut] out of scope by passing it as the first parameter
-    /// to the function [PrisonValueMut::unguard(p_val_mut)]
+    /// The [Scratch] is reset after the closure returns (whether or not it returned an error), so memory
+    /// allocated during one visit is reused by the next one
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, Scratch, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
     /// let prison: Prison<u32> = Prison::new();
     /// let key_0 = prison.insert(10)?;
-    /// let mut grd_0 = prison.guard_mut(key_0)?;
-    /// assert_eq!(*grd_0, 10);
-    /// *grd_0 = 20;
-    /// PrisonValueMut::unguard(grd_0);
-    /// prison.visit_ref(key_0, |val_0| {
-    ///     assert_eq!(*val_0, 20);
+    /// let key_1 = prison.insert(20)?;
+    /// let mut scratch = Scratch::new();
+    /// prison.visit_many_ref_scratch(&[key_0, key_1], &mut scratch, |vals, scratch| {
+    ///     let halves = scratch.alloc_slice_fill_with(vals.len(), |i| *vals[i] / 2);
+    ///     assert_eq!(halves, &[5, 10]);
     ///     Ok(())
-    /// });
+    /// })?;
     /// # Ok(())
     /// # }
     /// ```
     /// ## Errors
-    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
-    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if element has any number of immutable references
-    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
-    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation does not match
-    /// ### Example
-    /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
-    /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::with_capacity(2);
-    /// let key_0 = prison.insert(10)?;
-    /// let key_1_a = prison.insert(20)?;
-    /// let key_out_of_bounds = CellKey::from_raw_parts(10, 0);
-    /// prison.remove(key_1_a)?;
-    /// let key_1_b = prison.insert(30)?;
-    /// let mut grd_0 = prison.guard_mut(key_0)?;
-    /// assert!(prison.guard_mut(key_0).is_err());
-    /// assert!(prison.guard_mut(key_out_of_bounds).is_err());
-    /// assert!(prison.guard_mut(key_1_a).is_err());
-    /// # Ok(())
-    /// # }
-    /// ```
-    #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_mut<'a>(&'a self, key: CellKey) -> Result<PrisonValueMut<'a, T>, AccessError> {
-        let (cell, visits) = self._add_mut_ref(key.idx, key.gen, true)?;
-        return Ok(PrisonValueMut {
-            cell,
-            prison_accesses: visits,
+    /// Returns the same errors as [Prison::visit_many_ref()]
+    pub fn visit_many_ref_scratch<F>(
+        &self,
+        keys: &[CellKey],
+        scratch: &mut Scratch,
+        mut operation: F,
+    ) -> Result<(), AccessError>
+    where
+        F: FnMut(&[&T], &Scratch) -> Result<(), AccessError>,
+    {
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_many_imm_refs(keys)?;
+        let release = RefRelease::new(self, refs, |refs| {
+            _remove_many_imm_refs(refs, accesses);
+            self._recycle_buf(refs);
         });
+        let result = self._visit(|| operation(&vals, scratch));
+        self._recycle_buf(&mut vals);
+        drop(release);
+        scratch.reset();
+        return result;
     }
 
-    //FN Prison::guard_ref()
-    /// Return a [PrisonValueRef] that contains an immutable reference to the element and wraps it in
-    /// guarding data that automatically decrements its reference count it when it goes out of scope.
+    //FN Prison::visit_set_mut()
+    /// Visit every value referenced by a [CellKeySet] at the same time, obtaining a mutable reference
+    /// to all of them in the same closure, in ascending index order (the same order as [CellKeySet::as_slice()])
     ///
-    /// [PrisonValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], and
-    /// [Borrow<T>] to allow transparent access to its underlying value
-    ///
-    /// As long as the [PrisonValueRef] remains in scope, the element where it's value resides in the
-    /// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
-    /// You can manually drop the [PrisonValueRef] out of scope by passing it as the first parameter
-    /// to the function [PrisonValueRef::unguard(p_val_ref)]
+    /// Because the set is already sorted and de-duplicated, the cells are acquired walking forward
+    /// through the [Prison] and no temporary key slice needs to be built. Unlike `visit_many_mut()`,
+    /// every member of the set is checked before failing due to other active references, so the error
+    /// lists *all* blocked members at once
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+    /// # use grit_data_prison::{AccessError, CellKey, CellKeySet, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
     /// let prison: Prison<u32> = Prison::new();
     /// let key_0 = prison.insert(10)?;
-    /// let grd_0 = prison.guard_ref(key_0)?;
-    /// assert_eq!(*grd_0, 10);
-    /// prison.visit_ref(key_0, |val_0| {
-    ///     assert_eq!(*val_0, 10);
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// let set: CellKeySet = [key_2, key_0].into_iter().collect();
+    /// prison.visit_set_mut(&set, |vals_0_2| {
+    ///     *vals_0_2[0] += 1;
+    ///     *vals_0_2[1] += 1;
     ///     Ok(())
-    /// });
-    /// assert_eq!(*grd_0, 10);
-    /// PrisonValueRef::unguard(grd_0);
+    /// })?;
+    /// assert_eq!(prison.clone_many_vals(&[key_0, key_1, key_2])?, vec![11, 20, 31]);
     /// # Ok(())
     /// # }
     /// ```
     /// ## Errors
-    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
-    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references already
-    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
-    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation doe not match
+    /// - [AccessError::SetMembersBlocked(set)] if any members are already referenced, containing every blocked member
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* the [CellKey] generation doesnt match
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+    /// # use grit_data_prison::{AccessError, CellKey, CellKeySet, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::with_capacity(2);
+    /// let prison: Prison<u32> = Prison::new();
     /// let key_0 = prison.insert(10)?;
-    /// let key_1_a = prison.insert(20)?;
-    /// let key_out_of_bounds = CellKey::from_raw_parts(10, 0);
-    /// prison.remove(key_1_a)?;
-    /// let key_1_b = prison.insert(30)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// let set: CellKeySet = [key_0, key_1, key_2].into_iter().collect();
     /// let grd_0 = prison.guard_ref(key_0)?;
-    /// assert!(prison.guard_mut(key_0).is_err());
-    /// assert!(prison.guard_ref(key_out_of_bounds).is_err());
-    /// assert!(prison.guard_ref(key_1_a).is_err());
+    /// let grd_2 = prison.guard_mut(key_2)?;
+    /// match prison.visit_set_mut(&set, |vals| Ok(())) {
+    ///     Err(AccessError::SetMembersBlocked(blocked)) => assert_eq!(blocked.as_slice(), &[key_0, key_2]),
+    ///     _ => panic!(),
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_ref<'a>(&'a self, key: CellKey) -> Result<PrisonValueRef<'a, T>, AccessError> {
-        let (cell, visits) = self._add_imm_ref(key.idx, key.gen, true)?;
-        return Ok(PrisonValueRef {
-            cell,
-            prison_accesses: visits,
+    pub fn visit_set_mut<F>(&self, set: &CellKeySet, mut operation: F) -> Result<(), AccessError>
+    where
+        F: FnMut(&mut [&mut T]) -> Result<(), AccessError>,
+    {
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_set_mut_refs(set)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_mut_refs(refs, accesses);
+            self._recycle_buf(refs);
         });
+        let result = self._visit(|| operation(&mut vals));
+        self._recycle_buf(&mut vals);
+        return result;
     }
 
-    //FN Prison::guard_mut_idx()
-    /// Return a [PrisonValueMut] that contains a mutable reference to the element and wraps it in
-    /// guarding data that automatically frees its reference count it when it goes out of scope.
+    //FN Prison::visit_set_ref()
+    /// Visit every value referenced by a [CellKeySet] at the same time, obtaining an immutable reference
+    /// to all of them in the same closure, in ascending index order (the same order as [CellKeySet::as_slice()])
     ///
-    /// Smilar to `guard_mut()` but ignores the generation counter
-    ///
-    /// [PrisonValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
-    /// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value
-    ///
-    /// As long as the [PrisonValueMut] remains in scope, the element where it's value resides in the
-    /// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
-    /// You can manually drop the [PrisonValueMut] out of scope by passing it as the first parameter
-    /// to the function [PrisonValueMut::unguard(p_val_mut)]
+    /// See [Prison::visit_set_mut()] for how blocked members are reported
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, CellKeySet, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
     /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// let mut grd_0 = prison.guard_mut_idx(0)?;
-    /// assert_eq!(*grd_0, 10);
-    /// *grd_0 = 20;
-    /// PrisonValueMut::unguard(grd_0);
-    /// prison.visit_ref_idx(0, |val_0| {
-    ///     assert_eq!(*val_0, 20);
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let set: CellKeySet = [key_1, key_0].into_iter().collect();
+    /// prison.visit_set_ref(&set, |vals_0_1| {
+    ///     assert_eq!(*vals_0_1[0], 10);
+    ///     assert_eq!(*vals_0_1[1], 20);
     ///     Ok(())
-    /// });
+    /// })?;
     /// # Ok(())
     /// # }
     /// ```
     /// ## Errors
-    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
-    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if element has any number of immutable references
-    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
-    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted
-    /// ### Example
-    /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
-    /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::with_capacity(2);
-    /// prison.insert(10)?;
-    /// prison.insert(20)?;
-    /// prison.remove_idx(1)?;
-    /// let mut grd_0 = prison.guard_mut_idx(0)?;
-    /// assert!(prison.guard_mut_idx(0).is_err());
-    /// assert!(prison.guard_mut_idx(5).is_err());
-    /// assert!(prison.guard_mut_idx(1).is_err());
-    /// # Ok(())
-    /// # }
-    /// ```
-    #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_mut_idx<'a>(&'a self, idx: usize) -> Result<PrisonValueMut<'a, T>, AccessError> {
-        let (cell, visits) = self._add_mut_ref(idx, 0, false)?;
-        return Ok(PrisonValueMut {
-            cell,
-            prison_accesses: visits,
+    /// - [AccessError::SetMembersBlocked(set)] if any members are already mutably referenced, containing every blocked member
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* the [CellKey] generation doesnt match
+    pub fn visit_set_ref<F>(&self, set: &CellKeySet, mut operation: F) -> Result<(), AccessError>
+    where
+        F: FnMut(&[&T]) -> Result<(), AccessError>,
+    {
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_set_imm_refs(set)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_imm_refs(refs, accesses);
+            self._recycle_buf(refs);
         });
+        let result = self._visit(|| operation(&vals));
+        self._recycle_buf(&mut vals);
+        return result;
     }
 
-    //FN Prison::guard_ref_idx()
-    /// Return a [PrisonValueRef] that contains an immutable reference to the element and wraps it in
-    /// guarding data that automatically decrements its reference count it when it goes out of scope.
-    ///
-    /// Similar to `guard_ref()` but ignores the generation counter
-    ///
-    /// [PrisonValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], and
-    /// [Borrow<T>] to allow transparent access to its underlying value
+    //FN Prison::visit_slice_mut()
+    /// Visit a slice of values in the [Prison] at the same time, obtaining a mutable reference
+    /// to all of them in the same closure.
     ///
-    /// As long as the [PrisonValueRef] remains in scope, the element where it's value resides in the
-    /// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
-    /// You can manually drop the [PrisonValueRef] out of scope by passing it as the first parameter
-    /// to the function [PrisonValueRef::unguard(p_val_ref)]
+    /// Internally this is strictly identical to passing [Prison::visit_many_mut_idx()] a list of all
+    /// indexes in the slice range, and is subject to all the same restrictions and errors
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// let grd_0 = prison.guard_ref_idx(0)?;
-    /// assert_eq!(*grd_0, 10);
-    /// prison.visit_ref_idx(0, |val_0| {
-    ///     assert_eq!(*val_0, 10);
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// u32_prison.insert(42)?;
+    /// u32_prison.insert(43)?;
+    /// u32_prison.insert(44)?;
+    /// u32_prison.insert(45)?;
+    /// u32_prison.insert(46)?;
+    /// u32_prison.visit_slice_mut(2..5, |last_three| {
+    ///     assert_eq!(*last_three[0], 44);
+    ///     assert_eq!(*last_three[1], 45);
+    ///     assert_eq!(*last_three[2], 46);
     ///     Ok(())
     /// });
-    /// assert_eq!(*grd_0, 10);
-    /// PrisonValueRef::unguard(grd_0);
     /// # Ok(())
     /// # }
     /// ```
-    /// ## Errors
-    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
-    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references already
-    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
-    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation doe not match
+    /// Any standard [Range<usize>](std::ops::Range) notation is allowed as the first paramater,
+    /// but care must be taken because it is not guaranteed every index within range is a valid
+    /// value or does not have any other references to it that would violate Rust's memory safety.
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::with_capacity(2);
-    /// prison.insert(10)?;
-    /// prison.insert(20)?;
-    /// prison.remove_idx(1)?;
-    /// let grd_0 = prison.guard_ref_idx(0)?;
-    /// assert!(prison.guard_mut_idx(0).is_err());
-    /// assert!(prison.guard_ref_idx(5).is_err());
-    /// assert!(prison.guard_ref_idx(1).is_err());
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// u32_prison.insert(42)?;
+    /// u32_prison.insert(43)?;
+    /// u32_prison.insert(44)?;
+    /// u32_prison.insert(45)?;
+    /// u32_prison.insert(46)?;
+    /// assert!(u32_prison.visit_slice_mut(2..5,  |last_three| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_mut(2..=4, |also_last_three| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_mut(2..,   |again_last_three| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_mut(..3,   |first_three| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_mut(..=3,  |first_four| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_mut(..,    |all| Ok(())).is_ok());
+    /// u32_prison.remove_idx(2)?;
+    /// assert!(u32_prison.visit_slice_mut(..,    |all| Ok(())).is_err());
     /// # Ok(())
     /// # }
     /// ```
-    #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_ref_idx<'a>(&'a self, idx: usize) -> Result<PrisonValueRef<'a, T>, AccessError> {
-        let (cell, visits) = self._add_imm_ref(idx, 0, false)?;
-        return Ok(PrisonValueRef {
-            cell,
-            prison_accesses: visits,
-        });
-    }
-
-    //FN Prison::guard_many_mut()
-    /// Return a [PrisonSliceMut] that marks all the elements as mutably referenced and wraps
-    /// them in guarding data that automatically frees their mutable reference counts when it goes out of range.
-    ///
-    /// [PrisonSliceMut<T>] implements [Deref<Target = \[&mut T\]>](Deref), [DerefMut<Target = \[&mut T\]>](DerefMut), [AsRef<\[&mut T\]>](AsRef), [AsMut<\[&mut T\]>](AsMut),
-    /// [Borrow<\[&mut T\]>](Borrow), and [BorrowMut<\[&mut T\]>](BorrowMut) to allow transparent access to its underlying slice of values
+    /// See [Prison::visit_many_mut_idx()] for more info
+    pub fn visit_slice_mut<R, F>(&self, range: R, operation: F) -> Result<(), AccessError>
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&mut [&mut T]) -> Result<(), AccessError>,
+    {
+        let (start, end) = extract_true_start_end(range, self.vec_len());
+        let idxs: Vec<usize> = (start..end).map(|idx| self.salt_idx(idx)).collect();
+        self.visit_many_mut_idx(&idxs, operation)
+    }
+
+    //FN Prison::visit_slice_ref()
+    /// Visit a slice of values in the [Prison] at the same time, obtaining an immutable reference
+    /// to all of them in the same closure.
     ///
-    /// As long as the [PrisonSliceMut] remains in scope, the elements where it's values reside in the
-    /// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
-    /// You can manually drop the [PrisonSliceMut] out of scope by passing it as the first parameter
-    /// to the function [PrisonSliceMut::unguard(p_sli_mut)]
+    /// Internally this is strictly identical to passing [Prison::visit_many_ref_idx()] a list of all
+    /// indexes in the slice range, and is subject to all the same restrictions and errors
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// let key_0 = prison.insert(10)?;
-    /// let key_1 = prison.insert(20)?;
-    /// let key_2 = prison.insert(30)?;
-    /// let mut grd_0_1_2 = prison.guard_many_mut(&[key_0, key_1, key_2])?;
-    /// assert_eq!(*grd_0_1_2[0], 10);
-    /// *grd_0_1_2[0] = 20;
-    /// PrisonSliceMut::unguard(grd_0_1_2);
-    /// prison.visit_many_ref(&[key_0, key_1, key_2], |vals_0_1_2| {
-    ///     assert_eq!(*vals_0_1_2[0], 20);
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// u32_prison.insert(42)?;
+    /// u32_prison.insert(43)?;
+    /// u32_prison.insert(44)?;
+    /// u32_prison.insert(45)?;
+    /// u32_prison.insert(46)?;
+    /// u32_prison.visit_slice_ref(2..5, |last_three| {
+    ///     assert_eq!(*last_three[0], 44);
+    ///     assert_eq!(*last_three[1], 45);
+    ///     assert_eq!(*last_three[2], 46);
+    ///     u32_prison.visit_slice_ref(0..3, |first_three| {
+    ///         assert_eq!(*first_three[0], 42);
+    ///         assert_eq!(*first_three[1], 43);
+    ///         assert_eq!(*first_three[2], 44);
+    ///         Ok(())
+    ///     });
     ///     Ok(())
     /// });
     /// # Ok(())
     /// # }
     /// ```
-    /// ## Errors
-    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
-    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if any element has any number of immutable references
-    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
-    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* the [CellKey] generation doesnt match
+    /// Any standard [Range<usize>](std::ops::Range) notation is allowed as the first paramater,
+    /// but care must be taken because it is not guaranteed every index within range is a valid
+    /// value or does not have any other references to it that would violate Rust's memory safety.
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// let key_0 = prison.insert(10)?;
-    /// let key_1 = prison.insert(20)?;
-    /// let key_2 = prison.insert(30)?;
-    /// let key_4_a = prison.insert(40)?;
-    /// prison.remove(key_4_a)?;
-    /// let key_4_b = prison.insert(44)?;
-    /// let key_out_of_bounds = CellKey::from_raw_parts(10, 1);
-    /// let mut grd_0_1_2 = prison.guard_many_mut(&[key_0, key_1, key_2])?;
-    /// assert!(prison.guard_many_mut(&[key_0, key_1, key_2, key_4_b]).is_err());
-    /// assert!(prison.guard_many_mut(&[key_out_of_bounds]).is_err());
-    /// assert!(prison.guard_many_mut(&[key_4_a]).is_err());
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// u32_prison.insert(42)?;
+    /// u32_prison.insert(43)?;
+    /// u32_prison.insert(44)?;
+    /// u32_prison.insert(45)?;
+    /// u32_prison.insert(46)?;
+    /// assert!(u32_prison.visit_slice_ref(2..5,  |last_three| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_ref(2..=4, |also_last_three| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_ref(2..,   |again_last_three| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_ref(..3,   |first_three| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_ref(..=3,  |first_four| Ok(())).is_ok());
+    /// assert!(u32_prison.visit_slice_ref(..,    |all| Ok(())).is_ok());
+    /// u32_prison.remove_idx(2)?;
+    /// assert!(u32_prison.visit_slice_ref(..,    |all| Ok(())).is_err());
     /// # Ok(())
     /// # }
     /// ```
-    #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_many_mut<'a>(
-        &'a self,
-        keys: &[CellKey],
-    ) -> Result<PrisonSliceMut<'a, T>, AccessError> {
-        let (vals, refs, prison_accesses) = self._add_many_mut_refs(keys)?;
-        return Ok(PrisonSliceMut {
-            vals,
-            refs,
-            prison_accesses,
-        });
+    /// See [Prison::visit_many_ref_idx()] for more info
+    pub fn visit_slice_ref<R, F>(&self, range: R, operation: F) -> Result<(), AccessError>
+    where
+        R: RangeBounds<usize>,
+        F: FnMut(&[&T]) -> Result<(), AccessError>,
+    {
+        let (start, end) = extract_true_start_end(range, self.vec_len());
+        let idxs: Vec<usize> = (start..end).map(|idx| self.salt_idx(idx)).collect();
+        self.visit_many_ref_idx(&idxs, operation)
     }
 
-    //FN Prison::guard_many_ref()
-    /// Return a [PrisonSliceRef] that marks all the elements as immutably referenced and wraps
-    /// them in guarding data that automatically decreases their immutable reference counts when it goes out of range.
+    //FN Prison::state_digest()
+    /// #### This operation has O(N) time complexity
     ///
-    /// [PrisonSliceRef<T>] implements [Deref<Target = \[&T\]>](Deref), [AsRef<\[&T\]>](AsRef),
-    /// and [Borrow<\[&T\]>](Borrow), to allow transparent access to its underlying slice of values
+    /// Hash the whole observable state of the [Prison] into `hasher` and return [Hasher::finish()], for detecting
+    /// desyncs between peers running the same lockstep simulation
     ///
-    /// As long as the [PrisonSliceRef] remains in scope, the elements where it's values reside in the
-    /// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
-    /// You can manually drop the [PrisonSliceRef] out of scope by passing it as the first parameter
-    /// to the function [PrisonSliceRef::unguard(p_sli_ref)]
+    /// Every valid, unhidden value is immutably referenced for the whole operation, then the number of such values and, in
+    /// index order, the index, generation and value of each one are written to `hasher`. Indexes and generations are written
+    /// as [u64] so 32 and 64 bit peers agree, and values are hashed in their decoded form. Free cells, hidden values and
+    /// bookkeeping such as reference counts, pins, groups or the free list order do not affect the digest.
+    /// Two prisons produce the same digest only if `hasher` is seeded the same way on both, so avoid randomly keyed
+    /// hashers such as the ones built by [std::collections::hash_map::RandomState]
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # use std::collections::hash_map::DefaultHasher;
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// let key_0 = prison.insert(10)?;
-    /// let key_1 = prison.insert(20)?;
-    /// let key_2 = prison.insert(30)?;
-    /// let mut grd_0_1_2 = prison.guard_many_ref(&[key_0, key_1, key_2])?;
-    /// assert_eq!(*grd_0_1_2[0], 10);
-    /// prison.visit_many_ref(&[key_0, key_1, key_2], |vals_0_1_2| {
-    ///     assert_eq!(*vals_0_1_2[0], 10);
+    /// let peer_a: Prison<u32> = Prison::new();
+    /// let peer_b: Prison<u32> = Prison::new();
+    /// for prison in [&peer_a, &peer_b] {
+    ///     let key_0 = prison.insert(10)?;
+    ///     prison.insert(20)?;
+    ///     prison.remove(key_0)?;
+    /// }
+    /// let digest_a = peer_a.state_digest(DefaultHasher::new())?;
+    /// assert_eq!(digest_a, peer_b.state_digest(DefaultHasher::new())?);
+    /// peer_b.visit_mut_idx(1, |val| {
+    ///     *val += 1;
     ///     Ok(())
-    /// });
-    /// PrisonSliceRef::unguard(grd_0_1_2);
+    /// })?;
+    /// assert_ne!(digest_a, peer_b.state_digest(DefaultHasher::new())?);
     /// # Ok(())
     /// # }
     /// ```
     /// ## Errors
-    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
-    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references to any element
-    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
-    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* if the [CellKey] generation doesn't match
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if a value is currently mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if a value already has the maximum number of immutable references
+    /// - [AccessError::OffloadFailed(idx)] if an offloaded value could not be reloaded
+    pub fn state_digest<H>(&self, mut hasher: H) -> Result<u64, AccessError>
+    where
+        T: Hash,
+        H: Hasher,
+    {
+        let internal = internal!(self);
+        let mut keys = Vec::with_capacity(internal.vec.len() - internal.free_count);
+        let mut idxs = Vec::with_capacity(keys.capacity());
+        for (idx, cell) in internal.vec.iter().enumerate() {
+            if cell.is_cell() && cell.refs_or_next != Refs::HIDDEN {
+                keys.push((idx as u64, IdxD::val(cell.d_gen_or_prev) as u64));
+                idxs.push(self.salt_idx(idx));
+            }
+        }
+        let (mut vals, refs, accesses) = self._add_many_imm_refs_idx(&idxs)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_imm_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        hasher.write_u64(vals.len() as u64);
+        for ((idx, gen), val) in keys.iter().zip(vals.iter()) {
+            hasher.write_u64(*idx);
+            hasher.write_u64(*gen);
+            self._decoded_ref(val, |val| val.hash(&mut hasher));
+        }
+        self._recycle_buf(&mut vals);
+        return Ok(hasher.finish());
+    }
+
+    //FN Prison::visit_each_mut()
+    /// Visit every valid value in the [Prison] that is not currently referenced with a mutable reference,
+    /// in index order, passing the closure the [CellKey] of each value
+    ///
+    /// Values that are already referenced (for example by a guard or an outer `visit()` closure) are skipped
+    /// instead of causing an error. Each value is only referenced while the closure runs on it, so the closure
+    /// may freely visit, insert, or remove *other* values. Values inserted at indexes the visit has not reached yet
+    /// will also be visited
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
     /// let prison: Prison<u32> = Prison::new();
     /// let key_0 = prison.insert(10)?;
     /// let key_1 = prison.insert(20)?;
     /// let key_2 = prison.insert(30)?;
-    /// let key_4_a = prison.insert(40)?;
-    /// prison.remove(key_4_a)?;
-    /// let key_4_b = prison.insert(44)?;
-    /// let key_out_of_bounds = CellKey::from_raw_parts(10, 1);
-    /// let grd_0_1_2 = prison.guard_many_ref(&[key_0, key_1, key_2])?;
-    /// assert!(prison.guard_many_mut(&[key_0]).is_err());
-    /// assert!(prison.guard_many_ref(&[key_out_of_bounds]).is_err());
-    /// assert!(prison.guard_many_ref(&[key_4_a]).is_err());
+    /// prison.visit_ref(key_1, |_| {
+    ///     prison.visit_each_mut(|key, val| {
+    ///         *val += key.idx() as u32;
+    ///         Ok(())
+    ///     })
+    /// })?;
+    /// assert_eq!(prison.clone_val(key_0)?, 10);
+    /// assert_eq!(prison.clone_val(key_1)?, 20);
+    /// assert_eq!(prison.clone_val(key_2)?, 32);
     /// # Ok(())
     /// # }
     /// ```
-    #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_many_ref<'a>(
-        &'a self,
-        keys: &[CellKey],
-    ) -> Result<PrisonSliceRef<'a, T>, AccessError> {
-        let (vals, refs, prison_accesses) = self._add_many_imm_refs(keys)?;
-        return Ok(PrisonSliceRef {
-            vals,
-            refs,
-            prison_accesses,
-        });
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - Any error returned from `operation`, which stops the visit immediately
+    pub fn visit_each_mut<F>(&self, mut operation: F) -> Result<(), AccessError>
+    where
+        F: FnMut(CellKey, &mut T) -> Result<(), AccessError>,
+    {
+        self._check_depth()?;
+        self._check_unsealed()?;
+        let mut idx = 0;
+        while idx < internal!(self).vec.len() {
+            let gen = match &internal!(self).vec[idx] {
+                cell if cell.is_cell() && cell.refs_or_next == 0 => IdxD::val(cell.d_gen_or_prev),
+                _ => {
+                    idx += 1;
+                    continue;
+                }
+            };
+            let (cell, accesses) = self._add_mut_ref(idx, gen, true)?;
+            let release = RefRelease::new(self, &mut cell.refs_or_next, |refs| _remove_mut_ref(refs, accesses));
+            self._visit(|| self._decoded_mut(unsafe { cell.val.assume_init_mut() }, |val| operation(CellKey { idx, gen }, val)))?;
+            drop(release);
+            idx += 1;
+        }
+        return Ok(());
     }
 
-    //FN Prison::guard_many_mut_idx()
-    /// Return a [PrisonSliceMut] that marks all the elements as mutably referenced and wraps
-    /// them in guarding data that automatically frees their mutable reference counts when it goes out of range.
-    ///
-    /// Similar to `guard_many_mut()` but ignores the generation counter
+    //FN Prison::retain()
+    /// Visit every valid value in the [Prison] that is not currently referenced with a mutable reference,
+    /// in index order, and remove every value for which `predicate` returns `false`, returning the number
+    /// of values removed
     ///
-    /// [PrisonSliceMut<T>] implements [Deref<Target = \[&mut T\]>](Deref), [DerefMut<Target = \[&mut T\]>](DerefMut), [AsRef<\[&mut T\]>](AsRef), [AsMut<\[&mut T\]>](AsMut),
-    /// [Borrow<\[&mut T\]>](Borrow), and [BorrowMut<\[&mut T\]>](BorrowMut) to allow transparent access to its underlying slice of values
-    ///
-    /// As long as the [PrisonSliceMut] remains in scope, the elements where it's values reside in the
-    /// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
-    /// You can manually drop the [PrisonSliceMut] out of scope by passing it as the first parameter
-    /// to the function [PrisonSliceMut::unguard(p_sli_mut)]
+    /// Each value is removed exactly as if [Prison::remove()] was called on it after the closure returns,
+    /// so its cell is returned to the free list and its [CellKey] (and any copies of it) will return
+    /// [AccessError::ValueDeleted(idx, gen)] afterwards. Values that are already referenced are skipped
+    /// and kept, and pinned values (see [Prison::pin()]) are visited but always kept
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
     /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// prison.insert(20)?;
-    /// prison.insert(30)?;
-    /// let mut grd_0_1_2 = prison.guard_many_mut_idx(&[0, 1, 2])?;
-    /// assert_eq!(*grd_0_1_2[0], 10);
-    /// *grd_0_1_2[0] = 20;
-    /// PrisonSliceMut::unguard(grd_0_1_2);
-    /// prison.visit_many_ref_idx(&[0, 1, 2], |vals_0_1_2| {
-    ///     assert_eq!(*vals_0_1_2[0], 20);
-    ///     Ok(())
-    /// });
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(15)?;
+    /// let key_2 = prison.insert(20)?;
+    /// let removed = prison.retain(|_, val| {
+    ///     *val += 1;
+    ///     *val % 2 == 1
+    /// })?;
+    /// assert_eq!(removed, 1);
+    /// assert_eq!(prison.clone_val(key_0)?, 11);
+    /// assert!(prison.clone_val(key_1).is_err());
+    /// assert_eq!(prison.clone_val(key_2)?, 21);
+    /// assert_eq!(prison.insert(30)?.idx(), 1);
     /// # Ok(())
     /// # }
     /// ```
     /// ## Errors
-    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
-    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if any element has any number of immutable references
-    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
-    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::MaxValueForGenerationReached] if a value could not be removed because the generation counter is exhausted
+    pub fn retain<F>(&self, mut predicate: F) -> Result<usize, AccessError>
+    where
+        F: FnMut(CellKey, &mut T) -> bool,
+    {
+        self._check_depth()?;
+        self._check_unsealed()?;
+        let mut removed = 0;
+        let mut idx = 0;
+        while idx < internal!(self).vec.len() {
+            let gen = match &internal!(self).vec[idx] {
+                cell if cell.is_cell() && cell.refs_or_next == 0 => IdxD::val(cell.d_gen_or_prev),
+                _ => {
+                    idx += 1;
+                    continue;
+                }
+            };
+            let key = CellKey { idx, gen };
+            let (cell, accesses) = self._add_mut_ref(idx, gen, true)?;
+            let release = RefRelease::new(self, &mut cell.refs_or_next, |refs| _remove_mut_ref(refs, accesses));
+            let keep = self._visit(|| self._decoded_mut(unsafe { cell.val.assume_init_mut() }, |val| predicate(key, val)));
+            drop(release);
+            if !keep && !internal!(self).is_pinned(idx) {
+                self.remove(key)?;
+                removed += 1;
+            }
+            idx += 1;
+        }
+        return Ok(removed);
+    }
+
+    //FN Prison::visit_batch_mut()
+    /// #### This operation has O(N) time complexity (where N is the number of keys)
+    ///
+    /// Visit the value of every [CellKey] in `keys` with a mutable reference, one at a time and in order,
+    /// continuing past failures and returning every error with the position of the key that caused it
+    ///
+    /// Unlike [Prison::visit_many_mut()], only one value is referenced at a time, so the same key may appear more than once.
+    /// Errors from acquiring the reference and errors returned by `operation` are both collected into the
+    /// returned [BatchError], which makes this well suited to validating a large imported dataset in a single pass
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// prison.insert(20)?;
-    /// prison.insert(30)?;
-    /// prison.insert(40)?;
-    /// prison.remove_idx(3)?;
-    /// let mut grd_0_1_2 = prison.guard_many_mut_idx(&[0, 1, 2])?;
-    /// assert!(prison.guard_many_mut_idx(&[0, 1, 2]).is_err());
-    /// assert!(prison.guard_many_mut_idx(&[5]).is_err());
-    /// assert!(prison.guard_many_mut_idx(&[3]).is_err());
+    /// let prison: Prison<i32> = Prison::new();
+    /// let keys: Vec<CellKey> = [4, -1, 7, -3].into_iter().map(|val| prison.insert(val)).collect::<Result<_, _>>()?;
+    /// let err = prison.visit_batch_mut(&keys, |key, val| {
+    ///     if *val < 0 {
+    ///         return Err(AccessError::IndexIsNotFree(key.idx()));
+    ///     }
+    ///     *val *= 2;
+    ///     Ok(())
+    /// }).unwrap_err();
+    /// assert_eq!(err.positions().collect::<Vec<_>>(), vec![1, 3]);
+    /// assert_eq!(prison.clone_val(keys[2])?, 14);
     /// # Ok(())
     /// # }
     /// ```
-    #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_many_mut_idx<'a>(
-        &'a self,
-        indexes: &[usize],
-    ) -> Result<PrisonSliceMut<'a, T>, AccessError> {
-        let (vals, refs, prison_accesses) = self._add_many_mut_refs_idx(indexes)?;
-        return Ok(PrisonSliceMut {
-            vals,
-            refs,
-            prison_accesses,
-        });
+    /// ## Errors
+    /// - [BatchError] holding, for every position that failed, any error [Prison::visit_mut()] can return for that key
+    pub fn visit_batch_mut<F>(&self, keys: &[CellKey], mut operation: F) -> Result<(), BatchError>
+    where
+        F: FnMut(CellKey, &mut T) -> Result<(), AccessError>,
+    {
+        let mut errors = Vec::new();
+        for (pos, key) in keys.iter().copied().enumerate() {
+            if let Err(err) = self.visit_mut(key, |val| operation(key, val)) {
+                errors.push((pos, err));
+            }
+        }
+        return BatchError::from_errors(errors);
     }
 
-    //FN Prison::guard_many_ref_idx()
-    /// Return a [PrisonSliceRef] that marks all the elements as immutably referenced and wraps
-    /// them in guarding data that automatically decreases their immutable reference counts when it goes out of range.
-    ///
-    /// Similar to `guard_many_ref()` but ignores the generation counter
+    //FN Prison::visit_batch_ref()
+    /// #### This operation has O(N) time complexity (where N is the number of keys)
     ///
-    /// [PrisonSliceRef<T>] implements [Deref<Target = \[&T\]>](Deref), [AsRef<\[&T\]>](AsRef),
-    /// and [Borrow<\[&T\]>](Borrow), to allow transparent access to its underlying slice of values
+    /// Visit the value of every [CellKey] in `keys` with an immutable reference, one at a time and in order,
+    /// continuing past failures and returning every error with the position of the key that caused it
     ///
-    /// As long as the [PrisonSliceRef] remains in scope, the elements where it's values reside in the
-    /// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
-    /// You can manually drop the [PrisonSliceRef] out of scope by passing it as the first parameter
-    /// to the function [PrisonSliceRef::unguard(p_sli_ref)]
+    /// See [Prison::visit_batch_mut()] for details
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
     /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// prison.insert(20)?;
-    /// prison.insert(30)?;
-    /// let mut grd_0_1_2 = prison.guard_many_ref_idx(&[0, 1, 2])?;
-    /// assert_eq!(*grd_0_1_2[0], 10);
-    /// prison.visit_many_ref_idx(&[0, 1, 2], |vals_0_1_2| {
-    ///     assert_eq!(*vals_0_1_2[0], 10);
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let grd_1 = prison.guard_mut(key_1)?;
+    /// let mut sum = 0;
+    /// let err = prison.visit_batch_ref(&[key_0, key_1, key_0], |_, val| {
+    ///     sum += val;
     ///     Ok(())
-    /// });
-    /// PrisonSliceRef::unguard(grd_0_1_2);
+    /// }).unwrap_err();
+    /// assert_eq!(sum, 20);
+    /// assert_eq!(err.first(), (1, &AccessError::ValueAlreadyMutablyReferenced(1)));
     /// # Ok(())
     /// # }
     /// ```
     /// ## Errors
-    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
-    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references to any element
-    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
-    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted
+    /// - [BatchError] holding, for every position that failed, any error [Prison::visit_ref()] can return for that key
+    pub fn visit_batch_ref<F>(&self, keys: &[CellKey], mut operation: F) -> Result<(), BatchError>
+    where
+        F: FnMut(CellKey, &T) -> Result<(), AccessError>,
+    {
+        let mut errors = Vec::new();
+        for (pos, key) in keys.iter().copied().enumerate() {
+            if let Err(err) = self.visit_ref(key, |val| operation(key, val)) {
+                errors.push((pos, err));
+            }
+        }
+        return BatchError::from_errors(errors);
+    }
+
+    //FN Prison::guard_mut()
+    /// Return a [PrisonValueMut] that contains a mutable reference to the element and wraps it in
+    /// guarding data that automatically frees its reference count it when it goes out of scope.
+    ///
+    /// [PrisonValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
+    /// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value, as well as
+/// [ValueGuardRef<T>] and [ValueGuardMut<T>] so it can be passed to code that accepts any guard
+    ///
+    /// As long as the [PrisonValueMut] remains in scope, the element where it's value resides in the
+    /// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
+    /// You can manually drop the [PrisonValueMut] out of scope by passing it as the first parameter
+    /// to the function [PrisonValueMut::unguard(p_val_mut)]
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
     /// # fn main() -> Result<(), AccessError> {
     /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// prison.insert(20)?;
-    /// prison.insert(30)?;
-    /// prison.insert(40)?;
-    /// prison.remove_idx(3)?;
-    /// let grd_0_1_2 = prison.guard_many_ref_idx(&[0, 1, 2])?;
-    /// assert!(prison.guard_many_mut_idx(&[0]).is_err());
-    /// assert!(prison.guard_many_ref_idx(&[5]).is_err());
-    /// assert!(prison.guard_many_ref_idx(&[3]).is_err());
+    /// let key_0 = prison.insert(10)?;
+    /// let mut grd_0 = prison.guard_mut(key_0)?;
+    /// assert_eq!(*grd_0, 10);
+    /// *grd_0 = 20;
+    /// PrisonValueMut::unguard(grd_0);
+    /// prison.visit_ref(key_0, |val_0| {
+    ///     assert_eq!(*val_0, 20);
+    ///     Ok(())
+    /// });
     /// # Ok(())
     /// # }
     /// ```
-    #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_many_ref_idx<'a>(
-        &'a self,
-        indexes: &[usize],
-    ) -> Result<PrisonSliceRef<'a, T>, AccessError> {
-        let (vals, refs, prison_accesses) = self._add_many_imm_refs_idx(indexes)?;
-        return Ok(PrisonSliceRef {
-            vals,
-            refs,
-            prison_accesses,
-        });
-    }
-
-    //FN Prison::guard_slice_mut()
-    /// Return a [PrisonSliceMut] that marks all the elements as mutably referenced and wraps
-    /// them in guarding data that automatically frees their mutable reference counts when it goes out of range.
-    ///
-    /// Internally this is strictly identical to passing [Prison::guard_many_mut_idx()] a list of all
-    /// indexes in the slice range, and is subject to all the same restrictions and errors
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if element has any number of immutable references
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation does not match
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
     /// # fn main() -> Result<(), AccessError> {
-    /// let u32_prison: Prison<u32> = Prison::new();
-    /// u32_prison.insert(42)?;
-    /// u32_prison.insert(43)?;
-    /// u32_prison.insert(44)?;
-    /// u32_prison.insert(45)?;
-    /// u32_prison.insert(46)?;
-    /// let grd_last_three = u32_prison.guard_slice_mut(2..5)?;
-    /// assert_eq!(*grd_last_three[0], 44);
-    /// assert_eq!(*grd_last_three[1], 45);
-    /// assert_eq!(*grd_last_three[2], 46);
+    /// let prison: Prison<u32> = Prison::with_capacity(2);
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1_a = prison.insert(20)?;
+    /// let key_out_of_bounds = CellKey::from_raw_parts(10, 0);
+    /// prison.remove(key_1_a)?;
+    /// let key_1_b = prison.insert(30)?;
+    /// let mut grd_0 = prison.guard_mut(key_0)?;
+    /// assert!(prison.guard_mut(key_0).is_err());
+    /// assert!(prison.guard_mut(key_out_of_bounds).is_err());
+    /// assert!(prison.guard_mut(key_1_a).is_err());
     /// # Ok(())
     /// # }
     /// ```
-    /// Any standard [Range<usize>](std::ops::Range) notation is allowed as the first paramater,
-    /// but care must be taken because it is not guaranteed every index within range is a valid
-    /// value or does not have any other references to it that would violate Rust's memory safety.
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_mut<'a>(&'a self, key: CellKey) -> Result<PrisonValueMut<'a, T, S>, AccessError> {
+        let (cell, visits) = self._add_mut_ref(key.idx, key.gen, true)?;
+        return Ok(PrisonValueMut {
+            cell,
+            prison_accesses: visits,
+            prison: self,
+            idx: key.idx,
+        });
+    }
+
+    //FN Prison::entry_mut()
+    /// Exactly like [Prison::guard_mut()], but returning a [PrisonEntryMut] that also exposes the [CellKey]
+    /// of the value and can remove it with [PrisonEntryMut::remove()]
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let u32_prison: Prison<u32> = Prison::new();
-    /// u32_prison.insert(42)?;
-    /// u32_prison.insert(43)?;
-    /// u32_prison.insert(44)?;
-    /// u32_prison.insert(45)?;
-    /// u32_prison.insert(46)?;
-    /// assert!(u32_prison.guard_slice_mut(2..5).is_ok());
-    /// assert!(u32_prison.guard_slice_mut(2..=4).is_ok());
-    /// assert!(u32_prison.guard_slice_mut(2..).is_ok());
-    /// assert!(u32_prison.guard_slice_mut(..3).is_ok());
-    /// assert!(u32_prison.guard_slice_mut(..=3).is_ok());
-    /// assert!(u32_prison.guard_slice_mut(..).is_ok());
-    /// u32_prison.remove_idx(2)?;
-    /// assert!(u32_prison.guard_slice_mut(..).is_err());
+    /// let prison: Prison<String> = Prison::new();
+    /// let key_0 = prison.insert(String::from("done"))?;
+    /// let entry = prison.entry_mut(key_0)?;
+    /// assert!(prison.remove(key_0).is_err());
+    /// if entry.as_str() == "done" {
+    ///     assert_eq!(entry.remove()?, "done");
+    /// }
+    /// assert!(prison.visit_ref(key_0, |_| Ok(())).is_err());
     /// # Ok(())
     /// # }
     /// ```
-    /// See [Prison::guard_many_mut_idx()] for more info
+    /// ## Errors
+    /// Same as [Prison::guard_mut()]
     #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_slice_mut<'a, R>(&'a self, range: R) -> Result<PrisonSliceMut<'a, T>, AccessError>
-    where
-        R: RangeBounds<usize>,
-    {
-        let (start, end) = extract_true_start_end(range, self.vec_len());
-        let idxs: Vec<usize> = (start..end).into_iter().collect();
-        return self.guard_many_mut_idx(&idxs);
+    pub fn entry_mut<'a>(&'a self, key: CellKey) -> Result<PrisonEntryMut<'a, T, S>, AccessError> {
+        return Ok(self.guard_mut(key)?.into_entry());
     }
 
-    //FN Prison::guard_slice_ref()
-    /// Return a [PrisonSliceRef] that marks all the elements as immutably referenced and wraps
-    /// them in guarding data that automatically decreases their immutable reference counts when it goes out of range.
+    //FN Prison::guard_ref()
+    /// Return a [PrisonValueRef] that contains an immutable reference to the element and wraps it in
+    /// guarding data that automatically decrements its reference count it when it goes out of scope.
     ///
-    /// Internally this is strictly identical to passing [Prison::guard_many_ref_idx()] a list of all
-    /// indexes in the slice range, and is subject to all the same restrictions and errors
+    /// [PrisonValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], and
+    /// [Borrow<T>] to allow transparent access to its underlying value
+    ///
+    /// As long as the [PrisonValueRef] remains in scope, the element where it's value resides in the
+    /// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
+    /// You can manually drop the [PrisonValueRef] out of scope by passing it as the first parameter
+    /// to the function [PrisonValueRef::unguard(p_val_ref)]
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
     /// # fn main() -> Result<(), AccessError> {
-    /// let u32_prison: Prison<u32> = Prison::new();
-    /// u32_prison.insert(42)?;
-    /// u32_prison.insert(43)?;
-    /// u32_prison.insert(44)?;
-    /// u32_prison.insert(45)?;
-    /// u32_prison.insert(46)?;
-    /// let grd_last_three = u32_prison.guard_slice_ref(2..5)?;
-    /// let grd_all = u32_prison.guard_slice_ref(..)?;
-    /// assert_eq!(*grd_all[0], 42);
-    /// assert_eq!(*grd_all[1], 43);
-    /// assert_eq!(*grd_last_three[0], 44);
-    /// assert_eq!(*grd_last_three[1], 45);
-    /// assert_eq!(*grd_last_three[2], 46);
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// assert_eq!(*grd_0, 10);
+    /// prison.visit_ref(key_0, |val_0| {
+    ///     assert_eq!(*val_0, 10);
+    ///     Ok(())
+    /// });
+    /// assert_eq!(*grd_0, 10);
+    /// PrisonValueRef::unguard(grd_0);
     /// # Ok(())
     /// # }
     /// ```
-    /// Any standard [Range<usize>](std::ops::Range) notation is allowed as the first paramater,
-    /// but care must be taken because it is not guaranteed every index within range is a valid
-    /// value or does not have any other references to it that would violate Rust's memory safety.
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references already
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation doe not match
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
     /// # fn main() -> Result<(), AccessError> {
-    /// let u32_prison: Prison<u32> = Prison::new();
-    /// u32_prison.insert(42)?;
-    /// u32_prison.insert(43)?;
-    /// u32_prison.insert(44)?;
-    /// u32_prison.insert(45)?;
-    /// u32_prison.insert(46)?;
-    /// assert!(u32_prison.guard_slice_ref(2..5).is_ok());
-    /// assert!(u32_prison.guard_slice_ref(2..=4).is_ok());
-    /// assert!(u32_prison.guard_slice_ref(2..).is_ok());
-    /// assert!(u32_prison.guard_slice_ref(..3).is_ok());
-    /// assert!(u32_prison.guard_slice_ref(..=3).is_ok());
-    /// assert!(u32_prison.guard_slice_ref(..).is_ok());
-    /// u32_prison.remove_idx(2)?;
-    /// assert!(u32_prison.guard_slice_ref(..).is_err());
+    /// let prison: Prison<u32> = Prison::with_capacity(2);
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1_a = prison.insert(20)?;
+    /// let key_out_of_bounds = CellKey::from_raw_parts(10, 0);
+    /// prison.remove(key_1_a)?;
+    /// let key_1_b = prison.insert(30)?;
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// assert!(prison.guard_mut(key_0).is_err());
+    /// assert!(prison.guard_ref(key_out_of_bounds).is_err());
+    /// assert!(prison.guard_ref(key_1_a).is_err());
     /// # Ok(())
     /// # }
     /// ```
-    /// See [Prison::guard_many_ref_idx()] for more info
     #[must_use = "guarded reference will immediately fall out of scope"]
-    pub fn guard_slice_ref<'a, R>(&'a self, range: R) -> Result<PrisonSliceRef<'a, T>, AccessError>
-    where
-        R: RangeBounds<usize>,
-    {
-        let (start, end) = extract_true_start_end(range, self.vec_len());
-        let idxs: Vec<usize> = (start..end).into_iter().collect();
-        return self.guard_many_ref_idx(&idxs);
+    pub fn guard_ref<'a>(&'a self, key: CellKey) -> Result<PrisonValueRef<'a, T, S>, AccessError> {
+        let (cell, visits) = self._add_imm_ref(key.idx, key.gen, true)?;
+        return Ok(PrisonValueRef {
+            cell,
+            prison_accesses: visits,
+            prison: self,
+            idx: key.idx,
+        });
     }
 
-    //FN Prison::clone_val()
-    /// Clones the requested value out of the [Prison] into a new variable
+    //FN Prison::guard_mut_idx()
+    /// Return a [PrisonValueMut] that contains a mutable reference to the element and wraps it in
+    /// guarding data that automatically frees its reference count it when it goes out of scope.
     ///
-    /// Only available when elements of type T implement [Clone] (it is assumed that the implementation of `T::clone()` is memory safe).
+    /// Smilar to `guard_mut()` but ignores the generation counter
     ///
-    /// Because cloning does not alter the original, and because the new variable to hold the clone does not have any presumtions about the value, it
-    /// is safe (in a single-threaded context) to clone out the value even if it is being visited or guarded.
+    /// [PrisonValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
+    /// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value, as well as
+/// [ValueGuardRef<T>] and [ValueGuardMut<T>] so it can be passed to code that accepts any guard
     ///
-    /// This method *will* still return an error if the index or generation of the [CellKey] are invalid
+    /// As long as the [PrisonValueMut] remains in scope, the element where it's value resides in the
+    /// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
+    /// You can manually drop the [PrisonValueMut] out of scope by passing it as the first parameter
+    /// to the function [PrisonValueMut::unguard(p_val_mut)]
     /// ### Example
     /// ```rust
     /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<String> = Prison::new();
-    /// let key_0 = prison.insert(String::from("Foo"))?;
-    /// let key_1 = prison.insert(String::from("Bar"))?;
-    /// let mut take_foo = String::new();
-    /// let mut take_bar = String::new();
-    /// prison.visit_mut(key_0, |val_0| {
-    ///     take_foo = prison.clone_val(key_0)?;
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// let mut grd_0 = prison.guard_mut_idx(0)?;
+    /// assert_eq!(*grd_0, 10);
+    /// *grd_0 = 20;
+    /// PrisonValueMut::unguard(grd_0);
+    /// prison.visit_ref_idx(0, |val_0| {
+    ///     assert_eq!(*val_0, 20);
     ///     Ok(())
     /// });
-    /// let grd_1 = prison.guard_mut(key_1)?;
-    /// take_bar = prison.clone_val(key_1)?;
-    /// PrisonValueMut::unguard(grd_1);
-    /// assert_eq!(take_foo, String::from("Foo"));
-    /// assert_eq!(take_bar, String::from("Bar"));
-    /// prison.remove(key_1)?;
-    /// assert!(prison.clone_val(CellKey::from_raw_parts(10, 10)).is_err());
-    /// assert!(prison.clone_val(key_1).is_err());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn clone_val(&self, key: CellKey) -> Result<T, AccessError>
-    where
-        T: Clone,
-    {
-        let internal = internal!(self);
-        if key.idx >= internal.vec.len() {
-            return Err(AccessError::IndexOutOfRange(key.idx));
-        }
-        match &internal.vec[key.idx] {
-            cell if cell.is_cell_and_gen_match(key.gen) => {
-                return Ok(unsafe { cell.val.assume_init_ref().clone() });
-            }
-            _ => return Err(AccessError::ValueDeleted(key.idx, key.gen)),
-        }
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if element has any number of immutable references
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::with_capacity(2);
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// prison.remove_idx(1)?;
+    /// let mut grd_0 = prison.guard_mut_idx(0)?;
+    /// assert!(prison.guard_mut_idx(0).is_err());
+    /// assert!(prison.guard_mut_idx(5).is_err());
+    /// assert!(prison.guard_mut_idx(1).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_mut_idx<'a>(&'a self, idx: usize) -> Result<PrisonValueMut<'a, T, S>, AccessError> {
+        let idx = self._idx(idx)?;
+        let (cell, visits) = self._add_mut_ref(idx, 0, false)?;
+        return Ok(PrisonValueMut {
+            cell,
+            prison_accesses: visits,
+            prison: self,
+            idx,
+        });
     }
 
-    //FN Prison::clone_val_idx()
-    /// Clones the requested value out of the [Prison] into a new variable
-    ///
-    /// Same as `clone_val()` but ignores the generation counter
+    //FN Prison::guard_ref_idx()
+    /// Return a [PrisonValueRef] that contains an immutable reference to the element and wraps it in
+    /// guarding data that automatically decrements its reference count it when it goes out of scope.
     ///
-    /// Only available when elements of type T implement [Clone] (it is assumed that the implementation of `T::clone()` is memory safe).
+    /// Similar to `guard_ref()` but ignores the generation counter
     ///
-    /// Because cloning does not alter the original, and because the new variable to hold the clone does not have any presumtions about the value, it
-    /// is safe (in a single-threaded context) to clone out the value even if it is being visited or guarded.
+    /// [PrisonValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], and
+    /// [Borrow<T>] to allow transparent access to its underlying value
     ///
-    /// This method *will* still return an error if the index is invalid or the value is free/deleted
+    /// As long as the [PrisonValueRef] remains in scope, the element where it's value resides in the
+    /// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
+    /// You can manually drop the [PrisonValueRef] out of scope by passing it as the first parameter
+    /// to the function [PrisonValueRef::unguard(p_val_ref)]
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<String> = Prison::new();
-    /// prison.insert(String::from("Foo"))?;
-    /// prison.insert(String::from("Bar"))?;
-    /// let mut take_foo = String::new();
-    /// let mut take_bar = String::new();
-    /// prison.visit_mut_idx(0, |val_0| {
-    ///     take_foo = prison.clone_val_idx(0)?;
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// let grd_0 = prison.guard_ref_idx(0)?;
+    /// assert_eq!(*grd_0, 10);
+    /// prison.visit_ref_idx(0, |val_0| {
+    ///     assert_eq!(*val_0, 10);
     ///     Ok(())
     /// });
-    /// let grd_1 = prison.guard_mut_idx(1)?;
-    /// take_bar = prison.clone_val_idx(1)?;
-    /// PrisonValueMut::unguard(grd_1);
-    /// assert_eq!(take_foo, String::from("Foo"));
-    /// assert_eq!(take_bar, String::from("Bar"));
+    /// assert_eq!(*grd_0, 10);
+    /// PrisonValueRef::unguard(grd_0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if element is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references already
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation doe not match
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::with_capacity(2);
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
     /// prison.remove_idx(1)?;
-    /// assert!(prison.clone_val_idx(10).is_err());
-    /// assert!(prison.clone_val_idx(1).is_err());
+    /// let grd_0 = prison.guard_ref_idx(0)?;
+    /// assert!(prison.guard_mut_idx(0).is_err());
+    /// assert!(prison.guard_ref_idx(5).is_err());
+    /// assert!(prison.guard_ref_idx(1).is_err());
     /// # Ok(())
     /// # }
     /// ```
-    pub fn clone_val_idx(&self, idx: usize) -> Result<T, AccessError>
-    where
-        T: Clone,
-    {
-        let internal = internal!(self);
-        if idx >= internal.vec.len() {
-            return Err(AccessError::IndexOutOfRange(idx));
-        }
-        match &internal.vec[idx] {
-            cell if cell.is_cell() => {
-                return Ok(unsafe { cell.val.assume_init_ref().clone() });
-            }
-            _ => return Err(AccessError::ValueDeleted(idx, 0)),
-        }
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_ref_idx<'a>(&'a self, idx: usize) -> Result<PrisonValueRef<'a, T, S>, AccessError> {
+        let idx = self._idx(idx)?;
+        let (cell, visits) = self._add_imm_ref(idx, 0, false)?;
+        return Ok(PrisonValueRef {
+            cell,
+            prison_accesses: visits,
+            prison: self,
+            idx,
+        });
     }
 
-    //FN Prison::clone_many_vals()
-    /// Clones the requested values out of the [Prison] into a new [Vec<T>]
-    ///
-    /// Only available when elements of type T implement [Clone] (it is assumed that the implementation of `T::clone()` is memory safe).
+    //FN Prison::guard_mut_when_free()
+    /// Return a [Future] that resolves to a [PrisonValueMut] once the value at the [CellKey] can be mutably referenced
     ///
-    /// Because cloning does not alter the originals, and because the new variables to hold the clones do not have any presumtions about the values, it
-    /// is safe (in a single-threaded context) to clone out the values even if they are being visited or guarded.
+    /// Every time the future is polled it tries [Prison::guard_mut()]. If the value is still referenced by
+    /// another `visit()` or `guard()`, the task's [Waker] is stored in the [Prison] and woken as soon as any value in the
+    /// [Prison] is released, so an async task can simply `.await` access instead of retrying in a loop.
+    /// Any other error (such as the value being deleted) resolves the future immediately
     ///
-    /// This method *will* still return an error if any index or generation of the [CellKey]s are invalid
+    /// The future holds no references until it resolves, so dropping it at any point is safe. This is meant for
+    /// single-threaded executors, since [Prison] itself cannot be shared between threads
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # use std::{future::Future, pin::pin, task::{Context, Poll, Waker}};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<String> = Prison::new();
-    /// let key_0 = prison.insert(String::from("Foo"))?;
-    /// let key_1 = prison.insert(String::from("Bar"))?;
-    /// let mut take_foobar: Vec<String> = Vec::new();
-    /// prison.visit_mut(key_0, |val_0| {
-    ///     let grd_1 = prison.guard_mut(key_1)?;
-    ///     take_foobar = prison.clone_many_vals(&[key_0, key_1])?;
-    ///     PrisonValueMut::unguard(grd_1);
-    ///     Ok(())
-    /// });
-    /// assert_eq!(take_foobar[0], String::from("Foo"));
-    /// assert_eq!(take_foobar[1], String::from("Bar"));
-    /// prison.remove(key_1)?;
-    /// assert!(prison.clone_many_vals(&[CellKey::from_raw_parts(10, 10)]).is_err());
-    /// assert!(prison.clone_many_vals(&[key_1]).is_err());
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// let mut when_free = pin!(prison.guard_mut_when_free(key_0));
+    /// assert!(when_free.as_mut().poll(&mut cx).is_pending());
+    /// drop(grd_0);
+    /// match when_free.as_mut().poll(&mut cx) {
+    ///     Poll::Ready(Ok(mut grd_0)) => *grd_0 += 1,
+    ///     _ => panic!("value should be free"),
+    /// }
+    /// assert_eq!(prison.clone_val(key_0)?, 11);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn clone_many_vals(&self, keys: &[CellKey]) -> Result<Vec<T>, AccessError>
-    where
-        T: Clone,
-    {
-        let mut vals = Vec::with_capacity(keys.len());
-        for key in keys {
-            vals.push(self.clone_val(*key)?);
-        }
-        return Ok(vals);
+    /// Inside an async function this is simply:
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// async fn add_gold(prison: &Prison<u32>, key: CellKey) -> Result<(), AccessError> {
+    ///     let mut gold = prison.guard_mut_when_free(key).await?;
+    ///     *gold += 10;
+    ///     Ok(())
+    /// }
+    /// ```
+    /// ## Errors
+    /// The future resolves to the same non-contention errors as [Prison::guard_mut()]
+    pub fn guard_mut_when_free<'a>(&'a self, key: CellKey) -> GuardMutWhenFree<'a, T, S> {
+        return GuardMutWhenFree { prison: self, key };
     }
 
-    //FN Prison::clone_many_vals_idx()
-    /// Clones the requested values out of the [Prison] into a new [Vec<T>]
+    //FN Prison::guard_ref_when_free()
+    /// Return a [Future] that resolves to a [PrisonValueRef] once the value at the [CellKey] can be immutably referenced
     ///
-    /// Same as `clone_many_vals()` but ignores the generation counter
+    /// See [Prison::guard_mut_when_free()] for details
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # use std::{future::Future, pin::pin, task::{Context, Poll, Waker}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let grd_0 = prison.guard_mut(key_0)?;
+    /// let mut cx = Context::from_waker(Waker::noop());
+    /// let mut when_free = pin!(prison.guard_ref_when_free(key_0));
+    /// assert!(when_free.as_mut().poll(&mut cx).is_pending());
+    /// drop(grd_0);
+    /// assert!(matches!(when_free.as_mut().poll(&mut cx), Poll::Ready(Ok(_))));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// The future resolves to the same non-contention errors as [Prison::guard_ref()]
+    pub fn guard_ref_when_free<'a>(&'a self, key: CellKey) -> GuardRefWhenFree<'a, T, S> {
+        return GuardRefWhenFree { prison: self, key };
+    }
+
+    //FN Prison::iter_mut()
+    /// Return an iterator over every valid value in the [Prison] in index order, yielding the [CellKey]
+    /// of each value along with a [PrisonValueMut] guard to it
     ///
-    /// Only available when elements of type T implement [Clone] (it is assumed that the implementation of `T::clone()` is memory safe).
+    /// Free cells are never visited. Values that cannot be mutably referenced when the iterator reaches them
+    /// are either skipped or yielded as an [AccessError], according to the [IterPolicy]. Each guard
+    /// follows the normal reference rules, so guards may be kept past the next call to `next()` or dropped
+    /// immediately. Values inserted at indexes the iterator has not reached yet will also be visited
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{IterPolicy, Prison}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// prison.remove(key_1)?;
+    /// for item in prison.iter_mut(IterPolicy::Error) {
+    ///     let (key, mut grd) = item?;
+    ///     *grd += key.idx() as u32;
+    /// }
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// let mut iter = prison.iter_mut(IterPolicy::Error);
+    /// assert_eq!(iter.next().unwrap().err(), Some(AccessError::ValueStillImmutablyReferenced(0)));
+    /// assert_eq!(*iter.next().unwrap()?.1, 32);
+    /// let skipped: Vec<CellKey> = prison.iter_mut(IterPolicy::Skip).map(|item| item.unwrap().0).collect();
+    /// assert_eq!(skipped, vec![key_2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_mut(&self, policy: IterPolicy) -> PrisonIterMut<'_, T, S> {
+        return PrisonIterMut {
+            prison: self,
+            idx: 0,
+            policy,
+        };
+    }
+
+    //FN Prison::iter_ref()
+    /// Return an iterator over every valid value in the [Prison] in index order, yielding the [CellKey]
+    /// of each value along with a [PrisonValueRef] guard to it
     ///
-    /// Because cloning does not alter the originals, and because the new variables to hold the clones do not have any presumtions about the values, it
-    /// is safe (in a single-threaded context) to clone out the values even if they are being visited or guarded.
+    /// See [Prison::iter_mut()], this follows the same rules for immutable references
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{IterPolicy, Prison}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let grd_1 = prison.guard_mut(key_1)?;
+    /// let sum: u32 = prison.iter_ref(IterPolicy::Skip).map(|item| *item.unwrap().1).sum();
+    /// assert_eq!(sum, 10);
+    /// assert!(prison.iter_ref(IterPolicy::Error).any(|item| item.is_err()));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn iter_ref(&self, policy: IterPolicy) -> PrisonIterRef<'_, T, S> {
+        return PrisonIterRef {
+            prison: self,
+            idx: 0,
+            policy,
+        };
+    }
+
+    //FN Prison::map_refs()
+    /// Return a lazy iterator that visits the value at each [CellKey] from `keys` in turn with an immutable
+    /// reference, yielding whatever the closure returns
     ///
-    /// This method *will* still return an error if any index is out-of-range or free/deleted
+    /// Each value is referenced only while its item is being produced: the reference is taken and released
+    /// inside `next()`, exactly like a call to [Prison::visit_ref()]. This makes it safe to compose with
+    /// iterator adapters such as `filter()`, `take()` or `collect()`, and to mutate other values (or even
+    /// the same value) between items. Keys that cannot be visited yield the same error [Prison::visit_ref()]
+    /// would return, and the iterator carries on with the next key
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<String> = Prison::new();
-    /// prison.insert(String::from("Foo"))?;
-    /// prison.insert(String::from("Bar"))?;
-    /// let mut take_foobar: Vec<String> = Vec::new();
-    /// prison.visit_mut_idx(0, |val_0| {
-    ///     let grd_1 = prison.guard_mut_idx(1)?;
-    ///     take_foobar = prison.clone_many_vals_idx(&[0, 1])?;
-    ///     PrisonValueMut::unguard(grd_1);
-    ///     Ok(())
-    /// });
-    /// assert_eq!(take_foobar[0], String::from("Foo"));
-    /// assert_eq!(take_foobar[1], String::from("Bar"));
-    /// prison.remove_idx(1)?;
-    /// assert!(prison.clone_many_vals_idx(&[10]).is_err());
-    /// assert!(prison.clone_many_vals_idx(&[1]).is_err());
+    /// let prison: Prison<u32> = Prison::new();
+    /// let keys = prison.insert_many([5, 12, 7, 30, 18])?;
+    /// let big: Vec<u32> = prison
+    ///     .map_refs(keys.iter().copied(), |val| Ok(*val))
+    ///     .filter_map(Result::ok)
+    ///     .filter(|val| *val > 10)
+    ///     .take(2)
+    ///     .collect();
+    /// assert_eq!(big, vec![12, 30]);
+    /// for doubled in prison.map_refs(keys.iter().copied(), |val| Ok(*val * 2)) {
+    ///     assert!(prison.visit_mut(keys[0], |val| Ok(*val += 1)).is_ok());
+    ///     assert!(doubled? % 2 == 0);
+    /// }
     /// # Ok(())
     /// # }
     /// ```
-    pub fn clone_many_vals_idx(&self, indexes: &[usize]) -> Result<Vec<T>, AccessError>
+    pub fn map_refs<I, R, F>(&self, keys: I, operation: F) -> PrisonMapRefs<'_, T, I::IntoIter, F, S>
     where
-        T: Clone,
+        I: IntoIterator<Item = CellKey>,
+        F: FnMut(&T) -> Result<R, AccessError>,
     {
-        let mut vals = Vec::with_capacity(indexes.len());
-        for idx in indexes {
-            vals.push(self.clone_val_idx(*idx)?);
-        }
-        return Ok(vals);
+        return PrisonMapRefs {
+            prison: self,
+            keys: keys.into_iter(),
+            operation,
+        };
     }
 
-    //FN: Prison::peek_ref()
-    /// Get a reference to a value from it's associated [CellKey], ***ignoring reference counting and most other safety measures***
+    //FN Prison::split_at_view()
+    /// Split the [Prison] into two [PrisonView]'s, one covering the indexes before `idx` and one covering `idx` and every index after it
     ///
-    /// Returns [`Ok(&T)`] if the value exists and the generation matches, [Err(AccessError::ValueDeleted(idx, gen))] otherwise
+    /// Borrowing the [Prison] mutably proves no value is referenced, so each view hands out plain `&T` and `&mut T`
+    /// without any reference counting. Because the two views never overlap, they can be used at the same time, and
+    /// they can be sent to (or shared with) other threads whenever `T` is [Send] (or [Sync]), for example
+    /// with [std::thread::scope()]. Split a view again with [PrisonView::split_at()]
     ///
-    /// This method is provided as a way for libraries depending on this code to perform niche
-    /// optimized reads of contained values without the overhead of the normal safety checks,
-    /// or when a mutable reference is active but you can statically confirm that the value will
-    /// not be mutated from the begining of the returned `&T`'s lifetime to the end of it's lifetime.
+    /// Views cannot insert or remove values, hidden values cannot be accessed through them, and like the other
+    /// guard-style methods they expose the stored (encoded) form of values in a [Prison] created with `with_transform()`.
+    /// Access through a view is not recorded by the `lru` or `dirty` features. Offloaded values are reloaded first
     ///
-    /// The returned references are intended to be short-lived and safely contained in a scope where no
-    /// mutation of the [Prison] as a whole or ANY of its values takes place
-    /// # Safety
-    /// When you call this method and as long as the `&T` it returns remains in-scope/alive,
-    /// you MUST ensure the following:
-    /// - The value MUST NOT be mutated by ANY source, including active safe reference-counted mutable references
-    /// - NO operation can be performed that could *potentially* cause the underlying memory address of the [Prison]'s data to relocate
-    pub unsafe fn peek_ref<'a>(&'a self, key: CellKey) -> Result<&'a T, AccessError> {
-        match &internal!(self).vec[key.idx] {
-            cell if cell.is_cell_and_gen_match(key.gen) => {
-                Ok(unsafe { &cell.val.assume_init_ref() })
-            }
-            _ => Err(AccessError::ValueDeleted(key.idx, key.gen)),
+    /// Like [Prison::partitions()], [PrisonView::start()] and [PrisonView::end()], `idx` is a raw position in the
+    /// [Prison], never an index salted by [Prison::set_idx_validation()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let mut prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let (mut left, mut right) = prison.split_at_view(1)?;
+    /// std::thread::scope(|scope| {
+    ///     scope.spawn(|| *left.get_mut(key_0).unwrap() += 1);
+    ///     scope.spawn(|| *right.get_mut(key_1).unwrap() += 2);
+    /// });
+    /// assert_eq!(left.get(key_1), Err(AccessError::IndexOutOfRange(1)));
+    /// assert_eq!(prison.clone_val(key_0)?, 11);
+    /// assert_eq!(prison.clone_val(key_1)?, 22);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::AccessesOutstanding(count)] if a reference was leaked (for example with [mem::forget()](std::mem::forget))
+    /// - [AccessError::IndexOutOfRange(idx)] if `idx` is greater than the length of the [Prison]
+    /// - [AccessError::OffloadFailed(idx)] if an offloaded value could not be reloaded
+    pub fn split_at_view(&mut self, idx: usize) -> Result<(PrisonView<'_, T>, PrisonView<'_, T>), AccessError> {
+        let cells = self._view_cells()?;
+        if idx > cells.len() {
+            return Err(AccessError::IndexOutOfRange(idx));
         }
+        let (left, right) = cells.split_at_mut(idx);
+        return Ok((
+            PrisonView {
+                start: 0,
+                cells: left,
+            },
+            PrisonView {
+                start: idx,
+                cells: right,
+            },
+        ));
     }
 
-    //FN: Prison::peek_ref_idx()
+    //FN Prison::partitions()
+    /// Split the whole [Prison] into exactly `n` [PrisonView]'s over consecutive, non-overlapping index ranges of (nearly) equal length
+    ///
+    /// The first `len % n` views cover one more index than the rest, and views are empty when `n` is larger than the
+    /// length. This is the same as calling [Prison::split_at_view()] repeatedly, see it for what views can do. An `n` of `0` returns no views
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let mut prison: Prison<u32> = Prison::new();
+    /// for val in 0..10 {
+    ///     prison.insert(val)?;
+    /// }
+    /// let views = prison.partitions(3)?;
+    /// assert_eq!(views.iter().map(|view| view.len()).collect::<Vec<_>>(), vec![4, 3, 3]);
+    /// std::thread::scope(|scope| {
+    ///     for mut view in views {
+    ///         scope.spawn(move || view.iter_mut().for_each(|(_, val)| *val *= 2));
+    ///     }
+    /// });
+    /// assert_eq!(prison.clone_val_idx(9)?, 18);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::AccessesOutstanding(count)] if a reference was leaked (for example with [mem::forget()](std::mem::forget))
+    /// - [AccessError::OffloadFailed(idx)] if an offloaded value could not be reloaded
+    pub fn partitions(&mut self, n: usize) -> Result<Vec<PrisonView<'_, T>>, AccessError> {
+        let mut rest = self._view_cells()?;
+        let mut views = Vec::with_capacity(n);
+        if n == 0 {
+            return Ok(views);
+        }
+        let (size, extra) = (rest.len() / n, rest.len() % n);
+        let mut start = 0;
+        for part in 0..n {
+            let len = size + usize::from(part < extra);
+            let (cells, tail) = rest.split_at_mut(len);
+            views.push(PrisonView { start, cells });
+            start += len;
+            rest = tail;
+        }
+        return Ok(views);
+    }
+
+    //FN Prison::guard_many_mut()
+    /// Return a [PrisonSliceMut] that marks all the elements as mutably referenced and wraps
+    /// them in guarding data that automatically frees their mutable reference counts when it goes out of range.
+    ///
+    /// [PrisonSliceMut<T>] implements [Deref<Target = \[&mut T\]>](Deref), [DerefMut<Target = \[&mut T\]>](DerefMut), [AsRef<\[&mut T\]>](AsRef), [AsMut<\[&mut T\]>](AsMut),
+    /// [Borrow<\[&mut T\]>](Borrow), and [BorrowMut<\[&mut T\]>](BorrowMut) to allow transparent access to its underlying slice of values
+    ///
+    /// As long as the [PrisonSliceMut] remains in scope, the elements where it's values reside in the
+    /// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
+    /// You can manually drop the [PrisonSliceMut] out of scope by passing it as the first parameter
+    /// to the function [PrisonSliceMut::unguard(p_sli_mut)]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// let mut grd_0_1_2 = prison.guard_many_mut(&[key_0, key_1, key_2])?;
+    /// assert_eq!(*grd_0_1_2[0], 10);
+    /// *grd_0_1_2[0] = 20;
+    /// PrisonSliceMut::unguard(grd_0_1_2);
+    /// prison.visit_many_ref(&[key_0, key_1, key_2], |vals_0_1_2| {
+    ///     assert_eq!(*vals_0_1_2[0], 20);
+    ///     Ok(())
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if any element has any number of immutable references
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* the [CellKey] generation doesnt match
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// let key_4_a = prison.insert(40)?;
+    /// prison.remove(key_4_a)?;
+    /// let key_4_b = prison.insert(44)?;
+    /// let key_out_of_bounds = CellKey::from_raw_parts(10, 1);
+    /// let mut grd_0_1_2 = prison.guard_many_mut(&[key_0, key_1, key_2])?;
+    /// assert!(prison.guard_many_mut(&[key_0, key_1, key_2, key_4_b]).is_err());
+    /// assert!(prison.guard_many_mut(&[key_out_of_bounds]).is_err());
+    /// assert!(prison.guard_many_mut(&[key_4_a]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_many_mut<'a>(
+        &'a self,
+        keys: &[CellKey],
+    ) -> Result<PrisonSliceMut<'a, T, S>, AccessError> {
+        let (vals, refs, prison_accesses) = self._add_many_mut_refs(keys)?;
+        return Ok(PrisonSliceMut {
+            vals,
+            refs,
+            prison_accesses,
+            prison: self,
+        });
+    }
+
+    //FN Prison::guard_many_ref()
+    /// Return a [PrisonSliceRef] that marks all the elements as immutably referenced and wraps
+    /// them in guarding data that automatically decreases their immutable reference counts when it goes out of range.
+    ///
+    /// [PrisonSliceRef<T>] implements [Deref<Target = \[&T\]>](Deref), [AsRef<\[&T\]>](AsRef),
+    /// and [Borrow<\[&T\]>](Borrow), to allow transparent access to its underlying slice of values
+    ///
+    /// As long as the [PrisonSliceRef] remains in scope, the elements where it's values reside in the
+    /// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
+    /// You can manually drop the [PrisonSliceRef] out of scope by passing it as the first parameter
+    /// to the function [PrisonSliceRef::unguard(p_sli_ref)]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// let mut grd_0_1_2 = prison.guard_many_ref(&[key_0, key_1, key_2])?;
+    /// assert_eq!(*grd_0_1_2[0], 10);
+    /// prison.visit_many_ref(&[key_0, key_1, key_2], |vals_0_1_2| {
+    ///     assert_eq!(*vals_0_1_2[0], 10);
+    ///     Ok(())
+    /// });
+    /// PrisonSliceRef::unguard(grd_0_1_2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references to any element
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* if the [CellKey] generation doesn't match
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// let key_4_a = prison.insert(40)?;
+    /// prison.remove(key_4_a)?;
+    /// let key_4_b = prison.insert(44)?;
+    /// let key_out_of_bounds = CellKey::from_raw_parts(10, 1);
+    /// let grd_0_1_2 = prison.guard_many_ref(&[key_0, key_1, key_2])?;
+    /// assert!(prison.guard_many_mut(&[key_0]).is_err());
+    /// assert!(prison.guard_many_ref(&[key_out_of_bounds]).is_err());
+    /// assert!(prison.guard_many_ref(&[key_4_a]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_many_ref<'a>(
+        &'a self,
+        keys: &[CellKey],
+    ) -> Result<PrisonSliceRef<'a, T, S>, AccessError> {
+        let (vals, refs, prison_accesses) = self._add_many_imm_refs(keys)?;
+        return Ok(PrisonSliceRef {
+            vals,
+            refs,
+            prison_accesses,
+            prison: self,
+        });
+    }
+
+    //FN Prison::guard_many_mut_idx()
+    /// Return a [PrisonSliceMut] that marks all the elements as mutably referenced and wraps
+    /// them in guarding data that automatically frees their mutable reference counts when it goes out of range.
+    ///
+    /// Similar to `guard_many_mut()` but ignores the generation counter
+    ///
+    /// [PrisonSliceMut<T>] implements [Deref<Target = \[&mut T\]>](Deref), [DerefMut<Target = \[&mut T\]>](DerefMut), [AsRef<\[&mut T\]>](AsRef), [AsMut<\[&mut T\]>](AsMut),
+    /// [Borrow<\[&mut T\]>](Borrow), and [BorrowMut<\[&mut T\]>](BorrowMut) to allow transparent access to its underlying slice of values
+    ///
+    /// As long as the [PrisonSliceMut] remains in scope, the elements where it's values reside in the
+    /// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
+    /// You can manually drop the [PrisonSliceMut] out of scope by passing it as the first parameter
+    /// to the function [PrisonSliceMut::unguard(p_sli_mut)]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// prison.insert(30)?;
+    /// let mut grd_0_1_2 = prison.guard_many_mut_idx(&[0, 1, 2])?;
+    /// assert_eq!(*grd_0_1_2[0], 10);
+    /// *grd_0_1_2[0] = 20;
+    /// PrisonSliceMut::unguard(grd_0_1_2);
+    /// prison.visit_many_ref_idx(&[0, 1, 2], |vals_0_1_2| {
+    ///     assert_eq!(*vals_0_1_2[0], 20);
+    ///     Ok(())
+    /// });
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if any element has any number of immutable references
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// prison.insert(30)?;
+    /// prison.insert(40)?;
+    /// prison.remove_idx(3)?;
+    /// let mut grd_0_1_2 = prison.guard_many_mut_idx(&[0, 1, 2])?;
+    /// assert!(prison.guard_many_mut_idx(&[0, 1, 2]).is_err());
+    /// assert!(prison.guard_many_mut_idx(&[5]).is_err());
+    /// assert!(prison.guard_many_mut_idx(&[3]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_many_mut_idx<'a>(
+        &'a self,
+        indexes: &[usize],
+    ) -> Result<PrisonSliceMut<'a, T, S>, AccessError> {
+        let (vals, refs, prison_accesses) = self._add_many_mut_refs_idx(indexes)?;
+        return Ok(PrisonSliceMut {
+            vals,
+            refs,
+            prison_accesses,
+            prison: self,
+        });
+    }
+
+    //FN Prison::guard_many_ref_idx()
+    /// Return a [PrisonSliceRef] that marks all the elements as immutably referenced and wraps
+    /// them in guarding data that automatically decreases their immutable reference counts when it goes out of range.
+    ///
+    /// Similar to `guard_many_ref()` but ignores the generation counter
+    ///
+    /// [PrisonSliceRef<T>] implements [Deref<Target = \[&T\]>](Deref), [AsRef<\[&T\]>](AsRef),
+    /// and [Borrow<\[&T\]>](Borrow), to allow transparent access to its underlying slice of values
+    ///
+    /// As long as the [PrisonSliceRef] remains in scope, the elements where it's values reside in the
+    /// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
+    /// You can manually drop the [PrisonSliceRef] out of scope by passing it as the first parameter
+    /// to the function [PrisonSliceRef::unguard(p_sli_ref)]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// prison.insert(30)?;
+    /// let mut grd_0_1_2 = prison.guard_many_ref_idx(&[0, 1, 2])?;
+    /// assert_eq!(*grd_0_1_2[0], 10);
+    /// prison.visit_many_ref_idx(&[0, 1, 2], |vals_0_1_2| {
+    ///     assert_eq!(*vals_0_1_2[0], 10);
+    ///     Ok(())
+    /// });
+    /// PrisonSliceRef::unguard(grd_0_1_2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references to any element
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// prison.insert(30)?;
+    /// prison.insert(40)?;
+    /// prison.remove_idx(3)?;
+    /// let grd_0_1_2 = prison.guard_many_ref_idx(&[0, 1, 2])?;
+    /// assert!(prison.guard_many_mut_idx(&[0]).is_err());
+    /// assert!(prison.guard_many_ref_idx(&[5]).is_err());
+    /// assert!(prison.guard_many_ref_idx(&[3]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_many_ref_idx<'a>(
+        &'a self,
+        indexes: &[usize],
+    ) -> Result<PrisonSliceRef<'a, T, S>, AccessError> {
+        let (vals, refs, prison_accesses) = self._add_many_imm_refs_idx(indexes)?;
+        return Ok(PrisonSliceRef {
+            vals,
+            refs,
+            prison_accesses,
+            prison: self,
+        });
+    }
+
+    //FN Prison::guard_pair_mut()
+    /// Return a pair of [PrisonValueMut] guards to exactly two distinct values, in the order they were requested
+    ///
+    /// See [Prison::visit_pair_mut()] for the visiting version. Unlike [Prison::guard_many_mut()], each value
+    /// gets its own guard and nothing is allocated. If the second value cannot be referenced, the first one is
+    /// released again before the error is returned
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let (mut grd_0, grd_1) = prison.guard_pair_mut(key_0, key_1)?;
+    /// *grd_0 += *grd_1;
+    /// drop((grd_0, grd_1));
+    /// assert_eq!(prison.clone_val(key_0)?, 30);
+    /// let grd_1 = prison.guard_ref(key_1)?;
+    /// assert!(prison.guard_pair_mut(key_0, key_1).is_err());
+    /// assert!(prison.guard_mut(key_0).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PairSameIndex(idx)] if both keys have the same index
+    /// - Any error returned by [Prison::guard_mut()] for either key
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    #[allow(clippy::type_complexity)]
+    pub fn guard_pair_mut<'a>(
+        &'a self,
+        key_a: CellKey,
+        key_b: CellKey,
+    ) -> Result<(PrisonValueMut<'a, T, S>, PrisonValueMut<'a, T, S>), AccessError> {
+        if key_a.idx == key_b.idx {
+            return Err(AccessError::PairSameIndex(key_a.idx));
+        }
+        let grd_a = self.guard_mut(key_a)?;
+        let grd_b = self.guard_mut(key_b)?;
+        return Ok((grd_a, grd_b));
+    }
+
+    //FN Prison::guard_array_mut()
+    /// Exactly like [Prison::guard_many_mut()], but taking a fixed-size array of keys and returning a
+    /// [PrisonArrayMut] that dereferences to a fixed-size array of mutable references in the same order
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let mut grd = prison.guard_array_mut([key_0, key_1])?;
+    /// let [val_0, val_1] = &mut *grd;
+    /// std::mem::swap(*val_0, *val_1);
+    /// drop(grd);
+    /// assert_eq!(prison.clone_many_vals(&[key_0, key_1])?, vec![20, 10]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// Same as [Prison::guard_many_mut()]
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_array_mut<'a, const N: usize>(
+        &'a self,
+        keys: [CellKey; N],
+    ) -> Result<PrisonArrayMut<'a, T, N, S>, AccessError> {
+        let (vals, refs, prison_accesses) = self._add_array_mut_refs(&keys)?;
+        return Ok(PrisonArrayMut {
+            vals,
+            refs,
+            prison_accesses,
+            prison: self,
+        });
+    }
+
+    //FN Prison::guard_slice_mut()
+    /// Return a [PrisonSliceMut] that marks all the elements as mutably referenced and wraps
+    /// them in guarding data that automatically frees their mutable reference counts when it goes out of range.
+    ///
+    /// Internally this is strictly identical to passing [Prison::guard_many_mut_idx()] a list of all
+    /// indexes in the slice range, and is subject to all the same restrictions and errors
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// u32_prison.insert(42)?;
+    /// u32_prison.insert(43)?;
+    /// u32_prison.insert(44)?;
+    /// u32_prison.insert(45)?;
+    /// u32_prison.insert(46)?;
+    /// let grd_last_three = u32_prison.guard_slice_mut(2..5)?;
+    /// assert_eq!(*grd_last_three[0], 44);
+    /// assert_eq!(*grd_last_three[1], 45);
+    /// assert_eq!(*grd_last_three[2], 46);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// Any standard [Range<usize>](std::ops::Range) notation is allowed as the first paramater,
+    /// but care must be taken because it is not guaranteed every index within range is a valid
+    /// value or does not have any other references to it that would violate Rust's memory safety.
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// u32_prison.insert(42)?;
+    /// u32_prison.insert(43)?;
+    /// u32_prison.insert(44)?;
+    /// u32_prison.insert(45)?;
+    /// u32_prison.insert(46)?;
+    /// assert!(u32_prison.guard_slice_mut(2..5).is_ok());
+    /// assert!(u32_prison.guard_slice_mut(2..=4).is_ok());
+    /// assert!(u32_prison.guard_slice_mut(2..).is_ok());
+    /// assert!(u32_prison.guard_slice_mut(..3).is_ok());
+    /// assert!(u32_prison.guard_slice_mut(..=3).is_ok());
+    /// assert!(u32_prison.guard_slice_mut(..).is_ok());
+    /// u32_prison.remove_idx(2)?;
+    /// assert!(u32_prison.guard_slice_mut(..).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// See [Prison::guard_many_mut_idx()] for more info
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_slice_mut<'a, R>(&'a self, range: R) -> Result<PrisonSliceMut<'a, T, S>, AccessError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = extract_true_start_end(range, self.vec_len());
+        let idxs: Vec<usize> = (start..end).map(|idx| self.salt_idx(idx)).collect();
+        return self.guard_many_mut_idx(&idxs);
+    }
+
+    //FN Prison::guard_slice_ref()
+    /// Return a [PrisonSliceRef] that marks all the elements as immutably referenced and wraps
+    /// them in guarding data that automatically decreases their immutable reference counts when it goes out of range.
+    ///
+    /// Internally this is strictly identical to passing [Prison::guard_many_ref_idx()] a list of all
+    /// indexes in the slice range, and is subject to all the same restrictions and errors
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// u32_prison.insert(42)?;
+    /// u32_prison.insert(43)?;
+    /// u32_prison.insert(44)?;
+    /// u32_prison.insert(45)?;
+    /// u32_prison.insert(46)?;
+    /// let grd_last_three = u32_prison.guard_slice_ref(2..5)?;
+    /// let grd_all = u32_prison.guard_slice_ref(..)?;
+    /// assert_eq!(*grd_all[0], 42);
+    /// assert_eq!(*grd_all[1], 43);
+    /// assert_eq!(*grd_last_three[0], 44);
+    /// assert_eq!(*grd_last_three[1], 45);
+    /// assert_eq!(*grd_last_three[2], 46);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// Any standard [Range<usize>](std::ops::Range) notation is allowed as the first paramater,
+    /// but care must be taken because it is not guaranteed every index within range is a valid
+    /// value or does not have any other references to it that would violate Rust's memory safety.
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let u32_prison: Prison<u32> = Prison::new();
+    /// u32_prison.insert(42)?;
+    /// u32_prison.insert(43)?;
+    /// u32_prison.insert(44)?;
+    /// u32_prison.insert(45)?;
+    /// u32_prison.insert(46)?;
+    /// assert!(u32_prison.guard_slice_ref(2..5).is_ok());
+    /// assert!(u32_prison.guard_slice_ref(2..=4).is_ok());
+    /// assert!(u32_prison.guard_slice_ref(2..).is_ok());
+    /// assert!(u32_prison.guard_slice_ref(..3).is_ok());
+    /// assert!(u32_prison.guard_slice_ref(..=3).is_ok());
+    /// assert!(u32_prison.guard_slice_ref(..).is_ok());
+    /// u32_prison.remove_idx(2)?;
+    /// assert!(u32_prison.guard_slice_ref(..).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// See [Prison::guard_many_ref_idx()] for more info
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_slice_ref<'a, R>(&'a self, range: R) -> Result<PrisonSliceRef<'a, T, S>, AccessError>
+    where
+        R: RangeBounds<usize>,
+    {
+        let (start, end) = extract_true_start_end(range, self.vec_len());
+        let idxs: Vec<usize> = (start..end).map(|idx| self.salt_idx(idx)).collect();
+        return self.guard_many_ref_idx(&idxs);
+    }
+
+    //FN Prison::guard_set_mut()
+    /// Return a [PrisonSliceMut] that marks every value referenced by a [CellKeySet] as mutably referenced,
+    /// with the values in ascending index order (the same order as [CellKeySet::as_slice()])
+    ///
+    /// See [Prison::visit_set_mut()] for how blocked members are reported
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, CellKeySet, single_threaded::{Prison, PrisonSliceMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let set: CellKeySet = [key_1, key_0].into_iter().collect();
+    /// let mut grd_0_1 = prison.guard_set_mut(&set)?;
+    /// *grd_0_1[1] = 21;
+    /// assert!(prison.visit_ref(key_0, |val_0| Ok(())).is_err());
+    /// PrisonSliceMut::unguard(grd_0_1);
+    /// assert_eq!(prison.clone_val(key_1)?, 21);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::SetMembersBlocked(set)] if any members are already referenced, containing every blocked member
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* the [CellKey] generation doesnt match
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_set_mut<'a>(&'a self, set: &CellKeySet) -> Result<PrisonSliceMut<'a, T, S>, AccessError> {
+        let (vals, refs, prison_accesses) = self._add_set_mut_refs(set)?;
+        return Ok(PrisonSliceMut {
+            vals,
+            refs,
+            prison_accesses,
+            prison: self,
+        });
+    }
+
+    //FN Prison::guard_set_ref()
+    /// Return a [PrisonSliceRef] that marks every value referenced by a [CellKeySet] as immutably referenced,
+    /// with the values in ascending index order (the same order as [CellKeySet::as_slice()])
+    ///
+    /// See [Prison::visit_set_mut()] for how blocked members are reported
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, CellKeySet, single_threaded::{Prison, PrisonSliceRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// let set: CellKeySet = [key_2, key_0].into_iter().collect();
+    /// let grd_0_2 = prison.guard_set_ref(&set)?;
+    /// assert_eq!(*grd_0_2[0], 10);
+    /// assert_eq!(*grd_0_2[1], 30);
+    /// assert!(prison.visit_mut(key_1, |val_1| Ok(())).is_ok());
+    /// PrisonSliceRef::unguard(grd_0_2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::SetMembersBlocked(set)] if any members are already mutably referenced, containing every blocked member
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* the [CellKey] generation doesnt match
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn guard_set_ref<'a>(&'a self, set: &CellKeySet) -> Result<PrisonSliceRef<'a, T, S>, AccessError> {
+        let (vals, refs, prison_accesses) = self._add_set_imm_refs(set)?;
+        return Ok(PrisonSliceRef {
+            vals,
+            refs,
+            prison_accesses,
+            prison: self,
+        });
+    }
+
+    //FN Prison::pin_refs()
+    /// Return a [PinSet] that marks all the elements as immutably referenced for as long as it remains in scope,
+    /// and allows looking up an immutable reference to any of them by [CellKey] or index
+    ///
+    /// This is useful when many separate systems read the same set of "hot" values over and over
+    /// (for example every frame): the reference counts are only incremented once when the [PinSet] is
+    /// created and decremented once when it is dropped, instead of once per read.
+    ///
+    /// Lookups are performed with a binary search over the pinned indexes
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PinSet}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// let pinned = prison.pin_refs(&[key_2, key_0])?;
+    /// assert_eq!(pinned.get(key_0), Some(&10));
+    /// assert_eq!(pinned.get(key_2), Some(&30));
+    /// assert_eq!(pinned.get(key_1), None);
+    /// assert!(prison.visit_mut(key_0, |val_0| Ok(())).is_err());
+    /// assert!(prison.visit_ref(key_0, |val_0| Ok(())).is_ok());
+    /// PinSet::unguard(pinned);
+    /// assert!(prison.visit_mut(key_0, |val_0| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any element is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references to any element
+    /// - [AccessError::IndexOutOfRange(idx)] if any index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if any cell is marked as free/deleted *OR* if the [CellKey] generation doesn't match
+    #[must_use = "pinned references will immediately fall out of scope"]
+    pub fn pin_refs<'a>(&'a self, keys: &[CellKey]) -> Result<PinSet<'a, T, S>, AccessError> {
+        let (vals, refs, prison_accesses) = self._add_many_imm_refs(keys)?;
+        let mut pinned: Vec<(CellKey, &'a T)> = keys.iter().copied().zip(vals.iter().copied()).collect();
+        pinned.sort_unstable_by_key(|(key, _)| key.idx);
+        return Ok(PinSet {
+            prison_accesses,
+            prison: self,
+            refs,
+            pinned,
+        });
+    }
+
+    //FN Prison::snapshot_ref()
+    /// Return a [SnapshotRef] that marks every valid value in the [Prison] as immutably referenced
+    /// and allows natural indexing syntax with either a [CellKey] or a [usize] for as long as it is in scope
+    ///
+    /// Values inserted after the snapshot was taken are not part of it. Indexing a [SnapshotRef] panics
+    /// if the value is not part of it (including when the [CellKey] generation doesn't match),
+    /// use [SnapshotRef::get()] or [SnapshotRef::get_idx()] for a non-panicking lookup
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, SnapshotRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let snapshot = prison.snapshot_ref()?;
+    /// assert_eq!(snapshot[key_0] + snapshot[1], 30);
+    /// assert!(prison.visit_mut(key_1, |val_1| Ok(())).is_err());
+    /// SnapshotRef::unguard(snapshot);
+    /// assert!(prison.visit_mut(key_1, |val_1| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any value is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if you created [usize::MAX] - 2 immutable references to any value
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn snapshot_ref<'a>(&'a self) -> Result<SnapshotRef<'a, T, S>, AccessError> {
+        let internal = internal!(self);
+        let len = internal.vec.len();
+        let keys: Vec<CellKey> = internal
+            .vec
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.is_cell())
+            .map(|(idx, cell)| CellKey {
+                idx,
+                gen: IdxD::val(cell.d_gen_or_prev),
+            })
+            .collect();
+        let (vals, refs, prison_accesses) = self._add_many_imm_refs(&keys)?;
+        let mut entries: Vec<Option<(usize, &'a T)>> = Vec::with_capacity(len);
+        entries.resize_with(len, || None);
+        for (key, val) in keys.iter().zip(vals.iter().copied()) {
+            entries[key.idx] = Some((key.gen, val));
+        }
+        return Ok(SnapshotRef {
+            prison_accesses,
+            prison: self,
+            refs,
+            entries,
+        });
+    }
+
+    //FN Prison::raw_size()
+    /// Return the number of raw bytes that make up one value, the length expected by [Prison::overwrite_raw()]
+    ///
+    /// Only available with the `raw` feature
+    #[cfg(feature = "raw")]
+    pub fn raw_size(&self) -> usize
+    where
+        T: PlainData,
+    {
+        return size_of::<T>();
+    }
+
+    //FN Prison::overwrite_raw()
+    /// Replace the value referenced by the [CellKey] with a value copied from raw bytes
+    ///
+    /// The bytes must be exactly [Prison::raw_size()] long but may have any alignment, as they are copied
+    /// into place. Like [SnapshotRef::raw_cells()] this works on the stored form of the value, so no
+    /// transform set with `with_transform()` is applied. The value is mutably referenced for the copy, so
+    /// it counts as a mutation for features such as `dirty`
+    ///
+    /// Only available with the `raw` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<[u32; 2]> = Prison::new();
+    /// let key_0 = prison.insert([1, 2])?;
+    /// let patch: Vec<u8> = [30u32, 40u32].iter().flat_map(|val| val.to_ne_bytes()).collect();
+    /// prison.overwrite_raw(key_0, &patch)?;
+    /// assert_eq!(prison.clone_val(key_0)?, [30, 40]);
+    /// assert_eq!(prison.overwrite_raw(key_0, &patch[..4]), Err(AccessError::RawSizeMismatch(8, 4)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::RawSizeMismatch(size, given)] if `bytes` is not exactly [Prison::raw_size()] long
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if the value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if the value has any active immutable references
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation does not match
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    #[cfg(feature = "raw")]
+    pub fn overwrite_raw(&self, key: CellKey, bytes: &[u8]) -> Result<(), AccessError>
+    where
+        T: PlainData,
+    {
+        if bytes.len() != size_of::<T>() {
+            return Err(AccessError::RawSizeMismatch(size_of::<T>(), bytes.len()));
+        }
+        let (cell, visits) = self._add_mut_ref(key.idx, key.gen, true)?;
+        let val = unsafe { cell.val.assume_init_mut() };
+        unsafe { slice_from_raw_parts_mut(val as *mut T as *mut u8, size_of::<T>()) }.copy_from_slice(bytes);
+        _remove_mut_ref(&mut cell.refs_or_next, visits);
+        self._sweep_released();
+        return Ok(());
+    }
+
+    //FN Prison::with_guards()
+    /// Run a closure that is passed a [GuardCtx], from which any number of references to values
+    /// in the [Prison] can be requested and released dynamically while the closure runs
+    ///
+    /// This is a middle ground between the one-shot `visit()` methods and manually juggling
+    /// many `guard()` wrappers: references obtained from the [GuardCtx] follow the exact same
+    /// referencing rules as every other access, and any reference still held when the closure
+    /// returns (even by returning an error or panicking) is automatically released
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// prison.with_guards(|ctx| {
+    ///     let val_0 = ctx.mut_ref(key_0)?;
+    ///     let val_1 = ctx.imm_ref(key_1)?;
+    ///     *val_0 += *val_1;
+    ///     assert!(ctx.imm_ref(key_0).is_err());
+    ///     ctx.release(key_0);
+    ///     assert_eq!(*ctx.imm_ref(key_0)?, 30);
+    ///     Ok(())
+    /// })?;
+    /// assert!(prison.visit_mut(key_0, |val_0| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// References obtained from the [GuardCtx] cannot outlive the closure, and a reference
+    /// cannot be released while any reference borrowed from the context is still in use
+    /// ### Example
+    /// ```compile_fail
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// prison.with_guards(|ctx| {
+    ///     let val_0 = ctx.mut_ref(key_0)?;
+    ///     // will not compile: (error[E0502]: cannot borrow `*ctx` as mutable because it is also borrowed as immutable)
+    ///     ctx.release(key_0);
+    ///     *val_0 = 20;
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// Any error returned from the closure is passed through after all held references are released
+    pub fn with_guards<F>(&self, operation: F) -> Result<(), AccessError>
+    where
+        F: FnOnce(&mut GuardCtx<'_, T, S>) -> Result<(), AccessError>,
+    {
+        self._check_depth()?;
+        let mut ctx = GuardCtx {
+            prison: self,
+            held: UnsafeCell::new(Vec::new()),
+        };
+        return self._visit(|| operation(&mut ctx));
+    }
+
+    //FN Prison::seal_read_only()
+    /// Seal the [Prison] so it can only be read, making every immutable access free of house-keeping
+    ///
+    /// While sealed, `visit_*_ref()`, `guard_*_ref()` and every other immutable access only validate
+    /// the [CellKey] or index: neither the per-value reference counts nor the master access counter are
+    /// updated when references are obtained or released. In exchange, every operation that could modify the [Prison]
+    /// or mutably reference one of its values (`insert()`, `remove()`, `overwrite()`, `visit_*_mut()`, `guard_*_mut()`,
+    /// `recycle()`, etc.) immediately returns [AccessError::PrisonSealed]
+    ///
+    /// This is meant for arenas that are filled during a load phase and then only read. Sealing an already
+    /// sealed [Prison] does nothing. Because references are no longer counted, the seal can only be removed
+    /// with [Prison::unseal()], which requires exclusive access to prove no references remain
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let mut prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// prison.seal_read_only()?;
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// prison.visit_ref(key_0, |val_0| {
+    ///     assert_eq!(*val_0, *grd_0);
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(prison.insert(20), Err(AccessError::PrisonSealed));
+    /// assert_eq!(prison.visit_mut(key_0, |val_0| Ok(())), Err(AccessError::PrisonSealed));
+    /// drop(grd_0);
+    /// prison.unseal();
+    /// prison.visit_mut(key_0, |val_0| {
+    ///     *val_0 += 1;
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::AccessesOutstanding(count)] if any value is currently referenced
+    pub fn seal_read_only(&self) -> Result<(), AccessError> {
+        let internal = internal!(self);
+        if internal.access_count.count == SEALED_ACCESSES {
+            return Ok(());
+        }
+        if internal.access_count.count > 0 {
+            return Err(AccessError::AccessesOutstanding(internal.access_count.count));
+        }
+        internal.access_count.count = SEALED_ACCESSES;
+        return Ok(());
+    }
+
+    //FN Prison::unseal()
+    /// Remove the seal placed by [Prison::seal_read_only()], allowing the [Prison] to be modified again
+    ///
+    /// Requires `&mut self` because references to a sealed [Prison] are not counted: exclusive access
+    /// is the only proof that none of them are still alive. Unsealing a [Prison] that is not sealed does nothing
+    pub fn unseal(&mut self) {
+        let internal = self.internal.get_mut();
+        if internal.access_count.count == SEALED_ACCESSES {
+            internal.access_count.count = 0;
+        }
+    }
+
+    //FN Prison::is_sealed()
+    /// Return `true` if the [Prison] is currently sealed with [Prison::seal_read_only()]
+    pub fn is_sealed(&self) -> bool {
+        return internal!(self).access_count.count == SEALED_ACCESSES;
+    }
+
+    //FN Prison::set_depth_limit()
+    /// Limit how deeply `visit()` closures on this [Prison] may be nested, or remove the limit with [None] (the default)
+    ///
+    /// Every closure passed to a `visit()` method (including [Prison::with_guards()], [Prison::visit_each_mut()],
+    /// and the other bulk visits) counts as one level of nesting while it runs. Once `limit` closures are running
+    /// inside one another, the next `visit()` returns [AccessError::VisitDepthExceeded(limit)]
+    /// before touching any value, so no references are acquired and the closure never runs. This turns accidental recursion through visit closures into a
+    /// clear error instead of confusing reference errors or a stack overflow. Guards are not visits and are never limited
+    ///
+    /// Changing the limit while closures are running only affects visits started afterwards
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// fn sum_list(prison: &Prison<(u32, Option<CellKey>)>, key: CellKey) -> Result<u32, AccessError> {
+    ///     prison.visit_ref(key, |(val, next)| match next {
+    ///         Some(next) => Ok(val + sum_list(prison, *next)?),
+    ///         None => Ok(*val),
+    ///     })
+    /// }
+    /// let prison: Prison<(u32, Option<CellKey>)> = Prison::new();
+    /// let mut head = prison.insert((1, None))?;
+    /// for val in 2..=10 {
+    ///     head = prison.insert((val, Some(head)))?;
+    /// }
+    /// assert_eq!(sum_list(&prison, head)?, 55);
+    /// assert_eq!(prison.max_depth(), 10);
+    /// prison.set_depth_limit(Some(4));
+    /// assert_eq!(sum_list(&prison, head), Err(AccessError::VisitDepthExceeded(4)));
+    /// assert_eq!(prison.visit_depth(), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn set_depth_limit(&self, limit: Option<usize>) {
+        internal!(self).depth_limit = limit.unwrap_or(usize::MAX);
+    }
+
+    //FN Prison::depth_limit()
+    /// Return the limit set with [Prison::set_depth_limit()], or [None] if visits may be nested without limit
+    pub fn depth_limit(&self) -> Option<usize> {
+        return match internal!(self).depth_limit {
+            usize::MAX => None,
+            limit => Some(limit),
+        };
+    }
+
+    //FN Prison::visit_depth()
+    /// Return the number of `visit()` closures on this [Prison] that are currently running inside one another
+    ///
+    /// This is `0` outside of any visit, and `1` inside a single (non-nested) `visit()` closure
+    pub fn visit_depth(&self) -> usize {
+        return internal!(self).visit_depth;
+    }
+
+    //FN Prison::max_depth()
+    /// Return the deepest nesting of `visit()` closures reached on this [Prison] since it was created
+    /// or since the last call to [Prison::reset_max_depth()]
+    ///
+    /// Useful to choose a sensible value for [Prison::set_depth_limit()], or to spot unexpected recursion while debugging
+    pub fn max_depth(&self) -> usize {
+        return internal!(self).max_depth;
+    }
+
+    //FN Prison::reset_max_depth()
+    /// Reset the value reported by [Prison::max_depth()] to the current [Prison::visit_depth()]
+    pub fn reset_max_depth(&self) {
+        let internal = internal!(self);
+        internal.max_depth = internal.visit_depth;
+    }
+
+    //FN Prison::request_interrupt()
+    /// Request that all long-running operations on the [Prison] stop at their next checkpoint
+    ///
+    /// Closures passed to `visit()` methods or [Prison::with_guards()] may run for a long time while holding references,
+    /// which blocks operations that need the [Prison] to be idle. Such closures can cooperatively call
+    /// [Prison::checkpoint()] (or [GuardCtx::checkpoint()]) and propagate its error with `?`, which
+    /// releases their references once the interrupt is requested.
+    ///
+    /// The request stays active until [Prison::clear_interrupt()] is called. It does not prevent any
+    /// other operation by itself
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(0)?;
+    /// let result = prison.visit_mut(key_0, |val_0| {
+    ///     for step in 0..1000 {
+    ///         prison.checkpoint()?;
+    ///         *val_0 += 1;
+    ///         if step == 9 {
+    ///             prison.request_interrupt();
+    ///         }
+    ///     }
+    ///     Ok(())
+    /// });
+    /// assert_eq!(result, Err(AccessError::InterruptRequested));
+    /// assert!(prison.interrupt_requested());
+    /// prison.clear_interrupt();
+    /// assert_eq!(prison.clone_val(key_0)?, 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn request_interrupt(&self) {
+        internal!(self).extras().interrupt = true;
+    }
+
+    //FN Prison::clear_interrupt()
+    /// Clear an interrupt requested with [Prison::request_interrupt()], allowing checkpoints to pass again
+    pub fn clear_interrupt(&self) {
+        if let Some(extras) = internal!(self).extras.as_mut() {
+            extras.interrupt = false;
+        }
+    }
+
+    //FN Prison::interrupt_requested()
+    /// Return `true` if an interrupt was requested with [Prison::request_interrupt()] and not yet cleared
+    pub fn interrupt_requested(&self) -> bool {
+        return internal!(self).interrupt();
+    }
+
+    //FN Prison::checkpoint()
+    /// Return an error if an interrupt was requested with [Prison::request_interrupt()]
+    ///
+    /// Intended to be called periodically with `?` from inside long-running `visit()` closures,
+    /// see [Prison::request_interrupt()]
+    /// ## Errors
+    /// - [AccessError::InterruptRequested] if [Prison::request_interrupt()] was called and not yet cleared
+    #[inline(always)]
+    pub fn checkpoint(&self) -> Result<(), AccessError> {
+        if internal!(self).interrupt() {
+            return Err(AccessError::InterruptRequested);
+        }
+        return Ok(());
+    }
+
+    //FN Prison::clone_val()
+    /// Clones the requested value out of the [Prison] into a new variable
+    ///
+    /// Only available when elements of type T implement [Clone] (it is assumed that the implementation of `T::clone()` is memory safe).
+    ///
+    /// Because cloning does not alter the original, and because the new variable to hold the clone does not have any presumtions about the value, it
+    /// is safe (in a single-threaded context) to clone out the value even if it is being visited or guarded.
+    ///
+    /// This method *will* still return an error if the index or generation of the [CellKey] are invalid
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<String> = Prison::new();
+    /// let key_0 = prison.insert(String::from("Foo"))?;
+    /// let key_1 = prison.insert(String::from("Bar"))?;
+    /// let mut take_foo = String::new();
+    /// let mut take_bar = String::new();
+    /// prison.visit_mut(key_0, |val_0| {
+    ///     take_foo = prison.clone_val(key_0)?;
+    ///     Ok(())
+    /// });
+    /// let grd_1 = prison.guard_mut(key_1)?;
+    /// take_bar = prison.clone_val(key_1)?;
+    /// PrisonValueMut::unguard(grd_1);
+    /// assert_eq!(take_foo, String::from("Foo"));
+    /// assert_eq!(take_bar, String::from("Bar"));
+    /// prison.remove(key_1)?;
+    /// assert!(prison.clone_val(CellKey::from_raw_parts(10, 10)).is_err());
+    /// assert!(prison.clone_val(key_1).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_val(&self, key: CellKey) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        #[cfg(feature = "offload")]
+        internal!(self).reload(key.idx)?;
+        let internal = internal!(self);
+        if key.idx >= internal.vec.len() {
+            return Err(AccessError::IndexOutOfRange(key.idx));
+        }
+        match &internal.vec[key.idx] {
+            cell if cell.is_cell_and_gen_match(key.gen) => {
+                if cell.refs_or_next == Refs::HIDDEN {
+                    return Err(AccessError::ValueHidden(key.idx));
+                }
+                return Ok(self._decoded_clone(unsafe { cell.val.assume_init_ref() }));
+            }
+            _ => return Err(AccessError::ValueDeleted(key.idx, key.gen)),
+        }
+    }
+
+    //FN Prison::clone_val_idx()
+    /// Clones the requested value out of the [Prison] into a new variable
+    ///
+    /// Same as `clone_val()` but ignores the generation counter
+    ///
+    /// Only available when elements of type T implement [Clone] (it is assumed that the implementation of `T::clone()` is memory safe).
+    ///
+    /// Because cloning does not alter the original, and because the new variable to hold the clone does not have any presumtions about the value, it
+    /// is safe (in a single-threaded context) to clone out the value even if it is being visited or guarded.
+    ///
+    /// This method *will* still return an error if the index is invalid or the value is free/deleted
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<String> = Prison::new();
+    /// prison.insert(String::from("Foo"))?;
+    /// prison.insert(String::from("Bar"))?;
+    /// let mut take_foo = String::new();
+    /// let mut take_bar = String::new();
+    /// prison.visit_mut_idx(0, |val_0| {
+    ///     take_foo = prison.clone_val_idx(0)?;
+    ///     Ok(())
+    /// });
+    /// let grd_1 = prison.guard_mut_idx(1)?;
+    /// take_bar = prison.clone_val_idx(1)?;
+    /// PrisonValueMut::unguard(grd_1);
+    /// assert_eq!(take_foo, String::from("Foo"));
+    /// assert_eq!(take_bar, String::from("Bar"));
+    /// prison.remove_idx(1)?;
+    /// assert!(prison.clone_val_idx(10).is_err());
+    /// assert!(prison.clone_val_idx(1).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_val_idx(&self, idx: usize) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        let idx = self._idx(idx)?;
+        #[cfg(feature = "offload")]
+        internal!(self).reload(idx)?;
+        let internal = internal!(self);
+        if idx >= internal.vec.len() {
+            return Err(AccessError::IndexOutOfRange(idx));
+        }
+        match &internal.vec[idx] {
+            cell if cell.is_cell() => {
+                if cell.refs_or_next == Refs::HIDDEN {
+                    return Err(AccessError::ValueHidden(idx));
+                }
+                return Ok(self._decoded_clone(unsafe { cell.val.assume_init_ref() }));
+            }
+            _ => return Err(AccessError::ValueDeleted(idx, 0)),
+        }
+    }
+
+    //FN Prison::clone_many_vals()
+    /// Clones the requested values out of the [Prison] into a new [Vec<T>]
+    ///
+    /// Only available when elements of type T implement [Clone] (it is assumed that the implementation of `T::clone()` is memory safe).
+    ///
+    /// Because cloning does not alter the originals, and because the new variables to hold the clones do not have any presumtions about the values, it
+    /// is safe (in a single-threaded context) to clone out the values even if they are being visited or guarded.
+    ///
+    /// This method *will* still return an error if any index or generation of the [CellKey]s are invalid
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<String> = Prison::new();
+    /// let key_0 = prison.insert(String::from("Foo"))?;
+    /// let key_1 = prison.insert(String::from("Bar"))?;
+    /// let mut take_foobar: Vec<String> = Vec::new();
+    /// prison.visit_mut(key_0, |val_0| {
+    ///     let grd_1 = prison.guard_mut(key_1)?;
+    ///     take_foobar = prison.clone_many_vals(&[key_0, key_1])?;
+    ///     PrisonValueMut::unguard(grd_1);
+    ///     Ok(())
+    /// });
+    /// assert_eq!(take_foobar[0], String::from("Foo"));
+    /// assert_eq!(take_foobar[1], String::from("Bar"));
+    /// prison.remove(key_1)?;
+    /// assert!(prison.clone_many_vals(&[CellKey::from_raw_parts(10, 10)]).is_err());
+    /// assert!(prison.clone_many_vals(&[key_1]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_many_vals(&self, keys: &[CellKey]) -> Result<Vec<T>, AccessError>
+    where
+        T: Clone,
+    {
+        let mut vals = Vec::with_capacity(keys.len());
+        for key in keys {
+            vals.push(self.clone_val(*key)?);
+        }
+        return Ok(vals);
+    }
+
+    //FN Prison::clone_many_vals_idx()
+    /// Clones the requested values out of the [Prison] into a new [Vec<T>]
+    ///
+    /// Same as `clone_many_vals()` but ignores the generation counter
+    ///
+    /// Only available when elements of type T implement [Clone] (it is assumed that the implementation of `T::clone()` is memory safe).
+    ///
+    /// Because cloning does not alter the originals, and because the new variables to hold the clones do not have any presumtions about the values, it
+    /// is safe (in a single-threaded context) to clone out the values even if they are being visited or guarded.
+    ///
+    /// This method *will* still return an error if any index is out-of-range or free/deleted
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<String> = Prison::new();
+    /// prison.insert(String::from("Foo"))?;
+    /// prison.insert(String::from("Bar"))?;
+    /// let mut take_foobar: Vec<String> = Vec::new();
+    /// prison.visit_mut_idx(0, |val_0| {
+    ///     let grd_1 = prison.guard_mut_idx(1)?;
+    ///     take_foobar = prison.clone_many_vals_idx(&[0, 1])?;
+    ///     PrisonValueMut::unguard(grd_1);
+    ///     Ok(())
+    /// });
+    /// assert_eq!(take_foobar[0], String::from("Foo"));
+    /// assert_eq!(take_foobar[1], String::from("Bar"));
+    /// prison.remove_idx(1)?;
+    /// assert!(prison.clone_many_vals_idx(&[10]).is_err());
+    /// assert!(prison.clone_many_vals_idx(&[1]).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn clone_many_vals_idx(&self, indexes: &[usize]) -> Result<Vec<T>, AccessError>
+    where
+        T: Clone,
+    {
+        let mut vals = Vec::with_capacity(indexes.len());
+        for idx in indexes {
+            vals.push(self.clone_val_idx(*idx)?);
+        }
+        return Ok(vals);
+    }
+
+    //FN Prison::clone_many_vals_batch()
+    /// Clones the requested values out of the [Prison] into a new [Vec<T>], continuing past invalid keys
+    /// and returning every error with the position of the key that caused it
+    ///
+    /// Same as [Prison::clone_many_vals()], but if any key fails *all* failures are reported in the returned [BatchError]
+    /// instead of only the first one
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// assert_eq!(prison.clone_many_vals_batch(&[key_1, key_0]).unwrap(), vec![20, 10]);
+    /// prison.remove(key_1)?;
+    /// let err = prison.clone_many_vals_batch(&[key_1, key_0, key_1]).unwrap_err();
+    /// assert_eq!(err.positions().collect::<Vec<_>>(), vec![0, 2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [BatchError] holding, for every position that failed, any error [Prison::clone_val()] can return for that key
+    pub fn clone_many_vals_batch(&self, keys: &[CellKey]) -> Result<Vec<T>, BatchError>
+    where
+        T: Clone,
+    {
+        let mut vals = Vec::with_capacity(keys.len());
+        let mut errors = Vec::new();
+        for (pos, key) in keys.iter().enumerate() {
+            match self.clone_val(*key) {
+                Ok(val) => vals.push(val),
+                Err(err) => errors.push((pos, err)),
+            }
+        }
+        BatchError::from_errors(errors)?;
+        return Ok(vals);
+    }
+
+    //FN Prison::read()
+    /// Copy the requested value out of the [Prison] without touching any reference counts
+    ///
+    /// Only available when elements of type T implement [Copy]. This performs the same index and
+    /// generation checks as `visit_ref()`, but instead of incrementing and decrementing the reference counts
+    /// it copies the value directly, which removes measurable overhead for prisons of small types that are read far
+    /// more often than they are written.
+    ///
+    /// This is sound because a [Prison] can only ever be accessed from one thread, so the value cannot change
+    /// while it is being copied, and a [Copy] type has no destructor or interior ownership that the copy could
+    /// duplicate. The only reference that could conflict with the read is an active *mutable* reference, since
+    /// reading through any other path while one exists would violate Rust's aliasing rules, so that case is still rejected
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let positions: Prison<(f32, f32)> = Prison::new();
+    /// let key_0 = positions.insert((1.0, 2.0))?;
+    /// let grd_0 = positions.guard_ref(key_0)?;
+    /// assert_eq!(positions.read(key_0)?, (1.0, 2.0));
+    /// drop(grd_0);
+    /// positions.visit_mut(key_0, |pos_0| {
+    ///     assert_eq!(positions.read(key_0), Err(AccessError::ValueAlreadyMutablyReferenced(0)));
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if the value is currently mutably referenced
+    /// - [AccessError::ValueHidden(idx)] if the value was inserted with [Prison::insert_hidden()] and is not published yet
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the cell is marked as free/deleted *OR* the [CellKey] generation doesn't match
+    #[inline(always)]
+    pub fn read(&self, key: CellKey) -> Result<T, AccessError>
+    where
+        T: Copy,
+    {
+        #[cfg(feature = "offload")]
+        internal!(self).reload(key.idx)?;
+        match internal!(self).vec.get(key.idx) {
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) => {
+                if cell.refs_or_next == Refs::MUT {
+                    return Err(AccessError::ValueAlreadyMutablyReferenced(key.idx));
+                }
+                if cell.refs_or_next == Refs::HIDDEN {
+                    return Err(AccessError::ValueHidden(key.idx));
+                }
+                return Ok(self._decoded_clone(unsafe { cell.val.assume_init_ref() }));
+            }
+            Some(_) => return Err(AccessError::ValueDeleted(key.idx, key.gen)),
+            None => return Err(AccessError::IndexOutOfRange(key.idx)),
+        }
+    }
+
+    //FN Prison::read_idx()
+    /// Copy the value at the requested index out of the [Prison] without touching any reference counts
+    ///
+    /// Same as `read()` but ignores the generation counter
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if the value is currently mutably referenced
+    /// - [AccessError::ValueHidden(idx)] if the value was inserted with [Prison::insert_hidden()] and is not published yet
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, 0)] if the cell is marked as free/deleted
+    #[inline(always)]
+    pub fn read_idx(&self, idx: usize) -> Result<T, AccessError>
+    where
+        T: Copy,
+    {
+        let idx = self._idx(idx)?;
+        #[cfg(feature = "offload")]
+        internal!(self).reload(idx)?;
+        match internal!(self).vec.get(idx) {
+            Some(cell) if cell.is_cell() => {
+                if cell.refs_or_next == Refs::MUT {
+                    return Err(AccessError::ValueAlreadyMutablyReferenced(idx));
+                }
+                if cell.refs_or_next == Refs::HIDDEN {
+                    return Err(AccessError::ValueHidden(idx));
+                }
+                return Ok(self._decoded_clone(unsafe { cell.val.assume_init_ref() }));
+            }
+            Some(_) => return Err(AccessError::ValueDeleted(idx, 0)),
+            None => return Err(AccessError::IndexOutOfRange(idx)),
+        }
+    }
+
+    //FN Prison::pin()
+    /// Pin the value at the specified [CellKey], protecting it from removal
+    ///
+    /// While pinned, [Prison::remove()], [Prison::remove_idx()], [Prison::overwrite()] and
+    /// [Prison::remove_group()] fail with [AccessError::ValuePinned(idx)] even if the value has
+    /// no active references, and [Prison::recycle()] skips it. Pinning does not restrict
+    /// visiting or guarding the value in any way, and pinning an already pinned value does nothing
+    ///
+    /// This is unrelated to [Prison::pin_refs()], which holds references rather than protecting values
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = Prison::new();
+    /// let key_root = prison.insert("root")?;
+    /// prison.pin(key_root)?;
+    /// assert!(prison.is_pinned(key_root));
+    /// assert_eq!(prison.remove(key_root), Err(AccessError::ValuePinned(0)));
+    /// prison.unpin(key_root)?;
+    /// assert_eq!(prison.remove(key_root)?, "root");
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or the generation does not match
+    pub fn pin(&self, key: CellKey) -> Result<(), AccessError> {
+        let internal = internal!(self);
+        self._check_key(key)?;
+        let pinned = &mut internal.extras().pinned;
+        if let Err(pos) = pinned.binary_search(&key.idx) {
+            pinned.insert(pos, key.idx);
+        }
+        return Ok(());
+    }
+
+    //FN Prison::unpin()
+    /// Unpin the value at the specified [CellKey], allowing it to be removed or overwritten again
+    ///
+    /// Unpinning a value that is not pinned does nothing. A removal registered with [Prison::remove_when_free()] that
+    /// failed because the value was pinned runs now if the value is not referenced
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(42)?;
+    /// prison.pin(key_0)?;
+    /// assert!(prison.overwrite(0, 43).is_err());
+    /// prison.unpin(key_0)?;
+    /// assert!(!prison.is_pinned(key_0));
+    /// prison.overwrite(0, 43)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or the generation does not match
+    pub fn unpin(&self, key: CellKey) -> Result<(), AccessError> {
+        let internal = internal!(self);
+        self._check_key(key)?;
+        if let Ok(pos) = internal.pinned().binary_search(&key.idx) {
+            internal.extras().pinned.remove(pos);
+            self._sweep_released();
+        }
+        return Ok(());
+    }
+
+    //FN Prison::is_pinned()
+    /// Return `true` if the [CellKey] refers to a valid value that is currently pinned
+    ///
+    /// See [Prison::pin()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(42)?;
+    /// assert!(!prison.is_pinned(key_0));
+    /// prison.pin(key_0)?;
+    /// assert!(prison.is_pinned(key_0));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn is_pinned(&self, key: CellKey) -> bool {
+        return self._check_key(key).is_ok() && internal!(self).is_pinned(key.idx);
+    }
+
+    //FN Prison::insert_group()
+    /// Insert every value from an iterator into the [Prison] as a single group, receiving a [GroupKey]
+    /// that can later be used to visit or remove all of the values at once
+    ///
+    /// The [Prison] keeps track of which values belong to the group, so whole batches of related values
+    /// (for example everything loaded for one level or region) can be unloaded together without keeping
+    /// a separate list of their [CellKey]'s. Use [Prison::group_keys()] to retrieve the individual keys.
+    ///
+    /// If any insert fails, every value of the group inserted so far is removed again before the error is returned
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = Prison::new();
+    /// let player = prison.insert("player")?;
+    /// let level_1 = prison.insert_group(["tree", "rock", "chest"])?;
+    /// assert_eq!(prison.num_used(), 4);
+    /// let removed = prison.remove_group(level_1)?;
+    /// assert_eq!(removed, vec!["tree", "rock", "chest"]);
+    /// assert_eq!(prison.num_used(), 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// Any error that [Prison::insert()] could return
+    pub fn insert_group<I>(&self, values: I) -> Result<GroupKey, AccessError>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        self._check_unsealed()?;
+        let mut members = CellKeySet::new();
+        for value in values {
+            match self.insert(value) {
+                Ok(key) => members.keys.push(key),
+                Err(acc_err) => {
+                    for key in members.iter() {
+                        self.remove(*key)?;
+                    }
+                    return Err(acc_err);
+                }
+            }
+        }
+        members.keys.sort_unstable_by_key(|key| key.idx);
+        let internal = internal!(self);
+        let extras = internal.extras();
+        let id = extras.next_group;
+        extras.next_group += 1;
+        extras.groups.push((id, members));
+        return Ok(GroupKey { id });
+    }
+
+    //FN Prison::group_keys()
+    /// Return a [CellKeySet] containing the [CellKey] of every value in the group that is still valid
+    ///
+    /// Values from the group that were removed individually are no longer considered members
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let group = prison.insert_group([10, 20, 30])?;
+    /// let keys = prison.group_keys(group)?;
+    /// assert_eq!(keys.len(), 3);
+    /// prison.remove(keys.as_slice()[1])?;
+    /// assert_eq!(prison.group_keys(group)?.len(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::GroupNotFound(id)] if the group was already removed or belongs to a different [Prison]
+    pub fn group_keys(&self, group: GroupKey) -> Result<CellKeySet, AccessError> {
+        return Ok(self._group_members(group)?.clone());
+    }
+
+    //FN Prison::visit_group_mut()
+    /// Visit every valid value in a group at the same time, obtaining a mutable reference to
+    /// all of them in the same closure, in ascending index order
+    ///
+    /// Acquisition behaves exactly like [Prison::visit_set_mut()], so blocked members are all reported at once
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let group = prison.insert_group([10, 20, 30])?;
+    /// prison.visit_group_mut(group, |vals| {
+    ///     for val in vals.iter_mut() {
+    ///         **val += 1;
+    ///     }
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(prison.clone_val_idx(2)?, 31);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::GroupNotFound(id)] if the group was already removed or belongs to a different [Prison]
+    /// - [AccessError::SetMembersBlocked(set)] if any members are already referenced, containing every blocked member
+    pub fn visit_group_mut<F>(&self, group: GroupKey, mut operation: F) -> Result<(), AccessError>
+    where
+        F: FnMut(&mut [&mut T]) -> Result<(), AccessError>,
+    {
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_set_mut_refs(self._group_members(group)?)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_mut_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        let result = self._visit(|| operation(&mut vals));
+        self._recycle_buf(&mut vals);
+        return result;
+    }
+
+    //FN Prison::visit_group_ref()
+    /// Visit every valid value in a group at the same time, obtaining an immutable reference to
+    /// all of them in the same closure, in ascending index order
+    ///
+    /// Acquisition behaves exactly like [Prison::visit_set_ref()], so blocked members are all reported at once
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let group = prison.insert_group([10, 20, 30])?;
+    /// prison.visit_group_ref(group, |vals| {
+    ///     assert_eq!(vals.iter().map(|val| **val).sum::<u32>(), 60);
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::GroupNotFound(id)] if the group was already removed or belongs to a different [Prison]
+    /// - [AccessError::SetMembersBlocked(set)] if any members are already mutably referenced, containing every blocked member
+    pub fn visit_group_ref<F>(&self, group: GroupKey, mut operation: F) -> Result<(), AccessError>
+    where
+        F: FnMut(&[&T]) -> Result<(), AccessError>,
+    {
+        self._check_depth()?;
+        let (mut vals, refs, accesses) = self._add_set_imm_refs(self._group_members(group)?)?;
+        let _release = RefRelease::new(self, refs, |refs| {
+            _remove_many_imm_refs(refs, accesses);
+            self._recycle_buf(refs);
+        });
+        let result = self._visit(|| operation(&vals));
+        self._recycle_buf(&mut vals);
+        return result;
+    }
+
+    //FN Prison::remove_group()
+    /// Remove every valid value in a group and return them in ascending index order,
+    /// invalidating the [GroupKey]
+    ///
+    /// Every member is checked for active references, pins and the generation limit before anything
+    /// is removed, so either the whole group is removed or none of it is
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let group = prison.insert_group([10, 20, 30])?;
+    /// let grd_1 = prison.guard_ref_idx(1)?;
+    /// assert!(prison.remove_group(group).is_err());
+    /// drop(grd_1);
+    /// assert_eq!(prison.remove_group(group)?, vec![10, 20, 30]);
+    /// assert!(prison.remove_group(group).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::GroupNotFound(id)] if the group was already removed or belongs to a different [Prison]
+    /// - [AccessError::RemoveWhileValueReferenced(idx)] if any member has an active reference
+    /// - [AccessError::ValuePinned(idx)] if any member is pinned
+    /// - [AccessError::MaxValueForGenerationReached] if any removal would need a generation above the maximum
+    /// - [AccessError::OffloadFailed(idx)] if an offloaded member could not be reloaded
+    pub fn remove_group(&self, group: GroupKey) -> Result<Vec<T>, AccessError> {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        let members = self._group_members(group)?;
+        let mut new_gen = internal.generation;
+        for key in members.iter() {
+            #[cfg(feature = "offload")]
+            internal!(self).reload(key.idx)?;
+            let cell = &internal.vec[key.idx];
+            if cell.refs_or_next > 0 && cell.refs_or_next != Refs::HIDDEN {
+                return Err(AccessError::RemoveWhileValueReferenced(key.idx));
+            }
+            if internal.is_pinned(key.idx) {
+                return Err(AccessError::ValuePinned(key.idx));
+            }
+            let cell_gen = IdxD::val(cell.d_gen_or_prev);
+            if cell_gen >= new_gen {
+                if cell_gen == IdxD::MAX_GEN {
+                    return Err(AccessError::MaxValueForGenerationReached);
+                }
+                new_gen = cell_gen + 1;
+            }
+        }
+        let pos = self._group_pos(group)?;
+        let (_, members) = internal.extras().groups.remove(pos);
+        let mut vals = Vec::with_capacity(members.len());
+        for key in members.iter() {
+            match self.remove(*key) {
+                Ok(val) => vals.push(val),
+                Err(acc_err) => major_malfunction!( //COV_IGNORE
+                    "a checked group member at index {} could not be removed: {}", //COV_IGNORE
+                    key.idx, //COV_IGNORE
+                    acc_err //COV_IGNORE
+                ), //COV_IGNORE
+            }
+        }
+        return Ok(vals);
+    }
+
+    //FN Prison::gc_step()
+    /// Perform a bounded amount of maintenance work, returning `true` once there is nothing left to do
+    ///
+    /// Each call examines at most `max_cells` cells or group members, so calling it once per frame
+    /// spreads maintenance out instead of causing a spike. Progress is tracked inside the [Prison]
+    /// between calls. The work consists of:
+    /// - releasing free cells from the end of the [Prison] (the [Vec] capacity is kept, so this never reallocates)
+    /// - pruning group members that were removed or overwritten from every value group
+    ///
+    /// Keys to released cells return [AccessError::IndexOutOfRange(idx)] instead of
+    /// [AccessError::ValueDeleted(idx, gen)] afterwards, but can never match a future value.
+    /// It is safe to call while values are referenced
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let keys: Vec<CellKey> = (0..6).map(|i| prison.insert(i)).collect::<Result<_, _>>()?;
+    /// for key in keys {
+    ///     prison.remove(key)?;
+    /// }
+    /// assert_eq!(prison.vec_len(), 7);
+    /// assert!(!prison.gc_step(4)?);
+    /// assert!(prison.gc_step(4)?);
+    /// assert_eq!(prison.vec_len(), 1);
+    /// assert_eq!(prison.clone_val(key_0)?, 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::MAJOR_MALFUNCTION(msg)] if the free list is found to be corrupted
+    pub fn gc_step(&self, max_cells: usize) -> Result<bool, AccessError> {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        let mut budget = max_cells;
+        while budget > 0 {
+            let last = match internal.vec.last() {
+                Some(cell) if cell.is_free() && !internal!(self).is_reserved(internal.vec.len() - 1) => internal.vec.len() - 1,
+                _ => break,
+            };
+            self._unlink_free(last)?;
+            if internal.placeholder().is_some() {
+                unsafe { internal.vec[last].val.assume_init_drop() };
+            }
+            internal.vec.pop();
+            budget -= 1;
+        }
+        if budget == 0 && internal.vec.last().is_some_and(|cell| cell.is_free()) && !internal.is_reserved(internal.vec.len() - 1) {
+            return Ok(false);
+        }
+        if let Some(extras) = internal.extras.as_mut() {
+            while budget > 0 && extras.gc_cursor < extras.groups.len() {
+                let vec = &internal.vec;
+                let members = &mut extras.groups[extras.gc_cursor].1;
+                budget = budget.saturating_sub(members.len());
+                members
+                    .keys
+                    .retain(|key| vec.get(key.idx).is_some_and(|cell| cell.is_cell_and_gen_match(key.gen)));
+                extras.gc_cursor += 1;
+            }
+            if extras.gc_cursor < extras.groups.len() {
+                return Ok(false);
+            }
+            extras.gc_cursor = 0;
+        }
+        return Ok(true);
+    }
+
+    //FN Prison::purge()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Reset the generation counter to 0 and compact every valid value towards the start of the [Prison],
+    /// releasing the free cells left at the end, and return a [KeyRemap] from every old [CellKey] to its new one
+    ///
+    /// This is the way to recover from [AccessError::MaxValueForGenerationReached]. Values keep their relative order,
+    /// values at reserved indexes (see [Prison::reserve_index_range()]) stay where they are, and
+    /// every value receives generation 0. Pins, value groups, and any feature-specific tracking follow
+    /// the values to their new indexes.
+    ///
+    /// Because generations restart from 0, an old [CellKey] that was not remapped can match an unrelated value
+    /// after a purge. *Every* stored key must be replaced using the returned [KeyRemap]. Keys stored inside the
+    /// values themselves can be fixed up by a hook set with [Prison::set_move_hook()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// prison.remove(key_0)?;
+    /// prison.remove(key_1)?;
+    /// let key_3 = prison.insert(40)?;
+    /// let remap = prison.purge()?;
+    /// assert_eq!(prison.vec_len(), 2);
+    /// assert_eq!(prison.num_used(), 2);
+    /// let key_2 = remap.get(key_2).unwrap();
+    /// let key_3 = remap.get(key_3).unwrap();
+    /// assert_eq!(key_3, CellKey::from_raw_parts(0, 0));
+    /// assert_eq!(key_2, CellKey::from_raw_parts(1, 0));
+    /// assert_eq!(prison.clone_val(key_2)?, 30);
+    /// assert_eq!(prison.clone_val(key_3)?, 40);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::AccessesOutstanding(count)] if any value is currently referenced
+    pub fn purge(&self) -> Result<KeyRemap, AccessError> {
+        let pairs = self._compact(true)?;
+        let moved: Vec<(CellKey, CellKey)> = match internal!(self).move_hook() {
+            Some(_) => pairs.iter().filter(|(old_key, new_key)| old_key != new_key).copied().collect(),
+            None => Vec::new(),
+        };
+        let remap = KeyRemap::from_pairs(pairs);
+        self._run_move_hook(&moved)?;
+        return Ok(remap);
+    }
+
+    //FN Prison::compact()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Move every valid value down into the free cells before it, releasing the free cells left at the end,
+    /// and call `on_move` with the old and new [CellKey] of every value that moved, returning how many values moved
+    ///
+    /// Unlike [Prison::purge()] the generation counter is kept: values that do not move keep their [CellKey], and
+    /// values that move receive a generation newer than any value that ever lived at their new index, so an old
+    /// [CellKey] that was not updated returns [AccessError::ValueDeleted(idx, gen)] instead of matching an unrelated value.
+    /// Values keep their relative order, values at reserved indexes (see [Prison::reserve_index_range()]) stay where they are,
+    /// and pins, value groups, and any feature-specific tracking follow the values to their new indexes.
+    ///
+    /// Any hook set with [Prison::set_move_hook()] runs first, then `on_move` is called in index order of the old keys
+    /// once the compaction has finished, so it may access the [Prison] freely
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let mut key_2 = prison.insert(30)?;
+    /// prison.remove(key_1)?;
+    /// let moved = prison.compact(|old_key, new_key| {
+    ///     if old_key == key_2 {
+    ///         key_2 = new_key;
+    ///     }
+    /// })?;
+    /// assert_eq!(moved, 1);
+    /// assert_eq!(prison.vec_len(), 2);
+    /// assert_eq!(key_2.idx(), 1);
+    /// assert_eq!(prison.clone_val(key_0)?, 10);
+    /// assert_eq!(prison.clone_val(key_2)?, 30);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::AccessesOutstanding(count)] if any value is currently referenced
+    /// - [AccessError::MaxValueForGenerationReached] if a value that would move already has the maximum generation, use [Prison::purge()] instead
+    pub fn compact<F>(&self, mut on_move: F) -> Result<usize, AccessError>
+    where
+        F: FnMut(CellKey, CellKey),
+    {
+        let moved: Vec<(CellKey, CellKey)> = self._compact(false)?.into_iter().filter(|(old_key, new_key)| old_key != new_key).collect();
+        self._run_move_hook(&moved)?;
+        for (old_key, new_key) in moved.iter() {
+            on_move(*old_key, *new_key);
+        }
+        return Ok(moved.len());
+    }
+
+    //FN Prison::move_val()
+    /// Move a single unreferenced value to a specific free index, returning its new [CellKey]
+    ///
+    /// This is a manual, incremental alternative to [Prison::compact()], for defragmenting a few values at a time
+    /// or placing related values next to each other. The value receives a new generation exactly as if it had been
+    /// removed and re-inserted, so the old [CellKey] returns [AccessError::ValueDeleted(idx, gen)] afterwards, and
+    /// the index it left becomes free. Everything else about the value moves with it: its group memberships,
+    /// TTL, LRU position, UUID and dirty flag, and whether it is hidden. The move hook set with
+    /// [Prison::set_move_hook()] is run for the moved value
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = Prison::new();
+    /// let key_0 = prison.insert("zero")?;
+    /// let key_1 = prison.insert("one")?;
+    /// let key_2 = prison.insert("two")?;
+    /// prison.remove(key_0)?;
+    /// let key_2 = prison.move_val(key_2, 0)?;
+    /// assert_eq!(key_2.idx(), 0);
+    /// assert_eq!(prison.clone_val(key_2)?, "two");
+    /// assert_eq!(prison.insert("three")?.idx(), 2);
+    /// assert_eq!(prison.move_val(key_1, 0), Err(AccessError::IndexIsNotFree(0)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::IndexOutOfRange(idx)] if either index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the [CellKey] does not reference a valid value
+    /// - [AccessError::IndexIsNotFree(idx)] if the destination index holds a value, or is reserved with [Prison::reserve_index_range()]
+    /// - [AccessError::RemoveWhileValueReferenced(idx)] if the value is currently referenced
+    /// - [AccessError::ValuePinned(idx)] if the value was pinned with [Prison::pin()]
+    /// - [AccessError::MaxValueForGenerationReached] if the value's generation cannot be incremented
+    pub fn move_val(&self, from_key: CellKey, to_idx: usize) -> Result<CellKey, AccessError> {
+        self._check_unsealed()?;
+        let to_idx = self._idx(to_idx)?;
+        #[cfg(feature = "offload")]
+        internal!(self).reload(from_key.idx)?;
+        self._check_key(from_key)?;
+        let internal = internal!(self);
+        match internal.vec.get(to_idx) {
+            None => return Err(AccessError::IndexOutOfRange(to_idx)),
+            Some(cell) if !cell.is_free() || internal.is_reserved(to_idx) => return Err(AccessError::IndexIsNotFree(to_idx)),
+            Some(_) => {}
+        }
+        let from_idx = from_key.idx;
+        let refs = internal.vec[from_idx].refs_or_next;
+        if refs > 0 && refs != Refs::HIDDEN {
+            return Err(AccessError::RemoveWhileValueReferenced(from_idx));
+        }
+        if internal.is_pinned(from_idx) {
+            return Err(AccessError::ValuePinned(from_idx));
+        }
+        if from_key.gen >= internal.generation {
+            if from_key.gen == IdxD::MAX_GEN {
+                return Err(AccessError::MaxValueForGenerationReached);
+            }
+            internal.generation = from_key.gen + 1;
+        }
+        let new_key = CellKey {
+            idx: to_idx,
+            gen: internal.generation,
+        };
+        self._unlink_free(to_idx)?;
+        internal.vec.swap(from_idx, to_idx);
+        internal.vec[to_idx].d_gen_or_prev = IdxD::new_type_a(new_key.gen);
+        if internal.is_reserved(from_idx) {
+            let free = &mut internal.vec[from_idx];
+            free.refs_or_next = IdxD::INVALID;
+            free.d_gen_or_prev = IdxD::new_type_b(IdxD::INVALID);
+            internal.free_count += 1;
+            internal.extras().reserved_free += 1;
+        } else {
+            self._link_free(from_idx)?;
+        }
+        for (_, members) in internal.extras.iter_mut().flat_map(|extras| extras.groups.iter_mut()) {
+            if members.remove(from_key) {
+                members.insert(new_key);
+            }
+        }
+        #[cfg(feature = "uuid")]
+        for (_, uuid_idx) in internal.uuids.iter_mut().filter(|(_, uuid_idx)| *uuid_idx == from_idx) {
+            *uuid_idx = to_idx;
+        }
+        #[cfg(feature = "dirty")]
+        for dirty_idx in internal.dirty.iter_mut().filter(|dirty_idx| **dirty_idx == from_idx) {
+            *dirty_idx = to_idx;
+        }
+        self._write_key(new_key);
+        self._run_move_hook(&[(from_key, new_key)])?;
+        return Ok(new_key);
+    }
+
+    //FN Prison::invalidate()
+    /// Give the value at the [CellKey] a new generation without moving or removing it, returning its new [CellKey]
+    ///
+    /// Every existing copy of the old [CellKey] returns [AccessError::ValueDeleted(idx, gen)] afterwards, exactly as if the value
+    /// had been removed and re-inserted at the same index, so the caller "re-owns" the value after handing out keys to it.
+    /// The value keeps its index, pin, hidden state, and any other per-value state. Value groups holding the old key are
+    /// updated to the new one, watched copies of the old key report [KeyStatus::Replaced], and the move hook (see
+    /// [Prison::set_move_hook()]) is called with the old and new keys
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let shared_key = key_0;
+    /// let key_0 = prison.invalidate(key_0)?;
+    /// assert_eq!(key_0.idx(), shared_key.idx());
+    /// assert_eq!(prison.clone_val(shared_key), Err(AccessError::ValueDeleted(0, 0)));
+    /// assert_eq!(prison.clone_val(key_0)?, 10);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or the generation does not match
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] or [AccessError::ValueStillImmutablyReferenced(idx)] if the value is currently referenced
+    /// - [AccessError::MaxValueForGenerationReached] if the generation counter is exhausted
+    pub fn invalidate(&self, key: CellKey) -> Result<CellKey, AccessError> {
+        self._check_unsealed()?;
+        #[cfg(feature = "offload")]
+        internal!(self).reload(key.idx)?;
+        self._check_key(key)?;
+        let internal = internal!(self);
+        match internal.vec[key.idx].refs_or_next {
+            0 | Refs::HIDDEN => {}
+            Refs::MUT => return Err(AccessError::ValueAlreadyMutablyReferenced(key.idx)),
+            _ => return Err(AccessError::ValueStillImmutablyReferenced(key.idx)),
+        }
+        if key.gen >= internal.generation {
+            if key.gen == IdxD::MAX_GEN {
+                return Err(AccessError::MaxValueForGenerationReached);
+            }
+            internal.generation = key.gen + 1;
+        }
+        let new_key = CellKey {
+            idx: key.idx,
+            gen: internal.generation,
+        };
+        internal.vec[key.idx].d_gen_or_prev = IdxD::new_type_a(new_key.gen);
+        for (_, members) in internal.extras.iter_mut().flat_map(|extras| extras.groups.iter_mut()) {
+            if members.remove(key) {
+                members.insert(new_key);
+            }
+        }
+        self._write_key(new_key);
+        self._run_move_hook(&[(key, new_key)])?;
+        return Ok(new_key);
+    }
+
+    //FN Prison::recycle()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Find every valid value that matches `predicate` and, instead of removing it, reinitialize it
+    /// in place with `reinit`, returning the new [CellKey] for each recycled value in index order
+    ///
+    /// Recycled values receive a new generation exactly as if they had been removed and re-inserted,
+    /// so any old [CellKey] referencing them will return [AccessError::ValueDeleted(idx, gen)],
+    /// but the free list is never touched and no values are dropped or moved. This makes it easy
+    /// to build object-pool patterns directly on the [Prison]
+    ///
+    /// Values with any active references and values pinned with [Prison::pin()] are skipped
+    /// without calling `predicate`. While either
+    /// closure runs, the value passed to it is marked as mutably referenced
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let bullets: Prison<(u32, bool)> = Prison::new();
+    /// let key_0 = bullets.insert((100, true))?;
+    /// let key_1 = bullets.insert((250, false))?;
+    /// let key_2 = bullets.insert((40, false))?;
+    /// let recycled = bullets.recycle(|(_, alive)| !*alive, |bullet| *bullet = (0, true))?;
+    /// assert_eq!(recycled.len(), 2);
+    /// assert_eq!(recycled[0].idx(), 1);
+    /// assert_eq!(bullets.clone_val(recycled[0])?, (0, true));
+    /// assert!(bullets.visit_ref(key_1, |old_bullet| Ok(())).is_err());
+    /// assert!(bullets.visit_ref(key_0, |live_bullet| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::MaxValueForGenerationReached] if a recycled value would need a generation above the maximum
+    ///
+    /// Values recycled before an error was encountered remain recycled
+    pub fn recycle<P, F>(&self, mut predicate: P, mut reinit: F) -> Result<Vec<CellKey>, AccessError>
+    where
+        P: FnMut(&T) -> bool,
+        F: FnMut(&mut T),
+    {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        let mut keys = Vec::new();
+        let mut idx = 0;
+        while idx < internal.vec.len() {
+            let cell = &mut internal.vec[idx];
+            if !cell.is_cell() || cell.refs_or_next > 0 || internal!(self).is_pinned(idx) {
+                idx += 1;
+                continue;
+            }
+            Refs::add_mut(&mut cell.refs_or_next, idx)?;
+            internal.access_count.count += 1;
+            let release = RefRelease::new(self, &mut cell.refs_or_next, |refs| {
+                _remove_mut_ref(refs, &mut internal!(self).access_count)
+            });
+            if predicate(unsafe { cell.val.assume_init_ref() }) {
+                let cell_gen = IdxD::val(cell.d_gen_or_prev);
+                if cell_gen >= internal.generation {
+                    if cell_gen == IdxD::MAX_GEN {
+                        return Err(AccessError::MaxValueForGenerationReached);
+                    }
+                    internal.generation = cell_gen + 1;
+                }
+                cell.d_gen_or_prev = IdxD::new_type_a(internal.generation);
+                reinit(unsafe { cell.val.assume_init_mut() });
+                #[cfg(feature = "recycle_stats")]
+                {
+                    cell.recycled += 1;
+                }
+                #[cfg(feature = "ttl")]
+                {
+                    cell.ttl = 0;
+                    cell.expires = NEVER_EXPIRES;
+                }
+                #[cfg(feature = "lru")]
+                {
+                    internal.lru_clock += 1;
+                    cell.last_used = internal.lru_clock;
+                }
+                #[cfg(feature = "uuid")]
+                {
+                    internal!(self).release_uuid(idx);
+                    internal!(self).assign_uuid(idx);
+                }
+                #[cfg(feature = "dirty")]
+                internal!(self).mark_dirty(idx);
+                keys.push(self._write_key(CellKey {
+                    idx,
+                    gen: internal.generation,
+                }));
+            }
+            drop(release);
+            idx += 1;
+        }
+        return Ok(keys);
+    }
+
+    //FN Prison::serialize_reachable()
+    /// Write a self-contained snapshot of only the values reachable from `roots`,
+    /// returning a [KeyRemap] from their original [CellKey]'s to their keys in the snapshot
+    ///
+    /// Starting from the roots, `edge_fn` is called on every reached value to discover the [CellKey]'s
+    /// it refers to, walking the graph breadth-first. Every reachable value is held immutably
+    /// for the entire operation, so the snapshot is consistent. Each value is then passed, in
+    /// snapshot order, to `write_val` along with `writer` and the finished [KeyRemap] so that any
+    /// [CellKey]'s stored inside the value can be written in their remapped form.
+    ///
+    /// Snapshot keys are assigned densely starting at index 0 with generation 0, so inserting
+    /// the values into a new, empty [Prison] in the order they were written reproduces the
+    /// remapped keys exactly
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// struct Node {
+    ///     name: &'static str,
+    ///     children: Vec<CellKey>,
+    /// }
+    /// let world: Prison<Node> = Prison::new();
+    /// let sword = world.insert(Node { name: "sword", children: vec![] })?;
+    /// let other = world.insert(Node { name: "other", children: vec![] })?;
+    /// let hero = world.insert(Node { name: "hero", children: vec![sword] })?;
+    /// let mut saved = Vec::new();
+    /// let remap = world.serialize_reachable(
+    ///     &[hero],
+    ///     |node| node.children.clone(),
+    ///     &mut saved,
+    ///     |saved, node, remap| {
+    ///         let children: Vec<CellKey> = node.children.iter().filter_map(|key| remap.get(*key)).collect();
+    ///         saved.push((node.name, children));
+    ///         Ok::<(), AccessError>(())
+    ///     },
+    /// )?;
+    /// assert_eq!(remap.len(), 2);
+    /// assert_eq!(saved[0], ("hero", vec![CellKey::from_raw_parts(1, 0)]));
+    /// assert_eq!(saved[1], ("sword", vec![]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - Any error returned by `write_val`
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if any reachable value is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(idx)] if any reachable value has the maximum number of immutable references
+    /// - [AccessError::IndexOutOfRange(idx)] if a root or discovered [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if a root or discovered [CellKey] references a deleted value
+    ///
+    /// No references are held after any error is returned, but anything already written to `writer` remains written
+    pub fn serialize_reachable<W, G, I, V, E>(
+        &self,
+        roots: &[CellKey],
+        mut edge_fn: G,
+        writer: &mut W,
+        mut write_val: V,
+    ) -> Result<KeyRemap, E>
+    where
+        G: FnMut(&T) -> I,
+        I: IntoIterator<Item = CellKey>,
+        V: FnMut(&mut W, &T, &KeyRemap) -> Result<(), E>,
+        E: From<AccessError>,
+    {
+        let internal = internal!(self);
+        let mut new_idx_of = vec![IdxD::INVALID; internal.vec.len()];
+        let mut reached: Vec<(CellKey, &T)> = Vec::new();
+        let mut refs = RefRelease::new(self, Vec::new(), |refs: &mut Vec<&mut usize>| {
+            _remove_many_imm_refs(refs, &mut internal!(self).access_count)
+        });
+        let mut queue: Vec<CellKey> = roots.to_vec();
+        let mut queue_pos = 0;
+        let mut walk_result = Ok(());
+        while queue_pos < queue.len() {
+            let key = queue[queue_pos];
+            queue_pos += 1;
+            if let Some(new_idx) = new_idx_of.get(key.idx).copied().filter(|idx| *idx != IdxD::INVALID) {
+                if reached[new_idx].0.gen != key.gen {
+                    walk_result = Err(AccessError::ValueDeleted(key.idx, key.gen));
+                    break;
+                }
+                continue;
+            }
+            match self._add_imm_ref(key.idx, key.gen, true) {
+                Ok((cell, _)) => {
+                    let val = unsafe { cell.val.assume_init_ref() };
+                    refs.refs.push(&mut cell.refs_or_next);
+                    if key.idx >= new_idx_of.len() {
+                        new_idx_of.resize(key.idx + 1, IdxD::INVALID);
+                    }
+                    new_idx_of[key.idx] = reached.len();
+                    reached.push((key, val));
+                    queue.extend(self._decoded_ref(val, |val| edge_fn(val)));
+                }
+                Err(acc_err) => {
+                    walk_result = Err(acc_err);
+                    break;
+                }
+            }
+        }
+        if let Err(acc_err) = walk_result {
+            return Err(E::from(acc_err));
+        }
+        let remap = KeyRemap::from_pairs(
+            reached
+                .iter()
+                .enumerate()
+                .map(|(new_idx, (old_key, _))| (*old_key, CellKey { idx: new_idx, gen: 0 }))
+                .collect(),
+        );
+        let mut write_result = Ok(());
+        for (_, val) in reached.iter() {
+            write_result = self._decoded_ref(val, |val| write_val(writer, val, &remap));
+            if write_result.is_err() {
+                break;
+            }
+        }
+        drop(refs);
+        return write_result.map(|_| remap);
+    }
+
+    //FN Prison::map()
+    /// Consume the [Prison<T>] and convert every valid value into a new type `U`, returning a [Prison<U>]
+    /// with identical indexes, generations and free cells so that every existing [CellKey] stays valid
+    ///
+    /// This is meant for staged loading pipelines, where raw data is loaded into one [Prison] and then
+    /// processed into another while the rest of the program keeps holding the same keys. The closure is
+    /// passed the [CellKey] of each value along with the value itself, in index order. The conversion is
+    /// exactly the one performed by [Prison::map_into()], see it for the state that is carried over
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let raw: Prison<&str> = Prison::new();
+    /// let key_hp = raw.insert("100")?;
+    /// let key_bad = raw.insert("oops")?;
+    /// let key_mp = raw.insert("25")?;
+    /// raw.remove(key_bad)?;
+    /// let parsed: Prison<u32> = raw.map(|_, text| text.parse().unwrap());
+    /// assert_eq!(parsed.clone_val(key_hp)?, 100);
+    /// assert_eq!(parsed.clone_val(key_mp)?, 25);
+    /// assert!(parsed.clone_val(key_bad).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map<U, F>(self, operation: F) -> Prison<U, S>
+    where
+        F: FnMut(CellKey, T) -> U,
+    {
+        return self.map_into(operation);
+    }
+
+    //FN Prison::map_into()
+    /// Consume the [Prison<T>] and convert every valid value into a new type `U` using the provided
+    /// closure, returning a [Prison<U>] with the exact same layout
+    ///
+    /// Every index, generation, and free space is preserved, so any [CellKey] that was valid for
+    /// the old [Prison<T>] will reference the converted value in the new [Prison<U>], and any
+    /// [CellKey] that was invalid will remain invalid. This allows keys held by external systems
+    /// to survive a data-model migration.
+    ///
+    /// Reserved indexes, pins, value groups, watched keys, hidden values, index salting and the depth limit are carried
+    /// over as well, while the move hook, placeholder and any transform are dropped because they are specific to `T`
+    ///
+    /// The closure is passed the [CellKey] of each value along with the value itself
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// prison.remove(key_1)?;
+    /// let new_prison: Prison<String> = prison.map_into(|key, val| format!("{}: {}", key.idx(), val));
+    /// assert_eq!(new_prison.clone_val(key_0)?, String::from("0: 10"));
+    /// assert_eq!(new_prison.clone_val(key_2)?, String::from("2: 30"));
+    /// assert!(new_prison.clone_val(key_1).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn map_into<U, F>(self, mut operation: F) -> Prison<U, S>
+    where
+        F: FnMut(CellKey, T) -> U,
+    {
+        let mut internal = self.internal.into_inner();
+        let has_placeholder = internal.placeholder().is_some();
+        #[cfg(feature = "transform")]
+        let mut operation = {
+            let transform = internal.transform;
+            move |key: CellKey, val: T| match transform {
+                Some((_, decode)) => operation(key, decode(&val)),
+                None => operation(key, val),
+            }
+        };
+        let old_vec = mem_take(&mut internal.vec).into_vec();
+        let mut new_vec = Vec::with_capacity(old_vec.capacity());
+        for (idx, old_cell) in old_vec.into_iter().enumerate() {
+            new_vec.push(old_cell.map_into(idx, &mut operation, has_placeholder));
+        }
+        let mut new_internal = PrisonInternal::new(StorageContainer::from_vec(new_vec), None);
+        new_internal.generation = internal.generation;
+        new_internal.free_count = mem_take(&mut internal.free_count);
+        new_internal.next_free = internal.next_free;
+        new_internal.extras = internal.extras.take().map(|extras| {
+            Box::new(PrisonExtras {
+                next_group: extras.next_group,
+                groups: extras.groups,
+                gc_cursor: extras.gc_cursor,
+                reserved: extras.reserved,
+                reserved_free: extras.reserved_free,
+                pinned: extras.pinned,
+                watched: extras.watched,
+                idx_salt: extras.idx_salt,
+                id: extras.id,
+                redact_values: extras.redact_values,
+                ..PrisonExtras::new()
+            })
+        });
+        new_internal.depth_limit = internal.depth_limit;
+        #[cfg(feature = "lru")]
+        {
+            new_internal.lru_clock = internal.lru_clock;
+        }
+        #[cfg(feature = "ttl")]
+        {
+            new_internal.ttl_now = internal.ttl_now;
+            new_internal.next_expiry = internal.next_expiry;
+        }
+        #[cfg(feature = "uuid")]
+        {
+            new_internal.uuids = mem_take(&mut internal.uuids);
+        }
+        #[cfg(feature = "dirty")]
+        {
+            new_internal.dirty = mem_take(&mut internal.dirty);
+        }
+        return Prison {
+            internal: UnsafeCell::new(new_internal),
+        };
+    }
+
+    //FN Prison::filtered_clone()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Create a new [Prison<T>] holding a clone of every valid value for which `predicate` returns `true`,
+    /// with the exact same layout as this [Prison]
+    ///
+    /// Kept values occupy the same indexes with the same generations, and every other cell becomes free, so any
+    /// [CellKey] to a kept value also references its clone in the new [Prison], while [CellKey]'s to values that
+    /// were filtered out return [AccessError::ValueDeleted(idx, gen)]. This makes it easy to build read-only copies
+    /// for worker threads that still accept the original keys. Reserved indexes, pins, value groups and index salting are
+    /// carried over for the kept values, but the new [Prison] has no active references and its own identity for [ScriptHandle]'s
+    ///
+    /// Like [Prison::clone_val()], cloning does not check or alter the reference counts of the original values
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(15)?;
+    /// let key_2 = prison.insert(20)?;
+    /// let grd_2 = prison.guard_ref(key_2)?;
+    /// let evens = prison.filtered_clone(|key, val| val % 2 == 0);
+    /// assert_eq!(evens.clone_val(key_0)?, 10);
+    /// assert_eq!(evens.clone_val(key_2)?, 20);
+    /// assert!(evens.clone_val(key_1).is_err());
+    /// assert_eq!(evens.num_used(), 2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn filtered_clone<F>(&self, mut predicate: F) -> Prison<T, S>
+    where
+        T: Clone,
+        F: FnMut(CellKey, &T) -> bool,
+    {
+        let internal = internal!(self);
+        let mut new_vec = Vec::with_capacity(internal.vec.capacity());
+        for (idx, cell) in internal.vec.iter().enumerate() {
+            if cell.is_cell() {
+                let key = CellKey {
+                    idx,
+                    gen: IdxD::val(cell.d_gen_or_prev),
+                };
+                let val = unsafe { cell.val.assume_init_ref() };
+                if self._decoded_ref(val, |val| predicate(key, val)) {
+                    new_vec.push(PrisonCell::new_cell(val.clone(), key.gen));
+                    #[cfg(feature = "uuid")]
+                    {
+                        new_vec[idx].uuid = cell.uuid;
+                    }
+                    #[cfg(feature = "ttl")]
+                    {
+                        new_vec[idx].ttl = cell.ttl;
+                        new_vec[idx].expires = cell.expires;
+                    }
+                    continue;
+                }
+            }
+            new_vec.push(PrisonCell::new_free(IdxD::INVALID, IdxD::INVALID, internal.placeholder()));
+        }
+        #[cfg(feature = "recycle_stats")]
+        for (new_cell, cell) in new_vec.iter_mut().zip(internal.vec.iter()) {
+            new_cell.recycled = cell.recycled;
+        }
+        let mut new_internal = PrisonInternal::new(StorageContainer::from_vec(new_vec), internal.placeholder());
+        new_internal.generation = internal.generation;
+        if let Some(extras) = internal.extras.as_ref() {
+            new_internal.extras = Some(Box::new(PrisonExtras {
+                move_hook: extras.move_hook,
+                key_setter: extras.key_setter,
+                next_group: extras.next_group,
+                reserved: extras.reserved.clone(),
+                placeholder: extras.placeholder,
+                idx_salt: extras.idx_salt,
+                redact_values: extras.redact_values,
+                ..PrisonExtras::new()
+            }));
+        }
+        #[cfg(feature = "lru")]
+        {
+            new_internal.lru_clock = internal.lru_clock;
+        }
+        #[cfg(feature = "ttl")]
+        {
+            new_internal.ttl_now = internal.ttl_now;
+            new_internal.next_expiry = internal.next_expiry;
+        }
+        #[cfg(feature = "transform")]
+        {
+            new_internal.transform = internal.transform;
+        }
+        new_internal.depth_limit = internal.depth_limit;
+        let new_prison: Prison<T, S> = Prison {
+            internal: UnsafeCell::new(new_internal),
+        };
+        let new_internal = internal!(new_prison);
+        for idx in (0..new_internal.vec.len()).rev() {
+            if new_internal.vec[idx].is_cell() {
+                continue;
+            }
+            if new_internal.is_reserved(idx) {
+                new_internal.free_count += 1;
+                new_internal.extras().reserved_free += 1;
+            } else if new_prison._link_free(idx).is_err() {
+                unreachable!() //COV_IGNORE
+            }
+        }
+        let vec = &new_internal.vec;
+        let is_kept = |idx: usize| vec[idx].is_cell();
+        if let (Some(extras), Some(new_extras)) = (internal.extras.as_ref(), new_internal.extras.as_mut()) {
+            new_extras.pinned = extras.pinned.iter().copied().filter(|idx| is_kept(*idx)).collect();
+            new_extras.groups = extras
+                .groups
+                .iter()
+                .map(|(id, members)| {
+                    let keys: Vec<CellKey> = members
+                        .iter()
+                        .copied()
+                        .filter(|key| vec[key.idx].is_cell_and_gen_match(key.gen))
+                        .collect();
+                    (*id, CellKeySet::from(&keys[..]))
+                })
+                .collect();
+        }
+        #[cfg(feature = "uuid")]
+        {
+            new_internal.uuids = internal.uuids.iter().copied().filter(|(_, idx)| is_kept(*idx)).collect();
+        }
+        return new_prison;
+    }
+
+    //FN Prison::swap_contents()
+    /// Swap every value and all house-keeping of this [Prison] with another one, without moving any value
+    ///
+    /// This is meant for double-buffered state, where a "current" and a "next" [Prison] trade places every tick
+    /// through shared references. Everything a [CellKey] depends on moves along with the values, including generations,
+    /// the free list, reserved, pinned and hidden cells, groups, hooks, and the id checked by [TypedKey]'s and
+    /// [ScriptHandle]'s, so a key that was valid for one [Prison] is valid for the other afterwards. With the `profile` feature
+    /// the profiling data stays with each [Prison], since any open `ProfileScope` borrows it. Swapping a [Prison] with itself does nothing
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let current: Prison<u32> = Prison::new();
+    /// let next: Prison<u32> = Prison::new();
+    /// let key_0 = current.insert(1)?;
+    /// next.insert(current.clone_val(key_0)? + 1)?;
+    /// current.swap_contents(&next)?;
+    /// assert_eq!(current.clone_val(key_0)?, 2);
+    /// assert_eq!(next.clone_val(key_0)?, 1);
+    /// current.visit_ref(key_0, |_| {
+    ///     assert_eq!(current.swap_contents(&next), Err(AccessError::AccessesOutstanding(1)));
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if either [Prison] is sealed
+    /// - [AccessError::AccessesOutstanding(count)] if any value of either [Prison] is currently referenced
+    pub fn swap_contents(&self, other: &Prison<T, S>) -> Result<(), AccessError> {
+        self._check_unsealed()?;
+        other._check_unsealed()?;
+        if ptr_eq(self, other) {
+            return Ok(());
+        }
+        let (internal_a, internal_b) = (internal!(self), internal!(other));
+        let accesses = internal_a.access_count.count + internal_b.access_count.count;
+        if accesses > 0 {
+            return Err(AccessError::AccessesOutstanding(accesses));
+        }
+        mem_swap(internal_a, internal_b);
+        #[cfg(feature = "profile")]
+        mem_swap(&mut internal_a.profiler, &mut internal_b.profiler);
+        return Ok(());
+    }
+
+    //FN Prison::clone_all()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Create a new [Prison<T>] holding a clone of every value with the exact same layout and bookkeeping as this [Prison],
+    /// so every [CellKey] (and salted index) valid for this [Prison] is valid for the clone and references the cloned value
+    ///
+    /// Unlike [Prison::filtered_clone()], the clone is indistinguishable from the original to any later operation: free cells
+    /// keep their exact free-list order and reserved indexes, the generation counter is copied, and hidden values, pins, value groups,
+    /// move hooks and the per-value state of the optional features are carried over, so both prisons hand out the same keys
+    /// from then on. This makes it suitable for snapshot and rollback. The clone has no active references, its own identity
+    /// for [ScriptHandle]'s, and no watched keys, pending removals, or profiling data. With the `offload` feature every offloaded value
+    /// is reloaded first, and the clone keeps all of its values in memory
+    ///
+    /// Like [Prison::clone_val()], cloning does not check or alter the reference counts of values that are only immutably referenced.
+    /// [Clone] for [Prison] does the same, but panics wherever this method returns an error
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let world: Prison<u32> = Prison::new();
+    /// let key_0 = world.insert(10)?;
+    /// let key_1 = world.insert(20)?;
+    /// world.remove(key_0)?;
+    /// let snapshot = world.clone_all()?;
+    /// world.visit_mut(key_1, |val| {
+    ///     *val += 1;
+    ///     Ok(())
+    /// })?;
+    /// let key_2 = world.insert(30)?;
+    /// let world = snapshot;
+    /// assert_eq!(world.clone_val(key_1)?, 20);
+    /// assert_eq!(world.insert(30)?, key_2);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] if a value is currently mutably referenced
+    /// - [AccessError::OffloadFailed(idx)] if an offloaded value could not be reloaded
+    pub fn clone_all(&self) -> Result<Prison<T, S>, AccessError>
+    where
+        T: Clone,
+    {
+        let internal = internal!(self);
+        #[cfg(feature = "offload")]
+        internal.reload_all()?;
+        if let Some(idx) = internal.vec.iter().position(|cell| cell.is_cell() && cell.refs_or_next == Refs::MUT) {
+            return Err(AccessError::ValueAlreadyMutablyReferenced(idx));
+        }
+        let mut new_vec = Vec::with_capacity(internal.vec.capacity());
+        for (idx, cell) in internal.vec.iter().enumerate() {
+            match cell.is_cell() {
+                true => {
+                    let val = unsafe { cell.val.assume_init_ref() }.clone();
+                    new_vec.push(PrisonCell::new_cell(val, IdxD::val(cell.d_gen_or_prev)));
+                    if cell.refs_or_next == Refs::HIDDEN {
+                        new_vec[idx].refs_or_next = Refs::HIDDEN;
+                    }
+                }
+                false => new_vec.push(PrisonCell::new_free(cell.refs_or_next, IdxD::val(cell.d_gen_or_prev), internal.placeholder())),
+            }
+            #[cfg(feature = "lru")]
+            {
+                new_vec[idx].last_used = cell.last_used;
+            }
+            #[cfg(feature = "uuid")]
+            {
+                new_vec[idx].uuid = cell.uuid;
+            }
+            #[cfg(feature = "dirty")]
+            {
+                new_vec[idx].dirty = cell.dirty;
+            }
+            #[cfg(feature = "recycle_stats")]
+            {
+                new_vec[idx].recycled = cell.recycled;
+            }
+            #[cfg(feature = "ttl")]
+            {
+                new_vec[idx].ttl = cell.ttl;
+                new_vec[idx].expires = cell.expires;
+            }
+        }
+        let mut new_internal = PrisonInternal::new(StorageContainer::from_vec(new_vec), internal.placeholder());
+        new_internal.generation = internal.generation;
+        new_internal.free_count = internal.free_count;
+        new_internal.next_free = internal.next_free;
+        if let Some(extras) = internal.extras.as_ref() {
+            new_internal.extras = Some(Box::new(PrisonExtras {
+                move_hook: extras.move_hook,
+                key_setter: extras.key_setter,
+                next_group: extras.next_group,
+                groups: extras.groups.clone(),
+                gc_cursor: extras.gc_cursor,
+                reserved: extras.reserved.clone(),
+                reserved_free: extras.reserved_free,
+                pinned: extras.pinned.clone(),
+                placeholder: extras.placeholder,
+                idx_salt: extras.idx_salt,
+                redact_values: extras.redact_values,
+                ..PrisonExtras::new()
+            }));
+        }
+        new_internal.depth_limit = internal.depth_limit;
+        #[cfg(feature = "lru")]
+        {
+            new_internal.lru_clock = internal.lru_clock;
+        }
+        #[cfg(feature = "ttl")]
+        {
+            new_internal.ttl_now = internal.ttl_now;
+            new_internal.next_expiry = internal.next_expiry;
+        }
+        #[cfg(feature = "uuid")]
+        {
+            new_internal.uuids = internal.uuids.clone();
+        }
+        #[cfg(feature = "dirty")]
+        {
+            new_internal.dirty = internal.dirty.clone();
+        }
+        #[cfg(feature = "transform")]
+        {
+            new_internal.transform = internal.transform;
+        }
+        return Ok(Prison {
+            internal: UnsafeCell::new(new_internal),
+        });
+    }
+
+    //FN Prison::leak_val()
+    /// Remove the value indexed by the [CellKey] from the [Prison] and intentionally leak it,
+    /// returning a mutable reference to it that lives for the remainder of the program
+    ///
+    /// The cell the value occupied becomes free exactly as if `remove()` was called, but the value
+    /// itself is moved to its own heap allocation that is never freed or dropped. This is intended
+    /// for program-lifetime data loaded during startup, so it can be handed out as a plain reference
+    /// without any further reference counting
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<String> = Prison::new();
+    /// let key_0 = prison.insert(String::from("config.toml"))?;
+    /// let config_path: &'static str = prison.leak_val(key_0)?;
+    /// assert_eq!(config_path, "config.toml");
+    /// assert!(prison.visit_ref(key_0, |val_0| Ok(())).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// Any error that [Prison::remove()] could return
+    pub fn leak_val(&self, key: CellKey) -> Result<&'static mut T, AccessError>
+    where
+        T: 'static,
+    {
+        return Ok(Box::leak(Box::new(self.remove(key)?)));
+    }
+
+    //FN Prison::into_inner_checked()
+    /// Consume the [Prison] and return a [Vec] of every valid value in index order, but only if
+    /// it is idle and its house-keeping values are consistent
+    ///
+    /// Guards borrow the [Prison], so they normally cannot outlive it. A guard that was leaked
+    /// (for example with [mem::forget()](std::mem::forget)) never releases its access however,
+    /// and this method reports it instead of silently discarding the evidence
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// prison.insert(30)?;
+    /// prison.remove(key_0)?;
+    /// assert_eq!(prison.into_inner_checked()?, vec![20, 30]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::AccessesOutstanding(count)] if any access was never released, in which case the [Prison] is dropped
+    /// - [AccessError::MAJOR_MALFUNCTION(msg)] if the free count or reference counts are inconsistent
+    pub fn into_inner_checked(self) -> Result<Vec<T>, AccessError> {
+        let mut internal = self.internal.into_inner();
+        internal.verify_idle()?;
+        #[cfg(feature = "offload")]
+        internal.reload_all()?;
+        let placeholder = internal.placeholder();
+        let free_count = mem_take(&mut internal.free_count);
+        let mut vals = Vec::with_capacity(internal.vec.len() - free_count);
+        for mut cell in mem_take(&mut internal.vec).into_vec() {
+            match cell.is_cell() {
+                true => vals.push(cell.make_free_unchecked(IdxD::INVALID, IdxD::INVALID, None)),
+                false if placeholder.is_some() => unsafe { cell.val.assume_init_drop() },
+                false => {}
+            }
+        }
+        #[cfg(feature = "transform")]
+        if let Some((_, decode)) = internal.transform {
+            return Ok(vals.iter().map(decode).collect());
+        }
+        return Ok(vals);
+    }
+
+    //FN Prison::into_vec()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Consume the [Prison] and return every valid value along with its [CellKey], in index order
+    ///
+    /// This is the inverse of collecting values into a [Prison] (see [Prison::from()](From) and [FromIterator]),
+    /// and moves every value out exactly once without cloning. Hidden values are included, and with the `transform`
+    /// feature every value is returned decoded
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = ["grass", "water", "lava"].into_iter().collect();
+    /// let key_1 = CellKey::from_raw_parts(1, 0);
+    /// prison.remove(key_1)?;
+    /// let key_1 = prison.insert("sand")?;
+    /// let vals = prison.into_vec();
+    /// assert_eq!(vals.len(), 3);
+    /// assert_eq!(vals[1], (key_1, "sand"));
+    /// assert_eq!(vals[2], (CellKey::from_raw_parts(2, 0), "lava"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn into_vec(self) -> Vec<(CellKey, T)> {
+        let mut internal = self.internal.into_inner();
+        let placeholder = internal.placeholder();
+        let free_count = mem_take(&mut internal.free_count);
+        let mut vals = Vec::with_capacity(internal.vec.len() - free_count);
+        for (idx, mut cell) in mem_take(&mut internal.vec).into_vec().into_iter().enumerate() {
+            match cell.is_cell() {
+                true => {
+                    let key = CellKey { idx, gen: IdxD::val(cell.d_gen_or_prev) };
+                    vals.push((key, cell.make_free_unchecked(IdxD::INVALID, IdxD::INVALID, None)));
+                }
+                false if placeholder.is_some() => unsafe { cell.val.assume_init_drop() },
+                false => {}
+            }
+        }
+        #[cfg(feature = "transform")]
+        if let Some((_, decode)) = internal.transform {
+            return vals.iter().map(|(key, val)| (*key, decode(val))).collect();
+        }
+        return vals;
+    }
+
+    //FN Prison::leak_all()
+    /// Consume the [Prison] and intentionally leak every value it contains, returning a slice that
+    /// lives for the remainder of the program where every valid value is at the same index it had in the [Prison]
+    ///
+    /// Free cells are represented by [None]. The memory is never freed and the values are never dropped.
+    /// Because generations are no longer tracked, indexing the slice with a [CellKey]'s index behaves like
+    /// the `*_idx()` methods of [Prison]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<&str> = Prison::new();
+    /// let key_0 = prison.insert("grass.png")?;
+    /// let key_1 = prison.insert("water.png")?;
+    /// let key_2 = prison.insert("lava.png")?;
+    /// prison.remove(key_1)?;
+    /// let assets: &'static [Option<&str>] = prison.leak_all();
+    /// assert_eq!(assets[key_0.idx()], Some("grass.png"));
+    /// assert_eq!(assets[key_1.idx()], None);
+    /// assert_eq!(assets[key_2.idx()], Some("lava.png"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn leak_all(self) -> &'static mut [Option<T>]
+    where
+        T: 'static,
+    {
+        let mut internal = self.internal.into_inner();
+        let placeholder = internal.placeholder();
+        internal.free_count = 0;
+        let leaked: Vec<Option<T>> = mem_take(&mut internal.vec)
+            .into_vec()
+            .into_iter()
+            .map(|mut cell| match cell.is_cell() {
+                true => Some(cell.make_free_unchecked(IdxD::INVALID, IdxD::INVALID, None)),
+                false => {
+                    if placeholder.is_some() {
+                        unsafe { cell.val.assume_init_drop() };
+                    }
+                    None
+                }
+            })
+            .collect();
+        #[cfg(feature = "transform")]
+        let leaked: Vec<Option<T>> = match internal.transform {
+            Some((_, decode)) => leaked.iter().map(|val| val.as_ref().map(decode)).collect(),
+            None => leaked,
+        };
+        return leaked.leak();
+    }
+
+    //FN Prison::sample_keys()
+    /// Return up to `n` [CellKey]s chosen uniformly at random (without repeats) from all visible values in the [Prison]
+    ///
+    /// Values inserted with [Prison::insert_hidden()] are not chosen until they are published. If `n` is greater than
+    /// or equal to the number of visible values, every visible [CellKey] is returned in a random order.
+    ///
+    /// Only available with the `rand` crate feature, and accepts any random number generator implementing [RngCore]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # struct MyRng(u64);
+    /// # impl rand_core::RngCore for MyRng {
+    /// #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+    /// #     fn next_u64(&mut self) -> u64 { self.0 ^= self.0 << 13; self.0 ^= self.0 >> 7; self.0 ^= self.0 << 17; self.0 }
+    /// #     fn fill_bytes(&mut self, dest: &mut [u8]) { rand_core::impls::fill_bytes_via_next(self, dest) }
+    /// #     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> { Ok(self.fill_bytes(dest)) }
+    /// # }
+    /// # fn main() -> Result<(), AccessError> {
+    /// # let mut rng = MyRng(42);
+    /// let prison: Prison<u32> = Prison::new();
+    /// for i in 0..10 {
+    ///     prison.insert(i)?;
+    /// }
+    /// prison.remove_idx(5)?;
+    /// let sampled = prison.sample_keys(3, &mut rng);
+    /// assert_eq!(sampled.len(), 3);
+    /// assert!(sampled.iter().all(|key| key.idx() != 5));
+    /// assert_eq!(prison.sample_keys(100, &mut rng).len(), 9);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn sample_keys<R>(&self, n: usize, rng: &mut R) -> Vec<CellKey>
+    where
+        R: RngCore + ?Sized,
+    {
+        let internal = internal!(self);
+        let mut live: Vec<CellKey> = Vec::with_capacity(internal.vec.len() - internal.free_count);
+        for (idx, cell) in internal.vec.iter().enumerate() {
+            if cell.is_cell() && cell.refs_or_next != Refs::HIDDEN {
+                live.push(CellKey {
+                    idx,
+                    gen: IdxD::val(cell.d_gen_or_prev),
+                });
+            }
+        }
+        let n = n.min(live.len());
+        for i in 0..n {
+            let j = i + _random_below(rng, live.len() - i);
+            live.swap(i, j);
+        }
+        live.truncate(n);
+        return live;
+    }
+
+    //FN Prison::visit_random_mut()
+    /// Visit a single value chosen uniformly at random from all visible values in the [Prison],
+    /// obtaining a mutable reference to the value that is passed into a closure you provide
+    /// along with its [CellKey]
+    ///
+    /// When the [Prison] is dense a random index is re-rolled a few times until it lands on a visible value,
+    /// otherwise the visible values are counted to pick one directly. Values inserted with [Prison::insert_hidden()]
+    /// are never chosen.
+    ///
+    /// Only available with the `rand` crate feature, and accepts any random number generator implementing [RngCore]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # struct MyRng(u64);
+    /// # impl rand_core::RngCore for MyRng {
+    /// #     fn next_u32(&mut self) -> u32 { self.next_u64() as u32 }
+    /// #     fn next_u64(&mut self) -> u64 { self.0 ^= self.0 << 13; self.0 ^= self.0 >> 7; self.0 ^= self.0 << 17; self.0 }
+    /// #     fn fill_bytes(&mut self, dest: &mut [u8]) { rand_core::impls::fill_bytes_via_next(self, dest) }
+    /// #     fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> { Ok(self.fill_bytes(dest)) }
+    /// # }
+    /// # fn main() -> Result<(), AccessError> {
+    /// # let mut rng = MyRng(42);
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// prison.visit_random_mut(&mut rng, |key, val| {
+    ///     *val += 1;
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(prison.clone_val_idx(0)? + prison.clone_val_idx(1)?, 31);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::NoValuesToChoose] if the [Prison] has no visible values to choose from
+    /// - Any error [Prison::visit_mut()] would return for the chosen value
+    #[cfg(feature = "rand")]
+    pub fn visit_random_mut<R, F>(&self, rng: &mut R, mut operation: F) -> Result<(), AccessError>
+    where
+        R: RngCore + ?Sized,
+        F: FnMut(CellKey, &mut T) -> Result<(), AccessError>,
+    {
+        let key = self._random_key(rng)?;
+        return self.visit_mut(key, |val| operation(key, val));
+    }
+
+    //FN Prison::visit_random_ref()
+    /// Visit a single value chosen uniformly at random from all visible values in the [Prison],
+    /// obtaining an immutable reference to the value that is passed into a closure you provide
+    /// along with its [CellKey]
+    ///
+    /// See [Prison::visit_random_mut()] for more info
+    /// ## Errors
+    /// - [AccessError::NoValuesToChoose] if the [Prison] has no visible values to choose from
+    /// - Any error [Prison::visit_ref()] would return for the chosen value
+    #[cfg(feature = "rand")]
+    pub fn visit_random_ref<R, F>(&self, rng: &mut R, mut operation: F) -> Result<(), AccessError>
+    where
+        R: RngCore + ?Sized,
+        F: FnMut(CellKey, &T) -> Result<(), AccessError>,
+    {
+        let key = self._random_key(rng)?;
+        return self.visit_ref(key, |val| operation(key, val));
+    }
+
+    //FN Prison::keys()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Return a [Vec] holding the [CellKey] of every valid value currently in the [Prison], in index order
+    ///
+    /// The returned keys are a snapshot: values inserted or removed afterwards are not reflected in it.
+    /// Like [Prison::state()] this never changes any reference counts, so values that are currently
+    /// referenced are included
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// prison.remove(key_1)?;
+    /// let grd_2 = prison.guard_mut(key_2)?;
+    /// assert_eq!(prison.keys(), vec![key_0, key_2]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn keys(&self) -> Vec<CellKey> {
+        let internal = internal!(self);
+        let mut keys = Vec::with_capacity(internal.vec.len() - internal.free_count);
+        for (idx, cell) in internal.vec.iter().enumerate() {
+            if cell.is_cell() {
+                keys.push(CellKey {
+                    idx,
+                    gen: IdxD::val(cell.d_gen_or_prev),
+                });
+            }
+        }
+        return keys;
+    }
+
+    //FN Prison::contains_key()
+    /// Return `true` if the [CellKey] references a valid value in the [Prison]
+    ///
+    /// This is `true` exactly when the key's index is in range, the cell holds a value, and the generation
+    /// matches, regardless of whether the value is currently referenced. Use [Prison::validate_keys()] to
+    /// find out *why* a key is invalid
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// assert!(prison.contains_key(key_0));
+    /// prison.remove(key_0)?;
+    /// assert!(!prison.contains_key(key_0));
+    /// let key_0_b = prison.insert(20)?;
+    /// assert!(!prison.contains_key(key_0));
+    /// assert!(prison.contains_key(key_0_b));
+    /// assert!(!prison.contains_key(CellKey::from_raw_parts(10, 0)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contains_key(&self, key: CellKey) -> bool {
+        return self._check_key(key).is_ok();
+    }
+
+    //FN Prison::export_keyspace()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Return a [KeyspaceTable] recording the generation of every valid value currently in the [Prison]
+    ///
+    /// The table is a snapshot that can be moved to another thread or process to validate [CellKey]'s
+    /// without accessing the [Prison]: values inserted or removed afterwards are not reflected in it.
+    /// Like [Prison::keys()] this never changes any reference counts, so values that are currently
+    /// referenced are recorded as valid
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let before = prison.export_keyspace();
+    /// prison.remove(key_0)?;
+    /// let key_0_b = prison.insert(30)?;
+    /// let after = prison.export_keyspace();
+    /// assert!(before.is_valid(key_0));
+    /// assert!(!after.is_valid(key_0));
+    /// assert_eq!(before.diff(&after), (vec![key_0], vec![key_0_b]));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn export_keyspace(&self) -> KeyspaceTable {
+        let internal = internal!(self);
+        let mut entries = Vec::with_capacity(internal.vec.len());
+        for cell in internal.vec.iter() {
+            match cell.is_cell() {
+                true => entries.push(Some(IdxD::val(cell.d_gen_or_prev))),
+                false => entries.push(None),
+            }
+        }
+        return KeyspaceTable::from_generations(&entries);
+    }
+
+    //FN Prison::state()
+    /// Return a [CellState] describing the current state of the cell referenced by the [CellKey]
+    ///
+    /// This is a read-only inspection that never changes any reference counts, useful for tests,
+    /// debugging, and debug UIs
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, CellState, RefKind}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1_a = prison.insert(20)?;
+    /// prison.remove(key_1_a)?;
+    /// assert_eq!(prison.state(key_1_a)?, CellState::Free);
+    /// let key_1_b = prison.insert(30)?;
+    /// assert_eq!(prison.state(key_1_a)?, CellState::StaleKey { current_gen: 1 });
+    /// prison.visit_ref(key_0, |val_0| {
+    ///     assert_eq!(prison.state(key_0)?, CellState::Live { gen: 0, refs: RefKind::Immutable(1) });
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(prison.state(key_1_b)?, CellState::Live { gen: 1, refs: RefKind::None });
+    /// assert!(prison.state(CellKey::from_raw_parts(10, 0)).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    pub fn state(&self, key: CellKey) -> Result<CellState, AccessError> {
+        match self.state_idx(key.idx)? {
+            CellState::Live { gen, .. } if gen != key.gen => {
+                Ok(CellState::StaleKey { current_gen: gen })
+            }
+            state => Ok(state),
+        }
+    }
+
+    //FN Prison::key_status()
+    /// Return a [KeyStatus] telling whether the exact value the [CellKey] was created for is still alive
+    ///
+    /// Unlike an access attempt, this distinguishes a value that was removed from one that was replaced by
+    /// a newer value at the same index, and both from a key whose index never existed. It never changes
+    /// any reference counts and never fails
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, KeyStatus}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1_a = prison.insert(20)?;
+    /// assert_eq!(prison.key_status(key_1_a), KeyStatus::Alive);
+    /// prison.remove(key_1_a)?;
+    /// assert_eq!(prison.key_status(key_1_a), KeyStatus::Removed);
+    /// let key_1_b = prison.insert(30)?;
+    /// assert_eq!(prison.key_status(key_1_a), KeyStatus::Replaced { current_gen: 1 });
+    /// assert_eq!(prison.key_status(CellKey::from_raw_parts(10, 0)), KeyStatus::OutOfRange);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn key_status(&self, key: CellKey) -> KeyStatus {
+        match internal!(self).vec.get(key.idx) {
+            Some(cell) if cell.is_cell() => match IdxD::val(cell.d_gen_or_prev) {
+                gen if gen == key.gen => return KeyStatus::Alive,
+                current_gen => return KeyStatus::Replaced { current_gen },
+            },
+            Some(_) => return KeyStatus::Removed,
+            None => return KeyStatus::OutOfRange,
+        }
+    }
+
+    //FN Prison::watch_key()
+    /// Register interest in the value referenced by the [CellKey], so that [Prison::drain_invalidated()]
+    /// reports it once the value is no longer alive
+    ///
+    /// Keys are checked only when drained, so watching a key adds no cost to any other operation.
+    /// Watching a key that is already watched has no effect, and watching a key that is not alive
+    /// reports it on the next drain. Watches belong to this [Prison] and are not copied by clones
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, KeyStatus}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// prison.watch_key(key_0);
+    /// prison.watch_key(key_1);
+    /// prison.watch_key(key_2);
+    /// prison.remove(key_1)?;
+    /// assert!(prison.unwatch_key(key_2));
+    /// prison.remove(key_2)?;
+    /// assert_eq!(prison.drain_invalidated(), vec![(key_1, KeyStatus::Removed)]);
+    /// assert!(prison.drain_invalidated().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn watch_key(&self, key: CellKey) {
+        let watched = &mut internal!(self).extras().watched;
+        if !watched.contains(&key) {
+            watched.push(key);
+        }
+    }
+
+    //FN Prison::unwatch_key()
+    /// Stop watching a key registered with [Prison::watch_key()], returning `true` if it was being watched
+    pub fn unwatch_key(&self, key: CellKey) -> bool {
+        let watched = match internal!(self).extras.as_mut() {
+            Some(extras) => &mut extras.watched,
+            None => return false,
+        };
+        match watched.iter().position(|watched_key| *watched_key == key) {
+            Some(pos) => {
+                watched.remove(pos);
+                return true;
+            }
+            None => return false,
+        }
+    }
+
+    //FN Prison::drain_invalidated()
+    /// Return every key registered with [Prison::watch_key()] whose value is no longer alive, along with
+    /// its [KeyStatus], and stop watching them
+    ///
+    /// Keys are reported in the order they were first watched, and keys that are still alive stay watched
+    pub fn drain_invalidated(&self) -> Vec<(CellKey, KeyStatus)> {
+        let mut invalidated = Vec::new();
+        let mut watched = match internal!(self).extras.as_mut() {
+            Some(extras) => mem_take(&mut extras.watched),
+            None => return invalidated,
+        };
+        watched.retain(|key| match self.key_status(*key) {
+            KeyStatus::Alive => true,
+            status => {
+                invalidated.push((*key, status));
+                false
+            }
+        });
+        internal!(self).extras().watched = watched;
+        return invalidated;
+    }
+
+    //FN Prison::state_idx()
+    /// Return a [CellState] describing the current state of the cell at the index
+    ///
+    /// Similar to `state()` but ignores the generation counter, so it never returns [CellState::StaleKey]
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    pub fn state_idx(&self, idx: usize) -> Result<CellState, AccessError> {
+        let idx = self._idx(idx)?;
+        match internal!(self).vec.get(idx) {
+            Some(cell) if cell.is_cell() => Ok(CellState::Live {
+                gen: IdxD::val(cell.d_gen_or_prev),
+                refs: Refs::kind(cell.refs_or_next),
+            }),
+            Some(_) => Ok(CellState::Free),
+            None => Err(AccessError::IndexOutOfRange(idx)),
+        }
+    }
+
+    //FN Prison::validate_keys()
+    /// #### This operation has O(N) time complexity (where N is the number of keys)
+    ///
+    /// Check many [CellKey]s at once, returning one result per key in the same order
+    ///
+    /// Each result is `Ok(())` if the key's index is in range, the cell holds a valid value, and the generation
+    /// matches. Like [Prison::state()] this never changes any reference counts, so keys that are currently
+    /// referenced are still reported as valid. This lets systems that hold long lists of keys prune stale
+    /// entries in a single pass instead of attempting visits that fail one at a time
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// prison.remove(key_1)?;
+    /// let mut targets = vec![key_0, key_1, CellKey::from_raw_parts(9, 0)];
+    /// assert_eq!(prison.validate_keys(&targets), vec![
+    ///     Ok(()),
+    ///     Err(AccessError::ValueDeleted(1, 0)),
+    ///     Err(AccessError::IndexOutOfRange(9)),
+    /// ]);
+    /// let valid = prison.validate_keys(&targets);
+    /// let mut valid = valid.iter();
+    /// targets.retain(|_| valid.next().map_or(false, |res| res.is_ok()));
+    /// assert_eq!(targets, vec![key_0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn validate_keys(&self, keys: &[CellKey]) -> Vec<Result<(), AccessError>> {
+        return keys.iter().map(|key| self._check_key(*key)).collect();
+    }
+
+    //FN Prison::profile_scope()
+    /// Begin a named profiling scope, returning a [ProfileScope] guard that ends the scope when it goes out of scope
+    ///
+    /// While the scope is the innermost active scope, every reference acquisition on this [Prison]
+    /// (successful or not) and the time spent inside every `visit()` closure is attributed to `name`.
+    /// Scopes may be nested, in which case only the innermost scope is credited; time spent in a
+    /// `visit()` that itself contains nested visits is counted inclusively. Activity while no
+    /// scope is active is not recorded.
+    ///
+    /// Re-entering a scope with the same name accumulates into the same entry of [Prison::profile_report()]
+    ///
+    /// Only available with the `profile` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, ProfileScope}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let physics = prison.profile_scope("physics");
+    /// prison.visit_mut(key_0, |val_0| {
+    ///     assert!(prison.visit_ref(key_0, |val_0_again| Ok(())).is_err());
+    ///     Ok(())
+    /// })?;
+    /// ProfileScope::end(physics);
+    /// let report = prison.profile_report();
+    /// assert_eq!(report[0].name, "physics");
+    /// assert_eq!(report[0].acquisitions, 1);
+    /// assert_eq!(report[0].contention_errors, 1);
+    /// assert_eq!(report[0].visits, 1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "profile")]
+    #[must_use = "profile scope will immediately fall out of scope"]
+    pub fn profile_scope<'a>(&'a self, name: &'static str) -> ProfileScope<'a, T, S> {
+        let profiler = &mut internal!(self).profiler;
+        let depth = profiler.stack.len();
+        let scope_idx = match profiler.scopes.iter().position(|scope| scope.name == name) {
+            Some(scope_idx) => scope_idx,
+            None => {
+                profiler.scopes.push(ScopeProfile::new(name));
+                profiler.scopes.len() - 1
+            }
+        };
+        profiler.stack.push(scope_idx);
+        return ProfileScope {
+            prison: self,
+            depth,
+        };
+    }
+
+    //FN Prison::profile_report()
+    /// Return a copy of the statistics collected for every scope started with [Prison::profile_scope()],
+    /// in the order the scopes were first entered
+    ///
+    /// Only available with the `profile` feature
+    #[cfg(feature = "profile")]
+    pub fn profile_report(&self) -> Vec<ScopeProfile> {
+        return internal!(self).profiler.scopes.clone();
+    }
+
+    //FN Prison::profile_reset()
+    /// Reset the statistics of every profiling scope to zero, and forget the closure durations of every key
+    ///
+    /// Scopes that are still active remain active and continue collecting statistics
+    ///
+    /// Only available with the `profile` feature
+    #[cfg(feature = "profile")]
+    pub fn profile_reset(&self) {
+        let profiler = &mut internal!(self).profiler;
+        for scope in profiler.scopes.iter_mut() {
+            *scope = ScopeProfile::new(scope.name);
+        }
+        profiler.key_times.clear();
+    }
+
+    //FN Prison::visit_mut_timed()
+    /// Exactly like [Prison::visit_mut()], but also measure how long the closure runs and add the
+    /// measurement to the timing statistics of the key and of the innermost active profiling scope
+    ///
+    /// The time spent acquiring and releasing the reference is not included. Statistics are
+    /// retrieved with [Prison::visit_timings()], [Prison::slowest_visits()] and the `closure_times` of
+    /// [Prison::profile_report()], and cleared with [Prison::profile_reset()]. This makes it possible
+    /// to find the closures that hold values for so long that they block structural operations,
+    /// without attaching an external profiler
+    ///
+    /// Only available with the `profile` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// prison.visit_mut_timed(key_0, |val_0| {
+    ///     *val_0 += 1;
+    ///     Ok(())
+    /// })?;
+    /// prison.visit_mut_timed(key_1, |val_1| {
+    ///     std::thread::sleep(std::time::Duration::from_millis(5));
+    ///     Ok(())
+    /// })?;
+    /// assert_eq!(prison.visit_timings(key_0).unwrap().count, 1);
+    /// assert_eq!(prison.slowest_visits(1)[0].0, key_1);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// Same as [Prison::visit_mut()], in which case nothing is recorded
+    #[cfg(feature = "profile")]
+    pub fn visit_mut_timed<R, F>(&self, key: CellKey, mut operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&mut T) -> Result<R, AccessError>,
+    {
+        let mut elapsed = None;
+        let result = self.visit_mut(key, |val| {
+            let start = Instant::now();
+            let result = operation(val);
+            elapsed = Some(start.elapsed());
+            result
+        });
+        if let Some(elapsed) = elapsed {
+            internal!(self).profiler.record_timed(key, elapsed);
+        }
+        return result;
+    }
+
+    //FN Prison::visit_ref_timed()
+    /// Exactly like [Prison::visit_ref()], but also measure how long the closure runs
+    ///
+    /// See [Prison::visit_mut_timed()]
+    ///
+    /// Only available with the `profile` feature
+    /// ## Errors
+    /// Same as [Prison::visit_ref()], in which case nothing is recorded
+    #[cfg(feature = "profile")]
+    pub fn visit_ref_timed<R, F>(&self, key: CellKey, mut operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&T) -> Result<R, AccessError>,
+    {
+        let mut elapsed = None;
+        let result = self.visit_ref(key, |val| {
+            let start = Instant::now();
+            let result = operation(val);
+            elapsed = Some(start.elapsed());
+            result
+        });
+        if let Some(elapsed) = elapsed {
+            internal!(self).profiler.record_timed(key, elapsed);
+        }
+        return result;
+    }
+
+    //FN Prison::visit_timings()
+    /// Return a copy of the closure durations recorded for the [CellKey] by [Prison::visit_mut_timed()]
+    /// and [Prison::visit_ref_timed()], or [None] if no timed visit to this exact key was recorded
+    ///
+    /// Only available with the `profile` feature
+    #[cfg(feature = "profile")]
+    pub fn visit_timings(&self, key: CellKey) -> Option<DurationHistogram> {
+        let key_times = &internal!(self).profiler.key_times;
+        match key_times.binary_search_by_key(&key.idx, |(timed_key, _)| timed_key.idx) {
+            Ok(pos) if key_times[pos].0 == key => return Some(key_times[pos].1.clone()),
+            _ => return None,
+        }
+    }
+
+    //FN Prison::slowest_visits()
+    /// #### This operation has O(N log N) time complexity (where N is the number of timed keys)
+    ///
+    /// Return up to `n` keys recorded by [Prison::visit_mut_timed()] and [Prison::visit_ref_timed()]
+    /// along with their closure durations, ordered from the longest single closure run to the shortest
+    ///
+    /// Only available with the `profile` feature
+    #[cfg(feature = "profile")]
+    pub fn slowest_visits(&self, n: usize) -> Vec<(CellKey, DurationHistogram)> {
+        let mut key_times = internal!(self).profiler.key_times.clone();
+        key_times.sort_by_key(|(_, times)| core::cmp::Reverse(times.max));
+        key_times.truncate(n);
+        return key_times;
+    }
+
+    //FN Prison::least_recently_used()
+    /// #### This operation has O(N log N) time complexity
+    ///
+    /// Return the [CellKey]'s of up to `n` valid values that were used the longest time ago,
+    /// ordered from least to most recently used
+    ///
+    /// A value counts as used when it is inserted, overwritten, recycled, or when any `visit()`,
+    /// `guard()`, or other reference to it is successfully acquired. This allows cache-eviction
+    /// policies to be built directly on the [Prison] without a separate LRU list
+    ///
+    /// Only available with the `lru` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let cache: Prison<&str> = Prison::new();
+    /// let key_0 = cache.insert("a")?;
+    /// let key_1 = cache.insert("b")?;
+    /// let key_2 = cache.insert("c")?;
+    /// cache.visit_ref(key_0, |val_0| Ok(()))?;
+    /// assert_eq!(cache.least_recently_used(2), vec![key_1, key_2]);
+    /// assert_eq!(cache.most_recently_used(1), vec![key_0]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "lru")]
+    pub fn least_recently_used(&self, n: usize) -> Vec<CellKey> {
+        let mut used = self._lru_order();
+        used.truncate(n);
+        return used;
+    }
+
+    //FN Prison::most_recently_used()
+    /// #### This operation has O(N log N) time complexity
+    ///
+    /// Return the [CellKey]'s of up to `n` valid values that were used most recently,
+    /// ordered from most to least recently used
+    ///
+    /// See [Prison::least_recently_used()] for what counts as a use
+    ///
+    /// Only available with the `lru` feature
+    #[cfg(feature = "lru")]
+    pub fn most_recently_used(&self, n: usize) -> Vec<CellKey> {
+        let mut used = self._lru_order();
+        used.reverse();
+        used.truncate(n);
+        return used;
+    }
+
+    //FN Prison::enable_offload()
+    /// Set the file that cold values are offloaded to, along with the functions used to turn a value into
+    /// bytes and back
+    ///
+    /// Once set, [Prison::offload()] and [Prison::offload_coldest()] can move unreferenced values out of memory:
+    /// the value is serialized to the end of the file and replaced in its cell by `T::default()`, which should be cheap
+    /// to keep around. The next time the value is accessed through any `visit()`, `guard()`, `clone_val()`, `read()`
+    /// or `remove()` method it is deserialized from the file and put back *before* the access proceeds, so callers never
+    /// observe the stand-in. Values that are pinned with [Prison::pin()] are never offloaded, which keeps hot keys in memory.
+    /// The file is overwritten from its start and is truncated every time the last offloaded value is reloaded.
+    ///
+    /// Operations that read every cell directly without acquiring references (such as snapshots, [Prison::filtered_clone()],
+    /// [Prison::map_into()], [Prison::into_vec()] or [Prison::leak_all()]) see the stand-in of an offloaded value, call [Prison::reload_all()] before them.
+    /// With the `transform` feature the functions receive and return values in their stored, encoded form.
+    /// Calling this again reloads every offloaded value from the previous file before switching
+    ///
+    /// Only available with the `offload` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// # let path = std::env::temp_dir().join(format!("grit_offload_doc_{}", std::process::id()));
+    /// let file = std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(&path).unwrap();
+    /// let meshes: Prison<Vec<u8>> = Prison::new();
+    /// meshes.enable_offload(file, |mesh| mesh.clone(), |bytes| bytes.to_vec())?;
+    /// let key_0 = meshes.insert(vec![1, 2, 3])?;
+    /// let key_1 = meshes.insert(vec![4, 5, 6])?;
+    /// meshes.visit_ref(key_0, |mesh| Ok(()))?;
+    /// assert_eq!(meshes.offload_coldest(1)?, 1);
+    /// assert!(meshes.is_offloaded(key_1));
+    /// meshes.visit_ref(key_1, |mesh| {
+    ///     assert_eq!(*mesh, vec![4, 5, 6]);
+    ///     Ok(())
+    /// })?;
+    /// assert!(!meshes.is_offloaded(key_1));
+    /// # std::fs::remove_file(&path).unwrap();
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::OffloadFailed(idx)] if a value offloaded to the previous file could not be reloaded
+    #[cfg(feature = "offload")]
+    pub fn enable_offload(&self, file: File, serialize: fn(&T) -> Vec<u8>, deserialize: fn(&[u8]) -> T) -> Result<(), AccessError> {
+        let internal = internal!(self);
+        internal.reload_all()?;
+        internal.offloader = Some(Offloader {
+            file,
+            end: 0,
+            serialize,
+            deserialize,
+        });
+        return Ok(());
+    }
+
+    //FN Prison::offload()
+    /// Move the value at the [CellKey] out of memory and into the file set with [Prison::enable_offload()],
+    /// leaving `T::default()` in its place until the value is next accessed
+    ///
+    /// Offloading a value that is already offloaded does nothing
+    ///
+    /// Only available with the `offload` feature
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or the generation does not match
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)], [AccessError::ValueStillImmutablyReferenced(idx)] or [AccessError::ValueHidden(idx)] if the value is currently referenced or hidden
+    /// - [AccessError::ValuePinned(idx)] if the value is pinned
+    /// - [AccessError::OffloadFailed(idx)] if no offload file is set or writing to it failed, in which case the value stays in memory
+    #[cfg(feature = "offload")]
+    pub fn offload(&self, key: CellKey) -> Result<(), AccessError>
+    where
+        T: Default,
+    {
+        self._check_unsealed()?;
+        self._offload(key)?;
+        return Ok(());
+    }
+
+    //FN Prison::offload_coldest()
+    /// #### This operation has O(N log N) time complexity
+    ///
+    /// Offload up to `n` of the least recently used values (see [Prison::least_recently_used()]) into the file
+    /// set with [Prison::enable_offload()], returning how many values were offloaded
+    ///
+    /// Values that are currently referenced, hidden, pinned, or already offloaded are skipped
+    ///
+    /// Only available with the `offload` feature
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::OffloadFailed(idx)] if no offload file is set or writing to it failed, values offloaded before the error stay offloaded
+    #[cfg(feature = "offload")]
+    pub fn offload_coldest(&self, n: usize) -> Result<usize, AccessError>
+    where
+        T: Default,
+    {
+        self._check_unsealed()?;
+        let mut offloaded = 0;
+        for key in self._lru_order() {
+            if offloaded == n {
+                break;
+            }
+            let internal = internal!(self);
+            if internal.vec[key.idx].refs_or_next != 0 || internal.is_pinned(key.idx) {
+                continue;
+            }
+            if self._offload(key)? {
+                offloaded += 1;
+            }
+        }
+        return Ok(offloaded);
+    }
+
+    //FN Prison::is_offloaded()
+    /// Return `true` if the value at the [CellKey] is currently offloaded to the file set with [Prison::enable_offload()]
+    ///
+    /// Only available with the `offload` feature
+    #[cfg(feature = "offload")]
+    pub fn is_offloaded(&self, key: CellKey) -> bool {
+        let internal = internal!(self);
+        return match internal.offloaded_pos(key.idx) {
+            Ok(pos) => internal.offloaded[pos].1 == key.gen,
+            Err(_) => false,
+        };
+    }
+
+    //FN Prison::num_offloaded()
+    /// Return the number of values currently offloaded to the file set with [Prison::enable_offload()]
+    ///
+    /// Only available with the `offload` feature
+    #[cfg(feature = "offload")]
+    pub fn num_offloaded(&self) -> usize {
+        let internal = internal!(self);
+        return internal.offloaded.iter().filter(|(idx, ..)| internal.offloaded_pos(*idx).is_ok()).count();
+    }
+
+    //FN Prison::reload_all()
+    /// Bring every offloaded value back into memory
+    ///
+    /// See [Prison::enable_offload()] for the operations that need this first
+    ///
+    /// Only available with the `offload` feature
+    /// ## Errors
+    /// - [AccessError::OffloadFailed(idx)] if reading a value from the offload file failed, values reloaded before the error stay in memory
+    #[cfg(feature = "offload")]
+    pub fn reload_all(&self) -> Result<(), AccessError> {
+        return internal!(self).reload_all();
+    }
+
+    //FN Prison::generation_stats()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Return the lowest and highest generations of the values in the [Prison], a histogram of their generations,
+    /// and the next generation the shared counter will hand out
+    ///
+    /// Every removal or replacement of the value holding the highest generation advances the counter, and once it reaches
+    /// [GenerationStats::MAX_GENERATION] those operations return [AccessError::MaxValueForGenerationReached]. Watching
+    /// [GenerationStats::used_fraction()] lets a long-running [Prison] schedule [Prison::purge()] (which restarts the counter)
+    /// during a maintenance window before that happens. Free cells are not counted
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{GenerationStats, Prison}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// let mut key_1 = prison.insert(20)?;
+    /// for val in 0..5 {
+    ///     prison.remove(key_1)?;
+    ///     key_1 = prison.insert(val)?;
+    /// }
+    /// let stats = prison.generation_stats();
+    /// assert_eq!((stats.count, stats.min, stats.max, stats.next), (2, Some(0), Some(5), 5));
+    /// assert_eq!(stats.buckets[GenerationStats::bucket_of(5)], 1);
+    /// assert_eq!(prison.oldest_generation(), Some(0));
+    /// assert!(stats.used_fraction() < 0.01);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generation_stats(&self) -> GenerationStats {
+        let internal = internal!(self);
+        let mut stats = GenerationStats {
+            count: 0,
+            min: None,
+            max: None,
+            next: internal.generation,
+            buckets: [0; GenerationStats::BUCKETS],
+        };
+        for cell in internal.vec.iter().filter(|cell| cell.is_cell()) {
+            let gen = IdxD::val(cell.d_gen_or_prev);
+            stats.count += 1;
+            stats.min = Some(stats.min.map_or(gen, |min| min.min(gen)));
+            stats.max = Some(stats.max.map_or(gen, |max| max.max(gen)));
+            stats.buckets[GenerationStats::bucket_of(gen)] += 1;
+        }
+        return stats;
+    }
+
+    //FN Prison::oldest_generation()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Return the lowest generation of any value in the [Prison], or [None] if it holds no values
+    ///
+    /// This is the value that has gone the longest without being replaced, see [Prison::generation_stats()] for the full distribution
+    pub fn oldest_generation(&self) -> Option<usize> {
+        return internal!(self).vec.iter().filter(|cell| cell.is_cell()).map(|cell| IdxD::val(cell.d_gen_or_prev)).min();
+    }
+
+    //FN Prison::recycle_count()
+    /// Return how many times the cell at `idx` has had its value retired, whether by `remove()`,
+    /// `overwrite()`, or `recycle()`
+    ///
+    /// Every retirement hands out a new generation the next time the cell holds a value, so this is
+    /// the number of distinct [CellKey]s that have been invalidated at this index. [Prison::purge()]
+    /// resets every count to zero along with the generations
+    ///
+    /// Only available with the `recycle_stats` feature
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    #[cfg(feature = "recycle_stats")]
+    pub fn recycle_count(&self, idx: usize) -> Result<u64, AccessError> {
+        match internal!(self).vec.get(idx) {
+            Some(cell) => return Ok(cell.recycled),
+            None => return Err(AccessError::IndexOutOfRange(idx)),
+        }
+    }
+
+    //FN Prison::most_recycled()
+    /// #### This operation has O(N log N) time complexity
+    ///
+    /// Return the indexes and recycle counts of up to `n` cells that have had their values retired
+    /// the most times, ordered from most to least recycled (ties are ordered by index).
+    /// Cells that were never recycled are not reported
+    ///
+    /// See [Prison::recycle_count()] for what counts as a recycle
+    ///
+    /// A long-running [Prison] where a handful of slots account for most of the churn is burning
+    /// through generations far faster than its overall turnover suggests. Because the generation
+    /// counter is shared by every cell, those hot slots bring every [CellKey] closer to
+    /// [AccessError::MaxValueForGenerationReached]. Common fixes are to keep the hot values alive and
+    /// reinitialize them with [Prison::recycle()] only when their identity really changes, to move them into
+    /// a dedicated [Prison], or to [Prison::purge()] during a maintenance window once no keys are held
+    ///
+    /// Only available with the `recycle_stats` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let sessions: Prison<u32> = Prison::new();
+    /// let key_0 = sessions.insert(0)?;
+    /// let mut key_1 = sessions.insert(1)?;
+    /// for i in 0..5 {
+    ///     sessions.remove(key_1)?;
+    ///     key_1 = sessions.insert(i)?;
+    /// }
+    /// sessions.overwrite(0, 10)?;
+    /// assert_eq!(sessions.most_recycled(5), vec![(1, 5), (0, 1)]);
+    /// assert_eq!(sessions.recycle_count(1)?, 5);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "recycle_stats")]
+    pub fn most_recycled(&self, n: usize) -> Vec<(usize, u64)> {
+        let mut counts: Vec<(usize, u64)> = internal!(self)
+            .vec
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.recycled > 0)
+            .map(|(idx, cell)| (idx, cell.recycled))
+            .collect();
+        counts.sort_unstable_by(|(idx_a, count_a), (idx_b, count_b)| count_b.cmp(count_a).then(idx_a.cmp(idx_b)));
+        counts.truncate(n);
+        return counts;
+    }
+
+    //FN Prison::insert_with_ttl()
+    /// Insert a value into the [Prison] that expires `ticks` ticks after the current expiry time,
+    /// and recieve a [CellKey] that can be used to reference it in the future
+    ///
+    /// The expiry time is whatever `now` was last passed to [Prison::tick_expiry()] (starting at 0), and
+    /// the value is removed by the first call to [Prison::tick_expiry()] where `now` reaches its deadline.
+    /// Ticks have no fixed unit, so frames, milliseconds, or server ticks all work as long as they
+    /// never go backwards. Use [Prison::extend_ttl()] to push the deadline back.
+    /// Values inserted any other way never expire
+    ///
+    /// Only available with the `ttl` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let sessions: Prison<&str> = Prison::new();
+    /// let key_0 = sessions.insert_with_ttl("alice", 10)?;
+    /// let key_1 = sessions.insert_with_ttl("bob", 30)?;
+    /// assert!(sessions.tick_expiry(5)?.is_empty());
+    /// assert_eq!(sessions.tick_expiry(10)?, vec![(key_0, "alice")]);
+    /// assert_eq!(sessions.ttl_remaining(key_1)?, Some(20));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// Same as [Prison::insert()]
+    #[cfg(feature = "ttl")]
+    pub fn insert_with_ttl(&self, value: T, ticks: u64) -> Result<CellKey, AccessError> {
+        let key = self.insert(value)?;
+        let internal = internal!(self);
+        let expires = internal.ttl_now.saturating_add(ticks);
+        let cell = &mut internal.vec[key.idx];
+        cell.ttl = ticks;
+        cell.expires = expires;
+        internal.next_expiry = internal.next_expiry.min(expires);
+        return Ok(key);
+    }
+
+    //FN Prison::extend_ttl()
+    /// Restart the countdown of a value inserted with [Prison::insert_with_ttl()], so that it expires the same
+    /// number of ticks after the current expiry time as it was originally given
+    ///
+    /// This is the usual way to keep a session or cache entry alive while it is being used.
+    /// Values without a TTL are left alone and never expire
+    ///
+    /// Only available with the `ttl` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let sessions: Prison<&str> = Prison::new();
+    /// let key_0 = sessions.insert_with_ttl("alice", 10)?;
+    /// sessions.tick_expiry(8)?;
+    /// sessions.extend_ttl(key_0)?;
+    /// assert!(sessions.tick_expiry(12)?.is_empty());
+    /// assert_eq!(sessions.tick_expiry(18)?, vec![(key_0, "alice")]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or already expired
+    #[cfg(feature = "ttl")]
+    pub fn extend_ttl(&self, key: CellKey) -> Result<(), AccessError> {
+        self._check_key(key)?;
+        let internal = internal!(self);
+        let cell = &mut internal.vec[key.idx];
+        if cell.expires == NEVER_EXPIRES && cell.ttl == 0 {
+            return Ok(());
+        }
+        cell.expires = internal.ttl_now.saturating_add(cell.ttl);
+        internal.next_expiry = internal.next_expiry.min(cell.expires);
+        return Ok(());
+    }
+
+    //FN Prison::ttl_remaining()
+    /// Return how many ticks are left before a value expires, or [None] if it was not inserted with
+    /// [Prison::insert_with_ttl()]
+    ///
+    /// A value that is past its deadline but could not be removed yet (see [Prison::tick_expiry()]) has 0 ticks left
+    ///
+    /// Only available with the `ttl` feature
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or already expired
+    #[cfg(feature = "ttl")]
+    pub fn ttl_remaining(&self, key: CellKey) -> Result<Option<u64>, AccessError> {
+        self._check_key(key)?;
+        let internal = internal!(self);
+        let cell = &internal.vec[key.idx];
+        if cell.expires == NEVER_EXPIRES && cell.ttl == 0 {
+            return Ok(None);
+        }
+        return Ok(Some(cell.expires.saturating_sub(internal.ttl_now)));
+    }
+
+    //FN Prison::tick_expiry()
+    /// #### This operation has O(N) time complexity when any value expires
+    ///
+    /// Advance the expiry time to `now`, then remove every value inserted with [Prison::insert_with_ttl()] whose
+    /// deadline is at or before `now`, returning their old [CellKey]s and values in index order
+    ///
+    /// Expired values that are currently referenced or pinned with [Prison::pin()] cannot be removed, so they are
+    /// skipped and returned by the first later call that finds them free. The [Prison] remembers the earliest deadline
+    /// it has handed out, so ticks where nothing has expired yet return immediately without scanning.
+    /// A `now` earlier than the current expiry time does not move it backwards
+    ///
+    /// Only available with the `ttl` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let projectiles: Prison<(f32, f32)> = Prison::new();
+    /// let key_0 = projectiles.insert_with_ttl((0.0, 0.0), 3)?;
+    /// let key_1 = projectiles.insert_with_ttl((5.0, 0.0), 3)?;
+    /// let grd_1 = projectiles.guard_ref(key_1)?;
+    /// assert_eq!(projectiles.tick_expiry(3)?, vec![(key_0, (0.0, 0.0))]);
+    /// drop(grd_1);
+    /// assert_eq!(projectiles.tick_expiry(4)?, vec![(key_1, (5.0, 0.0))]);
+    /// assert_eq!(projectiles.num_used(), 0);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed
+    /// - [AccessError::MaxValueForGenerationReached] if removing a value would need a generation above the maximum
+    ///
+    /// Values removed before an error was encountered are dropped
+    #[cfg(feature = "ttl")]
+    pub fn tick_expiry(&self, now: u64) -> Result<Vec<(CellKey, T)>, AccessError> {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        internal.ttl_now = internal.ttl_now.max(now);
+        if internal.next_expiry > internal.ttl_now {
+            return Ok(Vec::new());
+        }
+        let mut next_expiry = NEVER_EXPIRES;
+        let mut keys = Vec::new();
+        for (idx, cell) in internal.vec.iter().enumerate() {
+            if !cell.is_cell() || cell.expires == NEVER_EXPIRES {
+                continue;
+            }
+            if cell.expires > internal.ttl_now || cell.refs_or_next != 0 || internal.is_pinned(idx) {
+                next_expiry = next_expiry.min(cell.expires);
+                continue;
+            }
+            keys.push(CellKey {
+                idx,
+                gen: IdxD::val(cell.d_gen_or_prev),
+            });
+        }
+        internal.next_expiry = next_expiry;
+        let mut expired = Vec::with_capacity(keys.len());
+        for key in keys {
+            expired.push((key, self.remove(key)?));
+        }
+        return Ok(expired);
+    }
+
+    //FN Prison::drain_dirty()
+    /// Return the [CellKey] of every value that was mutably referenced since the last call, and mark them all clean
+    ///
+    /// A value becomes dirty whenever it is mutably referenced by any `visit_*_mut()` or `guard_*_mut()` method
+    /// (or reinitialized by `recycle()`), and is reported at most once per drain in the order it first became dirty.
+    /// Values that are still mutably referenced when this is called stay dirty and are reported by the next call
+    /// instead, so a change is never reported before it is finished. Removing or overwriting a value discards its
+    /// dirty mark, and inserting a value does not mark it
+    ///
+    /// Only available with the `dirty` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let key_2 = prison.insert(30)?;
+    /// prison.visit_mut(key_2, |val_2| {
+    ///     *val_2 += 1;
+    ///     Ok(())
+    /// })?;
+    /// prison.visit_ref(key_1, |val_1| Ok(()))?;
+    /// prison.visit_many_mut(&[key_0, key_2], |vals| Ok(()))?;
+    /// assert_eq!(prison.drain_dirty(), vec![key_2, key_0]);
+    /// assert!(prison.drain_dirty().is_empty());
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "dirty")]
+    pub fn drain_dirty(&self) -> Vec<CellKey> {
+        let internal = internal!(self);
+        let mut keys = Vec::with_capacity(internal.dirty.len());
+        let mut still_dirty = Vec::new();
+        for idx in mem_take(&mut internal.dirty) {
+            match internal.vec.get_mut(idx) {
+                Some(cell) if cell.is_cell() && cell.dirty => {
+                    if cell.refs_or_next == Refs::MUT {
+                        still_dirty.push(idx);
+                        continue;
+                    }
+                    cell.dirty = false;
+                    keys.push(CellKey {
+                        idx,
+                        gen: IdxD::val(cell.d_gen_or_prev),
+                    });
+                }
+                _ => {}
+            }
+        }
+        internal.dirty = still_dirty;
+        return keys;
+    }
+
+    //FN: Prison::peek_ref()
+    /// Get a reference to a value from it's associated [CellKey], ***ignoring reference counting and most other safety measures***
+    ///
+    /// Returns `Ok(&T)` if the value exists and the generation matches, [Err(AccessError::ValueDeleted(idx, gen))] otherwise
+    ///
+    /// This method is provided as a way for libraries depending on this code to perform niche
+    /// optimized reads of contained values without the overhead of the normal safety checks,
+    /// or when a mutable reference is active but you can statically confirm that the value will
+    /// not be mutated from the begining of the returned `&T`'s lifetime to the end of it's lifetime.
+    ///
+    /// The returned references are intended to be short-lived and safely contained in a scope where no
+    /// mutation of the [Prison] as a whole or ANY of its values takes place
+    /// # Safety
+    /// When you call this method and as long as the `&T` it returns remains in-scope/alive,
+    /// you MUST ensure the following:
+    /// - The value MUST NOT be mutated by ANY source, including active safe reference-counted mutable references
+    /// - NO operation can be performed that could *potentially* cause the underlying memory address of the [Prison]'s data to relocate
+    pub unsafe fn peek_ref<'a>(&'a self, key: CellKey) -> Result<&'a T, AccessError> {
+        match &internal!(self).vec[key.idx] {
+            cell if cell.is_cell_and_gen_match(key.gen) => {
+                Ok(unsafe { cell.val.assume_init_ref() })
+            }
+            _ => Err(AccessError::ValueDeleted(key.idx, key.gen)),
+        }
+    }
+
+    //FN: Prison::peek_ref_idx()
     /// Get a reference to a value from it's associated index, ***ignoring reference counting and most other safety measures***
     ///
-    /// Returns [`Ok(&T)`] if the value exists, [Err(AccessError::ValueDeleted(idx, 0))] otherwise
+    /// Returns `Ok(&T)` if the value exists, [Err(AccessError::ValueDeleted(idx, 0))] otherwise
+    ///
+    /// This method is provided as a way for libraries depending on this code to perform niche
+    /// optimized reads of contained values without the overhead of the normal safety checks,
+    /// or when a mutable reference is active but you can statically confirm that the value will
+    /// not be mutated from the begining of the returned `&T`s lifetime to the end of it's lifetime.
+    ///
+    /// The returned references are intended to be short-lived and safely contained in a scope where no
+    /// mutation of the [Prison] as a whole or ANY of its values takes place
+    /// # Safety
+    /// When you call this method and as long as the `&T` it returns remains in-scope/alive,
+    /// you MUST ensure the following:
+    /// - The value MUST NOT be mutated by ANY source, including active safe reference-counted mutable references
+    /// - NO operation can be performed that could *potentially* cause the underlying memory address of the [Prison]'s data to relocate
+    pub unsafe fn peek_ref_idx<'a>(&'a self, idx: usize) -> Result<&'a T, AccessError> {
+        let idx = self._idx(idx)?;
+        match &internal!(self).vec[idx] {
+            cell if cell.is_cell() => Ok(unsafe { cell.val.assume_init_ref() }),
+            _ => Err(AccessError::ValueDeleted(idx, 0)),
+        }
+    }
+
+    //FN: Prison::peek_ref_or_placeholder_idx()
+    /// Get a reference to the value *or placeholder* at the given index, ***ignoring reference counting,
+    /// free/deleted status, and most other safety measures***
+    ///
+    /// Returns `Ok(&T)` with the value if the index holds one, or with the placeholder value if the index is
+    /// free and the [Prison] was created with [Prison::with_placeholder()]. Returns [Err(AccessError::IndexOutOfRange(idx))]
+    /// if the index is out of range, or [Err(AccessError::ValueDeleted(idx, 0))] if the index is free and the [Prison]
+    /// has no placeholder.
+    ///
+    /// The returned references are intended to be short-lived and safely contained in a scope where no
+    /// mutation of the [Prison] as a whole or ANY of its values takes place
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::with_placeholder(|| 0);
+    /// prison.insert(42)?;
+    /// prison.insert(69)?;
+    /// prison.remove_idx(1)?;
+    /// unsafe {
+    ///     assert_eq!(*prison.peek_ref_or_placeholder_idx(0)?, 42);
+    ///     assert_eq!(*prison.peek_ref_or_placeholder_idx(1)?, 0);
+    ///     assert!(prison.peek_ref_or_placeholder_idx(2).is_err());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// # Safety
+    /// When you call this method and as long as the `&T` it returns remains in-scope/alive,
+    /// you MUST ensure the following:
+    /// - The value MUST NOT be mutated by ANY source, including active safe reference-counted mutable references
+    /// - The index MUST NOT be inserted into, overwritten, or removed
+    /// - NO operation can be performed that could *potentially* cause the underlying memory address of the [Prison]'s data to relocate
+    pub unsafe fn peek_ref_or_placeholder_idx<'a>(&'a self, idx: usize) -> Result<&'a T, AccessError> {
+        let internal = internal!(self);
+        match internal.vec.get(idx) {
+            Some(cell) if cell.is_cell() || internal.placeholder().is_some() => {
+                Ok(unsafe { cell.val.assume_init_ref() })
+            }
+            Some(_) => Err(AccessError::ValueDeleted(idx, 0)),
+            None => Err(AccessError::IndexOutOfRange(idx)),
+        }
+    }
+
+    //------ Prison Private ------
+    //FN Prison::_add_mut_ref()
+    #[doc(hidden)]
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_mut_ref(
+        &self,
+        idx: usize,
+        gen: usize,
+        use_gen: bool,
+    ) -> Result<(&mut PrisonCell<T>, &mut AccessCount), AccessError> {
+        #[cfg(feature = "offload")]
+        internal!(self).reload(idx)?;
+        let result = self._try_mut_ref(idx, gen, use_gen);
+        #[cfg(feature = "profile")]
+        internal!(self).profiler.record_acquire(result.as_ref().err());
+        #[cfg(feature = "lru")]
+        if result.is_ok() {
+            internal!(self).touch(idx);
+        }
+        #[cfg(feature = "dirty")]
+        if result.is_ok() {
+            internal!(self).mark_dirty(idx);
+        }
+        return result;
+    }
+
+    //FN Prison::_try_mut_ref()
+    #[doc(hidden)]
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    fn _try_mut_ref(
+        &self,
+        idx: usize,
+        gen: usize,
+        use_gen: bool,
+    ) -> Result<(&mut PrisonCell<T>, &mut AccessCount), AccessError> {
+        let internal = internal!(self);
+        if internal.access_count.count == SEALED_ACCESSES {
+            return Err(AccessError::PrisonSealed);
+        }
+        if idx >= internal.vec.len() {
+            return Err(AccessError::IndexOutOfRange(idx));
+        }
+        match &mut internal.vec[idx] {
+            cell if cell.is_cell_and_gen_match_opt(gen, use_gen) => {
+                Refs::add_mut(&mut cell.refs_or_next, idx)?;
+                internal.access_count.count += 1;
+                return Ok((cell, &mut internal.access_count));
+            }
+            _ => return Err(AccessError::ValueDeleted(idx, gen)),
+        }
+    }
+
+    //FN Prison::_add_imm_ref()
+    #[doc(hidden)]
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_imm_ref(
+        &self,
+        idx: usize,
+        gen: usize,
+        use_gen: bool,
+    ) -> Result<(&mut PrisonCell<T>, &mut AccessCount), AccessError> {
+        #[cfg(feature = "offload")]
+        internal!(self).reload(idx)?;
+        let result = self._try_imm_ref(idx, gen, use_gen);
+        #[cfg(feature = "profile")]
+        internal!(self).profiler.record_acquire(result.as_ref().err());
+        #[cfg(feature = "lru")]
+        if result.is_ok() {
+            internal!(self).touch(idx);
+        }
+        return result;
+    }
+
+    //FN Prison::_try_imm_ref()
+    #[doc(hidden)]
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    fn _try_imm_ref(
+        &self,
+        idx: usize,
+        gen: usize,
+        use_gen: bool,
+    ) -> Result<(&mut PrisonCell<T>, &mut AccessCount), AccessError> {
+        let internal = internal!(self);
+        if idx >= internal.vec.len() {
+            return Err(AccessError::IndexOutOfRange(idx));
+        }
+        match &mut internal.vec[idx] {
+            cell if cell.is_cell_and_gen_match_opt(gen, use_gen) => {
+                if internal.access_count.count == SEALED_ACCESSES {
+                    if cell.refs_or_next == Refs::HIDDEN {
+                        return Err(AccessError::ValueHidden(idx));
+                    }
+                    return Ok((cell, &mut internal.access_count));
+                }
+                if Refs::add_imm(&mut cell.refs_or_next, idx)? {
+                    internal.access_count.count += 1;
+                }
+                return Ok((cell, &mut internal.access_count));
+            }
+            _ => return Err(AccessError::ValueDeleted(idx, gen)),
+        }
+    }
+
+    //FN Prison::_visit()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _visit<R, F>(&self, operation: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        let internal = internal!(self);
+        internal.visit_depth += 1;
+        if internal.visit_depth > internal.max_depth {
+            internal.max_depth = internal.visit_depth;
+        }
+        let _depth = VisitDepth { prison: self };
+        #[cfg(feature = "profile")]
+        {
+            let start = Instant::now();
+            let result = operation();
+            internal!(self).profiler.record_visit(start.elapsed());
+            return result;
+        }
+        #[cfg(not(feature = "profile"))]
+        return operation();
+    }
+
+    //FN Prison::_encode()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _encode(&self, value: T) -> T {
+        #[cfg(feature = "transform")]
+        if let Some((encode, _)) = internal!(self).transform {
+            return encode(value);
+        }
+        return value;
+    }
+
+    //FN Prison::_decode()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _decode(&self, value: T) -> T {
+        #[cfg(feature = "transform")]
+        if let Some((_, decode)) = internal!(self).transform {
+            return decode(&value);
+        }
+        return value;
+    }
+
+    //FN Prison::_decoded_clone()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _decoded_clone(&self, val: &T) -> T
+    where
+        T: Clone,
+    {
+        #[cfg(feature = "transform")]
+        if let Some((_, decode)) = internal!(self).transform {
+            return decode(val);
+        }
+        return val.clone();
+    }
+
+    //FN Prison::_decoded_ref()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _decoded_ref<R, F>(&self, val: &T, operation: F) -> R
+    where
+        F: FnOnce(&T) -> R,
+    {
+        #[cfg(feature = "transform")]
+        if let Some((_, decode)) = internal!(self).transform {
+            let scratch = decode(val);
+            return operation(&scratch);
+        }
+        return operation(val);
+    }
+
+    //FN Prison::_decoded_mut()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _decoded_mut<R, F>(&self, val: &mut T, operation: F) -> R
+    where
+        F: FnOnce(&mut T) -> R,
+    {
+        #[cfg(feature = "transform")]
+        if let Some((encode, decode)) = internal!(self).transform {
+            let mut scratch = decode(val);
+            let result = operation(&mut scratch);
+            *val = encode(scratch);
+            return result;
+        }
+        return operation(val);
+    }
+
+    //FN Prison::_check_depth()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _check_depth(&self) -> Result<(), AccessError> {
+        let internal = internal!(self);
+        if internal.visit_depth >= internal.depth_limit {
+            return Err(AccessError::VisitDepthExceeded(internal.depth_limit));
+        }
+        return Ok(());
+    }
+
+    //FN Prison::_check_unsealed()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _check_unsealed(&self) -> Result<(), AccessError> {
+        if internal!(self).access_count.count == SEALED_ACCESSES {
+            return Err(AccessError::PrisonSealed);
+        }
+        return Ok(());
+    }
+
+    //FN Prison::_view_cells()
+    #[doc(hidden)]
+    fn _view_cells(&mut self) -> Result<&mut [PrisonCell<T>], AccessError> {
+        self._check_unsealed()?;
+        let internal = self.internal.get_mut();
+        if internal.access_count.count > 0 {
+            return Err(AccessError::AccessesOutstanding(internal.access_count.count));
+        }
+        #[cfg(feature = "offload")]
+        internal.reload_all()?;
+        return Ok(&mut internal.vec[..]);
+    }
+
+    //FN Prison::_sweep_released()
+    /// Called once a reference has been fully released, after the release function has returned,
+    /// so that removals registered with [Prison::remove_when_free()] run through the [Prison] itself
+    /// and never while a reference into it is still held
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _sweep_released(&self) {
+        if !internal!(self).pending_removals().is_empty() {
+            self._run_pending_removals();
+        }
+    }
+
+    //FN Prison::_run_pending_removals()
+    /// Remove every value registered with [Prison::remove_when_free()] that is no longer referenced,
+    /// and discard registrations whose value no longer exists. A registration whose removal fails is kept,
+    /// so that it is retried on a later release
+    #[doc(hidden)]
+    #[cold]
+    #[inline(never)]
+    fn _run_pending_removals(&self) {
+        let mut pending = mem_take(&mut internal!(self).extras().pending_removals);
+        pending.retain(|key| match internal!(self).vec.get(key.idx) {
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) && cell.refs_or_next > 0 && cell.refs_or_next != Refs::HIDDEN => true,
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) => self.remove(*key).is_err(),
+            _ => false,
+        });
+        let internal = internal!(self);
+        let extras = internal.extras();
+        pending.append(&mut extras.pending_removals);
+        extras.pending_removals = pending;
+    }
+
+    //FN Prison::_reserve()
+    #[doc(hidden)]
+    fn _reserve(&self, additional: usize, exact: bool) -> Result<(), AccessError> {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        let len = internal.vec.len();
+        if additional <= internal.vec.capacity() - len {
+            return Ok(());
+        }
+        match len.checked_add(additional) {
+            Some(new_cap) if new_cap <= IdxD::MAX_CAP => {}
+            _ => return Err(AccessError::MaximumCapacityReached),
+        }
+        if internal.access_count.count > 0 {
+            return Err(AccessError::AccessesOutstanding(internal.access_count.count));
+        }
+        match exact {
+            true => internal.vec.reserve_exact(additional),
+            false => internal.vec.reserve(additional),
+        }
+        return Ok(());
+    }
+
+    //FN Prison::_reserve_for_insert()
+    #[doc(hidden)]
+    fn _reserve_for_insert(&self, appended: usize) -> Result<(), AccessError> {
+        let internal = internal!(self);
+        if appended > internal.vec.capacity() - internal.vec.len() && internal.access_count.count > 0 {
+            return Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
+        }
+        return self._reserve(appended, false);
+    }
+
+    //FN Prison::_undo_inserts()
+    #[doc(hidden)]
+    fn _undo_inserts<R>(&self, keys: &[CellKey], acc_err: AccessError) -> Result<R, AccessError> {
+        for key in keys.iter().rev() {
+            self.remove(*key)?;
+        }
+        return Err(acc_err);
+    }
+
+    //FN Prison::_compact()
+    /// Moves every valid value down into the free cells before it and returns the old and new key of every valid value.
+    /// With `reset_gen` every value receives generation 0, otherwise only moved values receive a new generation
+    #[doc(hidden)]
+    fn _compact(&self, reset_gen: bool) -> Result<Vec<(CellKey, CellKey)>, AccessError> {
+        self._check_unsealed()?;
+        let internal = internal!(self);
+        if internal.access_count.count > 0 {
+            return Err(AccessError::AccessesOutstanding(internal.access_count.count));
+        }
+        #[cfg(feature = "offload")]
+        internal.reload_all()?;
+        // Nothing is referenced, so any removal still registered belongs to a value that is already gone
+        if let Some(extras) = internal.extras.as_mut() {
+            extras.pending_removals.clear();
+        }
+        let old_len = internal.vec.len();
+        let moved_gen = match reset_gen {
+            true => 0,
+            false => {
+                let mut moved_gen = internal.generation;
+                for cell in internal.vec.iter().filter(|cell| cell.is_cell()) {
+                    let cell_gen = IdxD::val(cell.d_gen_or_prev);
+                    if cell_gen == IdxD::MAX_GEN {
+                        return Err(AccessError::MaxValueForGenerationReached);
+                    }
+                    moved_gen = moved_gen.max(cell_gen + 1);
+                }
+                moved_gen
+            }
+        };
+        let mut new_idx_of = vec![IdxD::INVALID; old_len];
+        let mut pairs = Vec::with_capacity(old_len - internal.free_count);
+        let mut cursor = 0;
+        for (idx, new_idx_slot) in new_idx_of.iter_mut().enumerate() {
+            while cursor < idx && internal.is_reserved(cursor) {
+                cursor += 1;
+            }
+            let new_idx = match internal.is_reserved(idx) {
+                true => idx,
+                false => cursor,
+            };
+            let cell = &mut internal.vec[idx];
+            if !cell.is_cell() {
+                continue;
+            }
+            let old_gen = IdxD::val(cell.d_gen_or_prev);
+            let new_gen = match (reset_gen, new_idx == idx) {
+                (false, true) => old_gen,
+                _ => moved_gen,
+            };
+            pairs.push((CellKey { idx, gen: old_gen }, CellKey { idx: new_idx, gen: new_gen }));
+            cell.d_gen_or_prev = IdxD::new_type_a(new_gen);
+            *new_idx_slot = new_idx;
+            if new_idx != idx {
+                internal.vec.swap(idx, new_idx);
+            }
+            if new_idx == cursor {
+                cursor += 1;
+            }
+        }
+        while cursor < old_len && internal.is_reserved(cursor) {
+            cursor += 1;
+        }
+        let reserved_end = internal.reserved().last().map_or(0, |(_, end)| (*end).min(old_len));
+        let new_len = cursor.max(reserved_end);
+        if internal.placeholder().is_some() {
+            for cell in internal.vec[new_len..].iter_mut() {
+                unsafe { cell.val.assume_init_drop() };
+            }
+        }
+        internal.vec.truncate(new_len);
+        #[cfg(feature = "recycle_stats")]
+        if reset_gen {
+            for cell in internal.vec.iter_mut() {
+                cell.recycled = 0;
+            }
+        }
+        internal.generation = match reset_gen {
+            true => 0,
+            false if pairs.iter().any(|(old_key, new_key)| old_key != new_key) => moved_gen,
+            false => internal.generation,
+        };
+        internal.next_free = IdxD::INVALID;
+        internal.free_count = internal.reserved_free();
+        for idx in (cursor..new_len).rev() {
+            if !internal.is_reserved(idx) {
+                self._link_free(idx)?;
+            }
+        }
+        let remap = KeyRemap::from_pairs(pairs.clone());
+        if let Some(extras) = internal.extras.as_mut() {
+            extras.pinned.retain(|pin_idx| new_idx_of[*pin_idx] != IdxD::INVALID);
+            for pin_idx in extras.pinned.iter_mut() {
+                *pin_idx = new_idx_of[*pin_idx];
+            }
+            extras.pinned.sort_unstable();
+            for (_, members) in extras.groups.iter_mut() {
+                let keys: Vec<CellKey> = members.iter().filter_map(|key| remap.get(*key)).collect();
+                *members = CellKeySet::from(&keys[..]);
+            }
+            extras.gc_cursor = 0;
+        }
+        #[cfg(feature = "uuid")]
+        for (_, uuid_idx) in internal.uuids.iter_mut() {
+            *uuid_idx = new_idx_of[*uuid_idx];
+        }
+        #[cfg(feature = "dirty")]
+        {
+            internal.dirty.retain(|dirty_idx| new_idx_of[*dirty_idx] != IdxD::INVALID);
+            for dirty_idx in internal.dirty.iter_mut() {
+                *dirty_idx = new_idx_of[*dirty_idx];
+            }
+        }
+        for (old_key, new_key) in pairs.iter() {
+            if old_key != new_key {
+                self._write_key(*new_key);
+            }
+        }
+        return Ok(pairs);
+    }
+
+    //FN Prison::_run_move_hook()
+    #[doc(hidden)]
+    fn _run_move_hook(&self, moved: &[(CellKey, CellKey)]) -> Result<(), AccessError> {
+        if let Some(hook) = internal!(self).move_hook() {
+            for (old_key, new_key) in moved.iter().copied() {
+                self.visit_hidden_mut(new_key, |val| {
+                    hook(old_key, new_key, val);
+                    Ok(())
+                })?;
+            }
+        }
+        return Ok(());
+    }
+
+    //FN Prison::_write_key()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _write_key(&self, key: CellKey) -> CellKey {
+        if let Some(set_key) = internal!(self).key_setter() {
+            self._write_key_with(key, set_key);
+        }
+        return key;
+    }
+
+    //FN Prison::_write_key_with()
+    /// Write the [CellKey] into the value that was just inserted at it, which nothing can be referencing yet
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _write_key_with(&self, key: CellKey, set_key: fn(&mut T, CellKey)) {
+        let cell = &mut internal!(self).vec[key.idx];
+        let val = unsafe { cell.val.assume_init_mut() };
+        self._decoded_mut(val, |val| set_key(val, key));
+    }
+
+    //FN Prison::_recycle_buf()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _recycle_buf<E>(&self, buf: &mut InlineVec<E>) {
+        if buf.is_spilled() {
+            buf.recycle_into(&mut internal!(self).extras().spare_bufs);
+        }
+    }
+
+    //FN Prison::_check_key()
+    #[doc(hidden)]
+    fn _check_key(&self, key: CellKey) -> Result<(), AccessError> {
+        match internal!(self).vec.get(key.idx) {
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) => return Ok(()),
+            Some(_) => return Err(AccessError::ValueDeleted(key.idx, key.gen)),
+            None => return Err(AccessError::IndexOutOfRange(key.idx)),
+        }
+    }
+
+    //FN Prison::_get_or_insert_key()
+    /// Return the [CellKey] if it is still valid, otherwise insert the value returned by `make_value` at the same
+    /// index if it is free, or wherever [Prison::insert()] puts it, and return the new [CellKey]
+    #[doc(hidden)]
+    fn _get_or_insert_key<F>(&self, key: CellKey, make_value: F) -> Result<CellKey, AccessError>
+    where
+        F: FnOnce() -> T,
+    {
+        return match internal!(self).vec.get(key.idx) {
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) => Ok(key),
+            Some(cell) if cell.is_free() => self.insert_at(self.salt_idx(key.idx), make_value()),
+            _ => self.insert(make_value()),
+        };
+    }
+
+    //FN Prison::_idx()
+    #[doc(hidden)]
+    #[inline(always)]
+    fn _idx(&self, idx: usize) -> Result<usize, AccessError> {
+        let salt = internal!(self).idx_salt();
+        if salt == 0 {
+            return Ok(idx);
+        }
+        if cfg!(debug_assertions) && idx & IDX_SALT_MASK != salt {
+            return Err(AccessError::ForeignIndex(idx));
+        }
+        return Ok(idx & !IDX_SALT_MASK);
+    }
+
+    //FN Prison::_free_idx_from()
+    #[doc(hidden)]
+    fn _free_idx_from(&self, start: usize) -> Option<usize> {
+        let internal = internal!(self);
+        if internal.free_count == 0 || start >= internal.vec.len() {
+            return None;
+        }
+        let offset = internal.vec[start..].iter().position(|cell| cell.is_free())?;
+        return Some(self.salt_idx(start + offset));
+    }
+
+    //FN Prison::_link_free()
+    #[doc(hidden)]
+    fn _link_free(&self, idx: usize) -> Result<(), AccessError> {
+        let internal = internal!(self);
+        if internal.next_free != IdxD::INVALID {
+            match &mut internal!(self).vec[internal.next_free] {
+                free if free.is_free() => free.d_gen_or_prev = IdxD::new_type_b(idx),
+                _ => major_malfunction!("the `prison.next_free` index ({}) pointed to an element that WAS NOT FREE", internal.next_free) //COV_IGNORE
+            }
+        }
+        let cell = &mut internal.vec[idx];
+        cell.refs_or_next = internal.next_free;
+        cell.d_gen_or_prev = IdxD::new_type_b(IdxD::INVALID);
+        internal.next_free = idx;
+        internal.free_count += 1;
+        return Ok(());
+    }
+
+    //FN Prison::_unlink_free()
+    #[doc(hidden)]
+    fn _unlink_free(&self, idx: usize) -> Result<(), AccessError> {
+        let internal = internal!(self);
+        if internal.is_reserved(idx) {
+            internal.extras().reserved_free -= 1;
+            internal.free_count -= 1;
+            return Ok(());
+        }
+        let free = &internal.vec[idx];
+        let (prev, next) = (IdxD::val(free.d_gen_or_prev), free.refs_or_next);
+        if prev != IdxD::INVALID {
+            match &mut internal!(self).vec[prev] {
+                prev_free if prev_free.is_free() => prev_free.refs_or_next = next,
+                _ => major_malfunction!("a `Free` index ({}) had a `prev_free` that pointed to an index ({}) that WAS NOT FREE", idx, prev) //COV_IGNORE
+            }
+        } else if internal.next_free == idx {
+            internal.next_free = next;
+        } else {
+            major_malfunction!("a `Free` index ({}) had a `prev_free` value that indicated `INVALID`, meaning it should have been the top of the `free` stack, but `Prison.next_free` ({}) did not match its index", idx, internal.next_free) //COV_IGNORE
+        }
+        if next != IdxD::INVALID {
+            match &mut internal!(self).vec[next] {
+                next_free if next_free.is_free() => next_free.d_gen_or_prev = IdxD::new_type_b(prev),
+                _ => major_malfunction!("a `Free` index ({}) had a `next_free` that pointed to an index ({}) that WAS NOT FREE", idx, next) //COV_IGNORE
+            }
+        }
+        internal.free_count -= 1;
+        return Ok(());
+    }
+
+    //FN Prison::_group_pos()
+    #[doc(hidden)]
+    fn _group_pos(&self, group: GroupKey) -> Result<usize, AccessError> {
+        return internal!(self)
+            .groups()
+            .binary_search_by_key(&group.id, |(id, _)| *id)
+            .map_err(|_| AccessError::GroupNotFound(group.id));
+    }
+
+    //FN Prison::_group_members()
+    #[doc(hidden)]
+    #[allow(clippy::mut_from_ref)]
+    fn _group_members(&self, group: GroupKey) -> Result<&mut CellKeySet, AccessError> {
+        let pos = self._group_pos(group)?;
+        let internal = internal!(self);
+        let vec = &internal.vec;
+        let members = match internal.extras.as_mut() {
+            Some(extras) => &mut extras.groups[pos].1,
+            None => return Err(AccessError::GroupNotFound(group.id)),
+        };
+        members
+            .keys
+            .retain(|key| vec.get(key.idx).is_some_and(|cell| cell.is_cell_and_gen_match(key.gen)));
+        return Ok(members);
+    }
+
+    //FN Prison::_lru_order()
+    #[doc(hidden)]
+    #[cfg(feature = "lru")]
+    fn _lru_order(&self) -> Vec<CellKey> {
+        let internal = internal!(self);
+        let mut used: Vec<(u64, CellKey)> = internal
+            .vec
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.is_cell())
+            .map(|(idx, cell)| {
+                let key = CellKey {
+                    idx,
+                    gen: IdxD::val(cell.d_gen_or_prev),
+                };
+                (cell.last_used, key)
+            })
+            .collect();
+        used.sort_unstable_by_key(|(last_used, _)| *last_used);
+        return used.into_iter().map(|(_, key)| key).collect();
+    }
+
+    //FN Prison::_offload()
+    /// Returns `false` if the value was already offloaded
+    #[doc(hidden)]
+    #[cfg(feature = "offload")]
+    fn _offload(&self, key: CellKey) -> Result<bool, AccessError>
+    where
+        T: Default,
+    {
+        let internal = internal!(self);
+        if key.idx >= internal.vec.len() {
+            return Err(AccessError::IndexOutOfRange(key.idx));
+        }
+        if !internal.vec[key.idx].is_cell_and_gen_match(key.gen) {
+            return Err(AccessError::ValueDeleted(key.idx, key.gen));
+        }
+        let mut refs = internal.vec[key.idx].refs_or_next;
+        Refs::add_mut(&mut refs, key.idx)?;
+        if internal.is_pinned(key.idx) {
+            return Err(AccessError::ValuePinned(key.idx));
+        }
+        let pos = match internal.offloaded_pos(key.idx) {
+            Ok(_) => return Ok(false),
+            Err(pos) => pos,
+        };
+        let cell = &mut internal.vec[key.idx];
+        let offloader = match internal.offloader.as_mut() {
+            Some(offloader) => offloader,
+            None => return Err(AccessError::OffloadFailed(key.idx)),
+        };
+        let bytes = (offloader.serialize)(unsafe { cell.val.assume_init_ref() });
+        let offset = offloader.end;
+        let written = offloader.file.seek(SeekFrom::Start(offset)).and_then(|_| offloader.file.write_all(&bytes));
+        if written.is_err() {
+            return Err(AccessError::OffloadFailed(key.idx));
+        }
+        offloader.end += bytes.len() as u64;
+        drop(mem_take(unsafe { cell.val.assume_init_mut() }));
+        let record = (key.idx, key.gen, offset, bytes.len());
+        match internal.offloaded.get(pos) {
+            Some((stale_idx, ..)) if *stale_idx == key.idx => internal.offloaded[pos] = record,
+            _ => internal.offloaded.insert(pos, record),
+        }
+        return Ok(true);
+    }
+
+    //FN Prison::_random_key()
+    #[doc(hidden)]
+    #[cfg(feature = "rand")]
+    fn _random_key<R>(&self, rng: &mut R) -> Result<CellKey, AccessError>
+    where
+        R: RngCore + ?Sized,
+    {
+        let internal = internal!(self);
+        let len = internal.vec.len();
+        let used = len - internal.free_count;
+        if used > 0 && used * 2 >= len {
+            for _ in 0..RANDOM_REROLLS {
+                let idx = _random_below(rng, len);
+                let cell = &internal.vec[idx];
+                if cell.is_cell() && cell.refs_or_next != Refs::HIDDEN {
+                    return Ok(CellKey {
+                        idx,
+                        gen: IdxD::val(cell.d_gen_or_prev),
+                    });
+                }
+            }
+        }
+        let visible = internal.vec.iter().filter(|cell| cell.is_cell() && cell.refs_or_next != Refs::HIDDEN).count();
+        if visible == 0 {
+            return Err(AccessError::NoValuesToChoose);
+        }
+        let nth = _random_below(rng, visible);
+        let chosen = internal
+            .vec
+            .iter()
+            .enumerate()
+            .filter(|(_, cell)| cell.is_cell() && cell.refs_or_next != Refs::HIDDEN)
+            .nth(nth);
+        match chosen {
+            Some((idx, cell)) => Ok(CellKey {
+                idx,
+                gen: IdxD::val(cell.d_gen_or_prev),
+            }),
+            None => major_malfunction!( //COV_IGNORE
+                "`Prison` had fewer visible values than it counted ({})", //COV_IGNORE
+                visible //COV_IGNORE
+            ), //COV_IGNORE
+        }
+    }
+
+    //FN Prison::_add_many_mut_refs()
+    #[doc(hidden)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_many_mut_refs(
+        &self,
+        cell_keys: &[CellKey],
+    ) -> Result<(InlineVec<&mut T>, InlineVec<&mut usize>, &mut AccessCount), AccessError> {
+        let internal = internal!(self);
+        let mut vals = InlineVec::with_capacity_from(cell_keys.len(), internal.spare_bufs());
+        let mut refs = InlineVec::with_capacity_from(cell_keys.len(), internal.spare_bufs());
+        let mut ref_all_result = Ok(());
+        for key in cell_keys {
+            let ref_result = self._add_mut_ref(key.idx, key.gen, true);
+            match ref_result {
+                Ok((cell, _)) => {
+                    vals.push(unsafe { cell.val.assume_init_mut() });
+                    refs.push(&mut cell.refs_or_next);
+                }
+                Err(e) => {
+                    ref_all_result = Err(e);
+                    break;
+                }
+            }
+        }
+        match ref_all_result {
+            Ok(_) => {
+                return Ok((vals, refs, &mut internal.access_count));
+            }
+            Err(acc_err) => {
+                _remove_many_mut_refs(&mut refs, &mut internal.access_count);
+                self._recycle_buf(&mut refs);
+                self._recycle_buf(&mut vals);
+                return Err(acc_err);
+            }
+        }
+    }
+
+    //FN Prison::_add_array_mut_refs()
+    #[doc(hidden)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_array_mut_refs<const N: usize>(
+        &self,
+        cell_keys: &[CellKey; N],
+    ) -> Result<([&mut T; N], InlineVec<&mut usize>, &mut AccessCount), AccessError> {
+        let (mut vals, refs, accesses) = self._add_many_mut_refs(cell_keys)?;
+        let array = unsafe { ptr_read(vals.as_ptr() as *const [&mut T; N]) };
+        self._recycle_buf(&mut vals);
+        return Ok((array, refs, accesses));
+    }
+
+    //FN Prison::_add_many_mut_refs_idx()
+    #[doc(hidden)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_many_mut_refs_idx(
+        &self,
+        idxs: &[usize],
+    ) -> Result<(InlineVec<&mut T>, InlineVec<&mut usize>, &mut AccessCount), AccessError> {
+        let internal = internal!(self);
+        let mut vals = InlineVec::with_capacity_from(idxs.len(), internal.spare_bufs());
+        let mut refs = InlineVec::with_capacity_from(idxs.len(), internal.spare_bufs());
+        let mut ref_all_result = Ok(());
+        for idx in idxs {
+            let ref_result = self._idx(*idx).and_then(|idx| self._add_mut_ref(idx, 0, false));
+            match ref_result {
+                Ok((cell, _)) => {
+                    vals.push(unsafe { cell.val.assume_init_mut() });
+                    refs.push(&mut cell.refs_or_next);
+                }
+                Err(e) => {
+                    ref_all_result = Err(e);
+                    break;
+                }
+            }
+        }
+        match ref_all_result {
+            Ok(_) => {
+                return Ok((vals, refs, &mut internal.access_count));
+            }
+            Err(acc_err) => {
+                _remove_many_mut_refs(&mut refs, &mut internal.access_count);
+                self._recycle_buf(&mut refs);
+                self._recycle_buf(&mut vals);
+                return Err(acc_err);
+            }
+        }
+    }
+
+    //FN Prison::_add_many_imm_refs()
+    #[doc(hidden)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_many_imm_refs(
+        &self,
+        cell_keys: &[CellKey],
+    ) -> Result<(InlineVec<&T>, InlineVec<&mut usize>, &mut AccessCount), AccessError> {
+        let internal = internal!(self);
+        let mut vals = InlineVec::with_capacity_from(cell_keys.len(), internal.spare_bufs());
+        let mut refs = InlineVec::with_capacity_from(cell_keys.len(), internal.spare_bufs());
+        let mut ref_all_result = Ok(());
+        for key in cell_keys {
+            let ref_result = self._add_imm_ref(key.idx, key.gen, true);
+            match ref_result {
+                Ok((cell, _)) => {
+                    vals.push(unsafe { cell.val.assume_init_ref() });
+                    refs.push(&mut cell.refs_or_next);
+                }
+                Err(e) => {
+                    ref_all_result = Err(e);
+                    break;
+                }
+            }
+        }
+        match ref_all_result {
+            Ok(_) => {
+                return Ok((vals, refs, &mut internal.access_count));
+            }
+            Err(acc_err) => {
+                _remove_many_imm_refs(&mut refs, &mut internal.access_count);
+                self._recycle_buf(&mut refs);
+                self._recycle_buf(&mut vals);
+                return Err(acc_err);
+            }
+        }
+    }
+
+    //FN Prison::_add_many_imm_refs_idx()
+    #[doc(hidden)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_many_imm_refs_idx(
+        &self,
+        idxs: &[usize],
+    ) -> Result<(InlineVec<&T>, InlineVec<&mut usize>, &mut AccessCount), AccessError> {
+        let internal = internal!(self);
+        let mut vals = InlineVec::with_capacity_from(idxs.len(), internal.spare_bufs());
+        let mut refs = InlineVec::with_capacity_from(idxs.len(), internal.spare_bufs());
+        let mut ref_all_result = Ok(());
+        for idx in idxs {
+            let ref_result = self._idx(*idx).and_then(|idx| self._add_imm_ref(idx, 0, false));
+            match ref_result {
+                Ok((cell, _)) => {
+                    vals.push(unsafe { cell.val.assume_init_ref() });
+                    refs.push(&mut cell.refs_or_next);
+                }
+                Err(e) => {
+                    ref_all_result = Err(e);
+                    break;
+                }
+            }
+        }
+        match ref_all_result {
+            Ok(_) => {
+                return Ok((vals, refs, &mut internal.access_count));
+            }
+            Err(acc_err) => {
+                _remove_many_imm_refs(&mut refs, &mut internal.access_count);
+                self._recycle_buf(&mut refs);
+                self._recycle_buf(&mut vals);
+                return Err(acc_err);
+            }
+        }
+    }
+
+    //FN Prison::_add_set_mut_refs()
+    #[doc(hidden)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_set_mut_refs(
+        &self,
+        set: &CellKeySet,
+    ) -> Result<(InlineVec<&mut T>, InlineVec<&mut usize>, &mut AccessCount), AccessError> {
+        let internal = internal!(self);
+        let mut vals = InlineVec::with_capacity_from(set.len(), internal.spare_bufs());
+        let mut refs = InlineVec::with_capacity_from(set.len(), internal.spare_bufs());
+        let mut blocked = CellKeySet::new();
+        for key in set {
+            match self._add_mut_ref(key.idx, key.gen, true) {
+                Ok((cell, _)) => {
+                    vals.push(unsafe { cell.val.assume_init_mut() });
+                    refs.push(&mut cell.refs_or_next);
+                }
+                Err(e) if e.is_contention() => {
+                    blocked.keys.push(*key);
+                }
+                Err(e) => {
+                    _remove_many_mut_refs(&mut refs, &mut internal.access_count);
+                    self._recycle_buf(&mut refs);
+                    self._recycle_buf(&mut vals);
+                    return Err(e);
+                }
+            }
+        }
+        if !blocked.is_empty() {
+            _remove_many_mut_refs(&mut refs, &mut internal.access_count);
+            self._recycle_buf(&mut refs);
+            self._recycle_buf(&mut vals);
+            return Err(AccessError::SetMembersBlocked(blocked));
+        }
+        return Ok((vals, refs, &mut internal.access_count));
+    }
+
+    //FN Prison::_add_set_imm_refs()
+    #[doc(hidden)]
+    #[allow(clippy::mut_from_ref)]
+    fn _add_set_imm_refs(
+        &self,
+        set: &CellKeySet,
+    ) -> Result<(InlineVec<&T>, InlineVec<&mut usize>, &mut AccessCount), AccessError> {
+        let internal = internal!(self);
+        let mut vals = InlineVec::with_capacity_from(set.len(), internal.spare_bufs());
+        let mut refs = InlineVec::with_capacity_from(set.len(), internal.spare_bufs());
+        let mut blocked = CellKeySet::new();
+        for key in set {
+            match self._add_imm_ref(key.idx, key.gen, true) {
+                Ok((cell, _)) => {
+                    vals.push(unsafe { cell.val.assume_init_ref() });
+                    refs.push(&mut cell.refs_or_next);
+                }
+                Err(e) if e.is_contention() => {
+                    blocked.keys.push(*key);
+                }
+                Err(e) => {
+                    _remove_many_imm_refs(&mut refs, &mut internal.access_count);
+                    self._recycle_buf(&mut refs);
+                    self._recycle_buf(&mut vals);
+                    return Err(e);
+                }
+            }
+        }
+        if !blocked.is_empty() {
+            _remove_many_imm_refs(&mut refs, &mut internal.access_count);
+            self._recycle_buf(&mut refs);
+            self._recycle_buf(&mut vals);
+            return Err(AccessError::SetMembersBlocked(blocked));
+        }
+        return Ok((vals, refs, &mut internal.access_count));
+    }
+}
+
+//FN _random_below()
+#[doc(hidden)]
+#[cfg(feature = "rand")]
+fn _random_below<R>(rng: &mut R, bound: usize) -> usize
+where
+    R: RngCore + ?Sized,
+{
+    let bound = bound as u64;
+    let zone = u64::MAX - (u64::MAX - bound + 1) % bound;
+    loop {
+        let val = rng.next_u64();
+        if val <= zone {
+            return (val % bound) as usize;
+        }
+    }
+}
+
+//FN _remove_mut_ref()
+#[doc(hidden)]
+#[inline(always)]
+fn _remove_mut_ref(refs: &mut usize, accesses: &mut AccessCount) {
+    Refs::remove_mut(refs);
+    accesses.count -= 1;
+    accesses.wake_all();
+}
+
+//FN _remove_imm_ref()
+#[doc(hidden)]
+#[inline(always)]
+fn _remove_imm_ref(refs: &mut usize, accesses: &mut AccessCount) {
+    if accesses.count == SEALED_ACCESSES {
+        return;
+    }
+    if Refs::remove_imm(refs) {
+        accesses.count -= 1;
+        accesses.wake_all();
+    }
+}
+
+//FN _remove_many_mut_refs()
+#[doc(hidden)]
+#[inline(always)]
+fn _remove_many_mut_refs(refs_list: &mut [&mut usize], accesses: &mut AccessCount) {
+    for refs in refs_list {
+        _remove_mut_ref(refs, accesses)
+    }
+}
+
+//FN _remove_many_imm_refs()
+#[doc(hidden)]
+#[inline(always)]
+fn _remove_many_imm_refs(refs_list: &mut [&mut usize], accesses: &mut AccessCount) {
+    for refs in refs_list {
+        _remove_imm_ref(refs, accesses)
+    }
+}
+
+//STRUCT RefRelease
+/// Holds the reference counts acquired by a `visit()` method and releases them when dropped,
+/// so they are restored even if the closure passed to the method panics. Pending removals are swept
+/// through the [Prison] once the release function has returned
+#[doc(hidden)]
+struct RefRelease<'a, T, R, F: FnMut(&mut R), S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    refs: R,
+    release: F,
+}
+
+impl<'a, T, R, F: FnMut(&mut R), S: PrisonStorage> RefRelease<'a, T, R, F, S> {
+    //FN RefRelease::new(self, )
+    #[inline(always)]
+    fn new(prison: &'a Prison<T, S>, refs: R, release: F) -> Self {
+        return RefRelease { prison, refs, release };
+    }
+}
+
+//IMPL Drop for RefRelease
+impl<'a, T, R, F: FnMut(&mut R), S: PrisonStorage> Drop for RefRelease<'a, T, R, F, S> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        (self.release)(&mut self.refs);
+        self.prison._sweep_released();
+    }
+}
+
+//STRUCT VisitDepth
+/// Restores the visit depth of a [Prison] when dropped, so it is restored even if the visited closure panics
+#[doc(hidden)]
+struct VisitDepth<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+}
+
+//IMPL Drop for VisitDepth
+impl<'a, T, S: PrisonStorage> Drop for VisitDepth<'a, T, S> {
+    #[inline(always)]
+    fn drop(&mut self) {
+        let prison = self.prison;
+        internal!(prison).visit_depth -= 1;
+    }
+}
+
+//IMPL Default for Prison
+impl<T> Default for Prison<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+//STATIC NEXT_PRISON_ID
+/// Starts at 1 so that an id of 0 in [PrisonExtras] means none was assigned yet
+#[doc(hidden)]
+static NEXT_PRISON_ID: AtomicU32 = AtomicU32::new(1);
+
+//STATIC NEXT_UUID
+#[doc(hidden)]
+#[cfg(feature = "uuid")]
+static NEXT_UUID: AtomicU64 = AtomicU64::new(1);
+
+//STATIC IDX_SALT_SHIFT
+#[doc(hidden)]
+const IDX_SALT_SHIFT: u32 = usize::BITS - 16;
+
+//STATIC IDX_SALT_MASK
+#[doc(hidden)]
+const IDX_SALT_MASK: usize = 0xFFFF << IDX_SALT_SHIFT;
+
+//STATIC SEALED_ACCESSES
+#[doc(hidden)]
+const SEALED_ACCESSES: usize = usize::MAX;
+
+//STATIC NEVER_EXPIRES
+#[doc(hidden)]
+#[cfg(feature = "ttl")]
+const NEVER_EXPIRES: u64 = u64::MAX;
+
+//STATIC RANDOM_REROLLS
+#[doc(hidden)]
+#[cfg(feature = "rand")]
+const RANDOM_REROLLS: usize = 8;
+
+//TYPE TransformFns
+#[doc(hidden)]
+#[cfg(feature = "transform")]
+type TransformFns<T> = (fn(T) -> T, fn(&T) -> T);
+
+//STRUCT Offloader
+#[doc(hidden)]
+#[cfg(feature = "offload")]
+#[derive(Debug)] //COV_IGNORE
+struct Offloader<T> {
+    file: File,
+    end: u64,
+    serialize: fn(&T) -> Vec<u8>,
+    deserialize: fn(&[u8]) -> T,
+}
+
+//STRUCT PrisonExtras
+/// House-keeping values of the less common [Prison] features (hooks, groups, reserved ranges, pins, watches,
+/// deferred removals, batch buffers, placeholders, index validation, interrupts and redaction), boxed and only allocated the first time one of them is used so that every
+/// [Prison] that never uses them stays small
+#[doc(hidden)]
+#[derive(Debug)] //COV_IGNORE
+struct PrisonExtras<T> {
+    move_hook: Option<fn(CellKey, CellKey, &mut T)>,
+    key_setter: Option<fn(&mut T, CellKey)>,
+    next_group: usize,
+    groups: Vec<(usize, CellKeySet)>,
+    gc_cursor: usize,
+    reserved: Vec<(usize, usize)>,
+    reserved_free: usize,
+    pinned: Vec<usize>,
+    watched: Vec<CellKey>,
+    pending_removals: Vec<CellKey>,
+    spare_bufs: Vec<Vec<usize>>,
+    placeholder: Option<fn() -> T>,
+    idx_salt: usize,
+    id: u32,
+    interrupt: bool,
+    redact_values: bool,
+}
+
+impl<T> PrisonExtras<T> {
+    //FN PrisonExtras::new()
+    fn new() -> Self {
+        return PrisonExtras {
+            move_hook: None,
+            key_setter: None,
+            next_group: 0,
+            groups: Vec::new(),
+            gc_cursor: 0,
+            reserved: Vec::new(),
+            reserved_free: 0,
+            pinned: Vec::new(),
+            watched: Vec::new(),
+            pending_removals: Vec::new(),
+            spare_bufs: Vec::new(),
+            placeholder: None,
+            idx_salt: 0,
+            id: 0,
+            interrupt: false,
+            redact_values: false,
+        };
+    }
+}
+
+//STRUCT PrisonInternal
+#[doc(hidden)]
+#[derive(Debug)] //COV_IGNORE
+struct PrisonInternal<T, S: PrisonStorage = VecStorage> {
+    access_count: AccessCount,
+    generation: usize,
+    free_count: usize,
+    next_free: usize,
+    vec: S::Container<PrisonCell<T>>,
+    extras: Option<Box<PrisonExtras<T>>>,
+    visit_depth: usize,
+    max_depth: usize,
+    depth_limit: usize,
+    #[cfg(feature = "profile")]
+    profiler: Profiler,
+    #[cfg(feature = "lru")]
+    lru_clock: u64,
+    #[cfg(feature = "ttl")]
+    ttl_now: u64,
+    #[cfg(feature = "ttl")]
+    next_expiry: u64,
+    #[cfg(feature = "uuid")]
+    uuids: Vec<(u64, usize)>,
+    #[cfg(feature = "dirty")]
+    dirty: Vec<usize>,
+    #[cfg(feature = "transform")]
+    transform: Option<TransformFns<T>>,
+    #[cfg(feature = "offload")]
+    offloader: Option<Offloader<T>>,
+    #[cfg(feature = "offload")]
+    offloaded: Vec<(usize, usize, u64, usize)>,
+}
+
+impl<T, S: PrisonStorage> PrisonInternal<T, S> {
+    //FN PrisonInternal::new()
+    #[inline(always)]
+    fn new(vec: S::Container<PrisonCell<T>>, placeholder: Option<fn() -> T>) -> Self {
+        let mut internal = PrisonInternal {
+            access_count: AccessCount {
+                count: 0,
+                wakers: Vec::new(),
+            },
+            free_count: 0,
+            generation: 0,
+            next_free: IdxD::INVALID,
+            vec,
+            extras: None,
+            visit_depth: 0,
+            max_depth: 0,
+            depth_limit: usize::MAX,
+            #[cfg(feature = "profile")]
+            profiler: Profiler::default(),
+            #[cfg(feature = "lru")]
+            lru_clock: 0,
+            #[cfg(feature = "ttl")]
+            ttl_now: 0,
+            #[cfg(feature = "ttl")]
+            next_expiry: NEVER_EXPIRES,
+            #[cfg(feature = "uuid")]
+            uuids: Vec::new(),
+            #[cfg(feature = "dirty")]
+            dirty: Vec::new(),
+            #[cfg(feature = "transform")]
+            transform: None,
+            #[cfg(feature = "offload")]
+            offloader: None,
+            #[cfg(feature = "offload")]
+            offloaded: Vec::new(),
+        };
+        if placeholder.is_some() {
+            internal.extras().placeholder = placeholder;
+        }
+        return internal;
+    }
+
+    //FN PrisonInternal::verify_idle()
+    fn verify_idle(&self) -> Result<(), AccessError> {
+        if self.access_count.count > 0 && self.access_count.count != SEALED_ACCESSES {
+            return Err(AccessError::AccessesOutstanding(self.access_count.count));
+        }
+        let mut free_count = 0;
+        for (idx, cell) in self.vec.iter().enumerate() {
+            if cell.is_free() {
+                free_count += 1;
+            } else if cell.refs_or_next != 0 && cell.refs_or_next != Refs::HIDDEN {
+                major_malfunction!("cell at index {} still had a reference count ({}) while `Prison.access_count` was 0", idx, cell.refs_or_next) //COV_IGNORE
+            }
+        }
+        if free_count != self.free_count {
+            major_malfunction!("`Prison.free_count` ({}) did not match the number of free cells ({})", self.free_count, free_count) //COV_IGNORE
+        }
+        return Ok(());
+    }
+
+    //FN PrisonInternal::extras()
+    /// Return the [PrisonExtras] for writing, allocating them if no less common feature was used yet
+    #[inline(always)]
+    fn extras(&mut self) -> &mut PrisonExtras<T> {
+        return self.extras.get_or_insert_with(|| Box::new(PrisonExtras::new()));
+    }
+
+    //FN PrisonInternal::move_hook()
+    #[inline(always)]
+    fn move_hook(&self) -> Option<fn(CellKey, CellKey, &mut T)> {
+        return self.extras.as_ref().and_then(|extras| extras.move_hook);
+    }
+
+    //FN PrisonInternal::key_setter()
+    #[inline(always)]
+    fn key_setter(&self) -> Option<fn(&mut T, CellKey)> {
+        return self.extras.as_ref().and_then(|extras| extras.key_setter);
+    }
+
+    //FN PrisonInternal::groups()
+    #[inline(always)]
+    fn groups(&self) -> &[(usize, CellKeySet)] {
+        return self.extras.as_ref().map_or(&[], |extras| extras.groups.as_slice());
+    }
+
+    //FN PrisonInternal::reserved()
+    #[inline(always)]
+    fn reserved(&self) -> &[(usize, usize)] {
+        return self.extras.as_ref().map_or(&[], |extras| extras.reserved.as_slice());
+    }
+
+    //FN PrisonInternal::reserved_free()
+    #[inline(always)]
+    fn reserved_free(&self) -> usize {
+        return self.extras.as_ref().map_or(0, |extras| extras.reserved_free);
+    }
+
+    //FN PrisonInternal::pinned()
+    #[inline(always)]
+    fn pinned(&self) -> &[usize] {
+        return self.extras.as_ref().map_or(&[], |extras| extras.pinned.as_slice());
+    }
+
+    //FN PrisonInternal::pending_removals()
+    #[inline(always)]
+    fn pending_removals(&self) -> &[CellKey] {
+        return self.extras.as_ref().map_or(&[], |extras| extras.pending_removals.as_slice());
+    }
+
+    //FN PrisonInternal::placeholder()
+    #[inline(always)]
+    fn placeholder(&self) -> Option<fn() -> T> {
+        return self.extras.as_ref().and_then(|extras| extras.placeholder);
+    }
+
+    //FN PrisonInternal::idx_salt()
+    #[inline(always)]
+    fn idx_salt(&self) -> usize {
+        return self.extras.as_ref().map_or(0, |extras| extras.idx_salt);
+    }
+
+    //FN PrisonInternal::interrupt()
+    #[inline(always)]
+    fn interrupt(&self) -> bool {
+        return self.extras.as_ref().is_some_and(|extras| extras.interrupt);
+    }
+
+    //FN PrisonInternal::redact_values()
+    #[inline(always)]
+    fn redact_values(&self) -> bool {
+        return self.extras.as_ref().is_some_and(|extras| extras.redact_values);
+    }
+
+    //FN PrisonInternal::spare_bufs()
+    /// Return the pool of spare batch buffers, or [None] if no buffer was ever recycled into it
+    #[inline(always)]
+    fn spare_bufs(&mut self) -> Option<&mut Vec<Vec<usize>>> {
+        return self.extras.as_mut().map(|extras| &mut extras.spare_bufs);
+    }
+
+    //FN PrisonInternal::id()
+    /// Return the unique id of the [Prison], assigning it the first time it is needed
+    fn id(&mut self) -> u32 {
+        let extras = self.extras();
+        if extras.id == 0 {
+            extras.id = NEXT_PRISON_ID.fetch_add(1, AtomicOrdering::Relaxed);
+        }
+        return extras.id;
+    }
+
+    //FN PrisonInternal::is_reserved()
+    #[inline(always)]
+    fn is_reserved(&self, idx: usize) -> bool {
+        return !self.reserved().is_empty() && self.reserved_pos(idx).is_ok();
+    }
+
+    //FN PrisonInternal::reserved_pos()
+    #[inline(always)]
+    fn reserved_pos(&self, idx: usize) -> Result<usize, usize> {
+        return self.reserved().binary_search_by(|(start, end)| match idx {
+            idx if idx < *start => CmpOrdering::Greater,
+            idx if idx >= *end => CmpOrdering::Less,
+            _ => CmpOrdering::Equal,
+        });
+    }
+
+    //FN PrisonInternal::is_pinned()
+    #[inline(always)]
+    fn is_pinned(&self, idx: usize) -> bool {
+        return !self.pinned().is_empty() && self.pinned().binary_search(&idx).is_ok();
+    }
+
+    //FN PrisonInternal::touch()
+    #[cfg(feature = "lru")]
+    #[inline(always)]
+    fn touch(&mut self, idx: usize) {
+        self.lru_clock += 1;
+        self.vec[idx].last_used = self.lru_clock;
+    }
+
+    //FN PrisonInternal::offloaded_pos()
+    /// Returns `Ok(pos)` if the value currently at `idx` is offloaded, or `Err(pos)` with the position its
+    /// record would be inserted at (a stale record left behind by a removed value is found at that position)
+    #[cfg(feature = "offload")]
+    #[inline(always)]
+    fn offloaded_pos(&self, idx: usize) -> Result<usize, usize> {
+        match self.offloaded.binary_search_by_key(&idx, |(offloaded_idx, ..)| *offloaded_idx) {
+            Ok(pos) => match self.vec.get(idx) {
+                Some(cell) if cell.is_cell_and_gen_match(self.offloaded[pos].1) => return Ok(pos),
+                _ => return Err(pos),
+            },
+            Err(pos) => return Err(pos),
+        }
+    }
+
+    //FN PrisonInternal::reload()
+    /// Bring the value at `idx` back from the offload file if it is offloaded, discarding the record
+    /// instead if it was left behind by a value that no longer exists
+    #[cfg(feature = "offload")]
+    #[inline(always)]
+    fn reload(&mut self, idx: usize) -> Result<(), AccessError> {
+        if self.offloaded.is_empty() {
+            return Ok(());
+        }
+        match self.offloaded_pos(idx) {
+            Ok(pos) => {
+                let (_, _, offset, len) = self.offloaded[pos];
+                let offloader = match self.offloader.as_mut() {
+                    Some(offloader) => offloader,
+                    None => return Err(AccessError::OffloadFailed(idx)),
+                };
+                let mut bytes = vec![0u8; len];
+                let read = offloader.file.seek(SeekFrom::Start(offset)).and_then(|_| offloader.file.read_exact(&mut bytes));
+                if read.is_err() {
+                    return Err(AccessError::OffloadFailed(idx));
+                }
+                let val = (offloader.deserialize)(&bytes);
+                unsafe { *self.vec[idx].val.assume_init_mut() = val };
+                self.offloaded.remove(pos);
+            }
+            Err(pos) if self.offloaded.get(pos).is_some_and(|(offloaded_idx, ..)| *offloaded_idx == idx) => {
+                self.offloaded.remove(pos);
+            }
+            Err(_) => return Ok(()),
+        }
+        if self.offloaded.is_empty() {
+            if let Some(offloader) = self.offloader.as_mut() {
+                offloader.end = 0;
+                let _ = offloader.file.set_len(0);
+            }
+        }
+        return Ok(());
+    }
+
+    //FN PrisonInternal::reload_all()
+    #[cfg(feature = "offload")]
+    fn reload_all(&mut self) -> Result<(), AccessError> {
+        while let Some(&(idx, ..)) = self.offloaded.last() {
+            self.reload(idx)?;
+        }
+        return Ok(());
+    }
+
+    //FN PrisonInternal::mark_dirty()
+    #[cfg(feature = "dirty")]
+    #[inline(always)]
+    fn mark_dirty(&mut self, idx: usize) {
+        let cell = &mut self.vec[idx];
+        if !cell.dirty {
+            cell.dirty = true;
+            self.dirty.push(idx);
+        }
+    }
+
+    //FN PrisonInternal::assign_uuid()
+    #[cfg(feature = "uuid")]
+    #[inline(always)]
+    fn assign_uuid(&mut self, idx: usize) {
+        let uuid = NEXT_UUID.fetch_add(1, AtomicOrdering::Relaxed);
+        self.vec[idx].uuid = uuid;
+        self.uuids.push((uuid, idx));
+    }
+
+    //FN PrisonInternal::release_uuid()
+    #[cfg(feature = "uuid")]
+    #[inline(always)]
+    fn release_uuid(&mut self, idx: usize) {
+        let uuid = self.vec[idx].uuid;
+        if let Ok(pos) = self.uuids.binary_search_by_key(&uuid, |(uuid, _)| *uuid) {
+            self.uuids.remove(pos);
+        }
+    }
+}
+
+//IMPL Drop for PrisonInternal
+impl<T, S: PrisonStorage> Drop for PrisonInternal<T, S> {
+    fn drop(&mut self) {
+        #[cfg(debug_assertions)]
+        if let Err(err) = self.verify_idle() {
+            #[cfg(feature = "major_malf_is_panic")]
+            panic!("Prison dropped in an inconsistent state: {}", err);
+            #[cfg(all(not(feature = "major_malf_is_panic"), feature = "std"))]
+            eprintln!("grit-data-prison: Prison dropped in an inconsistent state: {}", err);
+            #[cfg(all(not(feature = "major_malf_is_panic"), not(feature = "std")))]
+            let _ = err;
+        }
+        if self.placeholder().is_some() {
+            for cell in self.vec.iter_mut() {
+                if cell.is_free() {
+                    unsafe { cell.val.assume_init_drop() }
+                }
+            }
+        }
+    }
+}
+
+//STRUCT PrisonCell
+#[doc(hidden)]
+struct PrisonCell<T> {
+    refs_or_next: usize,
+    d_gen_or_prev: usize,
+    val: MaybeUninit<T>,
+    #[cfg(feature = "lru")]
+    last_used: u64,
+    #[cfg(feature = "uuid")]
+    uuid: u64,
+    #[cfg(feature = "dirty")]
+    dirty: bool,
+    #[cfg(feature = "recycle_stats")]
+    recycled: u64,
+    #[cfg(feature = "ttl")]
+    ttl: u64,
+    #[cfg(feature = "ttl")]
+    expires: u64,
+}
+
+//IMPL Debug for PrisonCell
+/// Shows the decoded slot state instead of the raw packed fields, the value itself is never read
+/// because the cell cannot know whether it is initialized or mutably referenced (see [DebugSlot])
+impl<T> Debug for PrisonCell<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        if self.is_free() {
+            return f
+                .debug_struct("Free")
+                .field("next", &DebugLink(self.refs_or_next))
+                .field("prev", &DebugLink(IdxD::val(self.d_gen_or_prev)))
+                .finish();
+        }
+        let mut val = f.debug_struct("Val");
+        val.field("gen", &IdxD::val(self.d_gen_or_prev));
+        match self.refs_or_next {
+            Refs::MUT => val.field("refs", &format_args!("mut")),
+            Refs::HIDDEN => val.field("refs", &format_args!("hidden")),
+            refs => val.field("refs", &refs),
+        };
+        return val.finish_non_exhaustive();
+    }
+}
+
+//IMPL Drop for PrisonCell
+impl<T> Drop for PrisonCell<T> {
+    fn drop(&mut self) {
+        if self.is_cell() {
+            unsafe { self.val.assume_init_drop() }
+        }
+    }
+}
+
+impl<T> PrisonCell<T> {
+    #[inline(always)]
+    fn is_cell_and_gen_match_opt(&self, gen: usize, use_gen: bool) -> bool {
+        IdxD::is_type_a(self.d_gen_or_prev) && (!use_gen || IdxD::val(self.d_gen_or_prev) == gen)
+    }
+    #[inline(always)]
+    fn is_cell_and_gen_match(&self, gen: usize) -> bool {
+        IdxD::is_type_a(self.d_gen_or_prev) && IdxD::val(self.d_gen_or_prev) == gen
+    }
+    #[inline(always)]
+    fn is_cell(&self) -> bool {
+        IdxD::is_type_a(self.d_gen_or_prev)
+    }
+    #[inline(always)]
+    fn is_free(&self) -> bool {
+        IdxD::is_type_b(self.d_gen_or_prev)
+    }
+
+    fn new_free(next: usize, prev: usize, placeholder: Option<fn() -> T>) -> PrisonCell<T> {
+        PrisonCell {
+            refs_or_next: next,
+            d_gen_or_prev: IdxD::new_type_b(prev),
+            val: match placeholder {
+                Some(make_placeholder) => MaybeUninit::new(make_placeholder()),
+                None => MaybeUninit::uninit(),
+            },
+            #[cfg(feature = "lru")]
+            last_used: 0,
+            #[cfg(feature = "uuid")]
+            uuid: 0,
+            #[cfg(feature = "dirty")]
+            dirty: false,
+            #[cfg(feature = "recycle_stats")]
+            recycled: 0,
+            #[cfg(feature = "ttl")]
+            ttl: 0,
+            #[cfg(feature = "ttl")]
+            expires: NEVER_EXPIRES,
+        }
+    }
+
+    fn new_cell(val: T, gen: usize) -> PrisonCell<T> {
+        PrisonCell {
+            refs_or_next: 0,
+            d_gen_or_prev: IdxD::new_type_a(gen),
+            val: MaybeUninit::new(val),
+            #[cfg(feature = "lru")]
+            last_used: 0,
+            #[cfg(feature = "uuid")]
+            uuid: 0,
+            #[cfg(feature = "dirty")]
+            dirty: false,
+            #[cfg(feature = "recycle_stats")]
+            recycled: 0,
+            #[cfg(feature = "ttl")]
+            ttl: 0,
+            #[cfg(feature = "ttl")]
+            expires: NEVER_EXPIRES,
+        }
+    }
+
+    fn make_free_unchecked(&mut self, next: usize, prev: usize, placeholder: Option<fn() -> T>) -> T {
+        let new_val = match placeholder {
+            Some(make_placeholder) => MaybeUninit::new(make_placeholder()),
+            None => MaybeUninit::uninit(),
+        };
+        self.d_gen_or_prev = IdxD::new_type_b(prev);
+        self.refs_or_next = next;
+        #[cfg(feature = "dirty")]
+        {
+            self.dirty = false;
+        }
+        #[cfg(feature = "recycle_stats")]
+        {
+            self.recycled += 1;
+        }
+        #[cfg(feature = "ttl")]
+        self.clear_ttl();
+        unsafe { mem_replace(&mut self.val, new_val).assume_init() }
+    }
+
+    fn make_cell_unchecked(&mut self, val: T, gen: usize, has_placeholder: bool) {
+        if has_placeholder {
+            unsafe { self.val.assume_init_drop() };
+        }
+        self.d_gen_or_prev = IdxD::new_type_a(gen);
+        self.refs_or_next = 0;
+        #[cfg(feature = "ttl")]
+        self.clear_ttl();
+        self.val = MaybeUninit::new(val);
+    }
+
+    #[cfg(feature = "ttl")]
+    #[inline(always)]
+    fn clear_ttl(&mut self) {
+        self.ttl = 0;
+        self.expires = NEVER_EXPIRES;
+    }
+
+    fn overwrite_cell_unchecked(&mut self, val: T, gen: usize) {
+        self.d_gen_or_prev = IdxD::new_type_a(gen);
+        self.refs_or_next = 0;
+        #[cfg(feature = "dirty")]
+        {
+            self.dirty = false;
+        }
+        #[cfg(feature = "recycle_stats")]
+        {
+            self.recycled += 1;
+        }
+        #[cfg(feature = "ttl")]
+        self.clear_ttl();
+        unsafe { self.val.assume_init_drop() };
+        self.val = MaybeUninit::new(val);
+    }
+
+    fn map_into<U, F>(mut self, idx: usize, operation: &mut F, has_placeholder: bool) -> PrisonCell<U>
+    where
+        F: FnMut(CellKey, T) -> U,
+    {
+        if self.is_free() {
+            if has_placeholder {
+                unsafe { self.val.assume_init_drop() };
+            }
+            return PrisonCell {
+                refs_or_next: self.refs_or_next,
+                d_gen_or_prev: self.d_gen_or_prev,
+                val: MaybeUninit::uninit(),
+                #[cfg(feature = "lru")]
+                last_used: self.last_used,
+                #[cfg(feature = "uuid")]
+                uuid: self.uuid,
+                #[cfg(feature = "dirty")]
+                dirty: self.dirty,
+                #[cfg(feature = "recycle_stats")]
+                recycled: self.recycled,
+                #[cfg(feature = "ttl")]
+                ttl: self.ttl,
+                #[cfg(feature = "ttl")]
+                expires: self.expires,
+            };
+        }
+        let gen = IdxD::val(self.d_gen_or_prev);
+        self.d_gen_or_prev = IdxD::new_type_b(IdxD::INVALID);
+        let old_val = unsafe { mem_replace(&mut self.val, MaybeUninit::uninit()).assume_init() };
+        let refs = if self.refs_or_next == Refs::HIDDEN { Refs::HIDDEN } else { 0 };
+        return PrisonCell {
+            refs_or_next: refs,
+            d_gen_or_prev: IdxD::new_type_a(gen),
+            val: MaybeUninit::new(operation(CellKey { idx, gen }, old_val)),
+            #[cfg(feature = "lru")]
+            last_used: self.last_used,
+            #[cfg(feature = "uuid")]
+            uuid: self.uuid,
+            #[cfg(feature = "dirty")]
+            dirty: self.dirty,
+            #[cfg(feature = "recycle_stats")]
+            recycled: self.recycled,
+            #[cfg(feature = "ttl")]
+            ttl: self.ttl,
+            #[cfg(feature = "ttl")]
+            expires: self.expires,
+        };
+    }
+}
+
+//------ Prison Profiling ------
+//STRUCT ScopeProfile
+/// Statistics collected for a single named scope started with [Prison::profile_scope()],
+/// as returned by [Prison::profile_report()]
+///
+/// Only available with the `profile` feature
+#[cfg(feature = "profile")]
+#[derive(Debug, Clone, PartialEq, Eq)] //COV_IGNORE
+pub struct ScopeProfile {
+    /// The name given to [Prison::profile_scope()]
+    pub name: &'static str,
+    /// Number of references (mutable or immutable) successfully acquired
+    pub acquisitions: u64,
+    /// Number of reference acquisitions that failed for any reason
+    pub errors: u64,
+    /// Number of failed acquisitions that were caused by another active reference (see [AccessError::is_contention()])
+    pub contention_errors: u64,
+    /// Number of `visit()` closures run
+    pub visits: u64,
+    /// Total time spent inside `visit()` closures
+    pub visit_time: Duration,
+    /// Distribution of closure durations recorded by [Prison::visit_mut_timed()] and [Prison::visit_ref_timed()]
+    pub closure_times: DurationHistogram,
+}
+
+#[cfg(feature = "profile")]
+impl ScopeProfile {
+    //FN ScopeProfile::new()
+    #[doc(hidden)]
+    fn new(name: &'static str) -> Self {
+        return ScopeProfile {
+            name,
+            acquisitions: 0,
+            errors: 0,
+            contention_errors: 0,
+            visits: 0,
+            visit_time: Duration::ZERO,
+            closure_times: DurationHistogram::default(),
+        };
+    }
+}
+
+//STRUCT ProfileScope
+/// Guard representing an active profiling scope started with [Prison::profile_scope()]
+///
+/// The scope (and any scopes nested inside it that are still active) ends when the [ProfileScope]
+/// goes out of scope. You can manually end it by passing it as the first parameter to the
+/// function [ProfileScope::end(scope)]
+///
+/// Only available with the `profile` feature
+#[cfg(feature = "profile")]
+pub struct ProfileScope<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    depth: usize,
+}
+
+#[cfg(feature = "profile")]
+impl<'a, T, S: PrisonStorage> ProfileScope<'a, T, S> {
+    //FN ProfileScope::end()
+    /// Manually end a [ProfileScope]
+    ///
+    /// This method simply takes ownership of the [ProfileScope] and immediately lets it go out of scope
+    pub fn end(_scope: Self) {}
+}
+
+//IMPL Drop for ProfileScope
+#[cfg(feature = "profile")]
+impl<'a, T, S: PrisonStorage> Drop for ProfileScope<'a, T, S> {
+    fn drop(&mut self) {
+        let prison = self.prison;
+        internal!(prison).profiler.stack.truncate(self.depth);
+    }
+}
+
+//STRUCT Profiler
+#[doc(hidden)]
+#[cfg(feature = "profile")]
+#[derive(Debug, Default)] //COV_IGNORE
+struct Profiler {
+    stack: Vec<usize>,
+    scopes: Vec<ScopeProfile>,
+    key_times: Vec<(CellKey, DurationHistogram)>,
+}
+
+#[cfg(feature = "profile")]
+impl Profiler {
+    //FN Profiler::record_acquire()
+    #[inline(always)]
+    fn record_acquire(&mut self, error: Option<&AccessError>) {
+        if let Some(scope_idx) = self.stack.last() {
+            let scope = &mut self.scopes[*scope_idx];
+            match error {
+                None => scope.acquisitions += 1,
+                Some(acc_err) => {
+                    scope.errors += 1;
+                    if acc_err.is_contention() {
+                        scope.contention_errors += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    //FN Profiler::record_visit()
+    #[inline(always)]
+    fn record_visit(&mut self, elapsed: Duration) {
+        if let Some(scope_idx) = self.stack.last() {
+            let scope = &mut self.scopes[*scope_idx];
+            scope.visits += 1;
+            scope.visit_time += elapsed;
+        }
+    }
+
+    //FN Profiler::record_timed()
+    fn record_timed(&mut self, key: CellKey, elapsed: Duration) {
+        if let Some(scope_idx) = self.stack.last() {
+            self.scopes[*scope_idx].closure_times.record(elapsed);
+        }
+        match self.key_times.binary_search_by_key(&key.idx, |(timed_key, _)| timed_key.idx) {
+            Ok(pos) => {
+                if self.key_times[pos].0 != key {
+                    self.key_times[pos] = (key, DurationHistogram::default());
+                }
+                self.key_times[pos].1.record(elapsed);
+            }
+            Err(pos) => {
+                let mut times = DurationHistogram::default();
+                times.record(elapsed);
+                self.key_times.insert(pos, (key, times));
+            }
+        }
+    }
+}
+
+//STRUCT DurationHistogram
+/// A histogram of durations with power-of-two buckets, used for the closure durations
+/// recorded by [Prison::visit_mut_timed()] and [Prison::visit_ref_timed()]
+///
+/// Bucket `0` counts durations shorter than 1 microsecond, and every following bucket `i` counts
+/// durations of at least `2^(i-1)` and less than `2^i` microseconds. The last bucket also counts
+/// every longer duration
+///
+/// Only available with the `profile` feature
+#[cfg(feature = "profile")]
+#[derive(Debug, Clone, Default, PartialEq, Eq)] //COV_IGNORE
+pub struct DurationHistogram {
+    /// Number of durations recorded
+    pub count: u64,
+    /// Sum of every duration recorded
+    pub total: Duration,
+    /// Longest duration recorded
+    pub max: Duration,
+    /// Number of durations recorded in each bucket
+    pub buckets: [u64; DurationHistogram::BUCKETS],
+}
+
+#[cfg(feature = "profile")]
+impl DurationHistogram {
+    /// The number of buckets in every [DurationHistogram]
+    pub const BUCKETS: usize = 32;
+
+    //FN DurationHistogram::bucket_of()
+    /// Return the index of the bucket a duration is counted in
+    pub fn bucket_of(elapsed: Duration) -> usize {
+        let micros = elapsed.as_micros();
+        if micros == 0 {
+            return 0;
+        }
+        let bucket = (u128::BITS - micros.leading_zeros()) as usize;
+        return bucket.min(Self::BUCKETS - 1);
+    }
+
+    //FN DurationHistogram::mean()
+    /// Return the average duration recorded, or [Duration::ZERO] if nothing was recorded
+    pub fn mean(&self) -> Duration {
+        if self.count == 0 {
+            return Duration::ZERO;
+        }
+        return Duration::from_nanos((self.total.as_nanos() / self.count as u128) as u64);
+    }
+
+    //FN DurationHistogram::percentile()
+    /// Return an upper bound for the duration below which `fraction` (between 0.0 and 1.0) of the recorded
+    /// durations fall, computed from the bucket boundaries and never above [DurationHistogram::max]
+    pub fn percentile(&self, fraction: f64) -> Duration {
+        let target = (self.count as f64 * fraction.clamp(0.0, 1.0)).ceil() as u64;
+        let mut seen = 0;
+        for (bucket, count) in self.buckets.iter().enumerate() {
+            seen += count;
+            if seen >= target.max(1) {
+                return Duration::from_micros(1 << bucket).min(self.max);
+            }
+        }
+        return self.max;
+    }
+
+    //FN DurationHistogram::record()
+    #[doc(hidden)]
+    fn record(&mut self, elapsed: Duration) {
+        self.count += 1;
+        self.total += elapsed;
+        self.max = self.max.max(elapsed);
+        self.buckets[Self::bucket_of(elapsed)] += 1;
+    }
+}
+
+//------ Prison Introspection ------
+//ENUM CellState
+/// The state of a single cell in a [Prison], as returned by [Prison::state()] and [Prison::state_idx()]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)] //COV_IGNORE
+pub enum CellState {
+    /// The cell is marked as free/deleted and holds no value
+    Free,
+    /// The cell holds a valid value with the given generation, currently referenced as described by `refs`
+    Live {
+        /// The generation of the value in the cell
+        gen: usize,
+        /// The kind of references currently active on the value
+        refs: RefKind,
+    },
+    /// The cell holds a valid value, but it is not the value the [CellKey] was created for
+    StaleKey {
+        /// The generation of the value currently in the cell
+        current_gen: usize,
+    },
+}
+
+//ENUM KeyStatus
+/// Whether the value a [CellKey] was created for is still alive, as returned by [Prison::key_status()]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)] //COV_IGNORE
+pub enum KeyStatus {
+    /// The value the [CellKey] was created for is still in the [Prison]
+    Alive,
+    /// The value was removed and the cell is currently free
+    Removed,
+    /// The value was removed and a newer value now lives at the same index
+    Replaced {
+        /// The generation of the value currently in the cell
+        current_gen: usize,
+    },
+    /// The index of the [CellKey] never existed in the [Prison] (or was truncated away)
+    OutOfRange,
+}
+
+//STRUCT RemovalTicket
+/// A handle to a removal registered with [Prison::remove_when_free()], used with [Prison::removal_pending()]
+/// and [Prison::cancel_removal()]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)] //COV_IGNORE
+pub struct RemovalTicket {
+    key: CellKey,
+}
+
+impl RemovalTicket {
+    //FN RemovalTicket::key()
+    /// Return the [CellKey] of the value this ticket removes
+    pub fn key(&self) -> CellKey {
+        return self.key;
+    }
+}
+
+//STRUCT GenerationStats
+/// The distribution of generations among the values of a [Prison], as returned by [Prison::generation_stats()]
+///
+/// Bucket `0` counts values with generation `0`, and every following bucket `i` counts values with a generation of
+/// at least `2^(i-1)` and less than `2^i`
+#[derive(Debug, Clone, PartialEq, Eq)] //COV_IGNORE
+pub struct GenerationStats {
+    /// Number of values counted
+    pub count: usize,
+    /// Lowest generation of any value, or [None] if the [Prison] holds no values
+    pub min: Option<usize>,
+    /// Highest generation of any value, or [None] if the [Prison] holds no values
+    pub max: Option<usize>,
+    /// The generation the next new value will receive, shared by every cell of the [Prison]
+    pub next: usize,
+    /// Number of values counted in each bucket
+    pub buckets: [usize; GenerationStats::BUCKETS],
+}
+
+impl GenerationStats {
+    /// The number of buckets in every [GenerationStats]
+    pub const BUCKETS: usize = usize::BITS as usize;
+    /// The highest generation a value can have, once the generation counter reaches it
+    /// removing or replacing that value returns [AccessError::MaxValueForGenerationReached]
+    pub const MAX_GENERATION: usize = IdxD::MAX_GEN;
+
+    //FN GenerationStats::bucket_of()
+    /// Return the index of the bucket a generation is counted in
+    pub fn bucket_of(gen: usize) -> usize {
+        return (usize::BITS - gen.leading_zeros()) as usize;
+    }
+
+    //FN GenerationStats::remaining()
+    /// Return how many more generations can be handed out before the counter is exhausted
+    pub fn remaining(&self) -> usize {
+        return Self::MAX_GENERATION - self.next;
+    }
+
+    //FN GenerationStats::used_fraction()
+    /// Return how much of the generation space (between 0.0 and 1.0) has already been used
+    pub fn used_fraction(&self) -> f64 {
+        return self.next as f64 / Self::MAX_GENERATION as f64;
+    }
+}
+
+//ENUM RefKind
+/// The kind of references currently active on a value, as reported by [CellState::Live]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)] //COV_IGNORE
+pub enum RefKind {
+    /// The value is not referenced
+    None,
+    /// The value has the given number of active immutable references
+    Immutable(usize),
+    /// The value has one active mutable reference
+    Mutable,
+    /// The value was inserted with [Prison::insert_hidden()] and is not published yet
+    Hidden,
+}
+
+//------ Guarded Prison ------
+//STRUCT PrisonValueMut
+/// Struct representing a mutable reference to a value that has been allowed to leave the
+/// [Prison] temporarily, but remains guarded by a wrapper to prevent it from leaking or never unlocking
+///
+/// [PrisonValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
+/// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value, as well as
+/// [ValueGuardRef<T>] and [ValueGuardMut<T>] so it can be passed to code that accepts any guard
+///
+/// As long as the [PrisonValueMut] remains in scope, the element where it's value resides in the
+/// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
+/// You can manually drop the [PrisonValueMut] out of scope by passing it as the first parameter
+/// to the function [PrisonValueMut::unguard(p_val_mut)]
+///
+/// You can obtain a [PrisonValueMut] by calling `guard_mut()` or `guard_mut_idx()` on a [Prison]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let mut grd_0 = prison.guard_mut(key_0)?;
+/// assert_eq!(*grd_0, 10);
+/// *grd_0 = 20;
+/// PrisonValueMut::unguard(grd_0);
+/// prison.visit_ref(key_0, |val_0| {
+///     assert_eq!(*val_0, 20);
+///     Ok(())
+/// });
+/// # Ok(())
+/// # }
+/// ```
+pub struct PrisonValueMut<'a, T, S: PrisonStorage = VecStorage> {
+    cell: &'a mut PrisonCell<T>,
+    prison_accesses: &'a mut AccessCount,
+    prison: &'a Prison<T, S>,
+    idx: usize,
+}
+
+impl<'a, T, S: PrisonStorage> PrisonValueMut<'a, T, S> {
+    //FN PrisonValueMut::unguard()
+    /// Manually end a [PrisonValueMut] value's temporary guarded absence from the [Prison]
+    ///
+    /// This method simply takes ownership of the [PrisonValueMut] and immediately lets it go out of scope,
+    /// causing it's `drop()` method to be called and clearing its mutable reference in the [Prison]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// let grd_0 = prison.guard_mut_idx(0)?;
+    /// // index 0 CANNOT be accessed here because it is being guarded outside the prison
+    /// assert!(prison.visit_ref_idx(0, |ref_0| Ok(())).is_err());
+    /// PrisonValueMut::unguard(grd_0);
+    /// // index 0 CAN be accessed here because it was returned to the prison
+    /// assert!(prison.visit_ref_idx(0, |ref_0| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unguard(_prison_val_mut: Self) {}
+
+    //FN PrisonValueMut::unguard_and_remove()
+    /// End a [PrisonValueMut] value's temporary guarded absence from the [Prison] and immediately
+    /// remove the element it guarded, returning the owned value
+    ///
+    /// This is equivalent to calling `unguard()` followed by `remove()` with the element's [CellKey],
+    /// but the reference is released and the value taken in one step: wakers and removals registered
+    /// with [Prison::remove_when_free()] only run once the value is already removed.
+    /// The guard is released even if the removal fails
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let mut grd_0 = prison.guard_mut(key_0)?;
+    /// *grd_0 += 5;
+    /// assert_eq!(grd_0.unguard_and_remove()?, 15);
+    /// assert_eq!(prison.visit_ref(key_0, |_| Ok(())), Err(AccessError::ValueDeleted(0, 0)));
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValuePinned(idx)] if the element was pinned with [Prison::pin()]
+    /// - [AccessError::MaxValueForGenerationReached] if the element's generation cannot be incremented
+    pub fn unguard_and_remove(self) -> Result<T, AccessError> {
+        let mut guard = ManuallyDrop::new(self);
+        let prison = guard.prison;
+        let key = CellKey {
+            idx: guard.idx,
+            gen: IdxD::val(guard.cell.d_gen_or_prev),
+        };
+        // Release the reference without waking anything, so no waker or pending removal runs before the value is taken
+        Refs::remove_mut(&mut guard.cell.refs_or_next);
+        guard.prison_accesses.count -= 1;
+        let removed = prison.remove(key);
+        internal!(prison).access_count.wake_all();
+        prison._sweep_released();
+        return removed;
+    }
+
+    //FN PrisonValueMut::downgrade()
+    /// Convert the [PrisonValueMut] into a [PrisonValueRef] to the same element without releasing it in between
+    ///
+    /// No other operation can reference or mutate the element during the conversion, and the element
+    /// does not need to be looked up again. Afterwards further immutable references to the element may be
+    /// created as usual
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let mut grd_0 = prison.guard_mut(key_0)?;
+    /// *grd_0 += 5;
+    /// let grd_0 = grd_0.downgrade();
+    /// assert_eq!(prison.visit_ref(key_0, |val_0| Ok(*val_0))?, 15);
+    /// assert!(prison.visit_mut(key_0, |val_0| Ok(())).is_err());
+    /// assert_eq!(*grd_0, 15);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn downgrade(self) -> PrisonValueRef<'a, T, S> {
+        let guard = ManuallyDrop::new(self);
+        let (cell, prison_accesses) = unsafe { (ptr_read(&guard.cell), ptr_read(&guard.prison_accesses)) };
+        Refs::downgrade(&mut cell.refs_or_next);
+        return PrisonValueRef {
+            cell,
+            prison_accesses,
+            prison: guard.prison,
+            idx: guard.idx,
+        };
+    }
+
+    //FN PrisonValueMut::into_entry()
+    /// Convert the [PrisonValueMut] into a [PrisonEntryMut] to the same element without releasing it in between
+    pub fn into_entry(self) -> PrisonEntryMut<'a, T, S> {
+        return PrisonEntryMut { guard: self };
+    }
+}
+
+//IMPL Drop for PrisonValueMut
+impl<'a, T, S: PrisonStorage> Drop for PrisonValueMut<'a, T, S> {
+    fn drop(&mut self) {
+        _remove_mut_ref(&mut self.cell.refs_or_next, self.prison_accesses);
+        self.prison._sweep_released();
+    }
+}
+
+//IMPL Deref for PrisonValueMut
+impl<'a, T, S: PrisonStorage> Deref for PrisonValueMut<'a, T, S> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.cell.val.assume_init_ref() }
+    }
+}
+
+//IMPL DerefMut for PrisonValueMut
+impl<'a, T, S: PrisonStorage> DerefMut for PrisonValueMut<'a, T, S> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        unsafe { self.cell.val.assume_init_mut() }
+    }
+}
+
+//IMPL AsRef for PrisonValueMut
+impl<'a, T, S: PrisonStorage> AsRef<T> for PrisonValueMut<'a, T, S> {
+    #[inline(always)]
+    fn as_ref(&self) -> &T {
+        unsafe { self.cell.val.assume_init_ref() }
+    }
+}
+
+//IMPL AsMut for PrisonValueMut
+impl<'a, T, S: PrisonStorage> AsMut<T> for PrisonValueMut<'a, T, S> {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut T {
+        unsafe { self.cell.val.assume_init_mut() }
+    }
+}
+
+//IMPL Borrow for PrisonValueMut
+impl<'a, T, S: PrisonStorage> Borrow<T> for PrisonValueMut<'a, T, S> {
+    #[inline(always)]
+    fn borrow(&self) -> &T {
+        unsafe { self.cell.val.assume_init_ref() }
+    }
+}
+
+//IMPL BorrowMut for PrisonValueMut
+impl<'a, T, S: PrisonStorage> BorrowMut<T> for PrisonValueMut<'a, T, S> {
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut T {
+        unsafe { self.cell.val.assume_init_mut() }
+    }
+}
+
+//IMPL ValueGuardRef for PrisonValueMut
+impl<'a, T, S: PrisonStorage> ValueGuardRef<T> for PrisonValueMut<'a, T, S> {}
+
+//IMPL ValueGuardMut for PrisonValueMut
+impl<'a, T, S: PrisonStorage> ValueGuardMut<T> for PrisonValueMut<'a, T, S> {}
+
+//STRUCT PrisonEntryMut
+/// Struct representing a mutable reference to a value in a [Prison] that also knows its own [CellKey],
+/// so the holder of the guard can inspect the key or remove the value without looking it up again
+///
+/// [PrisonEntryMut<T>] implements the same traits as [PrisonValueMut<T>]. Because its own methods take
+/// priority over methods of `T` reached through [Deref], call those through `(*entry)` when the names overlap
+/// (for example `(*entry).remove(0)` on a `Vec`)
+///
+/// You can obtain a [PrisonEntryMut] by calling [Prison::entry_mut()] or [PrisonValueMut::into_entry()]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonEntryMut}};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let mut entry = prison.entry_mut(key_0)?;
+/// assert_eq!((entry.key(), entry.idx(), entry.gen()), (key_0, 0, 0));
+/// *entry += 5;
+/// assert_eq!(entry.remove()?, 15);
+/// assert_eq!(prison.num_used(), 0);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PrisonEntryMut<'a, T, S: PrisonStorage = VecStorage> {
+    guard: PrisonValueMut<'a, T, S>,
+}
+
+impl<'a, T, S: PrisonStorage> PrisonEntryMut<'a, T, S> {
+    //FN PrisonEntryMut::key()
+    /// Return the [CellKey] of the guarded value
+    pub fn key(&self) -> CellKey {
+        return CellKey {
+            idx: self.guard.idx,
+            gen: self.gen(),
+        };
+    }
+
+    //FN PrisonEntryMut::idx()
+    /// Return the index of the guarded value
+    pub fn idx(&self) -> usize {
+        return self.guard.idx;
+    }
+
+    //FN PrisonEntryMut::gen()
+    /// Return the generation of the guarded value
+    pub fn gen(&self) -> usize {
+        return IdxD::val(self.guard.cell.d_gen_or_prev);
+    }
+
+    //FN PrisonEntryMut::remove()
+    /// Release the guard and remove the value from the [Prison] in one step, returning the owned value
+    ///
+    /// No other code can reference the value in between. The guard is released even if the removal fails
+    /// ## Errors
+    /// - Any error returned by [PrisonValueMut::unguard_and_remove()]
+    pub fn remove(self) -> Result<T, AccessError> {
+        return self.guard.unguard_and_remove();
+    }
+
+    //FN PrisonEntryMut::into_guard()
+    /// Convert the [PrisonEntryMut] back into a plain [PrisonValueMut] without releasing it in between
+    pub fn into_guard(self) -> PrisonValueMut<'a, T, S> {
+        return self.guard;
+    }
+}
+
+//IMPL Deref for PrisonEntryMut
+impl<'a, T, S: PrisonStorage> Deref for PrisonEntryMut<'a, T, S> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.guard
+    }
+}
+
+//IMPL DerefMut for PrisonEntryMut
+impl<'a, T, S: PrisonStorage> DerefMut for PrisonEntryMut<'a, T, S> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.guard
+    }
+}
+
+//IMPL AsRef for PrisonEntryMut
+impl<'a, T, S: PrisonStorage> AsRef<T> for PrisonEntryMut<'a, T, S> {
+    #[inline(always)]
+    fn as_ref(&self) -> &T {
+        &self.guard
+    }
+}
+
+//IMPL AsMut for PrisonEntryMut
+impl<'a, T, S: PrisonStorage> AsMut<T> for PrisonEntryMut<'a, T, S> {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+//IMPL Borrow for PrisonEntryMut
+impl<'a, T, S: PrisonStorage> Borrow<T> for PrisonEntryMut<'a, T, S> {
+    #[inline(always)]
+    fn borrow(&self) -> &T {
+        &self.guard
+    }
+}
+
+//IMPL BorrowMut for PrisonEntryMut
+impl<'a, T, S: PrisonStorage> BorrowMut<T> for PrisonEntryMut<'a, T, S> {
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut T {
+        &mut self.guard
+    }
+}
+
+//IMPL ValueGuardRef for PrisonEntryMut
+impl<'a, T, S: PrisonStorage> ValueGuardRef<T> for PrisonEntryMut<'a, T, S> {}
+
+//IMPL ValueGuardMut for PrisonEntryMut
+impl<'a, T, S: PrisonStorage> ValueGuardMut<T> for PrisonEntryMut<'a, T, S> {}
+
+//STRUCT PrisonValueRef
+/// Struct representing an immutable reference to a value that has been allowed to leave the
+/// [Prison] temporarily, but remains guarded by a wrapper to prevent it from leaking or never unlocking
+///
+/// [PrisonValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], and [Borrow<T>]
+/// to allow transparent access to its underlying value, as well as [ValueGuardRef<T>] so it can be
+/// passed to code that accepts any guard
+///
+/// As long as the [PrisonValueRef] remains in scope, the element where it's value resides in the
+/// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
+/// You can manually drop the [PrisonValueRef] out of scope by passing it as the first parameter
+/// to the function [PrisonValueRef::unguard(p_val_ref)]
+///
+/// You can obtain a [PrisonValueRef] by calling `guard_ref()` or `guard_ref_idx()` on a [Prison]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let mut grd_0 = prison.guard_ref(key_0)?;
+/// assert_eq!(*grd_0, 10);
+/// prison.visit_ref(key_0, |val_0| {
+///     assert_eq!(*val_0, 10);
+///     Ok(())
+/// });
+/// PrisonValueRef::unguard(grd_0);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PrisonValueRef<'a, T, S: PrisonStorage = VecStorage> {
+    cell: &'a mut PrisonCell<T>,
+    prison_accesses: &'a mut AccessCount,
+    prison: &'a Prison<T, S>,
+    idx: usize,
+}
+
+//IMPL UnwindSafe for PrisonValueRef
+impl<'a, T: RefUnwindSafe, S: PrisonStorage> UnwindSafe for PrisonValueRef<'a, T, S> {}
+
+impl<'a, T, S: PrisonStorage> PrisonValueRef<'a, T, S> {
+    //FN PrisonValueRef::unguard()
+    /// Manually end a [PrisonValueRef] value's temporary guarded absence from the [Prison]
+    ///
+    /// This method simply takes ownership of the [PrisonValueRef] and immediately lets it go out of scope,
+    /// causing it's `drop()` method to be called and decreasing its immutable reference count in the [Prison]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// let grd_0 = prison.guard_ref_idx(0)?;
+    /// // index 0 CANNOT be accessed here because it is being guarded outside the prison
+    /// assert!(prison.visit_mut_idx(0, |ref_0| Ok(())).is_err());
+    /// PrisonValueRef::unguard(grd_0);
+    /// // index 0 CAN be accessed here because it was returned to the prison
+    /// assert!(prison.visit_mut_idx(0, |ref_0| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unguard(_prison_val_ref: Self) {}
+
+    //FN PrisonValueRef::try_upgrade()
+    /// Try to convert the [PrisonValueRef] into a [PrisonValueMut] to the same element without releasing it in between
+    ///
+    /// This only succeeds if this guard is the *only* reference to the element, otherwise the
+    /// unchanged [PrisonValueRef] is handed back alongside the reason, so it can still be used or
+    /// upgraded again later. Like [PrisonValueMut::downgrade()], no other operation can
+    /// reference or mutate the element during the conversion
+    /// ## Errors
+    /// - [AccessError::ValueStillImmutablyReferenced(idx)] if any other immutable reference to the element exists
+    /// - [AccessError::PrisonSealed] if the [Prison] is sealed with [Prison::seal_read_only()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// let key_0 = prison.insert(10)?;
+    /// let grd_0 = prison.guard_ref(key_0)?;
+    /// let grd_0_b = prison.guard_ref(key_0)?;
+    /// let grd_0 = match grd_0.try_upgrade() {
+    ///     Ok(_) => unreachable!(),
+    ///     Err((grd_0, err)) => {
+    ///         assert_eq!(err, AccessError::ValueStillImmutablyReferenced(0));
+    ///         grd_0
+    ///     }
+    /// };
+    /// PrisonValueRef::unguard(grd_0_b);
+    /// let mut grd_0 = grd_0.try_upgrade().map_err(|(_, err)| err)?;
+    /// *grd_0 += 5;
+    /// assert!(prison.guard_ref(key_0).is_err());
+    /// drop(grd_0);
+    /// assert_eq!(prison.clone_val(key_0)?, 15);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn try_upgrade(self) -> Result<PrisonValueMut<'a, T, S>, (Self, AccessError)> {
+        if self.prison_accesses.count == SEALED_ACCESSES {
+            return Err((self, AccessError::PrisonSealed));
+        }
+        if self.cell.refs_or_next != 1 {
+            let idx = self.idx;
+            return Err((self, AccessError::ValueStillImmutablyReferenced(idx)));
+        }
+        let guard = ManuallyDrop::new(self);
+        let (cell, prison_accesses) = unsafe { (ptr_read(&guard.cell), ptr_read(&guard.prison_accesses)) };
+        Refs::upgrade(&mut cell.refs_or_next);
+        let prison = guard.prison;
+        #[cfg(feature = "lru")]
+        internal!(prison).touch(guard.idx);
+        #[cfg(feature = "dirty")]
+        internal!(prison).mark_dirty(guard.idx);
+        return Ok(PrisonValueMut {
+            cell,
+            prison_accesses,
+            prison,
+            idx: guard.idx,
+        });
+    }
+}
+
+//IMPL Drop for PrisonValueRef
+impl<'a, T, S: PrisonStorage> Drop for PrisonValueRef<'a, T, S> {
+    fn drop(&mut self) {
+        _remove_imm_ref(&mut self.cell.refs_or_next, self.prison_accesses);
+        self.prison._sweep_released();
+    }
+}
+
+//IMPL Deref for PrisonValueRef
+impl<'a, T, S: PrisonStorage> Deref for PrisonValueRef<'a, T, S> {
+    type Target = T;
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        unsafe { self.cell.val.assume_init_ref() }
+    }
+}
+
+//IMPL AsRef for PrisonValueRef
+impl<'a, T, S: PrisonStorage> AsRef<T> for PrisonValueRef<'a, T, S> {
+    #[inline(always)]
+    fn as_ref(&self) -> &T {
+        unsafe { self.cell.val.assume_init_ref() }
+    }
+}
+
+//IMPL Borrow for PrisonValueRef
+impl<'a, T, S: PrisonStorage> Borrow<T> for PrisonValueRef<'a, T, S> {
+    #[inline(always)]
+    fn borrow(&self) -> &T {
+        unsafe { self.cell.val.assume_init_ref() }
+    }
+}
+
+//IMPL ValueGuardRef for PrisonValueRef
+impl<'a, T, S: PrisonStorage> ValueGuardRef<T> for PrisonValueRef<'a, T, S> {}
+
+//STRUCT GuardMutWhenFree
+/// [Future] returned by [Prison::guard_mut_when_free()] that resolves to a [PrisonValueMut] once the value can be mutably referenced
+///
+/// Holds no references until it resolves, so it can be dropped at any time
+#[must_use = "futures do nothing unless polled or awaited"]
+pub struct GuardMutWhenFree<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    key: CellKey,
+}
+
+//IMPL Future for GuardMutWhenFree
+impl<'a, T, S: PrisonStorage> Future for GuardMutWhenFree<'a, T, S> {
+    type Output = Result<PrisonValueMut<'a, T, S>, AccessError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.prison.guard_mut(self.key) {
+            Err(err) if err.is_contention() => {
+                _register_waker(self.prison, cx.waker());
+                return Poll::Pending;
+            }
+            result => return Poll::Ready(result),
+        }
+    }
+}
+
+//STRUCT GuardRefWhenFree
+/// [Future] returned by [Prison::guard_ref_when_free()] that resolves to a [PrisonValueRef] once the value can be immutably referenced
+///
+/// Holds no references until it resolves, so it can be dropped at any time
+#[must_use = "futures do nothing unless polled or awaited"]
+pub struct GuardRefWhenFree<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    key: CellKey,
+}
+
+//IMPL Future for GuardRefWhenFree
+impl<'a, T, S: PrisonStorage> Future for GuardRefWhenFree<'a, T, S> {
+    type Output = Result<PrisonValueRef<'a, T, S>, AccessError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        match self.prison.guard_ref(self.key) {
+            Err(err) if err.is_contention() => {
+                _register_waker(self.prison, cx.waker());
+                return Poll::Pending;
+            }
+            result => return Poll::Ready(result),
+        }
+    }
+}
+
+//FN _register_waker()
+#[doc(hidden)]
+fn _register_waker<T, S: PrisonStorage>(prison: &Prison<T, S>, waker: &Waker) {
+    let wakers = &mut internal!(prison).access_count.wakers;
+    if !wakers.iter().any(|waiting| waiting.will_wake(waker)) {
+        wakers.push(waker.clone());
+    }
+}
+
+//ENUM IterPolicy
+/// What a [PrisonIterMut] or [PrisonIterRef] does when it reaches a value that cannot be referenced
+#[derive(Debug, Clone, Copy, PartialEq, Eq)] //COV_IGNORE
+pub enum IterPolicy {
+    /// Silently skip the value and continue with the next one
+    Skip,
+    /// Yield the [AccessError] that prevented referencing the value, then continue with the next one
+    Error,
+}
+
+//STRUCT PrisonIterMut
+/// Iterator returned by [Prison::iter_mut()] that yields a [CellKey] and a [PrisonValueMut] for each valid value
+pub struct PrisonIterMut<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    idx: usize,
+    policy: IterPolicy,
+}
+
+//IMPL Iterator for PrisonIterMut
+impl<'a, T, S: PrisonStorage> Iterator for PrisonIterMut<'a, T, S> {
+    type Item = Result<(CellKey, PrisonValueMut<'a, T, S>), AccessError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let prison = self.prison;
+        while let Some(cell) = internal!(prison).vec.get(self.idx) {
+            let key = CellKey {
+                idx: self.idx,
+                gen: IdxD::val(cell.d_gen_or_prev),
+            };
+            self.idx += 1;
+            if !cell.is_cell() {
+                continue;
+            }
+            match self.prison._add_mut_ref(key.idx, key.gen, true) {
+                Ok((cell, prison_accesses)) => {
+                    let grd = PrisonValueMut {
+                        cell,
+                        prison_accesses,
+                        prison: self.prison,
+                        idx: key.idx,
+                    };
+                    return Some(Ok((key, grd)));
+                }
+                Err(_) if self.policy == IterPolicy::Skip => continue,
+                Err(acc_err) => return Some(Err(acc_err)),
+            }
+        }
+        return None;
+    }
+}
+
+//STRUCT PrisonIterRef
+/// Iterator returned by [Prison::iter_ref()] that yields a [CellKey] and a [PrisonValueRef] for each valid value
+pub struct PrisonIterRef<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    idx: usize,
+    policy: IterPolicy,
+}
+
+//IMPL Iterator for PrisonIterRef
+impl<'a, T, S: PrisonStorage> Iterator for PrisonIterRef<'a, T, S> {
+    type Item = Result<(CellKey, PrisonValueRef<'a, T, S>), AccessError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let prison = self.prison;
+        while let Some(cell) = internal!(prison).vec.get(self.idx) {
+            let key = CellKey {
+                idx: self.idx,
+                gen: IdxD::val(cell.d_gen_or_prev),
+            };
+            self.idx += 1;
+            if !cell.is_cell() {
+                continue;
+            }
+            match self.prison._add_imm_ref(key.idx, key.gen, true) {
+                Ok((cell, prison_accesses)) => {
+                    let grd = PrisonValueRef {
+                        cell,
+                        prison_accesses,
+                        prison: self.prison,
+                        idx: key.idx,
+                    };
+                    return Some(Ok((key, grd)));
+                }
+                Err(_) if self.policy == IterPolicy::Skip => continue,
+                Err(acc_err) => return Some(Err(acc_err)),
+            }
+        }
+        return None;
+    }
+}
+
+//STRUCT PrisonMapRefs
+/// Iterator returned by [Prison::map_refs()] that visits one value per call to `next()`
+pub struct PrisonMapRefs<'a, T, I, F, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    keys: I,
+    operation: F,
+}
+
+//IMPL Iterator for PrisonMapRefs
+impl<'a, T, I, R, F, S: PrisonStorage> Iterator for PrisonMapRefs<'a, T, I, F, S>
+where
+    I: Iterator<Item = CellKey>,
+    F: FnMut(&T) -> Result<R, AccessError>,
+{
+    type Item = Result<R, AccessError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.keys.next()?;
+        return Some(self.prison.visit_ref(key, &mut self.operation));
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        return self.keys.size_hint();
+    }
+}
+
+//STRUCT PrisonView
+/// A non-overlapping range of indexes in a [Prison] with unchecked access to its values, created with
+/// [Prison::split_at_view()] or [Prison::partitions()]
+///
+/// The [Prison] stays mutably borrowed while any of its views exist, so views access values directly
+/// instead of counting references. A view is [Send] when `T` is [Send] and [Sync] when `T` is [Sync].
+/// Any [CellKey] whose index lies outside the view returns [AccessError::IndexOutOfRange(idx)]
+pub struct PrisonView<'a, T> {
+    start: usize,
+    cells: &'a mut [PrisonCell<T>],
+}
+
+impl<'a, T> PrisonView<'a, T> {
+    //FN PrisonView::start()
+    /// Return the first index covered by this view
+    pub fn start(&self) -> usize {
+        return self.start;
+    }
+
+    //FN PrisonView::end()
+    /// Return the index just past the last index covered by this view
+    pub fn end(&self) -> usize {
+        return self.start + self.cells.len();
+    }
+
+    //FN PrisonView::len()
+    /// Return the number of indexes (free or not) covered by this view
+    pub fn len(&self) -> usize {
+        return self.cells.len();
+    }
+
+    //FN PrisonView::is_empty()
+    /// Return `true` if this view covers no indexes
+    pub fn is_empty(&self) -> bool {
+        return self.cells.is_empty();
+    }
+
+    //FN PrisonView::contains()
+    /// Return `true` if the [CellKey] references a valid, unhidden value inside this view
+    pub fn contains(&self, key: CellKey) -> bool {
+        return self._cell(key).is_ok();
+    }
+
+    //FN PrisonView::get()
+    /// Return a reference to the value at the [CellKey]
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is outside this view
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or the generation does not match
+    /// - [AccessError::ValueHidden(idx)] if the value is hidden
+    pub fn get(&self, key: CellKey) -> Result<&T, AccessError> {
+        let local = self._cell(key)?;
+        return Ok(unsafe { self.cells[local].val.assume_init_ref() });
+    }
+
+    //FN PrisonView::get_mut()
+    /// Return a mutable reference to the value at the [CellKey]
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is outside this view
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed or the generation does not match
+    /// - [AccessError::ValueHidden(idx)] if the value is hidden
+    pub fn get_mut(&mut self, key: CellKey) -> Result<&mut T, AccessError> {
+        let local = self._cell(key)?;
+        return Ok(unsafe { self.cells[local].val.assume_init_mut() });
+    }
+
+    //FN PrisonView::iter_mut()
+    /// Return an iterator over every valid, unhidden value in this view in index order, yielding the [CellKey]
+    /// of each value along with a mutable reference to it
+    pub fn iter_mut(&mut self) -> PrisonViewIterMut<'_, T> {
+        return PrisonViewIterMut {
+            idx: self.start,
+            cells: &mut *self.cells,
+        };
+    }
+
+    //FN PrisonView::split_at()
+    /// Split this view into two views, one covering its indexes before `idx` and one covering `idx` and every index after it
+    ///
+    /// `idx` is a raw position in the whole [Prison], not an offset into this view, see [Prison::split_at_view()]
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if `idx` is not between [PrisonView::start()] and [PrisonView::end()] (inclusive)
+    pub fn split_at(self, idx: usize) -> Result<(PrisonView<'a, T>, PrisonView<'a, T>), AccessError> {
+        if idx < self.start || idx > self.end() {
+            return Err(AccessError::IndexOutOfRange(idx));
+        }
+        let (left, right) = self.cells.split_at_mut(idx - self.start);
+        return Ok((
+            PrisonView {
+                start: self.start,
+                cells: left,
+            },
+            PrisonView {
+                start: idx,
+                cells: right,
+            },
+        ));
+    }
+
+    //FN PrisonView::_cell()
+    #[doc(hidden)]
+    fn _cell(&self, key: CellKey) -> Result<usize, AccessError> {
+        let local = match key.idx.checked_sub(self.start) {
+            Some(local) if local < self.cells.len() => local,
+            _ => return Err(AccessError::IndexOutOfRange(key.idx)),
+        };
+        let cell = &self.cells[local];
+        if !cell.is_cell_and_gen_match(key.gen) {
+            return Err(AccessError::ValueDeleted(key.idx, key.gen));
+        }
+        if cell.refs_or_next == Refs::HIDDEN {
+            return Err(AccessError::ValueHidden(key.idx));
+        }
+        return Ok(local);
+    }
+}
+
+//STRUCT PrisonViewIterMut
+/// Iterator returned by [PrisonView::iter_mut()] that yields a [CellKey] and a mutable reference for each valid, unhidden value
+pub struct PrisonViewIterMut<'b, T> {
+    idx: usize,
+    cells: &'b mut [PrisonCell<T>],
+}
+
+//IMPL Iterator for PrisonViewIterMut
+impl<'b, T> Iterator for PrisonViewIterMut<'b, T> {
+    type Item = (CellKey, &'b mut T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((cell, rest)) = mem_take(&mut self.cells).split_first_mut() {
+            self.cells = rest;
+            let idx = self.idx;
+            self.idx += 1;
+            if cell.is_cell() && cell.refs_or_next != Refs::HIDDEN {
+                let key = CellKey {
+                    idx,
+                    gen: IdxD::val(cell.d_gen_or_prev),
+                };
+                return Some((key, unsafe { cell.val.assume_init_mut() }));
+            }
+        }
+        return None;
+    }
+}
+
+//STRUCT PrisonSliceMut
+/// Struct representing a slice of mutable references to values that have been allowed to leave the
+/// [Prison] temporarily, but remain guarded by a wrapper to prevent them from leaking or never unlocking
+///
+/// [PrisonSliceMut<T>] implements [Deref<Target = \[&mut T\]>](Deref), [DerefMut<Target = \[&mut T\]>](DerefMut), [AsRef<\[&mut T\]>](AsRef), [AsMut<\[&mut T\]>](AsMut),
+/// [Borrow<\[&mut T\]>](Borrow), and [BorrowMut<\[&mut T\]>](BorrowMut) to allow transparent access to its underlying slice of values
+///
+/// As long as the [PrisonSliceMut] remains in scope, the elements where it's values reside in the
+/// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
+/// You can manually drop the [PrisonSliceMut] out of scope by passing it as the first parameter
+/// to the function [PrisonSliceMut::unguard(p_sli_mut)]
+///
+/// You can obtain a [PrisonSliceMut] by calling `guard_many_mut()` or `guard_many_mut_idx()` on a [Prison]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// let key_2 = prison.insert(30)?;
+/// let mut grd_0_1_2 = prison.guard_many_mut(&[key_0, key_1, key_2])?;
+/// assert_eq!(*grd_0_1_2[1], 20);
+/// *grd_0_1_2[1] = 42;
+/// PrisonSliceMut::unguard(grd_0_1_2);
+/// prison.visit_ref(key_1, |val_1| {
+///     assert_eq!(*val_1, 42);
+///     Ok(())
+/// });
+/// # Ok(())
+/// # }
+/// ```
+pub struct PrisonSliceMut<'a, T, S: PrisonStorage = VecStorage> {
+    prison_accesses: &'a mut AccessCount,
+    prison: &'a Prison<T, S>,
+    refs: InlineVec<&'a mut usize>,
+    vals: InlineVec<&'a mut T>,
+}
+
+impl<'a, T, S: PrisonStorage> PrisonSliceMut<'a, T, S> {
+    //FN PrisonSliceMut::unguard()
+    /// Manually end a [PrisonSliceMut] value's temporary guarded absence from the [Prison]
+    ///
+    /// This method simply takes ownership of the [PrisonSliceMut] and immediately lets it go out of scope,
+    /// causing it's `drop()` method to be called and decreasing its immutable reference count in the [Prison]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// let grd_all = prison.guard_many_mut_idx(&[0, 1])?;
+    /// assert!(prison.visit_many_mut_idx(&[0, 1], |ref_all| Ok(())).is_err());
+    /// PrisonSliceMut::unguard(grd_all);
+    /// assert!(prison.visit_many_mut_idx(&[0, 1], |ref_all| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unguard(_prison_sli_mut: Self) {}
+}
+
+//IMPL Drop for PrisonSliceMut
+impl<'a, T, S: PrisonStorage> Drop for PrisonSliceMut<'a, T, S> {
+    fn drop(&mut self) {
+        _remove_many_mut_refs(&mut self.refs, self.prison_accesses);
+        self.prison._sweep_released();
+        self.prison._recycle_buf(&mut self.refs);
+        self.prison._recycle_buf(&mut self.vals);
+    }
+}
+
+//IMPL Deref for PrisonSliceMut
+impl<'a, T, S: PrisonStorage> Deref for PrisonSliceMut<'a, T, S> {
+    type Target = [&'a mut T];
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.vals.as_slice()
+    }
+}
+
+//IMPL DerefMut for PrisonSliceMut
+impl<'a, T, S: PrisonStorage> DerefMut for PrisonSliceMut<'a, T, S> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.vals.as_mut_slice()
+    }
+}
+
+//IMPL AsRef for PrisonSliceMut
+impl<'a, T, S: PrisonStorage> AsRef<[&'a mut T]> for PrisonSliceMut<'a, T, S> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[&'a mut T] {
+        self.vals.as_slice()
+    }
+}
+
+//IMPL AsMut for PrisonSliceMut
+impl<'a, T, S: PrisonStorage> AsMut<[&'a mut T]> for PrisonSliceMut<'a, T, S> {
+    #[inline(always)]
+    fn as_mut(&mut self) -> &mut [&'a mut T] {
+        self.vals.as_mut_slice()
+    }
+}
+
+//IMPL Borrow for PrisonSliceMut
+impl<'a, T, S: PrisonStorage> Borrow<[&'a mut T]> for PrisonSliceMut<'a, T, S> {
+    #[inline(always)]
+    fn borrow(&self) -> &[&'a mut T] {
+        self.vals.as_slice()
+    }
+}
+
+//IMPL BorrowMut for PrisonSliceMut
+impl<'a, T, S: PrisonStorage> BorrowMut<[&'a mut T]> for PrisonSliceMut<'a, T, S> {
+    #[inline(always)]
+    fn borrow_mut(&mut self) -> &mut [&'a mut T] {
+        self.vals.as_mut_slice()
+    }
+}
+
+//STRUCT PrisonArrayMut
+/// Struct representing a fixed-size array of mutable references to values that have been allowed to leave the
+/// [Prison] temporarily, but remain guarded by a wrapper to prevent them from leaking or never unlocking
+///
+/// [PrisonArrayMut<T, N>] implements [Deref<Target = \[&mut T; N\]>](Deref) and [DerefMut<Target = \[&mut T; N\]>](DerefMut),
+/// so the references can be destructured with `let [a, b] = &mut *grd;`. Otherwise it behaves exactly like [PrisonSliceMut]
+///
+/// You can obtain a [PrisonArrayMut] by calling `guard_array_mut()` on a [Prison]
+pub struct PrisonArrayMut<'a, T, const N: usize, S: PrisonStorage = VecStorage> {
+    prison_accesses: &'a mut AccessCount,
+    prison: &'a Prison<T, S>,
+    refs: InlineVec<&'a mut usize>,
+    vals: [&'a mut T; N],
+}
+
+impl<'a, T, const N: usize, S: PrisonStorage> PrisonArrayMut<'a, T, N, S> {
+    //FN PrisonArrayMut::unguard()
+    /// Manually end a [PrisonArrayMut] value's temporary guarded absence from the [Prison]
+    ///
+    /// See [PrisonSliceMut::unguard()]
+    pub fn unguard(_prison_arr_mut: Self) {}
+}
+
+//IMPL Drop for PrisonArrayMut
+impl<'a, T, const N: usize, S: PrisonStorage> Drop for PrisonArrayMut<'a, T, N, S> {
+    fn drop(&mut self) {
+        _remove_many_mut_refs(&mut self.refs, self.prison_accesses);
+        self.prison._sweep_released();
+        self.prison._recycle_buf(&mut self.refs);
+    }
+}
+
+//IMPL Deref for PrisonArrayMut
+impl<'a, T, const N: usize, S: PrisonStorage> Deref for PrisonArrayMut<'a, T, N, S> {
+    type Target = [&'a mut T; N];
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        &self.vals
+    }
+}
+
+//IMPL DerefMut for PrisonArrayMut
+impl<'a, T, const N: usize, S: PrisonStorage> DerefMut for PrisonArrayMut<'a, T, N, S> {
+    #[inline(always)]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.vals
+    }
+}
+
+//STRUCT PrisonSliceRef
+/// Struct representing a slice of immutable references to values that have been allowed to leave the
+/// [Prison] temporarily, but remain guarded by a wrapper to prevent them from leaking or never unlocking
+///
+/// [PrisonSliceRef<T>] implements [Deref<Target = \[&T\]>](Deref), [AsRef<\[&T\]>](AsRef),
+/// and [Borrow<\[&T\]>](Borrow) to allow transparent access to its underlying slice of values
+///
+/// As long as the [PrisonSliceRef] remains in scope, the elements where it's values reside in the
+/// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
+/// You can manually drop the [PrisonSliceRef] out of scope by passing it as the first parameter
+/// to the function [PrisonSliceRef::unguard(p_sli_ref)]
+///
+/// You can obtain a [PrisonSliceRef] by calling `guard_many_ref()` or `guard_many_ref_idx()` on a [Prison]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// let key_2 = prison.insert(30)?;
+/// let mut grd_0_1_2 = prison.guard_many_ref(&[key_0, key_1, key_2])?;
+/// assert_eq!(*grd_0_1_2[1], 20);
+/// prison.visit_ref(key_1, |val_1| {
+///     assert_eq!(*val_1, 20);
+///     Ok(())
+/// });
+/// PrisonSliceRef::unguard(grd_0_1_2);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PrisonSliceRef<'a, T, S: PrisonStorage = VecStorage> {
+    prison_accesses: &'a mut AccessCount,
+    prison: &'a Prison<T, S>,
+    refs: InlineVec<&'a mut usize>,
+    vals: InlineVec<&'a T>,
+}
+
+//IMPL UnwindSafe for PrisonSliceRef
+impl<'a, T: RefUnwindSafe, S: PrisonStorage> UnwindSafe for PrisonSliceRef<'a, T, S> {}
+
+impl<'a, T, S: PrisonStorage> PrisonSliceRef<'a, T, S> {
+    //FN PrisonSliceRef::unguard()
+    /// Manually end a [PrisonSliceRef] value's temporary guarded absence from the [Prison]
+    ///
+    /// This method simply takes ownership of the [PrisonSliceRef] and immediately lets it go out of scope,
+    /// causing it's `drop()` method to be called and decreasing its immutable reference count in the [Prison]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// prison.insert(20)?;
+    /// let grd_all = prison.guard_many_ref_idx(&[0, 1])?;
+    /// assert!(prison.visit_many_mut_idx(&[0, 1], |ref_all| Ok(())).is_err());
+    /// PrisonSliceRef::unguard(grd_all);
+    /// assert!(prison.visit_many_mut_idx(&[0, 1], |ref_all| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unguard(_prison_sli_ref: Self) {}
+}
+
+//IMPL Drop for PrisonSliceRef
+impl<'a, T, S: PrisonStorage> Drop for PrisonSliceRef<'a, T, S> {
+    fn drop(&mut self) {
+        _remove_many_imm_refs(&mut self.refs, self.prison_accesses);
+        self.prison._sweep_released();
+        self.prison._recycle_buf(&mut self.refs);
+        self.prison._recycle_buf(&mut self.vals);
+    }
+}
+
+//IMPL Deref for PrisonSliceRef
+impl<'a, T, S: PrisonStorage> Deref for PrisonSliceRef<'a, T, S> {
+    type Target = [&'a T];
+
+    #[inline(always)]
+    fn deref(&self) -> &Self::Target {
+        self.vals.as_slice()
+    }
+}
+
+//IMPL AsRef for PrisonSliceRef
+impl<'a, T, S: PrisonStorage> AsRef<[&'a T]> for PrisonSliceRef<'a, T, S> {
+    #[inline(always)]
+    fn as_ref(&self) -> &[&'a T] {
+        self.vals.as_slice()
+    }
+}
+
+//IMPL Borrow for PrisonSliceRef
+impl<'a, T, S: PrisonStorage> Borrow<[&'a T]> for PrisonSliceRef<'a, T, S> {
+    #[inline(always)]
+    fn borrow(&self) -> &[&'a T] {
+        self.vals.as_slice()
+    }
+}
+
+//STRUCT PinSet
+/// Struct representing a set of immutable references to values that have been pinned in place
+/// for repeated lookups, remaining guarded by a wrapper to prevent them from leaking or never unlocking
+///
+/// As long as the [PinSet] remains in scope, the elements where it's values reside in the
+/// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
+/// You can manually drop the [PinSet] out of scope by passing it as the first parameter
+/// to the function [PinSet::unguard(pin_set)]
+///
+/// You can obtain a [PinSet] by calling `pin_refs()` on a [Prison]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PinSet}};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// let pinned = prison.pin_refs(&[key_0, key_1])?;
+/// for _ in 0..3 {
+///     assert_eq!(pinned.get(key_0), Some(&10));
+///     assert_eq!(pinned.get_idx(1), Some(&20));
+/// }
+/// PinSet::unguard(pinned);
+/// # Ok(())
+/// # }
+/// ```
+pub struct PinSet<'a, T, S: PrisonStorage = VecStorage> {
+    prison_accesses: &'a mut AccessCount,
+    prison: &'a Prison<T, S>,
+    refs: InlineVec<&'a mut usize>,
+    pinned: Vec<(CellKey, &'a T)>,
+}
+
+impl<'a, T, S: PrisonStorage> PinSet<'a, T, S> {
+    //FN PinSet::get()
+    /// Return an immutable reference to the pinned value indexed by the [CellKey],
+    /// or [None] if the [CellKey] was not pinned by this [PinSet]
+    pub fn get(&self, key: CellKey) -> Option<&T> {
+        match self.pinned.binary_search_by_key(&key.idx, |(pinned_key, _)| pinned_key.idx) {
+            Ok(pos) if self.pinned[pos].0.gen == key.gen => Some(self.pinned[pos].1),
+            _ => None,
+        }
+    }
+
+    //FN PinSet::get_idx()
+    /// Return an immutable reference to the pinned value at the index,
+    /// or [None] if the index was not pinned by this [PinSet]
+    pub fn get_idx(&self, idx: usize) -> Option<&T> {
+        match self.pinned.binary_search_by_key(&idx, |(pinned_key, _)| pinned_key.idx) {
+            Ok(pos) => Some(self.pinned[pos].1),
+            Err(_) => None,
+        }
+    }
+
+    //FN PinSet::contains()
+    /// Return `true` if the [CellKey] was pinned by this [PinSet]
+    pub fn contains(&self, key: CellKey) -> bool {
+        return self.get(key).is_some();
+    }
+
+    //FN PinSet::len()
+    /// Return the number of references held by this [PinSet]
+    pub fn len(&self) -> usize {
+        return self.pinned.len();
+    }
+
+    //FN PinSet::is_empty()
+    /// Return `true` if this [PinSet] holds no references
+    pub fn is_empty(&self) -> bool {
+        return self.pinned.is_empty();
+    }
+
+    //FN PinSet::unguard()
+    /// Manually end a [PinSet]'s pinning of its values in the [Prison]
+    ///
+    /// This method simply takes ownership of the [PinSet] and immediately lets it go out of scope,
+    /// causing it's `drop()` method to be called and decreasing its immutable reference counts in the [Prison]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PinSet}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(10)?;
+    /// let key_1 = prison.insert(20)?;
+    /// let pinned = prison.pin_refs(&[key_1])?;
+    /// assert!(prison.visit_mut(key_1, |val_1| Ok(())).is_err());
+    /// PinSet::unguard(pinned);
+    /// assert!(prison.visit_mut(key_1, |val_1| Ok(())).is_ok());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unguard(_pin_set: Self) {}
+}
+
+//IMPL Drop for PinSet
+impl<'a, T, S: PrisonStorage> Drop for PinSet<'a, T, S> {
+    fn drop(&mut self) {
+        _remove_many_imm_refs(&mut self.refs, self.prison_accesses);
+        self.prison._sweep_released();
+    }
+}
+
+//STRUCT SnapshotRef
+/// Struct representing an immutable hold on every value that was valid in a [Prison] when it was created,
+/// remaining guarded by a wrapper to prevent them from leaking or never unlocking
+///
+/// [SnapshotRef<T>] implements [Index<CellKey>](Index) and [Index<usize>](Index), returning `&T`
+/// and panicking only if the value is not part of the snapshot. As long as the [SnapshotRef] remains in scope
+/// every value it holds will remain marked as immutably referenced and unable to be mutably referenced.
+/// You can manually drop the [SnapshotRef] out of scope by passing it as the first parameter
+/// to the function [SnapshotRef::unguard(snapshot)]
+///
+/// You can obtain a [SnapshotRef] by calling `snapshot_ref()` on a [Prison]
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, SnapshotRef}};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// let key_0 = prison.insert(10)?;
+/// let key_1 = prison.insert(20)?;
+/// prison.remove(key_0)?;
+/// let snapshot = prison.snapshot_ref()?;
+/// assert_eq!(snapshot.len(), 1);
+/// assert_eq!(snapshot[key_1], 20);
+/// assert_eq!(snapshot.get(key_0), None);
+/// SnapshotRef::unguard(snapshot);
+/// # Ok(())
+/// # }
+/// ```
+pub struct SnapshotRef<'a, T, S: PrisonStorage = VecStorage> {
+    prison_accesses: &'a mut AccessCount,
+    prison: &'a Prison<T, S>,
+    refs: InlineVec<&'a mut usize>,
+    entries: Vec<Option<(usize, &'a T)>>,
+}
+
+impl<'a, T, S: PrisonStorage> SnapshotRef<'a, T, S> {
+    //FN SnapshotRef::get()
+    /// Return an immutable reference to the value indexed by the [CellKey],
+    /// or [None] if the value is not part of this [SnapshotRef]
+    pub fn get(&self, key: CellKey) -> Option<&T> {
+        match self.entries.get(key.idx) {
+            Some(Some((gen, val))) if *gen == key.gen => Some(*val),
+            _ => None,
+        }
+    }
+
+    //FN SnapshotRef::get_idx()
+    /// Return an immutable reference to the value at the index,
+    /// or [None] if the index is not part of this [SnapshotRef]
+    pub fn get_idx(&self, idx: usize) -> Option<&T> {
+        match self.entries.get(idx) {
+            Some(Some((_, val))) => Some(*val),
+            _ => None,
+        }
+    }
+
+    //FN SnapshotRef::raw_cells()
+    /// Return an iterator over the [CellKey] and the raw bytes of every value held by this [SnapshotRef], in index order
+    ///
+    /// This lets tooling that cannot be generic over `T` (such as a hot-reload plugin) inspect every value
+    /// as plain bytes. In a [Prison] created with `with_transform()` the bytes are those of the stored
+    /// (encoded) form of each value. Use [Prison::overwrite_raw()] to patch a value from bytes
     ///
-    /// This method is provided as a way for libraries depending on this code to perform niche
-    /// optimized reads of contained values without the overhead of the normal safety checks,
-    /// or when a mutable reference is active but you can statically confirm that the value will
-    /// not be mutated from the begining of the returned `&T`s lifetime to the end of it's lifetime.
+    /// Only available with the `raw` feature
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u16> = Prison::new();
+    /// let key_0 = prison.insert(0x0102)?;
+    /// let key_1 = prison.insert(0x0304)?;
+    /// let snapshot = prison.snapshot_ref()?;
+    /// let raw: Vec<(CellKey, Vec<u8>)> = snapshot.raw_cells().map(|(key, bytes)| (key, bytes.to_vec())).collect();
+    /// assert_eq!(raw, vec![(key_0, 0x0102u16.to_ne_bytes().to_vec()), (key_1, 0x0304u16.to_ne_bytes().to_vec())]);
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[cfg(feature = "raw")]
+    pub fn raw_cells(&self) -> impl Iterator<Item = (CellKey, &[u8])> + '_
+    where
+        T: PlainData,
+    {
+        return self.entries.iter().enumerate().filter_map(|(idx, entry)| {
+            let (gen, val) = (*entry)?;
+            let bytes = unsafe { slice_from_raw_parts(val as *const T as *const u8, size_of::<T>()) };
+            return Some((CellKey { idx, gen }, bytes));
+        });
+    }
+
+    //FN SnapshotRef::len()
+    /// Return the number of values held by this [SnapshotRef]
+    pub fn len(&self) -> usize {
+        return self.refs.len();
+    }
+
+    //FN SnapshotRef::is_empty()
+    /// Return `true` if this [SnapshotRef] holds no values
+    pub fn is_empty(&self) -> bool {
+        return self.refs.is_empty();
+    }
+
+    //FN SnapshotRef::unguard()
+    /// Manually end a [SnapshotRef] value's temporary guarded absence from the [Prison]
     ///
-    /// The returned references are intended to be short-lived and safely contained in a scope where no
-    /// mutation of the [Prison] as a whole or ANY of its values takes place
-    /// # Safety
-    /// When you call this method and as long as the `&T` it returns remains in-scope/alive,
-    /// you MUST ensure the following:
-    /// - The value MUST NOT be mutated by ANY source, including active safe reference-counted mutable references
-    /// - NO operation can be performed that could *potentially* cause the underlying memory address of the [Prison]'s data to relocate
-    pub unsafe fn peek_ref_idx<'a>(&'a self, idx: usize) -> Result<&'a T, AccessError> {
-        match &internal!(self).vec[idx] {
-            cell if cell.is_cell() => Ok(unsafe { &cell.val.assume_init_ref() }),
-            _ => Err(AccessError::ValueDeleted(idx, 0)),
-        }
+    /// This method simply takes ownership of the [SnapshotRef] and immediately lets it go out of scope,
+    /// causing it's `drop()` method to be called and decreasing every held immutable reference count in the [Prison]
+    pub fn unguard(_snapshot: Self) {}
+}
+
+//IMPL Drop for SnapshotRef
+impl<'a, T, S: PrisonStorage> Drop for SnapshotRef<'a, T, S> {
+    fn drop(&mut self) {
+        _remove_many_imm_refs(&mut self.refs, self.prison_accesses);
+        self.prison._sweep_released();
     }
+}
 
-    //------ Prison Private ------
-    //FN Prison::_add_mut_ref()
-    #[doc(hidden)]
-    fn _add_mut_ref(
-        &self,
-        idx: usize,
-        gen: usize,
-        use_gen: bool,
-    ) -> Result<(&mut PrisonCell<T>, &mut usize), AccessError> {
-        let internal = internal!(self);
-        if idx >= internal.vec.len() {
-            return Err(AccessError::IndexOutOfRange(idx));
-        }
-        match &mut internal.vec[idx] {
-            cell if cell.is_cell_and_gen_match_opt(gen, use_gen) => {
-                if cell.refs_or_next == Refs::MUT {
-                    return Err(AccessError::ValueAlreadyMutablyReferenced(idx));
-                }
-                if cell.refs_or_next > 0 {
-                    return Err(AccessError::ValueStillImmutablyReferenced(idx));
-                }
-                cell.refs_or_next = Refs::MUT;
-                internal.access_count += 1;
-                return Ok((cell, &mut internal.access_count));
-            }
-            _ => return Err(AccessError::ValueDeleted(idx, gen)),
+//IMPL Index<CellKey> for SnapshotRef
+impl<'a, T, S: PrisonStorage> Index<CellKey> for SnapshotRef<'a, T, S> {
+    type Output = T;
+
+    #[inline(always)]
+    fn index(&self, key: CellKey) -> &Self::Output {
+        match self.get(key) {
+            Some(val) => val,
+            None => panic!("{:?} is not part of this SnapshotRef", key),
         }
     }
+}
 
-    //FN Prison::_add_imm_ref()
-    #[doc(hidden)]
-    fn _add_imm_ref(
-        &self,
-        idx: usize,
-        gen: usize,
-        use_gen: bool,
-    ) -> Result<(&mut PrisonCell<T>, &mut usize), AccessError> {
-        let internal = internal!(self);
-        if idx >= internal.vec.len() {
-            return Err(AccessError::IndexOutOfRange(idx));
+//IMPL Index<usize> for SnapshotRef
+impl<'a, T, S: PrisonStorage> Index<usize> for SnapshotRef<'a, T, S> {
+    type Output = T;
+
+    #[inline(always)]
+    fn index(&self, idx: usize) -> &Self::Output {
+        match self.get_idx(idx) {
+            Some(val) => val,
+            None => panic!("index {} is not part of this SnapshotRef", idx),
         }
-        match &mut internal.vec[idx] {
-            cell if cell.is_cell_and_gen_match_opt(gen, use_gen) => {
-                if cell.refs_or_next == Refs::MUT {
-                    return Err(AccessError::ValueAlreadyMutablyReferenced(idx));
-                }
-                if cell.refs_or_next == Refs::MAX_IMMUT {
-                    return Err(AccessError::MaximumImmutableReferencesReached(idx));
-                }
-                if cell.refs_or_next == 0 {
-                    internal.access_count += 1;
-                }
-                cell.refs_or_next += 1;
-                return Ok((cell, &mut internal.access_count));
-            }
-            _ => return Err(AccessError::ValueDeleted(idx, gen)),
+    }
+}
+
+//STRUCT GuardCtx
+/// Context object passed to the closure given to [Prison::with_guards()], allowing references
+/// to values in the [Prison] to be requested and released dynamically
+///
+/// References are requested through `&self`, so any number of them may be held at once as long
+/// as they obey the normal referencing rules. Releasing a reference requires `&mut self`, which
+/// guarantees at compile time that no reference borrowed from the context is still in use.
+///
+/// Every reference still held when the [GuardCtx] goes out of scope is released automatically,
+/// including when the closure returns an error or panics
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+/// # fn main() -> Result<(), AccessError> {
+/// let prison: Prison<u32> = Prison::new();
+/// prison.insert(10)?;
+/// prison.insert(20)?;
+/// prison.with_guards(|ctx| {
+///     let val_0 = ctx.mut_ref_idx(0)?;
+///     let val_1 = ctx.mut_ref_idx(1)?;
+///     std::mem::swap(val_0, val_1);
+///     Ok(())
+/// })?;
+/// assert_eq!(prison.clone_val_idx(0)?, 20);
+/// # Ok(())
+/// # }
+/// ```
+pub struct GuardCtx<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    held: UnsafeCell<Vec<(usize, bool)>>,
+}
+
+impl<'a, T, S: PrisonStorage> GuardCtx<'a, T, S> {
+    //FN GuardCtx::mut_ref()
+    /// Obtain a mutable reference to the value indexed by the [CellKey], held until it is
+    /// released or the [GuardCtx] goes out of scope
+    ///
+    /// Subject to the same restrictions and errors as [Prison::guard_mut()]
+    #[allow(clippy::mut_from_ref)]
+    pub fn mut_ref(&self, key: CellKey) -> Result<&mut T, AccessError> {
+        let (cell, _) = self.prison._add_mut_ref(key.idx, key.gen, true)?;
+        self._held().push((key.idx, true));
+        return Ok(unsafe { cell.val.assume_init_mut() });
+    }
+
+    //FN GuardCtx::imm_ref()
+    /// Obtain an immutable reference to the value indexed by the [CellKey], held until it is
+    /// released or the [GuardCtx] goes out of scope
+    ///
+    /// Subject to the same restrictions and errors as [Prison::guard_ref()]
+    pub fn imm_ref(&self, key: CellKey) -> Result<&T, AccessError> {
+        let (cell, _) = self.prison._add_imm_ref(key.idx, key.gen, true)?;
+        self._held().push((key.idx, false));
+        return Ok(unsafe { cell.val.assume_init_ref() });
+    }
+
+    //FN GuardCtx::mut_ref_idx()
+    /// Obtain a mutable reference to the value at the index, held until it is
+    /// released or the [GuardCtx] goes out of scope
+    ///
+    /// Subject to the same restrictions and errors as [Prison::guard_mut_idx()]
+    #[allow(clippy::mut_from_ref)]
+    pub fn mut_ref_idx(&self, idx: usize) -> Result<&mut T, AccessError> {
+        let idx = self.prison._idx(idx)?;
+        let (cell, _) = self.prison._add_mut_ref(idx, 0, false)?;
+        self._held().push((idx, true));
+        return Ok(unsafe { cell.val.assume_init_mut() });
+    }
+
+    //FN GuardCtx::imm_ref_idx()
+    /// Obtain an immutable reference to the value at the index, held until it is
+    /// released or the [GuardCtx] goes out of scope
+    ///
+    /// Subject to the same restrictions and errors as [Prison::guard_ref_idx()]
+    pub fn imm_ref_idx(&self, idx: usize) -> Result<&T, AccessError> {
+        let idx = self.prison._idx(idx)?;
+        let (cell, _) = self.prison._add_imm_ref(idx, 0, false)?;
+        self._held().push((idx, false));
+        return Ok(unsafe { cell.val.assume_init_ref() });
+    }
+
+    //FN GuardCtx::checkpoint()
+    /// Return an error if an interrupt was requested on the [Prison], see [Prison::checkpoint()]
+    ///
+    /// Returning this error from the closure passed to [Prison::with_guards()] releases every held reference
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::Prison};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let prison: Prison<u32> = Prison::new();
+    /// prison.insert(0)?;
+    /// let result = prison.with_guards(|ctx| {
+    ///     let val_0 = ctx.mut_ref_idx(0)?;
+    ///     loop {
+    ///         ctx.checkpoint()?;
+    ///         *val_0 += 1;
+    ///         if *val_0 == 100 {
+    ///             prison.request_interrupt();
+    ///         }
+    ///     }
+    /// });
+    /// assert_eq!(result, Err(AccessError::InterruptRequested));
+    /// assert_eq!(prison.clone_val_idx(0)?, 100);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::InterruptRequested] if [Prison::request_interrupt()] was called and not yet cleared
+    #[inline(always)]
+    pub fn checkpoint(&self) -> Result<(), AccessError> {
+        return self.prison.checkpoint();
+    }
+
+    //FN GuardCtx::release()
+    /// Release the most recently obtained reference to the value indexed by the [CellKey]
+    ///
+    /// Returns `true` if a reference was held and released, `false` if this context
+    /// did not hold a reference to the value
+    pub fn release(&mut self, key: CellKey) -> bool {
+        let prison = self.prison;
+        let internal = internal!(prison);
+        match internal.vec.get(key.idx) {
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) => self.release_idx(key.idx),
+            _ => false,
         }
     }
 
-    //FN Prison::_add_many_mut_refs()
-    #[doc(hidden)]
-    fn _add_many_mut_refs(
-        &self,
-        cell_keys: &[CellKey],
-    ) -> Result<(Vec<&mut T>, Vec<&mut usize>, &mut usize), AccessError> {
-        let internal = internal!(self);
-        let mut vals = Vec::new();
-        let mut refs = Vec::new();
-        let mut ref_all_result = Ok(());
-        for key in cell_keys {
-            let ref_result = self._add_mut_ref(key.idx, key.gen, true);
-            match ref_result {
-                Ok((cell, _)) => {
-                    vals.push(unsafe { cell.val.assume_init_mut() });
-                    refs.push(&mut cell.refs_or_next);
-                }
-                Err(e) => {
-                    ref_all_result = Err(e);
-                    break;
-                }
+    //FN GuardCtx::release_idx()
+    /// Release the most recently obtained reference to the value at the index
+    ///
+    /// Returns `true` if a reference was held and released, `false` if this context
+    /// did not hold a reference to the value
+    pub fn release_idx(&mut self, idx: usize) -> bool {
+        let idx = match self.prison._idx(idx) {
+            Ok(idx) => idx,
+            Err(_) => return false,
+        };
+        let held = self.held.get_mut();
+        match held.iter().rposition(|(held_idx, _)| *held_idx == idx) {
+            Some(pos) => {
+                let (idx, mutable) = held.remove(pos);
+                self._release_unchecked(idx, mutable);
+                true
             }
+            None => false,
         }
-        match ref_all_result {
-            Ok(_) => {
-                return Ok((vals, refs, &mut internal.access_count));
-            }
-            Err(acc_err) => {
-                _remove_many_mut_refs(&mut refs, &mut internal.access_count);
-                return Err(acc_err);
-            }
+    }
+
+    //FN GuardCtx::release_all()
+    /// Release every reference currently held by this context
+    pub fn release_all(&mut self) {
+        while let Some((idx, mutable)) = self.held.get_mut().pop() {
+            self._release_unchecked(idx, mutable);
         }
     }
 
-    //FN Prison::_add_many_mut_refs_idx()
+    //FN GuardCtx::num_held()
+    /// Return the number of references currently held by this context
+    pub fn num_held(&self) -> usize {
+        return self._held().len();
+    }
+
+    //FN GuardCtx::_held()
     #[doc(hidden)]
-    fn _add_many_mut_refs_idx(
-        &self,
-        idxs: &[usize],
-    ) -> Result<(Vec<&mut T>, Vec<&mut usize>, &mut usize), AccessError> {
-        let internal = internal!(self);
-        let mut vals = Vec::new();
-        let mut refs = Vec::new();
-        let mut ref_all_result = Ok(());
-        for idx in idxs {
-            let ref_result = self._add_mut_ref(*idx, 0, false);
-            match ref_result {
-                Ok((cell, _)) => {
-                    vals.push(unsafe { cell.val.assume_init_mut() });
-                    refs.push(&mut cell.refs_or_next);
-                }
-                Err(e) => {
-                    ref_all_result = Err(e);
-                    break;
-                }
-            }
-        }
-        match ref_all_result {
-            Ok(_) => {
-                return Ok((vals, refs, &mut internal.access_count));
-            }
-            Err(acc_err) => {
-                _remove_many_mut_refs(&mut refs, &mut internal.access_count);
-                return Err(acc_err);
-            }
-        }
+    #[allow(clippy::mut_from_ref)]
+    fn _held(&self) -> &mut Vec<(usize, bool)> {
+        unsafe { &mut *self.held.get() }
     }
 
-    //FN Prison::_add_many_imm_refs()
+    //FN GuardCtx::_release_unchecked()
     #[doc(hidden)]
-    fn _add_many_imm_refs(
-        &self,
-        cell_keys: &[CellKey],
-    ) -> Result<(Vec<&T>, Vec<&mut usize>, &mut usize), AccessError> {
-        let internal = internal!(self);
-        let mut vals = Vec::new();
-        let mut refs = Vec::new();
-        let mut ref_all_result = Ok(());
-        for key in cell_keys {
-            let ref_result = self._add_imm_ref(key.idx, key.gen, true);
-            match ref_result {
-                Ok((cell, _)) => {
-                    vals.push(unsafe { cell.val.assume_init_ref() });
-                    refs.push(&mut cell.refs_or_next);
-                }
-                Err(e) => {
-                    ref_all_result = Err(e);
-                    break;
-                }
-            }
-        }
-        match ref_all_result {
-            Ok(_) => {
-                return Ok((vals, refs, &mut internal.access_count));
-            }
-            Err(acc_err) => {
-                _remove_many_imm_refs(&mut refs, &mut internal.access_count);
-                return Err(acc_err);
-            }
+    fn _release_unchecked(&mut self, idx: usize, mutable: bool) {
+        let prison = self.prison;
+        let internal = internal!(prison);
+        let refs = &mut internal.vec[idx].refs_or_next;
+        if mutable {
+            _remove_mut_ref(refs, &mut internal.access_count);
+        } else {
+            _remove_imm_ref(refs, &mut internal.access_count);
         }
+        prison._sweep_released();
+    }
+}
+
+//IMPL Drop for GuardCtx
+impl<'a, T, S: PrisonStorage> Drop for GuardCtx<'a, T, S> {
+    fn drop(&mut self) {
+        self.release_all();
+    }
+}
+
+//------ Prison Transactions ------
+//STRUCT TwoPhase
+/// A coordinator that stages inserts, removals, and value replacements against any number of
+/// [Prison]s (of any value types) and applies them all at once, or not at all
+///
+/// [TwoPhase::commit()] first validates every staged operation against the current state of its [Prison]
+/// (capacity, outstanding references, pinned values, generations, and keys removed or replaced twice), and only if every
+/// operation can succeed does it apply them, in the order they were staged. If any check fails the error is
+/// returned, nothing is applied, and every staged value is dropped. This prevents half-applied updates when, for
+/// example, spawning or despawning an entity touches several component [Prison]s
+///
+/// Values removed by the commit are dropped
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, TwoPhase}};
+/// # fn main() -> Result<(), AccessError> {
+/// let positions: Prison<(f32, f32)> = Prison::new();
+/// let names: Prison<&str> = Prison::new();
+/// let healths: Prison<u32> = Prison::new();
+/// let key_hp = healths.insert(10)?;
+/// let mut spawn = TwoPhase::new();
+/// spawn.insert(&positions, (1.0, 2.0));
+/// spawn.insert(&names, "goblin");
+/// spawn.set(&healths, key_hp, 100);
+/// let keys = spawn.commit()?;
+/// assert_eq!(names.clone_val(keys[1])?, "goblin");
+/// assert_eq!(healths.clone_val(key_hp)?, 100);
+/// let mut despawn = TwoPhase::new();
+/// despawn.remove(&positions, keys[0]);
+/// despawn.remove(&names, keys[1]);
+/// despawn.remove(&healths, key_hp);
+/// let grd_hp = healths.guard_ref(key_hp)?;
+/// assert!(despawn.commit().is_err());
+/// drop(grd_hp);
+/// assert_eq!(names.clone_val(keys[1])?, "goblin");
+/// # Ok(())
+/// # }
+/// ```
+pub struct TwoPhase<'a> {
+    ops: Vec<Box<dyn StagedOp + 'a>>,
+}
+
+impl<'a> TwoPhase<'a> {
+    //FN TwoPhase::new()
+    /// Create a new [TwoPhase] with no staged operations
+    pub fn new() -> TwoPhase<'a> {
+        return TwoPhase { ops: Vec::new() };
+    }
+
+    //FN TwoPhase::insert()
+    /// Stage inserting `value` into the [Prison]
+    ///
+    /// Returns the position of the new [CellKey] in the [Vec] returned by [TwoPhase::commit()]
+    pub fn insert<T, S: PrisonStorage>(&mut self, prison: &'a Prison<T, S>, value: T) -> usize {
+        let position = self.ops.iter().filter(|op| op.target().is_none()).count();
+        self.ops.push(Box::new(StagedPrisonOp {
+            prison,
+            kind: StagedKind::Insert(Some(value)),
+        }));
+        return position;
+    }
+
+    //FN TwoPhase::remove()
+    /// Stage removing the value at the [CellKey] from the [Prison]
+    pub fn remove<T, S: PrisonStorage>(&mut self, prison: &'a Prison<T, S>, key: CellKey) {
+        self.ops.push(Box::new(StagedPrisonOp {
+            prison,
+            kind: StagedKind::Remove(key),
+        }));
+    }
+
+    //FN TwoPhase::set()
+    /// Stage replacing the value at the [CellKey] in the [Prison] with `value`, keeping the same [CellKey]
+    pub fn set<T, S: PrisonStorage>(&mut self, prison: &'a Prison<T, S>, key: CellKey, value: T) {
+        self.ops.push(Box::new(StagedPrisonOp {
+            prison,
+            kind: StagedKind::Set(key, Some(value)),
+        }));
+    }
+
+    //FN TwoPhase::len()
+    /// Return the number of staged operations
+    pub fn len(&self) -> usize {
+        return self.ops.len();
     }
 
-    //FN Prison::_add_many_imm_refs_idx()
-    #[doc(hidden)]
-    fn _add_many_imm_refs_idx(
-        &self,
-        idxs: &[usize],
-    ) -> Result<(Vec<&T>, Vec<&mut usize>, &mut usize), AccessError> {
-        let internal = internal!(self);
-        let mut vals = Vec::new();
-        let mut refs = Vec::new();
-        let mut ref_all_result = Ok(());
-        for idx in idxs {
-            let ref_result = self._add_imm_ref(*idx, 0, false);
-            match ref_result {
-                Ok((cell, _)) => {
-                    vals.push(unsafe { cell.val.assume_init_ref() });
-                    refs.push(&mut cell.refs_or_next);
-                }
-                Err(e) => {
-                    ref_all_result = Err(e);
-                    break;
-                }
+    //FN TwoPhase::is_empty()
+    /// Return `true` if no operations are staged
+    pub fn is_empty(&self) -> bool {
+        return self.ops.is_empty();
+    }
+
+    //FN TwoPhase::commit()
+    /// Validate every staged operation, then apply all of them in the order they were staged
+    ///
+    /// Returns the [CellKey] of every staged insert, in the order they were staged
+    /// ## Errors
+    /// If any staged operation would fail, the first error found is returned and no [Prison] is modified:
+    /// - [AccessError::PrisonSealed] if any targeted [Prison] is sealed
+    /// - [AccessError::InsertAtMaxCapacityWhileAValueIsReferenced] if the inserts into a [Prison] would need it to grow while any of its values are referenced
+    /// - [AccessError::MaximumCapacityReached] if the inserts into a [Prison] would grow it past the maximum capacity
+    /// - [AccessError::IndexOutOfRange(idx)] if a removed or replaced [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if a removed or replaced value does not exist, or is removed after already being removed or replaced
+    /// - [AccessError::RemoveWhileValueReferenced(idx)] if a removed value is referenced
+    /// - [AccessError::ValueAlreadyMutablyReferenced(idx)] or [AccessError::ValueStillImmutablyReferenced(idx)] if a replaced value is referenced
+    /// - [AccessError::ValuePinned(idx)] if a removed value is pinned
+    /// - [AccessError::MaxValueForGenerationReached] if a removal would need a generation above the maximum
+    pub fn commit(mut self) -> Result<Vec<CellKey>, AccessError> {
+        let mut inserts: Vec<(u32, usize)> = Vec::new();
+        let mut targets: Vec<(u32, CellKey, bool)> = Vec::new();
+        for op in &self.ops {
+            match op.target() {
+                None => match inserts.iter_mut().find(|(id, _)| *id == op.prison_id()) {
+                    Some((_, count)) => *count += 1,
+                    None => inserts.push((op.prison_id(), 1)),
+                },
+                Some((key, is_remove)) => targets.push((op.prison_id(), key, is_remove)),
             }
         }
-        match ref_all_result {
-            Ok(_) => {
-                return Ok((vals, refs, &mut internal.access_count));
+        for op in &self.ops {
+            let (_, count) = inserts.iter().find(|(id, _)| *id == op.prison_id()).unwrap_or(&(0, 0));
+            op.validate(*count)?;
+        }
+        targets.sort_by_key(|(id, key, _)| (*id, key.idx));
+        for pair in targets.windows(2) {
+            let ((id_a, key_a, remove_a), (id_b, key_b, remove_b)) = (pair[0], pair[1]);
+            if id_a == id_b && key_a.idx == key_b.idx && (remove_a || remove_b) {
+                return Err(AccessError::ValueDeleted(key_b.idx, key_b.gen));
             }
-            Err(acc_err) => {
-                _remove_many_imm_refs(&mut refs, &mut internal.access_count);
-                return Err(acc_err);
+        }
+        let mut keys = Vec::with_capacity(inserts.iter().map(|(_, count)| count).sum());
+        for op in &mut self.ops {
+            match op.apply() {
+                Ok(Some(key)) => keys.push(key),
+                Ok(None) => {}
+                Err(err) => {
+                    major_malfunction!("staged operation failed after being validated: {}", err) //COV_IGNORE
+                }
             }
         }
+        return Ok(keys);
     }
 }
 
-//FN _remove_mut_ref()
+//IMPL Default for TwoPhase
+impl<'a> Default for TwoPhase<'a> {
+    fn default() -> Self {
+        return TwoPhase::new();
+    }
+}
+
+//TRAIT StagedOp
 #[doc(hidden)]
-#[inline(always)]
-fn _remove_mut_ref(refs: &mut usize, accesses: &mut usize) {
-    *refs = 0;
-    *accesses -= 1;
+trait StagedOp {
+    fn prison_id(&self) -> u32;
+    fn target(&self) -> Option<(CellKey, bool)>;
+    fn validate(&self, inserts: usize) -> Result<(), AccessError>;
+    fn apply(&mut self) -> Result<Option<CellKey>, AccessError>;
 }
 
-//FN _remove_imm_ref()
+//ENUM StagedKind
 #[doc(hidden)]
-#[inline(always)]
-fn _remove_imm_ref(refs: &mut usize, accesses: &mut usize) {
-    *refs -= 1;
-    if *refs == 0 {
-        *accesses -= 1
-    }
+enum StagedKind<T> {
+    Insert(Option<T>),
+    Remove(CellKey),
+    Set(CellKey, Option<T>),
 }
 
-//FN _remove_many_mut_refs()
+//STRUCT StagedPrisonOp
 #[doc(hidden)]
-#[inline(always)]
-fn _remove_many_mut_refs(refs_list: &mut [&mut usize], accesses: &mut usize) {
-    for refs in refs_list {
-        _remove_mut_ref(refs, accesses)
+struct StagedPrisonOp<'a, T, S: PrisonStorage = VecStorage> {
+    prison: &'a Prison<T, S>,
+    kind: StagedKind<T>,
+}
+
+impl<'a, T, S: PrisonStorage> StagedOp for StagedPrisonOp<'a, T, S> {
+    fn prison_id(&self) -> u32 {
+        let prison = self.prison;
+        return internal!(prison).id();
+    }
+
+    fn target(&self) -> Option<(CellKey, bool)> {
+        match &self.kind {
+            StagedKind::Insert(_) => return None,
+            StagedKind::Remove(key) => return Some((*key, true)),
+            StagedKind::Set(key, _) => return Some((*key, false)),
+        }
+    }
+
+    fn validate(&self, inserts: usize) -> Result<(), AccessError> {
+        let prison = self.prison;
+        prison._check_unsealed()?;
+        let internal = internal!(prison);
+        let key = match &self.kind {
+            StagedKind::Insert(_) => {
+                let free = internal.free_count - internal.reserved_free();
+                let spare = internal.vec.capacity() - internal.vec.len();
+                if inserts > free + spare {
+                    if internal.access_count.count > 0 {
+                        return Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
+                    }
+                    if internal.vec.len() + (inserts - free) > IdxD::MAX_CAP {
+                        return Err(AccessError::MaximumCapacityReached);
+                    }
+                }
+                return Ok(());
+            }
+            StagedKind::Remove(key) | StagedKind::Set(key, _) => *key,
+        };
+        prison._check_key(key)?;
+        let cell = &internal.vec[key.idx];
+        if let StagedKind::Set(..) = self.kind {
+            match cell.refs_or_next {
+                0 => return Ok(()),
+                Refs::MUT => return Err(AccessError::ValueAlreadyMutablyReferenced(key.idx)),
+                _ => return Err(AccessError::ValueStillImmutablyReferenced(key.idx)),
+            }
+        }
+        if cell.refs_or_next > 0 {
+            return Err(AccessError::RemoveWhileValueReferenced(key.idx));
+        }
+        if internal.is_pinned(key.idx) {
+            return Err(AccessError::ValuePinned(key.idx));
+        }
+        if key.gen >= internal.generation && key.gen == IdxD::MAX_GEN {
+            return Err(AccessError::MaxValueForGenerationReached);
+        }
+        return Ok(());
+    }
+
+    fn apply(&mut self) -> Result<Option<CellKey>, AccessError> {
+        let prison = self.prison;
+        match &mut self.kind {
+            StagedKind::Insert(value) => match value.take() {
+                Some(value) => return Ok(Some(prison.insert(value)?)),
+                None => major_malfunction!("staged insert into `Prison` {} was applied twice", internal!(prison).id()), //COV_IGNORE
+            },
+            StagedKind::Remove(key) => {
+                prison.remove(*key)?;
+                return Ok(None);
+            }
+            StagedKind::Set(key, value) => match value.take() {
+                Some(value) => {
+                    *prison.guard_mut(*key)? = value;
+                    return Ok(None);
+                }
+                None => major_malfunction!("staged replacement in `Prison` {} was applied twice", internal!(prison).id()), //COV_IGNORE
+            },
+        }
     }
 }
 
-//FN _remove_many_imm_refs()
+//------ Prison Serde ------
+//IMPL Serialize for Prison
+/// Serializes the [Prison] as a struct with the fields `generation`, `cells`, `free`, and `reserved`,
+/// recording the generation of every valid value and the exact order of the free list so that
+/// deserializing it produces a [Prison] where every saved [CellKey] references the same value
+///
+/// Every value is immutably referenced while it is serialized, so serialization fails if any value is
+/// currently mutably referenced. Values stored with a transform (see `with_transform()`) are serialized in their
+/// decoded form. Placeholders, pins, value groups, index salting, the move hook, and per-cell data kept by the
+/// `lru`, `uuid`, `dirty`, `recycle_stats`, and `ttl` features are not serialized
+#[cfg(feature = "serde")]
+impl<T: serde::Serialize> serde::Serialize for Prison<T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+        let internal = internal!(self);
+        let mut free = Vec::with_capacity(internal.free_count - internal.reserved_free());
+        let mut next = internal.next_free;
+        while next != IdxD::INVALID {
+            free.push(next);
+            next = internal.vec[next].refs_or_next;
+        }
+        let mut state = serializer.serialize_struct("Prison", 4)?;
+        state.serialize_field("generation", &internal.generation)?;
+        state.serialize_field("cells", &SerdeCells(self))?;
+        state.serialize_field("free", &free)?;
+        state.serialize_field("reserved", internal.reserved())?;
+        return state.end();
+    }
+}
+
+//STRUCT SerdeCells
+/// Serializes the cells of a [Prison] as a sequence of `Option<(gen, value)>`, where `None` marks a free cell
 #[doc(hidden)]
-#[inline(always)]
-fn _remove_many_imm_refs(refs_list: &mut [&mut usize], accesses: &mut usize) {
-    for refs in refs_list {
-        _remove_imm_ref(refs, accesses)
+#[cfg(feature = "serde")]
+struct SerdeCells<'a, T>(&'a Prison<T>);
+
+//IMPL Serialize for SerdeCells
+#[cfg(feature = "serde")]
+impl<'a, T: serde::Serialize> serde::Serialize for SerdeCells<'a, T> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeSeq;
+        let prison = self.0;
+        let len = internal!(prison).vec.len();
+        let mut seq = serializer.serialize_seq(Some(len))?;
+        for idx in 0..len {
+            let cell = &internal!(prison).vec[idx];
+            if cell.is_free() {
+                seq.serialize_element(&Option::<(usize, &T)>::None)?;
+                continue;
+            }
+            let gen = IdxD::val(cell.d_gen_or_prev);
+            let (cell, accesses) = prison._try_imm_ref(idx, gen, true).map_err(<S::Error as serde::ser::Error>::custom)?;
+            let _release = RefRelease::new(prison, &mut cell.refs_or_next, |refs| _remove_imm_ref(refs, accesses));
+            prison._decoded_ref(unsafe { cell.val.assume_init_ref() }, |val| seq.serialize_element(&Some((gen, val))))?;
+        }
+        return seq.end();
     }
 }
 
-//IMPL Default for Prison
-impl<T> Default for Prison<T> {
-    fn default() -> Self {
-        Self::new()
+//IMPL Deserialize for Prison
+/// Deserializes a [Prison] saved by its `Serialize` implementation, accepting either the struct form or a plain
+/// sequence of its four fields (as used by compact binary formats)
+///
+/// The saved layout is validated before anything is built: every valid value must have a generation no greater than
+/// the saved `generation`, reserved ranges must be sorted, non-overlapping, and inside the [Prison], and the free list
+/// must name every free cell outside of a reserved range exactly once. The new [Prison] has no active references,
+/// no placeholder, and its own identity for [ScriptHandle]'s
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::Deserialize<'de> for Prison<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        return deserializer.deserialize_struct("Prison", SERDE_FIELDS, PrisonVisitor(PhantomData));
     }
 }
 
-//STRUCT PrisonInternal
+//STATIC SERDE_FIELDS
 #[doc(hidden)]
-#[derive(Debug)] //COV_IGNORE
-struct PrisonInternal<T> {
-    access_count: usize,
-    generation: usize,
-    free_count: usize,
-    next_free: usize,
-    vec: Vec<PrisonCell<T>>,
-}
+#[cfg(feature = "serde")]
+const SERDE_FIELDS: &[&str] = &["generation", "cells", "free", "reserved"];
 
-//STRUCT PrisonCell
+//STRUCT PrisonVisitor
 #[doc(hidden)]
-#[derive(Debug)] //COV_IGNORE
-struct PrisonCell<T> {
-    refs_or_next: usize,
-    d_gen_or_prev: usize,
-    val: MaybeUninit<T>,
-}
+#[cfg(feature = "serde")]
+struct PrisonVisitor<T>(PhantomData<T>);
 
-//IMPL Drop for PrisonCell
-impl<T> Drop for PrisonCell<T> {
-    fn drop(&mut self) {
-        if self.is_cell() {
-            unsafe { self.val.assume_init_drop() }
+//IMPL Visitor for PrisonVisitor
+#[cfg(feature = "serde")]
+impl<'de, T: serde::Deserialize<'de>> serde::de::Visitor<'de> for PrisonVisitor<T> {
+    type Value = Prison<T>;
+
+    fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
+        return formatter.write_str("struct Prison");
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::SeqAccess<'de>,
+    {
+        use serde::de::Error;
+        let generation = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(0, &self))?;
+        let cells = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(1, &self))?;
+        let free = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(2, &self))?;
+        let reserved = seq.next_element()?.ok_or_else(|| A::Error::invalid_length(3, &self))?;
+        return _prison_from_serde_parts(generation, cells, free, reserved);
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'de>,
+    {
+        use serde::de::Error;
+        let mut generation = None;
+        let mut cells = None;
+        let mut free = None;
+        let mut reserved = None;
+        while let Some(field) = map.next_key::<String>()? {
+            match field.as_str() {
+                "generation" if generation.is_none() => generation = Some(map.next_value()?),
+                "cells" if cells.is_none() => cells = Some(map.next_value()?),
+                "free" if free.is_none() => free = Some(map.next_value()?),
+                "reserved" if reserved.is_none() => reserved = Some(map.next_value()?),
+                "generation" | "cells" | "free" | "reserved" => return Err(A::Error::custom(format!("duplicate field `{}`", field))),
+                _ => return Err(A::Error::unknown_field(&field, SERDE_FIELDS)),
+            }
         }
+        let generation = generation.ok_or_else(|| A::Error::missing_field("generation"))?;
+        let cells = cells.ok_or_else(|| A::Error::missing_field("cells"))?;
+        let free = free.ok_or_else(|| A::Error::missing_field("free"))?;
+        let reserved = reserved.ok_or_else(|| A::Error::missing_field("reserved"))?;
+        return _prison_from_serde_parts(generation, cells, free, reserved);
     }
 }
 
-impl<T> PrisonCell<T> {
-    #[inline(always)]
-    fn is_cell_and_gen_match_opt(&self, gen: usize, use_gen: bool) -> bool {
-        IdxD::is_type_a(self.d_gen_or_prev) && (!use_gen || IdxD::val(self.d_gen_or_prev) == gen)
+//FN _prison_from_serde_parts()
+#[doc(hidden)]
+#[cfg(feature = "serde")]
+fn _prison_from_serde_parts<T, E>(
+    generation: usize,
+    cells: Vec<Option<(usize, T)>>,
+    free: Vec<usize>,
+    reserved: Vec<(usize, usize)>,
+) -> Result<Prison<T>, E>
+where
+    E: serde::de::Error,
+{
+    let len = cells.len();
+    if len > IdxD::MAX_CAP {
+        return Err(E::custom(AccessError::MaximumCapacityReached));
     }
-    #[inline(always)]
-    fn is_cell_and_gen_match(&self, gen: usize) -> bool {
-        IdxD::is_type_a(self.d_gen_or_prev) && IdxD::val(self.d_gen_or_prev) == gen
+    if generation > IdxD::MAX_GEN {
+        return Err(E::custom(format!("saved generation ({}) is larger than the maximum generation", generation)));
     }
-    #[inline(always)]
-    fn is_cell(&self) -> bool {
-        IdxD::is_type_a(self.d_gen_or_prev)
+    if let Some(idx) = cells.iter().position(|cell| matches!(cell, Some((gen, _)) if *gen > generation)) {
+        return Err(E::custom(format!("cell at index {} has a generation larger than the saved generation ({})", idx, generation)));
     }
-    #[inline(always)]
-    fn is_free(&self) -> bool {
-        IdxD::is_type_b(self.d_gen_or_prev)
+    let mut prev_end = 0;
+    for (start, end) in reserved.iter().copied() {
+        if start < prev_end || start >= end || end > len {
+            return Err(E::custom(format!("reserved range {}..{} is empty, out of order, overlapping, or out of range", start, end)));
+        }
+        prev_end = end;
     }
-
-    fn new_cell(val: T, gen: usize) -> PrisonCell<T> {
-        PrisonCell {
-            refs_or_next: 0,
-            d_gen_or_prev: IdxD::new_type_a(gen),
-            val: MaybeUninit::new(val),
+    let mut in_free_list = vec![false; len];
+    for idx in free.iter().copied() {
+        if idx >= len || cells[idx].is_some() || in_free_list[idx] {
+            return Err(E::custom(format!("free list entry {} is out of range, not free, or repeated", idx)));
         }
+        in_free_list[idx] = true;
     }
-
-    fn make_free_unchecked(&mut self, next: usize, prev: usize) -> T {
-        self.d_gen_or_prev = IdxD::new_type_b(prev);
-        self.refs_or_next = next;
-        unsafe { mem_replace(&mut self.val, MaybeUninit::uninit()).assume_init() }
+    let mut new_internal: PrisonInternal<T> = PrisonInternal::new(Vec::with_capacity(len), None);
+    new_internal.generation = generation;
+    if !reserved.is_empty() {
+        new_internal.extras().reserved = reserved;
     }
-
-    fn make_cell_unchecked(&mut self, val: T, gen: usize) {
-        self.d_gen_or_prev = IdxD::new_type_a(gen);
-        self.refs_or_next = 0;
-        self.val = MaybeUninit::new(val);
+    for (idx, cell) in cells.iter().enumerate() {
+        match (cell, new_internal.is_reserved(idx), in_free_list[idx]) {
+            (Some(_), ..) | (None, true, false) | (None, false, true) => {}
+            (None, true, true) => return Err(E::custom(format!("free list entry {} is inside a reserved range", idx))),
+            (None, false, false) => return Err(E::custom(format!("free cell at index {} is missing from the free list", idx))),
+        }
     }
-
-    fn overwrite_cell_unchecked(&mut self, val: T, gen: usize) {
-        self.d_gen_or_prev = IdxD::new_type_a(gen);
-        self.refs_or_next = 0;
-        unsafe { self.val.assume_init_drop() };
-        self.val = MaybeUninit::new(val);
+    for (idx, cell) in cells.into_iter().enumerate() {
+        match cell {
+            Some((gen, val)) => new_internal.vec.push(PrisonCell::new_cell(val, gen)),
+            None => {
+                if !in_free_list[idx] {
+                    new_internal.free_count += 1;
+                    new_internal.extras().reserved_free += 1;
+                }
+                new_internal.vec.push(PrisonCell::new_free(IdxD::INVALID, IdxD::INVALID, None));
+            }
+        }
+    }
+    #[cfg(feature = "uuid")]
+    for idx in 0..len {
+        if new_internal.vec[idx].is_cell() {
+            new_internal.assign_uuid(idx);
+        }
+    }
+    let new_prison = Prison {
+        internal: UnsafeCell::new(new_internal),
+    };
+    for idx in free.iter().rev().copied() {
+        if new_prison._link_free(idx).is_err() {
+            unreachable!() //COV_IGNORE
+        }
     }
+    return Ok(new_prison);
 }
 
-//------ Guarded Prison ------
-//STRUCT PrisonValueMut
-/// Struct representing a mutable reference to a value that has been allowed to leave the
-/// [Prison] temporarily, but remains guarded by a wrapper to prevent it from leaking or never unlocking
+//====== UnPrison ======
+//STRUCT UnPrison
+/// A [Prison] with accessors that skip reference counting entirely, for hot loops where you have
+/// already proven that every access is exclusive
 ///
-/// [PrisonValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
-/// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value
+/// An [UnPrison] has exactly the same storage layout as a [Prison] (it simply wraps one), so the same
+/// [CellKey]s work in both, generations are still checked, and converting between the two with
+/// [From]/[Into] is free. All accessors are `unsafe fn`s that never read or write any reference count,
+/// so they are as fast as indexing into a [Vec] with a generation check, but it is up to you to uphold
+/// Rust's aliasing rules.
 ///
-/// As long as the [PrisonValueMut] remains in scope, the element where it's value resides in the
-/// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
-/// You can manually drop the [PrisonValueMut] out of scope by passing it as the first parameter
-/// to the function [PrisonValueMut::unguard(p_val_mut)]
+/// `insert()` and `remove()` take `&mut self`, so the borrow checker guarantees that no reference from an
+/// accessor is still alive when the underlying [Vec] could reallocate. The full checked API remains
+/// available through [UnPrison::as_prison()].
 ///
-/// You can obtain a [PrisonValueMut] by calling `guard_mut()` or `guard_mut_idx()` on a [Prison]
+/// Unchecked accessors also skip any other per-access bookkeeping, such as `lru` and `dirty` tracking,
+/// and return the stored (encoded) form of values in a [Prison] created with `with_transform()`
 /// ### Example
 /// ```rust
-/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, UnPrison}};
 /// # fn main() -> Result<(), AccessError> {
-/// let prison: Prison<u32> = Prison::new();
-/// let key_0 = prison.insert(10)?;
-/// let mut grd_0 = prison.guard_mut(key_0)?;
-/// assert_eq!(*grd_0, 10);
-/// *grd_0 = 20;
-/// PrisonValueMut::unguard(grd_0);
-/// prison.visit_ref(key_0, |val_0| {
-///     assert_eq!(*val_0, 20);
-///     Ok(())
-/// });
+/// let prison: Prison<(f32, f32)> = Prison::new();
+/// let keys: Vec<CellKey> = (0..100).map(|i| prison.insert((i as f32, 1.0))).collect::<Result<_, _>>()?;
+/// let mut fast: UnPrison<(f32, f32)> = UnPrison::from(prison);
+/// for key in keys.iter() {
+///     // SAFETY: each key is visited once and no other reference to the values exists
+///     let (pos, vel) = unsafe { fast.get_mut(*key)? };
+///     *pos += *vel;
+/// }
+/// fast.remove(keys[0])?;
+/// let prison: Prison<(f32, f32)> = fast.into();
+/// assert_eq!(prison.clone_val(keys[99])?, (100.0, 1.0));
+/// assert!(prison.clone_val(keys[0]).is_err());
 /// # Ok(())
 /// # }
 /// ```
-pub struct PrisonValueMut<'a, T> {
-    cell: &'a mut PrisonCell<T>,
-    prison_accesses: &'a mut usize,
+pub struct UnPrison<T> {
+    prison: Prison<T>,
 }
 
-impl<'a, T> PrisonValueMut<'a, T> {
-    //FN PrisonValueMut::unguard()
-    /// Manually end a [PrisonValueMut] value's temporary guarded absence from the [Prison]
+impl<T> UnPrison<T> {
+    //FN UnPrison::new()
+    /// Create a new [UnPrison] with the default allocation strategy ([Vec::new()])
+    pub fn new() -> Self {
+        return UnPrison { prison: Prison::new() };
+    }
+
+    //FN UnPrison::with_capacity()
+    /// Create a new [UnPrison] with a specific starting capacity ([Vec::with_capacity()])
+    pub fn with_capacity(size: usize) -> Self {
+        return UnPrison {
+            prison: Prison::with_capacity(size),
+        };
+    }
+
+    //FN UnPrison::as_prison()
+    /// Return the underlying [Prison], to use any of its checked methods
     ///
-    /// This method simply takes ownership of the [PrisonValueMut] and immediately lets it go out of scope,
-    /// causing it's `drop()` method to be called and clearing its mutable reference in the [Prison]
-    /// ### Example
-    /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueMut}};
-    /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// let grd_0 = prison.guard_mut_idx(0)?;
-    /// // index 0 CANNOT be accessed here because it is being guarded outside the prison
-    /// assert!(prison.visit_ref_idx(0, |ref_0| Ok(())).is_err());
-    /// PrisonValueMut::unguard(grd_0);
-    /// // index 0 CAN be accessed here because it was returned to the prison
-    /// assert!(prison.visit_ref_idx(0, |ref_0| Ok(())).is_ok());
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub fn unguard(_prison_val_mut: Self) {}
-}
+    /// Checked methods only track the references they hand out themselves, so they cannot detect references
+    /// obtained from the unchecked accessors of this [UnPrison]
+    #[inline(always)]
+    pub fn as_prison(&self) -> &Prison<T> {
+        return &self.prison;
+    }
 
-//IMPL Drop for PrisonValueMut
-impl<'a, T> Drop for PrisonValueMut<'a, T> {
-    fn drop(&mut self) {
-        _remove_mut_ref(&mut self.cell.refs_or_next, self.prison_accesses)
+    //FN UnPrison::insert()
+    /// Insert a value and recieve a [CellKey] that can be used to reference it in the future
+    ///
+    /// See [Prison::insert()]
+    /// ## Errors
+    /// Same as [Prison::insert()]
+    #[inline(always)]
+    pub fn insert(&mut self, value: T) -> Result<CellKey, AccessError> {
+        return self.prison.insert(value);
     }
-}
 
-//IMPL Deref for PrisonValueMut
-impl<'a, T> Deref for PrisonValueMut<'a, T> {
-    type Target = T;
+    //FN UnPrison::remove()
+    /// Remove and return the value indexed by the provided [CellKey]
+    ///
+    /// See [Prison::remove()]
+    /// ## Errors
+    /// Same as [Prison::remove()]
+    #[inline(always)]
+    pub fn remove(&mut self, key: CellKey) -> Result<T, AccessError> {
+        return self.prison.remove(key);
+    }
 
+    //FN UnPrison::get_ref()
+    /// Get an immutable reference to the value indexed by the provided [CellKey] without any reference counting
+    /// # Safety
+    /// As long as the returned `&T` is alive, the value MUST NOT be mutably referenced by ANY source,
+    /// including the unchecked accessors of this [UnPrison] and the checked methods of [UnPrison::as_prison()],
+    /// and no operation that could relocate the underlying [Vec] may be performed through [UnPrison::as_prison()]
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed and its cell freed or filled with a new value
     #[inline(always)]
-    fn deref(&self) -> &Self::Target {
-        unsafe { self.cell.val.assume_init_ref() }
+    pub unsafe fn get_ref<'a>(&'a self, key: CellKey) -> Result<&'a T, AccessError> {
+        let prison = &self.prison;
+        match internal!(prison).vec.get(key.idx) {
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) => return Ok(unsafe { cell.val.assume_init_ref() }),
+            Some(_) => return Err(AccessError::ValueDeleted(key.idx, key.gen)),
+            None => return Err(AccessError::IndexOutOfRange(key.idx)),
+        }
     }
-}
 
-//IMPL DerefMut for PrisonValueMut
-impl<'a, T> DerefMut for PrisonValueMut<'a, T> {
+    //FN UnPrison::get_mut()
+    /// Get a mutable reference to the value indexed by the provided [CellKey] without any reference counting
+    /// # Safety
+    /// As long as the returned `&mut T` is alive, the value MUST NOT be referenced in any way by ANY other source,
+    /// including the unchecked accessors of this [UnPrison] and the checked methods of [UnPrison::as_prison()],
+    /// and no operation that could relocate the underlying [Vec] may be performed through [UnPrison::as_prison()]
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the [CellKey] index is out of range
+    /// - [AccessError::ValueDeleted(idx, gen)] if the value was removed and its cell freed or filled with a new value
     #[inline(always)]
-    fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe { self.cell.val.assume_init_mut() }
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut<'a>(&'a self, key: CellKey) -> Result<&'a mut T, AccessError> {
+        let prison = &self.prison;
+        match internal!(prison).vec.get_mut(key.idx) {
+            Some(cell) if cell.is_cell_and_gen_match(key.gen) => return Ok(unsafe { cell.val.assume_init_mut() }),
+            Some(_) => return Err(AccessError::ValueDeleted(key.idx, key.gen)),
+            None => return Err(AccessError::IndexOutOfRange(key.idx)),
+        }
     }
-}
 
-//IMPL AsRef for PrisonValueMut
-impl<'a, T> AsRef<T> for PrisonValueMut<'a, T> {
+    //FN UnPrison::get_ref_idx()
+    /// Get an immutable reference to the value at the provided index without any reference counting
+    ///
+    /// Like [UnPrison::get_ref()] but disregards the generation counter
+    /// # Safety
+    /// Same as [UnPrison::get_ref()]
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, 0)] if the cell at the index is free
     #[inline(always)]
-    fn as_ref(&self) -> &T {
-        unsafe { self.cell.val.assume_init_ref() }
+    pub unsafe fn get_ref_idx<'a>(&'a self, idx: usize) -> Result<&'a T, AccessError> {
+        let idx = self.prison._idx(idx)?;
+        let prison = &self.prison;
+        match internal!(prison).vec.get(idx) {
+            Some(cell) if cell.is_cell() => return Ok(unsafe { cell.val.assume_init_ref() }),
+            Some(_) => return Err(AccessError::ValueDeleted(idx, 0)),
+            None => return Err(AccessError::IndexOutOfRange(idx)),
+        }
+    }
+
+    //FN UnPrison::get_mut_idx()
+    /// Get a mutable reference to the value at the provided index without any reference counting
+    ///
+    /// Like [UnPrison::get_mut()] but disregards the generation counter
+    /// # Safety
+    /// Same as [UnPrison::get_mut()]
+    /// ## Errors
+    /// - [AccessError::IndexOutOfRange(idx)] if the index is out of range
+    /// - [AccessError::ValueDeleted(idx, 0)] if the cell at the index is free
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn get_mut_idx<'a>(&'a self, idx: usize) -> Result<&'a mut T, AccessError> {
+        let idx = self.prison._idx(idx)?;
+        let prison = &self.prison;
+        match internal!(prison).vec.get_mut(idx) {
+            Some(cell) if cell.is_cell() => return Ok(unsafe { cell.val.assume_init_mut() }),
+            Some(_) => return Err(AccessError::ValueDeleted(idx, 0)),
+            None => return Err(AccessError::IndexOutOfRange(idx)),
+        }
     }
 }
 
-//IMPL AsMut for PrisonValueMut
-impl<'a, T> AsMut<T> for PrisonValueMut<'a, T> {
-    #[inline(always)]
-    fn as_mut(&mut self) -> &mut T {
-        unsafe { self.cell.val.assume_init_mut() }
+//IMPL Default for UnPrison
+impl<T> Default for UnPrison<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-//IMPL Borrow for PrisonValueMut
-impl<'a, T> Borrow<T> for PrisonValueMut<'a, T> {
+//IMPL From<Prison> for UnPrison
+impl<T> From<Prison<T>> for UnPrison<T> {
     #[inline(always)]
-    fn borrow(&self) -> &T {
-        unsafe { self.cell.val.assume_init_ref() }
+    fn from(prison: Prison<T>) -> Self {
+        return UnPrison { prison };
     }
 }
 
-//IMPL BorrowMut for PrisonValueMut
-impl<'a, T> BorrowMut<T> for PrisonValueMut<'a, T> {
+//IMPL From<UnPrison> for Prison
+impl<T> From<UnPrison<T>> for Prison<T> {
     #[inline(always)]
-    fn borrow_mut(&mut self) -> &mut T {
-        unsafe { self.cell.val.assume_init_mut() }
+    fn from(un_prison: UnPrison<T>) -> Self {
+        return un_prison.prison;
     }
 }
 
-//STRUCT PrisonValueRef
-/// Struct representing an immutable reference to a value that has been allowed to leave the
-/// [Prison] temporarily, but remains guarded by a wrapper to prevent it from leaking or never unlocking
-///
-/// [PrisonValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], and [Borrow<T>]
-/// to allow transparent access to its underlying value
+//====== PrisonQueue ======
+//STRUCT PrisonQueue
+/// A double-ended queue whose elements live in a [Prison], so every element stays addressable by its
+/// [CellKey] while it waits in line
 ///
-/// As long as the [PrisonValueRef] remains in scope, the element where it's value resides in the
-/// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
-/// You can manually drop the [PrisonValueRef] out of scope by passing it as the first parameter
-/// to the function [PrisonValueRef::unguard(p_val_ref)]
+/// The order of the elements is kept in a side list of `prev`/`next` indexes parallel to the [Prison], so pushing,
+/// popping, and removing an element from the middle (for example to cancel a queued job) are all O(1)
+/// and never move any values. Use `push_back()` with `pop_front()` for a FIFO queue, or `push_back()` with
+/// `pop_back()` for a LIFO stack.
 ///
-/// You can obtain a [PrisonValueRef] by calling `guard_ref()` or `guard_ref_idx()` on a [Prison]
+/// Elements can only be inserted and removed through the [PrisonQueue] itself so the order always matches
+/// the values in the [Prison], but they can be visited, guarded, and cloned by [CellKey] just like in a [Prison].
+/// Mutable access is only offered through `visit_mut()`, because a [PrisonValueMut] could remove its element
+/// without the queue knowing. Like [Prison], every method takes `&self`
 /// ### Example
 /// ```rust
-/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::PrisonQueue};
 /// # fn main() -> Result<(), AccessError> {
-/// let prison: Prison<u32> = Prison::new();
-/// let key_0 = prison.insert(10)?;
-/// let mut grd_0 = prison.guard_ref(key_0)?;
-/// assert_eq!(*grd_0, 10);
-/// prison.visit_ref(key_0, |val_0| {
-///     assert_eq!(*val_0, 10);
+/// let jobs: PrisonQueue<&str> = PrisonQueue::new();
+/// let key_a = jobs.push_back("render")?;
+/// let key_b = jobs.push_back("upload")?;
+/// let key_c = jobs.push_back("email")?;
+/// jobs.visit_mut(key_c, |job| {
+///     *job = "email (urgent)";
 ///     Ok(())
-/// });
-/// PrisonValueRef::unguard(grd_0);
+/// })?;
+/// assert_eq!(jobs.remove(key_b)?, "upload");
+/// assert_eq!(jobs.pop_front()?, Some((key_a, "render")));
+/// assert_eq!(jobs.pop_front()?, Some((key_c, "email (urgent)")));
+/// assert_eq!(jobs.pop_front()?, None);
 /// # Ok(())
 /// # }
 /// ```
-pub struct PrisonValueRef<'a, T> {
-    cell: &'a mut PrisonCell<T>,
-    prison_accesses: &'a mut usize,
+pub struct PrisonQueue<T> {
+    prison: Prison<T>,
+    internal: UnsafeCell<QueueInternal>,
 }
 
-impl<'a, T> PrisonValueRef<'a, T> {
-    //FN PrisonValueRef::unguard()
-    /// Manually end a [PrisonValueRef] value's temporary guarded absence from the [Prison]
-    ///
-    /// This method simply takes ownership of the [PrisonValueRef] and immediately lets it go out of scope,
-    /// causing it's `drop()` method to be called and decreasing its immutable reference count in the [Prison]
-    /// ### Example
-    /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonValueRef}};
-    /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// let grd_0 = prison.guard_ref_idx(0)?;
-    /// // index 0 CANNOT be accessed here because it is being guarded outside the prison
-    /// assert!(prison.visit_mut_idx(0, |ref_0| Ok(())).is_err());
-    /// PrisonValueRef::unguard(grd_0);
-    /// // index 0 CAN be accessed here because it was returned to the prison
-    /// assert!(prison.visit_mut_idx(0, |ref_0| Ok(())).is_ok());
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub fn unguard(_prison_val_ref: Self) {}
+//STRUCT QueueInternal
+#[doc(hidden)]
+struct QueueInternal {
+    head: usize,
+    tail: usize,
+    len: usize,
+    links: Vec<(usize, usize)>,
 }
 
-//IMPL Drop for PrisonValueRef
-impl<'a, T> Drop for PrisonValueRef<'a, T> {
-    fn drop(&mut self) {
-        _remove_imm_ref(&mut self.cell.refs_or_next, self.prison_accesses)
+impl<T> PrisonQueue<T> {
+    //FN PrisonQueue::new()
+    /// Create a new, empty [PrisonQueue] with the default allocation strategy ([Vec::new()])
+    pub fn new() -> Self {
+        return PrisonQueue::from_prison(Prison::new());
     }
-}
 
-//IMPL Deref for PrisonValueRef
-impl<'a, T> Deref for PrisonValueRef<'a, T> {
-    type Target = T;
+    //FN PrisonQueue::with_capacity()
+    /// Create a new, empty [PrisonQueue] with a specific starting capacity ([Vec::with_capacity()])
+    pub fn with_capacity(size: usize) -> Self {
+        let queue = PrisonQueue::from_prison(Prison::with_capacity(size));
+        let internal = internal!(queue);
+        internal.links.reserve(size);
+        return queue;
+    }
+
+    //FN PrisonQueue::from_prison()
+    #[doc(hidden)]
+    fn from_prison(prison: Prison<T>) -> Self {
+        return PrisonQueue {
+            prison,
+            internal: UnsafeCell::new(QueueInternal {
+                head: IdxD::INVALID,
+                tail: IdxD::INVALID,
+                len: 0,
+                links: Vec::new(),
+            }),
+        };
+    }
+
+    //FN PrisonQueue::len()
+    /// Return the number of elements in the queue
+    pub fn len(&self) -> usize {
+        return internal!(self).len;
+    }
+
+    //FN PrisonQueue::is_empty()
+    /// Return `true` if the queue holds no elements
+    pub fn is_empty(&self) -> bool {
+        return internal!(self).len == 0;
+    }
+
+    //FN PrisonQueue::push_back()
+    /// Insert a value at the back of the queue and return the [CellKey] that references it
+    ///
+    /// The value is inserted with [Prison::insert()], so freed cells are reused first
+    /// ## Errors
+    /// Same as [Prison::insert()]
+    pub fn push_back(&self, value: T) -> Result<CellKey, AccessError> {
+        let key = self.prison.insert(value)?;
+        self._link(key.idx, true);
+        return Ok(key);
+    }
+
+    //FN PrisonQueue::push_front()
+    /// Insert a value at the front of the queue and return the [CellKey] that references it
+    ///
+    /// See [PrisonQueue::push_back()]
+    /// ## Errors
+    /// Same as [Prison::insert()]
+    pub fn push_front(&self, value: T) -> Result<CellKey, AccessError> {
+        let key = self.prison.insert(value)?;
+        self._link(key.idx, false);
+        return Ok(key);
+    }
+
+    //FN PrisonQueue::front()
+    /// Return the [CellKey] of the element at the front of the queue without removing it, or [None] if the queue is empty
+    pub fn front(&self) -> Option<CellKey> {
+        return self._key_at(internal!(self).head);
+    }
+
+    //FN PrisonQueue::back()
+    /// Return the [CellKey] of the element at the back of the queue without removing it, or [None] if the queue is empty
+    pub fn back(&self) -> Option<CellKey> {
+        return self._key_at(internal!(self).tail);
+    }
+
+    //FN PrisonQueue::pop_front()
+    /// Remove the element at the front of the queue, returning its (now invalid) [CellKey] and its value,
+    /// or [None] if the queue is empty
+    ///
+    /// If the element cannot be removed it stays at the front of the queue
+    /// ## Errors
+    /// - [AccessError::RemoveWhileValueReferenced(idx)] if the front element is currently referenced
+    /// - Any other error [Prison::remove()] can return
+    pub fn pop_front(&self) -> Result<Option<(CellKey, T)>, AccessError> {
+        return match self.front() {
+            Some(key) => Ok(Some((key, self.remove(key)?))),
+            None => Ok(None),
+        };
+    }
+
+    //FN PrisonQueue::pop_back()
+    /// Remove the element at the back of the queue, returning its (now invalid) [CellKey] and its value,
+    /// or [None] if the queue is empty
+    ///
+    /// See [PrisonQueue::pop_front()]
+    /// ## Errors
+    /// - [AccessError::RemoveWhileValueReferenced(idx)] if the back element is currently referenced
+    /// - Any other error [Prison::remove()] can return
+    pub fn pop_back(&self) -> Result<Option<(CellKey, T)>, AccessError> {
+        return match self.back() {
+            Some(key) => Ok(Some((key, self.remove(key)?))),
+            None => Ok(None),
+        };
+    }
 
+    //FN PrisonQueue::remove()
+    /// Remove the element referenced by the [CellKey] from anywhere in the queue and return its value
+    ///
+    /// This is how a queued element is cancelled. The order of the remaining elements is unchanged
+    /// ## Errors
+    /// Same as [Prison::remove()]
+    pub fn remove(&self, key: CellKey) -> Result<T, AccessError> {
+        let value = self.prison.remove(key)?;
+        self._unlink(key.idx);
+        return Ok(value);
+    }
+
+    //FN PrisonQueue::keys()
+    /// #### This operation has O(N) time complexity
+    ///
+    /// Return the [CellKey] of every element in queue order, from front to back
+    pub fn keys(&self) -> Vec<CellKey> {
+        let internal = internal!(self);
+        let mut keys = Vec::with_capacity(internal.len);
+        let mut idx = internal.head;
+        while let Some(key) = self._key_at(idx) {
+            keys.push(key);
+            idx = internal.links[idx].1;
+        }
+        return keys;
+    }
+
+    //FN PrisonQueue::contains_key()
+    /// Return `true` if the [CellKey] references an element still in the queue
+    ///
+    /// See [Prison::contains_key()]
+    pub fn contains_key(&self, key: CellKey) -> bool {
+        return self.prison.contains_key(key);
+    }
+
+    //FN PrisonQueue::visit_mut()
+    /// Visit a queued element with a mutable reference, without changing its position in the queue
+    ///
+    /// See [Prison::visit_mut()]
+    /// ## Errors
+    /// Same as [Prison::visit_mut()]
     #[inline(always)]
-    fn deref(&self) -> &Self::Target {
-        unsafe { self.cell.val.assume_init_ref() }
+    pub fn visit_mut<R, F>(&self, key: CellKey, operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&mut T) -> Result<R, AccessError>,
+    {
+        return self.prison.visit_mut(key, operation);
     }
-}
 
-//IMPL AsRef for PrisonValueRef
-impl<'a, T> AsRef<T> for PrisonValueRef<'a, T> {
+    //FN PrisonQueue::visit_ref()
+    /// Visit a queued element with an immutable reference, without changing its position in the queue
+    ///
+    /// See [Prison::visit_ref()]
+    /// ## Errors
+    /// Same as [Prison::visit_ref()]
     #[inline(always)]
-    fn as_ref(&self) -> &T {
-        unsafe { self.cell.val.assume_init_ref() }
+    pub fn visit_ref<R, F>(&self, key: CellKey, operation: F) -> Result<R, AccessError>
+    where
+        F: FnMut(&T) -> Result<R, AccessError>,
+    {
+        return self.prison.visit_ref(key, operation);
+    }
+
+    //FN PrisonQueue::guard_ref()
+    /// Return a [PrisonValueRef] guarding an immutable reference to a queued element
+    ///
+    /// See [Prison::guard_ref()]
+    /// ## Errors
+    /// Same as [Prison::guard_ref()]
+    pub fn guard_ref(&self, key: CellKey) -> Result<PrisonValueRef<'_, T>, AccessError> {
+        return self.prison.guard_ref(key);
+    }
+
+    //FN PrisonQueue::clone_val()
+    /// Return a clone of a queued element
+    ///
+    /// See [Prison::clone_val()]
+    /// ## Errors
+    /// Same as [Prison::clone_val()]
+    pub fn clone_val(&self, key: CellKey) -> Result<T, AccessError>
+    where
+        T: Clone,
+    {
+        return self.prison.clone_val(key);
+    }
+
+    //FN PrisonQueue::_key_at()
+    #[doc(hidden)]
+    fn _key_at(&self, idx: usize) -> Option<CellKey> {
+        if idx == IdxD::INVALID {
+            return None;
+        }
+        let prison = &self.prison;
+        let gen = IdxD::val(internal!(prison).vec[idx].d_gen_or_prev);
+        return Some(CellKey { idx, gen });
+    }
+
+    //FN PrisonQueue::_link()
+    #[doc(hidden)]
+    fn _link(&self, idx: usize, at_back: bool) {
+        let internal = internal!(self);
+        if internal.links.len() <= idx {
+            internal.links.resize(idx + 1, (IdxD::INVALID, IdxD::INVALID));
+        }
+        match (at_back, internal.len) {
+            (_, 0) => {
+                internal.links[idx] = (IdxD::INVALID, IdxD::INVALID);
+                internal.head = idx;
+                internal.tail = idx;
+            }
+            (true, _) => {
+                internal.links[idx] = (internal.tail, IdxD::INVALID);
+                internal.links[internal.tail].1 = idx;
+                internal.tail = idx;
+            }
+            (false, _) => {
+                internal.links[idx] = (IdxD::INVALID, internal.head);
+                internal.links[internal.head].0 = idx;
+                internal.head = idx;
+            }
+        }
+        internal.len += 1;
+    }
+
+    //FN PrisonQueue::_unlink()
+    #[doc(hidden)]
+    fn _unlink(&self, idx: usize) {
+        let internal = internal!(self);
+        let (prev, next) = internal.links[idx];
+        match prev {
+            IdxD::INVALID => internal.head = next,
+            prev => internal.links[prev].1 = next,
+        }
+        match next {
+            IdxD::INVALID => internal.tail = prev,
+            next => internal.links[next].0 = prev,
+        }
+        internal.links[idx] = (IdxD::INVALID, IdxD::INVALID);
+        internal.len -= 1;
     }
 }
 
-//IMPL Borrow for PrisonValueRef
-impl<'a, T> Borrow<T> for PrisonValueRef<'a, T> {
-    #[inline(always)]
-    fn borrow(&self) -> &T {
-        unsafe { self.cell.val.assume_init_ref() }
+//IMPL Default for PrisonQueue
+impl<T> Default for PrisonQueue<T> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
-//STRUCT PrisonSliceMut
-/// Struct representing a slice of mutable references to values that have been allowed to leave the
-/// [Prison] temporarily, but remain guarded by a wrapper to prevent them from leaking or never unlocking
+//====== RefCountCell ======
+//STRUCT RefCountCell
+/// A single value with a reference count that hands out fallible, guarded borrows
 ///
-/// [PrisonSliceMut<T>] implements [Deref<Target = \[&mut T\]>](Deref), [DerefMut<Target = \[&mut T\]>](DerefMut), [AsRef<\[&mut T\]>](AsRef), [AsMut<\[&mut T\]>](AsMut),
-/// [Borrow<\[&mut T\]>](Borrow), and [BorrowMut<\[&mut T\]>](BorrowMut) to allow transparent access to its underlying slice of values
-///
-/// As long as the [PrisonSliceMut] remains in scope, the elements where it's values reside in the
-/// [Prison] will remain marked as mutably referenced and unable to be referenced a second time.
-/// You can manually drop the [PrisonSliceMut] out of scope by passing it as the first parameter
-/// to the function [PrisonSliceMut::unguard(p_sli_mut)]
+/// [RefCountCell] is the building block behind [JailCell], and follows the same reference counting rules
+/// as every value in a [Prison]: any number of immutable references *or* a single mutable reference, with
+/// a failed borrow returning an [AccessError] instead of panicking. It has no `visit()` methods or other
+/// conveniences, so it can serve as a small core for your own containers (trees, maps, graphs) that
+/// need the same fallible-borrow semantics
 ///
-/// You can obtain a [PrisonSliceMut] by calling `guard_many_mut()` or `guard_many_mut_idx()` on a [Prison]
+/// Because a [RefCountCell] only holds one value, errors always report the index `0`
 /// ### Example
 /// ```rust
-/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::RefCountCell};
 /// # fn main() -> Result<(), AccessError> {
-/// let prison: Prison<u32> = Prison::new();
-/// let key_0 = prison.insert(10)?;
-/// let key_1 = prison.insert(20)?;
-/// let key_2 = prison.insert(30)?;
-/// let mut grd_0_1_2 = prison.guard_many_mut(&[key_0, key_1, key_2])?;
-/// assert_eq!(*grd_0_1_2[1], 20);
-/// *grd_0_1_2[1] = 42;
-/// PrisonSliceMut::unguard(grd_0_1_2);
-/// prison.visit_ref(key_1, |val_1| {
-///     assert_eq!(*val_1, 42);
-///     Ok(())
-/// });
+/// struct Node {
+///     val: u32,
+///     children: Vec<RefCountCell<Node>>,
+/// }
+/// let leaf = RefCountCell::new(Node { val: 2, children: Vec::new() });
+/// let root = RefCountCell::new(Node { val: 1, children: vec![leaf] });
+/// let root_ref = root.try_borrow()?;
+/// let mut leaf_mut = root_ref.children[0].try_borrow_mut()?;
+/// leaf_mut.val += root_ref.val;
+/// assert!(root.try_borrow_mut().is_err());
+/// assert_eq!(root_ref.children[0].try_borrow().err(), Some(AccessError::ValueAlreadyMutablyReferenced(0)));
+/// drop(leaf_mut);
+/// drop(root_ref);
+/// assert_eq!(root.into_inner().children[0].try_borrow()?.val, 3);
 /// # Ok(())
 /// # }
 /// ```
-pub struct PrisonSliceMut<'a, T> {
-    prison_accesses: &'a mut usize,
-    refs: Vec<&'a mut usize>,
-    vals: Vec<&'a mut T>,
+pub struct RefCountCell<T> {
+    internal: UnsafeCell<RefCountInternal<T>>,
 }
 
-impl<'a, T> PrisonSliceMut<'a, T> {
-    //FN PrisonSliceMut::unguard()
-    /// Manually end a [PrisonSliceMut] value's temporary guarded absence from the [Prison]
+//IMPL RefUnwindSafe for RefCountCell
+impl<T: RefUnwindSafe> RefUnwindSafe for RefCountCell<T> {}
+
+impl<T> RefCountCell<T> {
+    //FN RefCountCell::new()
+    /// Creates a new unreferenced [RefCountCell] holding `value`
+    pub fn new(value: T) -> RefCountCell<T> {
+        return RefCountCell {
+            internal: UnsafeCell::new(RefCountInternal { refs: 0, val: value }),
+        };
+    }
+
+    //FN RefCountCell::into_inner()
+    /// Consume the [RefCountCell] and return its value
     ///
-    /// This method simply takes ownership of the [PrisonSliceMut] and immediately lets it go out of scope,
-    /// causing it's `drop()` method to be called and decreasing its immutable reference count in the [Prison]
+    /// No borrows can be active, because they would all be borrowing the [RefCountCell]
+    pub fn into_inner(self) -> T {
+        return self.internal.into_inner().val;
+    }
+
+    //FN RefCountCell::get_mut()
+    /// Get a mutable reference to the value without any reference counting
+    ///
+    /// This is always safe because the `&mut self` receiver already proves no borrows are active
+    pub fn get_mut(&mut self) -> &mut T {
+        return &mut self.internal.get_mut().val;
+    }
+
+    //FN RefCountCell::try_borrow_mut()
+    /// Obtain a [RefCountMut] that marks the value mutably referenced as long as it remains in scope
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(0)] if value has any number of immutable references
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn try_borrow_mut<'a>(&'a self) -> Result<RefCountMut<'a, T>, AccessError> {
+        let internal = internal!(self);
+        internal.add_ref_internal(true)?;
+        return Ok(RefCountMut {
+            ref_internal: internal,
+        });
+    }
+
+    //FN RefCountCell::try_borrow()
+    /// Obtain a [RefCountRef] that marks the value immutably referenced as long as it remains in scope
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::MaximumImmutableReferencesReached(0)] if value has usize::MAX - 2 immutable references already
+    #[must_use = "guarded reference will immediately fall out of scope"]
+    pub fn try_borrow<'a>(&'a self) -> Result<RefCountRef<'a, T>, AccessError> {
+        let internal = internal!(self);
+        internal.add_ref_internal(false)?;
+        return Ok(RefCountRef {
+            ref_internal: internal,
+        });
+    }
+
+    //FN RefCountCell::ref_kind()
+    /// Return the kind of references currently active on the value
     /// ### Example
     /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceMut}};
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{RefCountCell, RefKind}};
     /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// prison.insert(20)?;
-    /// let grd_all = prison.guard_many_mut_idx(&[0, 1])?;
-    /// assert!(prison.visit_many_mut_idx(&[0, 1], |ref_all| Ok(())).is_err());
-    /// PrisonSliceMut::unguard(grd_all);
-    /// assert!(prison.visit_many_mut_idx(&[0, 1], |ref_all| Ok(())).is_ok());
+    /// let cell: RefCountCell<u32> = RefCountCell::new(7);
+    /// let (ref_a, ref_b) = (cell.try_borrow()?, cell.try_borrow()?);
+    /// assert_eq!(cell.ref_kind(), RefKind::Immutable(2));
+    /// drop((ref_a, ref_b));
+    /// assert_eq!(cell.ref_kind(), RefKind::None);
     /// # Ok(())
     /// # }
     /// ```
-    pub fn unguard(_prison_sli_mut: Self) {}
+    pub fn ref_kind(&self) -> RefKind {
+        return Refs::kind(internal!(self).refs);
+    }
+}
+
+//IMPL Default for RefCountCell
+impl<T> Default for RefCountCell<T>
+where
+    T: Default,
+{
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+//STRUCT RefCountInternal
+#[doc(hidden)]
+struct RefCountInternal<T> {
+    refs: usize,
+    val: T,
+}
+
+impl<T> RefCountInternal<T> {
+    //FN RefCountInternal::add_ref_internal()
+    #[inline(always)]
+    fn add_ref_internal(&mut self, mutable: bool) -> Result<(), AccessError> {
+        match mutable {
+            true => Refs::add_mut(&mut self.refs, 0)?,
+            false => {
+                Refs::add_imm(&mut self.refs, 0)?;
+            }
+        }
+        return Ok(());
+    }
+
+    //FN RefCountInternal::remove_ref_internal()
+    #[inline(always)]
+    fn remove_ref_internal(&mut self) {
+        match self.refs {
+            0 => {}
+            Refs::MUT => Refs::remove_mut(&mut self.refs),
+            _ => {
+                Refs::remove_imm(&mut self.refs);
+            }
+        }
+    }
+}
+
+//STRUCT RefCountMut
+/// A guarded wrapper around a mutable reference to the value contained in a [RefCountCell]
+///
+/// [RefCountMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
+/// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value, as well as
+/// [ValueGuardRef<T>] and [ValueGuardMut<T>] so it can be passed to code that accepts any guard
+///
+/// As long as the [RefCountMut] remains in scope, the value in the [RefCountCell] will
+/// remain marked as mutably referenced and unable to be referenced a second time.
+/// You can manually drop the [RefCountMut] out of scope by passing it as the first parameter
+/// to the function [RefCountMut::unguard(ref_count_mut)]
+///
+/// You can obtain a [RefCountMut] by calling `try_borrow_mut()` on a [RefCountCell]
+pub struct RefCountMut<'a, T> {
+    ref_internal: &'a mut RefCountInternal<T>,
 }
 
-//IMPL Drop for PrisonSliceMut
-impl<'a, T> Drop for PrisonSliceMut<'a, T> {
+impl<'a, T> RefCountMut<'a, T> {
+    //FN RefCountMut::unguard()
+    /// Manually end a [RefCountMut] borrow, clearing its mutable reference in the [RefCountCell]
+    pub fn unguard(_guarded_value: RefCountMut<'a, T>) {}
+
+    //FN RefCountMut::downgrade()
+    /// Convert the [RefCountMut] into a [RefCountRef] to the same value without releasing it in between
+    pub fn downgrade(self) -> RefCountRef<'a, T> {
+        let guard = ManuallyDrop::new(self);
+        let ref_internal = unsafe { ptr_read(&guard.ref_internal) };
+        Refs::downgrade(&mut ref_internal.refs);
+        return RefCountRef { ref_internal };
+    }
+}
+
+//IMPL Drop for RefCountMut
+impl<'a, T> Drop for RefCountMut<'a, T> {
     fn drop(&mut self) {
-        _remove_many_mut_refs(&mut self.refs, self.prison_accesses)
+        self.ref_internal.remove_ref_internal();
     }
 }
 
-//IMPL Deref for PrisonSliceMut
-impl<'a, T> Deref for PrisonSliceMut<'a, T> {
-    type Target = [&'a mut T];
+//IMPL Deref for RefCountMut
+impl<'a, T> Deref for RefCountMut<'a, T> {
+    type Target = T;
 
     #[inline(always)]
     fn deref(&self) -> &Self::Target {
-        self.vals.as_slice()
+        &self.ref_internal.val
     }
 }
 
-//IMPL DerefMut for PrisonSliceMut
-impl<'a, T> DerefMut for PrisonSliceMut<'a, T> {
+//IMPL DerefMut for RefCountMut
+impl<'a, T> DerefMut for RefCountMut<'a, T> {
     #[inline(always)]
     fn deref_mut(&mut self) -> &mut Self::Target {
-        self.vals.as_mut_slice()
+        &mut self.ref_internal.val
     }
 }
 
-//IMPL AsRef for PrisonSliceMut
-impl<'a, T> AsRef<[&'a mut T]> for PrisonSliceMut<'a, T> {
+//IMPL AsRef for RefCountMut
+impl<'a, T> AsRef<T> for RefCountMut<'a, T> {
     #[inline(always)]
-    fn as_ref(&self) -> &[&'a mut T] {
-        self.vals.as_slice()
+    fn as_ref(&self) -> &T {
+        &self.ref_internal.val
     }
 }
 
-//IMPL AsMut for PrisonSliceMut
-impl<'a, T> AsMut<[&'a mut T]> for PrisonSliceMut<'a, T> {
+//IMPL AsMut for RefCountMut
+impl<'a, T> AsMut<T> for RefCountMut<'a, T> {
     #[inline(always)]
-    fn as_mut(&mut self) -> &mut [&'a mut T] {
-        self.vals.as_mut_slice()
+    fn as_mut(&mut self) -> &mut T {
+        &mut self.ref_internal.val
     }
 }
 
-//IMPL Borrow for PrisonSliceMut
-impl<'a, T> Borrow<[&'a mut T]> for PrisonSliceMut<'a, T> {
+//IMPL Borrow for RefCountMut
+impl<'a, T> Borrow<T> for RefCountMut<'a, T> {
     #[inline(always)]
-    fn borrow(&self) -> &[&'a mut T] {
-        self.vals.as_slice()
+    fn borrow(&self) -> &T {
+        &self.ref_internal.val
     }
 }
 
-//IMPL BorrowMut for PrisonSliceMut
-impl<'a, T> BorrowMut<[&'a mut T]> for PrisonSliceMut<'a, T> {
+//IMPL BorrowMut for RefCountMut
+impl<'a, T> BorrowMut<T> for RefCountMut<'a, T> {
     #[inline(always)]
-    fn borrow_mut(&mut self) -> &mut [&'a mut T] {
-        self.vals.as_mut_slice()
+    fn borrow_mut(&mut self) -> &mut T {
+        &mut self.ref_internal.val
     }
 }
 
-//STRUCT PrisonSliceRef
-/// Struct representing a slice of immutable references to values that have been allowed to leave the
-/// [Prison] temporarily, but remain guarded by a wrapper to prevent them from leaking or never unlocking
+//IMPL ValueGuardRef for RefCountMut
+impl<'a, T> ValueGuardRef<T> for RefCountMut<'a, T> {}
+
+//IMPL ValueGuardMut for RefCountMut
+impl<'a, T> ValueGuardMut<T> for RefCountMut<'a, T> {}
+
+//STRUCT RefCountRef
+/// A guarded wrapper around an immutable reference to the value contained in a [RefCountCell]
 ///
-/// [PrisonSliceRef<T>] implements [Deref<Target = \[&T\]>](Deref), [AsRef<\[&T\]>](AsRef),
-/// and [Borrow<\[&T\]>](Borrow) to allow transparent access to its underlying slice of values
+/// [RefCountRef<T>] implements [Deref<Target = T>], [AsRef<T>], and [Borrow<T>]
+/// to allow transparent access to its underlying value, as well as [ValueGuardRef<T>] so it can be
+/// passed to code that accepts any guard
 ///
-/// As long as the [PrisonSliceRef] remains in scope, the elements where it's values reside in the
-/// [Prison] will remain marked as immutably referenced and unable to be mutably referenced.
-/// You can manually drop the [PrisonSliceRef] out of scope by passing it as the first parameter
-/// to the function [PrisonSliceRef::unguard(p_sli_ref)]
+/// As long as the [RefCountRef] remains in scope, the value in the [RefCountCell] will
+/// remain marked as immutably referenced and unable to be mutably referenced.
+/// You can manually drop the [RefCountRef] out of scope by passing it as the first parameter
+/// to the function [RefCountRef::unguard(ref_count_ref)]
 ///
-/// You can obtain a [PrisonSliceRef] by calling `guard_many_ref()` or `guard_many_ref_idx()` on a [Prison]
-/// ### Example
-/// ```rust
-/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
-/// # fn main() -> Result<(), AccessError> {
-/// let prison: Prison<u32> = Prison::new();
-/// let key_0 = prison.insert(10)?;
-/// let key_1 = prison.insert(20)?;
-/// let key_2 = prison.insert(30)?;
-/// let mut grd_0_1_2 = prison.guard_many_ref(&[key_0, key_1, key_2])?;
-/// assert_eq!(*grd_0_1_2[1], 20);
-/// prison.visit_ref(key_1, |val_1| {
-///     assert_eq!(*val_1, 20);
-///     Ok(())
-/// });
-/// PrisonSliceRef::unguard(grd_0_1_2);
-/// # Ok(())
-/// # }
-/// ```
-pub struct PrisonSliceRef<'a, T> {
-    prison_accesses: &'a mut usize,
-    refs: Vec<&'a mut usize>,
-    vals: Vec<&'a T>,
+/// You can obtain a [RefCountRef] by calling `try_borrow()` on a [RefCountCell]
+pub struct RefCountRef<'a, T> {
+    ref_internal: &'a mut RefCountInternal<T>,
 }
 
-impl<'a, T> PrisonSliceRef<'a, T> {
-    //FN PrisonSliceRef::unguard()
-    /// Manually end a [PrisonSliceRef] value's temporary guarded absence from the [Prison]
-    ///
-    /// This method simply takes ownership of the [PrisonSliceRef] and immediately lets it go out of scope,
-    /// causing it's `drop()` method to be called and decreasing its immutable reference count in the [Prison]
-    /// ### Example
-    /// ```rust
-    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{Prison, PrisonSliceRef}};
-    /// # fn main() -> Result<(), AccessError> {
-    /// let prison: Prison<u32> = Prison::new();
-    /// prison.insert(10)?;
-    /// prison.insert(20)?;
-    /// let grd_all = prison.guard_many_ref_idx(&[0, 1])?;
-    /// assert!(prison.visit_many_mut_idx(&[0, 1], |ref_all| Ok(())).is_err());
-    /// PrisonSliceRef::unguard(grd_all);
-    /// assert!(prison.visit_many_mut_idx(&[0, 1], |ref_all| Ok(())).is_ok());
-    /// # Ok(())
-    /// # }
-    /// ```
-    pub fn unguard(_prison_sli_ref: Self) {}
+//IMPL UnwindSafe for RefCountRef
+impl<'a, T: RefUnwindSafe> UnwindSafe for RefCountRef<'a, T> {}
+
+impl<'a, T> RefCountRef<'a, T> {
+    //FN RefCountRef::unguard()
+    /// Manually end a [RefCountRef] borrow, decreasing its immutable reference count in the [RefCountCell]
+    pub fn unguard(_guarded_value: RefCountRef<'a, T>) {}
 }
 
-//IMPL Drop for PrisonSliceRef
-impl<'a, T> Drop for PrisonSliceRef<'a, T> {
+//IMPL Drop for RefCountRef
+impl<'a, T> Drop for RefCountRef<'a, T> {
     fn drop(&mut self) {
-        _remove_many_imm_refs(&mut self.refs, self.prison_accesses)
+        self.ref_internal.remove_ref_internal();
     }
 }
 
-//IMPL Deref for PrisonSliceRef
-impl<'a, T> Deref for PrisonSliceRef<'a, T> {
-    type Target = [&'a T];
+//IMPL Deref for RefCountRef
+impl<'a, T> Deref for RefCountRef<'a, T> {
+    type Target = T;
 
     #[inline(always)]
     fn deref(&self) -> &Self::Target {
-        self.vals.as_slice()
+        &self.ref_internal.val
     }
 }
 
-//IMPL AsRef for PrisonSliceRef
-impl<'a, T> AsRef<[&'a T]> for PrisonSliceRef<'a, T> {
+//IMPL AsRef for RefCountRef
+impl<'a, T> AsRef<T> for RefCountRef<'a, T> {
     #[inline(always)]
-    fn as_ref(&self) -> &[&'a T] {
-        self.vals.as_slice()
+    fn as_ref(&self) -> &T {
+        &self.ref_internal.val
     }
 }
 
-//IMPL Borrow for PrisonSliceRef
-impl<'a, T> Borrow<[&'a T]> for PrisonSliceRef<'a, T> {
+//IMPL Borrow for RefCountRef
+impl<'a, T> Borrow<T> for RefCountRef<'a, T> {
     #[inline(always)]
-    fn borrow(&self) -> &[&'a T] {
-        self.vals.as_slice()
+    fn borrow(&self) -> &T {
+        &self.ref_internal.val
     }
 }
 
+//IMPL ValueGuardRef for RefCountRef
+impl<'a, T> ValueGuardRef<T> for RefCountRef<'a, T> {}
+
 //====== JailCell ======
 //STRUCT JailCell
 /// Represents a single standalone value that allows interior mutability while upholding memory safety
@@ -2964,9 +13073,12 @@ impl<'a, T> Borrow<[&'a T]> for PrisonSliceRef<'a, T> {
 /// # }
 /// ```
 pub struct JailCell<T> {
-    internal: UnsafeCell<JailCellMutable<T>>,
+    cell: RefCountCell<T>,
 }
 
+//IMPL RefUnwindSafe for JailCell
+impl<T: RefUnwindSafe> RefUnwindSafe for JailCell<T> {}
+
 impl<T> JailCell<T> {
     //FN JailCell::new()
     /// Creates a new [JailCell] with the supplied value of type `T`
@@ -2975,10 +13087,7 @@ impl<T> JailCell<T> {
     /// through its `visit_*()` or `guard_*()` methods
     pub fn new(value: T) -> JailCell<T> {
         return JailCell {
-            internal: UnsafeCell::new(JailCellMutable {
-                refs: 0,
-                val: value,
-            }),
+            cell: RefCountCell::new(value),
         };
     }
 
@@ -3018,15 +13127,11 @@ impl<T> JailCell<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn visit_mut<F>(&self, mut operation: F) -> Result<(), AccessError>
+    pub fn visit_mut<R, F>(&self, operation: F) -> Result<R, AccessError>
     where
-        F: FnMut(&mut T) -> Result<(), AccessError>,
+        F: FnMut(&mut T) -> Result<R, AccessError>,
     {
-        let internal = internal!(self);
-        internal.add_ref_internal(true)?;
-        let result = operation(&mut internal.val);
-        internal.remove_ref_internal();
-        return result;
+        return self.visit_mut_with(operation);
     }
 
     //FN JailCell::visit_ref()
@@ -3059,15 +13164,45 @@ impl<T> JailCell<T> {
     /// # Ok(())
     /// # }
     /// ```
-    pub fn visit_ref<F>(&self, mut operation: F) -> Result<(), AccessError>
+    pub fn visit_ref<R, F>(&self, operation: F) -> Result<R, AccessError>
     where
-        F: FnMut(&T) -> Result<(), AccessError>,
+        F: FnMut(&T) -> Result<R, AccessError>,
     {
-        let internal = internal!(self);
-        internal.add_ref_internal(false)?;
-        let result = operation(&internal.val);
-        internal.remove_ref_internal();
-        return result;
+        return self.visit_ref_with(operation);
+    }
+
+    //FN JailCell::visit_mut_with()
+    /// Identical to [JailCell::visit_mut()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()] for details
+    /// ## Errors
+    /// - Any error that [JailCell::visit_mut()] could return, converted into `E`
+    /// - Any error returned by the closure
+    pub fn visit_mut_with<R, E, F>(&self, mut operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnMut(&mut T) -> Result<R, E>,
+    {
+        let mut val = self.cell.try_borrow_mut()?;
+        return operation(&mut val);
+    }
+
+    //FN JailCell::visit_ref_with()
+    /// Identical to [JailCell::visit_ref()], but the closure may return any error type `E`
+    /// that an [AccessError] can be converted into
+    ///
+    /// See [Prison::visit_mut_with()] for details
+    /// ## Errors
+    /// - Any error that [JailCell::visit_ref()] could return, converted into `E`
+    /// - Any error returned by the closure
+    pub fn visit_ref_with<R, E, F>(&self, mut operation: F) -> Result<R, E>
+    where
+        E: From<AccessError>,
+        F: FnMut(&T) -> Result<R, E>,
+    {
+        let val = self.cell.try_borrow()?;
+        return operation(&val);
     }
 
     //FN JailCell::guard_mut()
@@ -3075,7 +13210,8 @@ impl<T> JailCell<T> {
     /// in scope and automatically unlocks it when it falls out of scope
     ///
     /// [JailValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
-    /// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value
+    /// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value, as well as
+/// [ValueGuardRef<T>] and [ValueGuardMut<T>] so it can be passed to code that accepts any guard
     ///
     /// You may manually drop the [JailValueMut] out of scope by passing it to the function
     /// [JailValueMut::unguard(_jail_val_mut)]
@@ -3111,10 +13247,8 @@ impl<T> JailCell<T> {
     /// ```
     #[must_use = "guarded reference will immediately fall out of scope"]
     pub fn guard_mut<'a>(&'a self) -> Result<JailValueMut<'a, T>, AccessError> {
-        let internal = internal!(self);
-        internal.add_ref_internal(true)?;
         return Ok(JailValueMut {
-            ref_internal: internal,
+            guard: self.cell.try_borrow_mut()?,
         });
     }
 
@@ -3123,7 +13257,8 @@ impl<T> JailCell<T> {
     /// in scope and automatically unlocks it when it falls out of scope
     ///
     /// [JailValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], and [Borrow<T>]
-    /// to allow transparent access to its underlying value
+    /// to allow transparent access to its underlying value, as well as [ValueGuardRef<T>] so it can be
+/// passed to code that accepts any guard
     ///
     /// You may manually drop the [JailValueRef] out of scope by passing it to the function
     /// [JailValueRef::unguard(_jail_val_ref)]
@@ -3154,10 +13289,8 @@ impl<T> JailCell<T> {
     /// ```
     #[must_use = "guarded reference will immediately fall out of scope"]
     pub fn guard_ref<'a>(&'a self) -> Result<JailValueRef<'a, T>, AccessError> {
-        let internal = internal!(self);
-        internal.add_ref_internal(false)?;
         return Ok(JailValueRef {
-            ref_internal: internal,
+            guard: self.cell.try_borrow()?,
         });
     }
     //FN JailCell::clone_val()
@@ -3183,7 +13316,111 @@ impl<T> JailCell<T> {
     where
         T: Clone,
     {
-        internal!(self).val.clone()
+        let cell = &self.cell;
+        internal!(cell).val.clone()
+    }
+
+    //FN JailCell::update_with()
+    /// Replace the value with the result of `operation(&old_value)`, returning the old value
+    ///
+    /// This performs a short mutable visit internally, so it fails in the same cases as [JailCell::visit_mut()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{JailCell}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let counter: JailCell<u32> = JailCell::new(5);
+    /// assert_eq!(counter.update_with(|count| count + 1)?, 5);
+    /// assert_eq!(counter.clone_val(), 6);
+    /// let grd = counter.guard_ref()?;
+    /// assert!(counter.update_with(|count| count + 1).is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(0)] if value has any number of immutable references
+    pub fn update_with<F>(&self, operation: F) -> Result<T, AccessError>
+    where
+        F: FnOnce(&T) -> T,
+    {
+        let mut val = self.cell.try_borrow_mut()?;
+        let new_val = operation(&val);
+        let old_val = mem_replace(&mut *val, new_val);
+        return Ok(old_val);
+    }
+
+    //FN JailCell::add_assign()
+    /// Add `delta` to the value in place
+    ///
+    /// This performs a short mutable visit internally, so it fails in the same cases as [JailCell::visit_mut()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{JailCell}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let gold: JailCell<i64> = JailCell::new(100);
+    /// gold.add_assign(25)?;
+    /// gold.sub_assign(50)?;
+    /// assert_eq!(gold.clone_val(), 75);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(0)] if value has any number of immutable references
+    pub fn add_assign<D>(&self, delta: D) -> Result<(), AccessError>
+    where
+        T: AddAssign<D>,
+    {
+        let mut val = self.cell.try_borrow_mut()?;
+        *val += delta;
+        return Ok(());
+    }
+
+    //FN JailCell::sub_assign()
+    /// Subtract `delta` from the value in place
+    ///
+    /// This performs a short mutable visit internally, so it fails in the same cases as [JailCell::visit_mut()]
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(0)] if value has any number of immutable references
+    pub fn sub_assign<D>(&self, delta: D) -> Result<(), AccessError>
+    where
+        T: SubAssign<D>,
+    {
+        let mut val = self.cell.try_borrow_mut()?;
+        *val -= delta;
+        return Ok(());
+    }
+
+    //FN JailCell::replace_if()
+    /// Replace the value with `new_value` only if `predicate(&old_value)` returns `true`
+    ///
+    /// Returns [Some(old_value)] if the value was replaced, or [None] if it was not (in which case `new_value` is dropped).
+    /// This performs a short mutable visit internally, so it fails in the same cases as [JailCell::visit_mut()]
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{JailCell}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let high_score: JailCell<u32> = JailCell::new(300);
+    /// assert_eq!(high_score.replace_if(|best| *best < 250, 250)?, None);
+    /// assert_eq!(high_score.replace_if(|best| *best < 420, 420)?, Some(300));
+    /// assert_eq!(high_score.clone_val(), 420);
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// ## Errors
+    /// - [AccessError::ValueAlreadyMutablyReferenced(0)] if value is already mutably referenced
+    /// - [AccessError::ValueStillImmutablyReferenced(0)] if value has any number of immutable references
+    pub fn replace_if<P>(&self, predicate: P, new_value: T) -> Result<Option<T>, AccessError>
+    where
+        P: FnOnce(&T) -> bool,
+    {
+        let mut val = self.cell.try_borrow_mut()?;
+        let old_val = match predicate(&val) {
+            true => Some(mem_replace(&mut *val, new_value)),
+            false => None,
+        };
+        return Ok(old_val);
     }
 
     //FN: JailCell::peek_ref()
@@ -3202,7 +13439,8 @@ impl<T> JailCell<T> {
     /// - The value MUST NOT be mutated by ANY source, including active safe reference-counted mutable references
     /// - NO operation can be performed that could *potentially* cause the underlying memory address of the [JailCell]'s data to relocate
     pub unsafe fn peek_ref<'a>(&'a self) -> &'a T {
-        &internal!(self).val
+        let cell = &self.cell;
+        &internal!(cell).val
     }
 }
 
@@ -3216,40 +13454,75 @@ where
     }
 }
 
-//STRUCT JailCellMutable
-#[doc(hidden)]
-struct JailCellMutable<T> {
-    refs: usize,
-    val: T,
-}
-
-impl<T> JailCellMutable<T> {
-    //FN JailCellMutable::add_ref_internal()
-    fn add_ref_internal(&mut self, mutable: bool) -> Result<(), AccessError> {
-        if self.refs == Refs::MUT {
-            return Err(AccessError::ValueAlreadyMutablyReferenced(0));
-        }
-        if mutable && self.refs > 0 {
-            return Err(AccessError::ValueStillImmutablyReferenced(0));
-        }
-        if self.refs == Refs::MAX_IMMUT {
-            return Err(AccessError::MaximumImmutableReferencesReached(0));
-        }
-        if mutable {
-            self.refs = Refs::MUT;
-        } else {
-            self.refs += 1;
-        }
-        return Ok(());
+//FN visit_both_mut()
+/// Obtain mutable references to the values of two separate [JailCell]'s at the same time,
+/// passing both into a closure you provide
+///
+/// `jail_a` is always acquired before `jail_b`. Because a [JailCell] never waits for a reference
+/// to become available (it returns an error instead), acquiring two cells this way can never deadlock,
+/// and if `jail_b` cannot be referenced the reference to `jail_a` is released before returning.
+///
+/// The index reported in any error is the position of the offending [JailCell] in the argument list:
+/// `0` for `jail_a` and `1` for `jail_b`
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{JailCell, visit_both_mut}};
+/// # fn main() -> Result<(), AccessError> {
+/// let health: JailCell<u32> = JailCell::new(100);
+/// let log: JailCell<Vec<String>> = JailCell::new(Vec::new());
+/// visit_both_mut(&health, &log, |hp, entries| {
+///     *hp -= 10;
+///     entries.push(format!("took 10 damage, {} hp left", hp));
+///     Ok(())
+/// })?;
+/// assert_eq!(health.clone_val(), 90);
+/// # Ok(())
+/// # }
+/// ```
+/// ## Errors
+/// - [AccessError::ValueAlreadyMutablyReferenced(pos)] if either value is already mutably referenced (including when both arguments are the same [JailCell])
+/// - [AccessError::ValueStillImmutablyReferenced(pos)] if either value has any number of immutable references
+/// ### Example
+/// ```rust
+/// # use grit_data_prison::{AccessError, CellKey, single_threaded::{JailCell, visit_both_mut}};
+/// # fn main() -> Result<(), AccessError> {
+/// let jail_a: JailCell<u32> = JailCell::new(1);
+/// let jail_b: JailCell<u32> = JailCell::new(2);
+/// let grd_b = jail_b.guard_ref()?;
+/// let result = visit_both_mut(&jail_a, &jail_b, |a, b| Ok(()));
+/// assert_eq!(result, Err(AccessError::ValueStillImmutablyReferenced(1)));
+/// let result = visit_both_mut(&jail_a, &jail_a, |a, a_again| Ok(()));
+/// assert_eq!(result, Err(AccessError::ValueAlreadyMutablyReferenced(1)));
+/// assert!(jail_a.visit_mut(|a| Ok(())).is_ok());
+/// # Ok(())
+/// # }
+/// ```
+pub fn visit_both_mut<T, U, F>(
+    jail_a: &JailCell<T>,
+    jail_b: &JailCell<U>,
+    operation: F,
+) -> Result<(), AccessError>
+where
+    F: FnOnce(&mut T, &mut U) -> Result<(), AccessError>,
+{
+    if jail_a as *const JailCell<T> as *const u8 == jail_b as *const JailCell<U> as *const u8 {
+        return Err(AccessError::ValueAlreadyMutablyReferenced(1));
     }
+    let mut val_a = jail_a.cell.try_borrow_mut()?;
+    let mut val_b = jail_b.cell.try_borrow_mut().map_err(|acc_err| _jail_err_at(acc_err, 1))?;
+    return operation(&mut val_a, &mut val_b);
+}
 
-    //FN JailCellMutable::remove_ref_internal()
-    fn remove_ref_internal(&mut self) {
-        if self.refs == Refs::MUT {
-            self.refs = 0;
-        } else if self.refs > 0 {
-            self.refs -= 1;
+//FN _jail_err_at()
+#[doc(hidden)]
+fn _jail_err_at(acc_err: AccessError, pos: usize) -> AccessError {
+    match acc_err {
+        AccessError::ValueAlreadyMutablyReferenced(_) => AccessError::ValueAlreadyMutablyReferenced(pos),
+        AccessError::ValueStillImmutablyReferenced(_) => AccessError::ValueStillImmutablyReferenced(pos),
+        AccessError::MaximumImmutableReferencesReached(_) => {
+            AccessError::MaximumImmutableReferencesReached(pos)
         }
+        other => other,
     }
 }
 
@@ -3258,7 +13531,8 @@ impl<T> JailCellMutable<T> {
 /// A guarded wrapper around a mutable reference to the value contained in a [JailCell]
 ///
 /// [JailValueMut<T>] implements [Deref<Target = T>], [DerefMut<Target = T>], [AsRef<T>], [AsMut<T>],
-/// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value
+/// [Borrow<T>], and [BorrowMut<T>] to allow transparent access to its underlying value, as well as
+/// [ValueGuardRef<T>] and [ValueGuardMut<T>] so it can be passed to code that accepts any guard
 ///
 /// As long as the [JailValueMut] remains in scope, the value in [JailCell] will
 /// remain marked as mutably referenced and unable to be referenced a second time.
@@ -3283,7 +13557,7 @@ impl<T> JailCellMutable<T> {
 /// # }
 /// ```
 pub struct JailValueMut<'a, T> {
-    ref_internal: &'a mut JailCellMutable<T>,
+    guard: RefCountMut<'a, T>,
 }
 
 impl<'a, T> JailValueMut<'a, T> {
@@ -3307,12 +13581,29 @@ impl<'a, T> JailValueMut<'a, T> {
     /// # }
     /// ```
     pub fn unguard(_guarded_jail_value: JailValueMut<'a, T>) {}
-}
 
-//IMPL Drop for JailValueMut
-impl<'a, T> Drop for JailValueMut<'a, T> {
-    fn drop(&mut self) {
-        self.ref_internal.remove_ref_internal();
+    //FN JailValueMut::downgrade()
+    /// Convert the [JailValueMut] into a [JailValueRef] to the same value without releasing it in between
+    ///
+    /// See [PrisonValueMut::downgrade()] for details
+    /// ### Example
+    /// ```rust
+    /// # use grit_data_prison::{AccessError, CellKey, single_threaded::{JailCell, JailValueMut}};
+    /// # fn main() -> Result<(), AccessError> {
+    /// let jail: JailCell<u32> = JailCell::new(42);
+    /// let mut grd_mut = jail.guard_mut()?;
+    /// *grd_mut += 1;
+    /// let grd_ref = grd_mut.downgrade();
+    /// assert_eq!(jail.visit_ref(|val| Ok(*val))?, 43);
+    /// assert!(jail.visit_mut(|val| Ok(())).is_err());
+    /// assert_eq!(*grd_ref, 43);
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn downgrade(self) -> JailValueRef<'a, T> {
+        return JailValueRef {
+            guard: self.guard.downgrade(),
+        };
     }
 }
 
@@ -3322,7 +13613,7 @@ impl<'a, T> Deref for JailValueMut<'a, T> {
 
     #[inline(always)]
     fn deref(&self) -> &Self::Target {
-        &self.ref_internal.val
+        &self.guard.ref_internal.val
     }
 }
 
@@ -3330,7 +13621,7 @@ impl<'a, T> Deref for JailValueMut<'a, T> {
 impl<'a, T> DerefMut for JailValueMut<'a, T> {
     #[inline(always)]
     fn deref_mut(&mut self) -> &mut Self::Target {
-        &mut self.ref_internal.val
+        &mut self.guard.ref_internal.val
     }
 }
 
@@ -3338,7 +13629,7 @@ impl<'a, T> DerefMut for JailValueMut<'a, T> {
 impl<'a, T> AsRef<T> for JailValueMut<'a, T> {
     #[inline(always)]
     fn as_ref(&self) -> &T {
-        &self.ref_internal.val
+        &self.guard.ref_internal.val
     }
 }
 
@@ -3346,7 +13637,7 @@ impl<'a, T> AsRef<T> for JailValueMut<'a, T> {
 impl<'a, T> AsMut<T> for JailValueMut<'a, T> {
     #[inline(always)]
     fn as_mut(&mut self) -> &mut T {
-        &mut self.ref_internal.val
+        &mut self.guard.ref_internal.val
     }
 }
 
@@ -3354,7 +13645,7 @@ impl<'a, T> AsMut<T> for JailValueMut<'a, T> {
 impl<'a, T> Borrow<T> for JailValueMut<'a, T> {
     #[inline(always)]
     fn borrow(&self) -> &T {
-        &self.ref_internal.val
+        &self.guard.ref_internal.val
     }
 }
 
@@ -3362,15 +13653,22 @@ impl<'a, T> Borrow<T> for JailValueMut<'a, T> {
 impl<'a, T> BorrowMut<T> for JailValueMut<'a, T> {
     #[inline(always)]
     fn borrow_mut(&mut self) -> &mut T {
-        &mut self.ref_internal.val
+        &mut self.guard.ref_internal.val
     }
 }
 
+//IMPL ValueGuardRef for JailValueMut
+impl<'a, T> ValueGuardRef<T> for JailValueMut<'a, T> {}
+
+//IMPL ValueGuardMut for JailValueMut
+impl<'a, T> ValueGuardMut<T> for JailValueMut<'a, T> {}
+
 //STRUCT JailValueRef
 /// A guarded wrapper around an immutable reference to the value contained in a [JailCell]
 ///
 /// [JailValueRef<T>] implements [Deref<Target = T>], [AsRef<T>], and [Borrow<T>]
-/// to allow transparent access to its underlying value
+/// to allow transparent access to its underlying value, as well as [ValueGuardRef<T>] so it can be
+/// passed to code that accepts any guard
 ///
 /// As long as the [JailValueRef] remains in scope, the value in [JailCell] will
 /// remain marked as immutably referenced and unable to be mutably referenced.
@@ -3394,9 +13692,12 @@ impl<'a, T> BorrowMut<T> for JailValueMut<'a, T> {
 /// # }
 /// ```
 pub struct JailValueRef<'a, T> {
-    ref_internal: &'a mut JailCellMutable<T>,
+    guard: RefCountRef<'a, T>,
 }
 
+//IMPL UnwindSafe for JailValueRef
+impl<'a, T: RefUnwindSafe> UnwindSafe for JailValueRef<'a, T> {}
+
 impl<'a, T> JailValueRef<'a, T> {
     //FN JailValueRef::unguard()
     /// Manually end a [JailValueRef] value's temporary guarded absence from the [JailCell]
@@ -3420,20 +13721,13 @@ impl<'a, T> JailValueRef<'a, T> {
     pub fn unguard(_guarded_jail_value: Self) {}
 }
 
-//IMPL Drop for JailValueRef
-impl<'a, T> Drop for JailValueRef<'a, T> {
-    fn drop(&mut self) {
-        self.ref_internal.remove_ref_internal();
-    }
-}
-
 //IMPL Deref for JailValueRef
 impl<'a, T> Deref for JailValueRef<'a, T> {
     type Target = T;
 
     #[inline(always)]
     fn deref(&self) -> &Self::Target {
-        &self.ref_internal.val
+        &self.guard.ref_internal.val
     }
 }
 
@@ -3441,7 +13735,7 @@ impl<'a, T> Deref for JailValueRef<'a, T> {
 impl<'a, T> AsRef<T> for JailValueRef<'a, T> {
     #[inline(always)]
     fn as_ref(&self) -> &T {
-        &self.ref_internal.val
+        &self.guard.ref_internal.val
     }
 }
 
@@ -3449,7 +13743,10 @@ impl<'a, T> AsRef<T> for JailValueRef<'a, T> {
 impl<'a, T> Borrow<T> for JailValueRef<'a, T> {
     #[inline(always)]
     fn borrow(&self) -> &T {
-        &self.ref_internal.val
+        &self.guard.ref_internal.val
     }
 }
 
+//IMPL ValueGuardRef for JailValueRef
+impl<'a, T> ValueGuardRef<T> for JailValueRef<'a, T> {}
+
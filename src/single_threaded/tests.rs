@@ -2,23 +2,29 @@
 #![allow(unused_variables)]
 #![allow(unused_assignments)]
 //====== Testing ======
-use std::{fmt::Display, mem};
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    fmt::Display,
+    mem,
+};
 
 use super::*;
+use crate::{SelfKeyed, INLINE_CAP, MAX_SPARE_BUFS};
 
 //MACRO assert_prison_state!
 /// prison, access_count, gen, next_free, free_count, vec_len
 macro_rules! assert_prison_state {
     ($P:ident, $A_CNT:expr, $GEN:expr, $NEXT:expr, $F_CNT:expr, $LEN:expr) => {
         let p = &internal!($P);
-        if p.access_count != $A_CNT
+        if p.access_count.count != $A_CNT
             || p.generation != $GEN
             || p.next_free != $NEXT
             || p.free_count != $F_CNT
             || p.vec.len() != $LEN {
                 panic!("\nIncorrect prison state:\n\tEXP:\taccess_count: {}, gen: {}, next_free: {}, free_count: {}, vec_len: {}\n\tGOT:\taccess_count: {}, gen: {}, next_free: {}, free_count: {}, vec_len: {}\n",
                 $A_CNT, $GEN, $NEXT, $F_CNT, $LEN,
-                p.access_count, p.generation, p.next_free, p.free_count, p.vec.len());
+                p.access_count.count, p.generation, p.next_free, p.free_count, p.vec.len());
             }
     };
 }
@@ -51,7 +57,8 @@ macro_rules! assert_free_state {
 /// jail, refs, val
 macro_rules! assert_jail_state {
     ($J:ident, $REFS:expr, $VAL:expr) => {
-        match &internal!($J) {
+        let cell = &$J.cell;
+        match &internal!(cell) {
             jail if (jail.refs == $REFS && jail.val == $VAL) => {},
             jail => panic!("\nJailCell unexpected state:\n\tEXP:\trefs = {}, val = {}\n\tGOT:\trefs = {}, val = {}\n", $REFS, $VAL, jail.refs, jail.val),
         }
@@ -98,6 +105,38 @@ impl Display for MyNoCopy {
     }
 }
 
+//STRUCT CountingAlloc
+/// Global allocator of the test binary that counts the allocations made by each thread,
+/// so a test can check that an operation never touches the heap
+struct CountingAlloc;
+
+thread_local! {
+    static THREAD_ALLOCS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = THREAD_ALLOCS.try_with(|allocs| allocs.set(allocs.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let _ = THREAD_ALLOCS.try_with(|allocs| allocs.set(allocs.get() + 1));
+        System.realloc(ptr, layout, new_size)
+    }
+}
+
+#[global_allocator]
+static COUNTING_ALLOC: CountingAlloc = CountingAlloc;
+
+fn thread_allocs() -> usize {
+    THREAD_ALLOCS.with(Cell::get)
+}
+
 // impl MyNoCopy {
 //     fn val(&self) -> usize {
 //         self.0
@@ -109,8 +148,18 @@ impl Display for MyNoCopy {
 // }
 
 //TEST memory footprint
+/// Checks the sizes documented in the crate-level docs, which describe the layout without optional features
 #[test]
-#[ignore]
+#[cfg(not(any(
+    feature = "profile",
+    feature = "lru",
+    feature = "ttl",
+    feature = "uuid",
+    feature = "dirty",
+    feature = "transform",
+    feature = "recycle_stats",
+    feature = "offload"
+)))]
 fn memory_footprint() -> Result<(), AccessError> {
     // Prison
     assert_eq!(mem::size_of::<PrisonCell<()>>(), 16);
@@ -122,7 +171,16 @@ fn memory_footprint() -> Result<(), AccessError> {
     );
     assert_eq!(mem::size_of::<PrisonCell<u128>>(), 32);
     let vec_size = mem::size_of::<Vec<u8>>();
-    assert_eq!(mem::size_of::<Prison<u8>>(), 32 + vec_size);
+    // The baseline is the access count, generation, free count, next free index and cell `Vec` (`32 + vec_size`).
+    // Everything else that stays inline is read or written on every access, so moving it into `PrisonExtras`
+    // would add a pointer check to the hot path:
+    // - the `Vec` of wakers is checked by every release, so that a waiting future is only woken when its value
+    //   is fully released
+    // - the pointer to `PrisonExtras` is checked by every release for pending removals
+    // - the visit depth, maximum depth and depth limit are updated and checked by every visit
+    assert_eq!(mem::size_of::<Prison<u8>>(), 32 + 8 + 24 + vec_size * 2);
+    assert_eq!(mem::size_of::<Prison<u128>>(), mem::size_of::<Prison<u8>>());
+    assert_eq!(mem::size_of::<PrisonExtras<u8>>(), 64 + vec_size * 6);
     // JailCell
     assert_eq!(mem::size_of::<JailCell<()>>(), 8);
     assert_eq!(mem::size_of::<JailCell<u8>>(), 16);
@@ -131,7 +189,8 @@ fn memory_footprint() -> Result<(), AccessError> {
         mem::size_of::<JailCell<(u8, u8, u8, u8, u8, u8, u8, u8, u8)>>(),
         24
     );
-    assert_eq!(mem::size_of::<JailCell<u128>>(), 24);
+    // `u128` is 16-byte aligned on x86_64 since Rust 1.77
+    assert_eq!(mem::size_of::<JailCell<u128>>(), 32);
     Ok(())
 }
 
@@ -144,6 +203,33 @@ fn memory_footprint() -> Result<(), AccessError> {
 //TODO: TEST Prison::num_used()
 //TODO: TEST Prison::density()
 
+//TEST Prison::first_free_idx() and Prison::next_free_after()
+#[test]
+fn prison_free_idx_queries() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(10);
+    assert_eq!(prison.first_free_idx(), None);
+    let keys = prison.insert_many((0..6).map(MyNoCopy))?;
+    assert_eq!(prison.first_free_idx(), None);
+    prison.remove(keys[4])?;
+    prison.remove(keys[1])?;
+    prison.remove(keys[2])?;
+    assert_eq!(prison.first_free_idx(), Some(1));
+    assert_eq!(prison.next_free_after(1), Some(2));
+    assert_eq!(prison.next_free_after(2), Some(4));
+    assert_eq!(prison.next_free_after(4), None);
+    assert_eq!(prison.next_free_after(usize::MAX), None);
+    prison.reserve_index_range(6..8)?;
+    assert_eq!(prison.next_free_after(4), Some(6));
+    assert_eq!(prison.insert_at(prison.next_free_after(6).unwrap(), MyNoCopy(7))?.idx(), 7);
+    prison.insert_at(1, MyNoCopy(1))?;
+    assert_eq!(prison.first_free_idx(), Some(2));
+    prison.set_idx_validation(true);
+    let salted = prison.first_free_idx().unwrap();
+    assert_eq!(salted, prison.salt_idx(2));
+    assert_eq!(prison.next_free_after(salted), Some(prison.salt_idx(4)));
+    Ok(())
+}
+
 //TEST Prison::insert()
 #[test]
 fn prison_insert() -> Result<(), AccessError> {
@@ -345,6 +431,94 @@ fn prison_remove() -> Result<(), AccessError> {
     Ok(())
 }
 
+//TEST Prison::remove_when_free()
+#[test]
+fn prison_remove_when_free() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(4);
+    let key_0 = prison.insert(10)?;
+    let key_1 = prison.insert(11)?;
+    let key_2 = prison.insert(12)?;
+    let key_3 = prison.insert(13)?;
+    let ticket_0 = prison.remove_when_free(key_0)?;
+    assert!(!prison.removal_pending(ticket_0));
+    assert_eq!(ticket_0.key(), key_0);
+    assert_free_state!(prison, 0, IdxD::INVALID, IdxD::INVALID);
+    prison.visit_mut(key_1, |_| {
+        let ticket_1 = prison.remove_when_free(key_1)?;
+        assert_eq!(prison.remove_when_free(key_1)?, ticket_1);
+        assert!(prison.removal_pending(ticket_1));
+        assert_eq!(prison.clone_val(key_2)?, 12);
+        assert!(prison.removal_pending(ticket_1));
+        Ok(())
+    })?;
+    assert_access_err!(prison.clone_val(key_1), AccessError::ValueDeleted(1, 0));
+    assert_prison_state!(prison, 0, 1, 1, 2, 4);
+    let grd_2_a = prison.guard_ref(key_2)?;
+    let grd_2_b = prison.guard_ref(key_2)?;
+    let ticket_2 = prison.remove_when_free(key_2)?;
+    drop(grd_2_a);
+    assert!(prison.removal_pending(ticket_2));
+    assert!(prison.cancel_removal(ticket_2));
+    assert!(!prison.cancel_removal(ticket_2));
+    drop(grd_2_b);
+    assert_cell_state!(prison, 2, 0, 0, 12);
+    let grd_3 = prison.guard_mut(key_3)?;
+    let ticket_3 = prison.remove_when_free(key_3)?;
+    drop(grd_3);
+    assert!(!prison.removal_pending(ticket_3));
+    assert_eq!(prison.num_used(), 1);
+    prison.pin(key_2)?;
+    assert_access_err!(prison.remove_when_free(key_2), AccessError::ValuePinned(2));
+    prison.unpin(key_2)?;
+    let grd_2 = prison.guard_ref(key_2)?;
+    let ticket_2 = prison.remove_when_free(key_2)?;
+    prison.pin(key_2)?;
+    drop(grd_2);
+    assert!(prison.removal_pending(ticket_2));
+    assert_cell_state!(prison, 2, 0, 0, 12);
+    prison.visit_ref(key_2, |_| Ok(()))?;
+    assert!(prison.removal_pending(ticket_2));
+    prison.unpin(key_2)?;
+    assert!(!prison.removal_pending(ticket_2));
+    assert_eq!(prison.num_used(), 0);
+    Ok(())
+}
+
+//TEST Prison::remove_when_free() release paths
+#[test]
+fn prison_remove_when_free_release_paths() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(4);
+    let key_0 = prison.insert(10)?;
+    let key_1 = prison.insert(11)?;
+    let key_2 = prison.insert(12)?;
+    let key_3 = prison.insert(13)?;
+    let pins = prison.pin_refs(&[key_0])?;
+    let ticket_0 = prison.remove_when_free(key_0)?;
+    drop(pins);
+    assert!(!prison.removal_pending(ticket_0));
+    let snapshot = prison.snapshot_ref()?;
+    let ticket_1 = prison.remove_when_free(key_1)?;
+    drop(snapshot);
+    assert!(!prison.removal_pending(ticket_1));
+    let result = prison.with_guards(|ctx| {
+        assert_eq!(*ctx.imm_ref(key_2)?, 12);
+        let ticket_2 = prison.remove_when_free(key_2)?;
+        assert!(prison.removal_pending(ticket_2));
+        ctx.release(key_2);
+        assert!(!prison.removal_pending(ticket_2));
+        Ok(())
+    });
+    assert!(result.is_ok());
+    let grds = prison.guard_many_ref(&[key_3])?;
+    let ticket_3 = prison.remove_when_free(key_3)?;
+    assert!(prison.removal_pending(ticket_3));
+    drop(grds);
+    assert!(!prison.removal_pending(ticket_3));
+    assert_eq!(prison.num_used(), 0);
+    assert_prison_state!(prison, 0, 1, 3, 4, 4);
+    Ok(())
+}
+
 //TEST Prison::remove_idx()
 #[test]
 fn prison_remove_idx() -> Result<(), AccessError> {
@@ -521,6 +695,7 @@ fn prison_visit_ref() -> Result<(), AccessError> {
         prison.visit_ref(key_1, |_| Ok(())),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
     Ok(())
 }
 
@@ -646,6 +821,7 @@ fn prison_visit_ref_idx() -> Result<(), AccessError> {
         prison.visit_ref_idx(1, |_| Ok(())),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
     Ok(())
 }
 
@@ -795,6 +971,7 @@ fn prison_visit_many_ref() -> Result<(), AccessError> {
         prison.visit_many_ref(&[key_1], |_| Ok(())),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
     Ok(())
 }
 
@@ -944,6 +1121,7 @@ fn prison_visit_many_ref_idx() -> Result<(), AccessError> {
         prison.visit_many_ref_idx(&[1], |_| Ok(())),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
     Ok(())
 }
 
@@ -1093,6 +1271,7 @@ fn prison_visit_slice_ref() -> Result<(), AccessError> {
         prison.visit_slice_ref(1..2, |_| Ok(())),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
     Ok(())
 }
 
@@ -1205,6 +1384,76 @@ fn prison_guard_ref() -> Result<(), AccessError> {
         prison.guard_ref(key_1),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
+    Ok(())
+}
+
+//TEST PrisonValueMut::downgrade() / JailValueMut::downgrade()
+#[test]
+fn guard_mut_downgrade() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let mut grd_0 = prison.guard_mut(key_0)?;
+    grd_0.0 = 10;
+    assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 2);
+    assert_cell_state!(prison, 0, Refs::MUT, 0, MyNoCopy(10));
+    let grd_0 = grd_0.downgrade();
+    assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 2);
+    assert_cell_state!(prison, 0, 1, 0, MyNoCopy(10));
+    let grd_0_b = prison.guard_ref(key_0)?;
+    assert_cell_state!(prison, 0, 2, 0, MyNoCopy(10));
+    assert_access_err!(prison.visit_mut(key_0, |_| Ok(())), AccessError::ValueStillImmutablyReferenced(0));
+    assert_access_err!(prison.remove(key_0), AccessError::RemoveWhileValueReferenced(0));
+    drop(grd_0);
+    assert_cell_state!(prison, 0, 1, 0, MyNoCopy(10));
+    drop(grd_0_b);
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 2);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(10));
+    let grd_1 = prison.guard_mut(key_1)?.downgrade();
+    assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 2);
+    PrisonValueRef::unguard(grd_1);
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 2);
+    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
+    let mut grd_mut = jail.guard_mut()?;
+    grd_mut.0 = 43;
+    assert_jail_state!(jail, Refs::MUT, MyNoCopy(43));
+    let grd_ref = grd_mut.downgrade();
+    assert_jail_state!(jail, 1, MyNoCopy(43));
+    assert!(jail.visit_ref(|val| Ok(val.0 == 43))?);
+    assert!(jail.visit_mut(|_| Ok(())).is_err());
+    drop(grd_ref);
+    assert_jail_state!(jail, 0, MyNoCopy(43));
+    Ok(())
+}
+
+//TEST PrisonValueRef::try_upgrade()
+#[test]
+fn guard_ref_try_upgrade() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let grd_0 = prison.guard_ref(key_0)?;
+    let grd_0_b = prison.guard_ref_idx(0)?;
+    assert_cell_state!(prison, 0, 2, 0, MyNoCopy(0));
+    let (grd_0, acc_err) = match grd_0.try_upgrade() {
+        Ok(_) => panic!("upgraded a shared value"),
+        Err(failed) => failed,
+    };
+    assert_eq!(acc_err, AccessError::ValueStillImmutablyReferenced(0));
+    assert_cell_state!(prison, 0, 2, 0, MyNoCopy(0));
+    drop(grd_0_b);
+    let mut grd_0 = grd_0.try_upgrade().map_err(|(_, acc_err)| acc_err)?;
+    grd_0.0 = 10;
+    assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 1);
+    assert_cell_state!(prison, 0, Refs::MUT, 0, MyNoCopy(10));
+    assert_access_err!(prison.guard_ref(key_0), AccessError::ValueAlreadyMutablyReferenced(0));
+    let grd_0 = grd_0.downgrade().try_upgrade().map_err(|(_, acc_err)| acc_err)?;
+    PrisonValueMut::unguard(grd_0);
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 1);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(10));
+    prison.seal_read_only()?;
+    let grd_0 = prison.guard_ref(key_0)?;
+    assert!(matches!(grd_0.try_upgrade(), Err((_, AccessError::PrisonSealed))));
     Ok(())
 }
 
@@ -1314,6 +1563,7 @@ fn prison_guard_ref_idx() -> Result<(), AccessError> {
         prison.guard_ref_idx(1),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
     Ok(())
 }
 
@@ -1459,6 +1709,7 @@ fn prison_guard_many_ref() -> Result<(), AccessError> {
         prison.guard_many_ref(&[key_1]),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
     Ok(())
 }
 
@@ -1604,6 +1855,7 @@ fn prison_guard_many_ref_idx() -> Result<(), AccessError> {
         prison.guard_many_ref_idx(&[1]),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
     Ok(())
 }
 
@@ -1746,6 +1998,111 @@ fn prison_guard_slice_ref() -> Result<(), AccessError> {
         prison.guard_slice_ref(1..2),
         AccessError::MaximumImmutableReferencesReached(1)
     );
+    internal!(prison).vec[1].refs_or_next = 0;
+    Ok(())
+}
+
+//TEST Prison::pin_refs()
+#[test]
+fn prison_pin_refs() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let key_3 = prison.insert(MyNoCopy(3))?;
+    prison.remove(key_3)?;
+    assert_access_err!(
+        prison.pin_refs(&[key_0, key_3]),
+        AccessError::ValueDeleted(3, 0)
+    );
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(0));
+    let key_3_b = prison.insert(MyNoCopy(33))?;
+    let pinned = prison.pin_refs(&[key_3_b, key_0, key_2, key_0])?;
+    assert_eq!(pinned.len(), 4);
+    assert!(!pinned.is_empty());
+    assert_cell_state!(prison, 0, 2, 0, MyNoCopy(0));
+    assert_cell_state!(prison, 1, 0, 0, MyNoCopy(1));
+    assert_cell_state!(prison, 2, 1, 0, MyNoCopy(2));
+    assert_cell_state!(prison, 3, 1, 1, MyNoCopy(33));
+    assert_prison_state!(prison, 3, 1, IdxD::INVALID, 0, 4);
+    assert_eq!(pinned.get(key_0), Some(&MyNoCopy(0)));
+    assert_eq!(pinned.get(key_2), Some(&MyNoCopy(2)));
+    assert_eq!(pinned.get(key_3_b), Some(&MyNoCopy(33)));
+    assert_eq!(pinned.get(key_3), None);
+    assert_eq!(pinned.get(key_1), None);
+    assert_eq!(pinned.get_idx(3), Some(&MyNoCopy(33)));
+    assert_eq!(pinned.get_idx(1), None);
+    assert!(pinned.contains(key_2));
+    assert!(!pinned.contains(key_1));
+    assert_access_err!(
+        prison.visit_mut(key_2, |_| Ok(())),
+        AccessError::ValueStillImmutablyReferenced(2)
+    );
+    prison.visit_mut(key_1, |_| Ok(()))?;
+    PinSet::unguard(pinned);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(0));
+    assert_cell_state!(prison, 2, 0, 0, MyNoCopy(2));
+    assert_cell_state!(prison, 3, 0, 1, MyNoCopy(33));
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 4);
+    Ok(())
+}
+
+//TEST Prison::with_guards()
+#[test]
+fn prison_with_guards() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    prison.with_guards(|ctx| {
+        let val_0 = ctx.mut_ref(key_0)?;
+        let val_1_a = ctx.imm_ref(key_1)?;
+        let val_1_b = ctx.imm_ref_idx(1)?;
+        assert_eq!(*val_1_a, *val_1_b);
+        val_0.0 = 10;
+        assert_cell_state!(prison, 0, Refs::MUT, 0, MyNoCopy(10));
+        assert_cell_state!(prison, 1, 2, 0, MyNoCopy(1));
+        assert_prison_state!(prison, 2, 0, IdxD::INVALID, 0, 3);
+        assert_access_err!(
+            ctx.imm_ref(key_0),
+            AccessError::ValueAlreadyMutablyReferenced(0)
+        );
+        assert_access_err!(
+            ctx.mut_ref_idx(1),
+            AccessError::ValueStillImmutablyReferenced(1)
+        );
+        assert_eq!(ctx.num_held(), 3);
+        assert!(ctx.release(key_1));
+        assert_cell_state!(prison, 1, 1, 0, MyNoCopy(1));
+        assert!(ctx.release_idx(1));
+        assert!(!ctx.release_idx(1));
+        assert!(!ctx.release(key_2));
+        assert_cell_state!(prison, 1, 0, 0, MyNoCopy(1));
+        assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 3);
+        ctx.mut_ref(key_2)?;
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(10));
+    assert_cell_state!(prison, 2, 0, 0, MyNoCopy(2));
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 3);
+    assert_access_err!(
+        prison.with_guards(|ctx| {
+            ctx.mut_ref(key_0)?;
+            ctx.mut_ref(key_0)?;
+            Ok(())
+        }),
+        AccessError::ValueAlreadyMutablyReferenced(0)
+    );
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 3);
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let _ = prison.with_guards(|ctx| {
+            ctx.mut_ref(key_0)?;
+            panic!("closure panicked while holding a reference");
+        });
+    }));
+    assert!(panicked.is_err());
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(10));
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 3);
     Ok(())
 }
 
@@ -1833,12 +2190,12 @@ fn prison_clone_many_vals() -> Result<(), AccessError> {
         Ok(())
     })?;
     let sentence_1 = words_1.iter().fold(String::new(), |mut sentence, word| {
-        sentence.push_str(&word);
+        sentence.push_str(word);
         sentence.push(' ');
         sentence
     });
     let sentence_2 = words_2.iter().fold(String::new(), |mut sentence, word| {
-        sentence.push_str(&word);
+        sentence.push_str(word);
         sentence.push(' ');
         sentence
     });
@@ -1884,12 +2241,12 @@ fn prison_clone_many_vals_idx() -> Result<(), AccessError> {
         Ok(())
     })?;
     let sentence_1 = words_1.iter().fold(String::new(), |mut sentence, word| {
-        sentence.push_str(&word);
+        sentence.push_str(word);
         sentence.push(' ');
         sentence
     });
     let sentence_2 = words_2.iter().fold(String::new(), |mut sentence, word| {
-        sentence.push_str(&word);
+        sentence.push_str(word);
         sentence.push(' ');
         sentence
     });
@@ -1948,159 +2305,3838 @@ fn prison_peek_ref_idx() -> Result<(), AccessError> {
     Ok(())
 }
 
-//------ JailCell Tests ------
-//TODO: TEST JailCell::new()
-
-//TEST JailCell::visit_mut()
+//TEST Prison::map_into()
 #[test]
-fn jail_visit_mut() -> Result<(), AccessError> {
-    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
-    jail.visit_mut(|val| {
-        assert_jail_state!(jail, Refs::MUT, MyNoCopy(42));
-        assert_eq!(*val, MyNoCopy(42));
-        *val = MyNoCopy(69);
-        assert_eq!(*val, MyNoCopy(69));
-        assert_jail_state!(jail, Refs::MUT, MyNoCopy(69));
-        assert_access_err!(
-            jail.visit_mut(|_| Ok(())),
-            AccessError::ValueAlreadyMutablyReferenced(0)
-        );
-        Ok(())
-    })?;
-    assert_jail_state!(jail, 0, MyNoCopy(69));
-    jail.visit_ref(|val| {
-        assert_access_err!(
-            jail.visit_mut(|_| Ok(())),
-            AccessError::ValueStillImmutablyReferenced(0)
-        );
-        Ok(())
-    })?;
+fn prison_map_into() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let key_3 = prison.insert(MyNoCopy(3))?;
+    prison.remove(key_1)?;
+    prison.remove(key_3)?;
+    let key_3_b = prison.insert(MyNoCopy(33))?;
+    prison.remove(key_0)?;
+    assert_prison_state!(prison, 0, 1, 0, 2, 4);
+    let mut visited: Vec<CellKey> = Vec::new();
+    let new_prison: Prison<String> = prison.map_into(|key, val| {
+        visited.push(key);
+        format!("{}", val.0 * 10)
+    });
+    assert_eq!(visited, vec![key_2, key_3_b]);
+    assert_prison_state!(new_prison, 0, 1, 0, 2, 4);
+    assert_free_state!(new_prison, 0, IdxD::INVALID, 1);
+    assert_free_state!(new_prison, 1, 0, IdxD::INVALID);
+    assert_cell_state!(new_prison, 2, 0, 0, String::from("20"));
+    assert_cell_state!(new_prison, 3, 0, 1, String::from("330"));
+    assert_access_err!(new_prison.clone_val(key_3), AccessError::ValueDeleted(3, 0));
+    assert_eq!(new_prison.clone_val(key_3_b)?, String::from("330"));
+    let key_0_b = assert_cell_key!(new_prison.insert(String::from("new")), 0, 1);
+    let key_1_b = assert_cell_key!(new_prison.insert(String::from("newer")), 1, 1);
+    new_prison.remove(key_2)?;
+    let key_hidden = new_prison.insert_hidden(String::from("hidden"))?;
+    new_prison.watch_key(key_2);
+    new_prison.set_depth_limit(Some(3));
+    let lens: Prison<usize> = new_prison.map_into(|_, val| val.len());
+    assert!(lens.is_hidden(key_hidden));
+    assert_access_err!(lens.clone_val(key_hidden), AccessError::ValueHidden(key_hidden.idx()));
+    assert_eq!(lens.drain_invalidated(), vec![(key_2, KeyStatus::Replaced { current_gen: key_hidden.gen })]);
+    assert_eq!(lens.depth_limit(), Some(3));
+    assert_eq!(lens.clone_val(key_1_b)?, 5);
     Ok(())
 }
 
-//TEST JailCell::visit_ref()
+//TEST Prison::map()
 #[test]
-fn jail_visit_ref() -> Result<(), AccessError> {
-    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
-    jail.visit_ref(|val| {
-        assert_jail_state!(jail, 1, MyNoCopy(42));
-        assert_eq!(*val, MyNoCopy(42));
-        jail.visit_ref(|val_b| {
-            assert_jail_state!(jail, 2, MyNoCopy(42));
-            assert_eq!(*val_b, MyNoCopy(42));
-            Ok(())
-        })?;
-        assert_jail_state!(jail, 1, MyNoCopy(42));
-        Ok(())
-    })?;
-    assert_jail_state!(jail, 0, MyNoCopy(42));
-    jail.visit_mut(|val| {
-        assert_access_err!(
-            jail.visit_ref(|_| Ok(())),
-            AccessError::ValueAlreadyMutablyReferenced(0)
-        );
-        Ok(())
-    })?;
-    internal!(jail).refs = Refs::MAX_IMMUT;
-    assert_access_err!(
-        jail.visit_ref(|_| Ok(())),
-        AccessError::MaximumImmutableReferencesReached(0)
-    );
+fn prison_map() -> Result<(), AccessError> {
+    let raw: Prison<&str> = Prison::with_capacity(4);
+    let key_0 = raw.insert("10")?;
+    let key_1 = raw.insert("x")?;
+    let key_2 = raw.insert("30")?;
+    raw.remove(key_1)?;
+    let key_1_b = raw.insert("20")?;
+    raw.remove(key_0)?;
+    let mut visited = Vec::new();
+    let parsed: Prison<u32> = raw.map(|key, text| {
+        visited.push(key);
+        text.parse().unwrap()
+    });
+    assert_eq!(visited, vec![key_1_b, key_2]);
+    assert_prison_state!(parsed, 0, 1, 0, 1, 3);
+    assert_free_state!(parsed, 0, IdxD::INVALID, IdxD::INVALID);
+    assert_cell_state!(parsed, 1, 0, 1, 20);
+    assert_cell_state!(parsed, 2, 0, 0, 30);
+    assert_access_err!(parsed.clone_val(key_0), AccessError::ValueDeleted(0, 0));
+    assert_access_err!(parsed.clone_val(key_1), AccessError::ValueDeleted(1, 0));
+    assert_eq!(parsed.clone_val(key_1_b)?, 20);
+    assert_cell_key!(parsed.insert(40), 0, 1);
     Ok(())
 }
 
-//TEST JailCell::guard_mut()
+//TEST Prison::with_placeholder()
 #[test]
-fn jail_guard_mut() -> Result<(), AccessError> {
-    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
+fn prison_with_placeholder() -> Result<(), AccessError> {
+    thread_local! {
+        static LIVE: std::cell::Cell<isize> = const { std::cell::Cell::new(0) };
+    }
+    struct Counted(usize);
+    impl Counted {
+        fn new(val: usize) -> Self {
+            LIVE.with(|live| live.set(live.get() + 1));
+            Counted(val)
+        }
+    }
+    impl Drop for Counted {
+        fn drop(&mut self) {
+            LIVE.with(|live| live.set(live.get() - 1));
+        }
+    }
+    let live = || LIVE.with(|live| live.get());
     {
-        let mut val = jail.guard_mut()?;
-        assert_jail_state!(jail, Refs::MUT, MyNoCopy(42));
-        assert_eq!(*val, MyNoCopy(42));
-        *val = MyNoCopy(69);
-        assert_eq!(*val, MyNoCopy(69));
-        assert_jail_state!(jail, Refs::MUT, MyNoCopy(69));
+        let prison: Prison<Counted> = Prison::with_capacity_and_placeholder(5, || Counted::new(999));
+        let key_0 = prison.insert(Counted::new(0))?;
+        let key_1 = prison.insert(Counted::new(1))?;
+        let key_2 = prison.insert(Counted::new(2))?;
+        assert_eq!(live(), 3);
+        let removed = prison.remove(key_1)?;
+        assert_eq!(removed.0, 1);
+        assert_eq!(live(), 4);
+        drop(removed);
+        assert_eq!(live(), 3);
+        assert_prison_state!(prison, 0, 1, 1, 1, 3);
+        assert_free_state!(prison, 1, IdxD::INVALID, IdxD::INVALID);
+        assert_access_err!(prison.visit_ref(key_1, |_| Ok(())), AccessError::ValueDeleted(1, 0));
+        assert_access_err!(prison.visit_ref_idx(1, |_| Ok(())), AccessError::ValueDeleted(1, 0));
+        assert_eq!(unsafe { prison.peek_ref_or_placeholder_idx(1)? }.0, 999);
+        assert_eq!(unsafe { prison.peek_ref_or_placeholder_idx(2)? }.0, 2);
         assert_access_err!(
-            jail.guard_mut(),
-            AccessError::ValueAlreadyMutablyReferenced(0)
+            unsafe { prison.peek_ref_or_placeholder_idx(3) },
+            AccessError::IndexOutOfRange(3)
         );
-    }
-    assert_jail_state!(jail, 0, MyNoCopy(69));
-    let mut val = jail.guard_mut()?;
-    assert_jail_state!(jail, Refs::MUT, MyNoCopy(69));
-    *val = MyNoCopy(42);
-    JailValueMut::unguard(val);
-    assert_jail_state!(jail, 0, MyNoCopy(42));
-    jail.visit_ref(|val| {
+        prison.insert(Counted::new(11))?;
+        assert_eq!(live(), 3);
+        prison.remove_idx(2)?;
+        assert_eq!(live(), 3);
+        prison.insert_at(2, Counted::new(22))?;
+        assert_eq!(live(), 3);
+        prison.remove(key_0)?;
+        prison.overwrite(0, Counted::new(100))?;
+        assert_eq!(live(), 3);
+        prison.remove_idx(0)?;
+        assert_eq!(live(), 3);
+        let mapped: Prison<usize> = prison.map_into(|_, val| val.0);
+        assert_eq!(live(), 0);
+        assert_eq!(mapped.clone_val_idx(2)?, 22);
         assert_access_err!(
-            jail.guard_mut(),
-            AccessError::ValueStillImmutablyReferenced(0)
+            unsafe { mapped.peek_ref_or_placeholder_idx(0) },
+            AccessError::ValueDeleted(0, 0)
         );
-        Ok(())
-    })?;
+    }
+    {
+        let prison: Prison<Counted> = Prison::with_placeholder(|| Counted::new(999));
+        prison.insert(Counted::new(0))?;
+        prison.insert(Counted::new(1))?;
+        prison.remove_idx(0)?;
+        assert_eq!(live(), 2);
+        prison.insert(Counted::new(2))?;
+        prison.insert(Counted::new(3))?;
+        prison.remove_idx(2)?;
+        assert_eq!(live(), 3);
+        prison.truncate(1)?;
+        assert_eq!(live(), 1);
+    }
+    assert_eq!(live(), 0);
     Ok(())
 }
 
-//TEST JailCell::guard_ref()
+//TEST SnapshotRef::raw_cells() / Prison::overwrite_raw()
+#[cfg(feature = "raw")]
 #[test]
-fn jail_guard_ref() -> Result<(), AccessError> {
-    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
-    {
-        let val = jail.guard_ref()?;
-        assert_jail_state!(jail, 1, MyNoCopy(42));
-        assert_eq!(*val, MyNoCopy(42));
-        let val_b = jail.guard_ref()?;
-        assert_jail_state!(jail, 2, MyNoCopy(42));
-        assert_eq!(*val_b, MyNoCopy(42));
-        JailValueRef::unguard(val_b);
-        assert_jail_state!(jail, 1, MyNoCopy(42));
+fn prison_raw_byte_view() -> Result<(), AccessError> {
+    let prison: Prison<[u16; 2]> = Prison::new();
+    let key_0 = prison.insert([1, 2])?;
+    let key_1 = prison.insert([3, 4])?;
+    let key_2 = prison.insert([5, 6])?;
+    prison.remove(key_1)?;
+    assert_eq!(prison.raw_size(), 4);
+    let snapshot = prison.snapshot_ref()?;
+    let raw: Vec<(CellKey, Vec<u8>)> = snapshot.raw_cells().map(|(key, bytes)| (key, bytes.to_vec())).collect();
+    assert_eq!(raw.len(), 2);
+    assert_eq!(raw[0].0, key_0);
+    assert_eq!(raw[1], (key_2, [5u16.to_ne_bytes(), 6u16.to_ne_bytes()].concat()));
+    assert_access_err!(prison.overwrite_raw(key_0, &raw[1].1), AccessError::ValueStillImmutablyReferenced(0));
+    drop(snapshot);
+    assert_prison_state!(prison, 0, 1, 1, 1, 3);
+    prison.overwrite_raw(key_0, &raw[1].1)?;
+    assert_eq!(prison.clone_val(key_0)?, [5, 6]);
+    assert_prison_state!(prison, 0, 1, 1, 1, 3);
+    assert_access_err!(prison.overwrite_raw(key_1, &raw[1].1), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.overwrite_raw(key_2, &[0; 5]), AccessError::RawSizeMismatch(4, 5));
+    let unaligned = [0u8, 7, 0, 8, 0];
+    prison.overwrite_raw(key_2, &unaligned[1..])?;
+    assert_eq!(prison.clone_val(key_2)?, [u16::from_ne_bytes([7, 0]), u16::from_ne_bytes([8, 0])]);
+    Ok(())
+}
+
+//TEST Prison::key_status() / Prison::watch_key() / Prison::drain_invalidated()
+#[test]
+fn prison_key_status_and_watch() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let grd_0 = prison.guard_mut(key_0)?;
+    assert_eq!(prison.key_status(key_0), KeyStatus::Alive);
+    drop(grd_0);
+    for key in [key_2, key_0, key_1, key_2] {
+        prison.watch_key(key);
     }
-    assert_jail_state!(jail, 0, MyNoCopy(42));
-    jail.visit_mut(|val| {
-        assert_access_err!(
-            jail.guard_ref(),
-            AccessError::ValueAlreadyMutablyReferenced(0)
-        );
-        Ok(())
-    })?;
-    internal!(jail).refs = Refs::MAX_IMMUT;
-    assert_access_err!(
-        jail.guard_ref(),
-        AccessError::MaximumImmutableReferencesReached(0)
+    prison.watch_key(CellKey::from_raw_parts(7, 0));
+    assert_eq!(internal!(prison).extras().watched.len(), 4);
+    prison.remove(key_1)?;
+    prison.remove(key_2)?;
+    let key_2_b = prison.insert(MyNoCopy(20))?;
+    assert_eq!(key_2_b.idx(), 2);
+    assert_eq!(prison.key_status(key_2_b), KeyStatus::Alive);
+    assert_eq!(
+        prison.drain_invalidated(),
+        vec![
+            (key_2, KeyStatus::Replaced { current_gen: 1 }),
+            (key_1, KeyStatus::Removed),
+            (CellKey::from_raw_parts(7, 0), KeyStatus::OutOfRange)
+        ]
     );
+    assert_eq!(internal!(prison).extras().watched, vec![key_0]);
+    assert!(!prison.unwatch_key(key_1));
+    assert!(prison.unwatch_key(key_0));
+    prison.remove(key_0)?;
+    assert!(prison.drain_invalidated().is_empty());
+    assert_eq!(prison.key_status(key_0), KeyStatus::Removed);
     Ok(())
 }
 
-//TEST JailCell::clone_val()
+//TEST Prison::export_keyspace()
 #[test]
-fn jail_clone_val() -> Result<(), AccessError> {
-    let jail: JailCell<String> = JailCell::new(String::from("fox"));
-    let mut animal_1: String = String::new();
-    let mut animal_2: String = String::new();
-    jail.visit_mut(|val| {
-        animal_1 = jail.clone_val();
-        animal_2 = val.clone();
-        *val = String::from("dog");
-        Ok(())
-    })?;
-    assert_eq!(animal_1, String::from("fox"));
-    assert_eq!(animal_1, animal_2);
-    animal_1 = String::from("bear");
-    animal_2 = String::from("cat");
-    assert_jail_state!(jail, 0, String::from("dog"));
+fn prison_export_keyspace() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    assert!(prison.export_keyspace().is_empty());
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let grd_2 = prison.guard_mut(key_2)?;
+    let before = prison.export_keyspace();
+    assert_eq!((before.len(), before.bits_per_index()), (3, 1));
+    assert!(before.is_valid(key_0) && before.is_valid(key_1) && before.is_valid(key_2));
+    drop(grd_2);
+    prison.remove(key_1)?;
+    prison.remove(key_0)?;
+    let key_0_b = prison.insert(MyNoCopy(10))?;
+    let after = prison.export_keyspace();
+    assert_eq!(after.generation_at(1), None);
+    assert_eq!(after.generation_at(3), None);
+    assert!(!after.is_valid(key_0) && after.is_valid(key_0_b) && !after.is_valid(key_1));
+    assert_eq!(before.diff(&after), (vec![key_0, key_1], vec![key_0_b]));
+    assert_eq!(after.diff(&after), (vec![], vec![]));
+    let gens = [Some(0), None, Some(1000), Some(usize::MAX >> 1), Some(7), None, Some(62)];
+    let wide = KeyspaceTable::from_generations(&gens);
+    assert_eq!(wide.bits_per_index(), usize::BITS);
+    for (idx, gen) in gens.iter().enumerate() {
+        assert_eq!(wide.generation_at(idx), *gen);
+    }
+    let narrow = KeyspaceTable::from_generations(&[Some(5); 30]);
+    assert_eq!(narrow.bits_per_index(), 3);
+    assert!((0..30).all(|idx| narrow.is_valid(CellKey::from_raw_parts(idx, 5))));
+    let words = narrow.to_words();
+    assert_eq!(words.len(), 2 + 2);
+    assert_eq!(KeyspaceTable::import_validation_table(&words), Some(narrow));
+    assert_eq!(KeyspaceTable::import_validation_table(&words[..3]), None);
+    assert_eq!(KeyspaceTable::import_validation_table(&[0, 0]), None);
+    assert_eq!(KeyspaceTable::import_validation_table(&wide.to_words()), Some(wide));
     Ok(())
 }
 
-//TEST JailCell::peek_ref()
+//TEST Prison::state() / Prison::state_idx()
 #[test]
-fn jail_peek_ref() -> Result<(), AccessError> {
-    let jail: JailCell<String> = JailCell::new(String::from("fox"));
-    jail.visit_mut(|val| {
-        let unsafe_ref = unsafe {jail.peek_ref()};
-        assert_eq!(*unsafe_ref, String::from("fox"));
-        assert_jail_state!(jail, Refs::MUT, String::from("fox"));
-        Ok(())
+fn prison_state() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    assert_eq!(prison.state(key_0)?, CellState::Live { gen: 0, refs: RefKind::None });
+    {
+        let grd_0_a = prison.guard_ref(key_0)?;
+        let grd_0_b = prison.guard_ref_idx(0)?;
+        let grd_1 = prison.guard_mut(key_1)?;
+        assert_eq!(prison.state(key_0)?, CellState::Live { gen: 0, refs: RefKind::Immutable(2) });
+        assert_eq!(prison.state_idx(1)?, CellState::Live { gen: 0, refs: RefKind::Mutable });
+    }
+    prison.remove(key_1)?;
+    assert_eq!(prison.state(key_1)?, CellState::Free);
+    assert_eq!(prison.state_idx(1)?, CellState::Free);
+    let key_1_b = prison.insert(MyNoCopy(11))?;
+    assert_eq!(prison.state(key_1)?, CellState::StaleKey { current_gen: 1 });
+    assert_eq!(prison.state(key_1_b)?, CellState::Live { gen: 1, refs: RefKind::None });
+    assert_eq!(prison.state_idx(1)?, CellState::Live { gen: 1, refs: RefKind::None });
+    assert_access_err!(prison.state(CellKey::from_raw_parts(2, 0)), AccessError::IndexOutOfRange(2));
+    assert_access_err!(prison.state_idx(5), AccessError::IndexOutOfRange(5));
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 2);
+    Ok(())
+}
+
+//TEST Prison::sample_keys() / Prison::visit_random_mut() / Prison::visit_random_ref()
+#[cfg(feature = "rand")]
+#[test]
+fn prison_random_sampling() -> Result<(), AccessError> {
+    struct XorShift(u64);
+    impl rand_core::RngCore for XorShift {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+        fn next_u64(&mut self) -> u64 {
+            self.0 ^= self.0 << 13;
+            self.0 ^= self.0 >> 7;
+            self.0 ^= self.0 << 17;
+            self.0
+        }
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+    let mut rng = XorShift(0x2545F4914F6CDD1D);
+    let prison: Prison<usize> = Prison::with_capacity(10);
+    assert_access_err!(
+        prison.visit_random_mut(&mut rng, |_, _| Ok(())),
+        AccessError::NoValuesToChoose
+    );
+    assert!(prison.sample_keys(3, &mut rng).is_empty());
+    let hidden_key = prison.insert_hidden(0)?;
+    assert_access_err!(prison.visit_random_ref(&mut rng, |_, _| Ok(())), AccessError::NoValuesToChoose);
+    assert!(prison.sample_keys(3, &mut rng).is_empty());
+    prison.remove(hidden_key)?;
+    for i in 0..10 {
+        prison.insert(0)?;
+    }
+    for idx in [1, 3, 5, 7] {
+        prison.remove_idx(idx)?;
+    }
+    let mut sampled = prison.sample_keys(6, &mut rng);
+    sampled.sort_by_key(|key| key.idx);
+    let sampled_idxs: Vec<usize> = sampled.iter().map(|key| key.idx).collect();
+    assert_eq!(sampled_idxs, vec![0, 2, 4, 6, 8, 9]);
+    assert_eq!(prison.sample_keys(2, &mut rng).len(), 2);
+    for _ in 0..600 {
+        prison.visit_random_mut(&mut rng, |key, val| {
+            assert!(internal!(prison).vec[key.idx].is_cell());
+            *val += 1;
+            Ok(())
+        })?;
+    }
+    let mut total = 0;
+    for idx in [0, 2, 4, 6, 8, 9] {
+        let count = prison.clone_val_idx(idx)?;
+        assert!(count > 50, "index {} was visited only {} times", idx, count);
+        total += count;
+    }
+    assert_eq!(total, 600);
+    for idx in [0, 2, 4, 6, 8] {
+        prison.remove_idx(idx)?;
+    }
+    for _ in 0..4 {
+        prison.insert_hidden(0)?;
+    }
+    for _ in 0..50 {
+        prison.visit_random_ref(&mut rng, |key, val| {
+            assert_eq!(key.idx, 9);
+            Ok(())
+        })?;
+    }
+    assert_eq!(prison.sample_keys(5, &mut rng), vec![CellKey::from_raw_parts(9, 1)]);
+    prison.visit_mut_idx(9, |_| {
+        assert_access_err!(
+            prison.visit_random_ref(&mut rng, |_, _| Ok(())),
+            AccessError::ValueAlreadyMutablyReferenced(9)
+        );
+        Ok(())
+    })?;
+    Ok(())
+}
+
+//TEST Prison::visit_set_mut() / Prison::visit_set_ref() / Prison::guard_set_mut() / Prison::guard_set_ref()
+#[test]
+fn prison_key_set() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let key_3 = prison.insert(MyNoCopy(3))?;
+    let set: CellKeySet = [key_3, key_1, key_3, key_0].into_iter().collect();
+    assert_eq!(set.as_slice(), &[key_0, key_1, key_3]);
+    prison.visit_set_mut(&set, |vals| {
+        assert_eq!(vals.len(), 3);
+        vals[2].0 = 33;
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 3, 0, 0, MyNoCopy(33));
+    {
+        let grd_1 = prison.guard_ref(key_1)?;
+        let grd_3 = prison.guard_mut(key_3)?;
+        match prison.visit_set_mut(&set, |_| Ok(())) {
+            Err(AccessError::SetMembersBlocked(blocked)) => {
+                assert_eq!(blocked.as_slice(), &[key_1, key_3])
+            }
+            _ => panic!("expected SetMembersBlocked"),
+        }
+        match prison.guard_set_ref(&set) {
+            Err(AccessError::SetMembersBlocked(blocked)) => assert_eq!(blocked.as_slice(), &[key_3]),
+            _ => panic!("expected SetMembersBlocked"),
+        }
+        assert_cell_state!(prison, 0, 0, 0, MyNoCopy(0));
+        assert_cell_state!(prison, 1, 1, 0, MyNoCopy(1));
+        assert_prison_state!(prison, 2, 0, IdxD::INVALID, 0, 4);
+    }
+    prison.remove(key_2)?;
+    let stale_set: CellKeySet = [key_0, key_2].into_iter().collect();
+    assert_access_err!(prison.guard_set_mut(&stale_set), AccessError::ValueDeleted(2, 0));
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(0));
+    let grd_all = prison.guard_set_ref(&set)?;
+    assert_eq!(grd_all[2].0, 33);
+    prison.visit_set_ref(&set, |vals| {
+        assert_eq!(vals[1].0, 1);
+        Ok(())
+    })?;
+    assert_prison_state!(prison, 3, 1, 2, 1, 4);
+    PrisonSliceRef::unguard(grd_all);
+    assert_prison_state!(prison, 0, 1, 2, 1, 4);
+    Ok(())
+}
+
+//TEST Prison::profile_scope() / Prison::profile_report() / Prison::profile_reset()
+#[cfg(feature = "profile")]
+#[test]
+fn prison_profile_scope() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    prison.visit_mut(key_0, |_| Ok(()))?;
+    assert!(prison.profile_report().is_empty());
+    {
+        let physics = prison.profile_scope("physics");
+        prison.visit_many_mut(&[key_0, key_1], |_| {
+            let render = prison.profile_scope("render");
+            assert!(prison.visit_ref(key_0, |_| Ok(())).is_err());
+            assert!(prison.guard_ref_idx(7).is_err());
+            Ok(())
+        })?;
+        let grd_1 = prison.guard_ref(key_1)?;
+    }
+    prison.visit_mut(key_0, |_| Ok(()))?;
+    let report = prison.profile_report();
+    assert_eq!(report.len(), 2);
+    assert_eq!(report[0].name, "physics");
+    assert_eq!(report[0].acquisitions, 3);
+    assert_eq!(report[0].errors, 0);
+    assert_eq!(report[0].visits, 1);
+    assert_eq!(report[1].name, "render");
+    assert_eq!(report[1].acquisitions, 0);
+    assert_eq!(report[1].errors, 2);
+    assert_eq!(report[1].contention_errors, 1);
+    assert_eq!(report[1].visits, 0);
+    let render = prison.profile_scope("render");
+    prison.profile_reset();
+    prison.visit_ref(key_1, |_| Ok(()))?;
+    ProfileScope::end(render);
+    let report = prison.profile_report();
+    assert_eq!(report[0].acquisitions, 0);
+    assert_eq!(report[1].acquisitions, 1);
+    assert_eq!(report[1].visits, 1);
+    Ok(())
+}
+
+//TEST Prison::visit_mut_timed() / Prison::visit_ref_timed()
+#[cfg(feature = "profile")]
+#[test]
+fn prison_visit_timed() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let scope = prison.profile_scope("timed");
+    prison.visit_mut_timed(key_0, |val_0| {
+        val_0.0 += 1;
+        Ok(())
+    })?;
+    prison.visit_ref_timed(key_0, |_| Ok(()))?;
+    let slow = prison.visit_ref_timed(key_1, |val_1| {
+        std::thread::sleep(Duration::from_millis(2));
+        Ok(val_1.0)
+    })?;
+    assert_eq!(slow, 1);
+    let grd_1 = prison.guard_mut(key_1)?;
+    assert!(prison.visit_mut_timed(key_1, |_| Ok(())).is_err());
+    drop(grd_1);
+    assert!(prison.visit_mut_timed(key_0, |_| Err::<(), _>(AccessError::PrisonSealed)).is_err());
+    ProfileScope::end(scope);
+    let times_0 = prison.visit_timings(key_0).unwrap();
+    assert_eq!(times_0.count, 3);
+    assert_eq!(times_0.buckets.iter().sum::<u64>(), 3);
+    let times_1 = prison.visit_timings(key_1).unwrap();
+    assert_eq!(times_1.count, 1);
+    assert!(times_1.max >= Duration::from_millis(2));
+    assert_eq!(times_1.buckets[DurationHistogram::bucket_of(times_1.max)], 1);
+    assert_eq!(times_1.mean(), times_1.max);
+    assert!(times_1.percentile(0.5) >= Duration::from_millis(2) && times_1.percentile(0.5) <= times_1.max);
+    let slowest = prison.slowest_visits(5);
+    assert_eq!(slowest.len(), 2);
+    assert_eq!(slowest[0].0, key_1);
+    assert_eq!(prison.profile_report()[0].closure_times.count, 4);
+    prison.remove(key_1)?;
+    let key_1_b = prison.insert(MyNoCopy(11))?;
+    assert_eq!(prison.visit_timings(key_1_b), None);
+    prison.visit_ref_timed(key_1_b, |_| Ok(()))?;
+    assert_eq!(prison.visit_timings(key_1), None);
+    assert_eq!(prison.visit_timings(key_1_b).unwrap().count, 1);
+    prison.profile_reset();
+    assert!(prison.slowest_visits(5).is_empty());
+    assert_eq!(DurationHistogram::bucket_of(Duration::from_nanos(999)), 0);
+    assert_eq!(DurationHistogram::bucket_of(Duration::from_micros(1)), 1);
+    assert_eq!(DurationHistogram::bucket_of(Duration::from_micros(3)), 2);
+    assert_eq!(DurationHistogram::bucket_of(Duration::MAX), DurationHistogram::BUCKETS - 1);
+    assert_eq!(DurationHistogram::default().mean(), Duration::ZERO);
+    Ok(())
+}
+
+//TEST Prison::recycle()
+#[test]
+fn prison_recycle() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let key_3 = prison.insert(MyNoCopy(3))?;
+    prison.remove(key_2)?;
+    let grd_3 = prison.guard_ref(key_3)?;
+    let recycled = prison.recycle(
+        |val| {
+            assert!(prison.visit_ref_idx(val.0, |_| Ok(())).is_err());
+            true
+        },
+        |val| val.0 += 10,
+    )?;
+    assert_eq!(recycled, vec![CellKey::from_raw_parts(0, 1), CellKey::from_raw_parts(1, 1)]);
+    assert_cell_state!(prison, 0, 0, 1, MyNoCopy(10));
+    assert_cell_state!(prison, 1, 0, 1, MyNoCopy(11));
+    assert_cell_state!(prison, 3, 1, 0, MyNoCopy(3));
+    assert_free_state!(prison, 2, IdxD::INVALID, IdxD::INVALID);
+    assert_prison_state!(prison, 1, 1, 2, 1, 4);
+    assert_access_err!(prison.visit_ref(key_0, |_| Ok(())), AccessError::ValueDeleted(0, 0));
+    PrisonValueRef::unguard(grd_3);
+    let recycled = prison.recycle(|val| val.0 == 3, |val| val.0 = 33)?;
+    assert_eq!(recycled, vec![CellKey::from_raw_parts(3, 1)]);
+    assert_cell_state!(prison, 3, 0, 1, MyNoCopy(33));
+    assert_prison_state!(prison, 0, 1, 2, 1, 4);
+    Ok(())
+}
+
+//TEST Prison::insert_group() / Prison::group_keys() / Prison::visit_group_mut() / Prison::visit_group_ref() / Prison::remove_group()
+#[test]
+fn prison_groups() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    prison.remove(key_0)?;
+    let group_a = prison.insert_group([MyNoCopy(10), MyNoCopy(20), MyNoCopy(30)])?;
+    let group_b = prison.insert_group([MyNoCopy(40)])?;
+    assert_eq!(group_a.id(), 0);
+    assert_eq!(group_b.id(), 1);
+    let keys_a = prison.group_keys(group_a)?;
+    assert_eq!(
+        keys_a.as_slice(),
+        &[CellKey::from_raw_parts(0, 1), CellKey::from_raw_parts(2, 1), CellKey::from_raw_parts(3, 1)]
+    );
+    prison.visit_group_mut(group_a, |vals| {
+        assert_eq!(vals.len(), 3);
+        vals[0].0 += 1;
+        assert!(prison.visit_group_ref(group_b, |vals_b| Ok(())).is_ok());
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 0, 0, 1, MyNoCopy(11));
+    prison.remove(CellKey::from_raw_parts(2, 1))?;
+    prison.visit_group_ref(group_a, |vals| {
+        assert_eq!(vals.len(), 2);
+        Ok(())
+    })?;
+    {
+        let grd_3 = prison.guard_mut_idx(3)?;
+        assert_access_err!(prison.remove_group(group_a), AccessError::RemoveWhileValueReferenced(3));
+        assert_cell_state!(prison, 0, 0, 1, MyNoCopy(11));
+    }
+    let removed = prison.remove_group(group_a)?;
+    assert_eq!(removed.len(), 2);
+    assert_eq!(removed[1].0, 30);
+    assert_access_err!(prison.remove_group(group_a), AccessError::GroupNotFound(0));
+    assert_access_err!(prison.visit_group_ref(group_a, |_| Ok(())), AccessError::GroupNotFound(0));
+    assert!(prison.group_keys(group_b)?.contains(CellKey::from_raw_parts(4, 1)));
+    assert_prison_state!(prison, 0, 2, 3, 3, 5);
+    let group_c = prison.insert_group([MyNoCopy(50), MyNoCopy(60)])?;
+    internal!(prison).vec[3].d_gen_or_prev = IdxD::new_type_a(IdxD::MAX_GEN);
+    internal!(prison).extras().groups[1].1.keys[1].gen = IdxD::MAX_GEN;
+    assert_access_err!(prison.remove_group(group_c), AccessError::MaxValueForGenerationReached);
+    assert_eq!(prison.group_keys(group_c)?.len(), 2);
+    assert_cell_state!(prison, 0, 0, 2, MyNoCopy(60));
+    assert_cell_state!(prison, 3, 0, IdxD::MAX_GEN, MyNoCopy(50));
+    assert_prison_state!(prison, 0, 2, 2, 1, 5);
+    internal!(prison).vec[3].d_gen_or_prev = IdxD::new_type_a(2);
+    internal!(prison).extras().groups[1].1.keys[1].gen = 2;
+    assert_eq!(prison.remove_group(group_c)?.len(), 2);
+    let small: Prison<MyNoCopy> = Prison::with_capacity(2);
+    let small_key = small.insert(MyNoCopy(0))?;
+    let grd_small = small.guard_ref(small_key)?;
+    assert_access_err!(
+        small.insert_group([MyNoCopy(1), MyNoCopy(2)]),
+        AccessError::InsertAtMaxCapacityWhileAValueIsReferenced
+    );
+    assert_free_state!(small, 1, IdxD::INVALID, IdxD::INVALID);
+    assert_prison_state!(small, 1, 1, 1, 1, 2);
+    Ok(())
+}
+
+//TEST Prison::leak_val() / Prison::leak_all()
+#[test]
+fn prison_leak() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let leaked_1 = prison.leak_val(key_1)?;
+    leaked_1.0 = 11;
+    assert_free_state!(prison, 1, IdxD::INVALID, IdxD::INVALID);
+    assert_prison_state!(prison, 0, 1, 1, 1, 3);
+    assert_access_err!(prison.leak_val(key_1), AccessError::ValueDeleted(1, 0));
+    {
+        let grd_0 = prison.guard_ref(key_0)?;
+        assert_access_err!(prison.leak_val(key_0), AccessError::RemoveWhileValueReferenced(0));
+    }
+    let leaked_all = prison.leak_all();
+    assert_eq!(leaked_all.len(), 3);
+    assert_eq!(leaked_all[0].as_ref().map(|val| val.0), Some(0));
+    assert!(leaked_all[1].is_none());
+    assert_eq!(leaked_all[2].as_ref().map(|val| val.0), Some(2));
+    assert_eq!(leaked_1.0, 11);
+    let placeholder_prison: Prison<MyNoCopy> = Prison::with_placeholder(|| MyNoCopy(99));
+    let key_p = placeholder_prison.insert(MyNoCopy(5))?;
+    placeholder_prison.remove(key_p)?;
+    assert!(placeholder_prison.leak_all()[0].is_none());
+    Ok(())
+}
+
+//TEST Prison::least_recently_used() / Prison::most_recently_used()
+#[cfg(feature = "lru")]
+#[test]
+fn prison_lru() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let key_3 = prison.insert(MyNoCopy(3))?;
+    assert_eq!(prison.least_recently_used(10), vec![key_0, key_1, key_2, key_3]);
+    prison.visit_mut(key_1, |_| Ok(()))?;
+    let grd_0 = prison.guard_ref(key_0)?;
+    assert!(prison.visit_mut(key_2, |_| Ok(())).is_ok());
+    assert!(prison.visit_mut(key_0, |_| Ok(())).is_err());
+    PrisonValueRef::unguard(grd_0);
+    assert_eq!(prison.least_recently_used(2), vec![key_3, key_1]);
+    assert_eq!(prison.most_recently_used(2), vec![key_2, key_0]);
+    prison.remove(key_3)?;
+    let key_3_b = prison.insert(MyNoCopy(33))?;
+    let key_1_b = prison.overwrite(1, MyNoCopy(11))?;
+    assert_eq!(prison.least_recently_used(4), vec![key_0, key_2, key_3_b, key_1_b]);
+    let mapped = prison.map_into(|_, val| val.0);
+    assert_eq!(mapped.most_recently_used(1), vec![key_1_b]);
+    Ok(())
+}
+
+//TEST Prison::enable_offload() / Prison::offload() / Prison::offload_coldest()
+#[cfg(feature = "offload")]
+#[test]
+fn prison_offload() -> Result<(), AccessError> {
+    let path = std::env::temp_dir().join(format!("grit_offload_test_{}", std::process::id()));
+    let path_b = path.with_extension("b");
+    let open = |path: &std::path::Path| std::fs::OpenOptions::new().read(true).write(true).create(true).truncate(true).open(path).unwrap();
+    let mut prison: Prison<String> = Prison::new();
+    let key_0 = prison.insert(String::from("zero"))?;
+    assert_eq!(prison.offload(key_0), Err(AccessError::OffloadFailed(0)));
+    prison.enable_offload(open(&path), |val| val.as_bytes().to_vec(), |bytes| String::from_utf8(bytes.to_vec()).unwrap())?;
+    let key_1 = prison.insert(String::from("one"))?;
+    let key_2 = prison.insert(String::from("two"))?;
+    let key_3 = prison.insert(String::from("three"))?;
+    prison.pin(key_0)?;
+    let grd_1 = prison.guard_ref(key_1)?;
+    assert_eq!(prison.offload(key_0), Err(AccessError::ValuePinned(0)));
+    assert_eq!(prison.offload(key_1), Err(AccessError::ValueStillImmutablyReferenced(1)));
+    assert_eq!(prison.offload_coldest(10)?, 2);
+    assert!(prison.is_offloaded(key_2) && prison.is_offloaded(key_3));
+    assert_eq!(prison.num_offloaded(), 2);
+    assert_eq!(prison.offload_coldest(10)?, 0);
+    drop(grd_1);
+    assert_eq!(prison.clone_val(key_2)?, String::from("two"));
+    assert!(!prison.is_offloaded(key_2));
+    prison.visit_mut(key_3, |val| {
+        val.push('!');
+        Ok(())
+    })?;
+    assert_eq!(prison.num_offloaded(), 0);
+    prison.offload(key_3)?;
+    prison.offload(key_3)?;
+    assert_eq!(prison.remove(key_3)?, String::from("three!"));
+    prison.offload(key_2)?;
+    assert_eq!(prison.clone_val_idx(2)?, String::from("two"));
+    prison.offload(key_2)?;
+    prison.remove_idx(key_1.idx())?;
+    prison.enable_offload(open(&path_b), |val| val.as_bytes().to_vec(), |bytes| String::from_utf8(bytes.to_vec()).unwrap())?;
+    assert_eq!(prison.num_offloaded(), 0);
+    prison.offload(key_2)?;
+    prison.unpin(key_0)?;
+    let remap = prison.purge()?;
+    let key_2 = remap.get(key_2).unwrap();
+    assert_eq!(prison.clone_val(key_2)?, String::from("two"));
+    prison.offload(key_2)?;
+    prison.seal_read_only()?;
+    assert_eq!(prison.offload(key_0), Err(AccessError::PrisonSealed));
+    assert_eq!(prison.visit_ref(key_2, |val| Ok(val.clone()))?, String::from("two"));
+    prison.unseal();
+    prison.offload(key_2)?;
+    assert_eq!(prison.into_inner_checked()?, vec![String::from("zero"), String::from("two")]);
+    std::fs::remove_file(&path).unwrap();
+    std::fs::remove_file(&path_b).unwrap();
+    Ok(())
+}
+
+//TEST AccessError::is_contention() / is_stale_key() / is_capacity() / is_refused() / is_io() / is_bug() / is_retryable()
+#[test]
+fn access_error_classification() -> Result<(), AccessError> {
+    let errors = [
+        AccessError::IndexOutOfRange(0),
+        AccessError::ValueAlreadyMutablyReferenced(0),
+        AccessError::ValueStillImmutablyReferenced(0),
+        AccessError::OverwriteWhileValueReferenced(0),
+        AccessError::InsertAtMaxCapacityWhileAValueIsReferenced,
+        AccessError::RemoveWhileValueReferenced(0),
+        AccessError::ValueDeleted(0, 0),
+        AccessError::MaxValueForGenerationReached,
+        AccessError::IndexIsNotFree(0),
+        AccessError::MaximumCapacityReached,
+        AccessError::MaximumImmutableReferencesReached(0),
+        AccessError::SetMembersBlocked(CellKeySet::from(&[CellKey::from_raw_parts(0, 0)][..])),
+        AccessError::GroupNotFound(0),
+        AccessError::InterruptRequested,
+        AccessError::ForeignHandle(0),
+        AccessError::ValuePinned(0),
+        AccessError::ForeignIndex(0),
+        AccessError::AccessesOutstanding(1),
+        AccessError::PrisonSealed,
+        AccessError::VisitDepthExceeded(0),
+        AccessError::RawSizeMismatch(4, 2),
+        AccessError::ValueHidden(0),
+        AccessError::OffloadFailed(0),
+        AccessError::PairSameIndex(0),
+        AccessError::NoValuesToChoose,
+        AccessError::MAJOR_MALFUNCTION(String::from("test")),
+    ];
+    for err in errors {
+        // No wildcard arm: a new variant fails to compile here until it is given a class
+        // [contention, stale_key, capacity, refused, io, bug, retryable]
+        let expected = match err {
+            AccessError::ValueAlreadyMutablyReferenced(_)
+            | AccessError::ValueStillImmutablyReferenced(_)
+            | AccessError::OverwriteWhileValueReferenced(_)
+            | AccessError::InsertAtMaxCapacityWhileAValueIsReferenced
+            | AccessError::RemoveWhileValueReferenced(_)
+            | AccessError::MaximumImmutableReferencesReached(_)
+            | AccessError::SetMembersBlocked(_)
+            | AccessError::AccessesOutstanding(_) => [true, false, false, false, false, false, true],
+            AccessError::IndexOutOfRange(_)
+            | AccessError::ValueDeleted(_, _)
+            | AccessError::GroupNotFound(_)
+            | AccessError::ForeignHandle(_)
+            | AccessError::ForeignIndex(_) => [false, true, false, false, false, false, false],
+            AccessError::MaxValueForGenerationReached | AccessError::MaximumCapacityReached | AccessError::VisitDepthExceeded(_) => {
+                [false, false, true, false, false, false, false]
+            }
+            AccessError::IndexIsNotFree(_)
+            | AccessError::ValuePinned(_)
+            | AccessError::PrisonSealed
+            | AccessError::RawSizeMismatch(_, _)
+            | AccessError::ValueHidden(_)
+            | AccessError::PairSameIndex(_)
+            | AccessError::NoValuesToChoose => [false, false, false, true, false, false, false],
+            AccessError::OffloadFailed(_) => [false, false, false, false, true, false, false],
+            AccessError::MAJOR_MALFUNCTION(_) => [false, false, false, false, false, true, false],
+            AccessError::InterruptRequested => [false, false, false, false, false, false, true],
+        };
+        let classes = [
+            err.is_contention(),
+            err.is_stale_key(),
+            err.is_capacity(),
+            err.is_refused(),
+            err.is_io(),
+            err.is_bug(),
+            err.is_retryable(),
+        ];
+        assert_eq!(classes, expected, "{}", err.kind());
+    }
+    Ok(())
+}
+
+//TEST Prison::request_interrupt() / Prison::checkpoint() / GuardCtx::checkpoint()
+#[test]
+fn prison_interrupt() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    assert!(!prison.interrupt_requested());
+    assert!(prison.checkpoint().is_ok());
+    let result = prison.visit_many_mut(&[key_0, key_1], |vals| {
+        prison.checkpoint()?;
+        vals[0].0 += 1;
+        prison.request_interrupt();
+        prison.checkpoint()?;
+        vals[1].0 += 1;
+        Ok(())
+    });
+    assert!(result.as_ref().unwrap_err().is_retryable());
+    assert_access_err!(result, AccessError::InterruptRequested);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(1));
+    assert_cell_state!(prison, 1, 0, 0, MyNoCopy(1));
+    assert_access_err!(
+        prison.with_guards(|ctx| {
+            ctx.imm_ref(key_0)?;
+            ctx.checkpoint()
+        }),
+        AccessError::InterruptRequested
+    );
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 2);
+    prison.clear_interrupt();
+    assert!(prison.with_guards(|ctx| ctx.checkpoint()).is_ok());
+    Ok(())
+}
+
+//TEST Prison::serialize_reachable()
+#[test]
+fn prison_serialize_reachable() -> Result<(), AccessError> {
+    let prison: Prison<(usize, Vec<CellKey>)> = Prison::with_capacity(6);
+    let key_0 = prison.insert((0, vec![]))?;
+    let key_1 = prison.insert((1, vec![]))?;
+    let key_2 = prison.insert((2, vec![key_0]))?;
+    let key_3 = prison.insert((3, vec![key_2, key_0]))?;
+    prison.visit_mut(key_0, |val_0| {
+        val_0.1.push(key_3);
+        Ok(())
+    })?;
+    let mut out: Vec<(usize, Vec<CellKey>)> = Vec::new();
+    let remap = prison.serialize_reachable(
+        &[key_3],
+        |val| val.1.clone(),
+        &mut out,
+        |out, val, remap| {
+            out.push((val.0, val.1.iter().map(|key| remap.get(*key).unwrap()).collect()));
+            Ok::<(), AccessError>(())
+        },
+    )?;
+    assert_eq!(remap.len(), 3);
+    assert_eq!(remap.get(key_3), Some(CellKey::from_raw_parts(0, 0)));
+    assert_eq!(remap.get(key_2), Some(CellKey::from_raw_parts(1, 0)));
+    assert_eq!(remap.get(key_0), Some(CellKey::from_raw_parts(2, 0)));
+    assert_eq!(remap.get(key_1), None);
+    assert_eq!(out[0], (3, vec![CellKey::from_raw_parts(1, 0), CellKey::from_raw_parts(2, 0)]));
+    assert_eq!(out[2], (0, vec![CellKey::from_raw_parts(0, 0)]));
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 4);
+    let reloaded: Prison<(usize, Vec<CellKey>)> = Prison::new();
+    for val in out {
+        reloaded.insert(val)?;
+    }
+    assert_eq!(reloaded.clone_val(remap.get(key_2).unwrap())?.0, 2);
+    {
+        let grd_2 = prison.guard_mut(key_2)?;
+        let mut out = Vec::new();
+        assert_access_err!(
+            prison.serialize_reachable(&[key_3], |val| val.1.clone(), &mut out, |out, val, _| {
+                out.push(val.0);
+                Ok::<(), AccessError>(())
+            }),
+            AccessError::ValueAlreadyMutablyReferenced(2)
+        );
+        assert!(out.is_empty());
+        assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 4);
+    }
+    prison.remove(key_1)?;
+    let mut out = Vec::new();
+    assert_access_err!(
+        prison.serialize_reachable(&[key_0, key_1], |val| val.1.clone(), &mut out, |out, val, _| {
+            out.push(val.0);
+            Ok::<(), AccessError>(())
+        }),
+        AccessError::ValueDeleted(1, 0)
+    );
+    assert_access_err!(
+        prison.serialize_reachable(&[key_0], |val| val.1.clone(), &mut out, |_, _, _| {
+            Err(AccessError::IndexOutOfRange(9))
+        }),
+        AccessError::IndexOutOfRange(9)
+    );
+    assert_prison_state!(prison, 0, 1, 1, 1, 4);
+    Ok(())
+}
+
+//TEST Prison::handle() / Prison::handle_to_key() / Prison::visit_handle_mut() / Prison::visit_handle_ref()
+#[test]
+fn prison_script_handle() -> Result<(), AccessError> {
+    assert_eq!(mem::size_of::<ScriptHandle>(), 16);
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let other: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    other.insert(MyNoCopy(100))?;
+    let handle_1 = prison.handle(key_1)?;
+    assert_eq!(ScriptHandle::from_bits(handle_1.to_bits()), handle_1);
+    assert_eq!(prison.handle_to_key(handle_1)?, key_1);
+    prison.visit_handle_mut(handle_1, |val_1| {
+        val_1.0 = 11;
+        assert!(prison.visit_handle_ref(handle_1, |_| Ok(())).is_err());
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 1, 0, 0, MyNoCopy(11));
+    let (_, prison_id, _) = handle_1.into_raw_parts();
+    assert_access_err!(other.visit_handle_ref(handle_1, |_| Ok(())), AccessError::ForeignHandle(prison_id as usize));
+    prison.remove(key_1)?;
+    assert_access_err!(prison.visit_handle_ref(handle_1, |_| Ok(())), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.handle(key_1), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.handle(CellKey::from_raw_parts(7, 0)), AccessError::IndexOutOfRange(7));
+    let handle_0 = prison.handle(key_0)?;
+    let mapped = prison.map_into(|_, val| val.0);
+    assert!(mapped.visit_handle_ref(handle_0, |_| Ok(())).is_ok());
+    Ok(())
+}
+
+//TEST Prison::pin()
+#[test]
+fn prison_pin_cells() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    prison.insert(MyNoCopy(2))?;
+    prison.pin(key_1)?;
+    prison.pin(key_1)?;
+    assert!(prison.is_pinned(key_1));
+    assert!(!prison.is_pinned(key_0));
+    assert_access_err!(prison.remove(key_1), AccessError::ValuePinned(1));
+    assert_access_err!(prison.remove_idx(1), AccessError::ValuePinned(1));
+    assert_access_err!(prison.overwrite(1, MyNoCopy(10)), AccessError::ValuePinned(1));
+    prison.visit_mut(key_1, |val_1| {
+        val_1.0 = 11;
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 1, 0, 0, MyNoCopy(11));
+    let recycled = prison.recycle(|_| true, |val| val.0 += 100)?;
+    assert_eq!(recycled.len(), 2);
+    assert_cell_state!(prison, 1, 0, 0, MyNoCopy(11));
+    prison.unpin(key_1)?;
+    prison.unpin(key_1)?;
+    assert!(!prison.is_pinned(key_1));
+    assert_eq!(prison.remove(key_1)?.0, 11);
+    assert!(!prison.is_pinned(key_1));
+    assert_access_err!(prison.pin(key_1), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.unpin(CellKey::from_raw_parts(9, 0)), AccessError::IndexOutOfRange(9));
+    let group = prison.insert_group([MyNoCopy(3), MyNoCopy(4)])?;
+    let members = prison.group_keys(group)?;
+    let members = members.as_slice();
+    prison.pin(members[1])?;
+    assert_access_err!(prison.remove_group(group), AccessError::ValuePinned(members[1].idx()));
+    assert_eq!(prison.group_keys(group)?.len(), 2);
+    prison.unpin(members[1])?;
+    assert_eq!(prison.remove_group(group)?.len(), 2);
+    Ok(())
+}
+
+//TEST Prison::visit_many_mut() / Prison::guard_many_ref() (inline and spilled buffers)
+#[test]
+fn prison_many_inline_buffers() -> Result<(), AccessError> {
+    let mut small: InlineVec<usize> = InlineVec::with_capacity(3);
+    for i in 0..INLINE_CAP {
+        small.push(i);
+    }
+    assert!(!small.is_spilled());
+    small.push(INLINE_CAP);
+    assert!(small.is_spilled());
+    assert_eq!(small.as_slice(), (0..=INLINE_CAP).collect::<Vec<_>>().as_slice());
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(12);
+    let mut keys = Vec::new();
+    for i in 0..12 {
+        keys.push(prison.insert(MyNoCopy(i))?);
+    }
+    prison.visit_many_mut(&keys[0..INLINE_CAP], |vals| {
+        assert_eq!(vals.len(), INLINE_CAP);
+        for val in vals.iter_mut() {
+            val.0 += 100;
+        }
+        Ok(())
+    })?;
+    prison.visit_many_mut(&keys, |vals| {
+        assert_eq!(vals.len(), 12);
+        for val in vals.iter_mut() {
+            val.0 += 100;
+        }
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 7, 0, 0, MyNoCopy(207));
+    assert_cell_state!(prison, 11, 0, 0, MyNoCopy(111));
+    let grd_all = prison.guard_many_ref(&keys)?;
+    assert_eq!(grd_all[10].0, 110);
+    assert_cell_state!(prison, 10, 1, 0, MyNoCopy(110));
+    PrisonSliceRef::unguard(grd_all);
+    let mut dup_keys = keys.clone();
+    dup_keys.push(keys[0]);
+    assert_access_err!(
+        prison.visit_many_mut(&dup_keys, |_| Ok(())),
+        AccessError::ValueAlreadyMutablyReferenced(0)
+    );
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 12);
+    Ok(())
+}
+
+//TEST Prison::set_idx_validation() / Prison::salt_idx()
+#[test]
+fn prison_idx_validation() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let other: Prison<MyNoCopy> = Prison::with_capacity(5);
+    prison.insert(MyNoCopy(0))?;
+    prison.insert(MyNoCopy(1))?;
+    other.insert(MyNoCopy(100))?;
+    assert!(!prison.idx_validation());
+    assert_eq!(prison.salt_idx(1), 1);
+    prison.set_idx_validation(true);
+    other.set_idx_validation(true);
+    assert!(prison.idx_validation());
+    let idx_0 = prison.salt_idx(0);
+    let idx_1 = prison.salt_idx(1);
+    assert_ne!(idx_1, 1);
+    assert_eq!(prison.salt_idx(idx_1), idx_1);
+    prison.visit_many_mut_idx(&[idx_0, idx_1], |vals| {
+        vals[1].0 = 11;
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 1, 0, 0, MyNoCopy(11));
+    prison.with_guards(|ctx| {
+        ctx.mut_ref_idx(idx_1)?.0 = 12;
+        assert!(ctx.release_idx(idx_1));
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 1, 0, 0, MyNoCopy(12));
+    if cfg!(debug_assertions) {
+        assert_access_err!(prison.visit_ref_idx(1, |_| Ok(())), AccessError::ForeignIndex(1));
+        assert_access_err!(other.remove_idx(idx_0), AccessError::ForeignIndex(idx_0));
+        assert_access_err!(
+            prison.guard_many_ref_idx(&[idx_0, 1]),
+            AccessError::ForeignIndex(1)
+        );
+        assert_cell_state!(prison, 0, 0, 0, MyNoCopy(0));
+    }
+    prison.visit_slice_mut(.., |vals| {
+        vals[0].0 = 10;
+        Ok(())
+    })?;
+    assert_eq!(prison.guard_slice_ref(1..)?[0].0, 12);
+    let mapped = prison.map_into(|_, val| val.0);
+    assert_eq!(mapped.clone_val_idx(idx_1)?, 12);
+    mapped.set_idx_validation(false);
+    assert_eq!(mapped.remove_idx(1)?, 12);
+    Ok(())
+}
+
+//TEST Prison::snapshot_ref()
+#[test]
+fn prison_snapshot_ref() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    prison.remove(key_1)?;
+    let snapshot = prison.snapshot_ref()?;
+    assert_eq!(snapshot.len(), 2);
+    assert_eq!(snapshot[key_0].0 + snapshot[2].0, 2);
+    assert!(snapshot.get(key_1).is_none());
+    assert!(snapshot.get_idx(1).is_none());
+    assert!(snapshot.get(CellKey::from_raw_parts(2, 1)).is_none());
+    assert_cell_state!(prison, 2, 1, 0, MyNoCopy(2));
+    let key_1_new = prison.insert(MyNoCopy(11))?;
+    assert!(snapshot.get(key_1_new).is_none());
+    assert_access_err!(prison.visit_mut(key_2, |_| Ok(())), AccessError::ValueStillImmutablyReferenced(2));
+    assert!(prison.visit_ref(key_2, |_| Ok(())).is_ok());
+    let missing = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| snapshot[key_1].0));
+    assert!(missing.is_err());
+    SnapshotRef::unguard(snapshot);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 3);
+    let grd_0 = prison.guard_mut(key_0)?;
+    assert_access_err!(prison.snapshot_ref(), AccessError::ValueAlreadyMutablyReferenced(0));
+    PrisonValueMut::unguard(grd_0);
+    assert_cell_state!(prison, 2, 0, 0, MyNoCopy(2));
+    Ok(())
+}
+
+//TEST Prison::uuid_of() / Prison::key_by_uuid()
+#[cfg(feature = "uuid")]
+#[test]
+fn prison_uuid() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let uuid_0 = prison.uuid_of(key_0)?;
+    let uuid_1 = prison.uuid_of(key_1)?;
+    let uuid_2 = prison.uuid_of(key_2)?;
+    assert!(uuid_0 != 0 && uuid_0 < uuid_1 && uuid_1 < uuid_2);
+    assert_eq!(prison.key_by_uuid(uuid_1), Some(key_1));
+    prison.remove(key_1)?;
+    assert_eq!(prison.key_by_uuid(uuid_1), None);
+    assert_access_err!(prison.uuid_of(key_1), AccessError::ValueDeleted(1, 0));
+    let key_1_new = prison.insert(MyNoCopy(11))?;
+    let uuid_1_new = prison.uuid_of(key_1_new)?;
+    assert!(uuid_1_new > uuid_2);
+    assert_eq!(prison.key_by_uuid(uuid_1_new), Some(key_1_new));
+    let key_2_new = prison.overwrite(2, MyNoCopy(22))?;
+    assert_eq!(prison.key_by_uuid(uuid_2), None);
+    assert_eq!(prison.key_by_uuid(prison.uuid_of(key_2_new)?), Some(key_2_new));
+    let recycled = prison.recycle(|val| val.0 == 0, |val| val.0 = 100)?;
+    assert_eq!(prison.key_by_uuid(uuid_0), None);
+    assert_eq!(prison.key_by_uuid(prison.uuid_of(recycled[0])?), Some(recycled[0]));
+    let mapped = prison.map_into(|_, val| val.0);
+    assert_eq!(mapped.key_by_uuid(uuid_1_new), Some(key_1_new));
+    Ok(())
+}
+
+//TEST visit_keys!
+#[test]
+fn prison_visit_keys_macro() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let sum = crate::visit_keys!(prison, mut a = key_0, ref b = key_1, ref c = key_1, mut d = key_2 => {
+        a.0 += b.0 + c.0;
+        d.0 *= 10;
+        assert_cell_state!(prison, 1, 2, 0, MyNoCopy(1));
+        Ok(a.0 + d.0)
+    })?;
+    assert_eq!(sum, 22);
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 3);
+    assert_access_err!(
+        crate::visit_keys!(prison, ref a = key_0, mut b = key_1, mut c = key_0 => { Ok(()) }),
+        AccessError::ValueStillImmutablyReferenced(0)
+    );
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 3);
+    prison.remove(key_2)?;
+    assert_access_err!(
+        crate::visit_keys!(prison, mut a = key_0, ref b = key_2 => { Ok(()) }),
+        AccessError::ValueDeleted(2, 0)
+    );
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(2));
+    Ok(())
+}
+
+//TEST Prison::into_inner_checked()
+#[test]
+fn prison_into_inner_checked() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    prison.insert(MyNoCopy(1))?;
+    prison.insert(MyNoCopy(2))?;
+    prison.remove(key_0)?;
+    let vals = prison.into_inner_checked()?;
+    assert_eq!(vals.iter().map(|val| val.0).collect::<Vec<_>>(), vec![1, 2]);
+    if !cfg!(feature = "major_malf_is_panic") {
+        let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+        let key_0 = prison.insert(MyNoCopy(0))?;
+        mem::forget(prison.guard_ref(key_0)?);
+        assert_access_err!(prison.into_inner_checked(), AccessError::AccessesOutstanding(1));
+    }
+    let prison: Prison<String> = Prison::with_placeholder(String::new);
+    let key_0 = prison.insert(String::from("a"))?;
+    prison.insert(String::from("b"))?;
+    prison.remove(key_0)?;
+    assert_eq!(prison.into_inner_checked()?, vec![String::from("b")]);
+    Ok(())
+}
+
+//TEST Prison::gc_step()
+#[test]
+fn prison_gc_step() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(10);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let group = prison.insert_group([MyNoCopy(2), MyNoCopy(3), MyNoCopy(4)])?;
+    let key_5 = prison.insert(MyNoCopy(5))?;
+    let key_6 = prison.insert(MyNoCopy(6))?;
+    prison.remove(key_1)?;
+    prison.remove_idx(3)?;
+    prison.remove(key_6)?;
+    prison.remove(key_5)?;
+    assert_prison_state!(prison, 0, 1, 5, 4, 7);
+    let grd_0 = prison.guard_ref(key_0)?;
+    assert!(!prison.gc_step(1)?);
+    assert_prison_state!(prison, 1, 1, 5, 3, 6);
+    assert_free_state!(prison, 5, IdxD::INVALID, 3);
+    assert!(!prison.gc_step(1)?);
+    assert_prison_state!(prison, 1, 1, 3, 2, 5);
+    assert_eq!(internal!(prison).groups()[0].1.len(), 3);
+    assert!(prison.gc_step(4)?);
+    assert_eq!(internal!(prison).groups()[0].1.len(), 2);
+    assert!(prison.gc_step(10)?);
+    PrisonValueRef::unguard(grd_0);
+    assert_access_err!(prison.visit_ref(key_6, |_| Ok(())), AccessError::IndexOutOfRange(6));
+    let key_new = prison.insert(MyNoCopy(7))?;
+    assert_eq!(key_new, CellKey::from_raw_parts(3, 1));
+    assert_eq!(prison.remove_group(group)?.len(), 2);
+    let prison: Prison<String> = Prison::with_placeholder(String::new);
+    let key_a = prison.insert(String::from("a"))?;
+    prison.remove(key_a)?;
+    assert!(prison.gc_step(1)?);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 0);
+    Ok(())
+}
+
+//TEST Prison::reserve_index_range() / Prison::release_index_range()
+#[test]
+fn prison_reserve_index_range() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(10);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    prison.insert(MyNoCopy(2))?;
+    prison.remove(key_1)?;
+    prison.reserve_index_range(1..5)?;
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 3, 5);
+    assert_eq!(prison.num_free(), 5);
+    assert!(prison.is_index_reserved(4));
+    assert!(!prison.is_index_reserved(5));
+    let key_5 = prison.insert(MyNoCopy(5))?;
+    assert_eq!(key_5.idx(), 5);
+    let key_3 = prison.insert_at(3, MyNoCopy(3))?;
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 2, 6);
+    prison.remove(key_3)?;
+    prison.remove_idx(2)?;
+    assert_prison_state!(prison, 0, 2, IdxD::INVALID, 4, 6);
+    prison.overwrite(2, MyNoCopy(22))?;
+    assert_eq!(prison.insert(MyNoCopy(6))?.idx(), 6);
+    prison.reserve_index_range(8..9)?;
+    assert_prison_state!(prison, 0, 2, 7, 5, 9);
+    assert_free_state!(prison, 7, IdxD::INVALID, IdxD::INVALID);
+    prison.reserve_index_range(4..=7)?;
+    assert_eq!(internal!(prison).reserved(), vec![(1, 9)]);
+    assert_eq!(prison.num_free(), 1);
+    prison.remove(key_0)?;
+    assert!(prison.gc_step(10)?);
+    assert_eq!(prison.vec_len(), 9);
+    prison.release_index_range(3..8)?;
+    assert_eq!(internal!(prison).reserved(), vec![(1, 3), (8, 9)]);
+    assert_prison_state!(prison, 0, 2, 7, 6, 9);
+    assert_eq!(prison.insert(MyNoCopy(7))?.idx(), 7);
+    prison.release_index_range(..)?;
+    assert!(internal!(prison).reserved().is_empty());
+    assert!(prison.gc_step(10)?);
+    assert_eq!(prison.vec_len(), 8);
+    Ok(())
+}
+
+//TEST Prison::visit_pair_mut() / Prison::guard_pair_mut()
+#[test]
+fn prison_pair_mut() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(3);
+    let key_0 = prison.insert(10)?;
+    let key_1 = prison.insert(20)?;
+    let key_2 = prison.insert(30)?;
+    prison.visit_pair_mut(key_2, key_0, |val_2, val_0| {
+        assert_prison_state!(prison, 2, 0, IdxD::INVALID, 0, 3);
+        assert_access_err!(prison.visit_ref(key_0, |_| Ok(())), AccessError::ValueAlreadyMutablyReferenced(0));
+        mem::swap(val_2, val_0);
+        Ok(())
+    })?;
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 3);
+    assert_cell_state!(prison, 0, 0, 0, 30);
+    assert_cell_state!(prison, 2, 0, 0, 10);
+    assert_access_err!(prison.visit_pair_mut(key_1, key_1, |_, _| Ok(())), AccessError::PairSameIndex(1));
+    assert_access_err!(
+        prison.visit_pair_mut(key_1, CellKey::from_raw_parts(1, 5), |_, _| Ok(())),
+        AccessError::PairSameIndex(1)
+    );
+    let grd_2 = prison.guard_ref(key_2)?;
+    assert_access_err!(
+        prison.visit_pair_mut(key_0, key_2, |_, _| Ok(())),
+        AccessError::ValueStillImmutablyReferenced(2)
+    );
+    assert_access_err!(prison.guard_pair_mut(key_0, key_2), AccessError::ValueStillImmutablyReferenced(2));
+    assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 3);
+    drop(grd_2);
+    let (mut grd_0, mut grd_1) = prison.guard_pair_mut(key_0, key_1)?;
+    assert_access_err!(prison.guard_pair_mut(key_1, key_2), AccessError::ValueAlreadyMutablyReferenced(1));
+    assert_prison_state!(prison, 2, 0, IdxD::INVALID, 0, 3);
+    *grd_0 += 1;
+    *grd_1 += 2;
+    drop((grd_0, grd_1));
+    assert_access_err!(prison.guard_pair_mut(key_2, key_2), AccessError::PairSameIndex(2));
+    assert_eq!(prison.visit_pair_mut(key_0, key_1, |val_0, val_1| Ok(*val_0 + *val_1))?, 53);
+    Ok(())
+}
+
+//TEST Prison::visit_array_mut() / Prison::guard_array_mut()
+#[test]
+fn prison_array_mut() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(12);
+    let keys = prison.insert_many(0..12)?;
+    prison.visit_array_mut([keys[3], keys[1], keys[2]], |[val_3, val_1, val_2]| {
+        assert_prison_state!(prison, 3, 0, IdxD::INVALID, 0, 12);
+        *val_3 += *val_1 * 10 + *val_2 * 100;
+        Ok(())
+    })?;
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 12);
+    assert_cell_state!(prison, 3, 0, 0, 213);
+    assert_access_err!(
+        prison.visit_array_mut([keys[0], keys[1], keys[0]], |_| Ok(())),
+        AccessError::ValueAlreadyMutablyReferenced(0)
+    );
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 12);
+    assert_eq!(prison.visit_array_mut([], |[]: [&mut u32; 0]| Ok(7))?, 7);
+    let mut grd = prison.guard_array_mut([keys[11], keys[10]])?;
+    assert_access_err!(prison.guard_array_mut([keys[9], keys[10]]), AccessError::ValueAlreadyMutablyReferenced(10));
+    assert_prison_state!(prison, 2, 0, IdxD::INVALID, 0, 12);
+    let [val_11, val_10] = &mut *grd;
+    mem::swap(*val_11, *val_10);
+    assert_eq!(*grd[0], 10);
+    PrisonArrayMut::unguard(grd);
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 12);
+    let all: [CellKey; 12] = std::array::from_fn(|idx| keys[idx]);
+    let grd = prison.guard_array_mut(all)?;
+    assert_eq!(grd.iter().map(|val| **val).sum::<u32>(), 66 + 210);
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), 1);
+    drop(grd);
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), 2);
+    Ok(())
+}
+
+//TEST Prison::visit_many_mut_scratch()
+#[test]
+fn prison_visit_many_scratch() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(10);
+    let mut keys = Vec::new();
+    for i in 0..10 {
+        keys.push(prison.insert(MyNoCopy(i))?);
+    }
+    let mut scratch = Scratch::new();
+    assert_eq!(scratch.capacity(), 0);
+    prison.visit_many_mut_scratch(&keys, &mut scratch, |vals, scratch| {
+        let bytes = scratch.alloc_slice_fill(3, 7u8);
+        let wide = scratch.alloc_slice_fill_with(vals.len(), |i| vals[i].0 as u64);
+        assert_eq!(wide.as_ptr() as usize % mem::align_of::<u64>(), 0);
+        let big = scratch.alloc_slice_copy(&[1u128; 16]);
+        assert_eq!(big.as_ptr() as usize % mem::align_of::<u128>(), 0);
+        let empty = scratch.alloc_slice_fill(0, 0u64);
+        assert!(empty.is_empty());
+        assert_eq!(bytes, &[7, 7, 7]);
+        assert_eq!(wide[9], 9);
+        assert_eq!(big[15], 1);
+        for val in vals.iter_mut() {
+            val.0 += wide[0] as usize + 1;
+        }
+        assert!(scratch.used() >= 3 + 80 + 256);
+        assert_eq!(internal!(prison).vec[4].refs_or_next, Refs::MUT);
+        Ok(())
+    })?;
+    assert_eq!(scratch.used(), 0);
+    let cap = scratch.capacity();
+    assert!(cap >= 3 + 80 + 256);
+    assert_cell_state!(prison, 4, 0, 0, MyNoCopy(5));
+    assert_access_err!(
+        prison.visit_many_ref_scratch(&keys[..4], &mut scratch, |vals, scratch| {
+            let sums = scratch.alloc_slice_fill_with(vals.len(), |i| vals[..=i].iter().map(|val| val.0).sum::<usize>());
+            assert_eq!(sums, &[1, 3, 6, 10]);
+            Err(AccessError::InterruptRequested)
+        }),
+        AccessError::InterruptRequested
+    );
+    assert_eq!(scratch.used(), 0);
+    assert_eq!(scratch.capacity(), cap);
+    assert_access_err!(
+        prison.visit_many_mut_scratch(&[keys[0], keys[0]], &mut scratch, |_, _| Ok(())),
+        AccessError::ValueAlreadyMutablyReferenced(0)
+    );
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 10);
+    Ok(())
+}
+
+//TEST ValueGuardRef / ValueGuardMut
+#[test]
+fn value_guard_traits() -> Result<(), AccessError> {
+    fn read_all<G: ValueGuardRef<MyNoCopy>>(guards: &[G]) -> usize {
+        return guards.iter().map(|guard| guard.value().0).sum();
+    }
+    fn bump<G: ValueGuardMut<MyNoCopy>>(mut guard: G) {
+        guard.value_mut().0 += 1;
+        guard.release();
+    }
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(10))?;
+    let key_1 = prison.insert(MyNoCopy(20))?;
+    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(5));
+    bump(prison.guard_mut(key_0)?);
+    bump(jail.guard_mut()?);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(11));
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 2);
+    assert_eq!(read_all(&[prison.guard_ref(key_0)?, prison.guard_ref(key_1)?]), 31);
+    assert_eq!(read_all(&[prison.guard_mut(key_1)?]), 20);
+    assert_eq!(read_all(&[jail.guard_ref()?, jail.guard_ref()?]), 12);
+    assert_eq!(read_all(&[jail.guard_mut()?]), 6);
+    let grd_0 = prison.guard_ref(key_0)?;
+    assert_eq!(internal!(prison).vec[0].refs_or_next, 1);
+    grd_0.release();
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 2);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(11));
+    assert!(jail.guard_mut().is_ok());
+    Ok(())
+}
+
+//TEST Prison::seal_read_only()
+#[test]
+fn prison_seal_read_only() -> Result<(), AccessError> {
+    let mut prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(10))?;
+    let key_1 = prison.insert(MyNoCopy(20))?;
+    let key_2 = prison.insert(MyNoCopy(30))?;
+    prison.remove(key_2)?;
+    let grd_0 = prison.guard_ref(key_0)?;
+    assert_access_err!(prison.seal_read_only(), AccessError::AccessesOutstanding(1));
+    assert!(!prison.is_sealed());
+    drop(grd_0);
+    prison.seal_read_only()?;
+    prison.seal_read_only()?;
+    assert!(prison.is_sealed());
+    let grd_0 = prison.guard_ref(key_0)?;
+    let grd_1 = prison.guard_ref_idx(1)?;
+    prison.visit_many_ref(&[key_0, key_1, key_0], |vals| {
+        assert_eq!(vals[2].0, 10);
+        assert_eq!(internal!(prison).vec[0].refs_or_next, 0);
+        Ok(())
+    })?;
+    let slice = prison.guard_slice_ref(..2)?;
+    assert_eq!(slice[1].0, 20);
+    assert_eq!(internal!(prison).vec[1].refs_or_next, 0);
+    assert_eq!(internal!(prison).access_count.count, SEALED_ACCESSES);
+    assert_access_err!(prison.visit_ref(key_2, |_| Ok(())), AccessError::ValueDeleted(2, 0));
+    assert_access_err!(prison.visit_mut(key_1, |_| Ok(())), AccessError::PrisonSealed);
+    assert_access_err!(prison.guard_mut_idx(1), AccessError::PrisonSealed);
+    assert_access_err!(prison.visit_many_mut(&[key_0], |_| Ok(())), AccessError::PrisonSealed);
+    assert_access_err!(prison.insert(MyNoCopy(40)), AccessError::PrisonSealed);
+    assert_access_err!(prison.insert_at(2, MyNoCopy(40)), AccessError::PrisonSealed);
+    assert_access_err!(prison.overwrite(0, MyNoCopy(40)), AccessError::PrisonSealed);
+    assert_access_err!(prison.remove(key_0), AccessError::PrisonSealed);
+    assert_access_err!(prison.remove_idx(1), AccessError::PrisonSealed);
+    assert_access_err!(prison.recycle(|_| true, |_| {}), AccessError::PrisonSealed);
+    assert_access_err!(prison.gc_step(10), AccessError::PrisonSealed);
+    prison.visit_ref(key_1, |val_1| {
+        assert_eq!(val_1.0, 20);
+        Ok(())
+    })?;
+    drop(slice);
+    drop(grd_1);
+    drop(grd_0);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(10));
+    prison.unseal();
+    prison.unseal();
+    assert!(!prison.is_sealed());
+    assert_prison_state!(prison, 0, 1, 2, 1, 3);
+    prison.visit_mut(key_1, |val_1| {
+        val_1.0 += 1;
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 1, 0, 0, MyNoCopy(21));
+    prison.seal_read_only()?;
+    assert_eq!(prison.into_inner_checked()?, vec![MyNoCopy(10), MyNoCopy(21)]);
+    Ok(())
+}
+
+//TEST Prison::guard_mut_when_free()
+#[test]
+fn prison_guard_when_free() -> Result<(), AccessError> {
+    use std::{
+        future::Future,
+        pin::pin,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        task::{Context, Poll, Wake, Waker},
+    };
+    struct CountWaker(AtomicUsize);
+    impl Wake for CountWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+    let count = Arc::new(CountWaker(AtomicUsize::new(0)));
+    let waker = Waker::from(count.clone());
+    let mut cx = Context::from_waker(&waker);
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(10))?;
+    let key_1 = prison.insert(MyNoCopy(20))?;
+    let grd_0 = prison.guard_ref(key_0)?;
+    let grd_0_again = prison.guard_ref(key_0)?;
+    {
+        let mut when_free = pin!(prison.guard_mut_when_free(key_0));
+        assert!(when_free.as_mut().poll(&mut cx).is_pending());
+        assert!(when_free.as_mut().poll(&mut cx).is_pending());
+        assert_eq!(internal!(prison).access_count.wakers.len(), 1);
+        drop(grd_0);
+        assert_eq!(count.0.load(Ordering::Relaxed), 0);
+        assert!(when_free.as_mut().poll(&mut cx).is_pending());
+        drop(grd_0_again);
+        assert_eq!(count.0.load(Ordering::Relaxed), 1);
+        assert!(internal!(prison).access_count.wakers.is_empty());
+        match when_free.as_mut().poll(&mut cx) {
+            Poll::Ready(Ok(mut grd_0)) => {
+                grd_0.0 += 1;
+                let mut ref_when_free = pin!(prison.guard_ref_when_free(key_0));
+                assert!(ref_when_free.as_mut().poll(&mut cx).is_pending());
+            }
+            _ => panic!("value should be free"),
+        }
+        assert_eq!(count.0.load(Ordering::Relaxed), 2);
+    }
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(11));
+    prison.visit_mut(key_1, |val_1| {
+        let mut cancelled = pin!(prison.guard_ref_when_free(key_1));
+        assert!(cancelled.as_mut().poll(&mut cx).is_pending());
+        Ok(())
+    })?;
+    assert_eq!(count.0.load(Ordering::Relaxed), 3);
+    prison.remove(key_1)?;
+    let mut deleted = pin!(prison.guard_mut_when_free(key_1));
+    assert!(matches!(deleted.as_mut().poll(&mut cx), Poll::Ready(Err(AccessError::ValueDeleted(1, 0)))));
+    assert!(internal!(prison).access_count.wakers.is_empty());
+    assert_prison_state!(prison, 0, 1, 1, 1, 2);
+    Ok(())
+}
+
+//TEST Prison::drain_dirty()
+#[cfg(feature = "dirty")]
+#[test]
+fn prison_drain_dirty() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(6);
+    let mut keys = Vec::new();
+    for i in 0..6 {
+        keys.push(prison.insert(MyNoCopy(i))?);
+    }
+    assert!(prison.drain_dirty().is_empty());
+    prison.visit_mut(keys[3], |val_3| Ok(()))?;
+    prison.visit_ref(keys[1], |val_1| Ok(()))?;
+    prison.visit_slice_mut(4.., |vals| Ok(()))?;
+    prison.visit_mut_idx(3, |val_3| Ok(()))?;
+    let grd_1 = prison.guard_mut(keys[1])?;
+    assert_eq!(internal!(prison).dirty, vec![3, 4, 5, 1]);
+    prison.remove(keys[4])?;
+    let key_4 = prison.overwrite(5, MyNoCopy(50))?;
+    assert_eq!(prison.drain_dirty(), vec![keys[3]]);
+    assert_eq!(internal!(prison).dirty, vec![1]);
+    drop(grd_1);
+    let recycled = prison.recycle(|val| val.0 == 0, |val| val.0 = 100)?;
+    assert_eq!(prison.drain_dirty(), vec![keys[1], recycled[0]]);
+    assert!(prison.drain_dirty().is_empty());
+    prison.visit_mut(key_4, |val_4| Ok(()))?;
+    prison.remove(key_4)?;
+    assert!(prison.gc_step(10)?);
+    assert_eq!(internal!(prison).dirty, vec![5]);
+    assert!(prison.drain_dirty().is_empty());
+    assert!(internal!(prison).dirty.is_empty());
+    Ok(())
+}
+
+//TEST TwoPhase
+#[test]
+fn two_phase_commit() -> Result<(), AccessError> {
+    let prison_a: Prison<MyNoCopy> = Prison::with_capacity(3);
+    let prison_b: Prison<u32> = Prison::with_capacity(2);
+    let key_a0 = prison_a.insert(MyNoCopy(0))?;
+    let key_a1 = prison_a.insert(MyNoCopy(1))?;
+    let key_b0 = prison_b.insert(10)?;
+    let key_b1 = prison_b.insert(11)?;
+    let mut tx = TwoPhase::new();
+    assert_eq!(tx.insert(&prison_a, MyNoCopy(2)), 0);
+    tx.set(&prison_b, key_b0, 20);
+    assert_eq!(tx.insert(&prison_a, MyNoCopy(3)), 1);
+    prison_a.visit_ref(key_a0, |val_a0| {
+        assert_eq!(tx.len(), 3);
+        Ok(())
+    })?;
+    let grd_a0 = prison_a.guard_ref(key_a0)?;
+    assert_access_err!(tx.commit(), AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
+    assert_prison_state!(prison_a, 1, 0, IdxD::INVALID, 0, 2);
+    assert_eq!(prison_b.clone_val(key_b0)?, 10);
+    drop(grd_a0);
+    let mut tx = TwoPhase::new();
+    tx.insert(&prison_a, MyNoCopy(2));
+    tx.remove(&prison_a, key_a1);
+    tx.set(&prison_b, key_b0, 20);
+    tx.remove(&prison_b, key_b0);
+    assert_access_err!(tx.commit(), AccessError::ValueDeleted(0, 0));
+    let mut tx = TwoPhase::new();
+    tx.remove(&prison_a, key_a1);
+    tx.set(&prison_b, key_b1, 21);
+    let grd_b1 = prison_b.guard_ref(key_b1)?;
+    assert_access_err!(tx.commit(), AccessError::ValueStillImmutablyReferenced(1));
+    drop(grd_b1);
+    prison_a.pin(key_a1)?;
+    let mut tx = TwoPhase::new();
+    tx.remove(&prison_a, key_a1);
+    assert_access_err!(tx.commit(), AccessError::ValuePinned(1));
+    prison_a.unpin(key_a1)?;
+    assert_prison_state!(prison_a, 0, 0, IdxD::INVALID, 0, 2);
+    let mut tx = TwoPhase::default();
+    assert!(tx.is_empty());
+    tx.remove(&prison_a, key_a1);
+    tx.insert(&prison_a, MyNoCopy(2));
+    tx.insert(&prison_a, MyNoCopy(3));
+    tx.set(&prison_b, key_b0, 20);
+    tx.set(&prison_b, key_b0, 30);
+    tx.insert(&prison_b, 12);
+    let keys = tx.commit()?;
+    assert_eq!(keys, vec![CellKey::from_raw_parts(1, 1), CellKey::from_raw_parts(2, 1), CellKey::from_raw_parts(2, 0)]);
+    assert_cell_state!(prison_a, 1, 0, 1, MyNoCopy(2));
+    assert_cell_state!(prison_a, 2, 0, 1, MyNoCopy(3));
+    assert_prison_state!(prison_a, 0, 1, IdxD::INVALID, 0, 3);
+    assert_eq!(prison_b.clone_many_vals(&[key_b0, key_b1, keys[2]])?, vec![30, 11, 12]);
+    assert_prison_state!(prison_b, 0, 0, IdxD::INVALID, 0, 3);
+    Ok(())
+}
+
+//TEST plan_disjoint_batches()
+#[test]
+fn prison_plan_disjoint_batches() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(10);
+    let mut keys = Vec::new();
+    for i in 0..10 {
+        keys.push(prison.insert(MyNoCopy(i))?);
+    }
+    let mut shuffled: Vec<CellKey> = keys.iter().rev().copied().collect();
+    shuffled.push(keys[4]);
+    shuffled.push(keys[9]);
+    let plan = crate::plan_disjoint_batches(&shuffled, 4);
+    assert_eq!(plan.len(), 4);
+    assert_eq!(plan.num_keys(), 10);
+    let sizes: Vec<usize> = plan.iter().map(|batch| batch.len()).collect();
+    assert_eq!(sizes, vec![3, 3, 2, 2]);
+    assert_eq!(plan.batch(1).as_slice(), &keys[3..6]);
+    let grds: Vec<_> = plan.iter().map(|batch| prison.guard_set_mut(batch)).collect::<Result<_, _>>()?;
+    assert_prison_state!(prison, 10, 0, IdxD::INVALID, 0, 10);
+    drop(grds);
+    let plan = crate::plan_disjoint_batches(&keys[..2], 0);
+    assert_eq!(plan.into_batches(), vec![CellKeySet::from(&keys[..2])]);
+    let plan = crate::plan_disjoint_batches(&keys[..2], 3);
+    assert_eq!(plan.num_keys(), 2);
+    assert!(plan.batch(2).is_empty());
+    assert!(crate::plan_disjoint_batches(&[], 2).iter().all(|batch| batch.is_empty()));
+    Ok(())
+}
+
+//TEST Prison::insert_at_idx_or_append()
+#[test]
+fn prison_insert_at_idx_or_append() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(2);
+    let key_0 = prison.insert_at_idx_or_append(0, MyNoCopy(0))?;
+    prison.insert_at_idx_or_append(1, MyNoCopy(1))?;
+    prison.remove(key_0)?;
+    assert_prison_state!(prison, 0, 1, 0, 1, 2);
+    assert_access_err!(prison.insert_at_idx_or_append(1, MyNoCopy(2)), AccessError::IndexIsNotFree(1));
+    assert_access_err!(prison.insert_at_idx_or_append(3, MyNoCopy(3)), AccessError::IndexOutOfRange(3));
+    prison.visit_ref_idx(1, |_| {
+        assert_access_err!(
+            prison.insert_at_idx_or_append(2, MyNoCopy(2)),
+            AccessError::InsertAtMaxCapacityWhileAValueIsReferenced
+        );
+        Ok(())
+    })?;
+    let key_2 = prison.insert_at_idx_or_append(2, MyNoCopy(2))?;
+    assert_eq!(key_2.idx(), 2);
+    assert_prison_state!(prison, 0, 1, 0, 1, 3);
+    prison.insert_at_idx_or_append(0, MyNoCopy(0))?;
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 3);
+    assert_cell_state!(prison, 2, 0, 1, MyNoCopy(2));
+    Ok(())
+}
+
+//TEST Prison::purge()
+#[test]
+fn prison_purge() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(10);
+    prison.reserve_index_range(2..4)?;
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_res = prison.insert_at(3, MyNoCopy(3))?;
+    let group = prison.insert_group([MyNoCopy(4), MyNoCopy(5), MyNoCopy(6)])?;
+    let group_keys = prison.group_keys(group)?.as_slice().to_vec();
+    prison.remove(key_0)?;
+    prison.remove(group_keys[0])?;
+    prison.pin(group_keys[2])?;
+    let key_tmp = prison.insert(MyNoCopy(7))?;
+    prison.remove(key_tmp)?;
+    let grd = prison.guard_ref(key_1)?;
+    assert_access_err!(prison.purge(), AccessError::AccessesOutstanding(1));
+    drop(grd);
+    assert_prison_state!(prison, 0, 2, 4, 3, 7);
+    let remap = prison.purge()?;
+    assert_eq!(remap.len(), 4);
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 1, 5);
+    assert_eq!(remap.get(key_1), Some(CellKey::from_raw_parts(0, 0)));
+    assert_eq!(remap.get(key_res), Some(CellKey::from_raw_parts(3, 0)));
+    assert_eq!(remap.get(group_keys[1]), Some(CellKey::from_raw_parts(1, 0)));
+    assert_eq!(remap.get(group_keys[2]), Some(CellKey::from_raw_parts(4, 0)));
+    assert_eq!(remap.get(key_0), None);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(1));
+    assert_cell_state!(prison, 4, 0, 0, MyNoCopy(6));
+    assert!(prison.is_pinned(CellKey::from_raw_parts(4, 0)));
+    assert_eq!(
+        prison.group_keys(group)?.as_slice(),
+        &[CellKey::from_raw_parts(1, 0), CellKey::from_raw_parts(4, 0)]
+    );
+    assert_eq!(prison.insert(MyNoCopy(8))?.idx(), 5);
+    prison.remove_idx(3)?;
+    assert_eq!(prison.insert_at_idx_or_append(6, MyNoCopy(9))?.idx(), 6);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 2, 7);
+    Ok(())
+}
+
+//TEST Prison::compact()
+#[test]
+fn prison_compact() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(10);
+    prison.reserve_index_range(2..4)?;
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_res = prison.insert_at(3, MyNoCopy(3))?;
+    let group = prison.insert_group([MyNoCopy(4), MyNoCopy(5), MyNoCopy(6)])?;
+    let group_keys = prison.group_keys(group)?.as_slice().to_vec();
+    prison.remove(key_0)?;
+    prison.remove(group_keys[0])?;
+    prison.pin(group_keys[2])?;
+    let key_tmp = prison.insert(MyNoCopy(7))?;
+    prison.remove(key_tmp)?;
+    let grd = prison.guard_ref(key_1)?;
+    assert_access_err!(prison.compact(|_, _| {}), AccessError::AccessesOutstanding(1));
+    drop(grd);
+    assert_prison_state!(prison, 0, 2, 4, 3, 7);
+    let mut moves = Vec::new();
+    assert_eq!(prison.compact(|old_key, new_key| moves.push((old_key, new_key)))?, 2);
+    assert_eq!(
+        moves,
+        vec![
+            (group_keys[1], CellKey::from_raw_parts(1, 2)),
+            (group_keys[2], CellKey::from_raw_parts(4, 2)),
+        ]
+    );
+    assert_prison_state!(prison, 0, 2, IdxD::INVALID, 1, 5);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(1));
+    assert_cell_state!(prison, 1, 0, 2, MyNoCopy(5));
+    assert_cell_state!(prison, 3, 0, 0, MyNoCopy(3));
+    assert_eq!(prison.visit_ref(key_1, |val| Ok(val.0))?, 1);
+    assert_access_err!(prison.visit_ref(group_keys[1], |_| Ok(())), AccessError::IndexOutOfRange(5));
+    assert_eq!(prison.visit_ref(key_res, |val| Ok(val.0))?, 3);
+    assert!(prison.is_pinned(CellKey::from_raw_parts(4, 2)));
+    assert_eq!(
+        prison.group_keys(group)?.as_slice(),
+        &[CellKey::from_raw_parts(1, 2), CellKey::from_raw_parts(4, 2)]
+    );
+    assert_eq!(prison.compact(|_, _| panic!("nothing should move"))?, 0);
+    assert_prison_state!(prison, 0, 2, IdxD::INVALID, 1, 5);
+    assert_eq!(prison.insert(MyNoCopy(8))?, CellKey::from_raw_parts(5, 2));
+    let hooked: Prison<(CellKey, u32)> = Prison::new();
+    hooked.set_move_hook(Some(|_, new_key, val| val.0 = new_key));
+    let key_a = hooked.insert((CellKey::from_raw_parts(0, 0), 0))?;
+    let key_b = hooked.insert_hidden((CellKey::from_raw_parts(1, 0), 1))?;
+    hooked.remove(key_a)?;
+    assert_eq!(hooked.compact(|_, _| {})?, 1);
+    let key_b = CellKey::from_raw_parts(0, 1);
+    assert!(hooked.is_hidden(key_b));
+    hooked.publish(key_b)?;
+    assert_eq!(hooked.clone_val(key_b)?, (key_b, 1));
+    Ok(())
+}
+
+//TEST Prison::move_val()
+#[test]
+fn prison_move_val() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(6);
+    let group = prison.insert_group([10, 11])?;
+    let group_keys = prison.group_keys(group)?.as_slice().to_vec();
+    let key_2 = prison.insert(12)?;
+    let key_3 = prison.insert(13)?;
+    prison.remove(group_keys[0])?;
+    prison.remove(key_2)?;
+    assert_prison_state!(prison, 0, 1, 2, 2, 4);
+    let key_1 = prison.move_val(group_keys[1], 0)?;
+    assert_eq!(key_1, CellKey::from_raw_parts(0, 1));
+    assert_prison_state!(prison, 0, 1, 1, 2, 4);
+    assert_cell_state!(prison, 0, 0, 1, 11);
+    assert_free_state!(prison, 1, IdxD::INVALID, 2);
+    assert_eq!(prison.group_keys(group)?.as_slice(), &[key_1]);
+    assert_access_err!(prison.visit_ref(group_keys[1], |_| Ok(())), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.move_val(group_keys[1], 2), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.move_val(key_3, 0), AccessError::IndexIsNotFree(0));
+    assert_access_err!(prison.move_val(key_3, 9), AccessError::IndexOutOfRange(9));
+    let grd = prison.guard_ref(key_3)?;
+    assert_access_err!(prison.move_val(key_3, 2), AccessError::RemoveWhileValueReferenced(3));
+    drop(grd);
+    prison.pin(key_3)?;
+    assert_access_err!(prison.move_val(key_3, 2), AccessError::ValuePinned(3));
+    prison.unpin(key_3)?;
+    let key_3 = prison.move_val(key_3, 2)?;
+    assert_eq!(key_3, CellKey::from_raw_parts(2, 1));
+    assert_prison_state!(prison, 0, 1, 3, 2, 4);
+    let key_hidden = prison.insert_hidden(14)?;
+    assert_eq!(key_hidden.idx(), 3);
+    let key_hidden = prison.move_val(key_hidden, 1)?;
+    assert_access_err!(prison.visit_ref(key_hidden, |_| Ok(())), AccessError::ValueHidden(1));
+    prison.publish(key_hidden)?;
+    assert_eq!(prison.clone_val(key_hidden)?, 14);
+    prison.reserve_index_range(3..4)?;
+    let key_res = prison.insert_at(3, 15)?;
+    let key_4 = prison.insert(16)?;
+    prison.remove(key_4)?;
+    let key_res = prison.move_val(key_res, 4)?;
+    assert!(prison.is_index_reserved(3));
+    assert_access_err!(prison.move_val(key_1, 3), AccessError::IndexIsNotFree(3));
+    assert!(prison.is_index_reserved(3));
+    assert_eq!(prison.clone_val(key_1)?, 11);
+    assert_eq!(prison.insert(16)?.idx(), 5);
+    assert_eq!(prison.insert_at(3, 17)?.idx(), 3);
+    assert_eq!(prison.clone_many_vals(&[key_1, key_hidden, key_3, key_res])?, vec![11, 14, 13, 15]);
+    Ok(())
+}
+
+//TEST Prison::invalidate()
+#[test]
+fn prison_invalidate() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(3);
+    let key_0 = prison.insert(10)?;
+    let group = prison.insert_group(vec![20])?;
+    let key_1 = prison.group_keys(group)?.as_slice()[0];
+    prison.watch_key(key_1);
+    let grd_1 = prison.guard_ref(key_1)?;
+    assert_access_err!(prison.invalidate(key_1), AccessError::ValueStillImmutablyReferenced(1));
+    drop(grd_1);
+    let new_1 = prison.invalidate(key_1)?;
+    assert_eq!(new_1, CellKey::from_raw_parts(1, 1));
+    assert_cell_state!(prison, 1, 0, 1, 20);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 2);
+    assert_access_err!(prison.clone_val(key_1), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.invalidate(key_1), AccessError::ValueDeleted(1, 0));
+    assert_eq!(prison.drain_invalidated(), vec![(key_1, KeyStatus::Replaced { current_gen: 1 })]);
+    assert_eq!(prison.group_keys(group)?.as_slice(), &[new_1]);
+    let new_0 = prison.invalidate(key_0)?;
+    assert_eq!(new_0.gen, 1);
+    assert_eq!(prison.invalidate(new_0)?.gen, 2);
+    prison.seal_read_only()?;
+    assert_access_err!(prison.invalidate(new_1), AccessError::PrisonSealed);
+    Ok(())
+}
+
+//TEST Prison::set_redact_values() / Debug
+#[test]
+fn prison_debug_redact() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(3);
+    let key_0 = prison.insert(10)?;
+    let key_1 = prison.insert(11)?;
+    prison.insert(12)?;
+    prison.remove(key_1)?;
+    let grd_0 = prison.guard_mut(key_0)?;
+    let debug = format!("{:?}", prison);
+    assert_eq!(
+        debug,
+        "Prison { len: 3, used: 2, generation: 1, accesses: 1, slots: [\
+        Val { idx: 0, gen: 0, refs: mut, val: <mutably referenced> }, \
+        Free { idx: 1, next: None, prev: None }, \
+        Val { idx: 2, gen: 0, refs: 0, val: 12 }] }"
+    );
+    drop(grd_0);
+    assert!(format!("{:?}", prison).contains("val: 10"));
+    assert!(!prison.values_redacted());
+    prison.set_redact_values(true);
+    assert!(prison.values_redacted());
+    let debug = format!("{:?}", prison);
+    assert!(debug.contains("Val { idx: 0, gen: 0, refs: 0, val: <redacted> }"));
+    assert!(!debug.contains("12"));
+    assert!(prison.filtered_clone(|_, _| true).values_redacted());
+    Ok(())
+}
+
+//TEST Prison::set_move_hook()
+#[test]
+fn prison_move_hook() -> Result<(), AccessError> {
+    thread_local! {
+        static MOVES: std::cell::RefCell<Vec<(CellKey, CellKey)>> = const { std::cell::RefCell::new(Vec::new()) };
+    }
+    fn record(old_key: CellKey, new_key: CellKey, val: &mut (CellKey, u32)) {
+        MOVES.with(|moves| moves.borrow_mut().push((old_key, new_key)));
+        val.0 = new_key;
+    }
+    let prison: Prison<(CellKey, u32)> = Prison::new();
+    prison.set_move_hook(Some(record));
+    let keys: Vec<CellKey> = (0..5).map(|i| prison.insert((CellKey::from_raw_parts(i, 0), i as u32))).collect::<Result<_, _>>()?;
+    prison.reserve_index_range(3..4)?;
+    prison.remove(keys[1])?;
+    prison.remove(keys[2])?;
+    let key_5 = prison.insert((CellKey::from_raw_parts(2, 1), 5))?;
+    prison.purge()?;
+    let moves = MOVES.with(|moves| moves.take());
+    assert_eq!(
+        moves,
+        vec![
+            (key_5, CellKey::from_raw_parts(1, 0)),
+            (keys[4], CellKey::from_raw_parts(2, 0)),
+        ]
+    );
+    for idx in 0..4 {
+        prison.visit_ref_idx(idx, |val| {
+            assert_eq!(val.0, CellKey::from_raw_parts(idx, 0));
+            Ok(())
+        })?;
+    }
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 4);
+    prison.set_move_hook(None);
+    prison.remove(CellKey::from_raw_parts(0, 0))?;
+    prison.insert((CellKey::from_raw_parts(0, 1), 6))?;
+    prison.purge()?;
+    assert!(MOVES.with(|moves| moves.take()).is_empty());
+    Ok(())
+}
+
+//TEST Prison::with_transform()
+#[cfg(feature = "transform")]
+#[test]
+fn prison_with_transform() -> Result<(), AccessError> {
+    let prison: Prison<(u32, u32)> = Prison::with_capacity_and_transform(5, |(a, b)| (b, a + 1), |(b, a)| (*a - 1, *b));
+    let key_0 = prison.insert((1, 2))?;
+    let key_1 = prison.insert_at_idx_or_append(1, (3, 4))?;
+    assert_eq!(*prison.guard_ref(key_0)?, (2, 2));
+    prison.visit_mut(key_0, |val_0| {
+        assert_eq!(*val_0, (1, 2));
+        val_0.0 = 10;
+        Ok(())
+    })?;
+    assert_eq!(*prison.guard_ref(key_0)?, (2, 11));
+    prison.visit_ref_idx(0, |val_0| {
+        assert_eq!(*val_0, (10, 2));
+        Ok(())
+    })?;
+    assert_eq!(prison.clone_many_vals(&[key_0, key_1])?, vec![(10, 2), (3, 4)]);
+    assert_eq!(*prison.guard_ref(key_1)?, (4, 4));
+    assert_eq!(prison.remove(key_1)?, (3, 4));
+    prison.overwrite(1, (5, 6))?;
+    assert_eq!(prison.into_inner_checked()?, vec![(10, 2), (5, 6)]);
+    Ok(())
+}
+
+//TEST Prison::iter_mut() and Prison::iter_ref()
+#[test]
+fn prison_iter_mut_ref() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let key_2 = prison.insert(MyNoCopy(2))?;
+    let key_3 = prison.insert(MyNoCopy(3))?;
+    prison.remove(key_2)?;
+    let grds: Vec<_> = prison.iter_mut(IterPolicy::Error).collect::<Result<_, _>>()?;
+    assert_eq!(grds.iter().map(|(key, _)| *key).collect::<Vec<_>>(), vec![key_0, key_1, key_3]);
+    assert_prison_state!(prison, 3, 1, 2, 1, 4);
+    assert_cell_state!(prison, 3, Refs::MUT, 0, MyNoCopy(3));
+    drop(grds);
+    let grd_1 = prison.guard_mut(key_1)?;
+    let mut iter = prison.iter_ref(IterPolicy::Error);
+    assert_eq!(iter.next().map(|item| item.map(|(key, _)| key)), Some(Ok(key_0)));
+    assert_eq!(
+        iter.next().map(|item| item.map(|(key, _)| key)),
+        Some(Err(AccessError::ValueAlreadyMutablyReferenced(1)))
+    );
+    let (_, grd_3) = iter.next().unwrap()?;
+    assert_eq!(*grd_3, MyNoCopy(3));
+    assert!(iter.next().is_none());
+    drop(grd_3);
+    let refs: Vec<_> = prison.iter_ref(IterPolicy::Skip).collect::<Result<_, _>>()?;
+    assert_eq!(refs.len(), 2);
+    assert_prison_state!(prison, 3, 1, 2, 1, 4);
+    drop(refs);
+    drop(grd_1);
+    assert_prison_state!(prison, 0, 1, 2, 1, 4);
+    Ok(())
+}
+
+//TEST Prison::map_refs()
+#[test]
+fn prison_map_refs() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(5);
+    let keys = prison.insert_many([10, 20, 30, 40])?;
+    prison.remove(keys[1])?;
+    let mut iter = prison.map_refs(keys.iter().copied(), |val| {
+        assert!(internal!(prison).access_count.count > 0);
+        Ok(*val + 1)
+    });
+    assert_eq!(iter.size_hint(), (4, Some(4)));
+    assert_eq!(iter.next(), Some(Ok(11)));
+    assert_prison_state!(prison, 0, 1, 1, 1, 4);
+    prison.visit_mut(keys[2], |val| {
+        *val = 35;
+        Ok(())
+    })?;
+    assert_eq!(iter.next(), Some(Err(AccessError::ValueDeleted(1, 0))));
+    let grd = prison.guard_mut(keys[3])?;
+    assert_eq!(iter.next(), Some(Ok(36)));
+    assert_eq!(iter.next(), Some(Err(AccessError::ValueAlreadyMutablyReferenced(3))));
+    assert_eq!(iter.next(), None);
+    drop(grd);
+    let sum: u32 = prison.map_refs(keys.iter().copied(), |val| Ok(*val)).filter_map(Result::ok).take(2).sum();
+    assert_eq!(sum, 45);
+    assert_prison_state!(prison, 0, 1, 1, 1, 4);
+    Ok(())
+}
+
+//TEST Prison::split_at_view() / Prison::partitions()
+#[test]
+fn prison_split_views() -> Result<(), AccessError> {
+    let mut prison: Prison<u32> = Prison::with_capacity(5);
+    let mut keys = Vec::new();
+    for val in 0..5 {
+        keys.push(prison.insert(val)?);
+    }
+    prison.remove(keys[3])?;
+    let grd_0 = prison.guard_mut(keys[0])?;
+    mem::forget(grd_0);
+    assert_access_err!(prison.split_at_view(2), AccessError::AccessesOutstanding(1));
+    internal!(prison).access_count.count = 0;
+    internal!(prison).vec[0].refs_or_next = 0;
+    assert_access_err!(prison.split_at_view(6), AccessError::IndexOutOfRange(6));
+    let (mut left, right) = prison.split_at_view(2)?;
+    assert_eq!((left.start(), left.end(), right.start(), right.end()), (0, 2, 2, 5));
+    assert_access_err!(left.get(keys[2]), AccessError::IndexOutOfRange(2));
+    assert_access_err!(right.get(keys[3]), AccessError::ValueDeleted(3, 0));
+    assert!(right.contains(keys[4]) && !right.contains(keys[3]));
+    *left.get_mut(keys[1])? += 10;
+    assert_access_err!(left.split_at(3), AccessError::IndexOutOfRange(3));
+    let (mut mid, mut tail) = right.split_at(4)?;
+    assert_eq!((mid.len(), tail.len()), (2, 1));
+    std::thread::scope(|scope| {
+        scope.spawn(|| mid.iter_mut().for_each(|(key, val)| *val += key.idx() as u32));
+        scope.spawn(|| tail.iter_mut().for_each(|(_, val)| *val = 100));
+    });
+    assert_eq!(prison.clone_val(keys[1])?, 11);
+    assert_eq!(prison.clone_val(keys[2])?, 4);
+    assert_eq!(prison.clone_val(keys[4])?, 100);
+    let views = prison.partitions(3)?;
+    let lens: Vec<usize> = views.iter().map(|view| view.len()).collect();
+    assert_eq!(lens, vec![2, 2, 1]);
+    assert_eq!(views[2].start(), 4);
+    assert!(prison.partitions(0)?.is_empty());
+    assert_eq!(prison.partitions(7)?.iter().filter(|view| view.is_empty()).count(), 2);
+    let hidden = prison.insert_hidden(7)?;
+    let mut views = prison.partitions(1)?;
+    assert_access_err!(views[0].get_mut(hidden), AccessError::ValueHidden(3));
+    assert_eq!(views[0].iter_mut().count(), 4);
+    prison.set_idx_validation(true);
+    let salted = prison.salt_idx(2);
+    assert_access_err!(prison.split_at_view(salted), AccessError::IndexOutOfRange(salted));
+    let (left, right) = prison.split_at_view(2)?;
+    assert_eq!((left.end(), right.start()), (2, 2));
+    assert_eq!(left.start(), 0);
+    assert_access_err!(right.split_at(salted), AccessError::IndexOutOfRange(salted));
+    prison.seal_read_only()?;
+    assert_access_err!(prison.partitions(2), AccessError::PrisonSealed);
+    Ok(())
+}
+
+//TEST Prison::state_digest()
+#[test]
+fn prison_state_digest() -> Result<(), AccessError> {
+    use std::collections::hash_map::DefaultHasher;
+    let prison_a: Prison<u32> = Prison::with_capacity(4);
+    let prison_b: Prison<u32> = Prison::new();
+    let key_a = prison_a.insert(10)?;
+    prison_a.insert(20)?;
+    let key_b = prison_b.insert(10)?;
+    prison_b.insert(20)?;
+    let digest = prison_a.state_digest(DefaultHasher::new())?;
+    assert_eq!(prison_b.state_digest(DefaultHasher::new())?, digest);
+    let grd_a = prison_a.guard_ref(key_a)?;
+    prison_b.pin(key_b)?;
+    prison_b.insert_hidden(30)?;
+    assert_eq!(prison_a.state_digest(DefaultHasher::new())?, digest);
+    assert_eq!(prison_b.state_digest(DefaultHasher::new())?, digest);
+    assert_cell_state!(prison_a, 0, 1, 0, 10);
+    assert_prison_state!(prison_a, 1, 0, IdxD::INVALID, 0, 2);
+    drop(grd_a);
+    prison_a.remove(key_a)?;
+    prison_a.insert(10)?;
+    assert_ne!(prison_a.state_digest(DefaultHasher::new())?, digest);
+    let grd_b = prison_b.guard_mut(key_b)?;
+    assert_access_err!(prison_b.state_digest(DefaultHasher::new()), AccessError::ValueAlreadyMutablyReferenced(0));
+    drop(grd_b);
+    assert_prison_state!(prison_b, 0, 0, IdxD::INVALID, 0, 3);
+    Ok(())
+}
+
+//TEST Prison::visit_each_mut() / Prison::retain()
+#[test]
+fn prison_visit_each_mut_and_retain() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    let keys: Vec<CellKey> = (0..5).map(|i| prison.insert(MyNoCopy(i))).collect::<Result<_, _>>()?;
+    prison.pin(keys[4])?;
+    let grd_1 = prison.guard_ref(keys[1])?;
+    prison.visit_each_mut(|key, val| {
+        if key.idx() == 0 {
+            prison.insert(MyNoCopy(100))?;
+        }
+        val.0 += 10;
+        Ok(())
+    })?;
+    let vals: Vec<usize> = (0..6).map(|idx| prison.visit_ref_idx(idx, |val| Ok(val.0))).collect::<Result<_, _>>()?;
+    assert_eq!(vals, vec![10, 1, 12, 13, 14, 110]);
+    let mut visited = Vec::new();
+    assert_access_err!(
+        prison.visit_each_mut(|key, _| {
+            visited.push(key.idx());
+            if key.idx() == 2 {
+                return Err(AccessError::ValuePinned(2));
+            }
+            Ok(())
+        }),
+        AccessError::ValuePinned(2)
+    );
+    assert_eq!(visited, vec![0, 2]);
+    let removed = prison.retain(|_, val| val.0 % 2 == 1)?;
+    assert_eq!(removed, 3);
+    assert_eq!(prison.num_used(), 3);
+    assert_access_err!(prison.visit_ref(keys[0], |_| Ok(())), AccessError::ValueDeleted(0, 0));
+    assert_access_err!(prison.visit_ref(keys[2], |_| Ok(())), AccessError::ValueDeleted(2, 0));
+    assert_eq!(prison.visit_ref(keys[3], |val| Ok(val.0))?, 13);
+    assert_eq!(prison.visit_ref(keys[4], |val| Ok(val.0))?, 14);
+    drop(grd_1);
+    assert_prison_state!(prison, 0, 1, 5, 3, 6);
+    assert_eq!(prison.insert(MyNoCopy(7))?, CellKey::from_raw_parts(5, 1));
+    assert_eq!(prison.insert(MyNoCopy(8))?, CellKey::from_raw_parts(2, 1));
+    assert_eq!(prison.insert(MyNoCopy(9))?, CellKey::from_raw_parts(0, 1));
+    prison.seal_read_only()?;
+    assert_access_err!(prison.retain(|_, _| true), AccessError::PrisonSealed);
+    assert_access_err!(prison.visit_each_mut(|_, _| Ok(())), AccessError::PrisonSealed);
+    Ok(())
+}
+
+//TEST Prison::visit_batch_mut() / Prison::visit_batch_ref() / Prison::clone_many_vals_batch()
+#[test]
+fn prison_batch_errors() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    let keys: Vec<CellKey> = (0..4).map(|i| prison.insert(MyNoCopy(i))).collect::<Result<_, _>>()?;
+    prison.remove(keys[1])?;
+    let grd_3 = prison.guard_ref(keys[3])?;
+    let batch = [keys[0], keys[1], keys[2], CellKey::from_raw_parts(9, 0), keys[3], keys[0]];
+    let err = prison
+        .visit_batch_mut(&batch, |key, val| {
+            val.0 += 10;
+            if key.idx() == 2 {
+                return Err(AccessError::ValuePinned(2));
+            }
+            Ok(())
+        })
+        .unwrap_err();
+    assert_eq!(
+        err.into_errors(),
+        vec![
+            (1, AccessError::ValueDeleted(1, 0)),
+            (2, AccessError::ValuePinned(2)),
+            (3, AccessError::IndexOutOfRange(9)),
+            (4, AccessError::ValueStillImmutablyReferenced(3)),
+        ]
+    );
+    assert_eq!(prison.visit_ref(keys[0], |val| Ok(val.0))?, 20);
+    assert_eq!(prison.visit_ref(keys[2], |val| Ok(val.0))?, 12);
+    assert_prison_state!(prison, 1, 1, 1, 1, 4);
+    let mut visited = Vec::new();
+    let err = prison
+        .visit_batch_ref(&batch, |key, _| {
+            visited.push(key.idx());
+            Ok(())
+        })
+        .unwrap_err();
+    assert_eq!(visited, vec![0, 2, 3, 0]);
+    assert_eq!(err.len(), 2);
+    assert_eq!(err.first(), (1, &AccessError::ValueDeleted(1, 0)));
+    assert_eq!(AccessError::from(err), AccessError::ValueDeleted(1, 0));
+    assert!(prison.visit_batch_ref(&[keys[0], keys[3]], |_, _| Ok(())).is_ok());
+    drop(grd_3);
+    assert_prison_state!(prison, 0, 1, 1, 1, 4);
+    Ok(())
+}
+
+//TEST Prison::keys() / Prison::contains_key()
+#[test]
+fn prison_keys_and_contains_key() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    assert!(prison.keys().is_empty());
+    let keys: Vec<CellKey> = (0..5).map(|i| prison.insert(MyNoCopy(i))).collect::<Result<_, _>>()?;
+    prison.remove(keys[1])?;
+    prison.remove(keys[3])?;
+    let key_3_b = prison.insert(MyNoCopy(30))?;
+    assert_eq!(prison.keys(), vec![keys[0], keys[2], key_3_b, keys[4]]);
+    prison.visit_mut(keys[0], |_| {
+        assert!(prison.contains_key(keys[0]));
+        assert_eq!(prison.keys().len(), 4);
+        Ok(())
+    })?;
+    assert!(!prison.contains_key(keys[1]));
+    assert!(!prison.contains_key(keys[3]));
+    assert!(prison.contains_key(key_3_b));
+    assert!(!prison.contains_key(CellKey::from_raw_parts(5, 0)));
+    assert!(prison.keys().into_iter().all(|key| prison.contains_key(key)));
+    Ok(())
+}
+
+//TEST Prison::set_depth_limit() / Prison::max_depth()
+#[test]
+fn prison_visit_depth_limit() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    assert_eq!(prison.depth_limit(), None);
+    prison.visit_ref(key_0, |_| {
+        assert_eq!(prison.visit_depth(), 1);
+        prison.visit_many_ref(&[key_0, key_1], |_| {
+            assert_eq!(prison.visit_depth(), 2);
+            prison.visit_each_mut(|_, _| Ok(())).or(Ok(()))
+        })
+    })?;
+    assert_eq!(prison.visit_depth(), 0);
+    assert_eq!(prison.max_depth(), 2);
+    prison.set_depth_limit(Some(2));
+    assert_eq!(prison.depth_limit(), Some(2));
+    let result = prison.visit_ref(key_0, |_| {
+        prison.visit_ref(key_1, |_| {
+            assert_prison_state!(prison, 2, 0, IdxD::INVALID, 0, 2);
+            let inner = prison.visit_ref(key_1, |_| Ok(()));
+            assert_eq!(inner, Err(AccessError::VisitDepthExceeded(2)));
+            assert_access_err!(prison.visit_mut(key_1, |_| Ok(())), AccessError::VisitDepthExceeded(2));
+            assert_cell_state!(prison, 0, 1, 0, MyNoCopy(0));
+            let grd_0 = prison.guard_ref(key_0)?;
+            Ok(grd_0.0)
+        })
+    });
+    assert_eq!(result, Ok(0));
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 2);
+    let panicked = std::panic::catch_unwind(|| prison.visit_ref(key_0, |_| -> Result<(), AccessError> { panic!("expected") }));
+    assert!(panicked.is_err());
+    assert_eq!(prison.visit_depth(), 0);
+    prison.reset_max_depth();
+    assert_eq!(prison.max_depth(), 0);
+    prison.set_depth_limit(Some(0));
+    assert_access_err!(prison.visit_ref(key_0, |_| Ok(())), AccessError::VisitDepthExceeded(0));
+    assert_access_err!(prison.retain(|_, _| true), AccessError::VisitDepthExceeded(0));
+    prison.set_depth_limit(None);
+    assert_eq!(prison.retain(|_, _| true)?, 0);
+    assert_eq!(prison.max_depth(), 1);
+    Ok(())
+}
+
+//TEST Prison::insert_many() / Prison::insert_many_at()
+#[test]
+fn prison_insert_many() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(2);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    prison.remove(key_0)?;
+    let keys = prison.insert_many((10..14).map(MyNoCopy))?;
+    assert_eq!(keys.iter().map(|key| key.idx()).collect::<Vec<_>>(), vec![0, 2, 3, 4]);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 5);
+    assert_cell_state!(prison, 0, 0, 1, MyNoCopy(10));
+    assert_cell_state!(prison, 4, 0, 1, MyNoCopy(13));
+    assert!(prison.vec_cap() >= 5);
+    assert!(prison.insert_many(Vec::new())?.is_empty());
+    let grd_1 = prison.guard_ref(key_1)?;
+    let spare = prison.vec_cap() - prison.vec_len();
+    assert_eq!(prison.insert_many((0..spare + 1).map(MyNoCopy)), Err(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced));
+    assert_eq!(prison.num_used(), 5);
+    assert_eq!(prison.insert_many((0..spare).map(MyNoCopy))?.len(), spare);
+    drop(grd_1);
+    for key in prison.keys() {
+        prison.remove(key)?;
+    }
+    let keys = prison.insert_many_at([(1, MyNoCopy(21)), (0, MyNoCopy(20))])?;
+    assert_eq!(keys.iter().map(|key| key.idx()).collect::<Vec<_>>(), vec![1, 0]);
+    let len = prison.vec_len();
+    assert_eq!(
+        prison.insert_many_at([(2, MyNoCopy(22)), (len, MyNoCopy(99)), (len + 2, MyNoCopy(100))]),
+        Err(AccessError::IndexOutOfRange(len + 2))
+    );
+    assert_eq!(prison.num_used(), 2);
+    assert_eq!(prison.vec_len(), len + 1);
+    assert_eq!(prison.insert_many_at([(0, MyNoCopy(0))]), Err(AccessError::IndexIsNotFree(0)));
+    prison.seal_read_only()?;
+    assert_eq!(prison.insert_many([MyNoCopy(0)]), Err(AccessError::PrisonSealed));
+    assert_eq!(prison.insert_many_at([(2, MyNoCopy(0))]), Err(AccessError::PrisonSealed));
+    Ok(())
+}
+
+//TEST Prison::insert_hidden() / Prison::publish() / Prison::visit_hidden_mut()
+#[test]
+fn prison_hidden_values() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(1);
+    let key_0 = prison.insert_hidden(MyNoCopy(0))?;
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 1);
+    assert_cell_state!(prison, 0, Refs::HIDDEN, 0, MyNoCopy(0));
+    assert_access_err!(prison.visit_ref(key_0, |_| Ok(())), AccessError::ValueHidden(0));
+    assert_access_err!(prison.visit_mut(key_0, |_| Ok(())), AccessError::ValueHidden(0));
+    assert!(prison.guard_ref_idx(0).is_err());
+    assert_eq!(prison.state(key_0)?, CellState::Live { gen: 0, refs: RefKind::Hidden });
+    assert!(prison.is_hidden(key_0));
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    prison.visit_hidden_mut(key_0, |val_0| {
+        assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 2);
+        assert_eq!(prison.visit_hidden_mut(key_0, |_| Ok(())), Err(AccessError::ValueAlreadyMutablyReferenced(0)));
+        val_0.0 = 10;
+        Ok(())
+    })?;
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 2);
+    assert_cell_state!(prison, 0, Refs::HIDDEN, 0, MyNoCopy(10));
+    assert_eq!(prison.visit_each_mut(|key, _| {
+        assert_eq!(key, key_1);
+        Ok(())
+    }), Ok(()));
+    assert!(internal!(prison).verify_idle().is_ok());
+    prison.seal_read_only()?;
+    assert_access_err!(prison.visit_ref(key_0, |_| Ok(())), AccessError::ValueHidden(0));
+    assert_access_err!(prison.publish(key_0), AccessError::PrisonSealed);
+    let mut prison = prison;
+    prison.unseal();
+    prison.publish(key_0)?;
+    prison.publish(key_0)?;
+    assert!(!prison.is_hidden(key_0));
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(10));
+    prison.visit_hidden_mut(key_0, |val_0| {
+        val_0.0 = 11;
+        Ok(())
+    })?;
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(11));
+    let key_2 = prison.insert_hidden(MyNoCopy(2))?;
+    assert_eq!(prison.remove(key_2)?, MyNoCopy(2));
+    assert!(!prison.is_hidden(key_2));
+    assert_access_err!(prison.publish(key_2), AccessError::ValueDeleted(2, 0));
+    Ok(())
+}
+
+//TEST Prison::insert_typed() / TypedKey
+#[test]
+fn prison_typed_keys() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert_typed(MyNoCopy(0))?;
+    let key_1: TypedKey<MyNoCopy> = TypedKey::from_key(prison.insert(MyNoCopy(1))?);
+    assert_eq!(mem::size_of::<TypedKey<MyNoCopy>>(), mem::size_of::<CellKey>());
+    assert_eq!(key_0.key(), CellKey::from_raw_parts(0, 0));
+    assert_eq!(key_1.idx(), 1);
+    let copied = key_0;
+    assert_eq!(copied, key_0);
+    assert_ne!(key_0, key_1);
+    prison.visit_mut_typed(key_0, |val_0| {
+        val_0.0 += 10;
+        Ok(())
+    })?;
+    assert_eq!(prison.visit_ref_typed(key_0, |val_0| Ok(val_0.0))?, 10);
+    assert_eq!(prison.remove_typed(key_1)?, MyNoCopy(1));
+    assert_access_err!(prison.visit_ref_typed(key_1, |_| Ok(())), AccessError::ValueDeleted(1, 0));
+    assert_eq!(format!("{:?}", key_0), "TypedKey { idx: 0, gen: 0 }");
+    let untyped: CellKey = key_0.into();
+    assert_eq!(untyped, key_0.key());
+    Ok(())
+}
+
+//TEST Prison::insert_keyed()
+#[test]
+fn prison_insert_keyed() -> Result<(), AccessError> {
+    struct Node {
+        key: CellKey,
+        val: u32,
+    }
+    crate::prison_value!(Node { key } => NodeKey);
+    let prison: Prison<Node> = Prison::with_capacity(2);
+    let key_0 = prison.insert_keyed(Node { key: CellKey::from_raw_parts(9, 9), val: 10 })?;
+    let key_1 = prison.insert_keyed(Node { key: CellKey::from_raw_parts(9, 9), val: 20 })?;
+    prison.remove(key_0.into())?;
+    let key_2 = prison.insert_keyed(Node { key: CellKey::from_raw_parts(9, 9), val: 30 })?;
+    assert_eq!(key_2, NodeKey(CellKey::from_raw_parts(0, 1)));
+    for key in [key_1, key_2] {
+        prison.visit_ref(key.into(), |node| {
+            assert_eq!(node.typed_key(), key);
+            Ok(())
+        })?;
+    }
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 2);
+    prison.visit_ref(key_1.into(), |_| {
+        assert_access_err!(
+            prison.insert_keyed(Node { key: CellKey::from_raw_parts(9, 9), val: 40 }),
+            AccessError::InsertAtMaxCapacityWhileAValueIsReferenced
+        );
+        Ok(())
+    })?;
+    prison.remove(key_2.into())?;
+    prison.set_depth_limit(Some(1));
+    prison.visit_ref(key_1.into(), |_| {
+        let key_3 = prison.insert_keyed(Node { key: CellKey::from_raw_parts(9, 9), val: 50 })?;
+        assert_eq!(key_3, NodeKey(CellKey::from_raw_parts(0, 2)));
+        assert_access_err!(prison.visit_ref(key_3.into(), |_| Ok(())), AccessError::VisitDepthExceeded(1));
+        Ok(())
+    })?;
+    prison.set_depth_limit(None);
+    prison.visit_ref(NodeKey(CellKey::from_raw_parts(0, 2)).into(), |node| {
+        assert_eq!(node.key, CellKey::from_raw_parts(0, 2));
+        assert_eq!(node.val, 50);
+        Ok(())
+    })?;
+    Ok(())
+}
+
+//TEST Prison::get_or_insert_with_key()
+#[test]
+fn prison_get_or_insert_with_key() -> Result<(), AccessError> {
+    struct Node {
+        key: CellKey,
+        val: u32,
+    }
+    crate::prison_value!(Node { key } => NodeKey);
+    let unset = CellKey::from_raw_parts(9, 9);
+    let prison: Prison<Node> = Prison::with_capacity(3);
+    let key_0 = prison.insert_keyed(Node { key: unset, val: 10 })?;
+    let (key, grd) = prison.get_or_insert_with_key(key_0, || Node { key: unset, val: 20 })?;
+    assert_eq!((key, grd.typed_key(), grd.val), (key_0, key_0, 10));
+    assert_access_err!(
+        prison.get_or_insert_with_key(key_0, || Node { key: unset, val: 20 }),
+        AccessError::ValueAlreadyMutablyReferenced(0)
+    );
+    drop(grd);
+    prison.remove(key_0.into())?;
+    let (key_1, grd) = prison.get_or_insert_with_key(key_0, || Node { key: unset, val: 30 })?;
+    assert_eq!(key_1, NodeKey(CellKey::from_raw_parts(0, 1)));
+    assert_eq!((grd.typed_key(), grd.val), (key_1, 30));
+    drop(grd);
+    let (key_2, grd) = prison.get_or_insert_with_key(key_0, || Node { key: unset, val: 40 })?;
+    assert_eq!(key_2, NodeKey(CellKey::from_raw_parts(1, 1)));
+    assert_eq!((grd.typed_key(), grd.val), (key_2, 40));
+    drop(grd);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 2);
+    Ok(())
+}
+
+//TEST #[derive(PrisonValue)]
+#[cfg(feature = "derive")]
+#[test]
+fn prison_derive_prison_value() -> Result<(), AccessError> {
+    #[derive(PrisonValue)]
+    #[prison_value(key = EnemyId)]
+    struct Enemy {
+        #[prison_key]
+        id: CellKey,
+        target: CellKey,
+    }
+    #[derive(PrisonValue)]
+    struct Tag(&'static str, CellKey);
+    #[derive(PrisonValue)]
+    struct Wrapper<T: Copy>
+    where
+        T: Default,
+    {
+        val: T,
+        key: CellKey,
+    }
+    let unset = CellKey::from_raw_parts(9, 9);
+    let enemies: Prison<Enemy> = Prison::new();
+    let id_0: EnemyId = enemies.insert_keyed(Enemy { id: unset, target: unset })?;
+    let id_1 = enemies.insert_keyed(Enemy { id: unset, target: id_0.into() })?;
+    assert_eq!(id_1, EnemyId(CellKey::from_raw_parts(1, 0)));
+    enemies.visit_ref(id_1.into(), |enemy| {
+        assert_eq!((enemy.key(), enemy.typed_key()), (id_1.0, id_1));
+        assert_eq!(enemy.target, id_0.0);
+        Ok(())
+    })?;
+    let tags: Prison<Tag> = Prison::new();
+    let tag_0: TagKey = tags.insert_keyed(Tag("boss", unset))?;
+    tags.remove(tag_0.into())?;
+    let (tag_1, grd) = tags.get_or_insert_with_key(tag_0, || Tag("minion", unset))?;
+    assert_eq!((grd.0, grd.1, tag_1), ("minion", CellKey::from_raw_parts(0, 1), TagKey(CellKey::from_raw_parts(0, 1))));
+    drop(grd);
+    let wrappers: Prison<Wrapper<u8>> = Prison::new();
+    let key_0: WrapperKey = wrappers.insert_keyed(Wrapper { val: 7, key: unset })?;
+    assert_eq!(wrappers.visit_ref(key_0.into(), |wrapper| Ok((wrapper.val, wrapper.typed_key())))?, (7, key_0));
+    assert_eq!(format!("{:?}", key_0), "WrapperKey(CellKey { idx: 0, gen: 0 })");
+    Ok(())
+}
+
+//TEST Prison::maintain_keys() / SelfKeyed
+#[test]
+fn prison_maintain_keys() -> Result<(), AccessError> {
+    let prison: Prison<SelfKeyed<u32>> = Prison::with_capacity(5);
+    let key_0 = prison.insert(SelfKeyed::new(0))?;
+    assert_eq!(prison.visit_ref(key_0, |val| Ok(val.key()))?, CellKey::from_raw_parts(usize::MAX, 0));
+    let grd = prison.guard_ref(key_0)?;
+    assert_access_err!(prison.maintain_keys(), AccessError::AccessesOutstanding(1));
+    drop(grd);
+    prison.maintain_keys()?;
+    let check_key = |key: CellKey| {
+        prison.visit_ref(key, |val| {
+            assert_eq!(val.key(), key);
+            Ok(())
+        })
+    };
+    check_key(key_0)?;
+    let key_1 = prison.insert(SelfKeyed::new(1))?;
+    let key_2 = prison.insert(SelfKeyed::new(2))?;
+    prison.remove(key_0)?;
+    let key_0 = prison.insert_at(0, SelfKeyed::new(3))?;
+    let key_1 = prison.overwrite(key_1.idx(), SelfKeyed::new(4))?;
+    for key in [key_0, key_1, key_2] {
+        check_key(key)?;
+    }
+    let recycled = prison.recycle(|val| **val == 4, |val| **val = 5)?;
+    assert_eq!(recycled.len(), 1);
+    check_key(recycled[0])?;
+    prison.remove(key_0)?;
+    let mut moved = Vec::new();
+    assert_eq!(prison.compact(|_, new_key| moved.push(new_key))?, 2);
+    for key in moved {
+        check_key(key)?;
+    }
+    let cloned = prison.filtered_clone(|_, _| true);
+    let key_3 = cloned.insert(SelfKeyed::new(6))?;
+    cloned.visit_ref(key_3, |val| {
+        assert_eq!(val.key(), key_3);
+        Ok(())
+    })?;
+    Ok(())
+}
+
+//TEST PartialEq for Prison / Hash + Ord for CellKey
+#[test]
+fn prison_partial_eq_and_key_ord() -> Result<(), AccessError> {
+    use std::collections::{BTreeSet, HashMap};
+    let prison_a: Prison<u32> = Prison::with_capacity(4);
+    let prison_b: Prison<u32> = Prison::new();
+    let key_0 = prison_a.insert(10)?;
+    let key_1 = prison_a.insert(11)?;
+    prison_a.insert(12)?;
+    prison_a.remove(CellKey::from_raw_parts(2, 0))?;
+    prison_b.insert(10)?;
+    prison_b.insert(11)?;
+    assert!(prison_a == prison_b);
+    let grd_1 = prison_a.guard_ref(key_1)?;
+    assert!(prison_a == prison_b);
+    drop(grd_1);
+    prison_b.overwrite(1, 11)?;
+    assert!(prison_a != prison_b);
+    let prison_c = prison_a.clone();
+    assert!(prison_a == prison_c);
+    prison_c.insert(12)?;
+    assert!(prison_a != prison_c);
+    let grd_0 = prison_a.guard_mut(key_0)?;
+    assert!(prison_a != prison_a.filtered_clone(|_, _| false));
+    drop(grd_0);
+    let mut keys = vec![CellKey::from_raw_parts(2, 0), CellKey::from_raw_parts(1, 3), CellKey::from_raw_parts(1, 1)];
+    keys.sort();
+    assert_eq!(keys, vec![CellKey::from_raw_parts(1, 1), CellKey::from_raw_parts(1, 3), CellKey::from_raw_parts(2, 0)]);
+    let set: BTreeSet<TypedKey<u32>> = keys.iter().rev().map(|key| TypedKey::from_key(*key)).collect();
+    assert_eq!(set.iter().next().map(|typed| typed.key()), Some(keys[0]));
+    let mut names: HashMap<CellKey, &str> = HashMap::new();
+    names.insert(key_0, "zero");
+    names.insert(key_1, "one");
+    assert_eq!(names.get(&key_1), Some(&"one"));
+    Ok(())
+}
+
+//TEST From<Vec> / FromIterator for Prison and Prison::into_vec()
+#[test]
+fn prison_from_vec_and_into_vec() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::from(vec![10, 11, 12, 13]);
+    assert_eq!(prison.num_used(), 4);
+    assert_eq!(prison.vec_cap(), 4);
+    assert_eq!(prison.clone_val(CellKey::from_raw_parts(3, 0))?, 13);
+    let key_1 = CellKey::from_raw_parts(1, 0);
+    prison.remove(key_1)?;
+    let key_4 = prison.insert_hidden(14)?;
+    let vals = prison.into_vec();
+    assert_eq!(vals, vec![(CellKey::from_raw_parts(0, 0), 10), (key_4, 14), (CellKey::from_raw_parts(2, 0), 12), (CellKey::from_raw_parts(3, 0), 13)]);
+    assert_eq!(key_4, CellKey::from_raw_parts(1, 1));
+    let prison: Prison<String> = (0..3).map(|num| num.to_string()).collect();
+    assert_eq!(prison.clone_val(CellKey::from_raw_parts(2, 0))?, "2");
+    let empty: Prison<String> = std::iter::empty().collect();
+    assert_eq!(empty.num_used(), 0);
+    assert!(empty.into_vec().is_empty());
+    Ok(())
+}
+
+//TEST Prison::swap_contents()
+#[test]
+fn prison_swap_contents() -> Result<(), AccessError> {
+    let prison_a: Prison<u32> = Prison::with_capacity(3);
+    let mut prison_b: Prison<u32> = Prison::new();
+    let key_0 = prison_a.insert(10)?;
+    let key_1 = prison_a.insert(11)?;
+    prison_a.remove(key_0)?;
+    let key_b = prison_b.insert(20)?;
+    prison_a.swap_contents(&prison_b)?;
+    assert_prison_state!(prison_a, 0, 0, IdxD::INVALID, 0, 1);
+    assert_prison_state!(prison_b, 0, 1, 0, 1, 2);
+    assert_cell_state!(prison_b, 1, 0, 0, 11);
+    assert_eq!(prison_a.clone_val(key_b)?, 20);
+    assert_eq!(prison_b.insert(12)?, CellKey::from_raw_parts(0, 1));
+    assert_eq!(prison_b.vec_cap(), 3);
+    prison_a.swap_contents(&prison_a)?;
+    let grd_1 = prison_b.guard_ref(key_1)?;
+    assert_access_err!(prison_a.swap_contents(&prison_b), AccessError::AccessesOutstanding(1));
+    drop(grd_1);
+    prison_b.seal_read_only()?;
+    assert_access_err!(prison_a.swap_contents(&prison_b), AccessError::PrisonSealed);
+    prison_b.unseal();
+    prison_b.swap_contents(&prison_a)?;
+    assert_eq!(prison_b.clone_val(key_b)?, 20);
+    assert!(prison_a.clone_val(key_1).is_ok());
+    Ok(())
+}
+
+//TEST Prison::clone_all() / Clone for Prison
+#[test]
+fn prison_clone_all() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(5);
+    let key_0 = prison.insert(10)?;
+    let key_1 = prison.insert(11)?;
+    let key_2 = prison.insert(12)?;
+    let key_3 = prison.insert(13)?;
+    let key_4 = prison.insert_hidden(14)?;
+    prison.remove(key_0)?;
+    prison.remove(key_2)?;
+    prison.pin(key_3)?;
+    let grd_1 = prison.guard_ref(key_1)?;
+    let clone = prison.clone_all()?;
+    assert_prison_state!(clone, 0, 1, 2, 2, 5);
+    assert_free_state!(clone, 2, IdxD::INVALID, 0);
+    assert_free_state!(clone, 0, 2, IdxD::INVALID);
+    assert_cell_state!(clone, 1, 0, 0, 11);
+    assert_cell_state!(clone, 4, Refs::HIDDEN, 0, 14);
+    assert_access_err!(clone.remove(key_3), AccessError::ValuePinned(3));
+    clone.visit_mut(key_1, |val| {
+        *val += 1;
+        Ok(())
+    })?;
+    assert_eq!(*grd_1, 11);
+    drop(grd_1);
+    assert_eq!(prison.insert(20)?, clone.insert(20)?);
+    assert_eq!(prison.insert(21)?, clone.insert(21)?);
+    let grd_1 = prison.guard_mut(key_1)?;
+    assert_access_err!(prison.clone_all(), AccessError::ValueAlreadyMutablyReferenced(1));
+    drop(grd_1);
+    let copy = clone.clone();
+    assert_eq!(copy.clone_val(key_1)?, 12);
+    clone.publish(key_4)?;
+    assert_access_err!(copy.clone_val(key_4), AccessError::ValueHidden(4));
+    Ok(())
+}
+
+//TEST Prison::filtered_clone()
+#[test]
+fn prison_filtered_clone() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(8);
+    let key_0 = prison.insert(0)?;
+    let key_1 = prison.insert(1)?;
+    let key_2 = prison.insert(2)?;
+    let key_3 = prison.insert(3)?;
+    let key_4 = prison.insert(4)?;
+    prison.reserve_index_range(5..6)?;
+    let key_5 = prison.insert_at(5, 5)?;
+    prison.remove(key_1)?;
+    prison.pin(key_2)?;
+    prison.pin(key_3)?;
+    let group = prison.insert_group([6, 7])?;
+    let grd_0 = prison.guard_mut(key_0)?;
+    let clone = prison.filtered_clone(|key, val| key.idx() != 3 && *val != 6);
+    drop(grd_0);
+    assert_prison_state!(clone, 0, 1, 1, 2, 7);
+    assert_cell_state!(clone, 0, 0, 0, 0);
+    assert_cell_state!(clone, 2, 0, 0, 2);
+    assert_cell_state!(clone, 5, 0, 0, 5);
+    assert_cell_state!(clone, 6, 0, 1, 7);
+    assert_free_state!(clone, 1, IdxD::INVALID, 3);
+    assert_free_state!(clone, 3, 1, IdxD::INVALID);
+    assert_eq!(clone.clone_val(key_4)?, 4);
+    assert_eq!(clone.clone_val(key_5)?, 5);
+    assert_access_err!(clone.clone_val(key_3), AccessError::ValueDeleted(3, 0));
+    assert!(clone.is_pinned(key_2));
+    assert!(!clone.is_pinned(key_3));
+    assert_eq!(clone.group_keys(group)?.as_slice(), &[CellKey::from_raw_parts(6, 1)]);
+    assert!(clone.is_index_reserved(5));
+    assert_eq!(clone.insert(8)?.idx(), 1);
+    assert_eq!(clone.insert(9)?.idx(), 3);
+    assert_eq!(clone.insert(10)?.idx(), 7);
+    let clone_2 = prison.filtered_clone(|key, _| key.idx() != 5);
+    assert_prison_state!(clone_2, 0, 1, IdxD::INVALID, 1, 7);
+    assert_eq!(clone_2.insert(11)?.idx(), 7);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 7);
+    Ok(())
+}
+
+//TEST Prison::read()
+#[test]
+fn prison_read() -> Result<(), AccessError> {
+    let prison: Prison<u64> = Prison::with_capacity(3);
+    let key_0 = prison.insert(10)?;
+    let key_1 = prison.insert(20)?;
+    prison.remove(key_1)?;
+    let grd_0 = prison.guard_ref(key_0)?;
+    assert_eq!(prison.read(key_0)?, 10);
+    assert_eq!(prison.read_idx(0)?, 10);
+    assert_cell_state!(prison, 0, 1, 0, 10);
+    assert_prison_state!(prison, 1, 1, 1, 1, 2);
+    drop(grd_0);
+    assert_access_err!(prison.read(key_1), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.read_idx(1), AccessError::ValueDeleted(1, 0));
+    assert_access_err!(prison.read(CellKey::from_raw_parts(2, 0)), AccessError::IndexOutOfRange(2));
+    assert_access_err!(prison.read_idx(2), AccessError::IndexOutOfRange(2));
+    let grd_0 = prison.guard_mut(key_0)?;
+    assert_access_err!(prison.read(key_0), AccessError::ValueAlreadyMutablyReferenced(0));
+    assert_access_err!(prison.read_idx(0), AccessError::ValueAlreadyMutablyReferenced(0));
+    drop(grd_0);
+    assert_prison_state!(prison, 0, 1, 1, 1, 2);
+    Ok(())
+}
+
+//TEST PrisonValueMut::unguard_and_remove()
+#[test]
+fn prison_unguard_and_remove() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(3);
+    let key_0 = prison.insert(10)?;
+    let key_1 = prison.insert(20)?;
+    let grd_0 = prison.guard_mut(key_0)?;
+    assert_prison_state!(prison, 1, 0, IdxD::INVALID, 0, 2);
+    assert_eq!(grd_0.unguard_and_remove()?, 10);
+    assert_prison_state!(prison, 0, 1, 0, 1, 2);
+    assert_free_state!(prison, 0, IdxD::INVALID, IdxD::INVALID);
+    prison.pin(key_1)?;
+    let grd_1 = prison.guard_mut_idx(1)?;
+    assert_eq!(grd_1.unguard_and_remove(), Err(AccessError::ValuePinned(1)));
+    assert_cell_state!(prison, 1, 0, 0, 20);
+    prison.unpin(key_1)?;
+    for grd in prison.iter_mut(IterPolicy::Skip) {
+        let (key, grd) = grd?;
+        assert_eq!(key, key_1);
+        assert_eq!(grd.unguard_and_remove()?, 20);
+    }
+    assert_eq!(prison.num_used(), 0);
+    let key_2 = prison.insert(30)?;
+    let grd_2 = prison.guard_mut(key_2)?;
+    let ticket_2 = prison.remove_when_free(key_2)?;
+    assert_eq!(grd_2.unguard_and_remove()?, 30);
+    assert!(!prison.removal_pending(ticket_2));
+    assert_prison_state!(prison, 0, 2, 1, 2, 2);
+    Ok(())
+}
+
+//TEST Prison::entry_mut() / PrisonEntryMut
+#[test]
+fn prison_entry_mut() -> Result<(), AccessError> {
+    let prison: Prison<Vec<u32>> = Prison::with_capacity(3);
+    let key_0 = prison.insert(vec![1, 2])?;
+    prison.remove(key_0)?;
+    let key_0 = prison.insert(vec![3, 4])?;
+    let key_1 = prison.insert(vec![5])?;
+    let mut entry = prison.entry_mut(key_0)?;
+    assert_eq!((entry.key(), entry.idx(), entry.gen()), (key_0, 0, 1));
+    assert_access_err!(prison.entry_mut(key_0), AccessError::ValueAlreadyMutablyReferenced(0));
+    assert_eq!((*entry).remove(0), 3);
+    entry.push(6);
+    assert_prison_state!(prison, 1, 1, IdxD::INVALID, 0, 2);
+    assert_eq!(entry.remove()?, vec![4, 6]);
+    assert_prison_state!(prison, 0, 2, 0, 1, 2);
+    prison.pin(key_1)?;
+    let entry = prison.guard_mut(key_1)?.into_entry();
+    assert_eq!(entry.key(), key_1);
+    assert_access_err!(entry.remove(), AccessError::ValuePinned(1));
+    assert_eq!(prison.num_used(), 1);
+    let grd = prison.entry_mut(key_1)?.into_guard();
+    assert_eq!(*grd, vec![5]);
+    Ok(())
+}
+
+//TEST Prison::visit_*() returning values
+#[test]
+fn prison_visit_return_value() -> Result<(), AccessError> {
+    let prison: Prison<String> = Prison::new();
+    let key_0 = prison.insert(String::from("Hello"))?;
+    let len = prison.visit_ref(key_0, |val| Ok(val.len()))?;
+    assert_eq!(len, 5);
+    let popped = prison.visit_mut_idx(0, |val| Ok(val.pop()))?;
+    assert_eq!(popped, Some('o'));
+    let nested = prison.visit_ref_idx(0, |val_a| prison.visit_ref(key_0, |val_b| Ok(val_a == val_b)))?;
+    assert!(nested);
+    let handle = prison.handle(key_0)?;
+    assert_eq!(prison.visit_handle_mut(handle, |val| Ok(val.clone()))?, "Hell");
+    assert_eq!(prison.visit_mut(key_0, |_| Err::<usize, _>(AccessError::PrisonSealed)), Err(AccessError::PrisonSealed));
+    assert_cell_state!(prison, 0, 0, 0, "Hell");
+    let jail: JailCell<u32> = JailCell::new(3);
+    assert_eq!(jail.visit_mut(|val| {
+        *val *= 2;
+        Ok(*val)
+    })?, 6);
+    Ok(())
+}
+
+//TEST Prison::visit_*_with()
+#[test]
+fn prison_visit_with() -> Result<(), AccessError> {
+    #[derive(Debug, PartialEq)]
+    enum AppError {
+        Access(AccessError),
+        Odd(u32),
+    }
+    impl From<AccessError> for AppError {
+        fn from(err: AccessError) -> Self {
+            AppError::Access(err)
+        }
+    }
+    let prison: Prison<u32> = Prison::new();
+    let key_0 = prison.insert(4)?;
+    let key_1 = prison.insert(5)?;
+    let halve = |val: &mut u32| {
+        if *val % 2 == 1 {
+            return Err(AppError::Odd(*val));
+        }
+        *val /= 2;
+        Ok(*val)
+    };
+    assert_eq!(prison.visit_mut_with(key_0, halve), Ok(2));
+    assert_eq!(prison.visit_mut_idx_with(1, halve), Err(AppError::Odd(5)));
+    assert_cell_state!(prison, 0, 0, 0, 2);
+    assert_cell_state!(prison, 1, 0, 0, 5);
+    let res = prison.visit_ref_with(key_0, |_| prison.visit_mut_with(key_0, halve));
+    assert_eq!(res, Err(AppError::Access(AccessError::ValueStillImmutablyReferenced(0))));
+    prison.remove(key_1)?;
+    assert_eq!(
+        prison.visit_ref_with(key_1, |val| Ok::<u32, AppError>(*val)),
+        Err(AppError::Access(AccessError::ValueDeleted(1, 0)))
+    );
+    assert_eq!(prison.visit_ref_idx_with(0, |val| Ok::<u32, AppError>(*val)), Ok(2));
+    assert_prison_state!(prison, 0, 1, 1, 1, 2);
+    let jail: JailCell<u32> = JailCell::new(8);
+    assert_eq!(jail.visit_mut_with(halve), Ok(4));
+    assert_eq!(
+        jail.visit_ref_with(|_| jail.visit_mut_with(halve)),
+        Err(AppError::Access(AccessError::ValueStillImmutablyReferenced(0)))
+    );
+    Ok(())
+}
+
+//TEST Prison::generation_stats() / Prison::oldest_generation()
+#[test]
+fn prison_generation_stats() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(4);
+    let stats = prison.generation_stats();
+    assert_eq!((stats.count, stats.min, stats.max, stats.next), (0, None, None, 0));
+    assert_eq!(prison.oldest_generation(), None);
+    let key_0 = prison.insert(10)?;
+    let mut key_1 = prison.insert(11)?;
+    let key_2 = prison.insert(12)?;
+    for val in 0..4 {
+        prison.remove(key_1)?;
+        key_1 = prison.insert(val)?;
+    }
+    prison.remove(key_0)?;
+    assert_prison_state!(prison, 0, 4, 0, 1, 3);
+    let stats = prison.generation_stats();
+    assert_eq!((stats.count, stats.min, stats.max, stats.next), (2, Some(0), Some(4), 4));
+    assert_eq!(&stats.buckets[..4], &[1, 0, 0, 1]);
+    assert_eq!(stats.buckets.iter().sum::<usize>(), 2);
+    assert_eq!(stats.remaining(), GenerationStats::MAX_GENERATION - 4);
+    assert_eq!(GenerationStats::bucket_of(0), 0);
+    assert_eq!(GenerationStats::bucket_of(1), 1);
+    assert_eq!(GenerationStats::bucket_of(GenerationStats::MAX_GENERATION), GenerationStats::BUCKETS - 1);
+    assert_eq!(prison.oldest_generation(), Some(0));
+    prison.remove(key_2)?;
+    assert_eq!(prison.oldest_generation(), Some(4));
+    assert_eq!(prison.generation_stats().min, Some(4));
+    Ok(())
+}
+
+//TEST Prison::most_recycled()
+#[cfg(feature = "recycle_stats")]
+#[test]
+fn prison_most_recycled() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::new();
+    let key_0 = prison.insert(0)?;
+    let mut key_1 = prison.insert(1)?;
+    prison.insert(2)?;
+    assert!(prison.most_recycled(3).is_empty());
+    for i in 0..3 {
+        prison.remove(key_1)?;
+        key_1 = prison.insert(i)?;
+    }
+    prison.overwrite(2, 20)?;
+    prison.recycle(|val| *val == 20, |val| *val = 0)?;
+    assert_eq!(prison.most_recycled(3), vec![(1, 3), (2, 2)]);
+    assert_eq!(prison.most_recycled(1), vec![(1, 3)]);
+    assert_eq!(prison.recycle_count(0)?, 0);
+    assert_eq!(prison.recycle_count(3), Err(AccessError::IndexOutOfRange(3)));
+    prison.remove(key_0)?;
+    let clone = prison.filtered_clone(|_, _| true);
+    assert_eq!(clone.most_recycled(3), vec![(1, 3), (2, 2), (0, 1)]);
+    let mapped = clone.map_into(|_, val| val);
+    assert_eq!(mapped.most_recycled(3), vec![(1, 3), (2, 2), (0, 1)]);
+    prison.purge()?;
+    assert!(prison.most_recycled(3).is_empty());
+    Ok(())
+}
+
+//TEST Prison::tick_expiry()
+#[cfg(feature = "ttl")]
+#[test]
+fn prison_tick_expiry() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::new();
+    let key_0 = prison.insert_with_ttl(0, 5)?;
+    let key_1 = prison.insert_with_ttl(1, 10)?;
+    let key_2 = prison.insert(2)?;
+    let key_3 = prison.insert_with_ttl(3, 5)?;
+    assert_eq!(prison.ttl_remaining(key_2)?, None);
+    assert!(prison.tick_expiry(4)?.is_empty());
+    assert_eq!(prison.ttl_remaining(key_0)?, Some(1));
+    prison.pin(key_3)?;
+    let expired = prison.visit_ref(key_0, |_| prison.tick_expiry(5))?;
+    assert!(expired.is_empty());
+    assert_eq!(prison.ttl_remaining(key_0)?, Some(0));
+    assert_eq!(prison.tick_expiry(2)?, vec![(key_0, 0)]);
+    assert_eq!(prison.ttl_remaining(key_0), Err(AccessError::ValueDeleted(0, 0)));
+    prison.extend_ttl(key_1)?;
+    prison.extend_ttl(key_2)?;
+    assert_eq!(prison.ttl_remaining(key_1)?, Some(10));
+    let key_4 = prison.insert(4)?;
+    assert_eq!(key_4.idx(), 0);
+    assert_eq!(prison.ttl_remaining(key_4)?, None);
+    prison.unpin(key_3)?;
+    assert_eq!(prison.tick_expiry(15)?, vec![(key_1, 1), (key_3, 3)]);
+    assert_prison_state!(prison, 0, 1, 3, 2, 4);
+    let key_5 = prison.insert_with_ttl(5, 1)?;
+    prison.overwrite(key_5.idx(), 50)?;
+    let key_6 = prison.insert_with_ttl(6, 1)?;
+    prison.recycle(|val| *val == 6, |val| *val = 60)?;
+    assert!(prison.tick_expiry(100)?.is_empty());
+    let key_7 = prison.insert_with_ttl(7, 1)?;
+    let mapped = prison.map_into(|_, val| val as u64);
+    assert_eq!(mapped.ttl_remaining(key_7)?, Some(1));
+    assert_eq!(mapped.tick_expiry(101)?, vec![(key_7, 7)]);
+    mapped.seal_read_only()?;
+    assert_eq!(mapped.tick_expiry(200), Err(AccessError::PrisonSealed));
+    Ok(())
+}
+
+//TEST Prison::validate_keys()
+#[test]
+fn prison_validate_keys() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::new();
+    let key_0 = prison.insert(0)?;
+    let key_1 = prison.insert(1)?;
+    let key_2 = prison.insert(2)?;
+    prison.remove(key_1)?;
+    let key_1_b = prison.insert(11)?;
+    prison.remove(key_2)?;
+    let grd_0 = prison.guard_mut(key_0)?;
+    let results = prison.validate_keys(&[key_0, key_1, key_1_b, key_2, CellKey::from_raw_parts(3, 0)]);
+    assert_eq!(results, vec![
+        Ok(()),
+        Err(AccessError::ValueDeleted(1, 0)),
+        Ok(()),
+        Err(AccessError::ValueDeleted(2, 0)),
+        Err(AccessError::IndexOutOfRange(3)),
+    ]);
+    assert_prison_state!(prison, 1, 1, 2, 1, 3);
+    assert_cell_state!(prison, 0, Refs::MUT, 0, 0);
+    drop(grd_0);
+    assert!(prison.validate_keys(&[]).is_empty());
+    Ok(())
+}
+
+//TEST Prison::get_or_insert_with()
+#[test]
+fn prison_get_or_insert_with() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(4);
+    let key_0 = prison.insert(0)?;
+    let key_1 = prison.insert(1)?;
+    let (key, mut grd) = prison.get_or_insert_with(key_0, || panic!("value should not be created"))?;
+    assert_eq!(key, key_0);
+    *grd += 10;
+    assert_eq!(prison.get_or_insert_with(key_0, || 99).err(), Some(AccessError::ValueAlreadyMutablyReferenced(0)));
+    drop(grd);
+    assert_cell_state!(prison, 0, 0, 0, 10);
+    prison.remove(key_1)?;
+    let (key_1_b, grd) = prison.get_or_insert_with(key_1, || 11)?;
+    assert_eq!(key_1_b, CellKey::from_raw_parts(1, 1));
+    drop(grd);
+    assert_cell_state!(prison, 1, 0, 1, 11);
+    let (key_2, grd) = prison.get_or_insert_with(key_1, || 2)?;
+    assert_eq!(key_2, CellKey::from_raw_parts(2, 1));
+    drop(grd);
+    let (key_3, grd) = prison.get_or_insert_with(CellKey::from_raw_parts(10, 0), || 3)?;
+    assert_eq!(key_3, CellKey::from_raw_parts(3, 1));
+    assert_eq!(prison.get_or_insert_with(CellKey::from_raw_parts(10, 0), || 4).err(), Some(AccessError::InsertAtMaxCapacityWhileAValueIsReferenced));
+    drop(grd);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 4);
+    Ok(())
+}
+
+//TEST Prison::with_storage() / Prison::with_capacity_and_storage()
+#[test]
+fn prison_with_storage() -> Result<(), AccessError> {
+    fn fill<S: PrisonStorage>(prison: &Prison<u32, S>) -> Result<Vec<CellKey>, AccessError> {
+        let mut keys = Vec::new();
+        for val in 0..4 {
+            keys.push(prison.insert(val * 10)?);
+        }
+        return Ok(keys);
+    }
+    let prison: Prison<u32, VecStorage> = Prison::with_capacity_and_storage(4, VecStorage);
+    assert_eq!(prison.vec_cap(), 4);
+    let keys = fill(&prison)?;
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 4);
+    prison.visit_mut(keys[1], |val_1| {
+        *val_1 += 5;
+        assert_access_err!(prison.insert(50), AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
+        Ok(())
+    })?;
+    let grd_2 = prison.guard_ref(keys[2])?;
+    assert_access_err!(prison.remove(keys[2]), AccessError::RemoveWhileValueReferenced(2));
+    drop(grd_2);
+    assert_eq!(prison.remove(keys[2])?, 20);
+    assert_prison_state!(prison, 0, 1, 2, 1, 4);
+    assert_eq!(prison.clone_val(keys[1])?, 15);
+    let default: Prison<u32> = Prison::with_storage(VecStorage);
+    assert_eq!(default.vec_cap(), 0);
+    fill(&default)?;
+    assert_prison_state!(default, 0, 0, IdxD::INVALID, 0, 4);
+    let boxed: Prison<u32, BoxedStorage> = Prison::with_storage(BoxedStorage);
+    assert_eq!(boxed.vec_cap(), 0);
+    fill(&boxed)?;
+    assert_prison_state!(boxed, 0, 0, IdxD::INVALID, 0, 4);
+    Ok(())
+}
+
+//STRUCT BoxedStorage
+/// A [PrisonStorage] backend that exists only in the tests, keeping its elements in a boxed slice of
+/// [MaybeUninit] instead of a [Vec] to prove nothing in [Prison] depends on the default backend
+///
+/// Every time the elements are moved to a new allocation `moves` goes up by one
+#[derive(Debug, Clone, Copy, Default)]
+struct BoxedStorage;
+
+impl crate::sealed::Sealed for BoxedStorage {}
+
+impl PrisonStorage for BoxedStorage {
+    type Container<E> = BoxedCells<E>;
+}
+
+//STRUCT BoxedCells
+struct BoxedCells<E> {
+    buf: Box<[MaybeUninit<E>]>,
+    len: usize,
+    moves: usize,
+}
+
+impl<E> crate::sealed::Sealed for BoxedCells<E> {}
+
+impl<E> BoxedCells<E> {
+    fn uninit_buf(capacity: usize) -> Box<[MaybeUninit<E>]> {
+        return (0..capacity).map(|_| MaybeUninit::uninit()).collect();
+    }
+
+    fn move_to(&mut self, capacity: usize) {
+        let mut buf = Self::uninit_buf(capacity);
+        for (new, old) in buf.iter_mut().zip(self.buf[..self.len].iter()) {
+            *new = MaybeUninit::new(unsafe { old.assume_init_read() });
+        }
+        self.buf = buf;
+        self.moves += 1;
+    }
+}
+
+impl<E> Default for BoxedCells<E> {
+    fn default() -> Self {
+        return Self::with_capacity(0);
+    }
+}
+
+impl<E> Deref for BoxedCells<E> {
+    type Target = [E];
+
+    fn deref(&self) -> &[E] {
+        return unsafe { std::slice::from_raw_parts(self.buf.as_ptr().cast::<E>(), self.len) };
+    }
+}
+
+impl<E> DerefMut for BoxedCells<E> {
+    fn deref_mut(&mut self) -> &mut [E] {
+        return unsafe { std::slice::from_raw_parts_mut(self.buf.as_mut_ptr().cast::<E>(), self.len) };
+    }
+}
+
+impl<E> Drop for BoxedCells<E> {
+    fn drop(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<E> StorageContainer<E> for BoxedCells<E> {
+    fn with_capacity(capacity: usize) -> Self {
+        return BoxedCells {
+            buf: Self::uninit_buf(capacity),
+            len: 0,
+            moves: 0,
+        };
+    }
+
+    fn from_vec(vec: Vec<E>) -> Self {
+        let mut cells = Self::with_capacity(vec.len());
+        for element in vec {
+            cells.push(element);
+        }
+        return cells;
+    }
+
+    fn into_vec(mut self) -> Vec<E> {
+        let mut vec = Vec::with_capacity(self.len);
+        while let Some(element) = self.pop() {
+            vec.push(element);
+        }
+        vec.reverse();
+        return vec;
+    }
+
+    fn capacity(&self) -> usize {
+        return self.buf.len();
+    }
+
+    fn push(&mut self, element: E) {
+        if self.len == self.buf.len() {
+            self.move_to((self.len * 2).max(4));
+        }
+        self.buf[self.len] = MaybeUninit::new(element);
+        self.len += 1;
+    }
+
+    fn pop(&mut self) -> Option<E> {
+        if self.len == 0 {
+            return None;
+        }
+        self.len -= 1;
+        return Some(unsafe { self.buf[self.len].assume_init_read() });
+    }
+
+    fn truncate(&mut self, len: usize) {
+        while self.len > len {
+            self.len -= 1;
+            unsafe { self.buf[self.len].assume_init_drop() };
+        }
+    }
+
+    fn reserve(&mut self, additional: usize) {
+        if self.len + additional > self.buf.len() {
+            self.move_to((self.len + additional).max(self.buf.len() * 2));
+        }
+    }
+
+    fn reserve_exact(&mut self, additional: usize) {
+        if self.len + additional > self.buf.len() {
+            self.move_to(self.len + additional);
+        }
+    }
+
+    fn shrink_to_fit(&mut self) {
+        if self.len < self.buf.len() {
+            self.move_to(self.len);
+        }
+    }
+}
+
+//TEST Prison with a non-Vec PrisonStorage backend
+#[test]
+fn prison_boxed_storage() -> Result<(), AccessError> {
+    let prison: Prison<String, BoxedStorage> = Prison::with_capacity_and_storage(2, BoxedStorage);
+    assert_eq!(prison.vec_cap(), 2);
+    let key_0 = prison.insert(String::from("a"))?;
+    let key_1 = prison.insert(String::from("b"))?;
+    prison.visit_ref(key_0, |val_0| {
+        assert_access_err!(prison.insert(String::from("c")), AccessError::InsertAtMaxCapacityWhileAValueIsReferenced);
+        assert_access_err!(prison.reserve(1), AccessError::AccessesOutstanding(1));
+        assert_eq!(val_0, "a");
+        Ok(())
+    })?;
+    assert_eq!(internal!(prison).vec.moves, 0);
+    let key_2 = prison.insert(String::from("c"))?;
+    assert_eq!(internal!(prison).vec.moves, 1);
+    assert_eq!(prison.vec_cap(), 4);
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 3);
+    assert_eq!(prison.remove(key_1)?, "b");
+    let key_3 = prison.insert(String::from("d"))?;
+    assert_eq!(key_3, CellKey::from_raw_parts(1, 1));
+    prison.visit_many_mut(&[key_0, key_2, key_3], |vals| {
+        for val in vals.iter_mut() {
+            val.push('!');
+        }
+        Ok(())
+    })?;
+    assert_eq!(prison.clone_val(key_2)?, "c!");
+    prison.truncate(2)?;
+    prison.shrink_to_fit()?;
+    assert_eq!(prison.vec_cap(), 2);
+    assert_eq!(internal!(prison).vec.moves, 2);
+    let lens: Prison<usize, BoxedStorage> = prison.map_into(|_, val| val.len());
+    assert_eq!(lens.clone_val(key_3)?, 2);
+    assert_eq!(lens.clone_val(key_2), Err(AccessError::IndexOutOfRange(2)));
+    Ok(())
+}
+
+//TEST StorageContainer for Vec
+#[test]
+fn vec_storage_container() {
+    let mut vec: Vec<u32> = StorageContainer::with_capacity(3);
+    assert_eq!(StorageContainer::capacity(&vec), 3);
+    StorageContainer::push(&mut vec, 1);
+    StorageContainer::push(&mut vec, 2);
+    StorageContainer::push(&mut vec, 3);
+    assert_eq!(StorageContainer::pop(&mut vec), Some(3));
+    StorageContainer::truncate(&mut vec, 1);
+    assert_eq!(&vec[..], &[1]);
+    StorageContainer::reserve(&mut vec, 10);
+    assert!(StorageContainer::capacity(&vec) >= 11);
+    StorageContainer::reserve_exact(&mut vec, 20);
+    assert!(StorageContainer::capacity(&vec) >= 21);
+    StorageContainer::shrink_to_fit(&mut vec);
+    assert_eq!(StorageContainer::capacity(&vec), 1);
+    let vec: Vec<u32> = StorageContainer::from_vec(vec![4, 5]);
+    assert_eq!(StorageContainer::into_vec(vec), vec![4, 5]);
+}
+
+//TEST StorageContainer for BoxedCells
+#[test]
+fn boxed_storage_container() {
+    let mut cells: BoxedCells<String> = StorageContainer::from_vec(vec![String::from("a"), String::from("b")]);
+    assert_eq!(cells.capacity(), 2);
+    cells.push(String::from("c"));
+    assert_eq!((cells.capacity(), cells.moves), (4, 1));
+    cells.reserve(1);
+    cells.reserve_exact(2);
+    assert_eq!((cells.capacity(), cells.moves), (5, 2));
+    cells[1].push('!');
+    assert_eq!(cells.pop().as_deref(), Some("c"));
+    cells.shrink_to_fit();
+    assert_eq!((cells.capacity(), cells.moves), (2, 3));
+    assert_eq!(cells.into_vec(), vec![String::from("a"), String::from("b!")]);
+}
+
+//TEST Prison: UnwindSafe + RefUnwindSafe
+#[test]
+fn prison_unwind_safe() -> Result<(), AccessError> {
+    fn assert_ref_unwind_safe<S: RefUnwindSafe>() {}
+    fn assert_unwind_safe<S: UnwindSafe>() {}
+    assert_ref_unwind_safe::<Prison<String>>();
+    assert_ref_unwind_safe::<JailCell<String>>();
+    assert_unwind_safe::<&Prison<String>>();
+    assert_unwind_safe::<PrisonValueRef<String>>();
+    assert_unwind_safe::<PrisonSliceRef<String>>();
+    assert_unwind_safe::<JailValueRef<String>>();
+    let prison: Prison<u32> = Prison::new();
+    let key_0 = prison.insert(0)?;
+    let key_1 = prison.insert(1)?;
+    let fail = |_: &mut u32| -> Result<(), AccessError> { panic!("closure panicked while holding a reference") };
+    assert!(std::panic::catch_unwind(|| prison.visit_mut(key_0, fail)).is_err());
+    assert!(std::panic::catch_unwind(|| prison.visit_ref_idx(1, |_| -> Result<(), AccessError> { panic!() })).is_err());
+    assert!(std::panic::catch_unwind(|| prison.visit_many_mut(&[key_0, key_1], |_| panic!())).is_err());
+    assert!(std::panic::catch_unwind(|| {
+        prison.serialize_reachable(&[key_0, key_1], |_| Vec::new(), &mut (), |_, _, _| -> Result<(), AccessError> { panic!() })
+    })
+    .is_err());
+    assert!(std::panic::catch_unwind(|| prison.recycle(|_| true, |_| panic!())).is_err());
+    let grd_1 = prison.guard_ref(key_1)?;
+    assert!(std::panic::catch_unwind(|| {
+        let _grd_1 = grd_1;
+        panic!();
+    })
+    .is_err());
+    assert_cell_state!(prison, 0, 0, 1, 0);
+    assert_cell_state!(prison, 1, 0, 0, 1);
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 2);
+    let jail_a: JailCell<u32> = JailCell::new(0);
+    let jail_b: JailCell<u32> = JailCell::new(0);
+    assert!(std::panic::catch_unwind(|| jail_a.visit_ref(|_| -> Result<(), AccessError> { panic!() })).is_err());
+    assert!(std::panic::catch_unwind(|| jail_a.add_assign(u32::MAX).and(jail_a.add_assign(1))).is_err() == cfg!(debug_assertions));
+    assert!(std::panic::catch_unwind(|| visit_both_mut(&jail_a, &jail_b, |_, _| panic!())).is_err());
+    assert!(jail_a.visit_mut(|_| Ok(())).is_ok());
+    assert!(jail_b.visit_mut(|_| Ok(())).is_ok());
+    Ok(())
+}
+
+//TEST Prison::visit_many_mut() / Prison::guard_many_mut(): small batches never allocate
+#[test]
+fn prison_small_batches_do_not_allocate() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(3);
+    let keys = [prison.insert(1)?, prison.insert(2)?, prison.insert(3)?];
+    // Feature bookkeeping such as the `dirty` set may allocate once on the first mutable access
+    prison.visit_many_mut(&keys, |_| Ok(()))?;
+    let allocs = thread_allocs();
+    for _ in 0..100 {
+        prison.visit_many_mut(&keys[..2], |vals| {
+            *vals[0] += *vals[1];
+            Ok(())
+        })?;
+        prison.visit_many_ref(&keys, |vals| {
+            assert_eq!(vals.len(), 3);
+            Ok(())
+        })?;
+        let mut grds = prison.guard_many_mut(&keys[1..])?;
+        *grds[1] += 1;
+        drop(grds);
+        let grds = prison.guard_many_ref(&keys)?;
+        assert_eq!(*grds[1], 2);
+    }
+    assert_eq!(thread_allocs(), allocs);
+    assert_cell_state!(prison, 0, 0, 0, 201);
+    assert_cell_state!(prison, 2, 0, 0, 103);
+    Ok(())
+}
+
+//TEST Prison: reuse of spare batch buffers
+#[test]
+fn prison_spare_buffers() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::new();
+    let keys = prison.insert_many(0..20)?;
+    prison.visit_many_mut(&keys[..INLINE_CAP], |_| Ok(()))?;
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), 0);
+    prison.visit_many_mut(&keys, |_| Ok(()))?;
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), 2);
+    let buf_ptrs: Vec<*const usize> = internal!(prison).extras().spare_bufs.iter().map(|buf| buf.as_ptr()).collect();
+    prison.visit_many_ref(&keys, |vals| {
+        assert_eq!(*vals[19], 19);
+        assert_eq!(internal!(prison).extras().spare_bufs.len(), 0);
+        prison.visit_many_ref(&keys, |_| Ok(()))?;
+        assert_eq!(internal!(prison).extras().spare_bufs.len(), 2);
+        prison.visit_many_ref(&keys, |_| Ok(()))
+    })?;
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), MAX_SPARE_BUFS);
+    assert!(buf_ptrs.iter().all(|ptr| internal!(prison).extras().spare_bufs.iter().any(|buf| buf.as_ptr() == *ptr)));
+    let grd = prison.guard_many_mut(&keys)?;
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), MAX_SPARE_BUFS - 2);
+    assert_access_err!(prison.visit_many_ref(&keys, |_| Ok(())), AccessError::ValueAlreadyMutablyReferenced(0));
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), MAX_SPARE_BUFS - 2);
+    drop(grd);
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), MAX_SPARE_BUFS);
+    let set = CellKeySet::from(&keys[..]);
+    prison.visit_set_mut(&set, |vals| {
+        *vals[10] = 100;
+        Ok(())
+    })?;
+    assert_eq!(prison.clone_val(keys[10])?, 100);
+    assert_eq!(internal!(prison).extras().spare_bufs.len(), MAX_SPARE_BUFS);
+    assert!(prison.spare_buffer_bytes() >= MAX_SPARE_BUFS * 20 * mem::size_of::<usize>());
+    prison.shrink_to_fit()?;
+    assert_eq!(prison.spare_buffer_bytes(), 0);
+    let strings: Prison<String> = Prison::new();
+    let keys = strings.insert_many((0..20).map(|val| val.to_string()))?;
+    strings.visit_many_ref(&keys, |vals| {
+        assert_eq!(vals[12], "12");
+        Ok(())
+    })?;
+    assert_eq!(internal!(strings).extras().spare_bufs.len(), 2);
+    Ok(())
+}
+
+//TEST Prison: reserve() and reserve_exact()
+#[test]
+fn prison_reserve() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::new();
+    prison.reserve(8)?;
+    assert!(prison.vec_cap() >= 8);
+    assert_eq!(prison.vec_len(), 0);
+    let key_0 = prison.insert(0)?;
+    let cap = prison.vec_cap();
+    prison.visit_mut(key_0, |_| {
+        assert_eq!(prison.reserve(cap - 1), Ok(()));
+        assert_eq!(prison.reserve_exact(cap), Err(AccessError::AccessesOutstanding(1)));
+        assert_eq!(prison.reserve(usize::MAX), Err(AccessError::MaximumCapacityReached));
+        Ok(())
+    })?;
+    assert_eq!(prison.vec_cap(), cap);
+    prison.reserve_exact(cap)?;
+    assert!(prison.vec_cap() > cap);
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 1);
+    assert_cell_state!(prison, 0, 0, 0, 0);
+    prison.seal_read_only()?;
+    assert_eq!(prison.reserve(1000), Err(AccessError::PrisonSealed));
+    Ok(())
+}
+
+//TEST Prison::truncate() / Prison::shrink_to_fit()
+#[test]
+fn prison_truncate_and_shrink() -> Result<(), AccessError> {
+    let prison: Prison<u32> = Prison::with_capacity(20);
+    let keys: Vec<CellKey> = (0..8).map(|val| prison.insert(val)).collect::<Result<_, _>>()?;
+    prison.remove(keys[1])?;
+    prison.remove(keys[6])?;
+    prison.remove(keys[3])?;
+    prison.remove(keys[7])?;
+    assert_prison_state!(prison, 0, 1, 7, 4, 8);
+    assert_free_state!(prison, 3, 7, 6);
+    prison.pin(keys[5])?;
+    assert_eq!(prison.truncate(4), Err(AccessError::ValuePinned(5)));
+    prison.unpin(keys[5])?;
+    prison.visit_ref(keys[4], |_| {
+        assert_eq!(prison.truncate(4), Err(AccessError::RemoveWhileValueReferenced(4)));
+        assert_eq!(prison.shrink_to_fit(), Err(AccessError::AccessesOutstanding(1)));
+        assert!(prison.truncate(5).is_ok());
+        Ok(())
+    })?;
+    assert_prison_state!(prison, 0, 1, 3, 2, 5);
+    assert_free_state!(prison, 3, IdxD::INVALID, 1);
+    assert_free_state!(prison, 1, 3, IdxD::INVALID);
+    prison.truncate(4)?;
+    assert_prison_state!(prison, 0, 1, 3, 2, 4);
+    prison.remove(keys[2])?;
+    prison.shrink_to_fit()?;
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 1);
+    assert!(prison.vec_cap() < 20);
+    assert_eq!(prison.clone_val(keys[4]), Err(AccessError::IndexOutOfRange(4)));
+    let key_1 = prison.insert(10)?;
+    assert_eq!(key_1, CellKey::from_raw_parts(1, 1));
+    assert_eq!(prison.insert(11)?, CellKey::from_raw_parts(2, 1));
+    assert_eq!(prison.clone_val(keys[2]), Err(AccessError::ValueDeleted(2, 0)));
+    prison.reserve_index_range(4..6)?;
+    prison.shrink_to_fit()?;
+    assert_eq!(prison.vec_len(), 6);
+    assert_eq!(prison.num_free(), 1);
+    prison.truncate(5)?;
+    assert!(prison.is_index_reserved(4));
+    assert_eq!(prison.insert(12)?.idx(), 3);
+    prison.truncate(4)?;
+    assert!(!prison.is_index_reserved(4));
+    assert_eq!(prison.insert(13)?.idx(), 4);
+    assert_eq!(prison.insert(14)?.idx(), 5);
+    prison.seal_read_only()?;
+    assert_eq!(prison.truncate(0), Err(AccessError::PrisonSealed));
+    assert_eq!(prison.shrink_to_fit(), Err(AccessError::PrisonSealed));
+    Ok(())
+}
+
+//TEST Prison: Serialize + Deserialize
+#[cfg(feature = "serde")]
+#[test]
+fn prison_serde_round_trip() -> Result<(), AccessError> {
+    let prison: Prison<String> = Prison::new();
+    let key_0 = prison.insert(String::from("zero"))?;
+    let key_1 = prison.insert(String::from("one"))?;
+    let key_2 = prison.insert(String::from("two"))?;
+    let key_3 = prison.insert(String::from("three"))?;
+    let key_4 = prison.insert(String::from("four"))?;
+    prison.remove(key_1)?;
+    prison.remove(key_3)?;
+    prison.reserve_index_range(5..7)?;
+    let key_5 = prison.insert_at(5, String::from("five"))?;
+    let saved_keys = serde_json::to_string(&[key_0, key_1, key_2, key_5]).unwrap();
+    let saved = prison.visit_ref(key_2, |_| Ok(serde_json::to_string(&prison).unwrap()))?;
+    assert!(prison.visit_mut(key_4, |_| Ok(serde_json::to_string(&prison).is_err()))?);
+    let loaded: Prison<String> = serde_json::from_str(&saved).unwrap();
+    let keys: [CellKey; 4] = serde_json::from_str(&saved_keys).unwrap();
+    assert_eq!(keys, [key_0, key_1, key_2, key_5]);
+    assert_eq!(loaded.clone_val(keys[0])?, "zero");
+    assert_eq!(loaded.clone_val(keys[2])?, "two");
+    assert_eq!(loaded.clone_val(keys[3])?, "five");
+    assert_eq!(loaded.clone_val(key_4)?, "four");
+    assert_access_err!(loaded.clone_val(keys[1]), AccessError::ValueDeleted(1, 0));
+    assert_eq!(loaded.num_used(), 4);
+    assert_eq!(loaded.insert(String::from("a"))?, prison.insert(String::from("a"))?);
+    assert_eq!(loaded.insert(String::from("b"))?, prison.insert(String::from("b"))?);
+    assert_eq!(loaded.insert(String::from("c"))?, prison.insert(String::from("c"))?);
+    assert_eq!(loaded.insert_at(6, String::from("six"))?, prison.insert_at(6, String::from("six"))?);
+    let reloaded: Prison<String> = serde_json::from_str(&serde_json::to_string(&loaded).unwrap()).unwrap();
+    assert_eq!(serde_json::to_string(&reloaded).unwrap(), serde_json::to_string(&prison).unwrap());
+    let bad_gen = r#"{"generation":0,"cells":[[1,"x"]],"free":[],"reserved":[]}"#;
+    assert!(serde_json::from_str::<Prison<String>>(bad_gen).is_err());
+    let bad_free = r#"{"generation":1,"cells":[null,[0,"x"]],"free":[1],"reserved":[]}"#;
+    assert!(serde_json::from_str::<Prison<String>>(bad_free).is_err());
+    let missing_free = r#"{"generation":1,"cells":[null,null],"free":[1],"reserved":[]}"#;
+    assert!(serde_json::from_str::<Prison<String>>(missing_free).is_err());
+    let bad_reserved = r#"{"generation":1,"cells":[null,null],"free":[],"reserved":[[0,3]]}"#;
+    assert!(serde_json::from_str::<Prison<String>>(bad_reserved).is_err());
+    Ok(())
+}
+
+//TEST Prison: Send
+#[test]
+fn prison_send() -> Result<(), AccessError> {
+    fn assert_send<S: Send>() {}
+    assert_send::<Prison<MyNoCopy>>();
+    assert_send::<Prison<String>>();
+    let prison: Prison<MyNoCopy> = Prison::with_capacity(5);
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    prison.remove(key_1)?;
+    let prison = std::thread::spawn(move || -> Result<Prison<MyNoCopy>, AccessError> {
+        prison.visit_mut(key_0, |val_0| {
+            val_0.0 = 42;
+            Ok(())
+        })?;
+        prison.insert(MyNoCopy(2))?;
+        Ok(prison)
+    })
+    .join()
+    .expect("thread panicked")?;
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(42));
+    assert_cell_state!(prison, 1, 0, 1, MyNoCopy(2));
+    assert_prison_state!(prison, 0, 1, IdxD::INVALID, 0, 2);
+    Ok(())
+}
+
+//------ JailCell Tests ------
+//TODO: TEST JailCell::new()
+
+//TEST UnPrison
+#[test]
+fn un_prison_unchecked_access() -> Result<(), AccessError> {
+    let prison: Prison<MyNoCopy> = Prison::new();
+    let key_0 = prison.insert(MyNoCopy(0))?;
+    let key_1 = prison.insert(MyNoCopy(1))?;
+    let mut un_prison: UnPrison<MyNoCopy> = prison.into();
+    unsafe {
+        let val_0 = un_prison.get_mut(key_0)?;
+        let val_1 = un_prison.get_mut_idx(1)?;
+        val_0.0 += 10;
+        val_1.0 += 10;
+        assert_eq!(*un_prison.get_ref(key_1)?, MyNoCopy(11));
+        assert_eq!(*un_prison.get_ref_idx(0)?, MyNoCopy(10));
+        assert_eq!(un_prison.get_ref_idx(2).err(), Some(AccessError::IndexOutOfRange(2)));
+    }
+    let prison = un_prison.as_prison();
+    assert_prison_state!(prison, 0, 0, IdxD::INVALID, 0, 2);
+    assert_cell_state!(prison, 0, 0, 0, MyNoCopy(10));
+    assert_eq!(un_prison.remove(key_0)?, MyNoCopy(10));
+    let key_2 = un_prison.insert(MyNoCopy(2))?;
+    unsafe {
+        assert_eq!(un_prison.get_mut(key_0).err(), Some(AccessError::ValueDeleted(0, 0)));
+        assert_eq!(un_prison.get_ref(key_2)?, &MyNoCopy(2));
+    }
+    un_prison.remove(key_1)?;
+    unsafe {
+        assert_eq!(un_prison.get_ref_idx(1).err(), Some(AccessError::ValueDeleted(1, 0)));
+    }
+    let prison: Prison<MyNoCopy> = un_prison.into();
+    assert_prison_state!(prison, 0, 1, 1, 1, 2);
+    assert_cell_state!(prison, 0, 0, 1, MyNoCopy(2));
+    Ok(())
+}
+
+//TEST PrisonQueue
+#[test]
+fn prison_queue_order_and_cancel() -> Result<(), AccessError> {
+    let queue: PrisonQueue<MyNoCopy> = PrisonQueue::with_capacity(4);
+    assert!(queue.is_empty());
+    assert_eq!(queue.front(), None);
+    assert!(queue.pop_back()?.is_none());
+    let key_1 = queue.push_back(MyNoCopy(1))?;
+    let key_2 = queue.push_back(MyNoCopy(2))?;
+    let key_0 = queue.push_front(MyNoCopy(0))?;
+    let key_3 = queue.push_back(MyNoCopy(3))?;
+    assert_eq!(queue.keys(), vec![key_0, key_1, key_2, key_3]);
+    assert_eq!(queue.len(), 4);
+    assert_eq!(queue.front(), Some(key_0));
+    assert_eq!(queue.back(), Some(key_3));
+    assert_eq!(queue.remove(key_2)?, MyNoCopy(2));
+    assert_access_err!(queue.remove(key_2), AccessError::ValueDeleted(1, 0));
+    assert_eq!(queue.keys(), vec![key_0, key_1, key_3]);
+    let key_4 = queue.push_back(MyNoCopy(4))?;
+    assert_eq!(key_4.idx(), key_2.idx());
+    assert_eq!(queue.keys(), vec![key_0, key_1, key_3, key_4]);
+    let grd_0 = queue.guard_ref(key_0)?;
+    assert_access_err!(queue.pop_front(), AccessError::RemoveWhileValueReferenced(2));
+    assert_eq!(queue.front(), Some(key_0));
+    drop(grd_0);
+    queue.visit_mut(key_3, |val| {
+        val.0 = 30;
+        Ok(())
+    })?;
+    assert_eq!(queue.pop_front()?, Some((key_0, MyNoCopy(0))));
+    assert_eq!(queue.pop_back()?, Some((key_4, MyNoCopy(4))));
+    assert_eq!(queue.pop_back()?, Some((key_3, MyNoCopy(30))));
+    assert!(queue.contains_key(key_1));
+    assert_eq!(queue.keys(), vec![key_1]);
+    assert_eq!(queue.pop_front()?, Some((key_1, MyNoCopy(1))));
+    assert!(!queue.contains_key(key_1));
+    assert!(queue.is_empty());
+    assert_eq!(queue.back(), None);
+    assert_eq!(queue.keys(), vec![]);
+    let key_5 = queue.push_front(MyNoCopy(5))?;
+    assert_eq!(queue.front(), queue.back());
+    assert_eq!(queue.visit_ref(key_5, |val| Ok(val.0))?, 5);
+    Ok(())
+}
+
+//TEST RefCountCell::try_borrow() / RefCountCell::try_borrow_mut()
+#[test]
+fn ref_count_cell_borrows() -> Result<(), AccessError> {
+    let mut cell: RefCountCell<MyNoCopy> = RefCountCell::new(MyNoCopy(1));
+    {
+        let mut grd_mut = cell.try_borrow_mut()?;
+        grd_mut.0 = 2;
+        assert_eq!(cell.ref_kind(), RefKind::Mutable);
+        assert_eq!(cell.try_borrow().err(), Some(AccessError::ValueAlreadyMutablyReferenced(0)));
+        assert_eq!(cell.try_borrow_mut().err(), Some(AccessError::ValueAlreadyMutablyReferenced(0)));
+    }
+    {
+        let grd_a = cell.try_borrow()?;
+        let grd_b = cell.try_borrow()?;
+        assert_eq!(cell.ref_kind(), RefKind::Immutable(2));
+        assert_eq!(cell.try_borrow_mut().err(), Some(AccessError::ValueStillImmutablyReferenced(0)));
+        RefCountRef::unguard(grd_a);
+        assert_eq!(*grd_b, MyNoCopy(2));
+    }
+    assert_eq!(cell.ref_kind(), RefKind::None);
+    cell.get_mut().0 = 3;
+    assert!(std::panic::catch_unwind(|| {
+        let _grd = cell.try_borrow_mut().unwrap();
+        panic!();
+    })
+    .is_err());
+    assert_eq!(cell.ref_kind(), RefKind::None);
+    let cell_ref = &cell;
+    internal!(cell_ref).refs = Refs::MAX_IMMUT;
+    assert_eq!(cell.try_borrow().err(), Some(AccessError::MaximumImmutableReferencesReached(0)));
+    internal!(cell_ref).refs = 0;
+    assert_eq!(cell.into_inner(), MyNoCopy(3));
+    Ok(())
+}
+
+//TEST JailCell::visit_mut()
+#[test]
+fn jail_visit_mut() -> Result<(), AccessError> {
+    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
+    jail.visit_mut(|val| {
+        assert_jail_state!(jail, Refs::MUT, MyNoCopy(42));
+        assert_eq!(*val, MyNoCopy(42));
+        *val = MyNoCopy(69);
+        assert_eq!(*val, MyNoCopy(69));
+        assert_jail_state!(jail, Refs::MUT, MyNoCopy(69));
+        assert_access_err!(
+            jail.visit_mut(|_| Ok(())),
+            AccessError::ValueAlreadyMutablyReferenced(0)
+        );
+        Ok(())
+    })?;
+    assert_jail_state!(jail, 0, MyNoCopy(69));
+    jail.visit_ref(|val| {
+        assert_access_err!(
+            jail.visit_mut(|_| Ok(())),
+            AccessError::ValueStillImmutablyReferenced(0)
+        );
+        Ok(())
+    })?;
+    Ok(())
+}
+
+//TEST JailCell::visit_ref()
+#[test]
+fn jail_visit_ref() -> Result<(), AccessError> {
+    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
+    jail.visit_ref(|val| {
+        assert_jail_state!(jail, 1, MyNoCopy(42));
+        assert_eq!(*val, MyNoCopy(42));
+        jail.visit_ref(|val_b| {
+            assert_jail_state!(jail, 2, MyNoCopy(42));
+            assert_eq!(*val_b, MyNoCopy(42));
+            Ok(())
+        })?;
+        assert_jail_state!(jail, 1, MyNoCopy(42));
+        Ok(())
+    })?;
+    assert_jail_state!(jail, 0, MyNoCopy(42));
+    jail.visit_mut(|val| {
+        assert_access_err!(
+            jail.visit_ref(|_| Ok(())),
+            AccessError::ValueAlreadyMutablyReferenced(0)
+        );
+        Ok(())
+    })?;
+    let cell = &jail.cell;
+    internal!(cell).refs = Refs::MAX_IMMUT;
+    assert_access_err!(
+        jail.visit_ref(|_| Ok(())),
+        AccessError::MaximumImmutableReferencesReached(0)
+    );
+    Ok(())
+}
+
+//TEST JailCell::guard_mut()
+#[test]
+fn jail_guard_mut() -> Result<(), AccessError> {
+    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
+    {
+        let mut val = jail.guard_mut()?;
+        assert_jail_state!(jail, Refs::MUT, MyNoCopy(42));
+        assert_eq!(*val, MyNoCopy(42));
+        *val = MyNoCopy(69);
+        assert_eq!(*val, MyNoCopy(69));
+        assert_jail_state!(jail, Refs::MUT, MyNoCopy(69));
+        assert_access_err!(
+            jail.guard_mut(),
+            AccessError::ValueAlreadyMutablyReferenced(0)
+        );
+    }
+    assert_jail_state!(jail, 0, MyNoCopy(69));
+    let mut val = jail.guard_mut()?;
+    assert_jail_state!(jail, Refs::MUT, MyNoCopy(69));
+    *val = MyNoCopy(42);
+    JailValueMut::unguard(val);
+    assert_jail_state!(jail, 0, MyNoCopy(42));
+    jail.visit_ref(|val| {
+        assert_access_err!(
+            jail.guard_mut(),
+            AccessError::ValueStillImmutablyReferenced(0)
+        );
+        Ok(())
+    })?;
+    Ok(())
+}
+
+//TEST JailCell::guard_ref()
+#[test]
+fn jail_guard_ref() -> Result<(), AccessError> {
+    let jail: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(42));
+    {
+        let val = jail.guard_ref()?;
+        assert_jail_state!(jail, 1, MyNoCopy(42));
+        assert_eq!(*val, MyNoCopy(42));
+        let val_b = jail.guard_ref()?;
+        assert_jail_state!(jail, 2, MyNoCopy(42));
+        assert_eq!(*val_b, MyNoCopy(42));
+        JailValueRef::unguard(val_b);
+        assert_jail_state!(jail, 1, MyNoCopy(42));
+    }
+    assert_jail_state!(jail, 0, MyNoCopy(42));
+    jail.visit_mut(|val| {
+        assert_access_err!(
+            jail.guard_ref(),
+            AccessError::ValueAlreadyMutablyReferenced(0)
+        );
+        Ok(())
+    })?;
+    let cell = &jail.cell;
+    internal!(cell).refs = Refs::MAX_IMMUT;
+    assert_access_err!(
+        jail.guard_ref(),
+        AccessError::MaximumImmutableReferencesReached(0)
+    );
+    Ok(())
+}
+
+//TEST JailCell::clone_val()
+#[test]
+fn jail_clone_val() -> Result<(), AccessError> {
+    let jail: JailCell<String> = JailCell::new(String::from("fox"));
+    let mut animal_1: String = String::new();
+    let mut animal_2: String = String::new();
+    jail.visit_mut(|val| {
+        animal_1 = jail.clone_val();
+        animal_2 = val.clone();
+        *val = String::from("dog");
+        Ok(())
+    })?;
+    assert_eq!(animal_1, String::from("fox"));
+    assert_eq!(animal_1, animal_2);
+    animal_1 = String::from("bear");
+    animal_2 = String::from("cat");
+    assert_jail_state!(jail, 0, String::from("dog"));
+    Ok(())
+}
+
+//TEST visit_both_mut()
+#[test]
+fn jail_visit_both_mut() -> Result<(), AccessError> {
+    let jail_a: JailCell<MyNoCopy> = JailCell::new(MyNoCopy(1));
+    let jail_b: JailCell<u32> = JailCell::new(2);
+    visit_both_mut(&jail_a, &jail_b, |a, b| {
+        a.0 += 10;
+        *b += 20;
+        assert_access_err!(jail_a.visit_ref(|_| Ok(())), AccessError::ValueAlreadyMutablyReferenced(0));
+        assert_access_err!(jail_b.visit_ref(|_| Ok(())), AccessError::ValueAlreadyMutablyReferenced(0));
+        Ok(())
+    })?;
+    jail_a.visit_ref(|a| {
+        assert_eq!(a.0, 11);
+        Ok(())
+    })?;
+    assert_eq!(jail_b.clone_val(), 22);
+    {
+        let grd_a = jail_a.guard_mut()?;
+        assert_access_err!(visit_both_mut(&jail_a, &jail_b, |_, _| Ok(())), AccessError::ValueAlreadyMutablyReferenced(0));
+    }
+    {
+        let grd_b = jail_b.guard_ref()?;
+        assert_access_err!(visit_both_mut(&jail_a, &jail_b, |_, _| Ok(())), AccessError::ValueStillImmutablyReferenced(1));
+        assert!(jail_a.visit_mut(|_| Ok(())).is_ok());
+    }
+    assert_access_err!(visit_both_mut(&jail_b, &jail_b, |_, _| Ok(())), AccessError::ValueAlreadyMutablyReferenced(1));
+    assert_access_err!(
+        visit_both_mut(&jail_a, &jail_b, |_, _| Err(AccessError::IndexOutOfRange(7))),
+        AccessError::IndexOutOfRange(7)
+    );
+    assert!(jail_a.visit_mut(|_| Ok(())).is_ok());
+    assert!(jail_b.visit_mut(|_| Ok(())).is_ok());
+    Ok(())
+}
+
+//TEST JailCell::peek_ref()
+#[test]
+fn jail_peek_ref() -> Result<(), AccessError> {
+    let jail: JailCell<String> = JailCell::new(String::from("fox"));
+    jail.visit_mut(|val| {
+        let unsafe_ref = unsafe {jail.peek_ref()};
+        assert_eq!(*unsafe_ref, String::from("fox"));
+        assert_jail_state!(jail, Refs::MUT, String::from("fox"));
+        Ok(())
     })?;
     assert_jail_state!(jail, 0, String::from("fox"));
     Ok(())
-}
\ No newline at end of file
+}
+
+//TEST JailCell::update_with() / add_assign() / sub_assign() / replace_if()
+#[test]
+fn jail_numeric_helpers() -> Result<(), AccessError> {
+    let jail: JailCell<u64> = JailCell::new(10);
+    assert_eq!(jail.update_with(|val| val * 3)?, 10);
+    assert_jail_state!(jail, 0, 30);
+    jail.add_assign(5)?;
+    jail.sub_assign(15)?;
+    assert_jail_state!(jail, 0, 20);
+    assert_eq!(jail.replace_if(|val| *val > 20, 0)?, None);
+    assert_eq!(jail.replace_if(|val| *val == 20, 1)?, Some(20));
+    assert_jail_state!(jail, 0, 1);
+    jail.visit_ref(|val| {
+        assert_access_err!(jail.update_with(|val| val + 1), AccessError::ValueStillImmutablyReferenced(0));
+        assert_access_err!(jail.add_assign(1), AccessError::ValueStillImmutablyReferenced(0));
+        Ok(())
+    })?;
+    let grd = jail.guard_mut()?;
+    assert_access_err!(jail.sub_assign(1), AccessError::ValueAlreadyMutablyReferenced(0));
+    assert_access_err!(jail.replace_if(|_| true, 2), AccessError::ValueAlreadyMutablyReferenced(0));
+    drop(grd);
+    assert_jail_state!(jail, 0, 1);
+    let strings: JailCell<String> = JailCell::new(String::from("fox"));
+    strings.add_assign(" hound")?;
+    assert_eq!(strings.update_with(|val| val.to_uppercase())?, String::from("fox hound"));
+    assert_jail_state!(strings, 0, String::from("FOX HOUND"));
+    Ok(())
+}